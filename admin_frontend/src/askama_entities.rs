@@ -1,4 +1,6 @@
+use chrono::{DateTime, Utc};
 use database_entity::dto::AFWorkspace;
+use shared_entity::dto::api_token_dto::{ApiToken, ApiTokenScope};
 
 use crate::ext::entities::WorkspaceMember;
 
@@ -6,3 +8,43 @@ pub struct WorkspaceWithMembers {
   pub workspace: AFWorkspace,
   pub members: Vec<WorkspaceMember>,
 }
+
+pub struct WorkspaceWithApiTokens {
+  pub workspace: AFWorkspace,
+  pub api_tokens: Vec<ApiTokenRow>,
+}
+
+pub struct ApiTokenRow {
+  pub token_id: String,
+  pub name: String,
+  pub token_prefix: String,
+  pub scope: &'static str,
+  pub created_at: String,
+  pub expires_at: String,
+  pub last_used_at: String,
+}
+
+impl From<ApiToken> for ApiTokenRow {
+  fn from(token: ApiToken) -> Self {
+    let format_time = |time: DateTime<Utc>| time.format("%Y-%m-%d %H:%M").to_string();
+    Self {
+      token_id: token.token_id.to_string(),
+      name: token.name,
+      token_prefix: token.token_prefix,
+      scope: match token.scope {
+        ApiTokenScope::Read => "Read only",
+        ApiTokenScope::Write => "Write",
+        ApiTokenScope::Admin => "Admin",
+      },
+      created_at: format_time(token.created_at),
+      expires_at: token
+        .expires_at
+        .map(format_time)
+        .unwrap_or_else(|| "Never".to_owned()),
+      last_used_at: token
+        .last_used_at
+        .map(format_time)
+        .unwrap_or_else(|| "Never".to_owned()),
+    }
+  }
+}
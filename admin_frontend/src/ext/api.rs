@@ -1,5 +1,10 @@
 use database_entity::dto::{AFRole, AFWorkspace, AFWorkspaceInvitation};
-use shared_entity::dto::{auth_dto::SignInTokenResponse, workspace_dto::WorkspaceMemberInvitation};
+use shared_entity::dto::{
+  api_token_dto::{ApiToken, CreateApiTokenParams, CreatedApiToken, RepeatedApiToken},
+  auth_dto::SignInTokenResponse,
+  oauth_dto::{OAuthAuthorizeParams, OAuthAuthorizeResponse, OAuthClient},
+  workspace_dto::WorkspaceMemberInvitation,
+};
 
 use super::{
   check_response,
@@ -293,3 +298,95 @@ pub async fn delete_current_user(
   check_response(resp).await?;
   Ok(())
 }
+
+pub async fn get_api_tokens(
+  access_token: &str,
+  workspace_id: &str,
+  appflowy_cloud_base_url: &str,
+) -> Result<Vec<ApiToken>, Error> {
+  let http_client = reqwest::Client::new();
+  let resp = http_client
+    .get(format!(
+      "{}/api/workspace/{}/api-token",
+      appflowy_cloud_base_url, workspace_id
+    ))
+    .header("Authorization", format!("Bearer {}", access_token))
+    .send()
+    .await?;
+
+  let tokens: RepeatedApiToken = from_json_response(resp).await?;
+  Ok(tokens.items)
+}
+
+pub async fn create_api_token(
+  access_token: &str,
+  workspace_id: &str,
+  params: &CreateApiTokenParams,
+  appflowy_cloud_base_url: &str,
+) -> Result<CreatedApiToken, Error> {
+  let http_client = reqwest::Client::new();
+  let resp = http_client
+    .post(format!(
+      "{}/api/workspace/{}/api-token",
+      appflowy_cloud_base_url, workspace_id
+    ))
+    .header("Authorization", format!("Bearer {}", access_token))
+    .json(params)
+    .send()
+    .await?;
+
+  from_json_response(resp).await
+}
+
+pub async fn revoke_api_token(
+  access_token: &str,
+  workspace_id: &str,
+  token_id: &str,
+  appflowy_cloud_base_url: &str,
+) -> Result<(), Error> {
+  let http_client = reqwest::Client::new();
+  let resp = http_client
+    .delete(format!(
+      "{}/api/workspace/{}/api-token/{}",
+      appflowy_cloud_base_url, workspace_id, token_id
+    ))
+    .header("Authorization", format!("Bearer {}", access_token))
+    .send()
+    .await?;
+
+  check_response(resp).await
+}
+
+pub async fn get_oauth_client(
+  access_token: &str,
+  client_id: &str,
+  appflowy_cloud_base_url: &str,
+) -> Result<OAuthClient, Error> {
+  let http_client = reqwest::Client::new();
+  let resp = http_client
+    .get(format!(
+      "{}/api/oauth/client/{}",
+      appflowy_cloud_base_url, client_id
+    ))
+    .header("Authorization", format!("Bearer {}", access_token))
+    .send()
+    .await?;
+
+  from_json_response(resp).await
+}
+
+pub async fn authorize_oauth_client(
+  access_token: &str,
+  params: &OAuthAuthorizeParams,
+  appflowy_cloud_base_url: &str,
+) -> Result<OAuthAuthorizeResponse, Error> {
+  let http_client = reqwest::Client::new();
+  let resp = http_client
+    .post(format!("{}/api/oauth/authorize", appflowy_cloud_base_url))
+    .header("Authorization", format!("Bearer {}", access_token))
+    .json(params)
+    .send()
+    .await?;
+
+  from_json_response(resp).await
+}
@@ -40,6 +40,15 @@ pub struct WebApiInviteUserRequest {
   pub email: String,
 }
 
+#[derive(Deserialize)]
+pub struct WebAppCreateApiTokenRequest {
+  pub workspace_id: String,
+  pub name: String,
+  pub scope: i32,
+  /// Empty if the token never expires.
+  pub expires_in_days: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct WebApiCreateSSOProviderRequest {
   #[serde(rename = "type")]
@@ -99,6 +108,32 @@ pub struct OAuthRedirectToken {
   pub code_verifier: Option<String>,
 }
 
+/// Authorization request of a third-party application registered with `/api/oauth/client`.
+#[derive(Debug, Deserialize)]
+pub struct WebAppOAuthAuthorizeQuery {
+  pub client_id: String,
+  pub redirect_uri: String,
+  pub response_type: String,
+  pub scope: Option<String>,
+  pub state: Option<String>,
+  pub code_challenge: Option<String>,
+  pub code_challenge_method: Option<String>,
+}
+
+/// Submitted by the consent page, `decision` is either `approve` or `deny`.
+#[derive(Debug, Deserialize)]
+pub struct WebApiOAuthConsentRequest {
+  pub client_id: String,
+  pub redirect_uri: String,
+  pub workspace_id: uuid::Uuid,
+  pub scope: String,
+  pub state: String,
+  pub code_challenge: String,
+  pub code_challenge_method: String,
+  pub csrf_token: String,
+  pub decision: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LoginParams {
   pub redirect_to: Option<String>,
@@ -7,10 +7,14 @@ use axum::{
   http::request::Parts,
   response::{IntoResponse, Redirect},
 };
-use axum_extra::extract::{cookie::Cookie, CookieJar};
+use axum_extra::extract::{
+  cookie::{Cookie, SameSite},
+  CookieJar,
+};
 use gotrue::grant::{Grant, RefreshTokenGrant};
 use gotrue_entity::dto::GotrueTokenResponse;
 use jwt::{Claims, Header};
+use rand::{distributions::Alphanumeric, Rng};
 use redis::{aio::ConnectionManager, AsyncCommands, FromRedisValue, ToRedisArgs};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
@@ -125,6 +129,32 @@ impl FromRedisValue for CodeSessionOptional {
 pub struct UserSession {
   pub session_id: String,
   pub token: GotrueTokenResponse,
+  /// Submitted with the forms that change state, so other sites can't post them on behalf of the
+  /// user. Empty for the sessions created before it was introduced, until they are loaded again.
+  #[serde(default)]
+  pub csrf_token: String,
+}
+
+impl UserSession {
+  pub fn new(session_id: String, token: GotrueTokenResponse) -> Self {
+    Self {
+      session_id,
+      token,
+      csrf_token: new_csrf_token(),
+    }
+  }
+
+  pub fn is_valid_csrf_token(&self, csrf_token: &str) -> bool {
+    !self.csrf_token.is_empty() && self.csrf_token == csrf_token
+  }
+}
+
+fn new_csrf_token() -> String {
+  rand::thread_rng()
+    .sample_iter(&Alphanumeric)
+    .take(32)
+    .map(char::from)
+    .collect()
 }
 struct UserSessionOptional(Option<UserSession>);
 
@@ -183,6 +213,12 @@ async fn get_session_from_store(
       None
     })?;
 
+  let mut updated = false;
+  if session.csrf_token.is_empty() {
+    session.csrf_token = new_csrf_token();
+    updated = true;
+  }
+
   if has_expired(session.token.access_token.as_str()) {
     // Get new pair of access token and refresh token
     let refresh_token = session.token.refresh_token;
@@ -200,7 +236,10 @@ async fn get_session_from_store(
 
     session.token.access_token = new_token.access_token;
     session.token.refresh_token = new_token.refresh_token;
+    updated = true;
+  }
 
+  if updated {
     // Update session in redis
     session_store
       .put_user_session(&session)
@@ -289,5 +328,6 @@ fn expect_redis_value_data(v: &redis::Value) -> redis::RedisResult<Option<&[u8]>
 pub fn new_session_cookie(id: uuid::Uuid) -> Cookie<'static> {
   let mut cookie = Cookie::new("session_id", id.to_string());
   cookie.set_path("/");
+  cookie.set_same_site(SameSite::Lax);
   cookie
 }
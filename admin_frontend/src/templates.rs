@@ -2,7 +2,10 @@ use askama::Template;
 use database_entity::dto::{AFWorkspace, AFWorkspaceInvitation};
 use gotrue_entity::{dto::User, sso::SSOProvider};
 
-use crate::{askama_entities::WorkspaceWithMembers, ext::entities::WorkspaceUsageLimits};
+use crate::{
+  askama_entities::{WorkspaceWithApiTokens, WorkspaceWithMembers},
+  ext::entities::WorkspaceUsageLimits,
+};
 
 #[derive(Template)]
 #[template(path = "pages/redirect.html")]
@@ -92,6 +95,29 @@ pub struct Invite {
   pub pending_workspace_invitations: Vec<AFWorkspaceInvitation>,
 }
 
+#[derive(Template)]
+#[template(path = "pages/oauth_consent.html")]
+pub struct OAuthConsent {
+  pub user_email: String,
+  pub client_name: String,
+  pub client_id: String,
+  pub redirect_uri: String,
+  pub scope: String,
+  pub scope_description: &'static str,
+  pub state: String,
+  pub code_challenge: String,
+  pub code_challenge_method: String,
+  pub csrf_token: String,
+  pub workspaces: Vec<AFWorkspace>,
+}
+
+#[derive(Template)]
+#[template(path = "components/api_tokens.html")]
+pub struct ApiTokens {
+  pub workspaces: Vec<WorkspaceWithApiTokens>,
+  pub created_token: Option<String>,
+}
+
 #[derive(Template)]
 #[template(path = "components/shared_workspaces.html")]
 pub struct SharedWorkspaces {
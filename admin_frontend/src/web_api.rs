@@ -1,13 +1,13 @@
 use crate::error::WebApiError;
 use crate::ext::api::{
-  accept_workspace_invitation, delete_current_user, invite_user_to_workspace, leave_workspace,
-  verify_token_cloud,
+  accept_workspace_invitation, authorize_oauth_client, delete_current_user, get_oauth_client,
+  invite_user_to_workspace, leave_workspace, revoke_api_token, verify_token_cloud,
 };
 use crate::models::{AppState, WebApiLoginRequest};
 use crate::models::{
   LoginParams, OAuthRedirect, OAuthRedirectToken, WebApiAdminCreateUserRequest,
   WebApiChangePasswordRequest, WebApiCreateSSOProviderRequest, WebApiInviteUserRequest,
-  WebApiPutUserRequest,
+  WebApiOAuthConsentRequest, WebApiPutUserRequest,
 };
 use crate::response::WebApiResponse;
 use crate::session::{self, new_session_cookie, CodeSession, UserSession};
@@ -29,6 +29,7 @@ use gotrue_entity::dto::{GotrueTokenResponse, SignUpResponse, UpdateGotrueUserPa
 use rand::distributions::Alphanumeric;
 use rand::Rng;
 use sha2::Digest;
+use shared_entity::dto::oauth_dto::OAuthAuthorizeParams;
 use tracing::info;
 
 pub fn router() -> Router<AppState> {
@@ -36,6 +37,7 @@ pub fn router() -> Router<AppState> {
     .route("/signin", post(sign_in_handler))
     .route("/oauth-redirect", get(oauth_redirect_handler))
     .route("/oauth-redirect/token", get(oauth_redirect_token_handler))
+    .route("/oauth/authorize", post(oauth_authorize_consent_handler))
     .route("/signup", post(sign_up_handler))
     .route("/login-refresh/:refresh_token", post(login_refresh_handler))
     .route("/logout", post(logout_handler))
@@ -46,6 +48,10 @@ pub fn router() -> Router<AppState> {
     .route("/invite", post(invite_handler))
     .route("/workspace/:workspace_id/invite", post(workspace_invite_handler))
     .route("/workspace/:workspace_id/leave", post(leave_workspace_handler))
+    .route(
+      "/workspace/:workspace_id/api-token/:token_id",
+      delete(revoke_api_token_handler),
+    )
     .route("/invite/:invite_id/accept", post(invite_accept_handler))
     .route("/open_app", post(open_app_handler))
     .route("/delete-account", delete(delete_account_handler))
@@ -187,6 +193,80 @@ async fn leave_workspace_handler(
   Ok(WebApiResponse::<()>::from_str("Left workspace".into()))
 }
 
+async fn revoke_api_token_handler(
+  State(state): State<AppState>,
+  session: UserSession,
+  Path((workspace_id, token_id)): Path<(String, String)>,
+) -> Result<WebApiResponse<()>, WebApiError<'static>> {
+  revoke_api_token(
+    &session.token.access_token,
+    &workspace_id,
+    &token_id,
+    &state.appflowy_cloud_url,
+  )
+  .await?;
+
+  Ok(WebApiResponse::<()>::from_str("API token revoked".into()))
+}
+
+async fn oauth_authorize_consent_handler(
+  State(state): State<AppState>,
+  session: UserSession,
+  Form(param): Form<WebApiOAuthConsentRequest>,
+) -> Result<Redirect, WebApiError<'static>> {
+  if !session.is_valid_csrf_token(&param.csrf_token) {
+    return Err(WebApiError::new(
+      StatusCode::FORBIDDEN,
+      "invalid csrf token",
+    ));
+  }
+
+  let mut query = if param.decision == "approve" {
+    let resp = authorize_oauth_client(
+      &session.token.access_token,
+      &OAuthAuthorizeParams {
+        client_id: param.client_id,
+        redirect_uri: param.redirect_uri.clone(),
+        workspace_id: param.workspace_id,
+        scope: param.scope,
+        code_challenge: Some(param.code_challenge).filter(|s| !s.is_empty()),
+        code_challenge_method: Some(param.code_challenge_method).filter(|s| !s.is_empty()),
+      },
+      &state.appflowy_cloud_url,
+    )
+    .await?;
+    format!("code={}", urlencoding::encode(&resp.code))
+  } else {
+    // The redirect uri comes from the form, so it must be checked before redirecting to it.
+    let client = get_oauth_client(
+      &session.token.access_token,
+      &param.client_id,
+      &state.appflowy_cloud_url,
+    )
+    .await?;
+    if !client.redirect_uris.contains(&param.redirect_uri) {
+      return Err(WebApiError::new(
+        StatusCode::BAD_REQUEST,
+        format!("invalid redirect_uri: {}", param.redirect_uri),
+      ));
+    }
+    "error=access_denied".to_string()
+  };
+  if !param.state.is_empty() {
+    query.push_str(&format!("&state={}", urlencoding::encode(&param.state)));
+  }
+
+  let separator = if param.redirect_uri.contains('?') {
+    '&'
+  } else {
+    '?'
+  };
+  Ok(Redirect::to(&format!(
+    "{}{}{}",
+    param.redirect_uri, separator, query
+  )))
+}
+
 async fn invite_accept_handler(
   State(state): State<AppState>,
   session: UserSession,
@@ -586,10 +666,7 @@ async fn session_login(
   .await?;
 
   let new_session_id = uuid::Uuid::new_v4();
-  let new_session = session::UserSession {
-    session_id: new_session_id.to_string(),
-    token,
-  };
+  let new_session = session::UserSession::new(new_session_id.to_string(), token);
   state.session_store.put_user_session(&new_session).await?;
 
   let decoded_redirect_to = redirect_to.and_then(|s| match urlencoding::decode(s) {
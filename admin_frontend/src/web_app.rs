@@ -1,19 +1,24 @@
-use crate::askama_entities::WorkspaceWithMembers;
+use crate::askama_entities::{ApiTokenRow, WorkspaceWithApiTokens, WorkspaceWithMembers};
 use crate::error::WebAppError;
 use crate::ext::api::{
-  accept_workspace_invitation, get_accepted_workspace_invitations,
-  get_pending_workspace_invitations, get_user_owned_workspaces, get_user_profile,
-  get_user_workspace_limit, get_user_workspace_usages, get_user_workspaces, get_workspace_members,
-  verify_token_cloud,
+  accept_workspace_invitation, create_api_token, get_accepted_workspace_invitations,
+  get_api_tokens, get_oauth_client, get_pending_workspace_invitations, get_user_owned_workspaces,
+  get_user_profile, get_user_workspace_limit, get_user_workspace_usages, get_user_workspaces,
+  get_workspace_members, verify_token_cloud,
+};
+use crate::models::{
+  LoginParams, OAuthLoginAction, WebAppCreateApiTokenRequest, WebAppOAuthAuthorizeQuery,
+  WebAppOAuthLoginRequest,
 };
-use crate::models::{LoginParams, OAuthLoginAction, WebAppOAuthLoginRequest};
 use crate::session::{self, new_session_cookie, UserSession};
 use askama::Template;
 use axum::extract::{Path, Query, State};
 use axum::response::{IntoResponse, Redirect, Result};
-use axum::{response::Html, routing::get, Router};
+use axum::{response::Html, routing::get, Form, Router};
 use axum_extra::extract::CookieJar;
+use chrono::{Duration, Utc};
 use gotrue_entity::dto::User;
+use shared_entity::dto::api_token_dto::{ApiTokenScope, CreateApiTokenParams};
 
 use crate::{templates, AppState};
 
@@ -37,6 +42,7 @@ fn page_router() -> Router<AppState> {
     )
     .route("/home", get(home_handler))
     .route("/admin/home", get(admin_home_handler))
+    .route("/oauth/authorize", get(oauth_authorize_handler))
 }
 
 fn component_router() -> Router<AppState> {
@@ -49,6 +55,10 @@ fn component_router() -> Router<AppState> {
     .route("/user/shared-workspaces", get(shared_workspaces_handler))
     .route("/user/user-usage", get(user_usage_handler))
     .route("/user/workspace-usage", get(workspace_usage_handler))
+    .route(
+      "/user/api-tokens",
+      get(api_tokens_handler).post(create_api_token_handler),
+    )
 
     // Admin actions
     .route("/admin/navigate", get(admin_navigate_handler))
@@ -127,10 +137,7 @@ async fn login_callback_query_handler(
   .await?;
 
   let new_session_id = uuid::Uuid::new_v4();
-  let new_session = session::UserSession {
-    session_id: new_session_id.to_string(),
-    token,
-  };
+  let new_session = session::UserSession::new(new_session_id.to_string(), token);
   state.session_store.put_user_session(&new_session).await?;
   jar = jar.add(new_session_cookie(new_session_id));
 
@@ -343,6 +350,125 @@ async fn workspace_usage_handler(
   render_template(templates::WorkspaceUsageList { workspace_usages })
 }
 
+async fn api_tokens_handler(
+  State(state): State<AppState>,
+  session: UserSession,
+) -> Result<Html<String>, WebAppError> {
+  let workspaces = get_workspaces_with_api_tokens(&state, &session).await?;
+  render_template(templates::ApiTokens {
+    workspaces,
+    created_token: None,
+  })
+}
+
+async fn create_api_token_handler(
+  State(state): State<AppState>,
+  session: UserSession,
+  Form(param): Form<WebAppCreateApiTokenRequest>,
+) -> Result<Html<String>, WebAppError> {
+  let expires_at = match param.expires_in_days.as_deref().map(str::trim) {
+    None | Some("") => None,
+    Some(days) => {
+      let days = days
+        .parse::<i64>()
+        .map_err(|err| WebAppError::BadRequest(format!("Invalid number of days: {}", err)))?;
+      Some(Utc::now() + Duration::days(days))
+    },
+  };
+  let created = create_api_token(
+    &session.token.access_token,
+    &param.workspace_id,
+    &CreateApiTokenParams {
+      name: param.name,
+      scope: ApiTokenScope::from(param.scope),
+      expires_at,
+    },
+    &state.appflowy_cloud_url,
+  )
+  .await?;
+
+  let workspaces = get_workspaces_with_api_tokens(&state, &session).await?;
+  render_template(templates::ApiTokens {
+    workspaces,
+    created_token: Some(created.token),
+  })
+}
+
+async fn get_workspaces_with_api_tokens(
+  state: &AppState,
+  session: &UserSession,
+) -> Result<Vec<WorkspaceWithApiTokens>, WebAppError> {
+  let user_workspaces =
+    get_user_workspaces(&session.token.access_token, &state.appflowy_cloud_url).await?;
+  let mut workspaces = Vec::with_capacity(user_workspaces.len());
+  for workspace in user_workspaces {
+    let api_tokens = get_api_tokens(
+      &session.token.access_token,
+      &workspace.workspace_id.to_string(),
+      &state.appflowy_cloud_url,
+    )
+    .await?;
+    workspaces.push(WorkspaceWithApiTokens {
+      workspace,
+      api_tokens: api_tokens.into_iter().map(ApiTokenRow::from).collect(),
+    });
+  }
+  Ok(workspaces)
+}
+
+/// Consent page of the authorization code flow for third-party applications. The decision is
+/// posted to `/web-api/oauth/authorize`, which redirects back to the application.
+async fn oauth_authorize_handler(
+  State(state): State<AppState>,
+  session: UserSession,
+  Query(query): Query<WebAppOAuthAuthorizeQuery>,
+) -> Result<Html<String>, WebAppError> {
+  if query.response_type != "code" {
+    return Err(WebAppError::BadRequest(
+      "invalid response_type, only 'code' is supported".to_string(),
+    ));
+  }
+  let scope = query
+    .scope
+    .unwrap_or_else(|| ApiTokenScope::Read.as_oauth_scope().to_string());
+  let scope_description = match ApiTokenScope::from_oauth_scope(&scope) {
+    Some(ApiTokenScope::Read) => "Read the content of the workspace",
+    Some(ApiTokenScope::Write) => "Read and edit the content of the workspace",
+    Some(ApiTokenScope::Admin) => "Read, edit and manage the workspace",
+    None => return Err(WebAppError::BadRequest(format!("invalid scope: {}", scope))),
+  };
+
+  // Never redirect to an uri that isn't registered for the client, show an error instead.
+  let client = get_oauth_client(
+    &session.token.access_token,
+    &query.client_id,
+    &state.appflowy_cloud_url,
+  )
+  .await?;
+  if !client.redirect_uris.contains(&query.redirect_uri) {
+    return Err(WebAppError::BadRequest(format!(
+      "invalid redirect_uri: {}",
+      query.redirect_uri
+    )));
+  }
+
+  let workspaces =
+    get_user_workspaces(&session.token.access_token, &state.appflowy_cloud_url).await?;
+  render_template(templates::OAuthConsent {
+    user_email: session.token.user.email.clone(),
+    client_name: client.name,
+    client_id: client.client_id,
+    redirect_uri: query.redirect_uri,
+    scope,
+    scope_description,
+    state: query.state.unwrap_or_default(),
+    code_challenge: query.code_challenge.unwrap_or_default(),
+    code_challenge_method: query.code_challenge_method.unwrap_or_default(),
+    csrf_token: session.csrf_token.clone(),
+    workspaces,
+  })
+}
+
 async fn admin_users_create_handler() -> Result<Html<String>, WebAppError> {
   render_template(templates::CreateUser)
 }
@@ -1,5 +1,5 @@
 use actix_http::Method;
-use database_entity::dto::{AFAccessLevel, AFRole};
+use database_entity::dto::{AFAccessLevel, AFRole, AFWorkspacePermission};
 use redis::{ErrorKind, FromRedisValue, RedisError, RedisResult, RedisWrite, ToRedisArgs, Value};
 use std::cmp::Ordering;
 
@@ -15,6 +15,7 @@ pub enum ActionVariant<'a> {
   FromRole(&'a AFRole),
   FromAccessLevel(&'a AFAccessLevel),
   FromAction(&'a Action),
+  FromPermission(&'a AFWorkspacePermission),
 }
 
 impl<'a> ActionVariant<'a> {
@@ -23,6 +24,7 @@ impl<'a> ActionVariant<'a> {
       ActionVariant::FromRole(role) => role.policy_acts(),
       ActionVariant::FromAccessLevel(level) => level.policy_acts(),
       ActionVariant::FromAction(action) => action.policy_acts(),
+      ActionVariant::FromPermission(permission) => permission.policy_acts(),
     }
   }
 
@@ -31,6 +33,7 @@ impl<'a> ActionVariant<'a> {
       ActionVariant::FromRole(role) => role.to_enforce_act(),
       ActionVariant::FromAccessLevel(level) => level.to_enforce_act(),
       ActionVariant::FromAction(action) => action.to_enforce_act(),
+      ActionVariant::FromPermission(permission) => permission.to_enforce_act(),
     }
   }
 }
@@ -111,6 +114,32 @@ impl Acts for AFRole {
   }
 }
 
+impl Acts for AFWorkspacePermission {
+  /// A permission is only granted by a policy with the same permission. Unlike the roles and the
+  /// access levels, permissions don't imply each other.
+  fn policy_acts(&self) -> Vec<&'static str> {
+    vec![self.to_enforce_act()]
+  }
+
+  fn to_enforce_act(&self) -> &'static str {
+    match self {
+      AFWorkspacePermission::InviteMembers => "p:invite_members",
+      AFWorkspacePermission::Publish => "p:publish",
+      AFWorkspacePermission::ManageBilling => "p:manage_billing",
+      AFWorkspacePermission::DeletePages => "p:delete_pages",
+      AFWorkspacePermission::UseAI => "p:use_ai",
+      AFWorkspacePermission::Export => "p:export",
+    }
+  }
+
+  fn from_enforce_act(act: &str) -> Self {
+    act
+      .strip_prefix("p:")
+      .and_then(AFWorkspacePermission::from_name)
+      .unwrap_or(AFWorkspacePermission::Export)
+  }
+}
+
 /// Represents the actions that can be performed on objects.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Action {
@@ -77,6 +77,35 @@ impl AccessControl {
     Ok(())
   }
 
+  pub async fn remove_policy_with_action(
+    &self,
+    sub: &SubjectType,
+    obj: &ObjectType<'_>,
+    act: ActionVariant<'_>,
+  ) -> Result<(), AppError> {
+    self.enforcer.remove_policy_with_action(sub, obj, act).await
+  }
+
+  pub async fn add_grouping_policy(
+    &self,
+    sub: &SubjectType,
+    group_sub: &SubjectType,
+  ) -> Result<(), AppError> {
+    self.enforcer.add_grouping_policy(sub, group_sub).await
+  }
+
+  pub async fn remove_grouping_policy(
+    &self,
+    sub: &SubjectType,
+    group_sub: &SubjectType,
+  ) -> Result<(), AppError> {
+    self.enforcer.remove_grouping_policy(sub, group_sub).await
+  }
+
+  pub async fn remove_group(&self, group_sub: &SubjectType) -> Result<(), AppError> {
+    self.enforcer.remove_group(group_sub).await
+  }
+
   pub async fn enforce(
     &self,
     workspace_id: &str,
@@ -114,10 +143,14 @@ impl AccessControl {
 /// - **"30" (Read and Write):** Permissions to `read` and `write`.
 /// - **"50" (Full Access):** Permissions to `read`, `write`, and `delete`.
 ///
+/// ## Grouping:
+/// - `g = role_or_level, action`: maps the roles and access levels to the actions they allow.
+/// - `g = uid, group::<group_id>`: links a member to the groups it belongs to.
+///
 /// ## Matchers:
-/// - `m = r.sub == p.sub && p.obj == r.obj && g(p.act, r.act)`
-///   Evaluates whether the subject and object in the request match those in a policy and if the
-///   given role or access level authorizes the action.
+/// - `m = p.obj == r.obj && (r.sub == p.sub || g(r.sub, p.sub)) && g(p.act, r.act)`
+///   Evaluates whether the subject, or one of its groups, and the object in the request match
+///   those in a policy and if the given role or access level authorizes the action.
 ///
 /// ## Examples:
 /// ### Policy 1 Evaluation (User Access with Role):
@@ -159,7 +192,7 @@ g = _, _ # grouping rule
 e = some(where (p.eft == allow))
 
 [matchers]
-m = r.sub == p.sub && p.obj == r.obj && (g(p.act, r.act) || cmpRoleOrLevel(r.act, p.act))
+m = p.obj == r.obj && (r.sub == p.sub || g(r.sub, p.sub)) && (g(p.act, r.act) || cmpRoleOrLevel(r.act, p.act))
 "###;
 
 pub async fn casbin_model() -> Result<DefaultModel, AppError> {
@@ -174,7 +207,7 @@ pub async fn casbin_model() -> Result<DefaultModel, AppError> {
 /// it is designed to compare roles or access levels specified in the request and policy.
 /// It supports two prefixes: "r:" for roles and "l:" for access levels. When the prefixes match,
 /// it compares the values to determine if the policy's role or level is greater than or equal to
-/// the request's role or level.
+/// the request's role or level. Permissions, prefixed with "p:", only match the same permission.
 ///
 /// # Arguments
 /// * `r_act` - The role or access level from the request, prefixed with "r:" for roles or "l:" for levels.
@@ -195,6 +228,10 @@ pub fn cmp_role_or_level(r_act: ImmutableString, p_act: ImmutableString) -> Dyna
     return Dynamic::from_bool(p >= r);
   }
 
+  if r_act.starts_with("p:") && p_act.starts_with("p:") {
+    return Dynamic::from_bool(r_act == p_act);
+  }
+
   if r_act.starts_with("l:") && p_act.starts_with("r:") {
     let r = AFAccessLevel::from_enforce_act(r_act.as_str());
     let role = AFRole::from_enforce_act(p_act.as_str());
@@ -223,6 +260,10 @@ const GROUPING_FIELD_INDEX_ROLE: usize = 0;
 #[allow(dead_code)]
 const GROUPING_FIELD_INDEX_ACTION: usize = 1;
 
+/// Index of the group in the grouping of a member to a group.
+/// `uid, group::<group_id>`
+pub const GROUPING_FIELD_INDEX_GROUP: usize = 1;
+
 pub(crate) async fn load_group_policies(enforcer: &mut Enforcer) -> Result<(), AppError> {
   // Grouping definition of access level to action.
   let af_access_levels = [
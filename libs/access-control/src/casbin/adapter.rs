@@ -1,18 +1,24 @@
 use async_trait::async_trait;
 
-use crate::entity::ObjectType;
+use crate::entity::{ObjectType, SubjectType};
 use crate::metrics::AccessControlMetrics;
+use casbin::error::AdapterError;
 use casbin::Adapter;
 use casbin::Filter;
 use casbin::Model;
 use casbin::Result;
 
+use database::group::{select_all_collab_group_members, select_all_workspace_group_members};
 use database::pg_row::AFWorkspaceMemberPermRow;
+use database::role::{permissions_from_names, select_workspace_member_custom_permissions};
+use database::view_permission::select_all_effective_view_permissions;
 use database::workspace::select_workspace_member_perm_stream;
+use database_entity::dto::{AFAccessLevel, AFWorkspacePermission};
 
 use crate::act::Acts;
 use futures_util::stream::BoxStream;
 use sqlx::PgPool;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio_stream::StreamExt;
@@ -72,8 +78,11 @@ impl PgAdapter {
 ///   `Member` implicitly has `Guest` permissions.
 /// - The policy object is derived from the `ObjectType::Workspace`, and actions are derived from
 ///   member roles (`Owner`, `Member`, `Guest`) using the `to_action` method.
+/// - Every member also gets a policy per workspace permission: the permissions of their custom
+///   role, found in `custom_permissions`, or the default permissions of their role.
 async fn load_workspace_policies(
   mut stream: BoxStream<'_, sqlx::Result<AFWorkspaceMemberPermRow>>,
+  custom_permissions: &HashMap<(i64, String), Vec<AFWorkspacePermission>>,
 ) -> Result<Vec<Vec<String>>> {
   let mut policies: Vec<Vec<String>> = Vec::new();
 
@@ -89,20 +98,110 @@ async fn load_workspace_policies(
       ];
       policies.push(policy);
     }
+
+    let permissions = custom_permissions
+      .get(&(uid, workspace_id.clone()))
+      .cloned()
+      .unwrap_or_else(|| member_permission.role.default_permissions());
+    for permission in permissions {
+      for act in permission.policy_acts() {
+        policies.push(vec![
+          uid.to_string(),
+          object_type.policy_object(),
+          act.to_string(),
+        ]);
+      }
+    }
+  }
+
+  Ok(policies)
+}
+
+/// Loads the access levels granted to the members on pages through space and page permissions,
+/// as `[uid, "collab::<view_id>", access_level]` policies.
+async fn load_view_policies(pg_pool: &PgPool) -> Result<Vec<Vec<String>>> {
+  let rows = select_all_effective_view_permissions(pg_pool)
+    .await
+    .map_err(|err| AdapterError(Box::new(err)))?;
+  let mut policies = Vec::with_capacity(rows.len());
+  for row in rows {
+    let view_id = row.view_id.to_string();
+    let object_type = ObjectType::Collab(&view_id);
+    for act in AFAccessLevel::from(row.access_level).policy_acts() {
+      policies.push(vec![
+        row.uid.to_string(),
+        object_type.policy_object(),
+        act.to_string(),
+      ]);
+    }
   }
+  Ok(policies)
+}
 
+/// Loads the access levels of the groups on collabs, as
+/// `["group::<group_id>", "collab::<oid>", access_level]` policies.
+async fn load_collab_group_policies(pg_pool: &PgPool) -> Result<Vec<Vec<String>>> {
+  let rows = select_all_collab_group_members(pg_pool)
+    .await
+    .map_err(|err| AdapterError(Box::new(err)))?;
+  let mut policies = Vec::with_capacity(rows.len());
+  for row in rows {
+    let subject = SubjectType::Group(row.group_id.to_string());
+    let object_type = ObjectType::Collab(&row.oid);
+    for act in AFAccessLevel::from(row.access_level).policy_acts() {
+      policies.push(vec![
+        subject.policy_subject(),
+        object_type.policy_object(),
+        act.to_string(),
+      ]);
+    }
+  }
   Ok(policies)
 }
 
+/// Loads the groups of the members, as `[uid, "group::<group_id>"]` grouping policies.
+async fn load_group_memberships(pg_pool: &PgPool) -> Result<Vec<Vec<String>>> {
+  let rows = select_all_workspace_group_members(pg_pool)
+    .await
+    .map_err(|err| AdapterError(Box::new(err)))?;
+  Ok(
+    rows
+      .into_iter()
+      .map(|row| {
+        vec![
+          SubjectType::User(row.uid).policy_subject(),
+          SubjectType::Group(row.group_id.to_string()).policy_subject(),
+        ]
+      })
+      .collect(),
+  )
+}
+
 #[async_trait]
 impl Adapter for PgAdapter {
   async fn load_policy(&mut self, model: &mut dyn Model) -> Result<()> {
     let start = Instant::now();
+    let custom_permissions = select_workspace_member_custom_permissions(&self.pg_pool)
+      .await
+      .map_err(|err| AdapterError(Box::new(err)))?
+      .into_iter()
+      .map(|row| {
+        (
+          (row.uid, row.workspace_id.to_string()),
+          permissions_from_names(&row.permissions),
+        )
+      })
+      .collect::<HashMap<_, _>>();
     let workspace_member_perm_stream = select_workspace_member_perm_stream(&self.pg_pool);
-    let workspace_policies = load_workspace_policies(workspace_member_perm_stream).await?;
+    let workspace_policies =
+      load_workspace_policies(workspace_member_perm_stream, &custom_permissions).await?;
 
     // Policy definition `p` of type `p`. See `model.conf`
     model.add_policies("p", "p", workspace_policies);
+    model.add_policies("p", "p", load_view_policies(&self.pg_pool).await?);
+    model.add_policies("p", "p", load_collab_group_policies(&self.pg_pool).await?);
+    // Grouping definition `g` of type `g`, linking the members to their groups.
+    model.add_policies("g", "g", load_group_memberships(&self.pg_pool).await?);
 
     self
       .access_control_metrics
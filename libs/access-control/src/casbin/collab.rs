@@ -1,24 +1,114 @@
 use app_error::AppError;
 use async_trait::async_trait;
+use database::share_link::select_share_link_access_level;
 use database_entity::dto::AFAccessLevel;
+use sqlx::PgPool;
 use tracing::instrument;
+use uuid::Uuid;
 
 use crate::{
   act::{Action, ActionVariant},
   collab::{CollabAccessControl, RealtimeAccessControl},
-  entity::ObjectType,
+  entity::{ObjectType, SubjectType},
 };
 
 use super::access::AccessControl;
 
+/// The workspace members that can perform the action on the workspace can perform it on any of
+/// its collabs. The others need an access level on the collab, granted through a space or page
+/// permission. Access levels only apply to the members of the workspace, so they don't outlive
+/// the membership.
+async fn enforce_member_collab_action(
+  access_control: &AccessControl,
+  workspace_id: &str,
+  uid: &i64,
+  oid: &str,
+  action: Action,
+) -> Result<(), AppError> {
+  access_control
+    .enforce(
+      workspace_id,
+      uid,
+      ObjectType::Workspace(workspace_id),
+      ActionVariant::FromAction(&Action::Read),
+    )
+    .await?;
+  access_control
+    .enforce(
+      workspace_id,
+      uid,
+      ObjectType::Collab(oid),
+      ActionVariant::FromAction(&action),
+    )
+    .await
+}
+
+/// The access level required to perform the action on a collab.
+fn required_access_level(action: &Action) -> AFAccessLevel {
+  match action {
+    Action::Read => AFAccessLevel::ReadOnly,
+    Action::Write => AFAccessLevel::ReadAndWrite,
+    Action::Delete => AFAccessLevel::FullAccess,
+  }
+}
+
+/// A page can also be accessed through the share links of the page the user joined, until the
+/// links expire or are revoked. The access level of a link applies to the collab of the page, i.e.
+/// the database of a database view.
+async fn enforce_share_link_access_level(
+  pg_pool: &PgPool,
+  workspace_id: &str,
+  uid: &i64,
+  oid: &str,
+  required_level: AFAccessLevel,
+) -> Result<(), AppError> {
+  let not_enough_permissions = || AppError::NotEnoughPermissions {
+    user: uid.to_string(),
+    workspace_id: workspace_id.to_string(),
+  };
+  let (Ok(workspace_uuid), Ok(object_id)) = (Uuid::parse_str(workspace_id), Uuid::parse_str(oid))
+  else {
+    return Err(not_enough_permissions());
+  };
+  let access_level = select_share_link_access_level(pg_pool, &workspace_uuid, &object_id, *uid)
+    .await?
+    .ok_or_else(not_enough_permissions)?;
+  if access_level >= required_level {
+    Ok(())
+  } else {
+    Err(not_enough_permissions())
+  }
+}
+
+async fn enforce_collab_action(
+  access_control: &AccessControl,
+  pg_pool: &PgPool,
+  workspace_id: &str,
+  uid: &i64,
+  oid: &str,
+  action: Action,
+  required_level: AFAccessLevel,
+) -> Result<(), AppError> {
+  match enforce_member_collab_action(access_control, workspace_id, uid, oid, action).await {
+    Err(AppError::NotEnoughPermissions { .. }) => {
+      enforce_share_link_access_level(pg_pool, workspace_id, uid, oid, required_level).await
+    },
+    result => result,
+  }
+}
+
 #[derive(Clone)]
 pub struct CollabAccessControlImpl {
   access_control: AccessControl,
+  pg_pool: PgPool,
 }
 
 impl CollabAccessControlImpl {
-  pub fn new(access_control: AccessControl) -> Self {
-    Self { access_control }
+  pub fn new(access_control: AccessControl, pg_pool: PgPool) -> Self {
+    Self {
+      access_control,
+      pg_pool,
+    }
   }
 }
 
@@ -28,11 +118,9 @@ impl CollabAccessControl for CollabAccessControlImpl {
     &self,
     workspace_id: &str,
     uid: &i64,
-    _oid: &str,
+    oid: &str,
     action: Action,
   ) -> Result<(), AppError> {
-    // TODO: allow non workspace member to read a collab.
-
     // Anyone who can write to a workspace, can also delete a collab.
     let workspace_action = match action {
       Action::Read => Action::Read,
@@ -40,26 +128,25 @@ impl CollabAccessControl for CollabAccessControlImpl {
       Action::Delete => Action::Write,
     };
 
-    self
-      .access_control
-      .enforce(
-        workspace_id,
-        uid,
-        ObjectType::Workspace(workspace_id),
-        ActionVariant::FromAction(&workspace_action),
-      )
-      .await
+    enforce_collab_action(
+      &self.access_control,
+      &self.pg_pool,
+      workspace_id,
+      uid,
+      oid,
+      workspace_action,
+      required_access_level(&action),
+    )
+    .await
   }
 
   async fn enforce_access_level(
     &self,
     workspace_id: &str,
     uid: &i64,
-    _oid: &str,
+    oid: &str,
     access_level: AFAccessLevel,
   ) -> Result<(), AppError> {
-    // TODO: allow non workspace member to read a collab.
-
     // Anyone who can write to a workspace, also have full access to a collab.
     let workspace_action = match access_level {
       AFAccessLevel::ReadOnly => Action::Read,
@@ -68,15 +155,16 @@ impl CollabAccessControl for CollabAccessControlImpl {
       AFAccessLevel::FullAccess => Action::Write,
     };
 
-    self
-      .access_control
-      .enforce(
-        workspace_id,
-        uid,
-        ObjectType::Workspace(workspace_id),
-        ActionVariant::FromAction(&workspace_action),
-      )
-      .await
+    enforce_collab_action(
+      &self.access_control,
+      &self.pg_pool,
+      workspace_id,
+      uid,
+      oid,
+      workspace_action,
+      access_level,
+    )
+    .await
   }
 
   #[instrument(level = "info", skip_all)]
@@ -95,27 +183,93 @@ impl CollabAccessControl for CollabAccessControlImpl {
     // TODO: allow non workspace member to read a collab.
     Ok(())
   }
+
+  #[instrument(level = "info", skip_all)]
+  async fn update_view_access_level(
+    &self,
+    uid: &i64,
+    oid: &str,
+    previous: Option<AFAccessLevel>,
+    level: Option<AFAccessLevel>,
+  ) -> Result<(), AppError> {
+    if let Some(previous) = previous {
+      self
+        .access_control
+        .remove_policy_with_action(
+          &SubjectType::User(*uid),
+          &ObjectType::Collab(oid),
+          ActionVariant::FromAccessLevel(&previous),
+        )
+        .await?;
+    }
+    if let Some(level) = level {
+      self
+        .access_control
+        .update_policy(
+          SubjectType::User(*uid),
+          ObjectType::Collab(oid),
+          ActionVariant::FromAccessLevel(&level),
+        )
+        .await?;
+    }
+    Ok(())
+  }
+
+  #[instrument(level = "info", skip_all)]
+  async fn update_group_access_level_policy(
+    &self,
+    group_id: &Uuid,
+    oid: &str,
+    level: AFAccessLevel,
+  ) -> Result<(), AppError> {
+    let group = SubjectType::Group(group_id.to_string());
+    self
+      .access_control
+      .remove_policy(&group, &ObjectType::Collab(oid))
+      .await?;
+    self
+      .access_control
+      .update_policy(
+        group,
+        ObjectType::Collab(oid),
+        ActionVariant::FromAccessLevel(&level),
+      )
+      .await
+  }
+
+  #[instrument(level = "info", skip_all)]
+  async fn remove_group_access_level(&self, group_id: &Uuid, oid: &str) -> Result<(), AppError> {
+    self
+      .access_control
+      .remove_policy(
+        &SubjectType::Group(group_id.to_string()),
+        &ObjectType::Collab(oid),
+      )
+      .await
+  }
 }
 
 #[derive(Clone)]
 pub struct RealtimeCollabAccessControlImpl {
   access_control: AccessControl,
+  pg_pool: PgPool,
 }
 
 impl RealtimeCollabAccessControlImpl {
-  pub fn new(access_control: AccessControl) -> Self {
-    Self { access_control }
+  pub fn new(access_control: AccessControl, pg_pool: PgPool) -> Self {
+    Self {
+      access_control,
+      pg_pool,
+    }
   }
 
   async fn can_perform_action(
     &self,
     workspace_id: &str,
     uid: &i64,
-    _oid: &str,
+    oid: &str,
     required_action: Action,
   ) -> Result<bool, AppError> {
-    // TODO: allow non workspace member to read a collab.
-
     // Anyone who can write to a workspace, can also delete a collab.
     let workspace_action = match required_action {
       Action::Read => Action::Read,
@@ -123,15 +277,16 @@ impl RealtimeCollabAccessControlImpl {
       Action::Delete => Action::Write,
     };
 
-    let enforcement_result = self
-      .access_control
-      .enforce(
-        workspace_id,
-        uid,
-        ObjectType::Workspace(workspace_id),
-        ActionVariant::FromAction(&workspace_action),
-      )
-      .await;
+    let enforcement_result = enforce_collab_action(
+      &self.access_control,
+      &self.pg_pool,
+      workspace_id,
+      uid,
+      oid,
+      workspace_action,
+      required_access_level(&required_action),
+    )
+    .await;
     match enforcement_result {
       Ok(_) => Ok(true),
       Err(AppError::NotEnoughPermissions {
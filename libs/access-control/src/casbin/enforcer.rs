@@ -1,4 +1,7 @@
-use super::access::{load_group_policies, POLICY_FIELD_INDEX_OBJECT, POLICY_FIELD_INDEX_SUBJECT};
+use super::access::{
+  load_group_policies, GROUPING_FIELD_INDEX_GROUP, POLICY_FIELD_INDEX_OBJECT,
+  POLICY_FIELD_INDEX_SUBJECT,
+};
 use crate::act::ActionVariant;
 use crate::entity::{ObjectType, SubjectType};
 use crate::metrics::MetricsCalState;
@@ -6,6 +9,7 @@ use crate::request::{PolicyRequest, WorkspacePolicyRequest};
 use anyhow::anyhow;
 use app_error::AppError;
 use casbin::{CoreApi, Enforcer, MgmtApi};
+use database_entity::dto::AFRole;
 use std::sync::atomic::Ordering;
 use tokio::sync::RwLock;
 use tracing::{event, instrument, trace};
@@ -68,8 +72,36 @@ impl AFEnforcer {
       .await
   }
 
-  /// Add a grouping policy.
-  #[allow(dead_code)]
+  /// Remove the policies added by [Self::update_policy] for the given action, keeping the other
+  /// policies of the subject on the object.
+  #[instrument(level = "debug", skip_all, err)]
+  pub async fn remove_policy_with_action(
+    &self,
+    sub: &SubjectType,
+    obj: &ObjectType<'_>,
+    act: ActionVariant<'_>,
+  ) -> Result<(), AppError> {
+    validate_obj_action(obj, &act)?;
+
+    let policies = act
+      .policy_acts()
+      .into_iter()
+      .map(|act| vec![sub.policy_subject(), obj.policy_object(), act.to_string()])
+      .collect::<Vec<Vec<_>>>();
+
+    trace!("[access control]: remove policy:{:?}", policies);
+    self
+      .enforcer
+      .write()
+      .await
+      .remove_policies(policies)
+      .await
+      .map_err(|e| AppError::Internal(anyhow!("fail to remove policy: {e:?}")))?;
+
+    Ok(())
+  }
+
+  /// Add a grouping policy, e.g. a user to a group. The user is granted the policies of the group.
   pub async fn add_grouping_policy(
     &self,
     sub: &SubjectType,
@@ -83,6 +115,35 @@ impl AFEnforcer {
     Ok(())
   }
 
+  /// Remove a grouping policy added by [Self::add_grouping_policy].
+  pub async fn remove_grouping_policy(
+    &self,
+    sub: &SubjectType,
+    group_sub: &SubjectType,
+  ) -> Result<(), AppError> {
+    let mut enforcer = self.enforcer.write().await;
+    enforcer
+      .remove_grouping_policy(vec![sub.policy_subject(), group_sub.policy_subject()])
+      .await
+      .map_err(|e| AppError::Internal(anyhow!("fail to remove grouping policy: {e:?}")))?;
+    Ok(())
+  }
+
+  /// Remove the policies of the group and the grouping policies of its members.
+  pub async fn remove_group(&self, group_sub: &SubjectType) -> Result<(), AppError> {
+    let group = group_sub.policy_subject();
+    let mut enforcer = self.enforcer.write().await;
+    enforcer
+      .remove_filtered_policy(POLICY_FIELD_INDEX_SUBJECT, vec![group.clone()])
+      .await
+      .map_err(|e| AppError::Internal(anyhow!("fail to remove group policies: {e:?}")))?;
+    enforcer
+      .remove_filtered_grouping_policy(GROUPING_FIELD_INDEX_GROUP, vec![group])
+      .await
+      .map_err(|e| AppError::Internal(anyhow!("fail to remove grouping policies: {e:?}")))?;
+    Ok(())
+  }
+
   /// 1. **Workspace Policy**: Initially, it checks if the user has permission at the workspace level. If the user
   ///    has permission to perform the action on the workspace, the function returns `true` without further checks.
   ///
@@ -113,15 +174,19 @@ impl AFEnforcer {
       .total_read_enforce_result
       .fetch_add(1, Ordering::Relaxed);
 
-    // 1. First, check workspace-level permissions.
-    let workspace_policy_request = WorkspacePolicyRequest::new(workspace_id, uid, &obj, &act);
-    let policy = workspace_policy_request.to_policy();
-    let mut result = self
-      .enforcer
-      .read()
-      .await
-      .enforce(policy)
-      .map_err(|e| AppError::Internal(anyhow!("enforce: {e:?}")))?;
+    // 1. First, check workspace-level permissions, unless the user was granted an access level on
+    //    the collab, which replaces the one of its role.
+    let mut result = false;
+    if !self.is_role_overridden(workspace_id, uid, &obj).await? {
+      let workspace_policy_request = WorkspacePolicyRequest::new(workspace_id, uid, &obj, &act);
+      let policy = workspace_policy_request.to_policy();
+      result = self
+        .enforcer
+        .read()
+        .await
+        .enforce(policy)
+        .map_err(|e| AppError::Internal(anyhow!("enforce: {e:?}")))?;
+    }
 
     // 2. Finally, enforce object-specific policy if previous checks fail.
     if !result {
@@ -145,6 +210,31 @@ impl AFEnforcer {
     }
   }
 
+  /// Returns true if the user has a policy of its own on the collab and doesn't own the
+  /// workspace. The owners keep full access to the collabs of their workspace.
+  async fn is_role_overridden(
+    &self,
+    workspace_id: &str,
+    uid: &i64,
+    obj: &ObjectType<'_>,
+  ) -> Result<bool, AppError> {
+    if !matches!(obj, ObjectType::Collab(_)) {
+      return Ok(false);
+    }
+    let enforcer = self.enforcer.read().await;
+    let object_policies =
+      policies_for_subject_with_given_object(&SubjectType::User(*uid), obj, &enforcer).await;
+    if object_policies.is_empty() {
+      return Ok(false);
+    }
+    let workspace = ObjectType::Workspace(workspace_id);
+    let owner = ActionVariant::FromRole(&AFRole::Owner);
+    let is_owner = enforcer
+      .enforce(WorkspacePolicyRequest::new(workspace_id, uid, &workspace, &owner).to_policy())
+      .map_err(|e| AppError::Internal(anyhow!("enforce: {e:?}")))?;
+    Ok(!is_owner)
+  }
+
   #[inline]
   async fn remove_with_enforcer(
     &self,
@@ -175,6 +265,7 @@ impl AFEnforcer {
 fn validate_obj_action(obj: &ObjectType<'_>, act: &ActionVariant) -> Result<(), AppError> {
   match (obj, act) {
     (ObjectType::Workspace(_), ActionVariant::FromRole(_))
+    | (ObjectType::Workspace(_), ActionVariant::FromPermission(_))
     | (ObjectType::Collab(_), ActionVariant::FromAccessLevel(_)) => Ok(()),
     _ => Err(AppError::Internal(anyhow!(
       "invalid object type and action type combination: object={:?}, action={:?}",
@@ -209,7 +300,7 @@ mod tests {
   };
   use app_error::ErrorCode;
   use casbin::{function_map::OperatorFunction, prelude::*};
-  use database_entity::dto::{AFAccessLevel, AFRole};
+  use database_entity::dto::{AFAccessLevel, AFRole, AFWorkspacePermission};
 
   use super::AFEnforcer;
 
@@ -416,6 +507,68 @@ mod tests {
     assert_eq!(error_code, ErrorCode::NotEnoughPermissions);
   }
 
+  #[tokio::test]
+  async fn workspace_member_collab_read_only_try_to_write_collab_test() {
+    let enforcer = test_enforcer().await;
+
+    let uid = 1;
+    let workspace_id = "w1";
+    let object_1 = "o1";
+
+    // add user as a member of the workspace
+    enforcer
+      .update_policy(
+        SubjectType::User(uid),
+        ObjectType::Workspace(workspace_id),
+        ActionVariant::FromRole(&AFRole::Member),
+      )
+      .await
+      .unwrap();
+
+    // the access level granted on the collab replaces the one of the member role
+    enforcer
+      .update_policy(
+        SubjectType::User(uid),
+        ObjectType::Collab(object_1),
+        ActionVariant::FromAccessLevel(&AFAccessLevel::ReadOnly),
+      )
+      .await
+      .unwrap();
+
+    let result = enforcer
+      .enforce_policy(
+        workspace_id,
+        &uid,
+        ObjectType::Collab(object_1),
+        ActionVariant::FromAction(&Action::Read),
+      )
+      .await;
+    assert!(result.is_ok());
+
+    let result = enforcer
+      .enforce_policy(
+        workspace_id,
+        &uid,
+        ObjectType::Collab(object_1),
+        ActionVariant::FromAction(&Action::Write),
+      )
+      .await;
+    assert!(result.is_err(), "the collab is read only");
+    let error_code = result.unwrap_err().code();
+    assert_eq!(error_code, ErrorCode::NotEnoughPermissions);
+
+    // the other collabs of the workspace can still be written
+    let result = enforcer
+      .enforce_policy(
+        workspace_id,
+        &uid,
+        ObjectType::Collab("o2"),
+        ActionVariant::FromAction(&Action::Write),
+      )
+      .await;
+    assert!(result.is_ok());
+  }
+
   #[tokio::test]
   async fn workspace_member_but_not_collab_member_and_try_full_access_collab_test() {
     let enforcer = test_enforcer().await;
@@ -757,4 +910,197 @@ mod tests {
       }
     }
   }
+
+  #[tokio::test]
+  async fn workspace_permission_test() {
+    let enforcer = test_enforcer().await;
+    let uid = 1;
+    let workspace_id = "w1";
+
+    // a member that can publish but not invite other members
+    enforcer
+      .update_policy(
+        SubjectType::User(uid),
+        ObjectType::Workspace(workspace_id),
+        ActionVariant::FromRole(&AFRole::Member),
+      )
+      .await
+      .unwrap();
+    enforcer
+      .update_policy(
+        SubjectType::User(uid),
+        ObjectType::Workspace(workspace_id),
+        ActionVariant::FromPermission(&AFWorkspacePermission::Publish),
+      )
+      .await
+      .unwrap();
+
+    assert!(enforcer
+      .enforce_policy(
+        workspace_id,
+        &uid,
+        ObjectType::Workspace(workspace_id),
+        ActionVariant::FromPermission(&AFWorkspacePermission::Publish),
+      )
+      .await
+      .is_ok());
+    for permission in [
+      AFWorkspacePermission::InviteMembers,
+      AFWorkspacePermission::DeletePages,
+    ] {
+      let result = enforcer
+        .enforce_policy(
+          workspace_id,
+          &uid,
+          ObjectType::Workspace(workspace_id),
+          ActionVariant::FromPermission(&permission),
+        )
+        .await;
+      assert_eq!(
+        result.unwrap_err().code(),
+        ErrorCode::NotEnoughPermissions,
+        "permission={:?}",
+        permission
+      );
+    }
+
+    // the permission doesn't leak into the roles
+    let result = enforcer
+      .enforce_policy(
+        workspace_id,
+        &uid,
+        ObjectType::Workspace(workspace_id),
+        ActionVariant::FromRole(&AFRole::Owner),
+      )
+      .await;
+    assert_eq!(result.unwrap_err().code(), ErrorCode::NotEnoughPermissions);
+  }
+
+  #[tokio::test]
+  async fn group_member_access_collab_test() {
+    let enforcer = test_enforcer().await;
+    let uid = 1;
+    let other_uid = 2;
+    let workspace_id = "w1";
+    let object_1 = "o1";
+    let group = SubjectType::Group("g1".to_string());
+
+    // the group can write the collab
+    enforcer
+      .update_policy(
+        SubjectType::Group("g1".to_string()),
+        ObjectType::Collab(object_1),
+        ActionVariant::FromAccessLevel(&AFAccessLevel::ReadAndWrite),
+      )
+      .await
+      .unwrap();
+    enforcer
+      .add_grouping_policy(&SubjectType::User(uid), &group)
+      .await
+      .unwrap();
+
+    for action in [Action::Read, Action::Write] {
+      let result = enforcer
+        .enforce_policy(
+          workspace_id,
+          &uid,
+          ObjectType::Collab(object_1),
+          ActionVariant::FromAction(&action),
+        )
+        .await;
+      assert!(result.is_ok(), "action={:?}", action);
+    }
+    let result = enforcer
+      .enforce_policy(
+        workspace_id,
+        &uid,
+        ObjectType::Collab(object_1),
+        ActionVariant::FromAction(&Action::Delete),
+      )
+      .await;
+    assert_eq!(result.unwrap_err().code(), ErrorCode::NotEnoughPermissions);
+
+    // a user outside of the group can't read the collab
+    let result = enforcer
+      .enforce_policy(
+        workspace_id,
+        &other_uid,
+        ObjectType::Collab(object_1),
+        ActionVariant::FromAction(&Action::Read),
+      )
+      .await;
+    assert_eq!(result.unwrap_err().code(), ErrorCode::NotEnoughPermissions);
+
+    // the user loses the access once removed from the group
+    enforcer
+      .remove_grouping_policy(&SubjectType::User(uid), &group)
+      .await
+      .unwrap();
+    let result = enforcer
+      .enforce_policy(
+        workspace_id,
+        &uid,
+        ObjectType::Collab(object_1),
+        ActionVariant::FromAction(&Action::Read),
+      )
+      .await;
+    assert_eq!(result.unwrap_err().code(), ErrorCode::NotEnoughPermissions);
+
+    // removing the group removes the access of all its members
+    enforcer
+      .add_grouping_policy(&SubjectType::User(other_uid), &group)
+      .await
+      .unwrap();
+    enforcer.remove_group(&group).await.unwrap();
+    let result = enforcer
+      .enforce_policy(
+        workspace_id,
+        &other_uid,
+        ObjectType::Collab(object_1),
+        ActionVariant::FromAction(&Action::Read),
+      )
+      .await;
+    assert_eq!(result.unwrap_err().code(), ErrorCode::NotEnoughPermissions);
+  }
+
+  #[tokio::test]
+  async fn remove_policy_with_action_keeps_other_levels_test() {
+    let enforcer = test_enforcer().await;
+    let uid = 1;
+    let workspace_id = "w1";
+    let object_1 = "o1";
+
+    // the user has full access to the collab and was granted read only on top of it
+    for level in [AFAccessLevel::FullAccess, AFAccessLevel::ReadOnly] {
+      enforcer
+        .update_policy(
+          SubjectType::User(uid),
+          ObjectType::Collab(object_1),
+          ActionVariant::FromAccessLevel(&level),
+        )
+        .await
+        .unwrap();
+    }
+
+    // removing the read only grant keeps the full access
+    enforcer
+      .remove_policy_with_action(
+        &SubjectType::User(uid),
+        &ObjectType::Collab(object_1),
+        ActionVariant::FromAccessLevel(&AFAccessLevel::ReadOnly),
+      )
+      .await
+      .unwrap();
+    for action in [Action::Read, Action::Write, Action::Delete] {
+      let result = enforcer
+        .enforce_policy(
+          workspace_id,
+          &uid,
+          ObjectType::Collab(object_1),
+          ActionVariant::FromAction(&action),
+        )
+        .await;
+      assert!(result.is_ok(), "action={:?}", action);
+    }
+  }
 }
@@ -7,7 +7,7 @@ use crate::act::{Action, ActionVariant};
 use crate::entity::{ObjectType, SubjectType};
 use crate::workspace::WorkspaceAccessControl;
 use app_error::AppError;
-use database_entity::dto::AFRole;
+use database_entity::dto::{AFRole, AFWorkspacePermission};
 
 #[derive(Clone)]
 pub struct WorkspaceAccessControlImpl {
@@ -56,6 +56,23 @@ impl WorkspaceAccessControl for WorkspaceAccessControlImpl {
       .await
   }
 
+  async fn enforce_permission(
+    &self,
+    uid: &i64,
+    workspace_id: &str,
+    permission: AFWorkspacePermission,
+  ) -> Result<(), AppError> {
+    self
+      .access_control
+      .enforce(
+        workspace_id,
+        uid,
+        ObjectType::Workspace(workspace_id),
+        ActionVariant::FromPermission(&permission),
+      )
+      .await
+  }
+
   #[instrument(level = "info", skip_all)]
   async fn insert_role(
     &self,
@@ -63,14 +80,47 @@ impl WorkspaceAccessControl for WorkspaceAccessControlImpl {
     workspace_id: &Uuid,
     role: AFRole,
   ) -> Result<(), AppError> {
+    let permissions = role.default_permissions();
+    self
+      .insert_role_with_permissions(uid, workspace_id, role, &permissions)
+      .await
+  }
+
+  #[instrument(level = "info", skip_all)]
+  async fn insert_role_with_permissions(
+    &self,
+    uid: &i64,
+    workspace_id: &Uuid,
+    role: AFRole,
+    permissions: &[AFWorkspacePermission],
+  ) -> Result<(), AppError> {
+    let workspace_id = workspace_id.to_string();
+    // Remove the previous role, otherwise a downgraded member would keep the higher role.
+    self
+      .access_control
+      .remove_policy(
+        &SubjectType::User(*uid),
+        &ObjectType::Workspace(&workspace_id),
+      )
+      .await?;
     self
       .access_control
       .update_policy(
         SubjectType::User(*uid),
-        ObjectType::Workspace(&workspace_id.to_string()),
+        ObjectType::Workspace(&workspace_id),
         ActionVariant::FromRole(&role),
       )
       .await?;
+    for permission in permissions {
+      self
+        .access_control
+        .update_policy(
+          SubjectType::User(*uid),
+          ObjectType::Workspace(&workspace_id),
+          ActionVariant::FromPermission(permission),
+        )
+        .await?;
+    }
     Ok(())
   }
 
@@ -97,4 +147,34 @@ impl WorkspaceAccessControl for WorkspaceAccessControlImpl {
       .await?;
     Ok(())
   }
+
+  #[instrument(level = "info", skip_all)]
+  async fn insert_group_member(&self, uid: &i64, group_id: &Uuid) -> Result<(), AppError> {
+    self
+      .access_control
+      .add_grouping_policy(
+        &SubjectType::User(*uid),
+        &SubjectType::Group(group_id.to_string()),
+      )
+      .await
+  }
+
+  #[instrument(level = "info", skip_all)]
+  async fn remove_group_member(&self, uid: &i64, group_id: &Uuid) -> Result<(), AppError> {
+    self
+      .access_control
+      .remove_grouping_policy(
+        &SubjectType::User(*uid),
+        &SubjectType::Group(group_id.to_string()),
+      )
+      .await
+  }
+
+  #[instrument(level = "info", skip_all)]
+  async fn remove_group(&self, group_id: &Uuid) -> Result<(), AppError> {
+    self
+      .access_control
+      .remove_group(&SubjectType::Group(group_id.to_string()))
+      .await
+  }
 }
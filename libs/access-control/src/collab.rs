@@ -2,6 +2,7 @@ use crate::act::Action;
 use app_error::AppError;
 use async_trait::async_trait;
 use database_entity::dto::AFAccessLevel;
+use uuid::Uuid;
 
 #[async_trait]
 pub trait CollabAccessControl: Sync + Send + 'static {
@@ -34,6 +35,28 @@ pub trait CollabAccessControl: Sync + Send + 'static {
   ) -> Result<(), AppError>;
 
   async fn remove_access_level(&self, uid: &i64, oid: &str) -> Result<(), AppError>;
+
+  /// Replaces the access level the user was granted on a page through a space or page
+  /// permission, `previous`, with `level`, or removes it if `level` is `None`. The other access
+  /// levels of the user on the page are kept. Only applies while the user is a member of the
+  /// workspace.
+  async fn update_view_access_level(
+    &self,
+    uid: &i64,
+    oid: &str,
+    previous: Option<AFAccessLevel>,
+    level: Option<AFAccessLevel>,
+  ) -> Result<(), AppError>;
+
+  /// Grants the access level in the collab to the members of the group.
+  async fn update_group_access_level_policy(
+    &self,
+    group_id: &Uuid,
+    oid: &str,
+    level: AFAccessLevel,
+  ) -> Result<(), AppError>;
+
+  async fn remove_group_access_level(&self, group_id: &Uuid, oid: &str) -> Result<(), AppError>;
 }
 
 #[async_trait]
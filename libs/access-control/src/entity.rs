@@ -1,6 +1,8 @@
 #[derive(Debug)]
 pub enum SubjectType {
   User(i64),
+  /// A group of workspace members, stored as `group::<group_id>`. The members are linked to the
+  /// group by grouping policies, so they are granted the policies of the group.
   Group(String),
 }
 
@@ -8,7 +10,7 @@ impl SubjectType {
   pub fn policy_subject(&self) -> String {
     match self {
       SubjectType::User(i) => i.to_string(),
-      SubjectType::Group(s) => s.clone(),
+      SubjectType::Group(s) => format!("group::{}", s),
     }
   }
 }
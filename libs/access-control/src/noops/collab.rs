@@ -1,6 +1,7 @@
 use app_error::AppError;
 use async_trait::async_trait;
 use database_entity::dto::AFAccessLevel;
+use uuid::Uuid;
 
 use crate::{
   act::Action,
@@ -56,6 +57,29 @@ impl CollabAccessControl for CollabAccessControlImpl {
   async fn remove_access_level(&self, _uid: &i64, _oid: &str) -> Result<(), AppError> {
     Ok(())
   }
+
+  async fn update_view_access_level(
+    &self,
+    _uid: &i64,
+    _oid: &str,
+    _previous: Option<AFAccessLevel>,
+    _level: Option<AFAccessLevel>,
+  ) -> Result<(), AppError> {
+    Ok(())
+  }
+
+  async fn update_group_access_level_policy(
+    &self,
+    _group_id: &Uuid,
+    _oid: &str,
+    _level: AFAccessLevel,
+  ) -> Result<(), AppError> {
+    Ok(())
+  }
+
+  async fn remove_group_access_level(&self, _group_id: &Uuid, _oid: &str) -> Result<(), AppError> {
+    Ok(())
+  }
 }
 
 #[derive(Clone)]
@@ -4,7 +4,7 @@ use uuid::Uuid;
 use crate::act::Action;
 use crate::workspace::WorkspaceAccessControl;
 use app_error::AppError;
-use database_entity::dto::AFRole;
+use database_entity::dto::{AFRole, AFWorkspacePermission};
 
 #[derive(Clone)]
 pub struct WorkspaceAccessControlImpl;
@@ -41,6 +41,15 @@ impl WorkspaceAccessControl for WorkspaceAccessControlImpl {
     Ok(())
   }
 
+  async fn enforce_permission(
+    &self,
+    _uid: &i64,
+    _workspace_id: &str,
+    _permission: AFWorkspacePermission,
+  ) -> Result<(), AppError> {
+    Ok(())
+  }
+
   async fn insert_role(
     &self,
     _uid: &i64,
@@ -50,6 +59,16 @@ impl WorkspaceAccessControl for WorkspaceAccessControlImpl {
     Ok(())
   }
 
+  async fn insert_role_with_permissions(
+    &self,
+    _uid: &i64,
+    _workspace_id: &Uuid,
+    _role: AFRole,
+    _permissions: &[AFWorkspacePermission],
+  ) -> Result<(), AppError> {
+    Ok(())
+  }
+
   async fn remove_user_from_workspace(
     &self,
     _uid: &i64,
@@ -57,4 +76,16 @@ impl WorkspaceAccessControl for WorkspaceAccessControlImpl {
   ) -> Result<(), AppError> {
     Ok(())
   }
+
+  async fn insert_group_member(&self, _uid: &i64, _group_id: &Uuid) -> Result<(), AppError> {
+    Ok(())
+  }
+
+  async fn remove_group_member(&self, _uid: &i64, _group_id: &Uuid) -> Result<(), AppError> {
+    Ok(())
+  }
+
+  async fn remove_group(&self, _group_id: &Uuid) -> Result<(), AppError> {
+    Ok(())
+  }
 }
@@ -1,7 +1,7 @@
 use crate::act::Action;
 use app_error::AppError;
 use async_trait::async_trait;
-use database_entity::dto::AFRole;
+use database_entity::dto::{AFRole, AFWorkspacePermission};
 use sqlx::types::Uuid;
 
 #[async_trait]
@@ -20,12 +20,41 @@ pub trait WorkspaceAccessControl: Send + Sync + 'static {
     action: Action,
   ) -> Result<(), AppError>;
 
+  /// Check if the user has the permission in the workspace.
+  /// Returns AppError::NotEnoughPermission if the user does not have the permission.
+  async fn enforce_permission(
+    &self,
+    uid: &i64,
+    workspace_id: &str,
+    permission: AFWorkspacePermission,
+  ) -> Result<(), AppError>;
+
+  /// Replaces the role of the user in the workspace. The user is granted the default permissions
+  /// of the role, see [AFRole::default_permissions].
   async fn insert_role(&self, uid: &i64, workspace_id: &Uuid, role: AFRole)
     -> Result<(), AppError>;
 
+  /// Replaces the role of the user in the workspace, granting the given permissions instead of the
+  /// default ones of the role. Used for the members that have a custom role.
+  async fn insert_role_with_permissions(
+    &self,
+    uid: &i64,
+    workspace_id: &Uuid,
+    role: AFRole,
+    permissions: &[AFWorkspacePermission],
+  ) -> Result<(), AppError>;
+
   async fn remove_user_from_workspace(
     &self,
     uid: &i64,
     workspace_id: &Uuid,
   ) -> Result<(), AppError>;
+
+  /// Adds the user to the group, granting it the access levels of the group.
+  async fn insert_group_member(&self, uid: &i64, group_id: &Uuid) -> Result<(), AppError>;
+
+  async fn remove_group_member(&self, uid: &i64, group_id: &Uuid) -> Result<(), AppError>;
+
+  /// Removes the access levels of the group and its members.
+  async fn remove_group(&self, group_id: &Uuid) -> Result<(), AppError>;
 }
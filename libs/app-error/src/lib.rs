@@ -177,6 +177,9 @@ pub enum AppError {
 
   #[error("{0}")]
   ServiceTemporaryUnavailable(String),
+
+  #[error("{0}")]
+  TooManyRequests(String),
 }
 
 impl AppError {
@@ -254,6 +257,7 @@ impl AppError {
         ErrorCode::CustomNamespaceInvalidCharacter
       },
       AppError::ServiceTemporaryUnavailable(_) => ErrorCode::ServiceTemporaryUnavailable,
+      AppError::TooManyRequests(_) => ErrorCode::TooManyRequests,
     }
   }
 }
@@ -394,6 +398,7 @@ pub enum ErrorCode {
   PublishNameTooLong = 1052,
   CustomNamespaceInvalidCharacter = 1053,
   ServiceTemporaryUnavailable = 1054,
+  TooManyRequests = 1055,
 }
 
 impl ErrorCode {
@@ -2,8 +2,8 @@ use crate::dto::{
   AIModel, CalculateSimilarityParams, ChatAnswer, ChatQuestion, CompleteTextResponse,
   CompletionType, CreateChatContext, CustomPrompt, Document, EmbeddingRequest, EmbeddingResponse,
   LocalAIConfig, MessageData, RepeatedLocalAIPackage, RepeatedRelatedQuestion,
-  SearchDocumentsRequest, SimilarityResponse, SummarizeRowResponse, TranslateRowData,
-  TranslateRowResponse,
+  SearchDocumentsRequest, SimilarityResponse, SummarizeRowResponse, ToolDefinition, ToolResult,
+  TranslateRowData, TranslateRowResponse,
 };
 use crate::error::AIError;
 
@@ -207,6 +207,32 @@ impl AppFlowyAIClient {
     content: &str,
     model: &AIModel,
     metadata: Option<Value>,
+  ) -> Result<ChatAnswer, AIError> {
+    self
+      .send_question_with_tools(
+        chat_id,
+        question_id,
+        content,
+        model,
+        metadata,
+        vec![],
+        vec![],
+      )
+      .await
+  }
+
+  /// Sends the question with the tools the model can call. When the model calls tools, the
+  /// returned answer contains the calls and the question must be sent again with their results.
+  #[allow(clippy::too_many_arguments)]
+  pub async fn send_question_with_tools(
+    &self,
+    chat_id: &str,
+    question_id: i64,
+    content: &str,
+    model: &AIModel,
+    metadata: Option<Value>,
+    tools: Vec<ToolDefinition>,
+    tool_results: Vec<ToolResult>,
   ) -> Result<ChatAnswer, AIError> {
     let json = ChatQuestion {
       chat_id: chat_id.to_string(),
@@ -215,6 +241,8 @@ impl AppFlowyAIClient {
         metadata,
         rag_ids: vec![],
         message_id: Some(question_id.to_string()),
+        tools,
+        tool_results,
       },
     };
     let url = format!("{}/chat/message", self.url);
@@ -244,6 +272,8 @@ impl AppFlowyAIClient {
         metadata,
         rag_ids,
         message_id: None,
+        tools: vec![],
+        tool_results: vec![],
       },
     };
     let url = format!("{}/chat/message/stream", self.url);
@@ -265,6 +295,34 @@ impl AppFlowyAIClient {
     metadata: Option<Value>,
     rag_ids: Vec<String>,
     model: &AIModel,
+  ) -> Result<impl Stream<Item = Result<Bytes, AIError>>, AIError> {
+    self
+      .stream_question_v2_with_tools(
+        chat_id,
+        question_id,
+        content,
+        metadata,
+        rag_ids,
+        model,
+        vec![],
+        vec![],
+      )
+      .await
+  }
+
+  /// Streams the answer of the question. Besides the metadata and the answer, the stream contains
+  /// the tools called by the model under the [crate::dto::STREAM_TOOL_CALL_KEY] key.
+  #[allow(clippy::too_many_arguments)]
+  pub async fn stream_question_v2_with_tools(
+    &self,
+    chat_id: &str,
+    question_id: i64,
+    content: &str,
+    metadata: Option<Value>,
+    rag_ids: Vec<String>,
+    model: &AIModel,
+    tools: Vec<ToolDefinition>,
+    tool_results: Vec<ToolResult>,
   ) -> Result<impl Stream<Item = Result<Bytes, AIError>>, AIError> {
     let json = ChatQuestion {
       chat_id: chat_id.to_string(),
@@ -273,6 +331,8 @@ impl AppFlowyAIClient {
         metadata,
         rag_ids,
         message_id: Some(question_id.to_string()),
+        tools,
+        tool_results,
       },
     };
     let url = format!("{}/v2/chat/message/stream", self.url);
@@ -7,6 +7,8 @@ use std::str::FromStr;
 
 pub const STREAM_METADATA_KEY: &str = "0";
 pub const STREAM_ANSWER_KEY: &str = "1";
+/// Key of the streamed values that carry a [ToolCall] requested by the model.
+pub const STREAM_TOOL_CALL_KEY: &str = "2";
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SummarizeRowResponse {
   pub text: String,
@@ -27,6 +29,12 @@ pub struct MessageData {
   pub rag_ids: Vec<String>,
   #[serde(default)]
   pub message_id: Option<String>,
+  /// Tools the model can call while answering the question.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub tools: Vec<ToolDefinition>,
+  /// Results of the tools called by the model in the previous rounds of the same question.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub tool_results: Vec<ToolResult>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -34,6 +42,35 @@ pub struct ChatAnswer {
   pub content: String,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub metadata: Option<serde_json::Value>,
+  /// Tools the model wants to call before it can answer. The content of the answer is not final
+  /// when it's not empty.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub tool_calls: Vec<ToolCall>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ToolDefinition {
+  pub name: String,
+  pub description: String,
+  /// JSON schema of the arguments of the tool.
+  pub parameters: serde_json::Value,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ToolCall {
+  pub id: String,
+  pub name: String,
+  #[serde(default)]
+  pub arguments: serde_json::Value,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ToolResult {
+  pub call_id: String,
+  pub name: String,
+  pub content: serde_json::Value,
+  #[serde(default)]
+  pub is_error: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
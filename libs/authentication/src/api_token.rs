@@ -0,0 +1,70 @@
+use gotrue_entity::gotrue_jwt::GoTrueJWTClaims;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::types::Uuid;
+
+use crate::jwt::Authorization;
+
+/// Prefix of the workspace API tokens. It tells them apart from the GoTrue JWTs sent in the same
+/// `Authorization: Bearer` header.
+pub const API_TOKEN_PREFIX: &str = "af_pat_";
+
+/// Number of characters of a token that are stored in plain text, so the owner can recognize the
+/// token in the list of tokens.
+const API_TOKEN_DISPLAY_LEN: usize = API_TOKEN_PREFIX.len() + 6;
+
+/// The user a workspace API token acts on behalf of. The server verifies the token and inserts
+/// the identity in the request extensions, where the [Authorization] extractor picks it up.
+#[derive(Debug, Clone)]
+pub struct ApiTokenIdentity {
+  pub token_id: Uuid,
+  pub workspace_id: Uuid,
+  pub user_uuid: Uuid,
+  pub email: String,
+}
+
+impl ApiTokenIdentity {
+  pub fn authorization(&self, token: &str) -> Authorization {
+    Authorization {
+      token: token.to_string(),
+      claims: GoTrueJWTClaims {
+        aud: None,
+        exp: None,
+        jti: Some(self.token_id.to_string()),
+        iat: None,
+        iss: None,
+        nbf: None,
+        sub: Some(self.user_uuid.to_string()),
+        email: self.email.clone(),
+        phone: String::new(),
+        app_metadata: Default::default(),
+        user_metadata: Default::default(),
+        role: "authenticated".to_string(),
+        aal: None,
+        amr: None,
+        session_id: None,
+      },
+    }
+  }
+}
+
+pub fn is_api_token(token: &str) -> bool {
+  token.starts_with(API_TOKEN_PREFIX)
+}
+
+/// Generates a new token. It is only shown to the user once, the server keeps its hash.
+pub fn generate_api_token() -> String {
+  let mut bytes = [0u8; 32];
+  rand::thread_rng().fill_bytes(&mut bytes);
+  format!("{}{}", API_TOKEN_PREFIX, hex::encode(bytes))
+}
+
+/// The tokens carry 256 random bits, so unlike passwords they don't need a salted, slow hash and
+/// can be looked up by their hash directly.
+pub fn hash_api_token(token: &str) -> String {
+  hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+pub fn api_token_display_prefix(token: &str) -> String {
+  token.chars().take(API_TOKEN_DISPLAY_LEN).collect()
+}
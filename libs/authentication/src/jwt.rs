@@ -1,6 +1,7 @@
 use actix_http::Payload;
 use actix_web::{web::Data, FromRequest, HttpRequest};
 
+use crate::api_token::{is_api_token, ApiTokenIdentity};
 use gotrue_entity::gotrue_jwt::GoTrueJWTClaims;
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
@@ -148,6 +149,15 @@ fn get_auth_from_request(req: &HttpRequest) -> Result<Authorization, actix_web::
       "Invalid Authorization header, missing Bearer",
     ))?;
 
+  // API tokens are verified against the database before the request reaches the extractors.
+  if is_api_token(token) {
+    return req
+      .extensions()
+      .get::<ApiTokenIdentity>()
+      .map(|identity| identity.authorization(token))
+      .ok_or(actix_web::error::ErrorUnauthorized("Invalid API token"));
+  }
+
   authorization_from_token(token, jwt_secret_data)
 }
 
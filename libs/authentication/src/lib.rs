@@ -1,4 +1,7 @@
+pub mod api_token;
 pub mod error;
 pub mod jwt;
+pub mod oauth;
 pub mod password;
+pub mod share_link;
 pub mod user;
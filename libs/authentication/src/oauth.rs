@@ -0,0 +1,39 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// The only PKCE method supported, `plain` doesn't protect against an intercepted code.
+pub const PKCE_METHOD_S256: &str = "S256";
+
+fn random_hex(prefix: &str, len: usize) -> String {
+  let mut bytes = vec![0u8; len];
+  rand::thread_rng().fill_bytes(&mut bytes);
+  format!("{}{}", prefix, hex::encode(bytes))
+}
+
+pub fn generate_oauth_client_id() -> String {
+  random_hex("af_client_", 16)
+}
+
+/// Like the API tokens, client secrets and authorization codes are random and only their hash is
+/// stored, see [crate::api_token::hash_api_token].
+pub fn generate_oauth_client_secret() -> String {
+  random_hex("af_secret_", 32)
+}
+
+pub fn generate_oauth_authorization_code() -> String {
+  random_hex("", 32)
+}
+
+pub fn generate_oauth_refresh_token() -> String {
+  random_hex("af_refresh_", 32)
+}
+
+/// Checks the `code_verifier` of the token request against the `code_challenge` of the
+/// authorization request, as described in RFC 7636: the challenge is the unpadded base64url
+/// encoding of the SHA-256 of the verifier.
+pub fn verify_pkce_s256(code_challenge: &str, code_verifier: &str) -> bool {
+  let hashed = Sha256::digest(code_verifier.as_bytes());
+  URL_SAFE_NO_PAD.encode(hashed) == code_challenge
+}
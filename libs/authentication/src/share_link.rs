@@ -0,0 +1,17 @@
+use rand::RngCore;
+
+/// Prefix of the share link tokens, which are hashed like the API tokens, see
+/// [crate::api_token::hash_api_token].
+pub const SHARE_LINK_TOKEN_PREFIX: &str = "af_share_";
+
+const SHARE_LINK_DISPLAY_LEN: usize = SHARE_LINK_TOKEN_PREFIX.len() + 6;
+
+pub fn generate_share_link_token() -> String {
+  let mut bytes = [0u8; 32];
+  rand::thread_rng().fill_bytes(&mut bytes);
+  format!("{}{}", SHARE_LINK_TOKEN_PREFIX, hex::encode(bytes))
+}
+
+pub fn share_link_display_prefix(token: &str) -> String {
+  token.chars().take(SHARE_LINK_DISPLAY_LEN).collect()
+}
@@ -1,6 +1,6 @@
 use client_api_entity::{
-  access_request_dto::AccessRequest, AccessRequestMinimal, ApproveAccessRequestParams,
-  CreateAccessRequestParams,
+  access_request_dto::{AccessRequest, QueryAccessRequestParams, RepeatedAccessRequestItem},
+  AccessRequestMinimal, ApproveAccessRequestParams, CreateAccessRequestParams,
 };
 use reqwest::Method;
 use shared_entity::response::{AppResponse, AppResponseError};
@@ -24,6 +24,24 @@ impl Client {
       .into_data()
   }
 
+  /// Returns the access requests of the workspace, the most recent first. Only the owners of the
+  /// workspace can list them.
+  pub async fn list_access_requests(
+    &self,
+    params: &QueryAccessRequestParams,
+  ) -> Result<RepeatedAccessRequestItem, AppResponseError> {
+    let url = format!("{}/api/access-request", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(params)
+      .send()
+      .await?;
+    AppResponse::<RepeatedAccessRequestItem>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
   pub async fn create_access_request(
     &self,
     data: CreateAccessRequestParams,
@@ -51,15 +69,44 @@ impl Client {
     let resp = self
       .http_client_with_auth(Method::POST, &url)
       .await?
-      .json(&ApproveAccessRequestParams { is_approved: true })
+      .json(&ApproveAccessRequestParams {
+        is_approved: true,
+        group_id: None,
+        reason: None,
+      })
+      .send()
+      .await?;
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Approves the request and adds the requester to the group of the workspace.
+  pub async fn approve_access_request_into_group(
+    &self,
+    access_request_id: Uuid,
+    group_id: Uuid,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/access-request/{}/approve",
+      self.base_url, access_request_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(&ApproveAccessRequestParams {
+        is_approved: true,
+        group_id: Some(group_id),
+        reason: None,
+      })
       .send()
       .await?;
     AppResponse::<()>::from_response(resp).await?.into_error()
   }
 
+  /// Rejects the request. The reason is sent to the requester.
   pub async fn reject_access_request(
     &self,
     access_request_id: Uuid,
+    reason: Option<String>,
   ) -> Result<(), AppResponseError> {
     let url = format!(
       "{}/api/access-request/{}/approve",
@@ -68,7 +115,11 @@ impl Client {
     let resp = self
       .http_client_with_auth(Method::POST, &url)
       .await?
-      .json(&ApproveAccessRequestParams { is_approved: false })
+      .json(&ApproveAccessRequestParams {
+        is_approved: false,
+        group_id: None,
+        reason,
+      })
       .send()
       .await?;
     AppResponse::<()>::from_response(resp).await?.into_error()
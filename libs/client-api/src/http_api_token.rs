@@ -0,0 +1,68 @@
+use reqwest::Method;
+use shared_entity::dto::api_token_dto::{CreateApiTokenParams, CreatedApiToken, RepeatedApiToken};
+use shared_entity::response::{AppResponse, AppResponseError};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::http::log_request_id;
+use crate::Client;
+
+impl Client {
+  /// Creates a workspace API token. The token is only returned by this call, the server keeps a
+  /// hash of it.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn create_api_token(
+    &self,
+    workspace_id: &str,
+    params: &CreateApiTokenParams,
+  ) -> Result<CreatedApiToken, AppResponseError> {
+    let url = format!("{}/api/workspace/{}/api-token", self.base_url, workspace_id);
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<CreatedApiToken>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Returns the API tokens the user created in the workspace.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_api_tokens(
+    &self,
+    workspace_id: &str,
+  ) -> Result<RepeatedApiToken, AppResponseError> {
+    let url = format!("{}/api/workspace/{}/api-token", self.base_url, workspace_id);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedApiToken>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn revoke_api_token(
+    &self,
+    workspace_id: &str,
+    token_id: &Uuid,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/api-token/{}",
+      self.base_url, workspace_id, token_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+}
@@ -0,0 +1,64 @@
+use app_error::AppError;
+use reqwest::{header, Method};
+use shared_entity::dto::audit_log_dto::{QueryAuditLogParams, RepeatedAuditLogEntry};
+use shared_entity::response::{AppResponse, AppResponseError};
+use tracing::instrument;
+
+use crate::http::log_request_id;
+use crate::Client;
+
+impl Client {
+  /// Returns the audit log entries of the workspace matching the filters, the most recent first.
+  /// Only the owners of the workspace can read the audit log.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_audit_log(
+    &self,
+    workspace_id: &str,
+    params: &QueryAuditLogParams,
+  ) -> Result<RepeatedAuditLogEntry, AppResponseError> {
+    let url = format!("{}/api/workspace/{}/audit-log", self.base_url, workspace_id);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedAuditLogEntry>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Exports the audit log entries of the workspace matching the filters as CSV.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn export_audit_log(
+    &self,
+    workspace_id: &str,
+    params: &QueryAuditLogParams,
+  ) -> Result<String, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/audit-log/export",
+      self.base_url, workspace_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    let is_json = resp
+      .headers()
+      .get(header::CONTENT_TYPE)
+      .and_then(|value| value.to_str().ok())
+      .map(|value| value.starts_with("application/json"))
+      .unwrap_or(false);
+    if is_json {
+      AppResponse::<()>::from_response(resp).await?.into_error()?;
+      return Err(AppResponseError::from(AppError::Unhandled(
+        "Expected a CSV file".to_string(),
+      )));
+    }
+    Ok(resp.text().await?)
+  }
+}
@@ -1,5 +1,6 @@
 use crate::http::log_request_id;
 use crate::Client;
+use app_error::AppError;
 
 use client_api_entity::chat_dto::{
   ChatMessage, CreateAnswerMessageParams, CreateChatMessageParams, CreateChatParams, MessageCursor,
@@ -13,7 +14,12 @@ use shared_entity::dto::ai_dto::{
   CalculateSimilarityParams, RepeatedRelatedQuestion, SimilarityResponse, STREAM_ANSWER_KEY,
   STREAM_METADATA_KEY,
 };
-use shared_entity::dto::chat_dto::{ChatSettings, UpdateChatParams};
+use shared_entity::dto::chat_dto::{
+  AddChatParticipantsParams, ChatExport, ChatExportFormat, ChatSettings, ExportChatParams,
+  RemoveChatParticipantsParams, RepeatedAnswerVersion, RepeatedChatBranch, RepeatedChatInfo,
+  RepeatedChatMessageSearchResult, RepeatedChatParticipant, SearchChatMessageParams,
+  SelectAnswerVersionParams, UpdateActiveChatBranchParams, UpdateChatParams,
+};
 use shared_entity::response::{AppResponse, AppResponseError};
 use std::pin::Pin;
 use std::task::{Context, Poll};
@@ -77,6 +83,84 @@ impl Client {
       .into_data()
   }
 
+  /// Return the chats of the workspace the user participates in, and the chats that are open to
+  /// every member of the workspace.
+  pub async fn get_chats(&self, workspace_id: &str) -> Result<RepeatedChatInfo, AppResponseError> {
+    let url = format!("{}/api/chat/{workspace_id}", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedChatInfo>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  pub async fn get_chat_participants(
+    &self,
+    workspace_id: &str,
+    chat_id: &str,
+  ) -> Result<RepeatedChatParticipant, AppResponseError> {
+    let url = format!(
+      "{}/api/chat/{workspace_id}/{chat_id}/participant",
+      self.base_url
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedChatParticipant>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Share the chat with members of the workspace. Only the owner of the chat can add participants.
+  pub async fn add_chat_participants(
+    &self,
+    workspace_id: &str,
+    chat_id: &str,
+    emails: Vec<String>,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/chat/{workspace_id}/{chat_id}/participant",
+      self.base_url
+    );
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(&AddChatParticipantsParams { emails })
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Remove participants from the chat. Participants other than the owner can only remove
+  /// themselves.
+  pub async fn remove_chat_participants(
+    &self,
+    workspace_id: &str,
+    chat_id: &str,
+    emails: Vec<String>,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/chat/{workspace_id}/{chat_id}/participant",
+      self.base_url
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .json(&RemoveChatParticipantsParams { emails })
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
   /// Delete a chat for given chat_id
   pub async fn delete_chat(
     &self,
@@ -232,6 +316,21 @@ impl Client {
     chat_id: &str,
     offset: MessageCursor,
     limit: u64,
+  ) -> Result<RepeatedChatMessage, AppResponseError> {
+    self
+      .get_chat_messages_with_branch(workspace_id, chat_id, offset, limit, None)
+      .await
+  }
+
+  /// Return list of chat messages of the given branch. The active branch of the chat is used when
+  /// `branch_id` is `None`.
+  pub async fn get_chat_messages_with_branch(
+    &self,
+    workspace_id: &str,
+    chat_id: &str,
+    offset: MessageCursor,
+    limit: u64,
+    branch_id: Option<i64>,
   ) -> Result<RepeatedChatMessage, AppResponseError> {
     let mut url = format!(
       "{}/api/chat/{workspace_id}/{chat_id}/message",
@@ -250,6 +349,9 @@ impl Client {
       },
       MessageCursor::NextBack => {},
     }
+    if let Some(branch_id) = branch_id {
+      query_params.push(("branch_id", branch_id.to_string()));
+    }
     let query = serde_urlencoded::to_string(&query_params).unwrap();
     url = format!("{}?{}", url, query);
     let resp = self
@@ -262,6 +364,153 @@ impl Client {
       .into_data()
   }
 
+  /// Return the branches created by editing the questions of a chat
+  pub async fn get_chat_branches(
+    &self,
+    workspace_id: &str,
+    chat_id: &str,
+  ) -> Result<RepeatedChatBranch, AppResponseError> {
+    let url = format!("{}/api/chat/{workspace_id}/{chat_id}/branch", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedChatBranch>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  pub async fn update_active_chat_branch(
+    &self,
+    workspace_id: &str,
+    chat_id: &str,
+    branch_id: i64,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/chat/{workspace_id}/{chat_id}/branch/active",
+      self.base_url
+    );
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(&UpdateActiveChatBranchParams { branch_id })
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Return all the generated versions of the answer to a question
+  pub async fn get_answer_versions(
+    &self,
+    workspace_id: &str,
+    chat_id: &str,
+    question_message_id: i64,
+  ) -> Result<RepeatedAnswerVersion, AppResponseError> {
+    let url = format!(
+      "{}/api/chat/{workspace_id}/{chat_id}/{question_message_id}/answer/versions",
+      self.base_url
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedAnswerVersion>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Select the version of the answer displayed in the conversation
+  pub async fn select_answer_version(
+    &self,
+    workspace_id: &str,
+    chat_id: &str,
+    question_message_id: i64,
+    answer_message_id: i64,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/chat/{workspace_id}/{chat_id}/{question_message_id}/answer/versions",
+      self.base_url
+    );
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(&SelectAnswerVersionParams { answer_message_id })
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Search the messages of all the chats in the workspace
+  pub async fn search_chat_messages(
+    &self,
+    workspace_id: &str,
+    params: SearchChatMessageParams,
+  ) -> Result<RepeatedChatMessageSearchResult, AppResponseError> {
+    let url = format!("{}/api/chat/{workspace_id}/search", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(&params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedChatMessageSearchResult>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Export all the messages of a chat
+  pub async fn export_chat(
+    &self,
+    workspace_id: &str,
+    chat_id: &str,
+  ) -> Result<ChatExport, AppResponseError> {
+    let url = format!("{}/api/chat/{workspace_id}/{chat_id}/export", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(&ExportChatParams {
+        format: ChatExportFormat::Json,
+      })
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<ChatExport>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Export all the messages of a chat as a Markdown document
+  pub async fn export_chat_markdown(
+    &self,
+    workspace_id: &str,
+    chat_id: &str,
+  ) -> Result<String, AppResponseError> {
+    let url = format!("{}/api/chat/{workspace_id}/{chat_id}/export", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(&ExportChatParams {
+        format: ChatExportFormat::Markdown,
+      })
+      .send()
+      .await?;
+    log_request_id(&resp);
+    if !resp.status().is_success() {
+      AppResponse::<()>::from_response(resp).await?.into_error()?;
+      return Err(AppResponseError::from(AppError::Unhandled(
+        "failed to export chat".to_string(),
+      )));
+    }
+    Ok(resp.text().await?)
+  }
+
   pub async fn calculate_similarity(
     &self,
     params: CalculateSimilarityParams,
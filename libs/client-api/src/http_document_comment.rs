@@ -0,0 +1,155 @@
+use reqwest::Method;
+use shared_entity::dto::document_comment_dto::{
+  CreateDocumentCommentParams, CreateDocumentCommentThreadParams, DocumentComment,
+  DocumentCommentThread, QueryDocumentCommentThreads, RepeatedDocumentCommentThread,
+  UpdateDocumentCommentParams, UpdateDocumentCommentThreadParams,
+};
+use shared_entity::response::{AppResponse, AppResponseError};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::http::log_request_id;
+use crate::Client;
+
+impl Client {
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_document_comment_threads(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    params: &QueryDocumentCommentThreads,
+  ) -> Result<RepeatedDocumentCommentThread, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/comment-thread",
+      self.base_url, workspace_id, view_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedDocumentCommentThread>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Starts a comment thread on a block of the document.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn create_document_comment_thread(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    params: &CreateDocumentCommentThreadParams,
+  ) -> Result<DocumentCommentThread, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/comment-thread",
+      self.base_url, workspace_id, view_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<DocumentCommentThread>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Resolves or reopens a comment thread.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn update_document_comment_thread(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    thread_id: &Uuid,
+    params: &UpdateDocumentCommentThreadParams,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/comment-thread/{}",
+      self.base_url, workspace_id, view_id, thread_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::PATCH, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Replies to a comment thread.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn create_document_comment(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    thread_id: &Uuid,
+    params: &CreateDocumentCommentParams,
+  ) -> Result<DocumentComment, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/comment-thread/{}/comment",
+      self.base_url, workspace_id, view_id, thread_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<DocumentComment>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn update_document_comment(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    thread_id: &Uuid,
+    comment_id: &Uuid,
+    params: &UpdateDocumentCommentParams,
+  ) -> Result<DocumentComment, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/comment-thread/{}/comment/{}",
+      self.base_url, workspace_id, view_id, thread_id, comment_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::PATCH, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<DocumentComment>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn delete_document_comment(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    thread_id: &Uuid,
+    comment_id: &Uuid,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/comment-thread/{}/comment/{}",
+      self.base_url, workspace_id, view_id, thread_id, comment_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+}
@@ -0,0 +1,219 @@
+use reqwest::Method;
+use shared_entity::dto::group_dto::{
+  CreateWorkspaceGroupParams, RepeatedCollabGroupMember, RepeatedWorkspaceGroup,
+  RepeatedWorkspaceGroupMember, UpdateWorkspaceGroupParams, UpsertCollabGroupMemberParams,
+  WorkspaceGroup, WorkspaceGroupMembersParams,
+};
+use shared_entity::response::{AppResponse, AppResponseError};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::http::log_request_id;
+use crate::Client;
+
+impl Client {
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_workspace_groups(
+    &self,
+    workspace_id: &str,
+  ) -> Result<RepeatedWorkspaceGroup, AppResponseError> {
+    let url = format!("{}/api/workspace/{}/group", self.base_url, workspace_id);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedWorkspaceGroup>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn create_workspace_group(
+    &self,
+    workspace_id: &str,
+    params: &CreateWorkspaceGroupParams,
+  ) -> Result<WorkspaceGroup, AppResponseError> {
+    let url = format!("{}/api/workspace/{}/group", self.base_url, workspace_id);
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<WorkspaceGroup>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn update_workspace_group(
+    &self,
+    workspace_id: &str,
+    group_id: &Uuid,
+    params: &UpdateWorkspaceGroupParams,
+  ) -> Result<WorkspaceGroup, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/group/{}",
+      self.base_url, workspace_id, group_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::PATCH, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<WorkspaceGroup>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// The members of the group lose the access levels that were granted to the group.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn delete_workspace_group(
+    &self,
+    workspace_id: &str,
+    group_id: &Uuid,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/group/{}",
+      self.base_url, workspace_id, group_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_workspace_group_members(
+    &self,
+    workspace_id: &str,
+    group_id: &Uuid,
+  ) -> Result<RepeatedWorkspaceGroupMember, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/group/{}/member",
+      self.base_url, workspace_id, group_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedWorkspaceGroupMember>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn add_workspace_group_members(
+    &self,
+    workspace_id: &str,
+    group_id: &Uuid,
+    params: &WorkspaceGroupMembersParams,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/group/{}/member",
+      self.base_url, workspace_id, group_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn remove_workspace_group_members(
+    &self,
+    workspace_id: &str,
+    group_id: &Uuid,
+    params: &WorkspaceGroupMembersParams,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/group/{}/member",
+      self.base_url, workspace_id, group_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_collab_group_members(
+    &self,
+    workspace_id: &str,
+    object_id: &str,
+  ) -> Result<RepeatedCollabGroupMember, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/collab/{}/group-member",
+      self.base_url, workspace_id, object_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedCollabGroupMember>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Grants an access level on the collab to all the members of a group.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn upsert_collab_group_member(
+    &self,
+    workspace_id: &str,
+    object_id: &str,
+    params: &UpsertCollabGroupMemberParams,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/collab/{}/group-member",
+      self.base_url, workspace_id, object_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn remove_collab_group_member(
+    &self,
+    workspace_id: &str,
+    object_id: &str,
+    group_id: &Uuid,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/collab/{}/group-member/{}",
+      self.base_url, workspace_id, object_id, group_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+}
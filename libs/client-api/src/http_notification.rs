@@ -0,0 +1,171 @@
+use reqwest::Method;
+use shared_entity::dto::notification_dto::{
+  ActivityDigestSetting, ActivityDigestSettings, MarkNotificationsReadParams, NotificationMute,
+  NotificationSettings, QueryNotifications, RepeatedNotification, RepeatedNotificationMute,
+  ResetActivityDigestSettingParams, UnmuteNotificationParams,
+};
+use shared_entity::response::{AppResponse, AppResponseError};
+use tracing::instrument;
+
+use crate::http::log_request_id;
+use crate::Client;
+
+impl Client {
+  /// Returns the notifications of the user, newest first.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_notifications(
+    &self,
+    params: &QueryNotifications,
+  ) -> Result<RepeatedNotification, AppResponseError> {
+    let url = format!("{}/api/notification", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedNotification>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn mark_notifications_read(
+    &self,
+    params: &MarkNotificationsReadParams,
+  ) -> Result<(), AppResponseError> {
+    let url = format!("{}/api/notification/read", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_notification_mutes(&self) -> Result<RepeatedNotificationMute, AppResponseError> {
+    let url = format!("{}/api/notification/mute", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedNotificationMute>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn mute_notifications(&self, mute: &NotificationMute) -> Result<(), AppResponseError> {
+    let url = format!("{}/api/notification/mute", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(mute)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn unmute_notifications(
+    &self,
+    params: &UnmuteNotificationParams,
+  ) -> Result<(), AppResponseError> {
+    let url = format!("{}/api/notification/mute", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_notification_settings(&self) -> Result<NotificationSettings, AppResponseError> {
+    let url = format!("{}/api/notification/settings", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<NotificationSettings>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn update_notification_settings(
+    &self,
+    settings: &NotificationSettings,
+  ) -> Result<(), AppResponseError> {
+    let url = format!("{}/api/notification/settings", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(settings)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_activity_digest_settings(
+    &self,
+  ) -> Result<ActivityDigestSettings, AppResponseError> {
+    let url = format!("{}/api/notification/activity-digest", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<ActivityDigestSettings>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Sets the frequency of the activity digests of a workspace, or the default frequency when the
+  /// workspace is None.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn update_activity_digest_setting(
+    &self,
+    setting: &ActivityDigestSetting,
+  ) -> Result<(), AppResponseError> {
+    let url = format!("{}/api/notification/activity-digest", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(setting)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn reset_activity_digest_setting(
+    &self,
+    params: &ResetActivityDigestSettingParams,
+  ) -> Result<(), AppResponseError> {
+    let url = format!("{}/api/notification/activity-digest", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+}
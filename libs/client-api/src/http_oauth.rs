@@ -0,0 +1,116 @@
+use reqwest::Method;
+use shared_entity::dto::oauth_dto::{
+  CreateOAuthClientParams, CreatedOAuthClient, OAuthAuthorizeParams, OAuthAuthorizeResponse,
+  OAuthClient, OAuthErrorResponse, OAuthTokenParams, OAuthTokenResponse, RepeatedOAuthClient,
+};
+use shared_entity::response::{AppResponse, AppResponseError, ErrorCode};
+use tracing::instrument;
+
+use crate::http::log_request_id;
+use crate::Client;
+
+impl Client {
+  /// Registers a third-party application that can request access to the workspaces of the users.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn create_oauth_client(
+    &self,
+    params: &CreateOAuthClientParams,
+  ) -> Result<CreatedOAuthClient, AppResponseError> {
+    let url = format!("{}/api/oauth/client", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<CreatedOAuthClient>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Returns the applications registered by the user.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_oauth_clients(&self) -> Result<RepeatedOAuthClient, AppResponseError> {
+    let url = format!("{}/api/oauth/client", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedOAuthClient>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_oauth_client(&self, client_id: &str) -> Result<OAuthClient, AppResponseError> {
+    let url = format!("{}/api/oauth/client/{}", self.base_url, client_id);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<OAuthClient>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn delete_oauth_client(&self, client_id: &str) -> Result<(), AppResponseError> {
+    let url = format!("{}/api/oauth/client/{}", self.base_url, client_id);
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Grants the application access to a workspace on behalf of the user. Returns the
+  /// authorization code to send back to the redirect uri of the application.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn authorize_oauth_client(
+    &self,
+    params: &OAuthAuthorizeParams,
+  ) -> Result<OAuthAuthorizeResponse, AppResponseError> {
+    let url = format!("{}/api/oauth/authorize", self.base_url);
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<OAuthAuthorizeResponse>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Exchanges an authorization code, or a refresh token, for an access token, as the application
+  /// would do. The request doesn't need the user to be signed in.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn exchange_oauth_code(
+    &self,
+    params: &OAuthTokenParams,
+  ) -> Result<OAuthTokenResponse, AppResponseError> {
+    let url = format!("{}/api/oauth/token", self.base_url);
+    let resp = self.cloud_client.post(&url).form(params).send().await?;
+    log_request_id(&resp);
+    if resp.status().is_success() {
+      return Ok(resp.json::<OAuthTokenResponse>().await?);
+    }
+    let err = resp.json::<OAuthErrorResponse>().await?;
+    Err(AppResponseError::new(
+      ErrorCode::InvalidRequest,
+      format!(
+        "{}: {}",
+        err.error,
+        err.error_description.unwrap_or_default()
+      ),
+    ))
+  }
+}
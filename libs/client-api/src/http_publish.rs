@@ -6,8 +6,14 @@ use client_api_entity::{
   GetReactionQueryParams, GlobalComments, PatchPublishedCollab, PublishInfoMeta, Reactions,
   UpdateDefaultPublishView,
 };
-use reqwest::Method;
-use shared_entity::response::{AppResponse, AppResponseError};
+use reqwest::{Method, RequestBuilder};
+use shared_entity::dto::comment_moderation_dto::{
+  BanCommenterParams, BannedCommenter, CommentModeration, PublishedViewCommentSettings,
+};
+use shared_entity::dto::publish_dto::{
+  PublishedViewAnalytics, QueryPublishedViewAnalytics, PUBLISHED_VIEW_PASSWORD_HEADER,
+};
+use shared_entity::response::{AppResponse, AppResponseError, ErrorCode};
 use tracing::instrument;
 
 use crate::{log_request_id, Client};
@@ -35,6 +41,31 @@ impl Client {
       .into_data()
   }
 
+  /// Reads and visitors of the published views of the workspace. Only the owners of the workspace
+  /// can read the analytics.
+  #[instrument(level = "debug", skip_all)]
+  pub async fn get_published_view_analytics(
+    &self,
+    workspace_id: &str,
+    query: &QueryPublishedViewAnalytics,
+  ) -> Result<Vec<PublishedViewAnalytics>, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/published-info/analytics",
+      self.base_url, workspace_id,
+    );
+
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(query)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<Vec<PublishedViewAnalytics>>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
   /// Changes the namespace for the first non-original publish namespace
   /// or the original publish namespace if not exists.
   pub async fn set_workspace_publish_namespace(
@@ -97,6 +128,15 @@ impl Client {
     AppResponse::<()>::from_response(resp).await?.into_error()
   }
 
+  /// Websocket address of a view published in live mode. The server sends a
+  /// `PublishedViewUpdate` text message each time the published blob of the view changes.
+  pub fn published_view_ws_url(&self, publish_namespace: &str, publish_name: &str) -> String {
+    format!(
+      "{}/published/{}/{}",
+      self.ws_addr, publish_namespace, publish_name
+    )
+  }
+
   pub async fn unpublish_collabs(
     &self,
     workspace_id: &str,
@@ -136,6 +176,140 @@ impl Client {
     AppResponse::<()>::from_response(resp).await?.into_error()
   }
 
+  pub async fn approve_comment_on_published_view(
+    &self,
+    view_id: &uuid::Uuid,
+    comment_id: &uuid::Uuid,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/published-info/{}/comment/{}/approve",
+      self.base_url, view_id, comment_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  pub async fn update_published_view_comment_settings(
+    &self,
+    view_id: &uuid::Uuid,
+    settings: &PublishedViewCommentSettings,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/published-info/{}/comment-settings",
+      self.base_url, view_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(settings)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  pub async fn get_workspace_comment_moderation(
+    &self,
+    workspace_id: &str,
+  ) -> Result<CommentModeration, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/published-comment-moderation",
+      self.base_url, workspace_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<CommentModeration>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  pub async fn update_workspace_comment_moderation(
+    &self,
+    workspace_id: &str,
+    moderation: &CommentModeration,
+  ) -> Result<CommentModeration, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/published-comment-moderation",
+      self.base_url, workspace_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(moderation)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<CommentModeration>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  pub async fn list_banned_commenters(
+    &self,
+    workspace_id: &str,
+  ) -> Result<Vec<BannedCommenter>, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/published-comment-ban",
+      self.base_url, workspace_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<Vec<BannedCommenter>>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  pub async fn ban_commenter(
+    &self,
+    workspace_id: &str,
+    user_uuid: &uuid::Uuid,
+    params: &BanCommenterParams,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/published-comment-ban/{}",
+      self.base_url, workspace_id, user_uuid
+    );
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  pub async fn unban_commenter(
+    &self,
+    workspace_id: &str,
+    user_uuid: &uuid::Uuid,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/published-comment-ban/{}",
+      self.base_url, workspace_id, user_uuid
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
   pub async fn delete_comment_on_published_view(
     &self,
     view_id: &uuid::Uuid,
@@ -285,6 +459,21 @@ impl Client {
 
 // Guest API (no login required)
 impl Client {
+  pub async fn get_published_view_comment_settings(
+    &self,
+    view_id: &uuid::Uuid,
+  ) -> Result<PublishedViewCommentSettings, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/published-info/{}/comment-settings",
+      self.base_url, view_id
+    );
+    let resp = self.cloud_client.get(&url).send().await?;
+    log_request_id(&resp);
+    AppResponse::<PublishedViewCommentSettings>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
   #[instrument(level = "debug", skip_all)]
   pub async fn get_published_collab_info(
     &self,
@@ -333,6 +522,21 @@ impl Client {
     publish_namespace: &str,
     publish_name: &str,
   ) -> Result<T, AppResponseError>
+  where
+    T: serde::de::DeserializeOwned + 'static,
+  {
+    self
+      .get_published_collab_with_password(publish_namespace, publish_name, None)
+      .await
+  }
+
+  /// Same as [Client::get_published_collab] for a view protected by a password.
+  pub async fn get_published_collab_with_password<T>(
+    &self,
+    publish_namespace: &str,
+    publish_name: &str,
+    password: Option<&str>,
+  ) -> Result<T, AppResponseError>
   where
     T: serde::de::DeserializeOwned + 'static,
   {
@@ -347,8 +551,8 @@ impl Client {
     );
 
     let resp = self
-      .cloud_client
-      .get(&url)
+      .published_view_request(&url, password)
+      .await?
       .send()
       .await?
       .error_for_status()?;
@@ -362,6 +566,19 @@ impl Client {
     &self,
     publish_namespace: &str,
     publish_name: &str,
+  ) -> Result<Bytes, AppResponseError> {
+    self
+      .get_published_collab_blob_with_password(publish_namespace, publish_name, None)
+      .await
+  }
+
+  /// Same as [Client::get_published_collab_blob] for a view protected by a password.
+  #[instrument(level = "debug", skip_all)]
+  pub async fn get_published_collab_blob_with_password(
+    &self,
+    publish_namespace: &str,
+    publish_name: &str,
+    password: Option<&str>,
   ) -> Result<Bytes, AppResponseError> {
     tracing::debug!(
       "get_published_collab_blob: {} {}",
@@ -372,7 +589,11 @@ impl Client {
       "{}/api/workspace/published/{}/{}/blob",
       self.base_url, publish_namespace, publish_name
     );
-    let resp = self.cloud_client.get(&url).send().await?;
+    let resp = self
+      .published_view_request(&url, password)
+      .await?
+      .send()
+      .await?;
     log_request_id(&resp);
     let bytes = resp.error_for_status()?.bytes().await?;
 
@@ -383,6 +604,71 @@ impl Client {
     Ok(bytes)
   }
 
+  /// Returns the published view rendered to a static HTML page.
+  #[instrument(level = "debug", skip_all)]
+  pub async fn get_published_view_html(
+    &self,
+    publish_namespace: &str,
+    publish_name: &str,
+  ) -> Result<String, AppResponseError> {
+    self
+      .get_published_view_html_with_password(publish_namespace, publish_name, None)
+      .await
+  }
+
+  /// Same as [Client::get_published_view_html] for a view protected by a password.
+  #[instrument(level = "debug", skip_all)]
+  pub async fn get_published_view_html_with_password(
+    &self,
+    publish_namespace: &str,
+    publish_name: &str,
+    password: Option<&str>,
+  ) -> Result<String, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/v1/published/{}/{}/html",
+      self.base_url, publish_namespace, publish_name
+    );
+    let resp = self
+      .published_view_request(&url, password)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    // Errors are returned as a JSON body, the page itself is HTML.
+    let is_json = resp
+      .headers()
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|value| value.to_str().ok())
+      .map(|value| value.starts_with("application/json"))
+      .unwrap_or(false);
+    if !resp.status().is_success() || is_json {
+      AppResponse::<()>::from_response(resp).await?.into_error()?;
+      return Err(AppResponseError::new(
+        ErrorCode::Internal,
+        "failed to render published view",
+      ));
+    }
+    Ok(resp.text().await?)
+  }
+
+  /// Guest requests are authenticated when the user is signed in, so that views restricted to some
+  /// email domains can be read.
+  async fn published_view_request(
+    &self,
+    url: &str,
+    password: Option<&str>,
+  ) -> Result<RequestBuilder, AppResponseError> {
+    let mut builder = if let Ok(builder) = self.http_client_with_auth(Method::GET, url).await {
+      builder
+    } else {
+      self.http_client_without_auth(Method::GET, url).await?
+    };
+    if let Some(password) = password {
+      builder = builder.header(PUBLISHED_VIEW_PASSWORD_HEADER, password);
+    }
+    Ok(builder)
+  }
+
   pub async fn duplicate_published_to_workspace(
     &self,
     workspace_id: &str,
@@ -0,0 +1,134 @@
+use reqwest::Method;
+use shared_entity::dto::role_dto::{
+  AssignCustomRoleParams, CreateCustomRoleParams, CustomRole, RepeatedCustomRole,
+  UpdateCustomRoleParams, WorkspacePermissions,
+};
+use shared_entity::response::{AppResponse, AppResponseError};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::http::log_request_id;
+use crate::Client;
+
+impl Client {
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn create_custom_role(
+    &self,
+    workspace_id: &str,
+    params: &CreateCustomRoleParams,
+  ) -> Result<CustomRole, AppResponseError> {
+    let url = format!("{}/api/workspace/{}/role", self.base_url, workspace_id);
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<CustomRole>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_custom_roles(
+    &self,
+    workspace_id: &str,
+  ) -> Result<RepeatedCustomRole, AppResponseError> {
+    let url = format!("{}/api/workspace/{}/role", self.base_url, workspace_id);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedCustomRole>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// The members that have the role get the new permissions.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn update_custom_role(
+    &self,
+    workspace_id: &str,
+    role_id: &Uuid,
+    params: &UpdateCustomRoleParams,
+  ) -> Result<CustomRole, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/role/{}",
+      self.base_url, workspace_id, role_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::PATCH, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<CustomRole>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn delete_custom_role(
+    &self,
+    workspace_id: &str,
+    role_id: &Uuid,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/role/{}",
+      self.base_url, workspace_id, role_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Assigns a custom role to a member of the workspace, or removes it if `role_id` is `None`.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn assign_custom_role(
+    &self,
+    workspace_id: &str,
+    params: &AssignCustomRoleParams,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/member/custom-role",
+      self.base_url, workspace_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Returns what the current user is allowed to do in the workspace.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_workspace_permissions(
+    &self,
+    workspace_id: &str,
+  ) -> Result<WorkspacePermissions, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/permission",
+      self.base_url, workspace_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<WorkspacePermissions>::from_response(resp)
+      .await?
+      .into_data()
+  }
+}
@@ -0,0 +1,119 @@
+use reqwest::Method;
+use shared_entity::dto::share_link_dto::{
+  CreateShareLinkParams, CreatedShareLink, JoinShareLinkParams, RepeatedShareLink, ShareLinkAccess,
+  SharedPage, SHARE_LINK_PASSWORD_HEADER,
+};
+use shared_entity::response::{AppResponse, AppResponseError};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::http::log_request_id;
+use crate::Client;
+
+impl Client {
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn create_share_link(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    params: &CreateShareLinkParams,
+  ) -> Result<CreatedShareLink, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/share-link",
+      self.base_url, workspace_id, view_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<CreatedShareLink>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_share_links(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+  ) -> Result<RepeatedShareLink, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/share-link",
+      self.base_url, workspace_id, view_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedShareLink>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn revoke_share_link(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    link_id: &Uuid,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/share-link/{}",
+      self.base_url, workspace_id, view_id, link_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Gives the user access to the page of the link, so it can be opened like the pages of the
+  /// workspaces of the user.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn join_share_link(
+    &self,
+    token: &str,
+    params: &JoinShareLinkParams,
+  ) -> Result<ShareLinkAccess, AppResponseError> {
+    let url = format!("{}/api/share-link/{}/join", self.base_url, token);
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<ShareLinkAccess>::from_response(resp)
+      .await?
+      .into_data()
+  }
+}
+
+// Guest API (no login required)
+impl Client {
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_shared_page(
+    &self,
+    token: &str,
+    password: Option<&str>,
+  ) -> Result<SharedPage, AppResponseError> {
+    let url = format!("{}/api/share-link/{}", self.base_url, token);
+    let mut builder = self.cloud_client.get(&url);
+    if let Some(password) = password {
+      builder = builder.header(SHARE_LINK_PASSWORD_HEADER, password);
+    }
+    let resp = builder.send().await?;
+    log_request_id(&resp);
+    AppResponse::<SharedPage>::from_response(resp)
+      .await?
+      .into_data()
+  }
+}
@@ -3,6 +3,10 @@ use client_api_entity::workspace_dto::{
 };
 use reqwest::Method;
 use serde_json::json;
+use shared_entity::dto::view_permission_dto::{
+  EffectiveViewPermission, GrantViewGroupPermissionParams, GrantViewPermissionParams,
+  QueryEffectiveViewPermission, RepeatedViewPermission,
+};
 use shared_entity::response::{AppResponse, AppResponseError};
 use uuid::Uuid;
 
@@ -148,4 +152,123 @@ impl Client {
       .await?;
     AppResponse::<()>::from_response(resp).await?.into_error()
   }
+
+  /// Returns the permissions granted on the space or page itself, not the inherited ones.
+  pub async fn get_view_permissions(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+  ) -> Result<RepeatedViewPermission, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/permission",
+      self.base_url, workspace_id, view_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    AppResponse::<RepeatedViewPermission>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  /// Grants an access level on the space or page to a member of the workspace. The pages below
+  /// it inherit the access level, unless they have their own.
+  pub async fn grant_view_permission(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    params: &GrantViewPermissionParams,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/permission",
+      self.base_url, workspace_id, view_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  pub async fn revoke_view_permission(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    uid: i64,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/permission/{}",
+      self.base_url, workspace_id, view_id, uid
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .send()
+      .await?;
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Grants an access level on the space or page to all the members of a group.
+  pub async fn grant_view_group_permission(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    params: &GrantViewGroupPermissionParams,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/group-permission",
+      self.base_url, workspace_id, view_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::PUT, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  pub async fn revoke_view_group_permission(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    group_id: &Uuid,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/group-permission/{}",
+      self.base_url, workspace_id, view_id, group_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .send()
+      .await?;
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Returns what the user, or the current user if `uid` is `None`, can do on the page.
+  pub async fn get_effective_view_permission(
+    &self,
+    workspace_id: Uuid,
+    view_id: &str,
+    uid: Option<i64>,
+  ) -> Result<EffectiveViewPermission, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/page-view/{}/permission/effective",
+      self.base_url, workspace_id, view_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(&QueryEffectiveViewPermission { uid })
+      .send()
+      .await?;
+    AppResponse::<EffectiveViewPermission>::from_response(resp)
+      .await?
+      .into_data()
+  }
 }
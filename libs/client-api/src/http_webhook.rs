@@ -0,0 +1,117 @@
+use reqwest::Method;
+use shared_entity::dto::webhook_dto::{
+  CreateWebhookParams, CreatedWebhook, RepeatedWebhook, RepeatedWebhookDelivery,
+  UpdateWebhookParams, Webhook,
+};
+use shared_entity::response::{AppResponse, AppResponseError};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::http::log_request_id;
+use crate::Client;
+
+impl Client {
+  /// Subscribes a url to events of the workspace. The secret used to sign the deliveries is only
+  /// returned by this call.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn create_webhook(
+    &self,
+    workspace_id: &str,
+    params: &CreateWebhookParams,
+  ) -> Result<CreatedWebhook, AppResponseError> {
+    let url = format!("{}/api/workspace/{}/webhook", self.base_url, workspace_id);
+    let resp = self
+      .http_client_with_auth(Method::POST, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<CreatedWebhook>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_webhooks(
+    &self,
+    workspace_id: &str,
+  ) -> Result<RepeatedWebhook, AppResponseError> {
+    let url = format!("{}/api/workspace/{}/webhook", self.base_url, workspace_id);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedWebhook>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn update_webhook(
+    &self,
+    workspace_id: &str,
+    webhook_id: &Uuid,
+    params: &UpdateWebhookParams,
+  ) -> Result<Webhook, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/webhook/{}",
+      self.base_url, workspace_id, webhook_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::PATCH, &url)
+      .await?
+      .json(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<Webhook>::from_response(resp)
+      .await?
+      .into_data()
+  }
+
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn delete_webhook(
+    &self,
+    workspace_id: &str,
+    webhook_id: &Uuid,
+  ) -> Result<(), AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/webhook/{}",
+      self.base_url, workspace_id, webhook_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::DELETE, &url)
+      .await?
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<()>::from_response(resp).await?.into_error()
+  }
+
+  /// Returns the most recent deliveries of the webhook first.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_webhook_deliveries(
+    &self,
+    workspace_id: &str,
+    webhook_id: &Uuid,
+    limit: i64,
+  ) -> Result<RepeatedWebhookDelivery, AppResponseError> {
+    let url = format!(
+      "{}/api/workspace/{}/webhook/{}/delivery",
+      self.base_url, workspace_id, webhook_id
+    );
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(&[("limit", limit)])
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedWebhookDelivery>::from_response(resp)
+      .await?
+      .into_data()
+  }
+}
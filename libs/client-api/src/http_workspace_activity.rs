@@ -0,0 +1,33 @@
+use reqwest::Method;
+use shared_entity::dto::workspace_activity_dto::{
+  QueryWorkspaceActivityParams, RepeatedWorkspaceActivity,
+};
+use shared_entity::response::{AppResponse, AppResponseError};
+use tracing::instrument;
+
+use crate::http::log_request_id;
+use crate::Client;
+
+impl Client {
+  /// Returns the activity feed of the workspace, the most recent activity first: the pages created,
+  /// renamed, moved, trashed, restored and published, the database rows added and the members
+  /// that joined.
+  #[instrument(level = "info", skip_all, err)]
+  pub async fn get_workspace_activities(
+    &self,
+    workspace_id: &str,
+    params: &QueryWorkspaceActivityParams,
+  ) -> Result<RepeatedWorkspaceActivity, AppResponseError> {
+    let url = format!("{}/api/workspace/{}/activity", self.base_url, workspace_id);
+    let resp = self
+      .http_client_with_auth(Method::GET, &url)
+      .await?
+      .query(params)
+      .send()
+      .await?;
+    log_request_id(&resp);
+    AppResponse::<RepeatedWorkspaceActivity>::from_response(resp)
+      .await?
+      .into_data()
+  }
+}
@@ -3,14 +3,24 @@ mod http_ai;
 mod http_billing;
 
 mod http_access_request;
+mod http_api_token;
+mod http_audit_log;
 mod http_blob;
 mod http_collab;
+mod http_document_comment;
+mod http_group;
 mod http_history;
 mod http_member;
+mod http_notification;
+mod http_oauth;
 mod http_publish;
+mod http_role;
 mod http_search;
+mod http_share_link;
 mod http_template;
 mod http_view;
+mod http_webhook;
+mod http_workspace_activity;
 pub use http::*;
 
 #[cfg(feature = "collab-sync")]
@@ -7,6 +7,10 @@ use std::hash::Hash;
 pub enum UserMessage {
   ProfileChange(AFUserChange),
   WorkspaceMemberChange(AFWorkspaceMemberChange),
+  ChatMessageChange(AFChatMessageChange),
+  AccessRequestChange(AFAccessRequestChange),
+  DocumentCommentChange(AFDocumentCommentChange),
+  Notification(AFNotificationChange),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
@@ -24,6 +28,80 @@ pub struct AFWorkspaceMemberChange {
   removed: Vec<AFWorkspaceMember>,
 }
 
+/// Sent to all the participants of a chat when a message is added to the chat or when the content
+/// of a message is updated.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct AFChatMessageChange {
+  pub workspace_id: String,
+  pub chat_id: String,
+  pub message_id: i64,
+  pub author_id: i64,
+  /// Same values as the `ChatAuthorType` of the chat api: 1 for human, 2 for system, 3 for AI.
+  pub author_type: u8,
+  pub content: String,
+  /// Timestamp in milliseconds.
+  pub created_at: i64,
+  pub reply_message_id: Option<i64>,
+  /// The metadata of the message serialized into a JSON string, bincode doesn't support the Serde
+  /// `deserialize_any` method.
+  pub metadata: String,
+  /// `true` when the content of an existing message was updated.
+  pub is_update: bool,
+}
+
+/// Sent to the owners of the workspace when an access request is created, and to the requester
+/// when the request is approved, rejected or expires.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct AFAccessRequestChange {
+  pub request_id: String,
+  pub workspace_id: String,
+  pub view_id: String,
+  pub requester_uid: i64,
+  /// Same values as the `AccessRequestStatus` of the access request api: 0 for pending, 1 for
+  /// approved, 2 for rejected, 3 for expired.
+  pub status: u8,
+  pub reason: Option<String>,
+  pub rejection_reason: Option<String>,
+}
+
+/// Sent to the members of the workspace when a comment is added to a document, edited or deleted,
+/// and when a comment thread is resolved or reopened.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct AFDocumentCommentChange {
+  pub workspace_id: String,
+  pub view_id: String,
+  pub thread_id: String,
+  /// The block of the document the thread is anchored to.
+  pub block_id: String,
+  /// None when the thread is resolved or reopened.
+  pub comment_id: Option<String>,
+  /// 0 for a new comment, 1 for an edited comment, 2 for a deleted comment, 3 for a resolved
+  /// thread, 4 for a reopened thread.
+  pub action: u8,
+  pub author_uid: Option<i64>,
+  pub content: Option<String>,
+  /// uids of the members mentioned in the comment.
+  pub mentions: Vec<i64>,
+  pub is_resolved: bool,
+}
+
+/// Sent to a user when a notification is added to its notification center.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct AFNotificationChange {
+  pub notification_id: String,
+  pub workspace_id: String,
+  /// Same values as the `NotificationKind` of the notification api.
+  pub kind: i16,
+  pub view_id: Option<String>,
+  pub object_id: Option<String>,
+  pub actor_uid: Option<i64>,
+  /// The payload of the notification serialized into a JSON string, bincode doesn't support the
+  /// Serde `deserialize_any` method.
+  pub payload: String,
+  /// Timestamp in milliseconds.
+  pub created_at: i64,
+}
+
 #[derive(Clone, Hash, PartialEq, Eq, Debug)]
 pub struct UserDevice {
   device_id: String,
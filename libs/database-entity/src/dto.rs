@@ -459,6 +459,20 @@ impl AFRole {
   pub fn can_create_collab(&self) -> bool {
     matches!(self, AFRole::Owner | AFRole::Member)
   }
+
+  /// The permissions granted to the members that have this role and no custom role.
+  pub fn default_permissions(&self) -> Vec<AFWorkspacePermission> {
+    match self {
+      AFRole::Owner => AFWorkspacePermission::ALL.to_vec(),
+      AFRole::Member => vec![
+        AFWorkspacePermission::Publish,
+        AFWorkspacePermission::DeletePages,
+        AFWorkspacePermission::UseAI,
+        AFWorkspacePermission::Export,
+      ],
+      AFRole::Guest => vec![AFWorkspacePermission::UseAI, AFWorkspacePermission::Export],
+    }
+  }
 }
 
 impl From<i32> for AFRole {
@@ -512,6 +526,49 @@ impl Ord for AFRole {
   }
 }
 
+/// Granular permissions in a workspace, on top of the read, write and delete actions of the
+/// [AFRole]. A custom role grants a chosen set of permissions instead of the defaults of its base
+/// role, see [AFRole::default_permissions].
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AFWorkspacePermission {
+  InviteMembers,
+  Publish,
+  ManageBilling,
+  DeletePages,
+  #[serde(rename = "use_ai")]
+  UseAI,
+  Export,
+}
+
+impl AFWorkspacePermission {
+  pub const ALL: [AFWorkspacePermission; 6] = [
+    AFWorkspacePermission::InviteMembers,
+    AFWorkspacePermission::Publish,
+    AFWorkspacePermission::ManageBilling,
+    AFWorkspacePermission::DeletePages,
+    AFWorkspacePermission::UseAI,
+    AFWorkspacePermission::Export,
+  ];
+
+  pub fn from_name(name: &str) -> Option<Self> {
+    Self::ALL
+      .into_iter()
+      .find(|permission| permission.as_str() == name)
+  }
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      AFWorkspacePermission::InviteMembers => "invite_members",
+      AFWorkspacePermission::Publish => "publish",
+      AFWorkspacePermission::ManageBilling => "manage_billing",
+      AFWorkspacePermission::DeletePages => "delete_pages",
+      AFWorkspacePermission::UseAI => "use_ai",
+      AFWorkspacePermission::Export => "export",
+    }
+  }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AFPermission {
   /// The permission id
@@ -532,6 +589,15 @@ pub enum AFAccessLevel {
 }
 
 impl AFAccessLevel {
+  pub fn can_comment(&self) -> bool {
+    match self {
+      AFAccessLevel::ReadOnly => false,
+      AFAccessLevel::ReadAndComment | AFAccessLevel::ReadAndWrite | AFAccessLevel::FullAccess => {
+        true
+      },
+    }
+  }
+
   pub fn can_write(&self) -> bool {
     match self {
       AFAccessLevel::ReadOnly | AFAccessLevel::ReadAndComment => false,
@@ -816,13 +882,14 @@ impl AFCollabEmbeddings {
 }
 
 /// Type of content stored by the embedding.
-/// Currently only plain text of the document is supported.
-/// In the future, we might support other kinds like i.e. PDF, images or image-extracted text.
+/// In the future, we might support other kinds like i.e. images or image-extracted text.
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, Serialize_repr, Deserialize_repr, Eq, PartialEq)]
 pub enum EmbeddingContentType {
   /// The plain text representation of the document.
   PlainText = 0,
+  /// Text extracted from a file attached to the document, ie. PDF or DOCX.
+  Attachment = 1,
 }
 
 impl EmbeddingContentType {
@@ -839,6 +906,8 @@ impl EmbeddingContentType {
   pub fn to_proto(&self) -> proto::collab::EmbeddingContentType {
     match self {
       EmbeddingContentType::PlainText => proto::collab::EmbeddingContentType::PlainText,
+      // Attachment fragments are never sent over the wire, they're written by the worker directly.
+      EmbeddingContentType::Attachment => proto::collab::EmbeddingContentType::Unknown,
     }
   }
 }
@@ -862,10 +931,42 @@ pub struct PublishCollabItem<Meta, Data> {
   pub data: Data,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct PatchPublishedCollab {
   pub view_id: Uuid,
   pub publish_name: Option<String>,
+  /// When true, the published view tracks its source document instead of being a copy taken at
+  /// publish time. Only documents can be published live.
+  #[serde(default)]
+  pub live: Option<bool>,
+  /// Password the readers must provide. `Some(None)` removes the password.
+  #[serde(
+    default,
+    deserialize_with = "deserialize_patch_value",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub password: Option<Option<String>>,
+  /// Date after which the view can no longer be read. `Some(None)` removes the expiry date.
+  #[serde(
+    default,
+    deserialize_with = "deserialize_patch_value",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub expires_at: Option<Option<DateTime<Utc>>>,
+  /// Only the signed-in users whose email belongs to one of the domains can read the view. An
+  /// empty list removes the restriction.
+  #[serde(default)]
+  pub allowed_email_domains: Option<Vec<String>>,
+}
+
+/// Distinguishes a field set to null, deserialized as `Some(None)`, from a missing field, which
+/// is `None` thanks to `#[serde(default)]`.
+fn deserialize_patch_value<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+  T: Deserialize<'de>,
+  D: serde::Deserializer<'de>,
+{
+  Option::<T>::deserialize(deserializer).map(Some)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -890,6 +991,10 @@ pub struct GlobalComment {
   pub comment_id: Uuid,
   pub is_deleted: bool,
   pub can_be_deleted: bool,
+  /// The comment waits for the approval of a moderator. Only the moderators and the author of the
+  /// comment can see it.
+  #[serde(default)]
+  pub is_pending_approval: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1165,6 +1270,8 @@ pub enum AccessRequestStatus {
   Pending = 0,
   Approved = 1,
   Rejected = 2,
+  /// The request was still pending when it expired.
+  Expired = 3,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1198,11 +1305,20 @@ pub struct AccessRequestMinimal {
 pub struct CreateAccessRequestParams {
   pub workspace_id: Uuid,
   pub view_id: Uuid,
+  /// Message to the owners of the workspace, sent with the request.
+  #[serde(default)]
+  pub reason: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ApproveAccessRequestParams {
   pub is_approved: bool,
+  /// If set, an approved requester is also added to this group of the workspace.
+  #[serde(default)]
+  pub group_id: Option<Uuid>,
+  /// Message to the requester when the request is rejected.
+  #[serde(default)]
+  pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Validate, Serialize, Deserialize)]
@@ -1,49 +1,71 @@
 use crate::pg_row::{
-  AFAccessRequestStatusColumn, AFAccessRequestWithViewIdColumn, AFAccessRequesterColumn,
-  AFWorkspaceWithMemberCountRow,
+  AFAccessRequestRow, AFAccessRequestStatusColumn, AFAccessRequestWithViewIdColumn,
+  AFAccessRequesterColumn, AFWorkspaceWithMemberCountRow,
 };
 use app_error::AppError;
-use database_entity::dto::AccessRequestWithViewId;
+use chrono::Duration;
+use database_entity::dto::{AFRole, AccessRequestWithViewId};
 use sqlx::{Executor, Postgres};
 use uuid::Uuid;
 
-pub async fn insert_new_access_request<'a, E: Executor<'a, Database = Postgres>>(
+/// Creates a pending request. An expired request of the user for the same view is reopened
+/// instead, so the user can ask again, and so is a rejected request once the cooldown has passed.
+pub async fn upsert_access_request<'a, E: Executor<'a, Database = Postgres>>(
   executor: E,
   workspace_id: Uuid,
   view_id: Uuid,
   uid: i64,
+  reason: Option<&str>,
+  rejection_cooldown: Duration,
 ) -> Result<Uuid, AppError> {
-  let request_id_result = sqlx::query_scalar!(
+  // The status is read from the snapshot taken before the upsert, so it's the status of the
+  // request that was not reopened
+  let row = sqlx::query!(
     r#"
-      INSERT INTO af_access_request (
-        workspace_id,
-        view_id,
-        uid,
-        status
+      WITH upserted AS (
+        INSERT INTO af_access_request (workspace_id, view_id, uid, status, reason)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (uid, workspace_id, view_id) DO UPDATE
+        SET status = EXCLUDED.status,
+            reason = EXCLUDED.reason,
+            rejection_reason = NULL,
+            created_at = CURRENT_TIMESTAMP,
+            expires_at = DEFAULT,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE af_access_request.status = $7
+          OR (
+            af_access_request.status = $6
+            AND af_access_request.updated_at < NOW() - $8::BIGINT * INTERVAL '1 second'
+          )
+        RETURNING request_id
       )
-      VALUES ($1, $2, $3, $4)
-      RETURNING request_id
+      SELECT
+        (SELECT request_id FROM upserted) AS "request_id?",
+        (
+          SELECT status FROM af_access_request
+          WHERE uid = $3 AND workspace_id = $1 AND view_id = $2
+        ) AS "previous_status?: AFAccessRequestStatusColumn"
     "#,
     workspace_id,
     view_id,
     uid,
     AFAccessRequestStatusColumn::Pending as _,
+    reason,
+    AFAccessRequestStatusColumn::Rejected as _,
+    AFAccessRequestStatusColumn::Expired as _,
+    rejection_cooldown.num_seconds(),
   )
   .fetch_one(executor)
-  .await;
-  match request_id_result {
-    Err(e)
-      if e
-        .as_database_error()
-        .map_or(false, |e| e.constraint().is_some()) =>
-    {
-      Err(AppError::AccessRequestAlreadyExists {
-        workspace_id,
-        view_id,
-      })
-    },
-    Err(e) => Err(e.into()),
-    Ok(request_id) => Ok(request_id),
+  .await?;
+  match (row.request_id, row.previous_status) {
+    (Some(request_id), _) => Ok(request_id),
+    (None, Some(AFAccessRequestStatusColumn::Rejected)) => Err(AppError::TooManyRequests(
+      "The access request was rejected recently, try again later".to_string(),
+    )),
+    (None, _) => Err(AppError::AccessRequestAlreadyExists {
+      workspace_id,
+      view_id,
+    }),
   }
 }
 
@@ -104,23 +126,144 @@ pub async fn select_access_request_by_request_id<'a, E: Executor<'a, Database =
   Ok(access_request)
 }
 
-pub async fn update_access_request_status<'a, E: Executor<'a, Database = Postgres>>(
+/// Sets the status of a request that is still pending and not expired. Returns `false` otherwise.
+pub async fn update_pending_access_request_status<'a, E: Executor<'a, Database = Postgres>>(
   executor: E,
   request_id: Uuid,
   status: AFAccessRequestStatusColumn,
-) -> Result<(), AppError> {
-  sqlx::query!(
+  rejection_reason: Option<&str>,
+) -> Result<bool, AppError> {
+  let result = sqlx::query!(
     r#"
       UPDATE af_access_request
-      SET status = $2
-      WHERE request_id = $1
+      SET status = $2, rejection_reason = $3, updated_at = CURRENT_TIMESTAMP
+      WHERE request_id = $1 AND status = $4 AND expires_at > CURRENT_TIMESTAMP
     "#,
     request_id,
     status as _,
+    rejection_reason,
+    AFAccessRequestStatusColumn::Pending as _,
   )
   .execute(executor)
   .await?;
-  Ok(())
+  Ok(result.rows_affected() > 0)
+}
+
+/// Marks the pending requests past their expiration time as expired. Returns the number of
+/// expired requests.
+pub async fn expire_access_requests<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+) -> Result<u64, AppError> {
+  let result = sqlx::query!(
+    r#"
+      UPDATE af_access_request
+      SET status = $2, updated_at = CURRENT_TIMESTAMP
+      WHERE status = $1 AND expires_at <= CURRENT_TIMESTAMP
+    "#,
+    AFAccessRequestStatusColumn::Pending as _,
+    AFAccessRequestStatusColumn::Expired as _,
+  )
+  .execute(executor)
+  .await?;
+  Ok(result.rows_affected())
+}
+
+/// Returns the request with its requester. A pending request past its expiration time is
+/// returned as expired, even before it is swept.
+pub async fn select_access_request_row<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  request_id: Uuid,
+) -> Result<AFAccessRequestRow, AppError> {
+  sqlx::query_as!(
+    AFAccessRequestRow,
+    r#"
+      SELECT
+        r.request_id,
+        r.workspace_id,
+        r.view_id,
+        r.uid,
+        u.uuid,
+        u.name,
+        u.email,
+        u.metadata ->> 'icon_url' AS avatar_url,
+        CASE WHEN r.status = 0 AND r.expires_at <= CURRENT_TIMESTAMP THEN 3 ELSE r.status END
+          AS "status!: AFAccessRequestStatusColumn",
+        r.reason,
+        r.rejection_reason,
+        r.created_at,
+        r.expires_at
+      FROM af_access_request r
+      JOIN af_user u USING (uid)
+      WHERE r.request_id = $1
+    "#,
+    request_id,
+  )
+  .fetch_optional(executor)
+  .await?
+  .ok_or_else(|| AppError::RecordNotFound(format!("Access request {} not found", request_id)))
+}
+
+/// Returns the requests of the workspace, the most recent first. Like in
+/// [select_access_request_row], the pending requests past their expiration time are expired.
+pub async fn select_access_requests<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: Option<&Uuid>,
+  status: Option<AFAccessRequestStatusColumn>,
+) -> Result<Vec<AFAccessRequestRow>, AppError> {
+  let rows = sqlx::query_as!(
+    AFAccessRequestRow,
+    r#"
+      SELECT
+        r.request_id,
+        r.workspace_id,
+        r.view_id,
+        r.uid,
+        u.uuid,
+        u.name,
+        u.email,
+        u.metadata ->> 'icon_url' AS avatar_url,
+        s.status AS "status!: AFAccessRequestStatusColumn",
+        r.reason,
+        r.rejection_reason,
+        r.created_at,
+        r.expires_at
+      FROM af_access_request r
+      JOIN af_user u USING (uid)
+      CROSS JOIN LATERAL (
+        SELECT CASE WHEN r.status = 0 AND r.expires_at <= CURRENT_TIMESTAMP THEN 3 ELSE r.status END
+          AS status
+      ) s
+      WHERE r.workspace_id = $1
+        AND ($2::UUID IS NULL OR r.view_id = $2)
+        AND ($3::INT IS NULL OR s.status = $3)
+      ORDER BY r.created_at DESC
+    "#,
+    workspace_id,
+    view_id,
+    status.map(|status| status as i32),
+  )
+  .fetch_all(executor)
+  .await?;
+  Ok(rows)
+}
+
+/// The users who can approve the requests of the workspace.
+pub async fn select_access_request_approver_uids<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+) -> Result<Vec<i64>, AppError> {
+  let uids = sqlx::query_scalar!(
+    r#"
+      SELECT uid FROM af_workspace_member
+      WHERE workspace_id = $1 AND role_id = $2
+    "#,
+    workspace_id,
+    AFRole::Owner as i32,
+  )
+  .fetch_all(executor)
+  .await?;
+  Ok(uids)
 }
 
 pub async fn delete_access_request<'a, E: Executor<'a, Database = Postgres>>(
@@ -0,0 +1,88 @@
+use crate::access_request::{select_access_request_approver_uids, select_access_request_row};
+use crate::listener::PostgresDBListener;
+use crate::pg_row::{AFAccessRequestNotification, AFAccessRequestStatusColumn};
+use anyhow::Error;
+use app_error::AppError;
+use collab_rt_entity::user::AFAccessRequestChange;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::{error, trace};
+
+/// An access request change and the uids of the users who should receive it.
+#[derive(Debug, Clone)]
+pub struct AccessRequestEvent {
+  pub recipients: Vec<i64>,
+  pub change: AFAccessRequestChange,
+}
+
+/// Listens to the notifications of the `af_access_request_change_trigger`. New requests are sent
+/// to the owners of the workspace and status changes to the requester.
+pub struct AccessRequestListener {
+  pub notify: broadcast::Sender<Arc<AccessRequestEvent>>,
+}
+
+impl AccessRequestListener {
+  pub async fn new(pg_pool: &PgPool, channel: &str) -> Result<Self, Error> {
+    let listener = PostgresDBListener::<AFAccessRequestNotification>::new(pg_pool, channel).await?;
+    let mut notifications = listener.notify.subscribe();
+    let (tx, _) = broadcast::channel(1000);
+    let notify = tx.clone();
+    let pg_pool = pg_pool.clone();
+    tokio::spawn(async move {
+      loop {
+        let notification = match notifications.recv().await {
+          Ok(notification) => notification,
+          Err(broadcast::error::RecvError::Lagged(count)) => {
+            error!(
+              "Access request listener lagged, {} notifications dropped",
+              count
+            );
+            continue;
+          },
+          Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        // Nobody is connected, skip the queries.
+        if tx.receiver_count() == 0 {
+          continue;
+        }
+        match load_access_request_event(&pg_pool, &notification).await {
+          Ok(event) => {
+            trace!("Receive access request change: {:?}", event);
+            let _ = tx.send(Arc::new(event));
+          },
+          Err(err) => error!(
+            "Failed to load access request {}: {}",
+            notification.request_id, err
+          ),
+        }
+      }
+    });
+    Ok(Self { notify })
+  }
+}
+
+async fn load_access_request_event(
+  pg_pool: &PgPool,
+  notification: &AFAccessRequestNotification,
+) -> Result<AccessRequestEvent, AppError> {
+  let request = select_access_request_row(pg_pool, notification.request_id).await?;
+  let recipients = if request.status == AFAccessRequestStatusColumn::Pending {
+    select_access_request_approver_uids(pg_pool, &request.workspace_id).await?
+  } else {
+    vec![request.uid]
+  };
+  Ok(AccessRequestEvent {
+    recipients,
+    change: AFAccessRequestChange {
+      request_id: request.request_id.to_string(),
+      workspace_id: request.workspace_id.to_string(),
+      view_id: request.view_id.to_string(),
+      requester_uid: request.uid,
+      status: request.status as u8,
+      reason: request.reason,
+      rejection_reason: request.rejection_reason,
+    },
+  })
+}
@@ -0,0 +1,274 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, FromRow, Postgres};
+use uuid::Uuid;
+
+/// Frequency of the activity digests of a workspace, or the default of the user when
+/// `workspace_id` is None.
+#[derive(Debug, Clone, FromRow)]
+pub struct AFActivityDigestSettingRow {
+  pub workspace_id: Option<Uuid>,
+  pub frequency: i16,
+}
+
+/// A member of a workspace whose next activity digest is due.
+#[derive(Debug, Clone, FromRow)]
+pub struct AFDueActivityDigestRow {
+  pub uid: i64,
+  pub email: String,
+  pub name: String,
+  pub workspace_id: Uuid,
+  pub workspace_name: String,
+  pub frequency: i16,
+  pub last_sent_at: Option<DateTime<Utc>>,
+}
+
+/// A document owned or followed by the user, edited since the last digest.
+#[derive(Debug, Clone, FromRow)]
+pub struct AFActivityDigestEditedViewRow {
+  pub view_id: String,
+  pub updated_at: DateTime<Utc>,
+}
+
+/// A comment on a document owned or followed by the user, added since the last digest.
+#[derive(Debug, Clone, FromRow)]
+pub struct AFActivityDigestCommentRow {
+  pub view_id: Uuid,
+  pub author_name: String,
+  pub content: String,
+  pub created_at: DateTime<Utc>,
+}
+
+/// The documents a user follows in a workspace: the documents the user created, was mentioned in
+/// or commented on. Expects the workspace id as $1 and the uid as $2.
+const FOLLOWED_VIEWS_CTE: &str = r#"
+  followed AS (
+    SELECT c.oid AS view_id
+    FROM af_collab c
+    WHERE c.workspace_id = $1 AND c.owner_uid = $2 AND c.partition_key = 0
+    UNION
+    SELECT m.view_id::TEXT
+    FROM af_document_mention m
+    WHERE m.workspace_id = $1 AND m.uid = $2
+    UNION
+    SELECT t.view_id::TEXT
+    FROM af_document_comment_thread t
+    JOIN af_document_comment dc ON dc.thread_id = t.thread_id
+    WHERE t.workspace_id = $1 AND dc.created_by = $2
+  )
+"#;
+
+pub async fn select_activity_digest_settings<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+) -> Result<Vec<AFActivityDigestSettingRow>, AppError> {
+  let settings = sqlx::query_as(
+    r#"
+      SELECT workspace_id, frequency
+      FROM af_activity_digest_setting
+      WHERE uid = $1
+      ORDER BY updated_at
+    "#,
+  )
+  .bind(uid)
+  .fetch_all(executor)
+  .await?;
+  Ok(settings)
+}
+
+pub async fn upsert_activity_digest_setting<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+  workspace_id: Option<&Uuid>,
+  frequency: i16,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_activity_digest_setting (uid, workspace_id, frequency)
+      VALUES ($1, $2, $3)
+      ON CONFLICT (uid, COALESCE(workspace_id, '00000000-0000-0000-0000-000000000000'::UUID))
+      DO UPDATE SET frequency = EXCLUDED.frequency, updated_at = NOW()
+    "#,
+  )
+  .bind(uid)
+  .bind(workspace_id)
+  .bind(frequency)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Returns false if the workspace didn't have its own frequency.
+pub async fn delete_activity_digest_setting<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+  workspace_id: &Uuid,
+) -> Result<bool, AppError> {
+  let res = sqlx::query(
+    r#"
+      DELETE FROM af_activity_digest_setting
+      WHERE uid = $1 AND workspace_id = $2
+    "#,
+  )
+  .bind(uid)
+  .bind(workspace_id)
+  .execute(executor)
+  .await?;
+  Ok(res.rows_affected() > 0)
+}
+
+/// Claims the members whose digest of a workspace was never compiled, or was compiled more than
+/// a day or a week ago depending on the frequency. The frequency is weekly unless the user changed
+/// it. The claimed digests are marked as sent, so concurrent workers never claim the same digest,
+/// and `last_sent_at` holds the time of the previous digest.
+pub async fn claim_due_activity_digests<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  limit: i64,
+) -> Result<Vec<AFDueActivityDigestRow>, AppError> {
+  let digests = sqlx::query_as(
+    r#"
+      WITH due AS (
+        SELECT
+          wm.uid, au.email, au.name, wm.workspace_id, w.workspace_name,
+          COALESCE(ws.frequency, us.frequency, 2) AS frequency,
+          sent.sent_at AS last_sent_at
+        FROM af_workspace_member wm
+        JOIN af_user au ON au.uid = wm.uid
+        JOIN af_workspace w ON w.workspace_id = wm.workspace_id
+        LEFT JOIN af_activity_digest_setting ws
+          ON ws.uid = wm.uid AND ws.workspace_id = wm.workspace_id
+        LEFT JOIN af_activity_digest_setting us
+          ON us.uid = wm.uid AND us.workspace_id IS NULL
+        LEFT JOIN af_activity_digest_sent sent
+          ON sent.uid = wm.uid AND sent.workspace_id = wm.workspace_id
+        WHERE au.deleted_at IS NULL
+          AND COALESCE(ws.frequency, us.frequency, 2) <> 0
+          AND (
+            sent.sent_at IS NULL
+            OR sent.sent_at < NOW() - CASE COALESCE(ws.frequency, us.frequency, 2)
+              WHEN 1 THEN INTERVAL '1 day'
+              ELSE INTERVAL '7 days'
+            END
+          )
+        ORDER BY sent.sent_at NULLS FIRST
+        LIMIT $1
+        FOR UPDATE OF wm SKIP LOCKED
+      ),
+      claimed AS (
+        -- A digest claimed by another worker after the snapshot was taken was sent less than a
+        -- day ago, so the conflict update skips it and it isn't returned
+        INSERT INTO af_activity_digest_sent (uid, workspace_id, sent_at)
+        SELECT uid, workspace_id, NOW() FROM due
+        ON CONFLICT (uid, workspace_id) DO UPDATE SET sent_at = EXCLUDED.sent_at
+        WHERE af_activity_digest_sent.sent_at < EXCLUDED.sent_at - INTERVAL '1 day'
+        RETURNING uid, workspace_id
+      )
+      SELECT
+        due.uid, due.email, due.name, due.workspace_id, due.workspace_name,
+        due.frequency, due.last_sent_at
+      FROM due
+      JOIN claimed ON claimed.uid = due.uid AND claimed.workspace_id = due.workspace_id
+      ORDER BY due.last_sent_at NULLS FIRST
+    "#,
+  )
+  .bind(limit)
+  .fetch_all(executor)
+  .await?;
+  Ok(digests)
+}
+
+/// Documents the user follows that were updated since the given time, most recent first.
+pub async fn select_activity_digest_edited_views<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+  since: DateTime<Utc>,
+  limit: i64,
+) -> Result<Vec<AFActivityDigestEditedViewRow>, AppError> {
+  let query = format!(
+    r#"
+      WITH {FOLLOWED_VIEWS_CTE}
+      SELECT c.oid AS view_id, c.updated_at
+      FROM af_collab c
+      WHERE c.workspace_id = $1
+        AND c.partition_key = 0
+        AND c.deleted_at IS NULL
+        AND c.updated_at > $3
+        AND c.oid IN (SELECT view_id FROM followed)
+      ORDER BY c.updated_at DESC
+      LIMIT $4
+    "#
+  );
+  let views = sqlx::query_as(&query)
+    .bind(workspace_id)
+    .bind(uid)
+    .bind(since)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+  Ok(views)
+}
+
+/// Comments of the other members on the documents the user follows, added since the given time,
+/// newest first.
+pub async fn select_activity_digest_comments<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+  since: DateTime<Utc>,
+  limit: i64,
+) -> Result<Vec<AFActivityDigestCommentRow>, AppError> {
+  let query = format!(
+    r#"
+      WITH {FOLLOWED_VIEWS_CTE}
+      SELECT t.view_id, COALESCE(au.name, '') AS author_name, dc.content, dc.created_at
+      FROM af_document_comment dc
+      JOIN af_document_comment_thread t ON t.thread_id = dc.thread_id
+      LEFT JOIN af_user au ON au.uid = dc.created_by
+      WHERE t.workspace_id = $1
+        AND dc.created_at > $3
+        AND NOT dc.is_deleted
+        AND dc.created_by IS DISTINCT FROM $2
+        AND t.view_id::TEXT IN (SELECT view_id FROM followed)
+      ORDER BY dc.created_at DESC
+      LIMIT $4
+    "#
+  );
+  let comments = sqlx::query_as(&query)
+    .bind(workspace_id)
+    .bind(uid)
+    .bind(since)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+  Ok(comments)
+}
+
+/// Names of the other members that joined the workspace since the given time, in joining order.
+pub async fn select_activity_digest_new_members<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+  since: DateTime<Utc>,
+  limit: i64,
+) -> Result<Vec<String>, AppError> {
+  let names = sqlx::query_scalar(
+    r#"
+      SELECT au.name
+      FROM af_workspace_member wm
+      JOIN af_user au ON au.uid = wm.uid
+      WHERE wm.workspace_id = $1
+        AND wm.uid <> $2
+        AND wm.created_at > $3
+      ORDER BY wm.created_at
+      LIMIT $4
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .bind(since)
+  .bind(limit)
+  .fetch_all(executor)
+  .await?;
+  Ok(names)
+}
@@ -0,0 +1,191 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use shared_entity::dto::api_token_dto::{ApiToken, ApiTokenScope};
+use sqlx::{Executor, FromRow, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug, FromRow)]
+struct AFApiTokenRow {
+  token_id: Uuid,
+  workspace_id: Uuid,
+  name: String,
+  scope: i32,
+  token_prefix: String,
+  created_at: DateTime<Utc>,
+  expires_at: Option<DateTime<Utc>>,
+  last_used_at: Option<DateTime<Utc>>,
+  client_id: Option<String>,
+}
+
+impl From<AFApiTokenRow> for ApiToken {
+  fn from(row: AFApiTokenRow) -> Self {
+    ApiToken {
+      token_id: row.token_id,
+      workspace_id: row.workspace_id,
+      name: row.name,
+      scope: ApiTokenScope::from(row.scope),
+      token_prefix: row.token_prefix,
+      created_at: row.created_at,
+      expires_at: row.expires_at,
+      last_used_at: row.last_used_at,
+      client_id: row.client_id,
+    }
+  }
+}
+
+/// A valid API token and the member it acts on behalf of, see [select_api_token_by_hash].
+#[derive(Debug, Clone, FromRow)]
+pub struct AFApiTokenOwner {
+  pub token_id: Uuid,
+  pub workspace_id: Uuid,
+  pub scope: i32,
+  pub uid: i64,
+  pub uuid: Uuid,
+  pub email: String,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_api_token<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+  name: &str,
+  scope: ApiTokenScope,
+  token_hash: &str,
+  token_prefix: &str,
+  expires_at: Option<DateTime<Utc>>,
+  client_id: Option<&str>,
+) -> Result<ApiToken, AppError> {
+  let row: AFApiTokenRow = sqlx::query_as(
+    r#"
+      INSERT INTO af_workspace_api_token
+        (workspace_id, uid, name, scope, token_hash, token_prefix, expires_at, client_id)
+      VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+      RETURNING token_id, workspace_id, name, scope, token_prefix, created_at, expires_at,
+        last_used_at, client_id
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .bind(name)
+  .bind(scope as i32)
+  .bind(token_hash)
+  .bind(token_prefix)
+  .bind(expires_at)
+  .bind(client_id)
+  .fetch_one(executor)
+  .await?;
+  Ok(row.into())
+}
+
+/// Returns the tokens the user created in the workspace, the most recent first.
+pub async fn select_api_tokens<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<Vec<ApiToken>, AppError> {
+  let rows: Vec<AFApiTokenRow> = sqlx::query_as(
+    r#"
+      SELECT token_id, workspace_id, name, scope, token_prefix, created_at, expires_at,
+        last_used_at, client_id
+      FROM af_workspace_api_token
+      WHERE workspace_id = $1 AND uid = $2
+      ORDER BY created_at DESC
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(ApiToken::from).collect())
+}
+
+/// Returns the uid of the member who created the token, if the token belongs to the workspace.
+pub async fn select_api_token_creator<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  token_id: &Uuid,
+) -> Result<Option<i64>, AppError> {
+  let uid = sqlx::query_scalar(
+    r#"
+      SELECT uid FROM af_workspace_api_token
+      WHERE workspace_id = $1 AND token_id = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(token_id)
+  .fetch_optional(executor)
+  .await?;
+  Ok(uid)
+}
+
+pub async fn delete_api_token<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  token_id: &Uuid,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      DELETE FROM af_workspace_api_token
+      WHERE workspace_id = $1 AND token_id = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(token_id)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Revokes all the tokens the user created in the workspace.
+pub async fn delete_api_tokens_of_user<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      DELETE FROM af_workspace_api_token
+      WHERE workspace_id = $1 AND uid = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Looks up a token by its hash. Expired tokens and tokens of users who are no longer members of
+/// the workspace are ignored. `last_used_at` is refreshed at most once a minute to avoid writing on
+/// every request.
+pub async fn select_api_token_by_hash<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  token_hash: &str,
+) -> Result<Option<AFApiTokenOwner>, AppError> {
+  let owner = sqlx::query_as(
+    r#"
+      WITH token AS (
+        SELECT t.token_id, t.workspace_id, t.scope, t.last_used_at, af_user.uid, af_user.uuid,
+          af_user.email
+        FROM af_workspace_api_token t
+          JOIN af_user ON t.uid = af_user.uid
+          JOIN af_workspace_member m ON m.workspace_id = t.workspace_id AND m.uid = t.uid
+        WHERE t.token_hash = $1
+          AND (t.expires_at IS NULL OR t.expires_at > NOW())
+      ), touched AS (
+        UPDATE af_workspace_api_token
+        SET last_used_at = NOW()
+        WHERE token_id IN (
+          SELECT token_id FROM token
+          WHERE last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute'
+        )
+      )
+      SELECT token_id, workspace_id, scope, uid, uuid, email FROM token
+    "#,
+  )
+  .bind(token_hash)
+  .fetch_optional(executor)
+  .await?;
+  Ok(owner)
+}
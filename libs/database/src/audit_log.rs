@@ -0,0 +1,118 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use shared_entity::dto::audit_log_dto::{AuditAction, AuditLogEntry, QueryAuditLogParams};
+use sqlx::{Executor, FromRow, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug, FromRow)]
+struct AFAuditLogRow {
+  log_id: i64,
+  workspace_id: Option<Uuid>,
+  actor_uid: Option<i64>,
+  actor_email: Option<String>,
+  action: String,
+  object_type: String,
+  object_id: Option<String>,
+  metadata: serde_json::Value,
+  ip_address: Option<String>,
+  user_agent: Option<String>,
+  created_at: DateTime<Utc>,
+}
+
+impl From<AFAuditLogRow> for AuditLogEntry {
+  fn from(row: AFAuditLogRow) -> Self {
+    AuditLogEntry {
+      log_id: row.log_id,
+      workspace_id: row.workspace_id,
+      actor_uid: row.actor_uid,
+      actor_email: row.actor_email,
+      action: row.action,
+      object_type: row.object_type,
+      object_id: row.object_id,
+      metadata: row.metadata,
+      ip_address: row.ip_address,
+      user_agent: row.user_agent,
+      created_at: row.created_at,
+    }
+  }
+}
+
+/// Inserts an entry of the audit log. The sign-in of a session that was already recorded is
+/// ignored, see the `session_id` of its metadata.
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_audit_log<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: Option<&Uuid>,
+  actor_uid: Option<i64>,
+  action: AuditAction,
+  object_id: Option<&str>,
+  metadata: &serde_json::Value,
+  ip_address: Option<&str>,
+  user_agent: Option<&str>,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_audit_log
+        (workspace_id, actor_uid, action, object_type, object_id, metadata, ip_address, user_agent)
+      VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+      ON CONFLICT DO NOTHING
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(actor_uid)
+  .bind(action.as_str())
+  .bind(action.object_type())
+  .bind(object_id)
+  .bind(metadata)
+  .bind(ip_address)
+  .bind(user_agent)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Returns the entries of the workspace matching the filters, the most recent first. The sign-ins
+/// of the current members are included, since they don't belong to a workspace.
+pub async fn select_audit_log<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  params: &QueryAuditLogParams,
+  limit: i64,
+) -> Result<Vec<AuditLogEntry>, AppError> {
+  let rows: Vec<AFAuditLogRow> = sqlx::query_as(
+    r#"
+      SELECT l.log_id, l.workspace_id, l.actor_uid, u.email AS actor_email, l.action,
+        l.object_type, l.object_id, l.metadata, l.ip_address, l.user_agent, l.created_at
+      FROM af_audit_log l
+        LEFT JOIN af_user u ON u.uid = l.actor_uid
+      WHERE (
+          l.workspace_id = $1
+          OR (
+            l.workspace_id IS NULL
+            AND l.actor_uid IN (SELECT uid FROM af_workspace_member WHERE workspace_id = $1)
+          )
+        )
+        AND ($2::BIGINT IS NULL OR l.actor_uid = $2)
+        AND ($3::TEXT IS NULL OR l.action = $3)
+        AND ($4::TEXT IS NULL OR l.object_type = $4)
+        AND ($5::TEXT IS NULL OR l.object_id = $5)
+        AND ($6::TIMESTAMPTZ IS NULL OR l.created_at >= $6)
+        AND ($7::TIMESTAMPTZ IS NULL OR l.created_at < $7)
+        AND ($8::BIGINT IS NULL OR l.log_id < $8)
+      ORDER BY l.log_id DESC
+      LIMIT $9
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(params.actor_uid)
+  .bind(params.action.map(|action| action.as_str()))
+  .bind(params.object_type.as_deref())
+  .bind(params.object_id.as_deref())
+  .bind(params.since)
+  .bind(params.until)
+  .bind(params.before)
+  .bind(limit)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(AuditLogEntry::from).collect())
+}
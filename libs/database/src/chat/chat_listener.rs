@@ -0,0 +1,90 @@
+use crate::chat::chat_ops::{select_chat, select_chat_message};
+use crate::chat::chat_participant_ops::select_chat_participant_uids;
+use crate::listener::PostgresDBListener;
+use crate::pg_row::AFChatMessageNotification;
+use anyhow::Error;
+use app_error::AppError;
+use collab_rt_entity::user::AFChatMessageChange;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::{error, trace};
+
+/// A chat message change and the uids of the participants who should receive it.
+#[derive(Debug, Clone)]
+pub struct ChatMessageEvent {
+  pub participants: Vec<i64>,
+  pub change: AFChatMessageChange,
+}
+
+/// Listens to the notifications of the `af_chat_message_change_trigger`. The notification only
+/// contains the ids of the message, so the message and the participants of the chat are loaded
+/// once here instead of once per connected user.
+pub struct ChatMessageListener {
+  pub notify: broadcast::Sender<Arc<ChatMessageEvent>>,
+}
+
+impl ChatMessageListener {
+  pub async fn new(pg_pool: &PgPool, channel: &str) -> Result<Self, Error> {
+    let listener = PostgresDBListener::<AFChatMessageNotification>::new(pg_pool, channel).await?;
+    let mut notifications = listener.notify.subscribe();
+    let (tx, _) = broadcast::channel(1000);
+    let notify = tx.clone();
+    let pg_pool = pg_pool.clone();
+    tokio::spawn(async move {
+      loop {
+        let notification = match notifications.recv().await {
+          Ok(notification) => notification,
+          Err(broadcast::error::RecvError::Lagged(count)) => {
+            error!(
+              "Chat message listener lagged, {} notifications dropped",
+              count
+            );
+            continue;
+          },
+          Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        // Nobody is connected, skip the queries.
+        if tx.receiver_count() == 0 {
+          continue;
+        }
+        match load_chat_message_event(&pg_pool, &notification).await {
+          Ok(event) => {
+            trace!("Receive chat message change: {:?}", event);
+            let _ = tx.send(Arc::new(event));
+          },
+          Err(err) => error!(
+            "Failed to load chat message {} of chat {}: {}",
+            notification.message_id, notification.chat_id, err
+          ),
+        }
+      }
+    });
+    Ok(Self { notify })
+  }
+}
+
+async fn load_chat_message_event(
+  pg_pool: &PgPool,
+  notification: &AFChatMessageNotification,
+) -> Result<ChatMessageEvent, AppError> {
+  let chat = select_chat(pg_pool, &notification.chat_id.to_string()).await?;
+  let message = select_chat_message(pg_pool, notification.message_id).await?;
+  let participants = select_chat_participant_uids(pg_pool, &notification.chat_id).await?;
+  Ok(ChatMessageEvent {
+    participants,
+    change: AFChatMessageChange {
+      workspace_id: chat.workspace_id.to_string(),
+      chat_id: notification.chat_id.to_string(),
+      message_id: message.message_id,
+      author_id: message.author.author_id,
+      author_type: message.author.author_type as u8,
+      content: message.content,
+      created_at: message.created_at.timestamp_millis(),
+      reply_message_id: message.reply_message_id,
+      metadata: serde_json::to_string(&message.meta_data).unwrap_or_default(),
+      is_update: notification.action_type == "UPDATE",
+    },
+  })
+}
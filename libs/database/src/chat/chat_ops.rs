@@ -3,9 +3,10 @@ use anyhow::anyhow;
 use app_error::AppError;
 use chrono::{DateTime, Utc};
 use shared_entity::dto::chat_dto::{
-  ChatAuthor, ChatMessage, ChatMessageMetadata, ChatSettings, CreateChatParams,
-  GetChatMessageParams, MessageCursor, RepeatedChatMessage, UpdateChatMessageContentParams,
-  UpdateChatMessageMetaParams, UpdateChatParams,
+  ChatAuthor, ChatBranch, ChatMessage, ChatMessageMetadata, ChatMessageSearchResult, ChatSettings,
+  CreateChatParams, GetChatMessageParams, MessageCursor, RepeatedAnswerVersion, RepeatedChatBranch,
+  RepeatedChatMessage, RepeatedChatMessageSearchResult, SearchChatMessageParams,
+  UpdateChatMessageContentParams, UpdateChatMessageMetaParams, UpdateChatParams,
 };
 
 use serde_json::json;
@@ -20,6 +21,7 @@ use uuid::Uuid;
 
 pub async fn insert_chat<'a, E: Executor<'a, Database = Postgres>>(
   executor: E,
+  uid: i64,
   workspace_id: &str,
   params: CreateChatParams,
 ) -> Result<(), AppError> {
@@ -28,13 +30,14 @@ pub async fn insert_chat<'a, E: Executor<'a, Database = Postgres>>(
   let rag_ids = json!(params.rag_ids);
   sqlx::query!(
     r#"
-       INSERT INTO af_chat (chat_id, name, workspace_id, rag_ids)
-       VALUES ($1, $2, $3, $4)
+       INSERT INTO af_chat (chat_id, name, workspace_id, rag_ids, created_by)
+       VALUES ($1, $2, $3, $4, $5)
     "#,
     chat_id,
     params.name,
     workspace_id,
     rag_ids,
+    uid,
   )
   .execute(executor)
   .await
@@ -157,7 +160,7 @@ pub async fn select_chat<'a, E: Executor<'a, Database = Postgres>>(
   let row = sqlx::query_as!(
     AFChatRow,
     r#"
-        SELECT *
+        SELECT chat_id, created_at, deleted_at, name, rag_ids, workspace_id, meta_data
         FROM af_chat
         WHERE chat_id = $1 AND deleted_at IS NULL
     "#,
@@ -193,6 +196,8 @@ pub async fn select_chat_rag_ids<'a, E: Executor<'a, Database = Postgres>>(
   Ok(rag_ids)
 }
 
+/// Inserts a new version of the answer to the given question. The previous versions are kept and
+/// the question is updated to reply with the new version.
 pub async fn insert_answer_message_with_transaction(
   transaction: &mut Transaction<'_, Postgres>,
   author: ChatAuthor,
@@ -202,101 +207,62 @@ pub async fn insert_answer_message_with_transaction(
   question_message_id: i64,
 ) -> Result<ChatMessage, AppError> {
   let chat_id = Uuid::from_str(chat_id)?;
-  let existing_reply_message_id: Option<i64> = sqlx::query_scalar!(
+  let row = sqlx::query!(
     r#"
-      SELECT reply_message_id
-      FROM af_chat_messages
-      WHERE message_id = $1
+      INSERT INTO af_chat_messages (chat_id, author, content, meta_data, question_message_id, branch_id)
+      SELECT $1, $2, $3, $4, question.message_id, question.branch_id
+      FROM af_chat_messages AS question
+      WHERE question.message_id = $5 AND question.chat_id = $1
+      RETURNING message_id, created_at
     "#,
-    question_message_id
+    chat_id,
+    json!(author),
+    &content,
+    &metadata,
+    question_message_id,
   )
-  .fetch_one(transaction.deref_mut())
-  .await?;
-
-  if let Some(reply_id) = existing_reply_message_id {
-    // If there is an existing reply_message_id, update the existing message
-    sqlx::query!(
-      r#"
-         UPDATE af_chat_messages
-         SET content = $2,
-             author = $3,
-             created_at = CURRENT_TIMESTAMP,
-             meta_data = $4
-         WHERE message_id = $1
-      "#,
-      reply_id,
-      &content,
-      json!(author),
-      metadata,
-    )
-    .execute(transaction.deref_mut())
-    .await
-    .map_err(|err| AppError::Internal(anyhow!("Failed to update chat message: {}", err)))?;
-
-    let row = sqlx::query!(
-      r#"
-        SELECT message_id, content, created_at, author, meta_data, reply_message_id
-        FROM af_chat_messages
-        WHERE message_id = $1
-      "#,
-      reply_id
-    )
-    .fetch_one(transaction.deref_mut())
-    .await
-    .map_err(|err| AppError::Internal(anyhow!("Failed to fetch updated message: {}", err)))?;
+  .fetch_optional(transaction.deref_mut())
+  .await
+  .map_err(|err| AppError::Internal(anyhow!("Failed to insert chat message: {}", err)))?
+  .ok_or_else(|| {
+    AppError::RecordNotFound(format!(
+      "question message with given id:{} is not found",
+      question_message_id
+    ))
+  })?;
 
-    let chat_message = ChatMessage {
-      author,
-      message_id: row.message_id,
-      content: row.content,
-      created_at: row.created_at,
-      meta_data: row.meta_data,
-      reply_message_id: Some(question_message_id),
-    };
+  update_question_reply_message_id(transaction, question_message_id, row.message_id).await?;
 
-    Ok(chat_message)
-  } else {
-    // Insert a new chat message
-    let row = sqlx::query!(
-      r#"
-        INSERT INTO af_chat_messages (chat_id, author, content, meta_data)
-        VALUES ($1, $2, $3, $4)
-        RETURNING message_id, created_at
-      "#,
-      chat_id,
-      json!(author),
-      &content,
-      &metadata,
-    )
-    .fetch_one(transaction.deref_mut())
-    .await
-    .map_err(|err| AppError::Internal(anyhow!("Failed to insert chat message: {}", err)))?;
-
-    // Update the question message with the new reply_message_id
-    sqlx::query!(
-      r#"
-        UPDATE af_chat_messages
-        SET reply_message_id = $2
-        WHERE message_id = $1
-      "#,
-      question_message_id,
-      row.message_id,
-    )
-    .execute(transaction.deref_mut())
-    .await
-    .map_err(|err| AppError::Internal(anyhow!("Failed to update reply_message_id: {}", err)))?;
+  let chat_message = ChatMessage {
+    author,
+    message_id: row.message_id,
+    content,
+    created_at: row.created_at,
+    meta_data: metadata,
+    reply_message_id: None,
+  };
 
-    let chat_message = ChatMessage {
-      author,
-      message_id: row.message_id,
-      content,
-      created_at: row.created_at,
-      meta_data: metadata,
-      reply_message_id: None,
-    };
+  Ok(chat_message)
+}
 
-    Ok(chat_message)
-  }
+async fn update_question_reply_message_id(
+  transaction: &mut Transaction<'_, Postgres>,
+  question_message_id: i64,
+  answer_message_id: i64,
+) -> Result<(), AppError> {
+  sqlx::query!(
+    r#"
+      UPDATE af_chat_messages
+      SET reply_message_id = $2
+      WHERE message_id = $1
+    "#,
+    question_message_id,
+    answer_message_id,
+  )
+  .execute(transaction.deref_mut())
+  .await
+  .map_err(|err| AppError::Internal(anyhow!("Failed to update reply_message_id: {}", err)))?;
+  Ok(())
 }
 
 pub async fn insert_answer_message(
@@ -326,25 +292,44 @@ pub async fn insert_answer_message(
   Ok(chat_message)
 }
 
+/// Inserts a question into the active branch of the chat.
 pub async fn insert_question_message<'a, E: Executor<'a, Database = Postgres>>(
   executor: E,
   author: ChatAuthor,
   chat_id: &str,
   content: String,
   metadata: Vec<ChatMessageMetadata>,
+) -> Result<ChatMessage, AppError> {
+  insert_question_message_in_branch(executor, author, chat_id, content, metadata, None).await
+}
+
+/// Inserts a question into the given branch, or into the active branch of the chat when
+/// `branch_id` is `None`.
+pub async fn insert_question_message_in_branch<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  author: ChatAuthor,
+  chat_id: &str,
+  content: String,
+  metadata: Vec<ChatMessageMetadata>,
+  branch_id: Option<i64>,
 ) -> Result<ChatMessage, AppError> {
   let metadata = json!(metadata);
   let chat_id = Uuid::from_str(chat_id)?;
   let row = sqlx::query!(
     r#"
-        INSERT INTO af_chat_messages (chat_id, author, content, meta_data)
-        VALUES ($1, $2, $3, $4)
+        INSERT INTO af_chat_messages (chat_id, author, content, meta_data, branch_id)
+        VALUES ($1, $2, $3, $4, COALESCE(
+          $5::BIGINT,
+          (SELECT active_branch_id FROM af_chat WHERE chat_id = $1),
+          0
+        ))
         RETURNING message_id, created_at
         "#,
     chat_id,
     json!(author),
     &content,
     &metadata,
+    branch_id,
   )
   .fetch_one(executor)
   .await
@@ -367,10 +352,11 @@ pub async fn select_chat_messages(
   params: GetChatMessageParams,
 ) -> Result<RepeatedChatMessage, AppError> {
   let chat_id = Uuid::from_str(chat_id)?;
+  // The messages visible in the branch, see the af_chat_visible_messages function
   let mut query = r#"
         SELECT message_id, content, created_at, author, meta_data, reply_message_id
-        FROM af_chat_messages
-        WHERE chat_id = $1
+        FROM af_chat_visible_messages($1, $2)
+        WHERE TRUE
     "#
   .to_string();
 
@@ -381,6 +367,12 @@ pub async fn select_chat_messages(
       desc: format!("unable to encode chat id {}", chat_id),
       err,
     })?;
+  args
+    .add(params.branch_id)
+    .map_err(|err| AppError::SqlxArgEncodingError {
+      desc: format!("unable to encode branch id {:?}", params.branch_id),
+      err,
+    })?;
 
   // Message IDs:   1    2    3    4    5
   // AfterMessageId(3, 5):   [4]  [5]  has_more = false
@@ -388,14 +380,14 @@ pub async fn select_chat_messages(
   // Offset(3, 5):           [4]  [5]  has_more = true
   match params.cursor {
     MessageCursor::AfterMessageId(after_message_id) => {
-      query += " AND message_id > $2";
+      query += " AND message_position > (SELECT af_chat_message_position(message_id, question_message_id) FROM af_chat_messages WHERE message_id = $3)";
       args
         .add(after_message_id)
         .map_err(|err| AppError::SqlxArgEncodingError {
           desc: format!("unable to encode message id {}", after_message_id),
           err,
         })?;
-      query += " ORDER BY message_id DESC LIMIT $3";
+      query += " ORDER BY message_position DESC LIMIT $4";
       args
         .add(params.limit as i64)
         .map_err(|err| AppError::SqlxArgEncodingError {
@@ -404,7 +396,7 @@ pub async fn select_chat_messages(
         })?;
     },
     MessageCursor::Offset(offset) => {
-      query += " ORDER BY message_id ASC LIMIT $2 OFFSET $3";
+      query += " ORDER BY message_position ASC LIMIT $3 OFFSET $4";
       args
         .add(params.limit as i64)
         .map_err(|err| AppError::SqlxArgEncodingError {
@@ -419,14 +411,14 @@ pub async fn select_chat_messages(
         })?;
     },
     MessageCursor::BeforeMessageId(before_message_id) => {
-      query += " AND message_id < $2";
+      query += " AND message_position < (SELECT af_chat_message_position(message_id, question_message_id) FROM af_chat_messages WHERE message_id = $3)";
       args
         .add(before_message_id)
         .map_err(|err| AppError::SqlxArgEncodingError {
           desc: format!("unable to encode message id {}", before_message_id),
           err,
         })?;
-      query += " ORDER BY message_id DESC LIMIT $3";
+      query += " ORDER BY message_position DESC LIMIT $4";
       args
         .add(params.limit as i64)
         .map_err(|err| AppError::SqlxArgEncodingError {
@@ -435,7 +427,7 @@ pub async fn select_chat_messages(
         })?;
     },
     MessageCursor::NextBack => {
-      query += " ORDER BY message_id DESC LIMIT $2";
+      query += " ORDER BY message_position DESC LIMIT $3";
       args
         .add(params.limit as i64)
         .map_err(|err| AppError::SqlxArgEncodingError {
@@ -481,47 +473,54 @@ pub async fn select_chat_messages(
 
   let total = sqlx::query_scalar!(
     r#"
-        SELECT COUNT(*)
-        FROM public.af_chat_messages
-        WHERE chat_id = $1
+        SELECT COUNT(*) AS "count!"
+        FROM af_chat_visible_messages($1, $2)
         "#,
-    &chat_id
+    chat_id,
+    params.branch_id,
   )
   .fetch_one(txn.deref_mut())
-  .await?
-  .unwrap_or(0);
+  .await?;
 
   let has_more = match params.cursor {
-    MessageCursor::AfterMessageId(_) => {
-      if messages.is_empty() {
-        false
-      } else {
-        sqlx::query!(
-          "SELECT EXISTS(SELECT 1 FROM af_chat_messages WHERE chat_id = $1 AND message_id > $2)",
-          &chat_id,
-          messages[0].message_id
+    MessageCursor::AfterMessageId(_) => match messages.first() {
+      None => false,
+      Some(message) => {
+        sqlx::query_scalar!(
+          r#"
+            SELECT EXISTS(
+              SELECT 1 FROM af_chat_visible_messages($1, $2) v
+              JOIN af_chat_messages m ON m.message_id = $3
+              WHERE v.message_position > af_chat_message_position(m.message_id, m.question_message_id)
+            ) AS "exists!"
+          "#,
+          chat_id,
+          params.branch_id,
+          message.message_id,
         )
         .fetch_one(txn.deref_mut())
         .await?
-        .exists
-        .unwrap_or(false)
-      }
+      },
     },
     MessageCursor::Offset(offset) => (offset + params.limit) < total as u64,
-    MessageCursor::BeforeMessageId(_) => {
-      if messages.is_empty() {
-        false
-      } else {
-        sqlx::query!(
-          "SELECT EXISTS(SELECT 1 FROM af_chat_messages WHERE chat_id = $1 AND message_id < $2)",
-          &chat_id,
-          messages.last().as_ref().unwrap().message_id
+    MessageCursor::BeforeMessageId(_) => match messages.last() {
+      None => false,
+      Some(message) => {
+        sqlx::query_scalar!(
+          r#"
+            SELECT EXISTS(
+              SELECT 1 FROM af_chat_visible_messages($1, $2) v
+              JOIN af_chat_messages m ON m.message_id = $3
+              WHERE v.message_position < af_chat_message_position(m.message_id, m.question_message_id)
+            ) AS "exists!"
+          "#,
+          chat_id,
+          params.branch_id,
+          message.message_id,
         )
         .fetch_one(txn.deref_mut())
         .await?
-        .exists
-        .unwrap_or(false)
-      }
+      },
     },
     MessageCursor::NextBack => params.limit < total as u64,
   };
@@ -533,18 +532,18 @@ pub async fn select_chat_messages(
   })
 }
 
+/// Returns all the messages visible in the active branch of the chat.
 pub async fn get_all_chat_messages<'a, E: Executor<'a, Database = Postgres>>(
   executor: E,
   chat_id: &str,
 ) -> Result<Vec<ChatMessage>, AppError> {
   let chat_id = Uuid::from_str(chat_id)?;
   let rows = sqlx::query!(
-    // ChatMessage,
     r#"
-     SELECT message_id, content, created_at, author, meta_data, reply_message_id
-          FROM af_chat_messages
-          WHERE chat_id = $1
-          ORDER BY created_at ASC
+     SELECT message_id AS "message_id!", content AS "content!", created_at AS "created_at!",
+            author AS "author!", meta_data AS "meta_data!", reply_message_id
+          FROM af_chat_visible_messages($1, NULL)
+          ORDER BY message_position ASC
    "#,
     chat_id,
   )
@@ -574,38 +573,6 @@ pub async fn get_all_chat_messages<'a, E: Executor<'a, Database = Postgres>>(
   Ok(messages)
 }
 
-pub async fn delete_answer_message_by_question_message_id(
-  transaction: &mut Transaction<'_, Postgres>,
-  message_id: i64,
-) -> Result<(), AppError> {
-  // Step 1: Get the reply_message_id of the chat message with the given message_id
-  let reply_message_id: Option<i64> = sqlx::query_scalar!(
-    r#"
-      SELECT reply_message_id
-      FROM af_chat_messages
-      WHERE message_id = $1
-    "#,
-    message_id
-  )
-  .fetch_one(transaction.deref_mut())
-  .await?;
-
-  if let Some(reply_id) = reply_message_id {
-    // Step 2: Delete the chat message with the reply_message_id
-    sqlx::query!(
-      r#"
-        DELETE FROM af_chat_messages
-        WHERE message_id = $1
-      "#,
-      reply_id
-    )
-    .execute(transaction.deref_mut())
-    .await?;
-  }
-
-  Ok(())
-}
-
 pub async fn update_chat_message_content(
   transaction: &mut Transaction<'_, Postgres>,
   params: &UpdateChatMessageContentParams,
@@ -653,6 +620,37 @@ pub async fn update_chat_message_meta(
   Ok(())
 }
 
+pub async fn select_chat_message<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  message_id: i64,
+) -> Result<ChatMessage, AppError> {
+  let row = sqlx::query!(
+    r#"
+        SELECT message_id, content, created_at, author, meta_data, reply_message_id
+        FROM af_chat_messages
+        WHERE message_id = $1
+    "#,
+    message_id,
+  )
+  .fetch_optional(executor)
+  .await?;
+
+  match row {
+    Some(row) => Ok(ChatMessage {
+      author: serde_json::from_value(row.author)?,
+      message_id: row.message_id,
+      content: row.content,
+      created_at: row.created_at,
+      meta_data: row.meta_data,
+      reply_message_id: row.reply_message_id,
+    }),
+    None => Err(AppError::RecordNotFound(format!(
+      "chat message with given id:{} is not found",
+      message_id
+    ))),
+  }
+}
+
 pub async fn select_chat_message_content<'a, E: Executor<'a, Database = Postgres>>(
   executor: E,
   message_id: i64,
@@ -669,3 +667,310 @@ pub async fn select_chat_message_content<'a, E: Executor<'a, Database = Postgres
   .await?;
   Ok((row.content, row.meta_data))
 }
+
+/// Full text search over the messages of the non deleted chats in the workspace the user created or
+/// participates in. The most recent messages are returned first. One extra row is fetched to tell
+/// whether there are more results after the current page.
+pub async fn search_chat_messages<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &str,
+  uid: i64,
+  params: &SearchChatMessageParams,
+  limit: u64,
+  offset: u64,
+) -> Result<RepeatedChatMessageSearchResult, AppError> {
+  let workspace_id = Uuid::from_str(workspace_id)?;
+  let rows = sqlx::query!(
+    r#"
+        SELECT c.chat_id, c.name, m.message_id, m.content, m.created_at, m.author, m.meta_data, m.reply_message_id
+        FROM af_chat_messages m
+        JOIN af_chat c ON c.chat_id = m.chat_id
+        WHERE c.workspace_id = $1
+          AND c.deleted_at IS NULL
+          AND m.deleted_at IS NULL
+          AND to_tsvector('simple', m.content) @@ websearch_to_tsquery('simple', $2)
+          AND (
+            c.created_by = $5
+            OR EXISTS(SELECT 1 FROM af_chat_participant p WHERE p.chat_id = c.chat_id AND p.uid = $5)
+          )
+        ORDER BY m.created_at DESC, m.message_id DESC
+        LIMIT $3 OFFSET $4
+    "#,
+    workspace_id,
+    params.query,
+    limit as i64 + 1,
+    offset as i64,
+    uid,
+  )
+  .fetch_all(executor)
+  .await?;
+
+  let has_more = rows.len() as u64 > limit;
+  let items = rows
+    .into_iter()
+    .take(limit as usize)
+    .flat_map(
+      |row| match serde_json::from_value::<ChatAuthor>(row.author) {
+        Ok(author) => Some(ChatMessageSearchResult {
+          chat_id: row.chat_id.to_string(),
+          chat_name: row.name,
+          message: ChatMessage {
+            author,
+            message_id: row.message_id,
+            content: row.content,
+            created_at: row.created_at,
+            meta_data: row.meta_data,
+            reply_message_id: row.reply_message_id,
+          },
+        }),
+        Err(err) => {
+          warn!("Failed to deserialize author: {}", err);
+          None
+        },
+      },
+    )
+    .collect();
+
+  Ok(RepeatedChatMessageSearchResult { items, has_more })
+}
+
+/// Forks a new branch from the branch of the given question and makes it the active branch of the
+/// chat. The question itself is not part of the new branch.
+pub async fn insert_chat_branch(
+  txn: &mut Transaction<'_, Postgres>,
+  chat_id: &str,
+  fork_message_id: i64,
+) -> Result<ChatBranch, AppError> {
+  let chat_id = Uuid::from_str(chat_id)?;
+  let fork_message = sqlx::query!(
+    r#"
+      SELECT branch_id, question_message_id
+      FROM af_chat_messages
+      WHERE message_id = $1 AND chat_id = $2
+    "#,
+    fork_message_id,
+    chat_id,
+  )
+  .fetch_optional(txn.deref_mut())
+  .await?;
+  let parent_branch_id = match fork_message {
+    Some(row) if row.question_message_id.is_none() => row.branch_id,
+    Some(_) => {
+      return Err(AppError::InvalidRequest(format!(
+        "message:{} is an answer, only questions can be edited",
+        fork_message_id
+      )))
+    },
+    None => {
+      return Err(AppError::RecordNotFound(format!(
+        "chat message with given id:{} is not found",
+        fork_message_id
+      )))
+    },
+  };
+
+  let branch = sqlx::query!(
+    r#"
+      INSERT INTO af_chat_branch (chat_id, parent_branch_id, fork_message_id)
+      VALUES ($1, $2, $3)
+      RETURNING branch_id, created_at
+    "#,
+    chat_id,
+    parent_branch_id,
+    fork_message_id,
+  )
+  .fetch_one(txn.deref_mut())
+  .await?;
+
+  sqlx::query!(
+    r#"
+      UPDATE af_chat
+      SET active_branch_id = $2
+      WHERE chat_id = $1
+    "#,
+    chat_id,
+    branch.branch_id,
+  )
+  .execute(txn.deref_mut())
+  .await?;
+
+  Ok(ChatBranch {
+    branch_id: branch.branch_id,
+    parent_branch_id,
+    fork_message_id,
+    created_at: branch.created_at,
+  })
+}
+
+pub async fn select_chat_branches<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  chat_id: &str,
+) -> Result<RepeatedChatBranch, AppError> {
+  let chat_id = Uuid::from_str(chat_id)?;
+  let rows = sqlx::query!(
+    r#"
+        SELECT b.branch_id AS "branch_id?", b.parent_branch_id AS "parent_branch_id?",
+               b.fork_message_id AS "fork_message_id?", b.created_at AS "created_at?",
+               c.active_branch_id
+        FROM af_chat c
+        LEFT JOIN af_chat_branch b ON b.chat_id = c.chat_id
+        WHERE c.chat_id = $1 AND c.deleted_at IS NULL
+        ORDER BY b.branch_id ASC
+      "#,
+    chat_id,
+  )
+  .fetch_all(executor)
+  .await?;
+
+  let active_branch_id = match rows.first() {
+    Some(row) => row.active_branch_id,
+    None => {
+      return Err(AppError::RecordNotFound(format!(
+        "chat with given id:{} is not found",
+        chat_id
+      )))
+    },
+  };
+  let branches = rows
+    .into_iter()
+    .filter_map(|row| {
+      Some(ChatBranch {
+        branch_id: row.branch_id?,
+        parent_branch_id: row.parent_branch_id?,
+        fork_message_id: row.fork_message_id?,
+        created_at: row.created_at?,
+      })
+    })
+    .collect();
+
+  Ok(RepeatedChatBranch {
+    active_branch_id,
+    branches,
+  })
+}
+
+pub async fn update_chat_active_branch<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  chat_id: &str,
+  branch_id: i64,
+) -> Result<(), AppError> {
+  let chat_id = Uuid::from_str(chat_id)?;
+  let result = sqlx::query!(
+    r#"
+      UPDATE af_chat
+      SET active_branch_id = $2
+      WHERE chat_id = $1
+        AND ($2::BIGINT = 0 OR EXISTS (
+          SELECT 1 FROM af_chat_branch WHERE chat_id = $1 AND branch_id = $2
+        ))
+    "#,
+    chat_id,
+    branch_id,
+  )
+  .execute(executor)
+  .await?;
+
+  if result.rows_affected() == 0 {
+    return Err(AppError::RecordNotFound(format!(
+      "branch:{} of chat:{} is not found",
+      branch_id, chat_id
+    )));
+  }
+  Ok(())
+}
+
+/// Returns all the versions of the answer to the given question of the chat, from the oldest to
+/// the newest.
+pub async fn select_answer_versions(
+  txn: &mut Transaction<'_, Postgres>,
+  chat_id: &str,
+  question_message_id: i64,
+) -> Result<RepeatedAnswerVersion, AppError> {
+  let chat_id = Uuid::from_str(chat_id)?;
+  let selected_message_id: Option<i64> = sqlx::query_scalar!(
+    r#"
+      SELECT reply_message_id
+      FROM af_chat_messages
+      WHERE message_id = $1 AND chat_id = $2
+    "#,
+    question_message_id,
+    chat_id,
+  )
+  .fetch_optional(txn.deref_mut())
+  .await?
+  .ok_or_else(|| {
+    AppError::RecordNotFound(format!(
+      "question message with given id:{} is not found",
+      question_message_id
+    ))
+  })?;
+
+  let rows = sqlx::query!(
+    r#"
+      SELECT message_id, content, created_at, author, meta_data, reply_message_id
+      FROM af_chat_messages
+      WHERE question_message_id = $1 AND chat_id = $2
+      ORDER BY message_id ASC
+    "#,
+    question_message_id,
+    chat_id,
+  )
+  .fetch_all(txn.deref_mut())
+  .await?;
+
+  let versions = rows
+    .into_iter()
+    .flat_map(
+      |row| match serde_json::from_value::<ChatAuthor>(row.author) {
+        Ok(author) => Some(ChatMessage {
+          author,
+          message_id: row.message_id,
+          content: row.content,
+          created_at: row.created_at,
+          meta_data: row.meta_data,
+          reply_message_id: row.reply_message_id,
+        }),
+        Err(err) => {
+          warn!("Failed to deserialize author: {}", err);
+          None
+        },
+      },
+    )
+    .collect();
+
+  Ok(RepeatedAnswerVersion {
+    versions,
+    selected_message_id,
+  })
+}
+
+/// Selects which version of the answer is displayed in the conversation.
+pub async fn select_answer_version(
+  txn: &mut Transaction<'_, Postgres>,
+  chat_id: &str,
+  question_message_id: i64,
+  answer_message_id: i64,
+) -> Result<(), AppError> {
+  let chat_id = Uuid::from_str(chat_id)?;
+  let is_answer = sqlx::query_scalar!(
+    r#"
+      SELECT EXISTS(
+        SELECT 1 FROM af_chat_messages
+        WHERE message_id = $1 AND question_message_id = $2 AND chat_id = $3
+      ) AS "exists!"
+    "#,
+    answer_message_id,
+    question_message_id,
+    chat_id,
+  )
+  .fetch_one(txn.deref_mut())
+  .await?;
+  if !is_answer {
+    return Err(AppError::RecordNotFound(format!(
+      "message:{} is not an answer to question:{}",
+      answer_message_id, question_message_id
+    )));
+  }
+
+  update_question_reply_message_id(txn, question_message_id, answer_message_id).await
+}
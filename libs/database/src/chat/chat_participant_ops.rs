@@ -0,0 +1,231 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use shared_entity::dto::chat_dto::{ChatInfo, ChatParticipant, ChatParticipantRole};
+use sqlx::{Executor, Postgres};
+use uuid::Uuid;
+
+/// Access of a user to a chat, see [select_chat_access].
+#[derive(Debug, Clone)]
+pub struct ChatAccess {
+  pub workspace_id: Uuid,
+  /// `None` if the user doesn't participate in the chat.
+  pub role: Option<ChatParticipantRole>,
+  /// Chats created before participants were introduced and never used have no participants.
+  pub has_participants: bool,
+  /// `None` if the creator of the chat is unknown or was deleted.
+  pub created_by: Option<i64>,
+}
+
+pub async fn insert_chat_participant<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  chat_id: &Uuid,
+  uid: i64,
+  role: ChatParticipantRole,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_chat_participant (chat_id, uid, role)
+      VALUES ($1, $2, $3)
+      ON CONFLICT (chat_id, uid) DO UPDATE SET role = EXCLUDED.role
+    "#,
+  )
+  .bind(chat_id)
+  .bind(uid)
+  .bind(role as i32)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Adds the given users as members of the chat. Users who already participate keep their role.
+pub async fn insert_chat_members<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  chat_id: &Uuid,
+  uids: &[i64],
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_chat_participant (chat_id, uid, role)
+      SELECT $1, uid, $3
+      FROM UNNEST($2::BIGINT[]) AS uid
+      ON CONFLICT (chat_id, uid) DO NOTHING
+    "#,
+  )
+  .bind(chat_id)
+  .bind(uids)
+  .bind(ChatParticipantRole::Member as i32)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+pub async fn delete_chat_participants<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  chat_id: &Uuid,
+  uids: &[i64],
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      DELETE FROM af_chat_participant
+      WHERE chat_id = $1 AND uid = ANY($2)
+    "#,
+  )
+  .bind(chat_id)
+  .bind(uids)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Resolves the emails of the members of the workspace into their uids. Emails of users who are
+/// not members of the workspace are left out.
+pub async fn select_workspace_member_uids_by_email<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  emails: &[String],
+) -> Result<Vec<(String, i64)>, AppError> {
+  let rows: Vec<(String, i64)> = sqlx::query_as(
+    r#"
+      SELECT af_user.email, af_user.uid
+      FROM af_workspace_member
+        JOIN af_user ON af_workspace_member.uid = af_user.uid
+      WHERE af_workspace_member.workspace_id = $1
+        AND af_user.email = ANY($2)
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(emails)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows)
+}
+
+pub async fn select_chat_participants<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  chat_id: &Uuid,
+) -> Result<Vec<ChatParticipant>, AppError> {
+  let rows: Vec<(i64, String, String, i32, DateTime<Utc>)> = sqlx::query_as(
+    r#"
+      SELECT af_user.uid, af_user.name, af_user.email, p.role, p.joined_at
+      FROM af_chat_participant p
+        JOIN af_user ON p.uid = af_user.uid
+      WHERE p.chat_id = $1
+      ORDER BY p.role ASC, p.joined_at ASC
+    "#,
+  )
+  .bind(chat_id)
+  .fetch_all(executor)
+  .await?;
+
+  Ok(
+    rows
+      .into_iter()
+      .map(|(uid, name, email, role, joined_at)| ChatParticipant {
+        uid,
+        name,
+        email,
+        role: ChatParticipantRole::from(role),
+        joined_at,
+      })
+      .collect(),
+  )
+}
+
+pub async fn select_chat_participant_uids<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  chat_id: &Uuid,
+) -> Result<Vec<i64>, AppError> {
+  let uids: Vec<i64> =
+    sqlx::query_scalar(r#"SELECT uid FROM af_chat_participant WHERE chat_id = $1"#)
+      .bind(chat_id)
+      .fetch_all(executor)
+      .await?;
+  Ok(uids)
+}
+
+/// Returns `None` if the chat doesn't exist or was deleted.
+pub async fn select_chat_access<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  chat_id: &Uuid,
+  uid: i64,
+) -> Result<Option<ChatAccess>, AppError> {
+  let row: Option<(Uuid, Option<i32>, bool, Option<i64>)> = sqlx::query_as(
+    r#"
+      SELECT c.workspace_id,
+             (SELECT role FROM af_chat_participant WHERE chat_id = c.chat_id AND uid = $2),
+             EXISTS(SELECT 1 FROM af_chat_participant WHERE chat_id = c.chat_id),
+             c.created_by
+      FROM af_chat c
+      WHERE c.chat_id = $1 AND c.deleted_at IS NULL
+    "#,
+  )
+  .bind(chat_id)
+  .bind(uid)
+  .fetch_optional(executor)
+  .await?;
+
+  Ok(row.map(
+    |(workspace_id, role, has_participants, created_by)| ChatAccess {
+      workspace_id,
+      role: role.map(ChatParticipantRole::from),
+      has_participants,
+      created_by,
+    },
+  ))
+}
+
+/// Lists the chats of the workspace the user participates in, and the chats without participants
+/// which the whole workspace can read. The most recently created chats come first.
+pub async fn select_chats_for_user<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<Vec<ChatInfo>, AppError> {
+  let rows: Vec<(Uuid, String, DateTime<Utc>, Option<i32>, i64, Option<i64>)> = sqlx::query_as(
+    r#"
+      SELECT c.chat_id, c.name, c.created_at, me.role,
+             (SELECT COUNT(*) FROM af_chat_participant WHERE chat_id = c.chat_id),
+             c.created_by
+      FROM af_chat c
+        LEFT JOIN af_chat_participant me ON me.chat_id = c.chat_id AND me.uid = $2
+      WHERE c.workspace_id = $1
+        AND c.deleted_at IS NULL
+        AND (
+          me.uid IS NOT NULL
+          OR NOT EXISTS(SELECT 1 FROM af_chat_participant WHERE chat_id = c.chat_id)
+        )
+      ORDER BY c.created_at DESC
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .fetch_all(executor)
+  .await?;
+
+  Ok(
+    rows
+      .into_iter()
+      .map(
+        |(chat_id, name, created_at, role, participant_count, created_by)| ChatInfo {
+          chat_id: chat_id.to_string(),
+          name,
+          created_at,
+          role: role
+            .map(ChatParticipantRole::from)
+            .unwrap_or_else(|| legacy_chat_role(uid, created_by)),
+          participant_count,
+        },
+      )
+      .collect(),
+  )
+}
+
+/// The role of the user in a chat without participants: its creator owns it, the other members of
+/// the workspace can only read it.
+pub fn legacy_chat_role(uid: i64, created_by: Option<i64>) -> ChatParticipantRole {
+  if created_by == Some(uid) {
+    ChatParticipantRole::Owner
+  } else {
+    ChatParticipantRole::Viewer
+  }
+}
@@ -1 +1,3 @@
+pub mod chat_listener;
 pub mod chat_ops;
+pub mod chat_participant_ops;
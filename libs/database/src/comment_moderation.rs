@@ -0,0 +1,305 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+use uuid::Uuid;
+
+use crate::pg_row::{
+  AFPublishedViewCommentPolicyRow, AFWorkspaceCommentBanRow, AFWorkspaceCommentModerationRow,
+};
+
+/// Settings of the view and moderation rules of its workspace that apply to the user. The
+/// moderators of a view are its publisher and the owners of the workspace. Use [Uuid::nil] for a
+/// user that is not logged in.
+pub async fn select_published_view_comment_policy<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  view_id: &Uuid,
+  user_uuid: &Uuid,
+) -> Result<AFPublishedViewCommentPolicyRow, AppError> {
+  let policy = sqlx::query_as(
+    r#"
+      WITH view AS (
+        SELECT workspace_id, published_by
+        FROM af_published_collab
+        WHERE view_id = $1
+        LIMIT 1
+      ), commenter AS (
+        SELECT uid FROM af_user WHERE uuid = $2
+      )
+      SELECT
+        (SELECT workspace_id FROM view) AS workspace_id,
+        COALESCE(s.comments_enabled, TRUE) AS comments_enabled,
+        COALESCE(s.require_approval, FALSE) AS require_approval,
+        COALESCE(m.blocked_keywords, '{}') AS blocked_keywords,
+        m.max_comments_per_hour,
+        EXISTS(
+          SELECT 1
+          FROM af_workspace_comment_ban b, view, commenter
+          WHERE b.workspace_id = view.workspace_id AND b.uid = commenter.uid
+        ) AS is_banned,
+        (
+          EXISTS(SELECT 1 FROM view, commenter WHERE view.published_by = commenter.uid)
+          OR EXISTS(
+            SELECT 1
+            FROM af_workspace_member wm
+            JOIN af_roles ON wm.role_id = af_roles.id, view, commenter
+            WHERE wm.workspace_id = view.workspace_id
+              AND wm.uid = commenter.uid
+              AND af_roles.name = 'Owner'
+          )
+        ) AS is_moderator
+      FROM (SELECT 1) AS one
+      LEFT JOIN af_published_view_comment_settings s ON s.view_id = $1
+      LEFT JOIN af_workspace_comment_moderation m
+        ON m.workspace_id = (SELECT workspace_id FROM view)
+    "#,
+  )
+  .bind(view_id)
+  .bind(user_uuid)
+  .fetch_one(executor)
+  .await?;
+  Ok(policy)
+}
+
+pub async fn upsert_published_view_comment_settings<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  view_id: &Uuid,
+  comments_enabled: bool,
+  require_approval: bool,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_published_view_comment_settings (view_id, comments_enabled, require_approval)
+      VALUES ($1, $2, $3)
+      ON CONFLICT (view_id) DO UPDATE
+      SET comments_enabled = EXCLUDED.comments_enabled,
+          require_approval = EXCLUDED.require_approval,
+          updated_at = NOW()
+    "#,
+  )
+  .bind(view_id)
+  .bind(comments_enabled)
+  .bind(require_approval)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+pub async fn select_workspace_comment_moderation<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+) -> Result<Option<AFWorkspaceCommentModerationRow>, AppError> {
+  let moderation = sqlx::query_as(
+    r#"
+      SELECT blocked_keywords, max_comments_per_hour
+      FROM af_workspace_comment_moderation
+      WHERE workspace_id = $1
+    "#,
+  )
+  .bind(workspace_id)
+  .fetch_optional(executor)
+  .await?;
+  Ok(moderation)
+}
+
+pub async fn upsert_workspace_comment_moderation<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  blocked_keywords: &[String],
+  max_comments_per_hour: Option<i32>,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_workspace_comment_moderation
+        (workspace_id, blocked_keywords, max_comments_per_hour)
+      VALUES ($1, $2, $3)
+      ON CONFLICT (workspace_id) DO UPDATE
+      SET blocked_keywords = EXCLUDED.blocked_keywords,
+          max_comments_per_hour = EXCLUDED.max_comments_per_hour,
+          updated_at = NOW()
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(blocked_keywords)
+  .bind(max_comments_per_hour)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Returns false if there is no user with the given uuid.
+pub async fn upsert_workspace_comment_ban<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  user_uuid: &Uuid,
+  reason: Option<&str>,
+  banned_by: i64,
+) -> Result<bool, AppError> {
+  let res = sqlx::query(
+    r#"
+      INSERT INTO af_workspace_comment_ban (workspace_id, uid, reason, banned_by)
+      SELECT $1, uid, $3, $4 FROM af_user WHERE uuid = $2
+      ON CONFLICT (workspace_id, uid) DO UPDATE
+      SET reason = EXCLUDED.reason,
+          banned_by = EXCLUDED.banned_by
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(user_uuid)
+  .bind(reason)
+  .bind(banned_by)
+  .execute(executor)
+  .await?;
+  Ok(res.rows_affected() > 0)
+}
+
+pub async fn delete_workspace_comment_ban<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  user_uuid: &Uuid,
+) -> Result<bool, AppError> {
+  let res = sqlx::query(
+    r#"
+      DELETE FROM af_workspace_comment_ban
+      WHERE workspace_id = $1
+        AND uid = (SELECT uid FROM af_user WHERE uuid = $2)
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(user_uuid)
+  .execute(executor)
+  .await?;
+  Ok(res.rows_affected() > 0)
+}
+
+pub async fn select_workspace_comment_bans<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+) -> Result<Vec<AFWorkspaceCommentBanRow>, AppError> {
+  let bans = sqlx::query_as(
+    r#"
+      SELECT
+        au.uuid,
+        au.name,
+        au.metadata ->> 'icon_url' AS avatar_url,
+        b.reason,
+        b.created_at
+      FROM af_workspace_comment_ban b
+      JOIN af_user au ON au.uid = b.uid
+      WHERE b.workspace_id = $1
+      ORDER BY b.created_at DESC
+    "#,
+  )
+  .bind(workspace_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(bans)
+}
+
+/// Marks the comments of the user on the published views of the workspace as deleted.
+pub async fn update_user_comments_deletion_status<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  user_uuid: &Uuid,
+) -> Result<u64, AppError> {
+  let res = sqlx::query(
+    r#"
+      UPDATE af_published_view_comment
+      SET is_deleted = TRUE
+      WHERE created_by = (SELECT uid FROM af_user WHERE uuid = $2)
+        AND view_id IN (SELECT view_id FROM af_published_collab WHERE workspace_id = $1)
+        AND NOT is_deleted
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(user_uuid)
+  .execute(executor)
+  .await?;
+  Ok(res.rows_affected())
+}
+
+/// Number of comments written by the user on the published views of the workspace since the given
+/// time.
+pub async fn select_user_comment_count_since<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  user_uuid: &Uuid,
+  since: DateTime<Utc>,
+) -> Result<i64, AppError> {
+  let count = sqlx::query_scalar(
+    r#"
+      SELECT COUNT(*)
+      FROM af_published_view_comment
+      WHERE created_by = (SELECT uid FROM af_user WHERE uuid = $2)
+        AND created_at > $3
+        AND view_id IN (SELECT view_id FROM af_published_collab WHERE workspace_id = $1)
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(user_uuid)
+  .bind(since)
+  .fetch_one(executor)
+  .await?;
+  Ok(count)
+}
+
+/// Same as [crate::workspace::insert_comment_to_published_view] for a comment that must be approved
+/// before being shown.
+pub async fn insert_pending_comment_to_published_view<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  view_id: &Uuid,
+  user_uuid: &Uuid,
+  content: &str,
+  reply_comment_id: &Option<Uuid>,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_published_view_comment
+        (view_id, created_by, content, reply_comment_id, is_approved)
+      VALUES ($1, (SELECT uid FROM af_user WHERE uuid = $2), $3, $4, FALSE)
+    "#,
+  )
+  .bind(view_id)
+  .bind(user_uuid)
+  .bind(content)
+  .bind(reply_comment_id)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+pub async fn select_pending_comment_ids<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  view_id: &Uuid,
+) -> Result<Vec<Uuid>, AppError> {
+  let comment_ids = sqlx::query_scalar(
+    r#"
+      SELECT comment_id
+      FROM af_published_view_comment
+      WHERE view_id = $1 AND NOT is_approved
+    "#,
+  )
+  .bind(view_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(comment_ids)
+}
+
+/// Returns false if the view has no such comment.
+pub async fn update_comment_approval_status<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  view_id: &Uuid,
+  comment_id: &Uuid,
+) -> Result<bool, AppError> {
+  let res = sqlx::query(
+    r#"
+      UPDATE af_published_view_comment
+      SET is_approved = TRUE
+      WHERE view_id = $1 AND comment_id = $2
+    "#,
+  )
+  .bind(view_id)
+  .bind(comment_id)
+  .execute(executor)
+  .await?;
+  Ok(res.rows_affected() > 0)
+}
@@ -0,0 +1,330 @@
+use app_error::AppError;
+use sqlx::{Executor, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::pg_row::{AFDocumentCommentRow, AFDocumentCommentThreadRow};
+
+const THREAD_COLUMNS: &str = r#"
+  thread_id, workspace_id, view_id, block_id, range_start, range_end, quoted_text, created_by,
+  created_at, resolved_by, resolved_at
+"#;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_document_comment_thread(
+  txn: &mut Transaction<'_, Postgres>,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  block_id: &str,
+  range_start: Option<i32>,
+  range_end: Option<i32>,
+  quoted_text: Option<&str>,
+  uid: i64,
+) -> Result<AFDocumentCommentThreadRow, AppError> {
+  let thread = sqlx::query_as(&format!(
+    r#"
+      INSERT INTO af_document_comment_thread
+        (workspace_id, view_id, block_id, range_start, range_end, quoted_text, created_by)
+      VALUES ($1, $2, $3, $4, $5, $6, $7)
+      RETURNING {}
+    "#,
+    THREAD_COLUMNS
+  ))
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(block_id)
+  .bind(range_start)
+  .bind(range_end)
+  .bind(quoted_text)
+  .bind(uid)
+  .fetch_one(txn.as_mut())
+  .await?;
+  Ok(thread)
+}
+
+/// Inserts the comment and its mentions, returns the id of the comment.
+pub async fn insert_document_comment(
+  txn: &mut Transaction<'_, Postgres>,
+  thread_id: &Uuid,
+  uid: i64,
+  content: &str,
+  mentions: &[i64],
+) -> Result<Uuid, AppError> {
+  let comment_id = sqlx::query_scalar(
+    r#"
+      INSERT INTO af_document_comment (thread_id, created_by, content)
+      VALUES ($1, $2, $3)
+      RETURNING comment_id
+    "#,
+  )
+  .bind(thread_id)
+  .bind(uid)
+  .bind(content)
+  .fetch_one(txn.as_mut())
+  .await?;
+  insert_document_comment_mentions(txn, &comment_id, mentions).await?;
+  Ok(comment_id)
+}
+
+async fn insert_document_comment_mentions(
+  txn: &mut Transaction<'_, Postgres>,
+  comment_id: &Uuid,
+  mentions: &[i64],
+) -> Result<(), AppError> {
+  if mentions.is_empty() {
+    return Ok(());
+  }
+  sqlx::query(
+    r#"
+      INSERT INTO af_document_comment_mention (comment_id, uid)
+      SELECT $1, mentioned.uid FROM UNNEST($2::BIGINT[]) AS mentioned(uid)
+      ON CONFLICT DO NOTHING
+    "#,
+  )
+  .bind(comment_id)
+  .bind(mentions)
+  .execute(txn.as_mut())
+  .await?;
+  Ok(())
+}
+
+/// Replaces the content and the mentions of the comment.
+pub async fn update_document_comment_content(
+  txn: &mut Transaction<'_, Postgres>,
+  comment_id: &Uuid,
+  content: &str,
+  mentions: &[i64],
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      UPDATE af_document_comment
+      SET content = $2, updated_at = NOW()
+      WHERE comment_id = $1
+    "#,
+  )
+  .bind(comment_id)
+  .bind(content)
+  .execute(txn.as_mut())
+  .await?;
+  delete_document_comment_mentions(txn, comment_id).await?;
+  insert_document_comment_mentions(txn, comment_id, mentions).await
+}
+
+async fn delete_document_comment_mentions(
+  txn: &mut Transaction<'_, Postgres>,
+  comment_id: &Uuid,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      DELETE FROM af_document_comment_mention
+      WHERE comment_id = $1
+    "#,
+  )
+  .bind(comment_id)
+  .execute(txn.as_mut())
+  .await?;
+  Ok(())
+}
+
+/// The content and the mentions of a deleted comment are removed, the comment itself is kept so
+/// the replies after it keep their place in the thread.
+pub async fn update_document_comment_deletion_status(
+  txn: &mut Transaction<'_, Postgres>,
+  comment_id: &Uuid,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      UPDATE af_document_comment
+      SET is_deleted = TRUE, content = '', updated_at = NOW()
+      WHERE comment_id = $1
+    "#,
+  )
+  .bind(comment_id)
+  .execute(txn.as_mut())
+  .await?;
+  delete_document_comment_mentions(txn, comment_id).await
+}
+
+/// Resolves the thread when `resolved_by` is set, reopens it otherwise.
+pub async fn update_document_comment_thread_resolution<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  thread_id: &Uuid,
+  resolved_by: Option<i64>,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      UPDATE af_document_comment_thread
+      SET resolved_by = $2,
+          resolved_at = CASE
+                          WHEN $2::BIGINT IS NULL THEN NULL
+                          ELSE COALESCE(resolved_at, NOW())
+                        END
+      WHERE thread_id = $1
+    "#,
+  )
+  .bind(thread_id)
+  .bind(resolved_by)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Threads of the view, oldest first.
+pub async fn select_document_comment_threads<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  include_resolved: bool,
+) -> Result<Vec<AFDocumentCommentThreadRow>, AppError> {
+  let threads = sqlx::query_as(&format!(
+    r#"
+      SELECT {}
+      FROM af_document_comment_thread
+      WHERE workspace_id = $1
+        AND view_id = $2
+        AND ($3 OR resolved_at IS NULL)
+      ORDER BY created_at, thread_id
+    "#,
+    THREAD_COLUMNS
+  ))
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(include_resolved)
+  .fetch_all(executor)
+  .await?;
+  Ok(threads)
+}
+
+pub async fn select_document_comment_thread<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  thread_id: &Uuid,
+) -> Result<Option<AFDocumentCommentThreadRow>, AppError> {
+  let thread = sqlx::query_as(&format!(
+    r#"
+      SELECT {}
+      FROM af_document_comment_thread
+      WHERE thread_id = $1
+    "#,
+    THREAD_COLUMNS
+  ))
+  .bind(thread_id)
+  .fetch_optional(executor)
+  .await?;
+  Ok(thread)
+}
+
+const COMMENT_QUERY: &str = r#"
+  SELECT
+    c.comment_id,
+    c.thread_id,
+    c.content,
+    c.created_by,
+    au.uuid AS author_uuid,
+    au.name AS author_name,
+    au.metadata ->> 'icon_url' AS author_avatar_url,
+    c.created_at,
+    c.updated_at,
+    c.is_deleted,
+    ARRAY(
+      SELECT m.uid FROM af_document_comment_mention m WHERE m.comment_id = c.comment_id
+    ) AS mentions
+  FROM af_document_comment c
+  LEFT JOIN af_user au ON au.uid = c.created_by
+"#;
+
+/// Comments of the threads, oldest first.
+pub async fn select_document_comments<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  thread_ids: &[Uuid],
+) -> Result<Vec<AFDocumentCommentRow>, AppError> {
+  let comments = sqlx::query_as(&format!(
+    r#"
+      {}
+      WHERE c.thread_id = ANY($1)
+      ORDER BY c.created_at, c.comment_id
+    "#,
+    COMMENT_QUERY
+  ))
+  .bind(thread_ids)
+  .fetch_all(executor)
+  .await?;
+  Ok(comments)
+}
+
+pub async fn select_document_comment<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  comment_id: &Uuid,
+) -> Result<Option<AFDocumentCommentRow>, AppError> {
+  let comment = sqlx::query_as(&format!(
+    r#"
+      {}
+      WHERE c.comment_id = $1
+    "#,
+    COMMENT_QUERY
+  ))
+  .bind(comment_id)
+  .fetch_optional(executor)
+  .await?;
+  Ok(comment)
+}
+
+/// Returns the uids that are not members of the workspace.
+pub async fn select_non_member_uids<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uids: &[i64],
+) -> Result<Vec<i64>, AppError> {
+  let non_member_uids = sqlx::query_scalar(
+    r#"
+      SELECT mentioned.uid FROM UNNEST($2::BIGINT[]) AS mentioned(uid)
+      WHERE NOT EXISTS (
+        SELECT 1 FROM af_workspace_member wm
+        WHERE wm.workspace_id = $1 AND wm.uid = mentioned.uid
+      )
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uids)
+  .fetch_all(executor)
+  .await?;
+  Ok(non_member_uids)
+}
+
+/// All the members of the workspace can read the comments of its documents.
+pub async fn select_document_comment_recipient_uids<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+) -> Result<Vec<i64>, AppError> {
+  let uids = sqlx::query_scalar(
+    r#"
+      SELECT uid FROM af_workspace_member
+      WHERE workspace_id = $1
+    "#,
+  )
+  .bind(workspace_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(uids)
+}
+
+/// The creator of the thread and the authors of its comments that were not deleted.
+pub async fn select_document_comment_thread_participant_uids<
+  'a,
+  E: Executor<'a, Database = Postgres>,
+>(
+  executor: E,
+  thread_id: &Uuid,
+) -> Result<Vec<i64>, AppError> {
+  let uids = sqlx::query_scalar(
+    r#"
+      SELECT created_by FROM af_document_comment_thread
+      WHERE thread_id = $1 AND created_by IS NOT NULL
+      UNION
+      SELECT created_by FROM af_document_comment
+      WHERE thread_id = $1 AND NOT is_deleted AND created_by IS NOT NULL
+    "#,
+  )
+  .bind(thread_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(uids)
+}
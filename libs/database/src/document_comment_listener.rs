@@ -0,0 +1,102 @@
+use crate::document_comment::{
+  select_document_comment, select_document_comment_recipient_uids, select_document_comment_thread,
+};
+use crate::listener::PostgresDBListener;
+use crate::pg_row::AFDocumentCommentNotification;
+use anyhow::Error;
+use app_error::AppError;
+use collab_rt_entity::user::AFDocumentCommentChange;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::{error, trace};
+
+/// A change of the comments of a document and the uids of the users who should receive it.
+#[derive(Debug, Clone)]
+pub struct DocumentCommentEvent {
+  pub recipients: Vec<i64>,
+  pub change: AFDocumentCommentChange,
+}
+
+/// Listens to the notifications of the `af_document_comment_change_trigger` and
+/// `af_document_comment_thread_change_trigger`. The changes are sent to all the members of the
+/// workspace of the document.
+pub struct DocumentCommentListener {
+  pub notify: broadcast::Sender<Arc<DocumentCommentEvent>>,
+}
+
+impl DocumentCommentListener {
+  pub async fn new(pg_pool: &PgPool, channel: &str) -> Result<Self, Error> {
+    let listener =
+      PostgresDBListener::<AFDocumentCommentNotification>::new(pg_pool, channel).await?;
+    let mut notifications = listener.notify.subscribe();
+    let (tx, _) = broadcast::channel(1000);
+    let notify = tx.clone();
+    let pg_pool = pg_pool.clone();
+    tokio::spawn(async move {
+      loop {
+        let notification = match notifications.recv().await {
+          Ok(notification) => notification,
+          Err(broadcast::error::RecvError::Lagged(count)) => {
+            error!(
+              "Document comment listener lagged, {} notifications dropped",
+              count
+            );
+            continue;
+          },
+          Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        // Nobody is connected, skip the queries.
+        if tx.receiver_count() == 0 {
+          continue;
+        }
+        match load_document_comment_event(&pg_pool, &notification).await {
+          Ok(Some(event)) => {
+            trace!("Receive document comment change: {:?}", event);
+            let _ = tx.send(Arc::new(event));
+          },
+          // The thread was deleted with its workspace in the meantime.
+          Ok(None) => {},
+          Err(err) => error!(
+            "Failed to load document comment thread {}: {}",
+            notification.thread_id, err
+          ),
+        }
+      }
+    });
+    Ok(Self { notify })
+  }
+}
+
+async fn load_document_comment_event(
+  pg_pool: &PgPool,
+  notification: &AFDocumentCommentNotification,
+) -> Result<Option<DocumentCommentEvent>, AppError> {
+  let thread = match select_document_comment_thread(pg_pool, &notification.thread_id).await? {
+    Some(thread) => thread,
+    None => return Ok(None),
+  };
+  let comment = match &notification.comment_id {
+    Some(comment_id) => select_document_comment(pg_pool, comment_id).await?,
+    None => None,
+  };
+  let recipients = select_document_comment_recipient_uids(pg_pool, &thread.workspace_id).await?;
+  Ok(Some(DocumentCommentEvent {
+    recipients,
+    change: AFDocumentCommentChange {
+      workspace_id: thread.workspace_id.to_string(),
+      view_id: thread.view_id.to_string(),
+      thread_id: thread.thread_id.to_string(),
+      block_id: thread.block_id,
+      comment_id: notification
+        .comment_id
+        .map(|comment_id| comment_id.to_string()),
+      action: notification.action,
+      author_uid: comment.as_ref().and_then(|comment| comment.created_by),
+      content: comment.as_ref().map(|comment| comment.content.clone()),
+      mentions: comment.map(|comment| comment.mentions).unwrap_or_default(),
+      is_resolved: thread.resolved_at.is_some(),
+    },
+  }))
+}
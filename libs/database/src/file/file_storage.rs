@@ -1,3 +1,4 @@
+use crate::index::delete_attachment_embeddings;
 use crate::pg_row::AFBlobMetadataRow;
 use crate::resource_usage::{
   delete_blob_metadata, get_blob_metadata, insert_blob_metadata, is_blob_metadata_exists,
@@ -10,6 +11,7 @@ use database_entity::file_dto::{
   UploadPartResponse,
 };
 use sqlx::PgPool;
+use std::ops::DerefMut;
 
 use tracing::{info, instrument, warn};
 use uuid::Uuid;
@@ -128,6 +130,7 @@ where
 
     let mut tx = self.pg_pool.begin().await?;
     delete_blob_metadata(&mut tx, key.workspace_id(), &key.meta_key()).await?;
+    delete_attachment_embeddings(tx.deref_mut(), &key.meta_key()).await?;
     tx.commit().await?;
     Ok(())
   }
@@ -0,0 +1,353 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use database_entity::dto::AFAccessLevel;
+use shared_entity::dto::group_dto::{CollabGroupMember, WorkspaceGroup, WorkspaceGroupMember};
+use sqlx::{Executor, FromRow, PgPool, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug, FromRow)]
+struct AFWorkspaceGroupRow {
+  group_id: Uuid,
+  workspace_id: Uuid,
+  name: String,
+  member_count: i64,
+  created_at: DateTime<Utc>,
+}
+
+impl From<AFWorkspaceGroupRow> for WorkspaceGroup {
+  fn from(row: AFWorkspaceGroupRow) -> Self {
+    WorkspaceGroup {
+      group_id: row.group_id,
+      workspace_id: row.workspace_id,
+      name: row.name,
+      member_count: row.member_count,
+      created_at: row.created_at,
+    }
+  }
+}
+
+#[derive(Debug, FromRow)]
+struct AFWorkspaceGroupMemberRow {
+  uid: i64,
+  email: String,
+  name: String,
+  created_at: DateTime<Utc>,
+}
+
+impl From<AFWorkspaceGroupMemberRow> for WorkspaceGroupMember {
+  fn from(row: AFWorkspaceGroupMemberRow) -> Self {
+    WorkspaceGroupMember {
+      uid: row.uid,
+      email: row.email,
+      name: row.name,
+      created_at: row.created_at,
+    }
+  }
+}
+
+#[derive(Debug, FromRow)]
+struct AFCollabGroupMemberRow {
+  group_id: Uuid,
+  name: String,
+  access_level: i32,
+  created_at: DateTime<Utc>,
+}
+
+impl From<AFCollabGroupMemberRow> for CollabGroupMember {
+  fn from(row: AFCollabGroupMemberRow) -> Self {
+    CollabGroupMember {
+      group_id: row.group_id,
+      name: row.name,
+      access_level: AFAccessLevel::from(row.access_level),
+      created_at: row.created_at,
+    }
+  }
+}
+
+/// A member of a group, used to load the grouping policies of the access control.
+#[derive(Debug, FromRow)]
+pub struct AFGroupMembershipRow {
+  pub uid: i64,
+  pub group_id: Uuid,
+}
+
+/// The access level of a group on a collab, used to load the access control policies.
+#[derive(Debug, FromRow)]
+pub struct AFCollabGroupAccessLevelRow {
+  pub group_id: Uuid,
+  pub oid: String,
+  pub access_level: i32,
+}
+
+/// Returns `None` if the workspace already has a group with this name.
+pub async fn insert_workspace_group<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  name: &str,
+  created_by: i64,
+) -> Result<Option<WorkspaceGroup>, AppError> {
+  let row: Option<AFWorkspaceGroupRow> = sqlx::query_as(
+    r#"
+      INSERT INTO af_workspace_group (workspace_id, name, created_by)
+      VALUES ($1, $2, $3)
+      ON CONFLICT (workspace_id, name) DO NOTHING
+      RETURNING group_id, workspace_id, name, 0::BIGINT AS member_count, created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(name)
+  .bind(created_by)
+  .fetch_optional(executor)
+  .await?;
+  Ok(row.map(WorkspaceGroup::from))
+}
+
+pub async fn select_workspace_groups<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+) -> Result<Vec<WorkspaceGroup>, AppError> {
+  let rows: Vec<AFWorkspaceGroupRow> = sqlx::query_as(
+    r#"
+      SELECT g.group_id, g.workspace_id, g.name,
+        (SELECT COUNT(*) FROM af_workspace_group_member m WHERE m.group_id = g.group_id)
+          AS member_count,
+        g.created_at
+      FROM af_workspace_group g
+      WHERE g.workspace_id = $1
+      ORDER BY g.created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(WorkspaceGroup::from).collect())
+}
+
+pub async fn select_workspace_group<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+) -> Result<Option<WorkspaceGroup>, AppError> {
+  let row: Option<AFWorkspaceGroupRow> = sqlx::query_as(
+    r#"
+      SELECT g.group_id, g.workspace_id, g.name,
+        (SELECT COUNT(*) FROM af_workspace_group_member m WHERE m.group_id = g.group_id)
+          AS member_count,
+        g.created_at
+      FROM af_workspace_group g
+      WHERE g.workspace_id = $1 AND g.group_id = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(group_id)
+  .fetch_optional(executor)
+  .await?;
+  Ok(row.map(WorkspaceGroup::from))
+}
+
+/// Returns `None` if the workspace has no such group.
+pub async fn update_workspace_group_name<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+  name: &str,
+) -> Result<Option<WorkspaceGroup>, AppError> {
+  let row: Option<AFWorkspaceGroupRow> = sqlx::query_as(
+    r#"
+      UPDATE af_workspace_group g
+      SET name = $3
+      WHERE g.workspace_id = $1 AND g.group_id = $2
+      RETURNING g.group_id, g.workspace_id, g.name,
+        (SELECT COUNT(*) FROM af_workspace_group_member m WHERE m.group_id = g.group_id)
+          AS member_count,
+        g.created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(group_id)
+  .bind(name)
+  .fetch_optional(executor)
+  .await?;
+  Ok(row.map(WorkspaceGroup::from))
+}
+
+/// Deletes the group along with its memberships and grants. Returns false if the workspace has no
+/// such group.
+pub async fn delete_workspace_group<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+) -> Result<bool, AppError> {
+  let result = sqlx::query(
+    r#"
+      DELETE FROM af_workspace_group
+      WHERE workspace_id = $1 AND group_id = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(group_id)
+  .execute(executor)
+  .await?;
+  Ok(result.rows_affected() > 0)
+}
+
+/// Adds the users to the group, ignoring the ones that are already members.
+pub async fn insert_workspace_group_members<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  group_id: &Uuid,
+  uids: &[i64],
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_workspace_group_member (group_id, uid)
+      SELECT $1, uid FROM UNNEST($2::BIGINT[]) AS t(uid)
+      ON CONFLICT (group_id, uid) DO NOTHING
+    "#,
+  )
+  .bind(group_id)
+  .bind(uids)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Returns the users that were members of the group.
+pub async fn delete_workspace_group_members<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  group_id: &Uuid,
+  uids: &[i64],
+) -> Result<Vec<i64>, AppError> {
+  let uids = sqlx::query_scalar(
+    r#"
+      DELETE FROM af_workspace_group_member
+      WHERE group_id = $1 AND uid = ANY($2)
+      RETURNING uid
+    "#,
+  )
+  .bind(group_id)
+  .bind(uids)
+  .fetch_all(executor)
+  .await?;
+  Ok(uids)
+}
+
+pub async fn select_workspace_group_members<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  group_id: &Uuid,
+) -> Result<Vec<WorkspaceGroupMember>, AppError> {
+  let rows: Vec<AFWorkspaceGroupMemberRow> = sqlx::query_as(
+    r#"
+      SELECT m.uid, u.email, u.name, m.created_at
+      FROM af_workspace_group_member m
+        JOIN af_user u ON u.uid = m.uid
+      WHERE m.group_id = $1
+      ORDER BY m.created_at
+    "#,
+  )
+  .bind(group_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(WorkspaceGroupMember::from).collect())
+}
+
+/// Removes a user that is no longer a member of the workspace from its groups, and returns them.
+pub async fn delete_workspace_group_memberships_of_user(
+  txn: &mut sqlx::Transaction<'_, Postgres>,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<Vec<Uuid>, AppError> {
+  let group_ids = sqlx::query_scalar(
+    r#"
+      DELETE FROM af_workspace_group_member m
+      USING af_workspace_group g
+      WHERE g.group_id = m.group_id AND g.workspace_id = $1 AND m.uid = $2
+      RETURNING m.group_id
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .fetch_all(txn.as_mut())
+  .await?;
+  Ok(group_ids)
+}
+
+/// Grants the access level to the group, replacing its previous access level on the collab.
+pub async fn upsert_collab_group_member<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  group_id: &Uuid,
+  oid: &str,
+  access_level: AFAccessLevel,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_collab_group_member (group_id, oid, access_level)
+      VALUES ($1, $2, $3)
+      ON CONFLICT (group_id, oid)
+      DO UPDATE SET access_level = EXCLUDED.access_level
+    "#,
+  )
+  .bind(group_id)
+  .bind(oid)
+  .bind(access_level as i32)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Returns false if the group is not a member of the collab.
+pub async fn delete_collab_group_member<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  group_id: &Uuid,
+  oid: &str,
+) -> Result<bool, AppError> {
+  let result = sqlx::query(
+    r#"
+      DELETE FROM af_collab_group_member
+      WHERE group_id = $1 AND oid = $2
+    "#,
+  )
+  .bind(group_id)
+  .bind(oid)
+  .execute(executor)
+  .await?;
+  Ok(result.rows_affected() > 0)
+}
+
+pub async fn select_collab_group_members<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  oid: &str,
+) -> Result<Vec<CollabGroupMember>, AppError> {
+  let rows: Vec<AFCollabGroupMemberRow> = sqlx::query_as(
+    r#"
+      SELECT c.group_id, g.name, c.access_level, c.created_at
+      FROM af_collab_group_member c
+        JOIN af_workspace_group g ON g.group_id = c.group_id
+      WHERE g.workspace_id = $1 AND c.oid = $2
+      ORDER BY c.created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(oid)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(CollabGroupMember::from).collect())
+}
+
+/// Used to load the grouping policies of the access control.
+pub async fn select_all_workspace_group_members(
+  pg_pool: &PgPool,
+) -> Result<Vec<AFGroupMembershipRow>, sqlx::Error> {
+  sqlx::query_as("SELECT uid, group_id FROM af_workspace_group_member")
+    .fetch_all(pg_pool)
+    .await
+}
+
+/// Used to load the access control policies of the collabs shared with groups.
+pub async fn select_all_collab_group_members(
+  pg_pool: &PgPool,
+) -> Result<Vec<AFCollabGroupAccessLevelRow>, sqlx::Error> {
+  sqlx::query_as("SELECT group_id, oid, access_level FROM af_collab_group_member")
+    .fetch_all(pg_pool)
+    .await
+}
@@ -110,6 +110,53 @@ pub async fn upsert_collab_embeddings(
   Ok(())
 }
 
+/// Stores embeddings of the text extracted from an attachment file. Fragments are kept next to the
+/// document that owns the attachment, so they show up in the same search results. Previously indexed
+/// fragments of the same file are replaced.
+pub async fn upsert_attachment_embeddings(
+  tx: &mut Transaction<'_, sqlx::Postgres>,
+  workspace_id: &Uuid,
+  file_id: &str,
+  tokens_used: u32,
+  records: Vec<AFCollabEmbeddingParams>,
+) -> Result<(), sqlx::Error> {
+  if records.is_empty() {
+    return Ok(());
+  }
+  let object_id = records[0].object_id.clone();
+  let collab_type = records[0].collab_type.clone();
+
+  let fragments = records.into_iter().map(Fragment::from).collect::<Vec<_>>();
+
+  sqlx::query(r#"CALL af_attachment_embeddings_upsert($1, $2, $3, $4, $5, $6::af_fragment[])"#)
+    .bind(*workspace_id)
+    .bind(object_id)
+    .bind(crate::collab::partition_key_from_collab_type(&collab_type))
+    .bind(file_id)
+    .bind(tokens_used as i32)
+    .bind(fragments)
+    .execute(tx.deref_mut())
+    .await?;
+  Ok(())
+}
+
+/// Removes all embeddings extracted from given attachment file.
+pub async fn delete_attachment_embeddings<'a, E>(
+  executor: E,
+  file_id: &str,
+) -> Result<(), sqlx::Error>
+where
+  E: Executor<'a, Database = Postgres>,
+{
+  sqlx::query!(
+    r#"DELETE FROM af_collab_embeddings WHERE file_id = $1"#,
+    file_id
+  )
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
 pub async fn get_collabs_without_embeddings<'a, E>(
   executor: E,
 ) -> Result<Vec<CollabId>, sqlx::Error>
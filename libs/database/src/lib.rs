@@ -1,13 +1,31 @@
 pub mod access_request;
+pub mod access_request_listener;
+pub mod activity_digest;
+pub mod api_token;
+pub mod audit_log;
 pub mod chat;
 pub mod collab;
+pub mod comment_moderation;
+pub mod document_comment;
+pub mod document_comment_listener;
 pub mod file;
+pub mod group;
 pub mod history;
 pub mod index;
 pub mod listener;
+pub mod notification;
+pub mod oauth;
 pub mod pg_row;
 pub mod publish;
+pub mod publish_analytics;
+pub mod reminder;
 pub mod resource_usage;
+pub mod role;
+pub mod scim;
+pub mod share_link;
 pub mod template;
 pub mod user;
+pub mod view_permission;
+pub mod webhook;
 pub mod workspace;
+pub mod workspace_activity;
@@ -0,0 +1,418 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use shared_entity::dto::notification_dto::NotificationKind;
+use sqlx::{Executor, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::pg_row::{AFNotificationDigestRecipientRow, AFNotificationMuteRow, AFNotificationRow};
+
+/// Creates a notification for each recipient, except the actor and the recipients that muted the
+/// workspace or the page. Returns the uids of the notified recipients.
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_notifications<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  recipients: &[i64],
+  workspace_id: &Uuid,
+  kind: NotificationKind,
+  view_id: Option<&Uuid>,
+  object_id: Option<&str>,
+  actor_uid: Option<i64>,
+  payload: &serde_json::Value,
+) -> Result<Vec<i64>, AppError> {
+  if recipients.is_empty() {
+    return Ok(vec![]);
+  }
+  let uids = sqlx::query_scalar(
+    r#"
+      INSERT INTO af_notification
+        (uid, workspace_id, kind, view_id, object_id, actor_uid, payload)
+      SELECT DISTINCT recipient.uid, $2, $3, $4, $5, $6, $7
+      FROM UNNEST($1::BIGINT[]) AS recipient(uid)
+      WHERE recipient.uid IS DISTINCT FROM $6
+        AND NOT EXISTS (
+          SELECT 1 FROM af_notification_mute m
+          WHERE m.uid = recipient.uid
+            AND m.workspace_id = $2
+            AND (m.view_id IS NULL OR m.view_id = $4)
+            AND (m.muted_until IS NULL OR m.muted_until > NOW())
+        )
+      RETURNING uid
+    "#,
+  )
+  .bind(recipients)
+  .bind(workspace_id)
+  .bind(kind as i16)
+  .bind(view_id)
+  .bind(object_id)
+  .bind(actor_uid)
+  .bind(payload)
+  .fetch_all(executor)
+  .await?;
+  Ok(uids)
+}
+
+/// Notifications of the user, newest first.
+pub async fn select_notifications<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+  workspace_id: Option<&Uuid>,
+  unread_only: bool,
+  before: Option<DateTime<Utc>>,
+  limit: i64,
+) -> Result<Vec<AFNotificationRow>, AppError> {
+  let notifications = sqlx::query_as(
+    r#"
+      SELECT
+        notification_id, uid, workspace_id, kind, view_id, object_id, actor_uid, payload,
+        created_at, read_at
+      FROM af_notification
+      WHERE uid = $1
+        AND ($2::UUID IS NULL OR workspace_id = $2)
+        AND (NOT $3 OR read_at IS NULL)
+        AND ($4::TIMESTAMPTZ IS NULL OR created_at < $4)
+      ORDER BY created_at DESC
+      LIMIT $5
+    "#,
+  )
+  .bind(uid)
+  .bind(workspace_id)
+  .bind(unread_only)
+  .bind(before)
+  .bind(limit)
+  .fetch_all(executor)
+  .await?;
+  Ok(notifications)
+}
+
+pub async fn select_unread_notification_count<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+  workspace_id: Option<&Uuid>,
+) -> Result<i64, AppError> {
+  let count = sqlx::query_scalar(
+    r#"
+      SELECT COUNT(*)
+      FROM af_notification
+      WHERE uid = $1
+        AND ($2::UUID IS NULL OR workspace_id = $2)
+        AND read_at IS NULL
+    "#,
+  )
+  .bind(uid)
+  .bind(workspace_id)
+  .fetch_one(executor)
+  .await?;
+  Ok(count)
+}
+
+/// Marks the notifications as read, all the notifications of the user when `notification_ids` is
+/// None. Returns the number of notifications marked.
+pub async fn update_notifications_read_at<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+  notification_ids: Option<&[Uuid]>,
+  workspace_id: Option<&Uuid>,
+) -> Result<u64, AppError> {
+  let res = sqlx::query(
+    r#"
+      UPDATE af_notification
+      SET read_at = NOW()
+      WHERE uid = $1
+        AND read_at IS NULL
+        AND ($2::UUID[] IS NULL OR notification_id = ANY($2))
+        AND ($3::UUID IS NULL OR workspace_id = $3)
+    "#,
+  )
+  .bind(uid)
+  .bind(notification_ids)
+  .bind(workspace_id)
+  .execute(executor)
+  .await?;
+  Ok(res.rows_affected())
+}
+
+pub async fn upsert_notification_mute<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: Option<&Uuid>,
+  muted_until: Option<DateTime<Utc>>,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_notification_mute (uid, workspace_id, view_id, muted_until)
+      VALUES ($1, $2, $3, $4)
+      ON CONFLICT (uid, workspace_id, COALESCE(view_id, '00000000-0000-0000-0000-000000000000'::UUID))
+      DO UPDATE SET muted_until = EXCLUDED.muted_until
+    "#,
+  )
+  .bind(uid)
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(muted_until)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Returns false if the workspace or the page was not muted.
+pub async fn delete_notification_mute<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: Option<&Uuid>,
+) -> Result<bool, AppError> {
+  let res = sqlx::query(
+    r#"
+      DELETE FROM af_notification_mute
+      WHERE uid = $1
+        AND workspace_id = $2
+        AND view_id IS NOT DISTINCT FROM $3
+    "#,
+  )
+  .bind(uid)
+  .bind(workspace_id)
+  .bind(view_id)
+  .execute(executor)
+  .await?;
+  Ok(res.rows_affected() > 0)
+}
+
+/// The mutes of the user that are not over yet.
+pub async fn select_notification_mutes<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+) -> Result<Vec<AFNotificationMuteRow>, AppError> {
+  let mutes = sqlx::query_as(
+    r#"
+      SELECT workspace_id, view_id, muted_until
+      FROM af_notification_mute
+      WHERE uid = $1
+        AND (muted_until IS NULL OR muted_until > NOW())
+      ORDER BY created_at
+    "#,
+  )
+  .bind(uid)
+  .fetch_all(executor)
+  .await?;
+  Ok(mutes)
+}
+
+/// Returns None if the user never changed the settings.
+pub async fn select_notification_email_digest_enabled<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+) -> Result<Option<bool>, AppError> {
+  let enabled = sqlx::query_scalar(
+    r#"
+      SELECT email_digest_enabled
+      FROM af_notification_settings
+      WHERE uid = $1
+    "#,
+  )
+  .bind(uid)
+  .fetch_optional(executor)
+  .await?;
+  Ok(enabled)
+}
+
+pub async fn upsert_notification_settings<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+  email_digest_enabled: bool,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_notification_settings (uid, email_digest_enabled)
+      VALUES ($1, $2)
+      ON CONFLICT (uid) DO UPDATE
+      SET email_digest_enabled = EXCLUDED.email_digest_enabled,
+          updated_at = NOW()
+    "#,
+  )
+  .bind(uid)
+  .bind(email_digest_enabled)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// uids of the members of the workspace with the given uuids.
+pub async fn select_workspace_member_uids_by_uuid<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  user_uuids: &[Uuid],
+) -> Result<Vec<i64>, AppError> {
+  let uids = sqlx::query_scalar(
+    r#"
+      SELECT wm.uid
+      FROM af_workspace_member wm
+      JOIN af_user au ON au.uid = wm.uid
+      WHERE wm.workspace_id = $1 AND au.uuid = ANY($2)
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(user_uuids)
+  .fetch_all(executor)
+  .await?;
+  Ok(uids)
+}
+
+/// Replaces the members mentioned in the document. Returns the uids that were not mentioned
+/// before, none the first time the mentions of a document that was not just created are stored.
+pub async fn replace_document_mentions(
+  txn: &mut Transaction<'_, Postgres>,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  is_new_document: bool,
+  uids: &[i64],
+) -> Result<Vec<i64>, AppError> {
+  let first_seen: Option<Uuid> = sqlx::query_scalar(
+    r#"
+      INSERT INTO af_document_mention_seen (view_id, workspace_id)
+      VALUES ($1, $2)
+      ON CONFLICT DO NOTHING
+      RETURNING view_id
+    "#,
+  )
+  .bind(view_id)
+  .bind(workspace_id)
+  .fetch_optional(txn.as_mut())
+  .await?;
+  sqlx::query(
+    r#"
+      DELETE FROM af_document_mention
+      WHERE view_id = $1 AND uid <> ALL($2)
+    "#,
+  )
+  .bind(view_id)
+  .bind(uids)
+  .execute(txn.as_mut())
+  .await?;
+  let new_uids = sqlx::query_scalar(
+    r#"
+      INSERT INTO af_document_mention (view_id, uid, workspace_id)
+      SELECT $1, mentioned.uid, $3 FROM UNNEST($2::BIGINT[]) AS mentioned(uid)
+      ON CONFLICT DO NOTHING
+      RETURNING uid
+    "#,
+  )
+  .bind(view_id)
+  .bind(uids)
+  .bind(workspace_id)
+  .fetch_all(txn.as_mut())
+  .await?;
+  if first_seen.is_some() && !is_new_document {
+    return Ok(vec![]);
+  }
+  Ok(new_uids)
+}
+
+/// Users with notifications created before the given time that are neither read nor emailed yet,
+/// and that didn't disable the email digests.
+pub async fn select_notification_digest_recipients<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  created_before: DateTime<Utc>,
+  limit: i64,
+) -> Result<Vec<AFNotificationDigestRecipientRow>, AppError> {
+  let recipients = sqlx::query_as(
+    r#"
+      SELECT au.uid, au.email, au.name
+      FROM af_user au
+      WHERE au.uid IN (
+          SELECT n.uid FROM af_notification n
+          WHERE n.read_at IS NULL AND n.emailed_at IS NULL AND n.created_at < $1
+        )
+        AND au.deleted_at IS NULL
+        AND COALESCE(
+          (SELECT s.email_digest_enabled FROM af_notification_settings s WHERE s.uid = au.uid),
+          TRUE
+        )
+      LIMIT $2
+    "#,
+  )
+  .bind(created_before)
+  .bind(limit)
+  .fetch_all(executor)
+  .await?;
+  Ok(recipients)
+}
+
+/// Notifications of the user to summarise in the next email digest, newest first.
+pub async fn select_notifications_to_email<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+  created_before: DateTime<Utc>,
+  limit: i64,
+) -> Result<Vec<AFNotificationRow>, AppError> {
+  let notifications = sqlx::query_as(
+    r#"
+      SELECT
+        notification_id, uid, workspace_id, kind, view_id, object_id, actor_uid, payload,
+        created_at, read_at
+      FROM af_notification
+      WHERE uid = $1
+        AND read_at IS NULL
+        AND emailed_at IS NULL
+        AND created_at < $2
+      ORDER BY created_at DESC
+      LIMIT $3
+    "#,
+  )
+  .bind(uid)
+  .bind(created_before)
+  .bind(limit)
+  .fetch_all(executor)
+  .await?;
+  Ok(notifications)
+}
+
+/// Marks all the unread notifications of the user created before the given time as emailed,
+/// including the ones that didn't fit in the digest.
+pub async fn update_notifications_emailed_at<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+  created_before: DateTime<Utc>,
+) -> Result<u64, AppError> {
+  let res = sqlx::query(
+    r#"
+      UPDATE af_notification
+      SET emailed_at = NOW()
+      WHERE uid = $1
+        AND read_at IS NULL
+        AND emailed_at IS NULL
+        AND created_at < $2
+    "#,
+  )
+  .bind(uid)
+  .bind(created_before)
+  .execute(executor)
+  .await?;
+  Ok(res.rows_affected())
+}
+
+/// Marks the unread notifications of the user about the object as emailed, for the notifications
+/// that are emailed as soon as they are created and must not be summarised in a digest again.
+pub async fn update_object_notifications_emailed_at<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  uid: i64,
+  kind: NotificationKind,
+  object_id: &str,
+) -> Result<u64, AppError> {
+  let res = sqlx::query(
+    r#"
+      UPDATE af_notification
+      SET emailed_at = NOW()
+      WHERE uid = $1
+        AND kind = $2
+        AND object_id = $3
+        AND emailed_at IS NULL
+    "#,
+  )
+  .bind(uid)
+  .bind(kind as i16)
+  .bind(object_id)
+  .execute(executor)
+  .await?;
+  Ok(res.rows_affected())
+}
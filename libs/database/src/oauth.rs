@@ -0,0 +1,264 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use shared_entity::dto::oauth_dto::OAuthClient;
+use sqlx::{Executor, FromRow, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug, FromRow)]
+struct AFOAuthClientRow {
+  client_id: String,
+  name: String,
+  redirect_uris: Vec<String>,
+  confidential: bool,
+  created_at: DateTime<Utc>,
+}
+
+impl From<AFOAuthClientRow> for OAuthClient {
+  fn from(row: AFOAuthClientRow) -> Self {
+    OAuthClient {
+      client_id: row.client_id,
+      name: row.name,
+      redirect_uris: row.redirect_uris,
+      confidential: row.confidential,
+      created_at: row.created_at,
+    }
+  }
+}
+
+/// A client and the hash of its secret, used to verify the token requests.
+#[derive(Debug)]
+pub struct AFOAuthClientWithSecret {
+  pub client: OAuthClient,
+  pub client_secret_hash: Option<String>,
+}
+
+#[derive(FromRow)]
+struct AFOAuthClientWithSecretRow {
+  #[sqlx(flatten)]
+  client: AFOAuthClientRow,
+  client_secret_hash: Option<String>,
+}
+
+/// A consumed authorization code, see [delete_oauth_authorization_code].
+#[derive(Debug, Clone, FromRow)]
+pub struct AFOAuthAuthorizationCode {
+  pub client_id: String,
+  pub uid: i64,
+  pub workspace_id: Uuid,
+  pub scope: i32,
+  pub redirect_uri: String,
+  pub code_challenge: Option<String>,
+}
+
+#[derive(FromRow)]
+struct AFOAuthAuthorizationCodeRow {
+  #[sqlx(flatten)]
+  code: AFOAuthAuthorizationCode,
+  is_valid: bool,
+}
+
+pub async fn insert_oauth_client<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  client_id: &str,
+  client_secret_hash: Option<&str>,
+  name: &str,
+  redirect_uris: &[String],
+  owner_uid: i64,
+) -> Result<OAuthClient, AppError> {
+  let row: AFOAuthClientRow = sqlx::query_as(
+    r#"
+      INSERT INTO af_oauth_client (client_id, client_secret_hash, name, redirect_uris, owner_uid)
+      VALUES ($1, $2, $3, $4, $5)
+      RETURNING client_id, name, redirect_uris, client_secret_hash IS NOT NULL AS confidential,
+        created_at
+    "#,
+  )
+  .bind(client_id)
+  .bind(client_secret_hash)
+  .bind(name)
+  .bind(redirect_uris)
+  .bind(owner_uid)
+  .fetch_one(executor)
+  .await?;
+  Ok(row.into())
+}
+
+pub async fn select_oauth_clients<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  owner_uid: i64,
+) -> Result<Vec<OAuthClient>, AppError> {
+  let rows: Vec<AFOAuthClientRow> = sqlx::query_as(
+    r#"
+      SELECT client_id, name, redirect_uris, client_secret_hash IS NOT NULL AS confidential,
+        created_at
+      FROM af_oauth_client
+      WHERE owner_uid = $1
+      ORDER BY created_at DESC
+    "#,
+  )
+  .bind(owner_uid)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(OAuthClient::from).collect())
+}
+
+pub async fn select_oauth_client<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  client_id: &str,
+) -> Result<Option<AFOAuthClientWithSecret>, AppError> {
+  let row: Option<AFOAuthClientWithSecretRow> = sqlx::query_as(
+    r#"
+      SELECT client_id, name, redirect_uris, client_secret_hash IS NOT NULL AS confidential,
+        created_at, client_secret_hash
+      FROM af_oauth_client
+      WHERE client_id = $1
+    "#,
+  )
+  .bind(client_id)
+  .fetch_optional(executor)
+  .await?;
+
+  Ok(row.map(|row| AFOAuthClientWithSecret {
+    client: row.client.into(),
+    client_secret_hash: row.client_secret_hash,
+  }))
+}
+
+/// Deletes the client owned by the user. The tokens issued to the client are deleted with it.
+/// Returns false if the user doesn't own such a client.
+pub async fn delete_oauth_client<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  client_id: &str,
+  owner_uid: i64,
+) -> Result<bool, AppError> {
+  let result = sqlx::query(
+    r#"
+      DELETE FROM af_oauth_client
+      WHERE client_id = $1 AND owner_uid = $2
+    "#,
+  )
+  .bind(client_id)
+  .bind(owner_uid)
+  .execute(executor)
+  .await?;
+  Ok(result.rows_affected() > 0)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_oauth_authorization_code<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  code_hash: &str,
+  client_id: &str,
+  uid: i64,
+  workspace_id: &Uuid,
+  scope: i32,
+  redirect_uri: &str,
+  code_challenge: Option<&str>,
+  expires_at: DateTime<Utc>,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_oauth_authorization_code
+        (code_hash, client_id, uid, workspace_id, scope, redirect_uri, code_challenge, expires_at)
+      VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+    "#,
+  )
+  .bind(code_hash)
+  .bind(client_id)
+  .bind(uid)
+  .bind(workspace_id)
+  .bind(scope)
+  .bind(redirect_uri)
+  .bind(code_challenge)
+  .bind(expires_at)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Consumes an authorization code, so it can't be exchanged twice. Returns `None` if the code
+/// doesn't exist or has expired.
+pub async fn delete_oauth_authorization_code<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  code_hash: &str,
+) -> Result<Option<AFOAuthAuthorizationCode>, AppError> {
+  let code = sqlx::query_as(
+    r#"
+      DELETE FROM af_oauth_authorization_code
+      WHERE code_hash = $1
+      RETURNING client_id, uid, workspace_id, scope, redirect_uri, code_challenge,
+        expires_at > NOW() AS is_valid
+    "#,
+  )
+  .bind(code_hash)
+  .fetch_optional(executor)
+  .await?
+  .and_then(|row: AFOAuthAuthorizationCodeRow| row.is_valid.then_some(row.code));
+  Ok(code)
+}
+
+/// A consumed refresh token, see [delete_oauth_refresh_token].
+#[derive(Debug, Clone, FromRow)]
+pub struct AFOAuthRefreshToken {
+  pub client_id: String,
+  pub uid: i64,
+  pub workspace_id: Uuid,
+  pub scope: i32,
+  pub access_token_id: Uuid,
+}
+
+#[derive(FromRow)]
+struct AFOAuthRefreshTokenRow {
+  #[sqlx(flatten)]
+  token: AFOAuthRefreshToken,
+  is_valid: bool,
+}
+
+pub async fn insert_oauth_refresh_token<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  token_hash: &str,
+  token: &AFOAuthRefreshToken,
+  expires_at: DateTime<Utc>,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_oauth_refresh_token
+        (token_hash, client_id, uid, workspace_id, scope, access_token_id, expires_at)
+      VALUES ($1, $2, $3, $4, $5, $6, $7)
+    "#,
+  )
+  .bind(token_hash)
+  .bind(&token.client_id)
+  .bind(token.uid)
+  .bind(token.workspace_id)
+  .bind(token.scope)
+  .bind(token.access_token_id)
+  .bind(expires_at)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Consumes a refresh token of the client, so it can't be used twice. Returns `None` if the token
+/// doesn't exist, was issued to another client or has expired. The token of another client is
+/// left untouched.
+pub async fn delete_oauth_refresh_token<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  token_hash: &str,
+  client_id: &str,
+) -> Result<Option<AFOAuthRefreshToken>, AppError> {
+  let token = sqlx::query_as(
+    r#"
+      DELETE FROM af_oauth_refresh_token
+      WHERE token_hash = $1 AND client_id = $2
+      RETURNING client_id, uid, workspace_id, scope, access_token_id,
+        expires_at > NOW() AS is_valid
+    "#,
+  )
+  .bind(token_hash)
+  .bind(client_id)
+  .fetch_optional(executor)
+  .await?
+  .and_then(|row: AFOAuthRefreshTokenRow| row.is_valid.then_some(row.token));
+  Ok(token)
+}
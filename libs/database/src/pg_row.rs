@@ -1,6 +1,6 @@
 use anyhow::anyhow;
 use app_error::AppError;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 
 use database_entity::dto::{
   AFAccessLevel, AFRole, AFUserProfile, AFWebUser, AFWorkspace, AFWorkspaceInvitationStatus,
@@ -211,6 +211,15 @@ pub struct AFUserNotification {
   pub payload: Option<AFUserRow>,
 }
 
+/// Sent by the `af_chat_message_change_trigger` when a chat message is inserted or its content is
+/// updated.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AFChatMessageNotification {
+  pub chat_id: Uuid,
+  pub message_id: i64,
+  pub action_type: String,
+}
+
 #[derive(FromRow, Debug, Clone)]
 pub struct AFPermissionRow {
   pub id: i32,
@@ -307,6 +316,7 @@ impl From<AFGlobalCommentRow> for GlobalComment {
       comment_id: val.comment_id,
       is_deleted: val.is_deleted,
       can_be_deleted: val.can_be_deleted,
+      is_pending_approval: false,
     }
   }
 }
@@ -565,12 +575,13 @@ pub struct AFImportTask {
   #[serde(default)]
   pub file_url: Option<String>,
 }
-#[derive(sqlx::Type, Serialize, Deserialize, Debug)]
+#[derive(sqlx::Type, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(i32)]
 pub enum AFAccessRequestStatusColumn {
   Pending = 0,
   Approved = 1,
   Rejected = 2,
+  Expired = 3,
 }
 
 impl From<AFAccessRequestStatusColumn> for AccessRequestStatus {
@@ -579,10 +590,217 @@ impl From<AFAccessRequestStatusColumn> for AccessRequestStatus {
       AFAccessRequestStatusColumn::Pending => AccessRequestStatus::Pending,
       AFAccessRequestStatusColumn::Approved => AccessRequestStatus::Approved,
       AFAccessRequestStatusColumn::Rejected => AccessRequestStatus::Rejected,
+      AFAccessRequestStatusColumn::Expired => AccessRequestStatus::Expired,
     }
   }
 }
 
+impl From<AccessRequestStatus> for AFAccessRequestStatusColumn {
+  fn from(value: AccessRequestStatus) -> Self {
+    match value {
+      AccessRequestStatus::Pending => AFAccessRequestStatusColumn::Pending,
+      AccessRequestStatus::Approved => AFAccessRequestStatusColumn::Approved,
+      AccessRequestStatus::Rejected => AFAccessRequestStatusColumn::Rejected,
+      AccessRequestStatus::Expired => AFAccessRequestStatusColumn::Expired,
+    }
+  }
+}
+
+/// An access request with its requester, see [crate::access_request::select_access_requests].
+#[derive(FromRow, Debug)]
+pub struct AFAccessRequestRow {
+  pub request_id: Uuid,
+  pub workspace_id: Uuid,
+  pub view_id: Uuid,
+  pub uid: i64,
+  pub uuid: Uuid,
+  pub name: String,
+  pub email: String,
+  pub avatar_url: Option<String>,
+  pub status: AFAccessRequestStatusColumn,
+  pub reason: Option<String>,
+  pub rejection_reason: Option<String>,
+  pub created_at: DateTime<Utc>,
+  pub expires_at: DateTime<Utc>,
+}
+
+impl AFAccessRequestRow {
+  pub fn requester(&self) -> AccessRequesterInfo {
+    AccessRequesterInfo {
+      uid: self.uid,
+      uuid: self.uuid,
+      email: self.email.clone(),
+      name: self.name.clone(),
+      avatar_url: self.avatar_url.clone(),
+    }
+  }
+}
+
+/// Sent by the `af_access_request_change_trigger` when a request is created or its status changes.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AFAccessRequestNotification {
+  pub request_id: Uuid,
+  pub status: i32,
+}
+
+/// Publish settings of a view, set by the owner with the patch of the published collabs.
+#[derive(Debug, Clone, FromRow)]
+pub struct AFPublishedCollabSettingsRow {
+  pub view_id: Uuid,
+  pub live: bool,
+  pub password_hash: Option<String>,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub allowed_email_domains: Vec<String>,
+}
+
+impl AFPublishedCollabSettingsRow {
+  /// Anyone can read the view, without password nor account.
+  pub fn is_public(&self) -> bool {
+    self.password_hash.is_none()
+      && self.expires_at.is_none()
+      && self.allowed_email_domains.is_empty()
+  }
+
+  pub fn is_expired(&self) -> bool {
+    self
+      .expires_at
+      .map(|expires_at| expires_at <= Utc::now())
+      .unwrap_or(false)
+  }
+}
+
+/// A published view resolved from its publish name, see [crate::publish_analytics].
+#[derive(Debug, Clone, FromRow)]
+pub struct AFPublishedViewNameRow {
+  pub workspace_id: Uuid,
+  pub view_id: Uuid,
+  pub namespace: String,
+  pub publish_name: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AFPublishedViewDailyStatsRow {
+  pub view_id: Uuid,
+  pub day: NaiveDate,
+  pub view_count: i64,
+  pub unique_visitor_count: i64,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AFPublishedViewReferrerStatsRow {
+  pub view_id: Uuid,
+  pub referrer: String,
+  pub view_count: i64,
+}
+
+/// What applies to a user commenting on a published view, see
+/// [crate::comment_moderation::select_published_view_comment_policy].
+#[derive(Debug, Clone, FromRow)]
+pub struct AFPublishedViewCommentPolicyRow {
+  pub workspace_id: Option<Uuid>,
+  pub comments_enabled: bool,
+  pub require_approval: bool,
+  pub blocked_keywords: Vec<String>,
+  pub max_comments_per_hour: Option<i32>,
+  pub is_banned: bool,
+  pub is_moderator: bool,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AFWorkspaceCommentModerationRow {
+  pub blocked_keywords: Vec<String>,
+  pub max_comments_per_hour: Option<i32>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AFWorkspaceCommentBanRow {
+  pub uuid: Uuid,
+  pub name: String,
+  pub avatar_url: Option<String>,
+  pub reason: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+/// Sent by the `af_published_collab_update_trigger` when the blob of a live published view
+/// changes or when a published view is switched to live mode.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AFPublishedCollabNotification {
+  pub workspace_id: Uuid,
+  pub view_id: Uuid,
+  pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AFDocumentCommentThreadRow {
+  pub thread_id: Uuid,
+  pub workspace_id: Uuid,
+  pub view_id: Uuid,
+  pub block_id: String,
+  pub range_start: Option<i32>,
+  pub range_end: Option<i32>,
+  pub quoted_text: Option<String>,
+  pub created_by: Option<i64>,
+  pub created_at: DateTime<Utc>,
+  pub resolved_by: Option<i64>,
+  pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AFDocumentCommentRow {
+  pub comment_id: Uuid,
+  pub thread_id: Uuid,
+  pub content: String,
+  pub created_by: Option<i64>,
+  pub author_uuid: Option<Uuid>,
+  pub author_name: Option<String>,
+  pub author_avatar_url: Option<String>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+  pub is_deleted: bool,
+  /// uids of the mentioned members
+  pub mentions: Vec<i64>,
+}
+
+/// Sent by the `af_document_comment_change_trigger` and `af_document_comment_thread_change_trigger`
+/// when a comment is added, edited or deleted, and when a thread is resolved or reopened.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AFDocumentCommentNotification {
+  pub thread_id: Uuid,
+  pub comment_id: Option<Uuid>,
+  pub action: u8,
+}
+
+/// A row of the af_notification table, also sent by the `af_notification_insert_trigger` when a
+/// notification is created.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AFNotificationRow {
+  pub notification_id: Uuid,
+  pub uid: i64,
+  pub workspace_id: Uuid,
+  pub kind: i16,
+  pub view_id: Option<Uuid>,
+  pub object_id: Option<String>,
+  pub actor_uid: Option<i64>,
+  pub payload: serde_json::Value,
+  pub created_at: DateTime<Utc>,
+  pub read_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AFNotificationMuteRow {
+  pub workspace_id: Uuid,
+  pub view_id: Option<Uuid>,
+  pub muted_until: Option<DateTime<Utc>>,
+}
+
+/// A user with unread notifications to summarise in an email digest.
+#[derive(Debug, Clone, FromRow)]
+pub struct AFNotificationDigestRecipientRow {
+  pub uid: i64,
+  pub email: String,
+  pub name: String,
+}
+
 #[derive(sqlx::Type, Serialize, Debug)]
 pub struct AFAccessRequesterColumn {
   pub uid: i64,
@@ -647,3 +865,17 @@ impl TryFrom<AFAccessRequestWithViewIdColumn> for AccessRequestWithViewId {
     })
   }
 }
+
+/// A reminder claimed by the worker, with the user to remind.
+#[derive(Debug, Clone, FromRow)]
+pub struct AFDueReminderRow {
+  pub object_id: Uuid,
+  pub reminder_id: String,
+  pub workspace_id: Uuid,
+  pub view_id: Option<Uuid>,
+  pub uid: i64,
+  pub scheduled_at: DateTime<Utc>,
+  pub payload: serde_json::Value,
+  pub email: String,
+  pub name: String,
+}
@@ -1,10 +1,13 @@
 use app_error::AppError;
+use chrono::{DateTime, Utc};
 use database_entity::dto::{
   PatchPublishedCollab, PublishCollabItem, PublishCollabKey, PublishInfo, WorkspaceNamespace,
 };
 use sqlx::{Executor, PgPool, Postgres};
 use uuid::Uuid;
 
+use crate::pg_row::AFPublishedCollabSettingsRow;
+
 pub async fn select_user_is_collab_publisher_for_all_views(
   pg_pool: &PgPool,
   user_uuid: &Uuid,
@@ -259,6 +262,25 @@ pub async fn insert_or_replace_publish_collabs(
   });
 
   let mut txn = pg_pool.begin().await?;
+  // Republishing a view must not reset its publish settings, even though the record is deleted
+  // when the publish name is reused.
+  let settings: Vec<AFPublishedCollabSettingsRow> = sqlx::query_as(
+    r#"
+      SELECT view_id, live, password_hash, expires_at, allowed_email_domains
+      FROM af_published_collab
+      WHERE workspace_id = $1
+        AND view_id = ANY($2::uuid[])
+        AND unpublished_at IS NULL
+        AND (live
+          OR password_hash IS NOT NULL
+          OR expires_at IS NOT NULL
+          OR cardinality(allowed_email_domains) > 0)
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(&view_ids)
+  .fetch_all(txn.as_mut())
+  .await?;
   delete_published_collabs(&mut txn, workspace_id, &publish_names).await?;
 
   let res = sqlx::query!(
@@ -296,6 +318,28 @@ pub async fn insert_or_replace_publish_collabs(
     );
   }
 
+  for settings in settings {
+    sqlx::query(
+      r#"
+        UPDATE af_published_collab
+        SET live = $3,
+            password_hash = $4,
+            expires_at = $5,
+            allowed_email_domains = $6
+        WHERE workspace_id = $1
+          AND view_id = $2
+      "#,
+    )
+    .bind(workspace_id)
+    .bind(settings.view_id)
+    .bind(settings.live)
+    .bind(settings.password_hash)
+    .bind(settings.expires_at)
+    .bind(settings.allowed_email_domains)
+    .execute(txn.as_mut())
+    .await?;
+  }
+
   txn.commit().await?;
   Ok(())
 }
@@ -677,3 +721,223 @@ pub async fn select_published_view_ids_for_workspace<'a, E: Executor<'a, Databas
 
   Ok(res)
 }
+
+/// Returns the workspace of a published view, if the view is still published.
+pub async fn select_workspace_id_for_published_view<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  view_id: &Uuid,
+) -> Result<Option<Uuid>, AppError> {
+  let workspace_id = sqlx::query_scalar(
+    r#"
+      SELECT workspace_id
+      FROM af_published_collab
+      WHERE view_id = $1 AND unpublished_at IS NULL
+    "#,
+  )
+  .bind(view_id)
+  .fetch_optional(executor)
+  .await?;
+  Ok(workspace_id)
+}
+
+/// Turns the live mode of a published view on or off. Returns false if the view is not published
+/// in the workspace.
+pub async fn update_published_collab_live<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  live: bool,
+) -> Result<bool, AppError> {
+  let res = sqlx::query(
+    r#"
+      UPDATE af_published_collab
+      SET live = $3
+      WHERE workspace_id = $1
+        AND view_id = $2
+        AND unpublished_at IS NULL
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(live)
+  .execute(executor)
+  .await?;
+  Ok(res.rows_affected() == 1)
+}
+
+/// Returns the views, among the given ones, that are published in live mode.
+pub async fn select_live_published_view_ids<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  view_ids: &[Uuid],
+) -> Result<Vec<Uuid>, AppError> {
+  let view_ids = sqlx::query_scalar(
+    r#"
+      SELECT view_id
+      FROM af_published_collab
+      WHERE view_id = ANY($1::uuid[])
+        AND live
+        AND unpublished_at IS NULL
+    "#,
+  )
+  .bind(view_ids)
+  .fetch_all(executor)
+  .await?;
+  Ok(view_ids)
+}
+
+/// Replaces the blobs of the given views, skipping the views that are not published in live mode.
+/// Returns the keys of the updated views.
+pub async fn update_live_published_collab_blobs<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_ids: &[Uuid],
+  view_ids: &[Uuid],
+  blobs: &[Vec<u8>],
+) -> Result<Vec<PublishCollabKey>, AppError> {
+  let rows: Vec<(Uuid, Uuid)> = sqlx::query_as(
+    r#"
+      UPDATE af_published_collab AS apc
+      SET blob = data.blob
+      FROM UNNEST($1::uuid[], $2::uuid[], $3::bytea[]) AS data(workspace_id, view_id, blob)
+      WHERE apc.workspace_id = data.workspace_id
+        AND apc.view_id = data.view_id
+        AND apc.live
+        AND apc.unpublished_at IS NULL
+      RETURNING apc.workspace_id, apc.view_id
+    "#,
+  )
+  .bind(workspace_ids)
+  .bind(view_ids)
+  .bind(blobs)
+  .fetch_all(executor)
+  .await?;
+  Ok(
+    rows
+      .into_iter()
+      .map(|(workspace_id, view_id)| PublishCollabKey {
+        workspace_id,
+        view_id,
+      })
+      .collect(),
+  )
+}
+
+/// Returns the view published under the given name if it is published in live mode.
+pub async fn select_live_published_view_id<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  publish_namespace: &str,
+  publish_name: &str,
+) -> Result<Option<Uuid>, AppError> {
+  let view_id = sqlx::query_scalar(
+    r#"
+      SELECT view_id
+      FROM af_published_collab
+      WHERE workspace_id = (SELECT workspace_id FROM af_workspace_namespace WHERE namespace = $1)
+        AND publish_name = $2
+        AND live
+        AND unpublished_at IS NULL
+    "#,
+  )
+  .bind(publish_namespace)
+  .bind(publish_name)
+  .fetch_optional(executor)
+  .await?;
+  Ok(view_id)
+}
+
+/// Key of the copy of a published collab stored in S3.
+pub fn published_collab_s3_key(workspace_id: &Uuid, view_id: &Uuid) -> String {
+  format!("published-collab/{}/{}", workspace_id, view_id)
+}
+
+/// Updates the restrictions on the readers of a published view, the `None` values are left
+/// unchanged. Returns false if the view is not published in the workspace.
+pub async fn update_published_collab_access<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  password_hash: Option<Option<String>>,
+  expires_at: Option<Option<DateTime<Utc>>>,
+  allowed_email_domains: Option<Vec<String>>,
+) -> Result<bool, AppError> {
+  let res = sqlx::query(
+    r#"
+      UPDATE af_published_collab
+      SET password_hash = CASE WHEN $3 THEN $4 ELSE password_hash END,
+          expires_at = CASE WHEN $5 THEN $6 ELSE expires_at END,
+          allowed_email_domains = COALESCE($7, allowed_email_domains)
+      WHERE workspace_id = $1
+        AND view_id = $2
+        AND unpublished_at IS NULL
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(password_hash.is_some())
+  .bind(password_hash.flatten())
+  .bind(expires_at.is_some())
+  .bind(expires_at.flatten())
+  .bind(allowed_email_domains)
+  .execute(executor)
+  .await?;
+  Ok(res.rows_affected() == 1)
+}
+
+pub async fn select_published_collab_settings<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  view_id: &Uuid,
+) -> Result<Option<AFPublishedCollabSettingsRow>, AppError> {
+  let settings = sqlx::query_as(
+    r#"
+      SELECT view_id, live, password_hash, expires_at, allowed_email_domains
+      FROM af_published_collab
+      WHERE view_id = $1
+        AND unpublished_at IS NULL
+    "#,
+  )
+  .bind(view_id)
+  .fetch_optional(executor)
+  .await?;
+  Ok(settings)
+}
+
+pub async fn select_published_collab_settings_by_name<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  publish_namespace: &str,
+  publish_name: &str,
+) -> Result<Option<AFPublishedCollabSettingsRow>, AppError> {
+  let settings = sqlx::query_as(
+    r#"
+      SELECT view_id, live, password_hash, expires_at, allowed_email_domains
+      FROM af_published_collab
+      WHERE workspace_id = (SELECT workspace_id FROM af_workspace_namespace WHERE namespace = $1)
+        AND publish_name = $2
+        AND unpublished_at IS NULL
+    "#,
+  )
+  .bind(publish_namespace)
+  .bind(publish_name)
+  .fetch_optional(executor)
+  .await?;
+  Ok(settings)
+}
+
+pub async fn select_published_collab_settings_for_workspace<
+  'a,
+  E: Executor<'a, Database = Postgres>,
+>(
+  executor: E,
+  workspace_id: &Uuid,
+) -> Result<Vec<AFPublishedCollabSettingsRow>, AppError> {
+  let settings = sqlx::query_as(
+    r#"
+      SELECT view_id, live, password_hash, expires_at, allowed_email_domains
+      FROM af_published_collab
+      WHERE workspace_id = $1
+        AND unpublished_at IS NULL
+    "#,
+  )
+  .bind(workspace_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(settings)
+}
@@ -0,0 +1,208 @@
+use app_error::AppError;
+use chrono::NaiveDate;
+use sqlx::{Executor, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::pg_row::{
+  AFPublishedViewDailyStatsRow, AFPublishedViewNameRow, AFPublishedViewReferrerStatsRow,
+};
+
+/// Returns the salt of the visitor hashes of the day, `new_salt` becomes the salt of the day if
+/// there is none yet.
+pub async fn select_or_insert_published_view_salt<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  day: NaiveDate,
+  new_salt: &[u8],
+) -> Result<Vec<u8>, AppError> {
+  let salt: Vec<u8> = sqlx::query_scalar(
+    r#"
+      WITH inserted AS (
+        INSERT INTO af_published_view_salt (day, salt)
+        VALUES ($1, $2)
+        ON CONFLICT (day) DO NOTHING
+        RETURNING salt
+      )
+      SELECT salt FROM inserted
+      UNION ALL
+      SELECT salt FROM af_published_view_salt WHERE day = $1
+      LIMIT 1
+    "#,
+  )
+  .bind(day)
+  .bind(new_salt)
+  .fetch_one(executor)
+  .await?;
+  Ok(salt)
+}
+
+/// Deletes the salts and the visitor hashes of the days before `day`.
+pub async fn delete_published_view_visitors_before(
+  txn: &mut Transaction<'_, Postgres>,
+  day: NaiveDate,
+) -> Result<(), AppError> {
+  sqlx::query("DELETE FROM af_published_view_salt WHERE day < $1")
+    .bind(day)
+    .execute(txn.as_mut())
+    .await?;
+  sqlx::query("DELETE FROM af_published_view_visitor WHERE day < $1")
+    .bind(day)
+    .execute(txn.as_mut())
+    .await?;
+  Ok(())
+}
+
+pub async fn select_published_views_by_name<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  namespaces: &[String],
+  publish_names: &[String],
+) -> Result<Vec<AFPublishedViewNameRow>, AppError> {
+  let rows = sqlx::query_as(
+    r#"
+      SELECT apc.workspace_id, apc.view_id, awn.namespace, apc.publish_name
+      FROM af_published_collab apc
+      JOIN af_workspace_namespace awn ON awn.workspace_id = apc.workspace_id
+      JOIN UNNEST($1::text[], $2::text[]) AS names(namespace, publish_name)
+        ON names.namespace = awn.namespace AND names.publish_name = apc.publish_name
+      WHERE apc.unpublished_at IS NULL
+    "#,
+  )
+  .bind(namespaces)
+  .bind(publish_names)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows)
+}
+
+/// Inserts the visitor hashes and returns the (view_id, day) of the visitors that were not seen
+/// yet on that day.
+pub async fn insert_published_view_visitors(
+  txn: &mut Transaction<'_, Postgres>,
+  view_ids: &[Uuid],
+  days: &[NaiveDate],
+  visitor_hashes: &[Vec<u8>],
+) -> Result<Vec<(Uuid, NaiveDate)>, AppError> {
+  let rows = sqlx::query_as(
+    r#"
+      INSERT INTO af_published_view_visitor (view_id, day, visitor_hash)
+      SELECT * FROM UNNEST($1::uuid[], $2::date[], $3::bytea[])
+      ON CONFLICT DO NOTHING
+      RETURNING view_id, day
+    "#,
+  )
+  .bind(view_ids)
+  .bind(days)
+  .bind(visitor_hashes)
+  .fetch_all(txn.as_mut())
+  .await?;
+  Ok(rows)
+}
+
+pub async fn upsert_published_view_daily_stats(
+  txn: &mut Transaction<'_, Postgres>,
+  workspace_ids: &[Uuid],
+  view_ids: &[Uuid],
+  days: &[NaiveDate],
+  view_counts: &[i64],
+  unique_visitor_counts: &[i64],
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_published_view_daily_stats
+        (workspace_id, view_id, day, view_count, unique_visitor_count)
+      SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::date[], $4::bigint[], $5::bigint[])
+      ON CONFLICT (view_id, day) DO UPDATE
+      SET view_count = af_published_view_daily_stats.view_count + EXCLUDED.view_count,
+          unique_visitor_count =
+            af_published_view_daily_stats.unique_visitor_count + EXCLUDED.unique_visitor_count
+    "#,
+  )
+  .bind(workspace_ids)
+  .bind(view_ids)
+  .bind(days)
+  .bind(view_counts)
+  .bind(unique_visitor_counts)
+  .execute(txn.as_mut())
+  .await?;
+  Ok(())
+}
+
+pub async fn upsert_published_view_referrer_stats(
+  txn: &mut Transaction<'_, Postgres>,
+  workspace_ids: &[Uuid],
+  view_ids: &[Uuid],
+  days: &[NaiveDate],
+  referrers: &[String],
+  view_counts: &[i64],
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_published_view_referrer_stats
+        (workspace_id, view_id, day, referrer, view_count)
+      SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::date[], $4::text[], $5::bigint[])
+      ON CONFLICT (view_id, day, referrer) DO UPDATE
+      SET view_count = af_published_view_referrer_stats.view_count + EXCLUDED.view_count
+    "#,
+  )
+  .bind(workspace_ids)
+  .bind(view_ids)
+  .bind(days)
+  .bind(referrers)
+  .bind(view_counts)
+  .execute(txn.as_mut())
+  .await?;
+  Ok(())
+}
+
+pub async fn select_published_view_daily_stats<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: Option<&Uuid>,
+  start: NaiveDate,
+  end: NaiveDate,
+) -> Result<Vec<AFPublishedViewDailyStatsRow>, AppError> {
+  let rows = sqlx::query_as(
+    r#"
+      SELECT view_id, day, view_count, unique_visitor_count
+      FROM af_published_view_daily_stats
+      WHERE workspace_id = $1
+        AND ($2::uuid IS NULL OR view_id = $2)
+        AND day BETWEEN $3 AND $4
+      ORDER BY view_id, day
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(start)
+  .bind(end)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows)
+}
+
+/// Referrers of the views over the range, the most frequent first.
+pub async fn select_published_view_referrer_stats<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: Option<&Uuid>,
+  start: NaiveDate,
+  end: NaiveDate,
+) -> Result<Vec<AFPublishedViewReferrerStatsRow>, AppError> {
+  let rows = sqlx::query_as(
+    r#"
+      SELECT view_id, referrer, SUM(view_count)::BIGINT AS view_count
+      FROM af_published_view_referrer_stats
+      WHERE workspace_id = $1
+        AND ($2::uuid IS NULL OR view_id = $2)
+        AND day BETWEEN $3 AND $4
+      GROUP BY view_id, referrer
+      ORDER BY view_id, view_count DESC, referrer
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(start)
+  .bind(end)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows)
+}
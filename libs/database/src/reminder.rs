@@ -0,0 +1,166 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::pg_row::AFDueReminderRow;
+
+/// A reminder found in the content of a document or a database row.
+#[derive(Debug, Clone)]
+pub struct ReminderParams {
+  pub reminder_id: String,
+  pub scheduled_at: DateTime<Utc>,
+  pub payload: serde_json::Value,
+}
+
+/// Replaces the reminders of the document or the database row. The reminders that are not in
+/// `reminders` anymore are cancelled, and a reminder fired before is fired again when its time
+/// changed. The reminder ids must be unique.
+///
+/// The users reminded are the owners of the reminders, see [replace_reminder_owners].
+pub async fn replace_reminders(
+  txn: &mut Transaction<'_, Postgres>,
+  workspace_id: &Uuid,
+  object_id: &Uuid,
+  view_id: Option<&Uuid>,
+  reminders: &[ReminderParams],
+) -> Result<(), AppError> {
+  let reminder_ids: Vec<&str> = reminders
+    .iter()
+    .map(|reminder| reminder.reminder_id.as_str())
+    .collect();
+  sqlx::query(
+    r#"
+      DELETE FROM af_reminder
+      WHERE object_id = $1 AND reminder_id <> ALL($2)
+    "#,
+  )
+  .bind(object_id)
+  .bind(&reminder_ids)
+  .execute(txn.as_mut())
+  .await?;
+  if reminders.is_empty() {
+    return Ok(());
+  }
+
+  let scheduled_ats: Vec<DateTime<Utc>> = reminders
+    .iter()
+    .map(|reminder| reminder.scheduled_at)
+    .collect();
+  let payloads: Vec<serde_json::Value> = reminders
+    .iter()
+    .map(|reminder| reminder.payload.clone())
+    .collect();
+  sqlx::query(
+    r#"
+      UPDATE af_reminder_owner o
+      SET fired_at = NULL
+      FROM af_reminder r, UNNEST($2::TEXT[], $3::TIMESTAMPTZ[]) AS n(reminder_id, scheduled_at)
+      WHERE r.object_id = $1 AND r.reminder_id = n.reminder_id
+        AND r.scheduled_at <> n.scheduled_at
+        AND o.workspace_id = r.workspace_id AND o.reminder_id = r.reminder_id
+    "#,
+  )
+  .bind(object_id)
+  .bind(&reminder_ids)
+  .bind(&scheduled_ats)
+  .execute(txn.as_mut())
+  .await?;
+  sqlx::query(
+    r#"
+      INSERT INTO af_reminder
+        (object_id, reminder_id, workspace_id, view_id, scheduled_at, payload)
+      SELECT $1, r.reminder_id, $3, $4, r.scheduled_at, r.payload
+      FROM UNNEST($2::TEXT[], $5::TIMESTAMPTZ[], $6::JSONB[])
+        AS r(reminder_id, scheduled_at, payload)
+      ON CONFLICT (object_id, reminder_id) DO UPDATE
+      SET scheduled_at = EXCLUDED.scheduled_at,
+          payload = EXCLUDED.payload
+    "#,
+  )
+  .bind(object_id)
+  .bind(&reminder_ids)
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(&scheduled_ats)
+  .bind(&payloads)
+  .execute(txn.as_mut())
+  .await?;
+  Ok(())
+}
+
+/// Replaces the reminders the user set in the workspace, as listed in its user awareness collab.
+/// The user is only reminded of the reminders it owns, whoever edits the date they are set on.
+pub async fn replace_reminder_owners(
+  txn: &mut Transaction<'_, Postgres>,
+  workspace_id: &Uuid,
+  uid: i64,
+  reminder_ids: &[String],
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      DELETE FROM af_reminder_owner
+      WHERE workspace_id = $1 AND uid = $2 AND reminder_id <> ALL($3)
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .bind(reminder_ids)
+  .execute(txn.as_mut())
+  .await?;
+  sqlx::query(
+    r#"
+      INSERT INTO af_reminder_owner (workspace_id, reminder_id, uid)
+      SELECT $1, reminder_id, $2
+      FROM UNNEST($3::TEXT[]) AS reminder_id
+      ON CONFLICT (workspace_id, reminder_id, uid) DO NOTHING
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .bind(reminder_ids)
+  .execute(txn.as_mut())
+  .await?;
+  Ok(())
+}
+
+/// Marks the reminders that are due as fired for their owners and returns them, one per owner,
+/// oldest first. The reminders claimed by another worker at the same time are skipped.
+pub async fn claim_due_reminders<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  limit: i64,
+) -> Result<Vec<AFDueReminderRow>, AppError> {
+  let reminders = sqlx::query_as(
+    r#"
+      WITH claimed AS (
+        SELECT o.workspace_id, o.reminder_id, o.uid
+        FROM af_reminder_owner o
+        JOIN af_reminder r ON r.workspace_id = o.workspace_id AND r.reminder_id = o.reminder_id
+        WHERE o.fired_at IS NULL AND r.scheduled_at <= NOW()
+        ORDER BY r.scheduled_at
+        LIMIT $1
+        FOR UPDATE OF o SKIP LOCKED
+      ),
+      due AS (
+        UPDATE af_reminder_owner o
+        SET fired_at = NOW()
+        FROM claimed
+        WHERE o.workspace_id = claimed.workspace_id AND o.reminder_id = claimed.reminder_id
+          AND o.uid = claimed.uid
+        RETURNING o.workspace_id, o.reminder_id, o.uid
+      )
+      SELECT
+        r.object_id, r.reminder_id, r.workspace_id, r.view_id, due.uid,
+        r.scheduled_at, r.payload, au.email, au.name
+      FROM due
+      JOIN af_reminder r ON r.workspace_id = due.workspace_id AND r.reminder_id = due.reminder_id
+      JOIN af_user au ON au.uid = due.uid
+      WHERE au.deleted_at IS NULL
+      ORDER BY r.scheduled_at
+    "#,
+  )
+  .bind(limit)
+  .fetch_all(executor)
+  .await?;
+  Ok(reminders)
+}
@@ -0,0 +1,272 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use database_entity::dto::{AFRole, AFWorkspacePermission};
+use shared_entity::dto::role_dto::CustomRole;
+use sqlx::{Executor, FromRow, PgPool, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug, FromRow)]
+struct AFCustomRoleRow {
+  role_id: Uuid,
+  workspace_id: Uuid,
+  name: String,
+  base_role_id: i32,
+  permissions: Vec<String>,
+  created_at: DateTime<Utc>,
+}
+
+impl From<AFCustomRoleRow> for CustomRole {
+  fn from(row: AFCustomRoleRow) -> Self {
+    CustomRole {
+      role_id: row.role_id,
+      workspace_id: row.workspace_id,
+      name: row.name,
+      base_role: AFRole::from(row.base_role_id),
+      permissions: permissions_from_names(&row.permissions),
+      created_at: row.created_at,
+    }
+  }
+}
+
+/// Permissions that are no longer supported are ignored.
+pub fn permissions_from_names(names: &[String]) -> Vec<AFWorkspacePermission> {
+  names
+    .iter()
+    .filter_map(|name| AFWorkspacePermission::from_name(name))
+    .collect()
+}
+
+fn permission_names(permissions: &[AFWorkspacePermission]) -> Vec<String> {
+  permissions
+    .iter()
+    .map(|permission| permission.as_str().to_string())
+    .collect()
+}
+
+/// The permissions of a member that has a custom role, see
+/// [select_workspace_member_custom_permissions].
+#[derive(Debug, FromRow)]
+pub struct AFWorkspaceMemberCustomPermissionRow {
+  pub uid: i64,
+  pub workspace_id: Uuid,
+  pub permissions: Vec<String>,
+}
+
+/// The role of a member and, if any, its custom role. See [select_workspace_member_role_info].
+#[derive(Debug, FromRow)]
+pub struct AFWorkspaceMemberRoleInfo {
+  pub role_id: i32,
+  pub custom_role_id: Option<Uuid>,
+  pub custom_permissions: Option<Vec<String>>,
+}
+
+/// Returns `None` if the workspace already has a role with this name.
+pub async fn insert_custom_role<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  name: &str,
+  base_role: &AFRole,
+  permissions: &[AFWorkspacePermission],
+  created_by: i64,
+) -> Result<Option<CustomRole>, AppError> {
+  let row: Option<AFCustomRoleRow> = sqlx::query_as(
+    r#"
+      INSERT INTO af_workspace_custom_role (workspace_id, name, base_role_id, permissions, created_by)
+      VALUES ($1, $2, $3, $4, $5)
+      ON CONFLICT (workspace_id, name) DO NOTHING
+      RETURNING role_id, workspace_id, name, base_role_id, permissions, created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(name)
+  .bind(i32::from(base_role))
+  .bind(permission_names(permissions))
+  .bind(created_by)
+  .fetch_optional(executor)
+  .await?;
+  Ok(row.map(CustomRole::from))
+}
+
+pub async fn select_custom_roles<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+) -> Result<Vec<CustomRole>, AppError> {
+  let rows: Vec<AFCustomRoleRow> = sqlx::query_as(
+    r#"
+      SELECT role_id, workspace_id, name, base_role_id, permissions, created_at
+      FROM af_workspace_custom_role
+      WHERE workspace_id = $1
+      ORDER BY created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(CustomRole::from).collect())
+}
+
+pub async fn select_custom_role<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  role_id: &Uuid,
+) -> Result<Option<CustomRole>, AppError> {
+  let row: Option<AFCustomRoleRow> = sqlx::query_as(
+    r#"
+      SELECT role_id, workspace_id, name, base_role_id, permissions, created_at
+      FROM af_workspace_custom_role
+      WHERE workspace_id = $1 AND role_id = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(role_id)
+  .fetch_optional(executor)
+  .await?;
+  Ok(row.map(CustomRole::from))
+}
+
+/// Updates the given fields of the role. Returns `None` if the workspace has no such role.
+pub async fn update_custom_role<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  role_id: &Uuid,
+  name: Option<&str>,
+  base_role: Option<&AFRole>,
+  permissions: Option<&[AFWorkspacePermission]>,
+) -> Result<Option<CustomRole>, AppError> {
+  let row: Option<AFCustomRoleRow> = sqlx::query_as(
+    r#"
+      UPDATE af_workspace_custom_role
+      SET name = COALESCE($3, name),
+        base_role_id = COALESCE($4, base_role_id),
+        permissions = COALESCE($5, permissions)
+      WHERE workspace_id = $1 AND role_id = $2
+      RETURNING role_id, workspace_id, name, base_role_id, permissions, created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(role_id)
+  .bind(name)
+  .bind(base_role.map(i32::from))
+  .bind(permissions.map(permission_names))
+  .fetch_optional(executor)
+  .await?;
+  Ok(row.map(CustomRole::from))
+}
+
+/// The members that had the role fall back to the default permissions of their role. Returns
+/// false if the workspace has no such role.
+pub async fn delete_custom_role<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  role_id: &Uuid,
+) -> Result<bool, AppError> {
+  let result = sqlx::query(
+    r#"
+      DELETE FROM af_workspace_custom_role
+      WHERE workspace_id = $1 AND role_id = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(role_id)
+  .execute(executor)
+  .await?;
+  Ok(result.rows_affected() > 0)
+}
+
+/// Returns the uid of the members that have the custom role.
+pub async fn select_custom_role_member_uids<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  role_id: &Uuid,
+) -> Result<Vec<i64>, AppError> {
+  let uids = sqlx::query_scalar(
+    r#"
+      SELECT uid FROM af_workspace_member
+      WHERE custom_role_id = $1
+    "#,
+  )
+  .bind(role_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(uids)
+}
+
+/// Sets the custom role of the member. The role of the member is set to `role` if given.
+/// Returns false if the user is not a member of the workspace.
+pub async fn update_workspace_member_custom_role<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+  custom_role_id: Option<&Uuid>,
+  role: Option<&AFRole>,
+) -> Result<bool, AppError> {
+  let result = sqlx::query(
+    r#"
+      UPDATE af_workspace_member
+      SET custom_role_id = $3,
+        role_id = COALESCE($4, role_id)
+      WHERE workspace_id = $1 AND uid = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .bind(custom_role_id)
+  .bind(role.map(i32::from))
+  .execute(executor)
+  .await?;
+  Ok(result.rows_affected() > 0)
+}
+
+/// Sets the role of all the members that have the custom role.
+pub async fn update_custom_role_members_role<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  role_id: &Uuid,
+  role: &AFRole,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      UPDATE af_workspace_member
+      SET role_id = $2
+      WHERE custom_role_id = $1
+    "#,
+  )
+  .bind(role_id)
+  .bind(i32::from(role))
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+pub async fn select_workspace_member_role_info<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<Option<AFWorkspaceMemberRoleInfo>, AppError> {
+  let info = sqlx::query_as(
+    r#"
+      SELECT m.role_id, m.custom_role_id, r.permissions AS custom_permissions
+      FROM af_workspace_member m
+        LEFT JOIN af_workspace_custom_role r ON r.role_id = m.custom_role_id
+      WHERE m.workspace_id = $1 AND m.uid = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .fetch_optional(executor)
+  .await?;
+  Ok(info)
+}
+
+/// Used to load the access control policies of the members that have a custom role.
+pub async fn select_workspace_member_custom_permissions(
+  pg_pool: &PgPool,
+) -> Result<Vec<AFWorkspaceMemberCustomPermissionRow>, sqlx::Error> {
+  sqlx::query_as(
+    r#"
+      SELECT m.uid, m.workspace_id, r.permissions
+      FROM af_workspace_member m
+        JOIN af_workspace_custom_role r ON r.role_id = m.custom_role_id
+    "#,
+  )
+  .fetch_all(pg_pool)
+  .await
+}
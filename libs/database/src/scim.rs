@@ -0,0 +1,362 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use shared_entity::dto::scim_dto::{ScimEmail, ScimMeta, ScimName, ScimUser, SCIM_USER_SCHEMA};
+use sqlx::{Executor, FromRow, Postgres};
+use uuid::Uuid;
+
+/// A member of the workspace, or a user that was deprovisioned from it.
+#[derive(Debug, Clone, FromRow)]
+pub struct AFScimUserRow {
+  pub uid: i64,
+  pub uuid: Uuid,
+  pub email: String,
+  pub name: String,
+  pub external_id: Option<String>,
+  /// True if the user is a member of the workspace.
+  pub active: bool,
+  /// True if the user was created by the provisioning.
+  pub managed: bool,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+impl From<AFScimUserRow> for ScimUser {
+  fn from(row: AFScimUserRow) -> Self {
+    ScimUser {
+      schemas: vec![SCIM_USER_SCHEMA.to_string()],
+      id: row.uid.to_string(),
+      external_id: row.external_id,
+      user_name: row.email.clone(),
+      name: ScimName {
+        formatted: Some(row.name.clone()),
+        ..Default::default()
+      },
+      display_name: row.name,
+      emails: vec![ScimEmail {
+        value: row.email,
+        primary: true,
+      }],
+      active: row.active,
+      meta: ScimMeta {
+        resource_type: "User".to_string(),
+        created: row.created_at,
+        last_modified: row.updated_at,
+      },
+    }
+  }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AFScimGroupRow {
+  pub group_id: Uuid,
+  pub name: String,
+  pub external_id: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AFScimGroupMemberRow {
+  pub group_id: Uuid,
+  pub uid: i64,
+  pub email: String,
+}
+
+/// Returns the members of the workspace and the users deprovisioned from it, ordered by uid.
+/// The users can be filtered by email or by id in the identity provider.
+pub async fn select_scim_users<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  email: Option<&str>,
+  external_id: Option<&str>,
+  offset: i64,
+  limit: i64,
+) -> Result<Vec<AFScimUserRow>, AppError> {
+  let rows = sqlx::query_as(
+    r#"
+      SELECT u.uid, u.uuid, u.email, u.name, s.external_id,
+        (m.uid IS NOT NULL) AS active,
+        COALESCE(s.managed, FALSE) AS managed,
+        COALESCE(s.created_at, u.created_at, NOW()) AS created_at,
+        COALESCE(s.updated_at, u.updated_at, NOW()) AS updated_at
+      FROM af_user u
+        LEFT JOIN af_workspace_member m ON m.uid = u.uid AND m.workspace_id = $1
+        LEFT JOIN af_scim_user s ON s.uid = u.uid AND s.workspace_id = $1
+      WHERE (m.uid IS NOT NULL OR s.uid IS NOT NULL)
+        AND ($2::TEXT IS NULL OR LOWER(u.email) = LOWER($2))
+        AND ($3::TEXT IS NULL OR s.external_id = $3)
+      ORDER BY u.uid
+      OFFSET $4
+      LIMIT $5
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(email)
+  .bind(external_id)
+  .bind(offset)
+  .bind(limit)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows)
+}
+
+/// The number of users [select_scim_users] returns without offset and limit.
+pub async fn select_scim_user_count<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  email: Option<&str>,
+  external_id: Option<&str>,
+) -> Result<i64, AppError> {
+  let count = sqlx::query_scalar(
+    r#"
+      SELECT COUNT(*)
+      FROM af_user u
+        LEFT JOIN af_workspace_member m ON m.uid = u.uid AND m.workspace_id = $1
+        LEFT JOIN af_scim_user s ON s.uid = u.uid AND s.workspace_id = $1
+      WHERE (m.uid IS NOT NULL OR s.uid IS NOT NULL)
+        AND ($2::TEXT IS NULL OR LOWER(u.email) = LOWER($2))
+        AND ($3::TEXT IS NULL OR s.external_id = $3)
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(email)
+  .bind(external_id)
+  .fetch_one(executor)
+  .await?;
+  Ok(count)
+}
+
+/// Returns `None` if the user is neither a member of the workspace nor deprovisioned from it.
+pub async fn select_scim_user<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<Option<AFScimUserRow>, AppError> {
+  let row = sqlx::query_as(
+    r#"
+      SELECT u.uid, u.uuid, u.email, u.name, s.external_id,
+        (m.uid IS NOT NULL) AS active,
+        COALESCE(s.managed, FALSE) AS managed,
+        COALESCE(s.created_at, u.created_at, NOW()) AS created_at,
+        COALESCE(s.updated_at, u.updated_at, NOW()) AS updated_at
+      FROM af_user u
+        LEFT JOIN af_workspace_member m ON m.uid = u.uid AND m.workspace_id = $1
+        LEFT JOIN af_scim_user s ON s.uid = u.uid AND s.workspace_id = $1
+      WHERE u.uid = $2 AND (m.uid IS NOT NULL OR s.uid IS NOT NULL)
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .fetch_optional(executor)
+  .await?;
+  Ok(row)
+}
+
+/// Records that the identity provider knows the user. A user that was once created by the
+/// provisioning stays managed, and the external id is kept if none is given.
+pub async fn upsert_scim_user<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+  external_id: Option<&str>,
+  managed: bool,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_scim_user (workspace_id, uid, external_id, managed)
+      VALUES ($1, $2, $3, $4)
+      ON CONFLICT (workspace_id, uid)
+      DO UPDATE SET external_id = COALESCE(EXCLUDED.external_id, af_scim_user.external_id),
+        managed = af_scim_user.managed OR EXCLUDED.managed,
+        updated_at = NOW()
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .bind(external_id)
+  .bind(managed)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+pub async fn update_scim_user_external_id<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+  external_id: Option<&str>,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_scim_user (workspace_id, uid, external_id)
+      VALUES ($1, $2, $3)
+      ON CONFLICT (workspace_id, uid)
+      DO UPDATE SET external_id = EXCLUDED.external_id, updated_at = NOW()
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .bind(external_id)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+pub async fn delete_scim_user<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      DELETE FROM af_scim_user
+      WHERE workspace_id = $1 AND uid = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Returns whether the user is a member of a workspace shared with other users, besides the given
+/// one. The personal workspace created with the account doesn't count.
+pub async fn select_user_has_other_shared_workspace<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<bool, AppError> {
+  let exists = sqlx::query_scalar(
+    r#"
+      SELECT EXISTS (
+        SELECT 1 FROM af_workspace_member m
+        WHERE m.uid = $2 AND m.workspace_id <> $1
+          AND EXISTS (
+            SELECT 1 FROM af_workspace_member other
+            WHERE other.workspace_id = m.workspace_id AND other.uid <> $2
+          )
+      )
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .fetch_one(executor)
+  .await?;
+  Ok(exists)
+}
+
+/// Returns the groups of the workspace ordered by creation, filtered by name or by id in the
+/// identity provider.
+pub async fn select_scim_groups<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  name: Option<&str>,
+  external_id: Option<&str>,
+  offset: i64,
+  limit: i64,
+) -> Result<Vec<AFScimGroupRow>, AppError> {
+  let rows = sqlx::query_as(
+    r#"
+      SELECT group_id, name, external_id, created_at
+      FROM af_workspace_group
+      WHERE workspace_id = $1
+        AND ($2::TEXT IS NULL OR name = $2)
+        AND ($3::TEXT IS NULL OR external_id = $3)
+      ORDER BY created_at
+      OFFSET $4
+      LIMIT $5
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(name)
+  .bind(external_id)
+  .bind(offset)
+  .bind(limit)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows)
+}
+
+/// The number of groups [select_scim_groups] returns without offset and limit.
+pub async fn select_scim_group_count<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  name: Option<&str>,
+  external_id: Option<&str>,
+) -> Result<i64, AppError> {
+  let count = sqlx::query_scalar(
+    r#"
+      SELECT COUNT(*)
+      FROM af_workspace_group
+      WHERE workspace_id = $1
+        AND ($2::TEXT IS NULL OR name = $2)
+        AND ($3::TEXT IS NULL OR external_id = $3)
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(name)
+  .bind(external_id)
+  .fetch_one(executor)
+  .await?;
+  Ok(count)
+}
+
+pub async fn select_scim_group<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+) -> Result<Option<AFScimGroupRow>, AppError> {
+  let row = sqlx::query_as(
+    r#"
+      SELECT group_id, name, external_id, created_at
+      FROM af_workspace_group
+      WHERE workspace_id = $1 AND group_id = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(group_id)
+  .fetch_optional(executor)
+  .await?;
+  Ok(row)
+}
+
+pub async fn update_workspace_group_external_id<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+  external_id: Option<&str>,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      UPDATE af_workspace_group
+      SET external_id = $3
+      WHERE workspace_id = $1 AND group_id = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(group_id)
+  .bind(external_id)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Returns the members of the groups, ordered by the time they joined.
+pub async fn select_scim_group_members<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  group_ids: &[Uuid],
+) -> Result<Vec<AFScimGroupMemberRow>, AppError> {
+  let rows = sqlx::query_as(
+    r#"
+      SELECT m.group_id, m.uid, u.email
+      FROM af_workspace_group_member m
+        JOIN af_user u ON u.uid = m.uid
+      WHERE m.group_id = ANY($1)
+      ORDER BY m.created_at
+    "#,
+  )
+  .bind(group_ids)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows)
+}
@@ -0,0 +1,193 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use database_entity::dto::AFAccessLevel;
+use shared_entity::dto::share_link_dto::ShareLink;
+use sqlx::{Executor, FromRow, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug, FromRow)]
+struct AFShareLinkRow {
+  link_id: Uuid,
+  view_id: Uuid,
+  access_level: i32,
+  token_prefix: String,
+  has_password: bool,
+  created_by: i64,
+  created_at: DateTime<Utc>,
+  expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<AFShareLinkRow> for ShareLink {
+  fn from(row: AFShareLinkRow) -> Self {
+    ShareLink {
+      link_id: row.link_id,
+      view_id: row.view_id,
+      access_level: AFAccessLevel::from(row.access_level),
+      token_prefix: row.token_prefix,
+      has_password: row.has_password,
+      created_by: row.created_by,
+      created_at: row.created_at,
+      expires_at: row.expires_at,
+    }
+  }
+}
+
+/// A valid share link, see [select_share_link_by_hash].
+#[derive(Debug, Clone, FromRow)]
+pub struct AFShareLinkTokenRow {
+  pub link_id: Uuid,
+  pub workspace_id: Uuid,
+  pub view_id: Uuid,
+  pub access_level: i32,
+  pub password_hash: Option<String>,
+  pub created_by: i64,
+  pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// The `database_id` is set for the links on a database view: its collab is the database.
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_share_link<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  database_id: Option<&Uuid>,
+  created_by: i64,
+  access_level: AFAccessLevel,
+  token_hash: &str,
+  token_prefix: &str,
+  password_hash: Option<&str>,
+  expires_at: Option<DateTime<Utc>>,
+) -> Result<ShareLink, AppError> {
+  let row: AFShareLinkRow = sqlx::query_as(
+    r#"
+      INSERT INTO af_share_link
+        (workspace_id, view_id, database_id, created_by, access_level, token_hash, token_prefix,
+          password_hash, expires_at)
+      VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+      RETURNING link_id, view_id, access_level, token_prefix,
+        (password_hash IS NOT NULL) AS has_password, created_by, created_at, expires_at
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(database_id)
+  .bind(created_by)
+  .bind(access_level as i32)
+  .bind(token_hash)
+  .bind(token_prefix)
+  .bind(password_hash)
+  .bind(expires_at)
+  .fetch_one(executor)
+  .await?;
+  Ok(row.into())
+}
+
+/// Returns the links of the page, including the expired ones, from the most recent one.
+pub async fn select_share_links<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+) -> Result<Vec<ShareLink>, AppError> {
+  let rows: Vec<AFShareLinkRow> = sqlx::query_as(
+    r#"
+      SELECT link_id, view_id, access_level, token_prefix,
+        (password_hash IS NOT NULL) AS has_password, created_by, created_at, expires_at
+      FROM af_share_link
+      WHERE workspace_id = $1 AND view_id = $2
+      ORDER BY created_at DESC
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(ShareLink::from).collect())
+}
+
+/// Returns false if the link doesn't exist.
+pub async fn delete_share_link<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  link_id: &Uuid,
+) -> Result<bool, AppError> {
+  let result = sqlx::query(
+    r#"
+      DELETE FROM af_share_link
+      WHERE workspace_id = $1 AND view_id = $2 AND link_id = $3
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(link_id)
+  .execute(executor)
+  .await?;
+  Ok(result.rows_affected() > 0)
+}
+
+/// Returns the link with the token hash, unless it expired or its creator is no longer a member
+/// of the workspace.
+pub async fn select_share_link_by_hash<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  token_hash: &str,
+) -> Result<Option<AFShareLinkTokenRow>, AppError> {
+  let row = sqlx::query_as(
+    r#"
+      SELECT l.link_id, l.workspace_id, l.view_id, l.access_level, l.password_hash, l.created_by,
+        l.expires_at
+      FROM af_share_link l
+        JOIN af_workspace_member m ON m.workspace_id = l.workspace_id AND m.uid = l.created_by
+      WHERE l.token_hash = $1
+        AND (l.expires_at IS NULL OR l.expires_at > NOW())
+    "#,
+  )
+  .bind(token_hash)
+  .fetch_optional(executor)
+  .await?;
+  Ok(row)
+}
+
+pub async fn upsert_share_link_user<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  link_id: &Uuid,
+  uid: i64,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_share_link_user (link_id, uid)
+      VALUES ($1, $2)
+      ON CONFLICT (link_id, uid) DO NOTHING
+    "#,
+  )
+  .bind(link_id)
+  .bind(uid)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Returns the highest access level the user got on the collab by joining the valid share links
+/// of its page. The collab of a database view is its database, see [insert_share_link].
+pub async fn select_share_link_access_level<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  object_id: &Uuid,
+  uid: i64,
+) -> Result<Option<AFAccessLevel>, AppError> {
+  let access_level: Option<i32> = sqlx::query_scalar(
+    r#"
+      SELECT MAX(l.access_level)
+      FROM af_share_link l
+        JOIN af_share_link_user u ON u.link_id = l.link_id
+        JOIN af_workspace_member m ON m.workspace_id = l.workspace_id AND m.uid = l.created_by
+      WHERE l.workspace_id = $1 AND (l.view_id = $2 OR l.database_id = $2) AND u.uid = $3
+        AND (l.expires_at IS NULL OR l.expires_at > NOW())
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(object_id)
+  .bind(uid)
+  .fetch_one(executor)
+  .await?;
+  Ok(access_level.map(AFAccessLevel::from))
+}
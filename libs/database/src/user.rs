@@ -192,6 +192,22 @@ pub async fn select_uid_from_email<'a, E: Executor<'a, Database = Postgres>>(
   Ok(uid)
 }
 
+/// Returns None if no user signed up with the email.
+pub async fn select_optional_uid_from_email<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  email: &str,
+) -> Result<Option<i64>, AppError> {
+  let uid = sqlx::query_scalar(
+    r#"
+      SELECT uid FROM af_user WHERE email = $1
+    "#,
+  )
+  .bind(email)
+  .fetch_optional(executor)
+  .await?;
+  Ok(uid)
+}
+
 #[inline]
 pub async fn is_user_exist<'a, E: Executor<'a, Database = Postgres>>(
   executor: E,
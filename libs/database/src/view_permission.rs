@@ -0,0 +1,364 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use database_entity::dto::AFAccessLevel;
+use shared_entity::dto::view_permission_dto::{ViewGroupPermission, ViewPermission};
+use sqlx::{Executor, FromRow, PgPool, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug, FromRow)]
+struct AFViewPermissionRow {
+  view_id: Uuid,
+  uid: i64,
+  email: String,
+  name: String,
+  access_level: i32,
+  granted_by: Option<i64>,
+  created_at: DateTime<Utc>,
+}
+
+impl From<AFViewPermissionRow> for ViewPermission {
+  fn from(row: AFViewPermissionRow) -> Self {
+    ViewPermission {
+      view_id: row.view_id,
+      uid: row.uid,
+      email: row.email,
+      name: row.name,
+      access_level: AFAccessLevel::from(row.access_level),
+      granted_by: row.granted_by,
+      created_at: row.created_at,
+    }
+  }
+}
+
+#[derive(Debug, FromRow)]
+struct AFViewGroupPermissionRow {
+  view_id: Uuid,
+  group_id: Uuid,
+  name: String,
+  access_level: i32,
+  granted_by: Option<i64>,
+  created_at: DateTime<Utc>,
+}
+
+impl From<AFViewGroupPermissionRow> for ViewGroupPermission {
+  fn from(row: AFViewGroupPermissionRow) -> Self {
+    ViewGroupPermission {
+      view_id: row.view_id,
+      group_id: row.group_id,
+      name: row.name,
+      access_level: AFAccessLevel::from(row.access_level),
+      granted_by: row.granted_by,
+      created_at: row.created_at,
+    }
+  }
+}
+
+/// An access level resulting from a grant, see [insert_effective_view_permissions].
+#[derive(Debug, Clone)]
+pub struct AFEffectiveViewPermission {
+  pub view_id: Uuid,
+  pub access_level: AFAccessLevel,
+  pub source_view_id: Uuid,
+}
+
+/// The access level of a member on a page, used to load the access control policies.
+#[derive(Debug, FromRow)]
+pub struct AFEffectiveViewPermissionRow {
+  pub uid: i64,
+  pub view_id: Uuid,
+  pub access_level: i32,
+}
+
+/// Grants the access level, replacing the previous grant of the user on the view.
+pub async fn upsert_view_permission<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  uid: i64,
+  access_level: AFAccessLevel,
+  granted_by: i64,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_view_permission (workspace_id, view_id, uid, access_level, granted_by)
+      VALUES ($1, $2, $3, $4, $5)
+      ON CONFLICT (workspace_id, view_id, uid)
+      DO UPDATE SET access_level = EXCLUDED.access_level,
+        granted_by = EXCLUDED.granted_by,
+        created_at = NOW()
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(uid)
+  .bind(access_level as i32)
+  .bind(granted_by)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Returns false if the user has no grant on the view.
+pub async fn delete_view_permission<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  uid: i64,
+) -> Result<bool, AppError> {
+  let result = sqlx::query(
+    r#"
+      DELETE FROM af_view_permission
+      WHERE workspace_id = $1 AND view_id = $2 AND uid = $3
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(uid)
+  .execute(executor)
+  .await?;
+  Ok(result.rows_affected() > 0)
+}
+
+/// Returns the grants made on the view itself, not the inherited ones.
+pub async fn select_view_permissions<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+) -> Result<Vec<ViewPermission>, AppError> {
+  let rows: Vec<AFViewPermissionRow> = sqlx::query_as(
+    r#"
+      SELECT p.view_id, p.uid, u.email, u.name, p.access_level, p.granted_by, p.created_at
+      FROM af_view_permission p
+        JOIN af_user u ON u.uid = p.uid
+      WHERE p.workspace_id = $1 AND p.view_id = $2
+      ORDER BY p.created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(ViewPermission::from).collect())
+}
+
+/// Returns the access levels granted to the user on the views of the workspace, either to the user
+/// or to one of its groups. If both were granted an access level on a view, the highest one is
+/// returned.
+pub async fn select_user_view_grants<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<Vec<(Uuid, AFAccessLevel)>, AppError> {
+  let rows: Vec<(Uuid, i32)> = sqlx::query_as(
+    r#"
+      SELECT view_id, MAX(access_level) AS access_level
+      FROM (
+        SELECT view_id, access_level
+        FROM af_view_permission
+        WHERE workspace_id = $1 AND uid = $2
+        UNION ALL
+        SELECT p.view_id, p.access_level
+        FROM af_view_group_permission p
+          JOIN af_workspace_group_member m ON m.group_id = p.group_id
+        WHERE p.workspace_id = $1 AND m.uid = $2
+      ) AS grants
+      GROUP BY view_id
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .fetch_all(executor)
+  .await?;
+  Ok(
+    rows
+      .into_iter()
+      .map(|(view_id, access_level)| (view_id, AFAccessLevel::from(access_level)))
+      .collect(),
+  )
+}
+
+/// Returns the users whose access levels on the views of the workspace depend on a grant: the
+/// users that were granted an access level, the members of the groups that were granted one, and
+/// the users that still have an access level from a previous grant.
+pub async fn select_view_permission_grantees<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+) -> Result<Vec<i64>, AppError> {
+  let uids = sqlx::query_scalar(
+    r#"
+      SELECT uid FROM af_view_permission
+      WHERE workspace_id = $1
+      UNION
+      SELECT m.uid
+      FROM af_view_group_permission p
+        JOIN af_workspace_group_member m ON m.group_id = p.group_id
+      WHERE p.workspace_id = $1
+      UNION
+      SELECT uid FROM af_view_effective_permission
+      WHERE workspace_id = $1
+    "#,
+  )
+  .bind(workspace_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(uids)
+}
+
+/// Grants the access level, replacing the previous grant of the group on the view.
+pub async fn upsert_view_group_permission<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  group_id: &Uuid,
+  access_level: AFAccessLevel,
+  granted_by: i64,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      INSERT INTO af_view_group_permission
+        (workspace_id, view_id, group_id, access_level, granted_by)
+      VALUES ($1, $2, $3, $4, $5)
+      ON CONFLICT (workspace_id, view_id, group_id)
+      DO UPDATE SET access_level = EXCLUDED.access_level,
+        granted_by = EXCLUDED.granted_by,
+        created_at = NOW()
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(group_id)
+  .bind(access_level as i32)
+  .bind(granted_by)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Returns false if the group has no grant on the view.
+pub async fn delete_view_group_permission<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  group_id: &Uuid,
+) -> Result<bool, AppError> {
+  let result = sqlx::query(
+    r#"
+      DELETE FROM af_view_group_permission
+      WHERE workspace_id = $1 AND view_id = $2 AND group_id = $3
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .bind(group_id)
+  .execute(executor)
+  .await?;
+  Ok(result.rows_affected() > 0)
+}
+
+/// Returns the grants made to groups on the view itself, not the inherited ones.
+pub async fn select_view_group_permissions<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+) -> Result<Vec<ViewGroupPermission>, AppError> {
+  let rows: Vec<AFViewGroupPermissionRow> = sqlx::query_as(
+    r#"
+      SELECT p.view_id, p.group_id, g.name, p.access_level, p.granted_by, p.created_at
+      FROM af_view_group_permission p
+        JOIN af_workspace_group g ON g.group_id = p.group_id
+      WHERE p.workspace_id = $1 AND p.view_id = $2
+      ORDER BY p.created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(view_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(ViewGroupPermission::from).collect())
+}
+
+/// Deletes the effective access levels of the user in the workspace and returns them.
+pub async fn delete_effective_view_permissions<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<Vec<(Uuid, AFAccessLevel)>, AppError> {
+  let rows: Vec<(Uuid, i32)> = sqlx::query_as(
+    r#"
+      DELETE FROM af_view_effective_permission
+      WHERE workspace_id = $1 AND uid = $2
+      RETURNING view_id, access_level
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .fetch_all(executor)
+  .await?;
+  Ok(
+    rows
+      .into_iter()
+      .map(|(view_id, access_level)| (view_id, AFAccessLevel::from(access_level)))
+      .collect(),
+  )
+}
+
+pub async fn insert_effective_view_permissions<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  uid: i64,
+  permissions: &[AFEffectiveViewPermission],
+) -> Result<(), AppError> {
+  let view_ids: Vec<Uuid> = permissions.iter().map(|p| p.view_id).collect();
+  let access_levels: Vec<i32> = permissions.iter().map(|p| p.access_level as i32).collect();
+  let source_view_ids: Vec<Uuid> = permissions.iter().map(|p| p.source_view_id).collect();
+  sqlx::query(
+    r#"
+      INSERT INTO af_view_effective_permission
+        (workspace_id, view_id, uid, access_level, source_view_id)
+      SELECT $1, view_id, $2, access_level, source_view_id
+      FROM UNNEST($3::UUID[], $4::INT[], $5::UUID[]) AS t(view_id, access_level, source_view_id)
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(uid)
+  .bind(view_ids)
+  .bind(access_levels)
+  .bind(source_view_ids)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Deletes the grants of a user that is no longer a member of the workspace.
+pub async fn delete_view_permissions_of_user(
+  txn: &mut sqlx::Transaction<'_, Postgres>,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<(), AppError> {
+  sqlx::query("DELETE FROM af_view_permission WHERE workspace_id = $1 AND uid = $2")
+    .bind(workspace_id)
+    .bind(uid)
+    .execute(txn.as_mut())
+    .await?;
+  sqlx::query("DELETE FROM af_view_effective_permission WHERE workspace_id = $1 AND uid = $2")
+    .bind(workspace_id)
+    .bind(uid)
+    .execute(txn.as_mut())
+    .await?;
+  Ok(())
+}
+
+/// Used to load the access control policies of the pages shared with the members.
+pub async fn select_all_effective_view_permissions(
+  pg_pool: &PgPool,
+) -> Result<Vec<AFEffectiveViewPermissionRow>, sqlx::Error> {
+  sqlx::query_as(
+    r#"
+      SELECT e.uid, e.view_id, e.access_level
+      FROM af_view_effective_permission e
+        JOIN af_workspace_member m ON m.workspace_id = e.workspace_id AND m.uid = e.uid
+    "#,
+  )
+  .fetch_all(pg_pool)
+  .await
+}
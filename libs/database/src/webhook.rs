@@ -0,0 +1,314 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use shared_entity::dto::webhook_dto::{
+  Webhook, WebhookDelivery, WebhookDeliveryStatus, WebhookEvent,
+};
+use sqlx::{Executor, FromRow, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug, FromRow)]
+struct AFWebhookRow {
+  webhook_id: Uuid,
+  workspace_id: Uuid,
+  url: String,
+  events: Vec<String>,
+  enabled: bool,
+  created_at: DateTime<Utc>,
+}
+
+impl From<AFWebhookRow> for Webhook {
+  fn from(row: AFWebhookRow) -> Self {
+    Webhook {
+      webhook_id: row.webhook_id,
+      workspace_id: row.workspace_id,
+      url: row.url,
+      // Events that are no longer supported are ignored.
+      events: row
+        .events
+        .iter()
+        .filter_map(|event| WebhookEvent::from_name(event))
+        .collect(),
+      enabled: row.enabled,
+      created_at: row.created_at,
+    }
+  }
+}
+
+#[derive(Debug, FromRow)]
+struct AFWebhookDeliveryRow {
+  delivery_id: Uuid,
+  event: String,
+  payload: serde_json::Value,
+  status: i16,
+  attempts: i32,
+  response_status: Option<i32>,
+  error: Option<String>,
+  created_at: DateTime<Utc>,
+  delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<AFWebhookDeliveryRow> for WebhookDelivery {
+  fn from(row: AFWebhookDeliveryRow) -> Self {
+    WebhookDelivery {
+      delivery_id: row.delivery_id,
+      event: row.event,
+      payload: row.payload,
+      status: WebhookDeliveryStatus::from(row.status),
+      attempts: row.attempts,
+      response_status: row.response_status,
+      error: row.error,
+      created_at: row.created_at,
+      delivered_at: row.delivered_at,
+    }
+  }
+}
+
+/// An enabled webhook subscribed to an event, see [select_webhook_targets].
+#[derive(Debug, Clone, FromRow)]
+pub struct AFWebhookTarget {
+  pub webhook_id: Uuid,
+  pub url: String,
+  pub secret: String,
+}
+
+/// A delivery claimed by the worker, see [claim_due_webhook_deliveries].
+#[derive(Debug, Clone, FromRow)]
+pub struct AFPendingWebhookDelivery {
+  pub delivery_id: Uuid,
+  pub event: String,
+  pub payload: serde_json::Value,
+  pub attempts: i32,
+  pub url: String,
+  pub secret: String,
+}
+
+fn event_names(events: &[WebhookEvent]) -> Vec<String> {
+  events
+    .iter()
+    .map(|event| event.as_str().to_string())
+    .collect()
+}
+
+pub async fn insert_webhook<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  url: &str,
+  secret: &str,
+  events: &[WebhookEvent],
+  created_by: i64,
+) -> Result<Webhook, AppError> {
+  let row: AFWebhookRow = sqlx::query_as(
+    r#"
+      INSERT INTO af_webhook (workspace_id, url, secret, events, created_by)
+      VALUES ($1, $2, $3, $4, $5)
+      RETURNING webhook_id, workspace_id, url, events, enabled, created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(url)
+  .bind(secret)
+  .bind(event_names(events))
+  .bind(created_by)
+  .fetch_one(executor)
+  .await?;
+  Ok(row.into())
+}
+
+pub async fn select_webhooks<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+) -> Result<Vec<Webhook>, AppError> {
+  let rows: Vec<AFWebhookRow> = sqlx::query_as(
+    r#"
+      SELECT webhook_id, workspace_id, url, events, enabled, created_at
+      FROM af_webhook
+      WHERE workspace_id = $1
+      ORDER BY created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(Webhook::from).collect())
+}
+
+/// Updates the given fields of the webhook. Returns `None` if the workspace has no such webhook.
+pub async fn update_webhook<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  webhook_id: &Uuid,
+  url: Option<&str>,
+  events: Option<&[WebhookEvent]>,
+  enabled: Option<bool>,
+) -> Result<Option<Webhook>, AppError> {
+  let row: Option<AFWebhookRow> = sqlx::query_as(
+    r#"
+      UPDATE af_webhook
+      SET url = COALESCE($3, url),
+        events = COALESCE($4, events),
+        enabled = COALESCE($5, enabled)
+      WHERE workspace_id = $1 AND webhook_id = $2
+      RETURNING webhook_id, workspace_id, url, events, enabled, created_at
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(webhook_id)
+  .bind(url)
+  .bind(events.map(event_names))
+  .bind(enabled)
+  .fetch_optional(executor)
+  .await?;
+  Ok(row.map(Webhook::from))
+}
+
+/// Deletes the webhook and its delivery log. Returns false if the workspace has no such webhook.
+pub async fn delete_webhook<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  webhook_id: &Uuid,
+) -> Result<bool, AppError> {
+  let result = sqlx::query(
+    r#"
+      DELETE FROM af_webhook
+      WHERE workspace_id = $1 AND webhook_id = $2
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(webhook_id)
+  .execute(executor)
+  .await?;
+  Ok(result.rows_affected() > 0)
+}
+
+/// Returns the most recent deliveries of the webhook first.
+pub async fn select_webhook_deliveries<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  webhook_id: &Uuid,
+  limit: i64,
+) -> Result<Vec<WebhookDelivery>, AppError> {
+  let rows: Vec<AFWebhookDeliveryRow> = sqlx::query_as(
+    r#"
+      SELECT d.delivery_id, d.event, d.payload, d.status, d.attempts, d.response_status, d.error,
+        d.created_at, d.delivered_at
+      FROM af_webhook_delivery d
+        JOIN af_webhook w ON w.webhook_id = d.webhook_id
+      WHERE w.workspace_id = $1 AND d.webhook_id = $2
+      ORDER BY d.created_at DESC
+      LIMIT $3
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(webhook_id)
+  .bind(limit)
+  .fetch_all(executor)
+  .await?;
+  Ok(rows.into_iter().map(WebhookDelivery::from).collect())
+}
+
+/// Returns the enabled webhooks of the workspace that are subscribed to the event.
+pub async fn select_webhook_targets<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  event: &str,
+) -> Result<Vec<AFWebhookTarget>, AppError> {
+  let targets = sqlx::query_as(
+    r#"
+      SELECT webhook_id, url, secret
+      FROM af_webhook
+      WHERE workspace_id = $1 AND enabled AND $2 = ANY(events)
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(event)
+  .fetch_all(executor)
+  .await?;
+  Ok(targets)
+}
+
+/// Logs a delivery that is about to be attempted. Until `next_attempt_at` the delivery is not
+/// picked up by [claim_due_webhook_deliveries].
+pub async fn insert_webhook_delivery<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  webhook_id: &Uuid,
+  event: &str,
+  payload: &serde_json::Value,
+  next_attempt_at: DateTime<Utc>,
+) -> Result<Uuid, AppError> {
+  let delivery_id = sqlx::query_scalar(
+    r#"
+      INSERT INTO af_webhook_delivery (webhook_id, event, payload, next_attempt_at)
+      VALUES ($1, $2, $3, $4)
+      RETURNING delivery_id
+    "#,
+  )
+  .bind(webhook_id)
+  .bind(event)
+  .bind(payload)
+  .bind(next_attempt_at)
+  .fetch_one(executor)
+  .await?;
+  Ok(delivery_id)
+}
+
+/// Claims the pending deliveries that are due for a retry. The claimed deliveries are postponed
+/// to `lease_until`, so another worker doesn't pick them up while they are being sent.
+pub async fn claim_due_webhook_deliveries<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  limit: i64,
+  lease_until: DateTime<Utc>,
+) -> Result<Vec<AFPendingWebhookDelivery>, AppError> {
+  let deliveries = sqlx::query_as(
+    r#"
+      UPDATE af_webhook_delivery d
+      SET next_attempt_at = $2
+      FROM af_webhook w
+      WHERE w.webhook_id = d.webhook_id
+        AND d.delivery_id IN (
+          SELECT delivery_id FROM af_webhook_delivery
+          WHERE status = 0 AND next_attempt_at <= NOW()
+          ORDER BY next_attempt_at
+          LIMIT $1
+          FOR UPDATE SKIP LOCKED
+        )
+      RETURNING d.delivery_id, d.event, d.payload, d.attempts, w.url, w.secret
+    "#,
+  )
+  .bind(limit)
+  .bind(lease_until)
+  .fetch_all(executor)
+  .await?;
+  Ok(deliveries)
+}
+
+/// Records the result of an attempt. `next_attempt_at` is only used if the delivery is still
+/// pending.
+pub async fn update_webhook_delivery_attempt<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  delivery_id: &Uuid,
+  status: WebhookDeliveryStatus,
+  response_status: Option<i32>,
+  error: Option<&str>,
+  next_attempt_at: DateTime<Utc>,
+) -> Result<(), AppError> {
+  sqlx::query(
+    r#"
+      UPDATE af_webhook_delivery
+      SET status = $2,
+        attempts = attempts + 1,
+        response_status = $3,
+        error = $4,
+        next_attempt_at = $5,
+        delivered_at = CASE WHEN $2 = 1 THEN NOW() ELSE delivered_at END
+      WHERE delivery_id = $1
+    "#,
+  )
+  .bind(delivery_id)
+  .bind(status as i16)
+  .bind(response_status)
+  .bind(error)
+  .bind(next_attempt_at)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
@@ -0,0 +1,86 @@
+use app_error::AppError;
+use chrono::{DateTime, Utc};
+use shared_entity::dto::workspace_activity_dto::WorkspaceActivityKind;
+use sqlx::{Executor, FromRow, Postgres};
+use uuid::Uuid;
+
+/// An activity to add to the feed of a workspace.
+#[derive(Debug, Clone)]
+pub struct WorkspaceActivityParams {
+  pub actor_uid: Option<i64>,
+  pub kind: WorkspaceActivityKind,
+  pub object_id: String,
+  pub metadata: serde_json::Value,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AFWorkspaceActivityRow {
+  pub activity_id: i64,
+  pub kind: i16,
+  pub actor_uid: Option<i64>,
+  pub actor_name: Option<String>,
+  pub object_id: String,
+  pub metadata: serde_json::Value,
+  pub created_at: DateTime<Utc>,
+}
+
+pub async fn insert_workspace_activities<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  activities: &[WorkspaceActivityParams],
+) -> Result<(), AppError> {
+  if activities.is_empty() {
+    return Ok(());
+  }
+  let actor_uids: Vec<Option<i64>> = activities.iter().map(|a| a.actor_uid).collect();
+  let kinds: Vec<i16> = activities.iter().map(|a| a.kind as i16).collect();
+  let object_ids: Vec<String> = activities.iter().map(|a| a.object_id.clone()).collect();
+  let metadata: Vec<serde_json::Value> = activities.iter().map(|a| a.metadata.clone()).collect();
+  sqlx::query(
+    r#"
+      INSERT INTO af_workspace_activity (workspace_id, actor_uid, kind, object_id, metadata)
+      SELECT $1, activity.actor_uid, activity.kind, activity.object_id, activity.metadata
+      FROM UNNEST($2::BIGINT[], $3::SMALLINT[], $4::TEXT[], $5::JSONB[])
+        WITH ORDINALITY AS activity(actor_uid, kind, object_id, metadata, position)
+      ORDER BY activity.position
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(actor_uids)
+  .bind(kinds)
+  .bind(object_ids)
+  .bind(metadata)
+  .execute(executor)
+  .await?;
+  Ok(())
+}
+
+/// Activities of the workspace, the most recent first.
+pub async fn select_workspace_activities<'a, E: Executor<'a, Database = Postgres>>(
+  executor: E,
+  workspace_id: &Uuid,
+  since: Option<DateTime<Utc>>,
+  before: Option<i64>,
+  limit: i64,
+) -> Result<Vec<AFWorkspaceActivityRow>, AppError> {
+  let activities = sqlx::query_as(
+    r#"
+      SELECT a.activity_id, a.kind, a.actor_uid, u.name AS actor_name, a.object_id, a.metadata,
+        a.created_at
+      FROM af_workspace_activity a
+        LEFT JOIN af_user u ON u.uid = a.actor_uid
+      WHERE a.workspace_id = $1
+        AND ($2::TIMESTAMPTZ IS NULL OR a.created_at >= $2)
+        AND ($3::BIGINT IS NULL OR a.activity_id < $3)
+      ORDER BY a.activity_id DESC
+      LIMIT $4
+    "#,
+  )
+  .bind(workspace_id)
+  .bind(since)
+  .bind(before)
+  .bind(limit)
+  .fetch_all(executor)
+  .await?;
+  Ok(activities)
+}
@@ -4,4 +4,6 @@ pub mod env_util;
 pub mod file_util;
 #[cfg(feature = "request_util")]
 pub mod reqwest;
+#[cfg(feature = "url_util")]
+pub mod url_util;
 pub mod validate;
@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Error};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use url::Url;
+
+/// A url checked by [check_public_http_url] and the addresses its host resolved to.
+#[derive(Debug, Clone)]
+pub struct PublicHttpUrl {
+  pub url: Url,
+  pub host: String,
+  /// The request must be sent to these addresses: resolving the host again could return another
+  /// address, that was not checked.
+  pub addrs: Vec<SocketAddr>,
+}
+
+/// Resolves the host of a user supplied url and rejects it when any of its addresses is in the
+/// loopback, private, link-local, unique-local or unspecified ranges, so the server can't be
+/// used to send requests to the internal network.
+pub async fn check_public_http_url(url: &str) -> Result<PublicHttpUrl, Error> {
+  let parsed = Url::parse(url).map_err(|err| anyhow!("Invalid url {}: {}", url, err))?;
+  match parsed.scheme() {
+    "http" | "https" => {},
+    scheme => return Err(anyhow!("Unsupported url scheme: {}", scheme)),
+  }
+  let host = parsed
+    .host_str()
+    .ok_or_else(|| anyhow!("Url {} has no host", url))?;
+  let port = parsed
+    .port_or_known_default()
+    .ok_or_else(|| anyhow!("Url {} has no port", url))?;
+  let host = host.trim_start_matches('[').trim_end_matches(']');
+  let addrs = tokio::net::lookup_host((host, port))
+    .await
+    .map_err(|err| anyhow!("Failed to resolve {}: {}", host, err))?
+    .collect::<Vec<_>>();
+  if addrs.is_empty() {
+    return Err(anyhow!("{} doesn't resolve to any address", host));
+  }
+  if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(&addr.ip())) {
+    return Err(anyhow!(
+      "{} resolves to the non public address {}",
+      host,
+      addr.ip()
+    ));
+  }
+  Ok(PublicHttpUrl {
+    host: host.to_string(),
+    url: parsed,
+    addrs,
+  })
+}
+
+pub fn is_public_ip(ip: &IpAddr) -> bool {
+  match ip {
+    IpAddr::V4(ip) => is_public_ipv4(ip),
+    IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+      Some(ip) => is_public_ipv4(&ip),
+      None => is_public_ipv6(ip),
+    },
+  }
+}
+
+fn is_public_ipv4(ip: &Ipv4Addr) -> bool {
+  let [a, b, ..] = ip.octets();
+  !(ip.is_loopback()
+    || ip.is_private()
+    || ip.is_link_local()
+    || ip.is_unspecified()
+    || ip.is_broadcast()
+    || ip.is_documentation()
+    // 0.0.0.0/8
+    || a == 0
+    // Shared address space, 100.64.0.0/10
+    || (a == 100 && (b & 0b1100_0000) == 64))
+}
+
+fn is_public_ipv6(ip: &Ipv6Addr) -> bool {
+  let first_segment = ip.segments()[0];
+  !(ip.is_loopback()
+    || ip.is_unspecified()
+    // Unique local, fc00::/7
+    || (first_segment & 0xfe00) == 0xfc00
+    // Link-local, fe80::/10
+    || (first_segment & 0xffc0) == 0xfe80)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn is_public_ip_test() {
+    for ip in [
+      "127.0.0.1",
+      "10.1.2.3",
+      "172.16.0.1",
+      "192.168.1.1",
+      "169.254.169.254",
+      "0.0.0.0",
+      "100.64.0.1",
+      "::1",
+      "::",
+      "fd00::1",
+      "fe80::1",
+      "::ffff:127.0.0.1",
+    ] {
+      assert!(!is_public_ip(&ip.parse().unwrap()), "{}", ip);
+    }
+    for ip in ["1.1.1.1", "93.184.216.34", "2606:4700:4700::1111"] {
+      assert!(is_public_ip(&ip.parse().unwrap()), "{}", ip);
+    }
+  }
+}
@@ -20,4 +20,43 @@ pub struct AccessRequest {
   pub view: AccessRequestView,
   pub status: AccessRequestStatus,
   pub created_at: chrono::DateTime<chrono::Utc>,
+  /// Message of the requester.
+  #[serde(default)]
+  pub reason: Option<String>,
+  /// Message of the owner who rejected the request.
+  #[serde(default)]
+  pub rejection_reason: Option<String>,
+  /// A request still pending at this time expires.
+  pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// An access request as listed to the owners of the workspace.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AccessRequestItem {
+  pub request_id: Uuid,
+  pub view_id: Uuid,
+  /// `None` if the view was deleted from the folder.
+  pub view: Option<AccessRequestView>,
+  pub requester: AccessRequesterInfo,
+  pub status: AccessRequestStatus,
+  pub reason: Option<String>,
+  pub rejection_reason: Option<String>,
+  pub created_at: chrono::DateTime<chrono::Utc>,
+  pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RepeatedAccessRequestItem {
+  pub items: Vec<AccessRequestItem>,
+}
+
+/// Filters of the access requests of a workspace. The most recent requests are returned first.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueryAccessRequestParams {
+  pub workspace_id: Uuid,
+  /// Only the requests to access this page.
+  #[serde(default)]
+  pub view_id: Option<Uuid>,
+  #[serde(default)]
+  pub status: Option<AccessRequestStatus>,
 }
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use database_entity::dto::{AFAccessLevel, AFRole};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use uuid::Uuid;
+use validator::Validate;
+
+/// What a workspace API token is allowed to do. The token never grants more than the role of the
+/// member who created it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(i32)]
+pub enum ApiTokenScope {
+  /// Only read requests, such as `GET` and `HEAD`.
+  Read = 1,
+  /// Read and write the content of the workspace.
+  Write = 2,
+  /// Also manage the members, invitations and settings of the workspace.
+  Admin = 3,
+}
+
+impl ApiTokenScope {
+  /// The role the member must have in the workspace to create a token with this scope.
+  pub fn required_role(&self) -> AFRole {
+    match self {
+      ApiTokenScope::Read => AFRole::Guest,
+      ApiTokenScope::Write => AFRole::Member,
+      ApiTokenScope::Admin => AFRole::Owner,
+    }
+  }
+
+  /// The access level the token has on the objects of the workspace.
+  pub fn access_level(&self) -> AFAccessLevel {
+    match self {
+      ApiTokenScope::Read => AFAccessLevel::ReadOnly,
+      ApiTokenScope::Write => AFAccessLevel::ReadAndWrite,
+      ApiTokenScope::Admin => AFAccessLevel::FullAccess,
+    }
+  }
+
+  /// The name of the scope in the OAuth2 flow.
+  pub fn as_oauth_scope(&self) -> &'static str {
+    match self {
+      ApiTokenScope::Read => "workspace:read",
+      ApiTokenScope::Write => "workspace:write",
+      ApiTokenScope::Admin => "workspace:admin",
+    }
+  }
+
+  /// Parses the `scope` parameter of the OAuth2 flow. Several scopes can be requested separated by
+  /// spaces, the widest one is granted.
+  pub fn from_oauth_scope(scope: &str) -> Option<Self> {
+    scope
+      .split_whitespace()
+      .map(|scope| match scope {
+        "workspace:read" => Some(ApiTokenScope::Read),
+        "workspace:write" => Some(ApiTokenScope::Write),
+        "workspace:admin" => Some(ApiTokenScope::Admin),
+        _ => None,
+      })
+      .collect::<Option<Vec<_>>>()?
+      .into_iter()
+      .max_by_key(|scope| *scope as i32)
+  }
+}
+
+impl From<i32> for ApiTokenScope {
+  fn from(value: i32) -> Self {
+    match value {
+      3 => ApiTokenScope::Admin,
+      2 => ApiTokenScope::Write,
+      _ => ApiTokenScope::Read,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct CreateApiTokenParams {
+  #[validate(length(min = 1, max = 100))]
+  pub name: String,
+  pub scope: ApiTokenScope,
+  /// The token never expires if not set.
+  #[serde(default)]
+  pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+  pub token_id: Uuid,
+  pub workspace_id: Uuid,
+  pub name: String,
+  pub scope: ApiTokenScope,
+  /// The first characters of the token, to recognize it.
+  pub token_prefix: String,
+  pub created_at: DateTime<Utc>,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub last_used_at: Option<DateTime<Utc>>,
+  /// Set if the token was issued to an OAuth2 client.
+  #[serde(default)]
+  pub client_id: Option<String>,
+}
+
+/// Returned once when the token is created. The server only keeps a hash of the token, it can't
+/// be retrieved afterwards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+  pub token: String,
+  pub api_token: ApiToken,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedApiToken {
+  pub items: Vec<ApiToken>,
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Security-relevant actions recorded in the audit log.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum AuditAction {
+  #[serde(rename = "user.signed_in")]
+  UserSignedIn,
+  #[serde(rename = "member.invited")]
+  MemberInvited,
+  #[serde(rename = "member.role_updated")]
+  MemberRoleUpdated,
+  #[serde(rename = "member.removed")]
+  MemberRemoved,
+  #[serde(rename = "collab_member.updated")]
+  CollabMemberUpdated,
+  #[serde(rename = "page.published")]
+  PagePublished,
+  #[serde(rename = "page.unpublished")]
+  PageUnpublished,
+  #[serde(rename = "access_request.approved")]
+  AccessRequestApproved,
+  #[serde(rename = "access_request.rejected")]
+  AccessRequestRejected,
+  #[serde(rename = "blob.deleted")]
+  BlobDeleted,
+  #[serde(rename = "workspace.deleted")]
+  WorkspaceDeleted,
+  #[serde(rename = "workspace.settings_updated")]
+  WorkspaceSettingsUpdated,
+  #[serde(rename = "group.members_updated")]
+  GroupMembersUpdated,
+  #[serde(rename = "member.provisioned")]
+  MemberProvisioned,
+  #[serde(rename = "member.deprovisioned")]
+  MemberDeprovisioned,
+  #[serde(rename = "share_link.created")]
+  ShareLinkCreated,
+  #[serde(rename = "share_link.revoked")]
+  ShareLinkRevoked,
+  #[serde(rename = "commenter.banned")]
+  CommenterBanned,
+  #[serde(rename = "commenter.unbanned")]
+  CommenterUnbanned,
+}
+
+impl AuditAction {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      AuditAction::UserSignedIn => "user.signed_in",
+      AuditAction::MemberInvited => "member.invited",
+      AuditAction::MemberRoleUpdated => "member.role_updated",
+      AuditAction::MemberRemoved => "member.removed",
+      AuditAction::CollabMemberUpdated => "collab_member.updated",
+      AuditAction::PagePublished => "page.published",
+      AuditAction::PageUnpublished => "page.unpublished",
+      AuditAction::AccessRequestApproved => "access_request.approved",
+      AuditAction::AccessRequestRejected => "access_request.rejected",
+      AuditAction::BlobDeleted => "blob.deleted",
+      AuditAction::WorkspaceDeleted => "workspace.deleted",
+      AuditAction::WorkspaceSettingsUpdated => "workspace.settings_updated",
+      AuditAction::GroupMembersUpdated => "group.members_updated",
+      AuditAction::MemberProvisioned => "member.provisioned",
+      AuditAction::MemberDeprovisioned => "member.deprovisioned",
+      AuditAction::ShareLinkCreated => "share_link.created",
+      AuditAction::ShareLinkRevoked => "share_link.revoked",
+      AuditAction::CommenterBanned => "commenter.banned",
+      AuditAction::CommenterUnbanned => "commenter.unbanned",
+    }
+  }
+
+  /// The kind of object the action is applied to. The id of the object is stored along.
+  pub fn object_type(&self) -> &'static str {
+    match self {
+      AuditAction::UserSignedIn => "user",
+      AuditAction::MemberInvited
+      | AuditAction::MemberRoleUpdated
+      | AuditAction::MemberRemoved
+      | AuditAction::MemberProvisioned
+      | AuditAction::MemberDeprovisioned => "member",
+      AuditAction::CollabMemberUpdated => "collab",
+      AuditAction::PagePublished | AuditAction::PageUnpublished => "view",
+      AuditAction::AccessRequestApproved | AuditAction::AccessRequestRejected => "access_request",
+      AuditAction::BlobDeleted => "blob",
+      AuditAction::WorkspaceDeleted | AuditAction::WorkspaceSettingsUpdated => "workspace",
+      AuditAction::GroupMembersUpdated => "group",
+      AuditAction::ShareLinkCreated | AuditAction::ShareLinkRevoked => "share_link",
+      AuditAction::CommenterBanned | AuditAction::CommenterUnbanned => "user",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+  pub log_id: i64,
+  pub workspace_id: Option<Uuid>,
+  pub actor_uid: Option<i64>,
+  /// Email of the actor, if the user still exists.
+  pub actor_email: Option<String>,
+  pub action: String,
+  pub object_type: String,
+  pub object_id: Option<String>,
+  pub metadata: serde_json::Value,
+  pub ip_address: Option<String>,
+  pub user_agent: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedAuditLogEntry {
+  pub items: Vec<AuditLogEntry>,
+  /// Pass it as `before` to get the next page. `None` if there are no more entries.
+  pub next_cursor: Option<i64>,
+}
+
+/// Filters of the audit log of a workspace. The entries are returned from the most recent one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryAuditLogParams {
+  pub actor_uid: Option<i64>,
+  pub action: Option<AuditAction>,
+  pub object_type: Option<String>,
+  pub object_id: Option<String>,
+  /// Only the entries created at or after this time.
+  pub since: Option<DateTime<Utc>>,
+  /// Only the entries created before this time.
+  pub until: Option<DateTime<Utc>>,
+  /// Only the entries older than the entry with this id.
+  pub before: Option<i64>,
+  pub limit: Option<i64>,
+}
@@ -246,6 +246,9 @@ impl CreateChatMessageParams {
 pub struct GetChatMessageParams {
   pub cursor: MessageCursor,
   pub limit: u64,
+  /// The branch to read the messages from. The active branch of the chat is used when it's not set.
+  #[serde(default)]
+  pub branch_id: Option<i64>,
 }
 
 impl GetChatMessageParams {
@@ -253,6 +256,7 @@ impl GetChatMessageParams {
     Self {
       cursor: MessageCursor::Offset(offset),
       limit,
+      branch_id: None,
     }
   }
 
@@ -260,12 +264,14 @@ impl GetChatMessageParams {
     Self {
       cursor: MessageCursor::AfterMessageId(after_message_id),
       limit,
+      branch_id: None,
     }
   }
   pub fn before_message_id(before_message_id: i64, limit: u64) -> Self {
     Self {
       cursor: MessageCursor::BeforeMessageId(before_message_id),
       limit,
+      branch_id: None,
     }
   }
 
@@ -273,8 +279,14 @@ impl GetChatMessageParams {
     Self {
       cursor: MessageCursor::NextBack,
       limit,
+      branch_id: None,
     }
   }
+
+  pub fn with_branch_id(mut self, branch_id: i64) -> Self {
+    self.branch_id = Some(branch_id);
+    self
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -308,6 +320,121 @@ pub struct RepeatedChatMessage {
   pub total: i64,
 }
 
+/// Full text search over the messages of all chats in a workspace. The query supports the web
+/// search syntax: quoted phrases, `or` and `-` for exclusion.
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct SearchChatMessageParams {
+  #[validate(custom = "validate_not_empty_str")]
+  pub query: String,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub limit: Option<u64>,
+  #[serde(default)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub offset: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessageSearchResult {
+  pub chat_id: String,
+  pub chat_name: String,
+  pub message: ChatMessage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedChatMessageSearchResult {
+  pub items: Vec<ChatMessageSearchResult>,
+  pub has_more: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatExportFormat {
+  #[default]
+  Json,
+  Markdown,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportChatParams {
+  #[serde(default)]
+  pub format: ChatExportFormat,
+}
+
+/// The whole conversation of a chat, messages are ordered from the oldest to the newest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatExport {
+  pub chat_id: String,
+  pub name: String,
+  pub created_at: DateTime<Utc>,
+  pub messages: Vec<ChatMessage>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(i32)]
+pub enum ChatParticipantRole {
+  Owner = 0,
+  #[default]
+  Member = 1,
+  /// A member of the workspace who doesn't participate in a chat without participants. It can
+  /// only read the chat.
+  Viewer = 2,
+}
+
+impl From<i32> for ChatParticipantRole {
+  fn from(value: i32) -> Self {
+    match value {
+      0 => ChatParticipantRole::Owner,
+      2 => ChatParticipantRole::Viewer,
+      _ => ChatParticipantRole::Member,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatParticipant {
+  pub uid: i64,
+  pub name: String,
+  pub email: String,
+  pub role: ChatParticipantRole,
+  pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedChatParticipant {
+  pub items: Vec<ChatParticipant>,
+}
+
+/// Adds workspace members to a chat. Only the owner of the chat can add participants.
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct AddChatParticipantsParams {
+  #[validate(length(min = 1))]
+  pub emails: Vec<String>,
+}
+
+/// Removes participants from a chat. The owner can remove anyone, other participants can only
+/// remove themselves.
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct RemoveChatParticipantsParams {
+  #[validate(length(min = 1))]
+  pub emails: Vec<String>,
+}
+
+/// A chat the user participates in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatInfo {
+  pub chat_id: String,
+  pub name: String,
+  pub created_at: DateTime<Utc>,
+  pub role: ChatParticipantRole,
+  pub participant_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedChatInfo {
+  pub items: Vec<ChatInfo>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatSettings {
   // Currently we have not used the `name` field in the ChatSettings
@@ -354,6 +481,40 @@ impl ChatAuthor {
   }
 }
 
+/// A branch is created when a question is edited. It contains the messages of the parent branch
+/// created before `fork_message_id`, followed by the edited question and its answers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatBranch {
+  pub branch_id: i64,
+  pub parent_branch_id: i64,
+  pub fork_message_id: i64,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedChatBranch {
+  /// Branch 0 is the main branch of the chat.
+  pub active_branch_id: i64,
+  pub branches: Vec<ChatBranch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateActiveChatBranchParams {
+  pub branch_id: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectAnswerVersionParams {
+  pub answer_message_id: i64,
+}
+
+/// All the answers generated for a question, from the oldest to the newest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedAnswerVersion {
+  pub versions: Vec<ChatMessage>,
+  pub selected_message_id: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateChatMessageResponse {
   pub answer: Option<ChatMessage>,
@@ -369,3 +530,20 @@ pub struct CreateAnswerMessageParams {
 
   pub question_message_id: i64,
 }
+
+/// Source of the metadata items that record the tools called by the AI, see [ChatToolCallMetadata].
+pub const CHAT_TOOL_METADATA_SOURCE: &str = "tool";
+
+/// A tool called by the AI to answer a question. It's appended to the metadata of the answer, next
+/// to the other sources of the answer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatToolCallMetadata {
+  pub id: String,
+  /// Always [CHAT_TOOL_METADATA_SOURCE].
+  pub source: String,
+  pub name: String,
+  pub arguments: serde_json::Value,
+  pub result: serde_json::Value,
+  #[serde(default)]
+  pub is_error: bool,
+}
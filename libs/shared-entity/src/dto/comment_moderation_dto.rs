@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use database_entity::dto::AFWebUser;
+use serde::{Deserialize, Serialize};
+
+/// Comment settings of a published view, changed by its moderators: the publisher of the view and
+/// the owners of its workspace.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PublishedViewCommentSettings {
+  /// When false, no comment nor reaction can be added. The existing comments are kept.
+  pub comments_enabled: bool,
+  /// When true, the new comments are only shown once approved by a moderator.
+  pub require_approval: bool,
+}
+
+impl Default for PublishedViewCommentSettings {
+  fn default() -> Self {
+    Self {
+      comments_enabled: true,
+      require_approval: false,
+    }
+  }
+}
+
+/// Rules applied to the comments on all the published views of a workspace. They don't apply to
+/// the moderators.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CommentModeration {
+  /// Comments containing one of these keywords, ignoring the case, are rejected.
+  pub blocked_keywords: Vec<String>,
+  /// Maximum number of comments a user can write in an hour, no limit if None.
+  pub max_comments_per_hour: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BanCommenterParams {
+  pub reason: Option<String>,
+  /// Also deletes the comments the user already wrote on the published views of the workspace.
+  #[serde(default)]
+  pub delete_comments: bool,
+}
+
+/// A user that can't comment nor react on the published views of a workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BannedCommenter {
+  pub user: AFWebUser,
+  pub reason: Option<String>,
+  pub banned_at: DateTime<Utc>,
+}
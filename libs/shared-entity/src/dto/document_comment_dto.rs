@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use database_entity::dto::AFWebUser;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Where a comment thread is attached in the document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DocumentCommentAnchor {
+  pub block_id: String,
+  /// Range of the text of the block, in characters, the end excluded. The whole block is
+  /// commented when there is no range.
+  pub range_start: Option<i32>,
+  pub range_end: Option<i32>,
+  /// The commented text, shown next to the thread once the block is edited or deleted.
+  pub quoted_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentComment {
+  pub comment_id: Uuid,
+  pub thread_id: Uuid,
+  /// None if the account of the author was deleted.
+  pub author: Option<AFWebUser>,
+  pub author_uid: Option<i64>,
+  /// Empty if the comment was deleted.
+  pub content: String,
+  /// uids of the mentioned members.
+  pub mentions: Vec<i64>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+  pub is_deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentCommentThread {
+  pub thread_id: Uuid,
+  pub view_id: Uuid,
+  pub anchor: DocumentCommentAnchor,
+  pub created_by: Option<i64>,
+  pub created_at: DateTime<Utc>,
+  pub resolved_by: Option<i64>,
+  pub resolved_at: Option<DateTime<Utc>>,
+  /// Oldest first. The first comment started the thread.
+  pub comments: Vec<DocumentComment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedDocumentCommentThread {
+  pub threads: Vec<DocumentCommentThread>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryDocumentCommentThreads {
+  /// The resolved threads are skipped unless true.
+  #[serde(default)]
+  pub include_resolved: bool,
+}
+
+/// Starts a thread with its first comment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDocumentCommentThreadParams {
+  pub anchor: DocumentCommentAnchor,
+  pub content: String,
+  /// uids of the members mentioned in the comment.
+  #[serde(default)]
+  pub mentions: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDocumentCommentParams {
+  pub content: String,
+  #[serde(default)]
+  pub mentions: Vec<i64>,
+}
+
+/// Replaces the content and the mentions of a comment. Only its author can edit it.
+pub type UpdateDocumentCommentParams = CreateDocumentCommentParams;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateDocumentCommentThreadParams {
+  pub resolved: bool,
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use database_entity::dto::AFAccessLevel;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct CreateWorkspaceGroupParams {
+  #[validate(length(min = 1, max = 50))]
+  pub name: String,
+}
+
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct UpdateWorkspaceGroupParams {
+  #[validate(length(min = 1, max = 50))]
+  pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceGroup {
+  pub group_id: Uuid,
+  pub workspace_id: Uuid,
+  pub name: String,
+  pub member_count: i64,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedWorkspaceGroup {
+  pub items: Vec<WorkspaceGroup>,
+}
+
+/// Adds or removes members of a group. The users must be members of the workspace.
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct WorkspaceGroupMembersParams {
+  #[validate(length(min = 1))]
+  pub emails: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceGroupMember {
+  pub uid: i64,
+  pub email: String,
+  pub name: String,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedWorkspaceGroupMember {
+  pub items: Vec<WorkspaceGroupMember>,
+}
+
+/// Grants an access level on a collab to all the members of a group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpsertCollabGroupMemberParams {
+  pub group_id: Uuid,
+  pub access_level: AFAccessLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollabGroupMember {
+  pub group_id: Uuid,
+  pub name: String,
+  pub access_level: AFAccessLevel,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedCollabGroupMember {
+  pub items: Vec<CollabGroupMember>,
+}
@@ -1,12 +1,25 @@
 pub mod access_request_dto;
 pub mod ai_dto;
+pub mod api_token_dto;
+pub mod audit_log_dto;
 pub mod auth_dto;
 pub mod billing_dto;
 pub mod chat_dto;
+pub mod comment_moderation_dto;
+pub mod document_comment_dto;
 pub mod file_dto;
+pub mod group_dto;
 pub mod history_dto;
 pub mod import_dto;
+pub mod notification_dto;
+pub mod oauth_dto;
 pub mod publish_dto;
+pub mod role_dto;
+pub mod scim_dto;
 pub mod search_dto;
 pub mod server_info_dto;
+pub mod share_link_dto;
+pub mod view_permission_dto;
+pub mod webhook_dto;
+pub mod workspace_activity_dto;
 pub mod workspace_dto;
@@ -0,0 +1,168 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use uuid::Uuid;
+
+#[derive(Serialize_repr, Deserialize_repr, Eq, PartialEq, Debug, Clone, Copy)]
+#[repr(i16)]
+pub enum NotificationKind {
+  /// The user was mentioned in a document.
+  DocumentMention = 0,
+  /// The user was mentioned in a comment on a document.
+  CommentMention = 1,
+  /// Someone replied to a comment thread the user participates in.
+  CommentReply = 2,
+  /// The user was invited to a workspace.
+  WorkspaceInvitation = 3,
+  /// Someone requested access to a page of a workspace the user owns.
+  AccessRequest = 4,
+  AccessRequestApproved = 5,
+  AccessRequestRejected = 6,
+  /// A reminder set in a document or a database is due.
+  Reminder = 7,
+}
+
+impl NotificationKind {
+  pub fn from_i16(value: i16) -> Option<Self> {
+    match value {
+      0 => Some(NotificationKind::DocumentMention),
+      1 => Some(NotificationKind::CommentMention),
+      2 => Some(NotificationKind::CommentReply),
+      3 => Some(NotificationKind::WorkspaceInvitation),
+      4 => Some(NotificationKind::AccessRequest),
+      5 => Some(NotificationKind::AccessRequestApproved),
+      6 => Some(NotificationKind::AccessRequestRejected),
+      7 => Some(NotificationKind::Reminder),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+  pub notification_id: Uuid,
+  pub workspace_id: Uuid,
+  pub kind: NotificationKind,
+  pub view_id: Option<Uuid>,
+  /// The comment, invitation, access request or reminder the notification is about.
+  pub object_id: Option<String>,
+  /// The user that caused the notification, if any.
+  pub actor_uid: Option<i64>,
+  /// Depends on the kind, for example the name of the page and the beginning of the comment.
+  pub payload: serde_json::Value,
+  pub created_at: DateTime<Utc>,
+  pub read_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedNotification {
+  /// Newest first.
+  pub notifications: Vec<Notification>,
+  pub unread_count: i64,
+  pub has_more: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryNotifications {
+  /// Only the notifications of the workspace when set.
+  pub workspace_id: Option<Uuid>,
+  #[serde(default)]
+  pub unread_only: bool,
+  /// Only the notifications created before, to load the next page.
+  pub before: Option<DateTime<Utc>>,
+  /// Defaults to 50, at most 100.
+  pub limit: Option<u32>,
+}
+
+/// Marks the given notifications as read, or all the notifications of the user (of the workspace
+/// if set) when `notification_ids` is None.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarkNotificationsReadParams {
+  pub notification_ids: Option<Vec<Uuid>>,
+  pub workspace_id: Option<Uuid>,
+}
+
+/// Mutes the notifications from a workspace, or from a page of the workspace when `view_id` is
+/// set. Muting again replaces `muted_until`.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct NotificationMute {
+  pub workspace_id: Uuid,
+  pub view_id: Option<Uuid>,
+  /// Muted until unmuted when None.
+  pub muted_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedNotificationMute {
+  pub items: Vec<NotificationMute>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnmuteNotificationParams {
+  pub workspace_id: Uuid,
+  pub view_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct NotificationSettings {
+  /// When true, the unread notifications are summarised in an email.
+  pub email_digest_enabled: bool,
+}
+
+impl Default for NotificationSettings {
+  fn default() -> Self {
+    Self {
+      email_digest_enabled: true,
+    }
+  }
+}
+
+/// How often the email digest of the activity of a workspace is sent: the pages the user owns or
+/// follows that were edited, the new comments on them and the new members.
+#[derive(Serialize_repr, Deserialize_repr, Eq, PartialEq, Debug, Clone, Copy, Default)]
+#[repr(i16)]
+pub enum ActivityDigestFrequency {
+  Off = 0,
+  Daily = 1,
+  #[default]
+  Weekly = 2,
+}
+
+impl ActivityDigestFrequency {
+  pub fn from_i16(value: i16) -> Option<Self> {
+    match value {
+      0 => Some(ActivityDigestFrequency::Off),
+      1 => Some(ActivityDigestFrequency::Daily),
+      2 => Some(ActivityDigestFrequency::Weekly),
+      _ => None,
+    }
+  }
+}
+
+/// Sets the frequency of the activity digests of the workspace, or the default frequency of the
+/// user when `workspace_id` is None.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ActivityDigestSetting {
+  pub workspace_id: Option<Uuid>,
+  pub frequency: ActivityDigestFrequency,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivityDigestSettings {
+  /// Used for the workspaces without their own frequency.
+  pub frequency: ActivityDigestFrequency,
+  /// The workspaces with their own frequency.
+  pub workspaces: Vec<WorkspaceActivityDigestSetting>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct WorkspaceActivityDigestSetting {
+  pub workspace_id: Uuid,
+  pub frequency: ActivityDigestFrequency,
+}
+
+/// Makes the workspace use the default frequency of the user again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResetActivityDigestSettingParams {
+  pub workspace_id: Uuid,
+}
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Registers a third-party application. The redirect uri of an authorization request must match
+/// one of the `redirect_uris` exactly.
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct CreateOAuthClientParams {
+  #[validate(length(min = 1, max = 100))]
+  pub name: String,
+  #[validate(length(min = 1))]
+  pub redirect_uris: Vec<String>,
+  /// Confidential clients, such as server-side applications, get a secret. Public clients, such
+  /// as mobile or single-page applications, can't keep a secret and must use PKCE instead.
+  #[serde(default)]
+  pub confidential: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthClient {
+  pub client_id: String,
+  pub name: String,
+  pub redirect_uris: Vec<String>,
+  pub confidential: bool,
+  pub created_at: DateTime<Utc>,
+}
+
+/// Returned once when the client is registered. Only a hash of the secret is kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedOAuthClient {
+  pub client: OAuthClient,
+  pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedOAuthClient {
+  pub items: Vec<OAuthClient>,
+}
+
+/// Sent by the consent page once the user approved the access of the client to a workspace.
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct OAuthAuthorizeParams {
+  #[validate(length(min = 1))]
+  pub client_id: String,
+  #[validate(length(min = 1))]
+  pub redirect_uri: String,
+  pub workspace_id: Uuid,
+  /// Space separated scopes, see [crate::dto::api_token_dto::ApiTokenScope::from_oauth_scope].
+  pub scope: String,
+  #[serde(default)]
+  pub code_challenge: Option<String>,
+  #[serde(default)]
+  pub code_challenge_method: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthAuthorizeResponse {
+  pub code: String,
+}
+
+/// The form of the token request of the authorization code grant, RFC 6749 section 4.1.3, and of
+/// the refresh token grant, section 6. `code`, `redirect_uri` and `code_verifier` are only sent
+/// with the authorization code grant, `refresh_token` with the refresh token grant.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OAuthTokenParams {
+  pub grant_type: String,
+  #[serde(default)]
+  pub code: String,
+  #[serde(default)]
+  pub redirect_uri: String,
+  pub client_id: String,
+  #[serde(default)]
+  pub client_secret: Option<String>,
+  #[serde(default)]
+  pub code_verifier: Option<String>,
+  #[serde(default)]
+  pub refresh_token: Option<String>,
+}
+
+/// The access token is a workspace API token that expires after `expires_in` seconds. The client
+/// exchanges the refresh token for a new pair of tokens, and the user can revoke the access like
+/// any other API token of the workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthTokenResponse {
+  pub access_token: String,
+  pub token_type: String,
+  pub expires_in: i64,
+  pub refresh_token: String,
+  pub scope: String,
+  pub workspace_id: Uuid,
+}
+
+/// Error of the token endpoint, RFC 6749 section 5.2.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthErrorResponse {
+  pub error: String,
+  #[serde(default)]
+  pub error_description: Option<String>,
+}
@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 
 use super::workspace_dto::{ViewIcon, ViewLayout};
 
+/// Header carrying the password of a published view, when the view is protected by one.
+pub const PUBLISHED_VIEW_PASSWORD_HEADER: &str = "published-view-password";
+
 /// Copied from AppFlowy-IO/AppFlowy/frontend/rust-lib/flowy-folder-pub/src/entities.rs
 /// TODO(zack): make AppFlowy use from this crate instead
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -59,3 +62,44 @@ pub struct PublishDatabaseData {
   /// Relation view id map
   pub database_relations: HashMap<String, String>,
 }
+
+/// Sent over the websocket of a live published view each time the published blob changes. The
+/// reader fetches the blob again to get the new content.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PublishedViewUpdate {
+  pub view_id: uuid::Uuid,
+  pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Query of the analytics of the published views of a workspace. The range defaults to the last
+/// 30 days, both bounds are included.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryPublishedViewAnalytics {
+  pub view_id: Option<uuid::Uuid>,
+  pub start: Option<chrono::NaiveDate>,
+  pub end: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PublishedViewAnalytics {
+  pub view_id: uuid::Uuid,
+  pub view_count: i64,
+  /// Visitors are counted once per day, a visitor coming back on another day is counted again.
+  pub unique_visitor_count: i64,
+  pub referrers: Vec<PublishedViewReferrer>,
+  pub daily: Vec<PublishedViewDailyStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PublishedViewReferrer {
+  /// Host of the referring page, None for direct visits.
+  pub referrer: Option<String>,
+  pub view_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PublishedViewDailyStats {
+  pub day: chrono::NaiveDate,
+  pub view_count: i64,
+  pub unique_visitor_count: i64,
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use database_entity::dto::{AFRole, AFWorkspacePermission};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct CreateCustomRoleParams {
+  #[validate(length(min = 1, max = 50))]
+  pub name: String,
+  /// [AFRole::Member] or [AFRole::Guest]. Decides whether the members can edit the workspace.
+  pub base_role: AFRole,
+  pub permissions: Vec<AFWorkspacePermission>,
+}
+
+#[derive(Debug, Clone, Default, Validate, Serialize, Deserialize)]
+pub struct UpdateCustomRoleParams {
+  #[validate(length(min = 1, max = 50))]
+  pub name: Option<String>,
+  pub base_role: Option<AFRole>,
+  pub permissions: Option<Vec<AFWorkspacePermission>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomRole {
+  pub role_id: Uuid,
+  pub workspace_id: Uuid,
+  pub name: String,
+  pub base_role: AFRole,
+  pub permissions: Vec<AFWorkspacePermission>,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedCustomRole {
+  pub items: Vec<CustomRole>,
+}
+
+/// Assigns a custom role to a member, or removes it if `role_id` is `None`. A member without a
+/// custom role has the default permissions of its role.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignCustomRoleParams {
+  pub email: String,
+  pub role_id: Option<Uuid>,
+}
+
+/// What the user is allowed to do in the workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspacePermissions {
+  pub role: AFRole,
+  pub custom_role_id: Option<Uuid>,
+  pub permissions: Vec<AFWorkspacePermission>,
+}
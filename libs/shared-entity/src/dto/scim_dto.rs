@@ -0,0 +1,207 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const SCIM_USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const SCIM_GROUP_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const SCIM_LIST_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const SCIM_ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+pub const SCIM_SERVICE_PROVIDER_CONFIG_SCHEMA: &str =
+  "urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig";
+/// Media type of the SCIM requests and responses, RFC 7644 section 3.1.
+pub const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub formatted: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub given_name: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub family_name: Option<String>,
+}
+
+impl ScimName {
+  /// The formatted name, or the given and family names joined.
+  pub fn full_name(&self) -> Option<String> {
+    if let Some(formatted) = self
+      .formatted
+      .as_ref()
+      .filter(|name| !name.trim().is_empty())
+    {
+      return Some(formatted.trim().to_string());
+    }
+    let name = [&self.given_name, &self.family_name]
+      .into_iter()
+      .flatten()
+      .map(|part| part.trim())
+      .filter(|part| !part.is_empty())
+      .collect::<Vec<_>>()
+      .join(" ");
+    (!name.is_empty()).then_some(name)
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScimEmail {
+  pub value: String,
+  #[serde(default)]
+  pub primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+  pub resource_type: String,
+  pub created: DateTime<Utc>,
+  pub last_modified: DateTime<Utc>,
+}
+
+/// A user of the workspace: a member, or a user that was deprovisioned by the identity provider.
+/// The id is the uid of the user and the `userName` its email.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+  pub schemas: Vec<String>,
+  pub id: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub external_id: Option<String>,
+  pub user_name: String,
+  pub name: ScimName,
+  pub display_name: String,
+  pub emails: Vec<ScimEmail>,
+  /// False if the user is no longer a member of the workspace.
+  pub active: bool,
+  pub meta: ScimMeta,
+}
+
+/// Body of the requests that create or replace a user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserParams {
+  #[serde(default)]
+  pub external_id: Option<String>,
+  pub user_name: String,
+  #[serde(default)]
+  pub name: Option<ScimName>,
+  #[serde(default)]
+  pub display_name: Option<String>,
+  #[serde(default)]
+  pub emails: Vec<ScimEmail>,
+  #[serde(default = "default_active")]
+  pub active: bool,
+}
+
+fn default_active() -> bool {
+  true
+}
+
+impl ScimUserParams {
+  /// The primary email, or the `userName` if the identity provider sends no email.
+  pub fn email(&self) -> String {
+    self
+      .emails
+      .iter()
+      .find(|email| email.primary)
+      .or_else(|| self.emails.first())
+      .map(|email| email.value.clone())
+      .unwrap_or_else(|| self.user_name.clone())
+      .trim()
+      .to_lowercase()
+  }
+
+  pub fn full_name(&self) -> Option<String> {
+    self
+      .display_name
+      .as_ref()
+      .map(|name| name.trim().to_string())
+      .filter(|name| !name.is_empty())
+      .or_else(|| self.name.as_ref().and_then(ScimName::full_name))
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScimGroupMember {
+  /// Id of the user.
+  pub value: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub display: Option<String>,
+}
+
+/// A group of the workspace. The id is the id of the group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroup {
+  pub schemas: Vec<String>,
+  pub id: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub external_id: Option<String>,
+  pub display_name: String,
+  pub members: Vec<ScimGroupMember>,
+  pub meta: ScimMeta,
+}
+
+/// Body of the requests that create or replace a group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroupParams {
+  #[serde(default)]
+  pub external_id: Option<String>,
+  pub display_name: String,
+  #[serde(default)]
+  pub members: Vec<ScimGroupMember>,
+}
+
+/// Body of a PATCH request, RFC 7644 section 3.5.2.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScimPatchParams {
+  #[serde(rename = "Operations")]
+  pub operations: Vec<ScimPatchOperation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScimPatchOperation {
+  /// `add`, `replace` or `remove`. Some identity providers capitalize it.
+  pub op: String,
+  #[serde(default)]
+  pub path: Option<String>,
+  #[serde(default)]
+  pub value: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListQuery {
+  #[serde(default)]
+  pub filter: Option<String>,
+  /// 1-based index of the first resource.
+  #[serde(default)]
+  pub start_index: Option<i64>,
+  #[serde(default)]
+  pub count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScimListResponse<T> {
+  pub schemas: Vec<String>,
+  #[serde(rename = "totalResults")]
+  pub total_results: i64,
+  #[serde(rename = "startIndex")]
+  pub start_index: i64,
+  #[serde(rename = "itemsPerPage")]
+  pub items_per_page: i64,
+  #[serde(rename = "Resources")]
+  pub resources: Vec<T>,
+}
+
+/// Error response, RFC 7644 section 3.12.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimError {
+  pub schemas: Vec<String>,
+  /// The HTTP status code, as a string.
+  pub status: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub scim_type: Option<String>,
+  pub detail: String,
+}
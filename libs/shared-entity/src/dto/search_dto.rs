@@ -46,6 +46,8 @@ pub struct SearchDocumentResponseItem {
 pub enum SearchContentType {
   /// Document block contents displayed as plain text.
   PlainText = 0,
+  /// Text extracted from a file attached to the document, ie. PDF or DOCX.
+  Attachment = 1,
 }
 
 impl SearchContentType {
@@ -53,6 +55,7 @@ impl SearchContentType {
   pub fn from_record(content_type: i32) -> Option<Self> {
     match content_type {
       0 => Some(SearchContentType::PlainText),
+      1 => Some(SearchContentType::Attachment),
       _ => None,
     }
   }
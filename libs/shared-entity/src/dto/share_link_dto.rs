@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use database_entity::dto::AFAccessLevel;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::dto::workspace_dto::PageCollab;
+
+/// Header carrying the password of a share link, when the link is protected by one.
+pub const SHARE_LINK_PASSWORD_HEADER: &str = "share-link-password";
+
+/// Creates a link granting the access level on a page to anyone holding it. Full access can't be
+/// shared through a link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateShareLinkParams {
+  pub access_level: AFAccessLevel,
+  #[serde(default)]
+  pub password: Option<String>,
+  #[serde(default)]
+  pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareLink {
+  pub link_id: Uuid,
+  pub view_id: Uuid,
+  pub access_level: AFAccessLevel,
+  /// The first characters of the token, to recognize the link.
+  pub token_prefix: String,
+  pub has_password: bool,
+  pub created_by: i64,
+  pub created_at: DateTime<Utc>,
+  pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// The token is only returned when the link is created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedShareLink {
+  pub token: String,
+  pub share_link: ShareLink,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedShareLink {
+  pub items: Vec<ShareLink>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JoinShareLinkParams {
+  #[serde(default)]
+  pub password: Option<String>,
+}
+
+/// What a share link gives access to. After joining the link, the user can open the page with
+/// the realtime server like the pages of its own workspaces.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareLinkAccess {
+  pub workspace_id: Uuid,
+  pub view_id: Uuid,
+  pub access_level: AFAccessLevel,
+  pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// The page a share link points to, for the holders of the link that are not signed in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedPage {
+  pub access: ShareLinkAccess,
+  pub page: PageCollab,
+}
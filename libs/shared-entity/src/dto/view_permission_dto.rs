@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use database_entity::dto::{AFAccessLevel, AFRole};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Grants an access level to a member of the workspace on a space or page and the pages below
+/// it. A grant on a page overrides the grants on the spaces and pages above it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrantViewPermissionParams {
+  pub email: String,
+  pub access_level: AFAccessLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewPermission {
+  pub view_id: Uuid,
+  pub uid: i64,
+  pub email: String,
+  pub name: String,
+  pub access_level: AFAccessLevel,
+  pub granted_by: Option<i64>,
+  pub created_at: DateTime<Utc>,
+}
+
+/// Grants an access level to all the members of a group on a space or page and the pages below
+/// it. The members get the highest of their own grant and the grants of their groups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrantViewGroupPermissionParams {
+  pub group_id: Uuid,
+  pub access_level: AFAccessLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewGroupPermission {
+  pub view_id: Uuid,
+  pub group_id: Uuid,
+  pub name: String,
+  pub access_level: AFAccessLevel,
+  pub granted_by: Option<i64>,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedViewPermission {
+  pub items: Vec<ViewPermission>,
+  #[serde(default)]
+  pub groups: Vec<ViewGroupPermission>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryEffectiveViewPermission {
+  /// Defaults to the current user.
+  pub uid: Option<i64>,
+}
+
+/// What a member can do on a page, combining the role of the member with the access levels
+/// granted on the page and the spaces and pages above it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveViewPermission {
+  pub view_id: Uuid,
+  pub uid: i64,
+  pub role: AFRole,
+  /// The highest of the access level of the role and of the granted access level.
+  pub access_level: AFAccessLevel,
+  pub granted_access_level: Option<AFAccessLevel>,
+  /// The space or page `granted_access_level` was granted on. Same as `view_id` if the page has
+  /// its own grant.
+  pub granted_on: Option<Uuid>,
+}
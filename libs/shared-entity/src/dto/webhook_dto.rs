@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Events of a workspace a webhook can subscribe to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum WebhookEvent {
+  #[serde(rename = "page.created")]
+  PageCreated,
+  #[serde(rename = "page.renamed")]
+  PageRenamed,
+  #[serde(rename = "page.trashed")]
+  PageTrashed,
+  #[serde(rename = "member.joined")]
+  MemberJoined,
+  #[serde(rename = "member.removed")]
+  MemberRemoved,
+  #[serde(rename = "page.published")]
+  PagePublished,
+  #[serde(rename = "page.unpublished")]
+  PageUnpublished,
+  #[serde(rename = "database.row_changed")]
+  DatabaseRowChanged,
+  #[serde(rename = "comment.created")]
+  CommentCreated,
+}
+
+impl WebhookEvent {
+  pub const ALL: [WebhookEvent; 9] = [
+    WebhookEvent::PageCreated,
+    WebhookEvent::PageRenamed,
+    WebhookEvent::PageTrashed,
+    WebhookEvent::MemberJoined,
+    WebhookEvent::MemberRemoved,
+    WebhookEvent::PagePublished,
+    WebhookEvent::PageUnpublished,
+    WebhookEvent::DatabaseRowChanged,
+    WebhookEvent::CommentCreated,
+  ];
+
+  pub fn from_name(name: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|event| event.as_str() == name)
+  }
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      WebhookEvent::PageCreated => "page.created",
+      WebhookEvent::PageRenamed => "page.renamed",
+      WebhookEvent::PageTrashed => "page.trashed",
+      WebhookEvent::MemberJoined => "member.joined",
+      WebhookEvent::MemberRemoved => "member.removed",
+      WebhookEvent::PagePublished => "page.published",
+      WebhookEvent::PageUnpublished => "page.unpublished",
+      WebhookEvent::DatabaseRowChanged => "database.row_changed",
+      WebhookEvent::CommentCreated => "comment.created",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+pub struct CreateWebhookParams {
+  #[validate(url)]
+  pub url: String,
+  #[validate(length(min = 1))]
+  pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Debug, Clone, Default, Validate, Serialize, Deserialize)]
+pub struct UpdateWebhookParams {
+  #[validate(url)]
+  pub url: Option<String>,
+  #[validate(length(min = 1))]
+  pub events: Option<Vec<WebhookEvent>>,
+  pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+  pub webhook_id: Uuid,
+  pub workspace_id: Uuid,
+  pub url: String,
+  pub events: Vec<WebhookEvent>,
+  pub enabled: bool,
+  pub created_at: DateTime<Utc>,
+}
+
+/// Returned once when the webhook is created. The secret signs every delivery, see
+/// [WEBHOOK_SIGNATURE_HEADER].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedWebhook {
+  pub webhook: Webhook,
+  pub secret: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedWebhook {
+  pub items: Vec<Webhook>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(i16)]
+pub enum WebhookDeliveryStatus {
+  Pending = 0,
+  Delivered = 1,
+  /// All the attempts failed.
+  Failed = 2,
+}
+
+impl From<i16> for WebhookDeliveryStatus {
+  fn from(value: i16) -> Self {
+    match value {
+      1 => WebhookDeliveryStatus::Delivered,
+      2 => WebhookDeliveryStatus::Failed,
+      _ => WebhookDeliveryStatus::Pending,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+  pub delivery_id: Uuid,
+  pub event: String,
+  pub payload: serde_json::Value,
+  pub status: WebhookDeliveryStatus,
+  pub attempts: i32,
+  /// HTTP status of the last attempt, if the receiver answered.
+  pub response_status: Option<i32>,
+  pub error: Option<String>,
+  pub created_at: DateTime<Utc>,
+  pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedWebhookDelivery {
+  pub items: Vec<WebhookDelivery>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryWebhookDeliveries {
+  #[serde(default = "default_delivery_limit")]
+  pub limit: i64,
+}
+
+fn default_delivery_limit() -> i64 {
+  50
+}
+
+/// Header holding `sha256=<hex encoded HMAC-SHA256 of the body>`, computed with the secret of the
+/// webhook.
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-AppFlowy-Signature";
+pub const WEBHOOK_EVENT_HEADER: &str = "X-AppFlowy-Event";
+pub const WEBHOOK_DELIVERY_HEADER: &str = "X-AppFlowy-Delivery";
+
+/// Body of a delivery. `data` depends on the event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookPayload {
+  pub event: String,
+  pub workspace_id: Uuid,
+  pub timestamp: DateTime<Utc>,
+  pub data: serde_json::Value,
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+#[derive(Serialize_repr, Deserialize_repr, Eq, PartialEq, Debug, Clone, Copy)]
+#[repr(i16)]
+pub enum WorkspaceActivityKind {
+  /// The metadata holds the `name` and the `parent_view_id` of the page.
+  PageCreated = 0,
+  /// The metadata holds the `old_name` and the `name` of the page.
+  PageRenamed = 1,
+  /// The metadata holds the `old_parent_view_id` and the `parent_view_id` of the page.
+  PageMoved = 2,
+  /// The metadata holds the `name` of the page.
+  PageTrashed = 3,
+  /// The metadata holds the `name` of the page.
+  PageRestored = 4,
+  /// The metadata holds the `database_id` of the row.
+  DatabaseRowAdded = 5,
+  MemberJoined = 6,
+  /// The metadata holds the `publish_name` of the page.
+  PagePublished = 7,
+}
+
+impl WorkspaceActivityKind {
+  pub fn from_i16(value: i16) -> Option<Self> {
+    match value {
+      0 => Some(WorkspaceActivityKind::PageCreated),
+      1 => Some(WorkspaceActivityKind::PageRenamed),
+      2 => Some(WorkspaceActivityKind::PageMoved),
+      3 => Some(WorkspaceActivityKind::PageTrashed),
+      4 => Some(WorkspaceActivityKind::PageRestored),
+      5 => Some(WorkspaceActivityKind::DatabaseRowAdded),
+      6 => Some(WorkspaceActivityKind::MemberJoined),
+      7 => Some(WorkspaceActivityKind::PagePublished),
+      _ => None,
+    }
+  }
+
+  /// True if the object of the activity is a page.
+  pub fn is_page(&self) -> bool {
+    matches!(
+      self,
+      WorkspaceActivityKind::PageCreated
+        | WorkspaceActivityKind::PageRenamed
+        | WorkspaceActivityKind::PageMoved
+        | WorkspaceActivityKind::PageTrashed
+        | WorkspaceActivityKind::PageRestored
+        | WorkspaceActivityKind::PagePublished
+    )
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceActivity {
+  pub activity_id: i64,
+  pub kind: WorkspaceActivityKind,
+  /// The member that made the change, or the member that joined. Unknown for some changes made
+  /// by older clients.
+  pub actor_uid: Option<i64>,
+  pub actor_name: Option<String>,
+  /// The view id for the page events, the row id for the database rows and the uid for the
+  /// members.
+  pub object_id: String,
+  pub metadata: serde_json::Value,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedWorkspaceActivity {
+  pub items: Vec<WorkspaceActivity>,
+  /// Pass it as `before` to get the next page. `None` if there are no more activities.
+  pub next_cursor: Option<i64>,
+}
+
+/// The activities are returned from the most recent one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryWorkspaceActivityParams {
+  /// Only the activities that happened at or after this time.
+  pub since: Option<DateTime<Utc>>,
+  /// Only the activities older than the activity with this id.
+  pub before: Option<i64>,
+  /// Defaults to 50, at most 200.
+  pub limit: Option<i64>,
+}
@@ -248,6 +248,17 @@ pub struct FolderViewMinimal {
 pub struct PublishInfoView {
   pub view: FolderViewMinimal,
   pub info: PublishInfo,
+  #[serde(default)]
+  pub settings: PublishedViewSettings,
+}
+
+/// Publish settings of a view, changed with the patch of the published collabs.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct PublishedViewSettings {
+  pub live: bool,
+  pub has_password: bool,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub allowed_email_domains: Vec<String>,
 }
 
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Serialize_repr, Deserialize_repr)]
@@ -0,0 +1,52 @@
+-- Text extracted from attachments (PDF, DOCX, plain text) is stored alongside the document that owns
+-- the attachment. `file_id` is the blob metadata key of the attachment, NULL for document content.
+ALTER TABLE af_collab_embeddings ADD COLUMN IF NOT EXISTS file_id TEXT;
+CREATE INDEX IF NOT EXISTS af_collab_embeddings_file_id_idx ON af_collab_embeddings (file_id) WHERE file_id IS NOT NULL;
+
+-- Re-indexing a document must not remove fragments extracted from its attachments.
+CREATE OR REPLACE PROCEDURE af_collab_embeddings_upsert(
+    IN p_workspace_id UUID,
+    IN p_oid TEXT,
+    IN p_partition_key INT,
+    IN p_tokens_used INT,
+    IN p_fragments af_fragment[]
+)
+LANGUAGE plpgsql
+AS $$
+BEGIN
+    DELETE FROM af_collab_embeddings WHERE oid = p_oid AND file_id IS NULL;
+
+    INSERT INTO af_collab_embeddings (fragment_id, oid, partition_key, content_type, content, embedding, indexed_at)
+    SELECT f.fragment_id, p_oid, p_partition_key, f.content_type, f.contents, f.embedding, NOW()
+    FROM UNNEST(p_fragments) as f;
+
+    INSERT INTO af_workspace_ai_usage(created_at, workspace_id, search_requests, search_tokens_consumed, index_tokens_consumed)
+    VALUES (now()::date, p_workspace_id, 0, 0, p_tokens_used)
+    ON CONFLICT (created_at, workspace_id)
+    DO UPDATE SET index_tokens_consumed = af_workspace_ai_usage.index_tokens_consumed + p_tokens_used;
+END
+$$;
+
+CREATE OR REPLACE PROCEDURE af_attachment_embeddings_upsert(
+    IN p_workspace_id UUID,
+    IN p_oid TEXT,
+    IN p_partition_key INT,
+    IN p_file_id TEXT,
+    IN p_tokens_used INT,
+    IN p_fragments af_fragment[]
+)
+LANGUAGE plpgsql
+AS $$
+BEGIN
+    DELETE FROM af_collab_embeddings WHERE oid = p_oid AND file_id = p_file_id;
+
+    INSERT INTO af_collab_embeddings (fragment_id, oid, partition_key, content_type, content, embedding, indexed_at, file_id)
+    SELECT f.fragment_id, p_oid, p_partition_key, f.content_type, f.contents, f.embedding, NOW(), p_file_id
+    FROM UNNEST(p_fragments) as f;
+
+    INSERT INTO af_workspace_ai_usage(created_at, workspace_id, search_requests, search_tokens_consumed, index_tokens_consumed)
+    VALUES (now()::date, p_workspace_id, 0, 0, p_tokens_used)
+    ON CONFLICT (created_at, workspace_id)
+    DO UPDATE SET index_tokens_consumed = af_workspace_ai_usage.index_tokens_consumed + p_tokens_used;
+END
+$$;
@@ -0,0 +1,4 @@
+-- Full text search over chat messages. The 'simple' configuration is used because chats are not
+-- limited to a single language, so no stemming is applied.
+CREATE INDEX IF NOT EXISTS idx_af_chat_messages_content_tsv
+    ON af_chat_messages USING gin (to_tsvector('simple', content));
@@ -0,0 +1,95 @@
+-- A branch is created when a question is edited: the edited question and the rest of the conversation
+-- are stored in the new branch, the original messages are kept in the parent branch. Branch 0 is the
+-- main branch of every chat and has no row in this table.
+CREATE TABLE IF NOT EXISTS af_chat_branch
+(
+    branch_id        BIGSERIAL PRIMARY KEY,
+    chat_id          UUID                     NOT NULL,
+    parent_branch_id BIGINT                   NOT NULL DEFAULT 0,
+    -- the message of the parent branch which was edited. Only the messages of the parent branch
+    -- created before this message are part of the new branch.
+    fork_message_id  BIGINT                   NOT NULL,
+    created_at       TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (chat_id) REFERENCES af_chat (chat_id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_af_chat_branch_chat_id ON af_chat_branch (chat_id);
+
+-- The branch that is returned when no branch is specified and where new questions are added.
+ALTER TABLE af_chat
+    ADD COLUMN IF NOT EXISTS active_branch_id BIGINT NOT NULL DEFAULT 0;
+
+-- Regenerating an answer keeps the previous versions. Every answer references the question it
+-- answers, `reply_message_id` of the question points to the selected version.
+ALTER TABLE af_chat_messages
+    ADD COLUMN IF NOT EXISTS branch_id BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS question_message_id BIGINT;
+
+UPDATE af_chat_messages AS answer
+SET question_message_id = question.message_id
+FROM af_chat_messages AS question
+WHERE question.reply_message_id = answer.message_id
+  AND answer.question_message_id IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_af_chat_messages_question_message_id
+    ON af_chat_messages (question_message_id) WHERE question_message_id IS NOT NULL;
+
+-- Position of a message in the conversation, see af_chat_visible_messages.
+CREATE OR REPLACE FUNCTION af_chat_message_position(p_message_id BIGINT, p_question_message_id BIGINT)
+    RETURNS BIGINT
+    LANGUAGE sql
+    IMMUTABLE
+AS
+$$
+SELECT COALESCE(p_question_message_id, p_message_id) * 2
+           + CASE WHEN p_question_message_id IS NULL THEN 0 ELSE 1 END
+$$;
+
+-- The messages visible in a branch of a chat, the active branch when p_branch_id is NULL:
+-- - the messages of the branch itself and the messages of its ancestors which were created before
+--   the branch was forked.
+-- - only the selected version of every answer. Answers follow the branch of their question.
+-- Messages are ordered by message_position: every answer version is placed right after its
+-- question, even when it was regenerated after newer questions were asked.
+CREATE OR REPLACE FUNCTION af_chat_visible_messages(p_chat_id UUID, p_branch_id BIGINT)
+    RETURNS TABLE
+            (
+                message_id       BIGINT,
+                content          TEXT,
+                created_at       TIMESTAMP WITH TIME ZONE,
+                author           JSONB,
+                meta_data        JSONB,
+                reply_message_id BIGINT,
+                message_position BIGINT
+            )
+    LANGUAGE sql
+    STABLE
+AS
+$$
+WITH RECURSIVE target AS (
+    SELECT COALESCE(p_branch_id, (SELECT active_branch_id FROM af_chat WHERE chat_id = p_chat_id), 0) AS branch_id
+),
+lineage AS (
+    SELECT b.branch_id, b.parent_branch_id, b.fork_message_id
+    FROM af_chat_branch b
+    JOIN target t ON b.branch_id = t.branch_id
+    WHERE b.chat_id = p_chat_id
+  UNION ALL
+    SELECT b.branch_id, b.parent_branch_id, b.fork_message_id
+    FROM af_chat_branch b
+    JOIN lineage l ON b.branch_id = l.parent_branch_id
+),
+segments AS (
+    SELECT branch_id, 9223372036854775807::BIGINT AS upper_message_id FROM target
+  UNION ALL
+    SELECT parent_branch_id, fork_message_id FROM lineage
+)
+SELECT m.message_id, m.content, m.created_at, m.author, m.meta_data, m.reply_message_id,
+       af_chat_message_position(m.message_id, m.question_message_id)
+FROM af_chat_messages m
+LEFT JOIN af_chat_messages q ON q.message_id = m.question_message_id
+JOIN segments s
+  ON s.branch_id = COALESCE(q.branch_id, m.branch_id)
+ AND COALESCE(q.message_id, m.message_id) < s.upper_message_id
+WHERE m.chat_id = p_chat_id
+  AND (m.question_message_id IS NULL OR q.reply_message_id = m.message_id)
+$$;
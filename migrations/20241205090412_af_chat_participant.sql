@@ -0,0 +1,63 @@
+-- Members of the workspace who take part in a chat. Role 0 is the owner of the chat, role 1 a member.
+CREATE TABLE IF NOT EXISTS af_chat_participant
+(
+    chat_id   UUID                     NOT NULL,
+    uid       BIGINT                   NOT NULL,
+    role      INT                      NOT NULL DEFAULT 1,
+    joined_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (chat_id, uid),
+    FOREIGN KEY (chat_id) REFERENCES af_chat (chat_id) ON DELETE CASCADE,
+    FOREIGN KEY (uid) REFERENCES af_user (uid) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_af_chat_participant_uid ON af_chat_participant (uid);
+
+-- The user who created the chat. The other members of the workspace can only read a chat without
+-- participants.
+ALTER TABLE af_chat ADD COLUMN IF NOT EXISTS created_by BIGINT REFERENCES af_user (uid) ON DELETE SET NULL;
+
+-- Chats created before participants existed: the first human author becomes the owner and the
+-- other human authors become members. The creator of the chats without any question is unknown.
+UPDATE af_chat c
+SET created_by = first_author.uid
+FROM (SELECT DISTINCT ON (m.chat_id) m.chat_id, u.uid
+      FROM af_chat_messages m
+      JOIN af_user u ON u.uid = (m.author ->> 'author_id')::BIGINT
+      WHERE m.author ->> 'author_type' = '1'
+      ORDER BY m.chat_id, m.message_id) first_author
+WHERE c.chat_id = first_author.chat_id AND c.created_by IS NULL;
+
+INSERT INTO af_chat_participant (chat_id, uid, role)
+SELECT chat_id, created_by, 0
+FROM af_chat
+WHERE created_by IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+INSERT INTO af_chat_participant (chat_id, uid, role)
+SELECT DISTINCT m.chat_id, u.uid, 1
+FROM af_chat_messages m
+JOIN af_user u ON u.uid = (m.author ->> 'author_id')::BIGINT
+WHERE m.author ->> 'author_type' = '1'
+ON CONFLICT DO NOTHING;
+
+-- Notify the participants of a chat when a message is added or its content changes. Only the ids
+-- are sent because the payload of pg_notify is limited to 8000 bytes.
+CREATE OR REPLACE FUNCTION notify_af_chat_message_change() RETURNS TRIGGER AS $$
+DECLARE
+    payload TEXT;
+BEGIN
+    payload := json_build_object(
+            'chat_id', NEW.chat_id,
+            'message_id', NEW.message_id,
+            'action_type', TG_OP
+            )::text;
+
+    PERFORM pg_notify('af_chat_message_channel', payload);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS af_chat_message_change_trigger ON af_chat_messages;
+CREATE TRIGGER af_chat_message_change_trigger
+    AFTER INSERT OR UPDATE OF content ON af_chat_messages
+    FOR EACH ROW
+EXECUTE FUNCTION notify_af_chat_message_change();
@@ -0,0 +1,19 @@
+-- Long-lived tokens that act on behalf of a member of a workspace, for bots and scripts. Only the
+-- SHA-256 hash of the token is stored, token_prefix keeps the first characters for display.
+-- Scope 1 is read-only, 2 allows writes and 3 allows administrating the workspace.
+CREATE TABLE IF NOT EXISTS af_workspace_api_token
+(
+    token_id     UUID PRIMARY KEY                  DEFAULT gen_random_uuid(),
+    workspace_id UUID                     NOT NULL,
+    uid          BIGINT                   NOT NULL,
+    name         TEXT                     NOT NULL,
+    token_hash   TEXT                     NOT NULL UNIQUE,
+    token_prefix TEXT                     NOT NULL,
+    scope        INT                      NOT NULL,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at   TIMESTAMP WITH TIME ZONE,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    FOREIGN KEY (workspace_id) REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    FOREIGN KEY (uid) REFERENCES af_user (uid) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_af_workspace_api_token_workspace_uid ON af_workspace_api_token (workspace_id, uid);
@@ -0,0 +1,52 @@
+-- Third-party applications that access workspaces through the OAuth2 authorization code flow.
+-- Public clients have no secret and must use PKCE.
+CREATE TABLE IF NOT EXISTS af_oauth_client
+(
+    client_id          TEXT PRIMARY KEY,
+    client_secret_hash TEXT,
+    name               TEXT                     NOT NULL,
+    redirect_uris      TEXT[]                   NOT NULL,
+    owner_uid          BIGINT                   NOT NULL,
+    created_at         TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (owner_uid) REFERENCES af_user (uid) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_af_oauth_client_owner_uid ON af_oauth_client (owner_uid);
+
+-- Codes granted by the user on the consent page. They are exchanged once for an access token.
+CREATE TABLE IF NOT EXISTS af_oauth_authorization_code
+(
+    code_hash      TEXT PRIMARY KEY,
+    client_id      TEXT                     NOT NULL,
+    uid            BIGINT                   NOT NULL,
+    workspace_id   UUID                     NOT NULL,
+    scope          INT                      NOT NULL,
+    redirect_uri   TEXT                     NOT NULL,
+    code_challenge TEXT,
+    expires_at     TIMESTAMP WITH TIME ZONE NOT NULL,
+    FOREIGN KEY (client_id) REFERENCES af_oauth_client (client_id) ON DELETE CASCADE,
+    FOREIGN KEY (uid) REFERENCES af_user (uid) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES af_workspace (workspace_id) ON DELETE CASCADE
+);
+
+-- The access tokens issued to a client are workspace API tokens. Deleting the client revokes them.
+ALTER TABLE af_workspace_api_token
+    ADD COLUMN IF NOT EXISTS client_id TEXT REFERENCES af_oauth_client (client_id) ON DELETE CASCADE;
+
+-- The access tokens issued to OAuth clients expire. The client gets a new one with the refresh
+-- token, which is rotated on every use. Revoking the access token also revokes its refresh token.
+CREATE TABLE IF NOT EXISTS af_oauth_refresh_token
+(
+    token_hash      TEXT PRIMARY KEY,
+    client_id       TEXT                     NOT NULL,
+    uid             BIGINT                   NOT NULL,
+    workspace_id    UUID                     NOT NULL,
+    scope           INT                      NOT NULL,
+    access_token_id UUID                     NOT NULL,
+    expires_at      TIMESTAMP WITH TIME ZONE NOT NULL,
+    FOREIGN KEY (client_id) REFERENCES af_oauth_client (client_id) ON DELETE CASCADE,
+    FOREIGN KEY (uid) REFERENCES af_user (uid) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    FOREIGN KEY (access_token_id) REFERENCES af_workspace_api_token (token_id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_af_oauth_refresh_token_access_token_id
+    ON af_oauth_refresh_token (access_token_id);
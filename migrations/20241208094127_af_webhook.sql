@@ -0,0 +1,38 @@
+-- Outgoing webhooks of a workspace. Events are queued by the server on the webhook_event_stream
+-- Redis stream and delivered by appflowy-worker. The secret signs the payload with HMAC-SHA256,
+-- so it is kept as is: the receiver needs the same value to verify the signature.
+CREATE TABLE IF NOT EXISTS af_webhook
+(
+    webhook_id   UUID PRIMARY KEY                  DEFAULT gen_random_uuid(),
+    workspace_id UUID                     NOT NULL,
+    url          TEXT                     NOT NULL,
+    secret       TEXT                     NOT NULL,
+    -- Names of the subscribed events, such as page.created
+    events       TEXT[]                   NOT NULL,
+    enabled      BOOLEAN                  NOT NULL DEFAULT TRUE,
+    created_by   BIGINT                   NOT NULL,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES af_user (uid) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_af_webhook_workspace_id ON af_webhook (workspace_id);
+
+-- One row per event sent to a webhook. Status 0 is pending, 1 delivered and 2 failed after the last
+-- retry. Pending deliveries are retried with an exponential backoff until next_attempt_at.
+CREATE TABLE IF NOT EXISTS af_webhook_delivery
+(
+    delivery_id     UUID PRIMARY KEY                  DEFAULT gen_random_uuid(),
+    webhook_id      UUID                     NOT NULL,
+    event           TEXT                     NOT NULL,
+    payload         JSONB                    NOT NULL,
+    status          SMALLINT                 NOT NULL DEFAULT 0,
+    attempts        INT                      NOT NULL DEFAULT 0,
+    response_status INT,
+    error           TEXT,
+    created_at      TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    delivered_at    TIMESTAMP WITH TIME ZONE,
+    FOREIGN KEY (webhook_id) REFERENCES af_webhook (webhook_id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_af_webhook_delivery_webhook_id ON af_webhook_delivery (webhook_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_af_webhook_delivery_pending ON af_webhook_delivery (next_attempt_at) WHERE status = 0;
@@ -0,0 +1,32 @@
+-- Append-only log of the security-relevant actions. The log must outlive the workspaces and users
+-- it refers to, so there are no foreign keys. workspace_id is NULL for actions that don't belong to
+-- a workspace, such as signing in.
+CREATE TABLE IF NOT EXISTS af_audit_log
+(
+    log_id       BIGSERIAL PRIMARY KEY,
+    workspace_id UUID,
+    actor_uid    BIGINT,
+    action       TEXT                     NOT NULL,
+    object_type  TEXT                     NOT NULL,
+    object_id    TEXT,
+    metadata     JSONB                    NOT NULL DEFAULT '{}'::jsonb,
+    ip_address   TEXT,
+    user_agent   TEXT,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_af_audit_log_workspace_id ON af_audit_log (workspace_id, log_id DESC);
+CREATE INDEX IF NOT EXISTS idx_af_audit_log_actor_uid ON af_audit_log (actor_uid, log_id DESC);
+-- A sign-in is recorded once per GoTrue session, however many times its access token is verified.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_af_audit_log_sign_in_session
+    ON af_audit_log ((metadata ->> 'session_id')) WHERE action = 'user.signed_in';
+
+CREATE OR REPLACE FUNCTION prevent_af_audit_log_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'af_audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER af_audit_log_append_only
+BEFORE UPDATE OR DELETE ON af_audit_log
+FOR EACH ROW EXECUTE FUNCTION prevent_af_audit_log_change();
@@ -0,0 +1,23 @@
+-- Roles defined by a workspace. A custom role builds on a built-in role (base_role_id), which
+-- decides whether the members can read or write, and grants the listed permissions instead of the
+-- default ones of the built-in role.
+CREATE TABLE IF NOT EXISTS af_workspace_custom_role
+(
+    role_id      UUID PRIMARY KEY                  DEFAULT gen_random_uuid(),
+    workspace_id UUID                     NOT NULL,
+    name         TEXT                     NOT NULL,
+    base_role_id INT                      NOT NULL,
+    permissions  TEXT[]                   NOT NULL DEFAULT '{}',
+    created_by   BIGINT,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    FOREIGN KEY (base_role_id) REFERENCES af_roles (id),
+    UNIQUE (workspace_id, name)
+);
+
+-- The role_id of a member with a custom role is the base role of the custom role.
+ALTER TABLE af_workspace_member
+    ADD COLUMN IF NOT EXISTS custom_role_id UUID
+        REFERENCES af_workspace_custom_role (role_id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_af_workspace_member_custom_role_id
+    ON af_workspace_member (custom_role_id) WHERE custom_role_id IS NOT NULL;
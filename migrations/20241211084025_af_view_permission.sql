@@ -0,0 +1,29 @@
+-- Access levels granted to a member on a space or page. A grant applies to the pages below the
+-- space or page, unless one of them has its own grant for the same member.
+CREATE TABLE IF NOT EXISTS af_view_permission
+(
+    workspace_id UUID                     NOT NULL,
+    view_id      UUID                     NOT NULL,
+    uid          BIGINT                   NOT NULL,
+    access_level INT                      NOT NULL,
+    granted_by   BIGINT,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (workspace_id, view_id, uid),
+    FOREIGN KEY (workspace_id) REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    FOREIGN KEY (uid) REFERENCES af_user (uid) ON DELETE CASCADE
+);
+
+-- The access level resulting from the grants on every page below a granted space or page. It is
+-- recomputed from af_view_permission and the folder of the workspace, and loaded by the access
+-- control.
+CREATE TABLE IF NOT EXISTS af_view_effective_permission
+(
+    workspace_id   UUID   NOT NULL,
+    view_id        UUID   NOT NULL,
+    uid            BIGINT NOT NULL,
+    access_level   INT    NOT NULL,
+    source_view_id UUID   NOT NULL,
+    PRIMARY KEY (workspace_id, view_id, uid),
+    FOREIGN KEY (workspace_id) REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    FOREIGN KEY (uid) REFERENCES af_user (uid) ON DELETE CASCADE
+);
@@ -0,0 +1,49 @@
+-- Named groups of members within a workspace. A group can be granted access wherever a member
+-- can: as a collab member and through space and page permissions.
+CREATE TABLE IF NOT EXISTS af_workspace_group
+(
+    group_id     UUID PRIMARY KEY                  DEFAULT gen_random_uuid(),
+    workspace_id UUID                     NOT NULL,
+    name         TEXT                     NOT NULL,
+    created_by   BIGINT,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (workspace_id) REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    UNIQUE (workspace_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS af_workspace_group_member
+(
+    group_id   UUID                     NOT NULL,
+    uid        BIGINT                   NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_id, uid),
+    FOREIGN KEY (group_id) REFERENCES af_workspace_group (group_id) ON DELETE CASCADE,
+    FOREIGN KEY (uid) REFERENCES af_user (uid) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_af_workspace_group_member_uid ON af_workspace_group_member (uid);
+
+-- Access level of a group on a collab, like af_collab_member for a single user.
+CREATE TABLE IF NOT EXISTS af_collab_group_member
+(
+    group_id     UUID                     NOT NULL,
+    oid          TEXT                     NOT NULL,
+    access_level INT                      NOT NULL,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_id, oid),
+    FOREIGN KEY (group_id) REFERENCES af_workspace_group (group_id) ON DELETE CASCADE
+);
+
+-- Access levels granted to a group on a space or page. The members of the group get the highest
+-- of their own grant and the grants of their groups on every space or page.
+CREATE TABLE IF NOT EXISTS af_view_group_permission
+(
+    workspace_id UUID                     NOT NULL,
+    view_id      UUID                     NOT NULL,
+    group_id     UUID                     NOT NULL,
+    access_level INT                      NOT NULL,
+    granted_by   BIGINT,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (workspace_id, view_id, group_id),
+    FOREIGN KEY (workspace_id) REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    FOREIGN KEY (group_id) REFERENCES af_workspace_group (group_id) ON DELETE CASCADE
+);
@@ -0,0 +1,19 @@
+-- Users known to the identity provider of a workspace through SCIM provisioning. A user that is
+-- deprovisioned is no longer a member of the workspace, but stays here so the identity provider
+-- can reactivate it.
+CREATE TABLE IF NOT EXISTS af_scim_user
+(
+    workspace_id UUID                     NOT NULL,
+    uid          BIGINT                   NOT NULL,
+    external_id  TEXT,
+    -- True if the user was created by the provisioning, rather than matched to an existing user.
+    managed      BOOLEAN                  NOT NULL DEFAULT FALSE,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (workspace_id, uid),
+    FOREIGN KEY (workspace_id) REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    FOREIGN KEY (uid) REFERENCES af_user (uid) ON DELETE CASCADE
+);
+
+-- Id of the group in the identity provider, for the groups provisioned through SCIM.
+ALTER TABLE af_workspace_group ADD COLUMN IF NOT EXISTS external_id TEXT;
@@ -0,0 +1,36 @@
+-- Links that grant an access level on a page to anyone holding the token. Only the SHA-256 hash
+-- of the token is stored, token_prefix keeps the first characters for display. The optional
+-- password is stored as an argon2 hash.
+CREATE TABLE IF NOT EXISTS af_share_link
+(
+    link_id       UUID PRIMARY KEY                  DEFAULT gen_random_uuid(),
+    workspace_id  UUID                     NOT NULL,
+    view_id       UUID                     NOT NULL,
+    -- the database of a database view, whose collab gets the access level of the link
+    database_id   UUID,
+    token_hash    TEXT                     NOT NULL UNIQUE,
+    token_prefix  TEXT                     NOT NULL,
+    access_level  INT                      NOT NULL,
+    password_hash TEXT,
+    created_by    BIGINT                   NOT NULL,
+    created_at    TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at    TIMESTAMP WITH TIME ZONE,
+    FOREIGN KEY (workspace_id) REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES af_user (uid) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_af_share_link_workspace_view ON af_share_link (workspace_id, view_id);
+CREATE INDEX IF NOT EXISTS idx_af_share_link_workspace_database ON af_share_link (workspace_id, database_id)
+    WHERE database_id IS NOT NULL;
+
+-- Signed-in users that joined a share link, so they can open the page through the realtime
+-- server. Revoking the link removes them.
+CREATE TABLE IF NOT EXISTS af_share_link_user
+(
+    link_id   UUID                     NOT NULL,
+    uid       BIGINT                   NOT NULL,
+    joined_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (link_id, uid),
+    FOREIGN KEY (link_id) REFERENCES af_share_link (link_id) ON DELETE CASCADE,
+    FOREIGN KEY (uid) REFERENCES af_user (uid) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_af_share_link_user_uid ON af_share_link_user (uid);
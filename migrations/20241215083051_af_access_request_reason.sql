@@ -0,0 +1,32 @@
+-- Free-text reasons given by the requester and by the owner rejecting the request. Requests that
+-- are still pending when they expire get the status 3 (expired).
+ALTER TABLE af_access_request
+    ADD COLUMN IF NOT EXISTS reason           TEXT,
+    ADD COLUMN IF NOT EXISTS rejection_reason TEXT,
+    ADD COLUMN IF NOT EXISTS expires_at       TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP + INTERVAL '30 days',
+    ADD COLUMN IF NOT EXISTS updated_at       TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS idx_pending_expires_at_on_af_access_request
+    ON af_access_request (expires_at) WHERE status = 0;
+
+-- Notify the approvers when a request is created and the requester when the status of its request
+-- changes.
+CREATE OR REPLACE FUNCTION notify_af_access_request_change() RETURNS TRIGGER AS $$
+DECLARE
+    payload TEXT;
+BEGIN
+    payload := json_build_object(
+            'request_id', NEW.request_id,
+            'status', NEW.status
+            )::text;
+
+    PERFORM pg_notify('af_access_request_channel', payload);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS af_access_request_change_trigger ON af_access_request;
+CREATE TRIGGER af_access_request_change_trigger
+    AFTER INSERT OR UPDATE OF status ON af_access_request
+    FOR EACH ROW
+EXECUTE FUNCTION notify_af_access_request_change();
@@ -0,0 +1,28 @@
+-- Live published views track their source document: the published blob is replaced whenever the
+-- document is persisted, instead of being a copy taken when the view was published.
+ALTER TABLE af_published_collab
+    ADD COLUMN IF NOT EXISTS live BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Notify the readers of a live published view when its blob changes, or when the view is switched to
+-- live mode.
+CREATE OR REPLACE FUNCTION notify_af_published_collab_update() RETURNS TRIGGER AS $$
+DECLARE
+    payload TEXT;
+BEGIN
+    payload := json_build_object(
+            'workspace_id', NEW.workspace_id,
+            'view_id', NEW.view_id,
+            'updated_at', CURRENT_TIMESTAMP
+            )::text;
+
+    PERFORM pg_notify('af_published_collab_channel', payload);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS af_published_collab_update_trigger ON af_published_collab;
+CREATE TRIGGER af_published_collab_update_trigger
+    AFTER UPDATE OF blob, live ON af_published_collab
+    FOR EACH ROW
+    WHEN (NEW.live AND NEW.unpublished_at IS NULL)
+EXECUTE FUNCTION notify_af_published_collab_update();
@@ -0,0 +1,7 @@
+-- Restrictions on the readers of a published view: a password, a date after which the view is no
+-- longer accessible and the email domains of the users allowed to read it. An empty list of
+-- domains means anyone can read the view.
+ALTER TABLE af_published_collab
+    ADD COLUMN IF NOT EXISTS password_hash         TEXT,
+    ADD COLUMN IF NOT EXISTS expires_at            TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS allowed_email_domains TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,40 @@
+-- Daily analytics of the published views, aggregated by the server before being written.
+CREATE TABLE IF NOT EXISTS af_published_view_daily_stats
+(
+    workspace_id         UUID   NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    view_id              UUID   NOT NULL,
+    day                  DATE   NOT NULL,
+    view_count           BIGINT NOT NULL DEFAULT 0,
+    unique_visitor_count BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (view_id, day)
+);
+CREATE INDEX IF NOT EXISTS idx_af_published_view_daily_stats_workspace_day
+    ON af_published_view_daily_stats (workspace_id, day);
+
+-- Host of the page the readers came from. An empty referrer is a direct visit.
+CREATE TABLE IF NOT EXISTS af_published_view_referrer_stats
+(
+    workspace_id UUID   NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    view_id      UUID   NOT NULL,
+    day          DATE   NOT NULL,
+    referrer     TEXT   NOT NULL,
+    view_count   BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (view_id, day, referrer)
+);
+
+-- Visitors are identified by a hash of their address and user agent, salted with a salt that
+-- changes every day. The salts and the hashes of the previous days are deleted, so a visitor can't
+-- be followed from one day to another.
+CREATE TABLE IF NOT EXISTS af_published_view_salt
+(
+    day  DATE  NOT NULL PRIMARY KEY,
+    salt BYTEA NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS af_published_view_visitor
+(
+    view_id      UUID  NOT NULL,
+    day          DATE  NOT NULL,
+    visitor_hash BYTEA NOT NULL,
+    PRIMARY KEY (view_id, day, visitor_hash)
+);
@@ -0,0 +1,39 @@
+-- Comments waiting for the approval of a moderator are only shown to the moderators and to their
+-- author.
+ALTER TABLE af_published_view_comment
+    ADD COLUMN IF NOT EXISTS is_approved BOOLEAN NOT NULL DEFAULT TRUE;
+-- used to rate limit the commenters
+CREATE INDEX IF NOT EXISTS idx_created_by_created_at_on_af_published_view_comment
+    ON af_published_view_comment (created_by, created_at);
+
+-- Comment settings of a published view. Like the comments, they are kept when the view is
+-- unpublished.
+CREATE TABLE IF NOT EXISTS af_published_view_comment_settings
+(
+    view_id          UUID    NOT NULL PRIMARY KEY,
+    comments_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    require_approval BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at       TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Moderation rules applied to the comments on all the published views of a workspace.
+CREATE TABLE IF NOT EXISTS af_workspace_comment_moderation
+(
+    workspace_id          UUID   NOT NULL PRIMARY KEY REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    -- comments containing one of the keywords are rejected, the keywords are lowercase
+    blocked_keywords      TEXT[] NOT NULL DEFAULT '{}',
+    -- NULL means no limit
+    max_comments_per_hour INT,
+    updated_at            TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Users that can't comment nor react on the published views of a workspace.
+CREATE TABLE IF NOT EXISTS af_workspace_comment_ban
+(
+    workspace_id UUID   NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    uid          BIGINT NOT NULL REFERENCES af_user (uid) ON DELETE CASCADE,
+    reason       TEXT,
+    banned_by    BIGINT REFERENCES af_user (uid) ON DELETE SET NULL,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (workspace_id, uid)
+);
@@ -0,0 +1,84 @@
+-- Comment threads anchored to a block of a document, or to a range of the text of the block.
+-- Only the members of the workspace can see them, unlike the comments on the published views.
+CREATE TABLE IF NOT EXISTS af_document_comment_thread
+(
+    thread_id    UUID    NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    workspace_id UUID    NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    view_id      UUID    NOT NULL,
+    block_id     TEXT    NOT NULL,
+    -- range of the text of the block, in characters. NULL when the whole block is commented.
+    range_start  INT,
+    range_end    INT,
+    -- the commented text when the thread was created, shown when the block is gone
+    quoted_text  TEXT,
+    created_by   BIGINT  REFERENCES af_user (uid) ON DELETE SET NULL,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_by  BIGINT  REFERENCES af_user (uid) ON DELETE SET NULL,
+    resolved_at  TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX IF NOT EXISTS idx_workspace_id_view_id_on_af_document_comment_thread
+    ON af_document_comment_thread (workspace_id, view_id);
+
+CREATE TABLE IF NOT EXISTS af_document_comment
+(
+    comment_id UUID    NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    thread_id  UUID    NOT NULL REFERENCES af_document_comment_thread (thread_id) ON DELETE CASCADE,
+    content    TEXT    NOT NULL,
+    created_by BIGINT  REFERENCES af_user (uid) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- deleted comments are kept, without their content, so the replies keep their place
+    is_deleted BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX IF NOT EXISTS idx_thread_id_on_af_document_comment
+    ON af_document_comment (thread_id, created_at);
+
+-- Members of the workspace mentioned in a comment.
+CREATE TABLE IF NOT EXISTS af_document_comment_mention
+(
+    comment_id UUID   NOT NULL REFERENCES af_document_comment (comment_id) ON DELETE CASCADE,
+    uid        BIGINT NOT NULL REFERENCES af_user (uid) ON DELETE CASCADE,
+    PRIMARY KEY (comment_id, uid)
+);
+
+-- Notify the members of the workspace when a comment is added, edited or deleted, and when a
+-- thread is resolved or reopened.
+CREATE OR REPLACE FUNCTION notify_af_document_comment_change() RETURNS TRIGGER AS $$
+DECLARE
+    payload TEXT;
+BEGIN
+    IF TG_TABLE_NAME = 'af_document_comment_thread' THEN
+        payload := json_build_object(
+                'thread_id', NEW.thread_id,
+                'comment_id', NULL,
+                'action', CASE WHEN NEW.resolved_at IS NULL THEN 4 ELSE 3 END
+                )::text;
+    ELSE
+        payload := json_build_object(
+                'thread_id', NEW.thread_id,
+                'comment_id', NEW.comment_id,
+                'action', CASE
+                              WHEN TG_OP = 'INSERT' THEN 0
+                              WHEN NEW.is_deleted THEN 2
+                              ELSE 1
+                          END
+                )::text;
+    END IF;
+
+    PERFORM pg_notify('af_document_comment_channel', payload);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS af_document_comment_change_trigger ON af_document_comment;
+CREATE TRIGGER af_document_comment_change_trigger
+    AFTER INSERT OR UPDATE OF content, is_deleted ON af_document_comment
+    FOR EACH ROW
+EXECUTE FUNCTION notify_af_document_comment_change();
+
+DROP TRIGGER IF EXISTS af_document_comment_thread_change_trigger ON af_document_comment_thread;
+CREATE TRIGGER af_document_comment_thread_change_trigger
+    AFTER UPDATE OF resolved_at ON af_document_comment_thread
+    FOR EACH ROW
+    WHEN (OLD.resolved_at IS DISTINCT FROM NEW.resolved_at)
+EXECUTE FUNCTION notify_af_document_comment_change();
@@ -0,0 +1,93 @@
+-- Notifications shown in the notification center of a user. The kind is the `NotificationKind` of
+-- the notification api, the payload holds what the clients need to show the notification without
+-- loading its source, like the name of the page or the beginning of a comment.
+CREATE TABLE IF NOT EXISTS af_notification
+(
+    notification_id UUID     NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    uid             BIGINT   NOT NULL REFERENCES af_user (uid) ON DELETE CASCADE,
+    workspace_id    UUID     NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    kind            SMALLINT NOT NULL,
+    view_id         UUID,
+    -- id of the comment, invitation, access request or reminder the notification is about
+    object_id       TEXT,
+    actor_uid       BIGINT   REFERENCES af_user (uid) ON DELETE SET NULL,
+    payload         JSONB    NOT NULL DEFAULT '{}',
+    created_at      TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    read_at         TIMESTAMP WITH TIME ZONE,
+    -- set once the notification was summarised in an email digest
+    emailed_at      TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX IF NOT EXISTS idx_uid_created_at_on_af_notification
+    ON af_notification (uid, created_at DESC);
+-- used by the email digests
+CREATE INDEX IF NOT EXISTS idx_unread_created_at_on_af_notification
+    ON af_notification (created_at) WHERE read_at IS NULL AND emailed_at IS NULL;
+
+-- A user doesn't get notifications from a muted workspace, or from a muted page when view_id is
+-- set, until muted_until if set.
+CREATE TABLE IF NOT EXISTS af_notification_mute
+(
+    uid          BIGINT NOT NULL REFERENCES af_user (uid) ON DELETE CASCADE,
+    workspace_id UUID   NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    view_id      UUID,
+    muted_until  TIMESTAMP WITH TIME ZONE,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_uid_workspace_id_view_id_on_af_notification_mute
+    ON af_notification_mute (uid, workspace_id, COALESCE(view_id, '00000000-0000-0000-0000-000000000000'::UUID));
+
+CREATE TABLE IF NOT EXISTS af_notification_settings
+(
+    uid                  BIGINT  NOT NULL PRIMARY KEY REFERENCES af_user (uid) ON DELETE CASCADE,
+    email_digest_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at           TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Members currently mentioned in a document. A member is notified when it's mentioned in a
+-- document it wasn't mentioned in before.
+CREATE TABLE IF NOT EXISTS af_document_mention
+(
+    view_id      UUID   NOT NULL,
+    uid          BIGINT NOT NULL REFERENCES af_user (uid) ON DELETE CASCADE,
+    workspace_id UUID   NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (view_id, uid)
+);
+
+-- Documents whose mentions were stored at least once. The mentions found in a document the first
+-- time it is saved are stored without notifying anyone, unless the document was just created: they
+-- were added before the mentions were tracked.
+CREATE TABLE IF NOT EXISTS af_document_mention_seen
+(
+    view_id      UUID PRIMARY KEY,
+    workspace_id UUID NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Push the new notifications to their recipient.
+CREATE OR REPLACE FUNCTION notify_af_notification_insert() RETURNS TRIGGER AS $$
+DECLARE
+    payload TEXT;
+BEGIN
+    payload := json_build_object(
+            'notification_id', NEW.notification_id,
+            'uid', NEW.uid,
+            'workspace_id', NEW.workspace_id,
+            'kind', NEW.kind,
+            'view_id', NEW.view_id,
+            'object_id', NEW.object_id,
+            'actor_uid', NEW.actor_uid,
+            'payload', NEW.payload,
+            'created_at', NEW.created_at
+            )::text;
+
+    PERFORM pg_notify('af_notification_channel', payload);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS af_notification_insert_trigger ON af_notification;
+CREATE TRIGGER af_notification_insert_trigger
+    AFTER INSERT ON af_notification
+    FOR EACH ROW
+EXECUTE FUNCTION notify_af_notification_insert();
@@ -0,0 +1,34 @@
+-- Reminders set in the date mentions of a document or in the date cells of a database row. The
+-- reminders of a collab are replaced every time the collab is saved, so a reminder removed from
+-- the content is cancelled and a moved date is rescheduled.
+CREATE TABLE IF NOT EXISTS af_reminder
+(
+    -- id of the document or the database row the reminder is set in
+    object_id    UUID   NOT NULL,
+    -- id generated by the client when the reminder was set
+    reminder_id  TEXT   NOT NULL,
+    workspace_id UUID   NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    -- the page to open, NULL for a database row
+    view_id      UUID,
+    scheduled_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    payload      JSONB  NOT NULL DEFAULT '{}',
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (object_id, reminder_id)
+);
+CREATE INDEX IF NOT EXISTS idx_scheduled_at_on_af_reminder ON af_reminder (scheduled_at);
+CREATE INDEX IF NOT EXISTS idx_workspace_reminder_on_af_reminder ON af_reminder (workspace_id, reminder_id);
+
+-- The users reminded of a reminder. A reminder belongs to the user that set it, who keeps it in its
+-- user awareness collab, while the date it is set on is in a document or a database row that the
+-- other members edit too. A reminder is fired once per owner.
+CREATE TABLE IF NOT EXISTS af_reminder_owner
+(
+    workspace_id UUID   NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    -- id generated by the client when the reminder was set
+    reminder_id  TEXT   NOT NULL,
+    uid          BIGINT NOT NULL REFERENCES af_user (uid) ON DELETE CASCADE,
+    -- set once the reminder was claimed by the worker for the owner
+    fired_at     TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (workspace_id, reminder_id, uid)
+);
+CREATE INDEX IF NOT EXISTS idx_uid_on_af_reminder_owner ON af_reminder_owner (workspace_id, uid);
@@ -0,0 +1,23 @@
+-- How often a user gets the email digest of the activity of a workspace. The row without a
+-- workspace is the default of the user, the rows with a workspace override it. The frequency is
+-- the `ActivityDigestFrequency` of the notification api: 0 off, 1 daily, 2 weekly.
+CREATE TABLE IF NOT EXISTS af_activity_digest_setting
+(
+    uid          BIGINT   NOT NULL REFERENCES af_user (uid) ON DELETE CASCADE,
+    workspace_id UUID     REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    frequency    SMALLINT NOT NULL,
+    updated_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_uid_workspace_id_on_af_activity_digest_setting
+    ON af_activity_digest_setting (uid, COALESCE(workspace_id, '00000000-0000-0000-0000-000000000000'::UUID));
+
+-- When the last digest of the workspace was compiled for the user. The next digest covers the
+-- activity since then.
+CREATE TABLE IF NOT EXISTS af_activity_digest_sent
+(
+    uid          BIGINT NOT NULL REFERENCES af_user (uid) ON DELETE CASCADE,
+    workspace_id UUID   NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    sent_at      TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (uid, workspace_id)
+);
+
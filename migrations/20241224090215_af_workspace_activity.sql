@@ -0,0 +1,38 @@
+-- Feed of the meaningful events of a workspace: pages created, renamed, moved, trashed, restored
+-- and published, database rows added and members joined. The kind is the `WorkspaceActivityKind`
+-- of the workspace api. object_id is the view id for the page events, the row id for the database
+-- rows and the uid for the members. The metadata only holds what can't be looked up later, like
+-- the previous name of a renamed page.
+CREATE TABLE IF NOT EXISTS af_workspace_activity
+(
+    activity_id  BIGSERIAL PRIMARY KEY,
+    workspace_id UUID                     NOT NULL REFERENCES af_workspace (workspace_id) ON DELETE CASCADE,
+    actor_uid    BIGINT,
+    kind         SMALLINT                 NOT NULL,
+    object_id    TEXT                     NOT NULL,
+    metadata     JSONB                    NOT NULL DEFAULT '{}'::jsonb,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_af_workspace_activity_workspace_id
+    ON af_workspace_activity (workspace_id, activity_id DESC);
+
+-- Members join through invitations, access requests, SCIM provisioning or the admin api, so the
+-- event is recorded for every new member. The owner added along with the workspace is left out.
+CREATE OR REPLACE FUNCTION af_workspace_member_activity_trigger()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM af_workspace
+        WHERE workspace_id = NEW.workspace_id AND owner_uid = NEW.uid
+    ) THEN
+        INSERT INTO af_workspace_activity (workspace_id, actor_uid, kind, object_id)
+        VALUES (NEW.workspace_id, NEW.uid, 6, NEW.uid::TEXT);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER af_workspace_member_after_insert_activity
+AFTER INSERT ON af_workspace_member
+FOR EACH ROW
+EXECUTE FUNCTION af_workspace_member_activity_trigger();
@@ -268,7 +268,15 @@ async fn start_connect(
       );
 
       // Receive user change notifications and send them to the client.
-      listen_on_user_change(state, uid, tx);
+      listen_on_user_change(state, uid, tx.clone());
+      // Receive the messages of the chats the user participates in.
+      listen_on_chat_message_change(state, uid, tx.clone());
+      // Receive the access requests to approve and the answers to the requests of the user.
+      listen_on_access_request_change(state, uid, tx.clone());
+      // Receive the comments on the documents of the workspaces of the user.
+      listen_on_document_comment_change(state, uid, tx.clone());
+      // Receive the new notifications of the user.
+      listen_on_notification(state, uid, tx);
 
       match ws::WsResponseBuilder::new(client, request, payload)
         .frame_size(MAX_FRAME_SIZE * 2)
@@ -316,6 +324,62 @@ fn listen_on_user_change(state: &Data<AppState>, uid: i64, tx: Sender<RealtimeMe
   });
 }
 
+fn listen_on_chat_message_change(state: &Data<AppState>, uid: i64, tx: Sender<RealtimeMessage>) {
+  let mut chat_message_recv = state.pg_listeners.subscribe_chat_message_change(uid);
+  actix::spawn(async move {
+    while let Some(change) = chat_message_recv.recv().await {
+      trace!("Receive chat message change: {:?}", change);
+      let msg = UserMessage::ChatMessageChange(change);
+      if tx.send(RealtimeMessage::User(msg)).await.is_err() {
+        break;
+      }
+    }
+  });
+}
+
+fn listen_on_access_request_change(state: &Data<AppState>, uid: i64, tx: Sender<RealtimeMessage>) {
+  let mut access_request_recv = state.pg_listeners.subscribe_access_request_change(uid);
+  actix::spawn(async move {
+    while let Some(change) = access_request_recv.recv().await {
+      trace!("Receive access request change: {:?}", change);
+      let msg = UserMessage::AccessRequestChange(change);
+      if tx.send(RealtimeMessage::User(msg)).await.is_err() {
+        break;
+      }
+    }
+  });
+}
+
+fn listen_on_document_comment_change(
+  state: &Data<AppState>,
+  uid: i64,
+  tx: Sender<RealtimeMessage>,
+) {
+  let mut document_comment_recv = state.pg_listeners.subscribe_document_comment_change(uid);
+  actix::spawn(async move {
+    while let Some(change) = document_comment_recv.recv().await {
+      trace!("Receive document comment change: {:?}", change);
+      let msg = UserMessage::DocumentCommentChange(change);
+      if tx.send(RealtimeMessage::User(msg)).await.is_err() {
+        break;
+      }
+    }
+  });
+}
+
+fn listen_on_notification(state: &Data<AppState>, uid: i64, tx: Sender<RealtimeMessage>) {
+  let mut notification_recv = state.pg_listeners.subscribe_notification(uid);
+  actix::spawn(async move {
+    while let Some(notification) = notification_recv.recv().await {
+      trace!("Receive notification: {:?}", notification);
+      let msg = UserMessage::Notification(notification);
+      if tx.send(RealtimeMessage::User(msg)).await.is_err() {
+        break;
+      }
+    }
+  });
+}
+
 struct ConnectInfo {
   access_token: String,
   client_version: Version,
@@ -28,7 +28,13 @@ use access_control::casbin::access::AccessControl;
 use appflowy_ai_client::client::AppFlowyAIClient;
 use database::file::s3_client_impl::AwsS3BucketClientImpl;
 
+use crate::collab::document_mention::DocumentMentionNotifier;
+use crate::collab::live_publish::LivePublisher;
+use crate::collab::persistence_hook::PersistenceHook;
+use crate::collab::reminder::{ReminderOwnerRecorder, ReminderScheduler};
 use crate::collab::storage::CollabStorageImpl;
+use crate::collab::view_permission::ViewPermissionRefresher;
+use crate::collab::workspace_activity::WorkspaceActivityRecorder;
 use crate::command::{CLCommandReceiver, CLCommandSender};
 use crate::config::{Config, DatabaseSetting, S3Setting};
 use crate::indexer::IndexerProvider;
@@ -76,6 +82,7 @@ pub async fn run_actix_server(
     storage.clone(),
     Arc::new(RealtimeCollabAccessControlImpl::new(
       state.access_control.clone(),
+      state.pg_pool.clone(),
     )),
     state.metrics.realtime_metrics.clone(),
     rt_cmd_recv,
@@ -83,6 +90,7 @@ pub async fn run_actix_server(
     config.collab.edit_state_max_count,
     config.collab.edit_state_max_secs,
     state.indexer_provider.clone(),
+    state.persistence_hooks.clone(),
   )
   .await
   .unwrap();
@@ -124,8 +132,36 @@ pub async fn init_state(config: &Config, rt_cmd_tx: CLCommandSender) -> Result<A
     config.s3.bucket.clone(),
   );
 
-  let collab_access_control = CollabAccessControlImpl::new(access_control.clone());
+  let collab_access_control = CollabAccessControlImpl::new(access_control.clone(), pg_pool.clone());
   let workspace_access_control = WorkspaceAccessControlImpl::new(access_control.clone());
+  let persistence_hooks: Vec<Arc<dyn PersistenceHook>> = vec![
+    Arc::new(LivePublisher::new(
+      pg_pool.clone(),
+      s3_client.clone(),
+      Duration::from_secs(config.collab.live_publish_debounce_secs),
+    )),
+    Arc::new(DocumentMentionNotifier::new(
+      pg_pool.clone(),
+      Duration::from_secs(config.collab.mention_notify_debounce_secs),
+    )),
+    Arc::new(ReminderScheduler::new(
+      pg_pool.clone(),
+      Duration::from_secs(config.collab.reminder_sync_debounce_secs),
+    )),
+    Arc::new(ReminderOwnerRecorder::new(
+      pg_pool.clone(),
+      Duration::from_secs(config.collab.reminder_sync_debounce_secs),
+    )),
+    Arc::new(ViewPermissionRefresher::new(
+      pg_pool.clone(),
+      Arc::new(collab_access_control.clone()),
+      Duration::from_secs(config.collab.view_permission_refresh_debounce_secs),
+    )),
+    Arc::new(WorkspaceActivityRecorder::new(
+      pg_pool.clone(),
+      redis_conn_manager.clone(),
+    )),
+  ];
   let collab_cache = CollabCache::new(
     redis_conn_manager.clone(),
     pg_pool.clone(),
@@ -150,9 +186,11 @@ pub async fn init_state(config: &Config, rt_cmd_tx: CLCommandSender) -> Result<A
     snapshot_control,
     rt_cmd_tx,
     metrics.collab_metrics.clone(),
+    redis_conn_manager.clone(),
   ));
   let app_state = AppState {
     config: Arc::new(config.clone()),
+    pg_pool,
     pg_listeners,
     user_cache,
     redis_connection_manager: redis_conn_manager,
@@ -160,6 +198,7 @@ pub async fn init_state(config: &Config, rt_cmd_tx: CLCommandSender) -> Result<A
     collab_access_control_storage: collab_storage,
     metrics,
     indexer_provider,
+    persistence_hooks,
   };
   Ok(app_state)
 }
@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use bytes::Bytes;
+use collab::preclude::Collab;
+use collab_document::document::DocumentBody;
+use collab_entity::CollabType;
+use sqlx::PgPool;
+use tracing::{error, trace};
+use uuid::Uuid;
+
+use app_error::AppError;
+use database::notification::{
+  insert_notifications, replace_document_mentions, select_workspace_member_uids_by_uuid,
+};
+use shared_entity::dto::notification_dto::NotificationKind;
+
+use super::persistence_hook::{DebouncedQueue, PersistedCollab, PersistenceHook};
+
+struct DocumentMentions {
+  workspace_id: Uuid,
+  view_id: Uuid,
+  is_new: bool,
+  /// The users whose changes were saved, the latest editor last.
+  editors: Vec<i64>,
+  user_uuids: Vec<Uuid>,
+}
+
+impl DocumentMentions {
+  /// Keeps the latest mentions, and the editors of all the saves.
+  fn merge(&mut self, latest: DocumentMentions) {
+    self.is_new |= latest.is_new;
+    for editor in latest.editors {
+      self.editors.retain(|uid| *uid != editor);
+      self.editors.push(editor);
+    }
+    self.user_uuids = latest.user_uuids;
+  }
+
+  /// The member that mentioned the user: the latest editor other than the user. Returns None if the
+  /// user mentioned itself, and Some(None) if the editors are unknown.
+  fn mentioned_by(&self, uid: i64) -> Option<Option<i64>> {
+    if self.editors.is_empty() {
+      return Some(None);
+    }
+    self
+      .editors
+      .iter()
+      .rev()
+      .find(|editor| **editor != uid)
+      .map(|editor| Some(*editor))
+  }
+}
+
+/// Notifies the members mentioned in the documents.
+///
+/// The person mentions of a document are queued after each save and the pending documents are
+/// processed every `debounce` interval. A member is notified when it's mentioned in a document it
+/// wasn't mentioned in before, so removing and adding back a mention within the interval doesn't
+/// notify twice. The mentions of a document seen for the first time are stored without notifying,
+/// unless the document was just created, and the members that mention themselves are not notified.
+pub struct DocumentMentionNotifier {
+  queue: DebouncedQueue<Uuid, DocumentMentions>,
+}
+
+impl DocumentMentionNotifier {
+  pub fn new(pg_pool: PgPool, debounce: Duration) -> Self {
+    let queue = DebouncedQueue::with_merge(
+      debounce,
+      DocumentMentions::merge,
+      move |pending: Vec<DocumentMentions>| {
+        let pg_pool = pg_pool.clone();
+        async move {
+          for mentions in pending {
+            if let Err(err) = notify_mentions(&pg_pool, &mentions).await {
+              error!(
+                "Failed to notify the mentions of document {}: {}",
+                mentions.view_id, err
+              );
+            }
+          }
+        }
+      },
+    );
+    Self { queue }
+  }
+}
+
+impl PersistenceHook for DocumentMentionNotifier {
+  fn is_enabled(&self, collab_type: &CollabType) -> bool {
+    matches!(collab_type, CollabType::Document)
+  }
+
+  fn on_saved(&self, collab: &PersistedCollab, content: &Collab, _encoded_collab: &Bytes) {
+    let (workspace_id, view_id) = match (
+      Uuid::parse_str(&collab.workspace_id),
+      Uuid::parse_str(&collab.object_id),
+    ) {
+      (Ok(workspace_id), Ok(view_id)) => (workspace_id, view_id),
+      _ => return,
+    };
+    self.queue.push(
+      view_id,
+      DocumentMentions {
+        workspace_id,
+        view_id,
+        is_new: collab.is_new,
+        editors: collab.editors.clone(),
+        user_uuids: person_mentions(content),
+      },
+    );
+  }
+}
+
+/// Returns the users mentioned in the document. Person mentions are stored in the deltas of the
+/// document texts as `{"mention": {"type": "person", "person_id": <user uuid>}}` attributes.
+fn person_mentions(collab: &Collab) -> Vec<Uuid> {
+  let document_data = match DocumentBody::from_collab(collab)
+    .and_then(|body| body.get_document_data(&collab.transact()).ok())
+  {
+    Some(document_data) => document_data,
+    None => return vec![],
+  };
+  let mut user_uuids = HashSet::new();
+  for delta in document_data
+    .meta
+    .text_map
+    .iter()
+    .flat_map(|map| map.values())
+  {
+    let ops = match serde_json::from_str::<serde_json::Value>(delta) {
+      Ok(serde_json::Value::Array(ops)) => ops,
+      _ => continue,
+    };
+    user_uuids.extend(
+      ops
+        .iter()
+        .flat_map(|op| op.get("attributes"))
+        .flat_map(|attributes| attributes.get("mention"))
+        .filter(|mention| mention.get("type").and_then(|t| t.as_str()) == Some("person"))
+        .flat_map(|mention| mention.get("person_id"))
+        .flat_map(|person_id| person_id.as_str())
+        .flat_map(|person_id| Uuid::parse_str(person_id).ok()),
+    );
+  }
+  user_uuids.into_iter().collect()
+}
+
+async fn notify_mentions(pg_pool: &PgPool, mentions: &DocumentMentions) -> Result<(), AppError> {
+  // Mentions of users that are not members of the workspace are ignored
+  let uids =
+    select_workspace_member_uids_by_uuid(pg_pool, &mentions.workspace_id, &mentions.user_uuids)
+      .await?;
+  let mut txn = pg_pool.begin().await?;
+  let new_uids = replace_document_mentions(
+    &mut txn,
+    &mentions.workspace_id,
+    &mentions.view_id,
+    mentions.is_new,
+    &uids,
+  )
+  .await?;
+  let mut recipients_by_actor: HashMap<Option<i64>, Vec<i64>> = HashMap::new();
+  for uid in new_uids {
+    if let Some(actor_uid) = mentions.mentioned_by(uid) {
+      recipients_by_actor.entry(actor_uid).or_default().push(uid);
+    }
+  }
+  let mut notified = vec![];
+  for (actor_uid, recipients) in recipients_by_actor {
+    notified.extend(
+      insert_notifications(
+        txn.as_mut(),
+        &recipients,
+        &mentions.workspace_id,
+        NotificationKind::DocumentMention,
+        Some(&mentions.view_id),
+        None,
+        actor_uid,
+        &serde_json::json!({}),
+      )
+      .await?,
+    );
+  }
+  txn.commit().await?;
+  if !notified.is_empty() {
+    trace!(
+      "Notified {} members mentioned in document {}",
+      notified.len(),
+      mentions.view_id
+    );
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn mentions(editors: Vec<i64>) -> DocumentMentions {
+    DocumentMentions {
+      workspace_id: Uuid::nil(),
+      view_id: Uuid::nil(),
+      is_new: false,
+      editors,
+      user_uuids: vec![],
+    }
+  }
+
+  #[test]
+  fn mentioned_by_latest_other_editor_test() {
+    let mentions = mentions(vec![1, 2, 3]);
+    assert_eq!(mentions.mentioned_by(4), Some(Some(3)));
+    assert_eq!(mentions.mentioned_by(3), Some(Some(2)));
+  }
+
+  #[test]
+  fn self_mention_is_not_notified_test() {
+    assert_eq!(mentions(vec![1]).mentioned_by(1), None);
+    assert_eq!(mentions(vec![]).mentioned_by(1), Some(None));
+  }
+
+  #[test]
+  fn merge_keeps_editors_of_all_saves_test() {
+    let mut pending = mentions(vec![1, 2]);
+    let mut latest = mentions(vec![1]);
+    latest.is_new = true;
+    pending.merge(latest);
+    assert_eq!(pending.editors, vec![2, 1]);
+    assert!(pending.is_new);
+  }
+}
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use collab::preclude::Collab;
+use collab_entity::CollabType;
+use sqlx::PgPool;
+use tracing::{error, trace};
+use uuid::Uuid;
+use yrs::{ReadTxn, StateVector};
+
+use database::file::s3_client_impl::AwsS3BucketClientImpl;
+use database::file::BucketClient;
+use database::publish::{
+  published_collab_s3_key, select_live_published_view_ids, update_live_published_collab_blobs,
+};
+
+use super::persistence_hook::{DebouncedQueue, PersistedCollab, PersistenceHook};
+
+struct LivePublishUpdate {
+  workspace_id: Uuid,
+  view_id: Uuid,
+  doc_state: Vec<u8>,
+}
+
+/// Re-materialises the published blob of the documents published in live mode.
+///
+/// The doc state of a document is queued after each save and the latest doc state of the pending
+/// documents are written at once, every `debounce` interval. Documents that are not published live
+/// are skipped.
+pub struct LivePublisher {
+  queue: DebouncedQueue<Uuid, LivePublishUpdate>,
+}
+
+impl LivePublisher {
+  pub fn new(pg_pool: PgPool, bucket_client: AwsS3BucketClientImpl, debounce: Duration) -> Self {
+    let queue = DebouncedQueue::new(debounce, move |updates| {
+      let pg_pool = pg_pool.clone();
+      let bucket_client = bucket_client.clone();
+      async move { publish_updates(&pg_pool, &bucket_client, updates).await }
+    });
+    Self { queue }
+  }
+}
+
+impl PersistenceHook for LivePublisher {
+  fn is_enabled(&self, collab_type: &CollabType) -> bool {
+    matches!(collab_type, CollabType::Document)
+  }
+
+  fn on_saved(&self, collab: &PersistedCollab, content: &Collab, _encoded_collab: &Bytes) {
+    let (workspace_id, view_id) = match (
+      Uuid::parse_str(&collab.workspace_id),
+      Uuid::parse_str(&collab.object_id),
+    ) {
+      (Ok(workspace_id), Ok(view_id)) => (workspace_id, view_id),
+      _ => return,
+    };
+    // The published blob of a document is its doc state
+    let doc_state = content
+      .transact()
+      .encode_state_as_update_v1(&StateVector::default());
+    self.queue.push(
+      view_id,
+      LivePublishUpdate {
+        workspace_id,
+        view_id,
+        doc_state,
+      },
+    );
+  }
+}
+
+async fn publish_updates(
+  pg_pool: &PgPool,
+  bucket_client: &AwsS3BucketClientImpl,
+  mut updates: Vec<LivePublishUpdate>,
+) {
+  // Most of the persisted documents are not published live, filter them out before sending the
+  // doc states to the database.
+  let view_ids: Vec<Uuid> = updates.iter().map(|update| update.view_id).collect();
+  match select_live_published_view_ids(pg_pool, &view_ids).await {
+    Ok(live_view_ids) => updates.retain(|update| live_view_ids.contains(&update.view_id)),
+    Err(err) => {
+      error!("Failed to select live published collabs: {}", err);
+      return;
+    },
+  }
+  if updates.is_empty() {
+    return;
+  }
+
+  let mut workspace_ids = Vec::with_capacity(updates.len());
+  let mut view_ids = Vec::with_capacity(updates.len());
+  let mut blobs = Vec::with_capacity(updates.len());
+  for update in updates {
+    workspace_ids.push(update.workspace_id);
+    view_ids.push(update.view_id);
+    blobs.push(update.doc_state);
+  }
+
+  let published =
+    match update_live_published_collab_blobs(pg_pool, &workspace_ids, &view_ids, &blobs).await {
+      Ok(published) => published,
+      Err(err) => {
+        error!("Failed to update live published collabs: {}", err);
+        return;
+      },
+    };
+  if published.is_empty() {
+    return;
+  }
+  trace!("Republished {} live published collabs", published.len());
+
+  // Postgres holds the latest blob, remove the copies stored in S3 at publish time so that the
+  // readers fall back to it.
+  let object_keys = published
+    .iter()
+    .map(|key| published_collab_s3_key(&key.workspace_id, &key.view_id))
+    .collect();
+  if let Err(err) = bucket_client.delete_blobs(object_keys).await {
+    error!("Failed to delete stale published collabs from S3: {}", err);
+  }
+}
@@ -1,3 +1,9 @@
 pub mod access_control;
+pub mod document_mention;
+pub mod live_publish;
+pub mod persistence_hook;
+pub mod reminder;
 pub mod storage;
 pub mod validator;
+pub mod view_permission;
+pub mod workspace_activity;
@@ -0,0 +1,105 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::time::Duration;
+
+use bytes::Bytes;
+use collab::preclude::Collab;
+use collab_entity::CollabType;
+use tokio::sync::mpsc;
+use tokio::time::interval;
+
+/// The collab a [PersistenceHook] is called for.
+#[derive(Debug, Clone)]
+pub struct PersistedCollab {
+  pub workspace_id: String,
+  pub object_id: String,
+  pub collab_type: CollabType,
+  /// The user that opened the collab group, not necessarily the one that made the changes.
+  pub uid: i64,
+  /// Whether the collab was created in the group and saved for the first time.
+  pub is_new: bool,
+  /// The users whose changes were saved, the latest editor last. Empty when the collab is opened or
+  /// closed.
+  pub editors: Vec<i64>,
+}
+
+/// Derives data from the collabs persisted by the collab groups, e.g. to republish a document or
+/// to notify the members mentioned in it.
+///
+/// The hooks are called by the persistence of the group with the collab locked, so they only read
+/// what they need from the collab and hand it over to their own task.
+pub trait PersistenceHook: Send + Sync {
+  /// Whether the hook is called for the collabs of this type.
+  fn is_enabled(&self, collab_type: &CollabType) -> bool;
+
+  /// Called when the group of the collab is created.
+  fn on_opened(&self, _collab: &PersistedCollab, _content: &Collab) {}
+
+  /// Called on a blocking thread once the collab was saved, with the encoded collab that was
+  /// written to the storage.
+  fn on_saved(&self, collab: &PersistedCollab, content: &Collab, encoded_collab: &Bytes);
+
+  /// Called when the group of the collab is destroyed, after the last save.
+  fn on_closed(&self, _collab: &PersistedCollab) {}
+}
+
+/// Hands the items queued by a [PersistenceHook] over to `process` every `debounce` interval. Only
+/// the latest item of a key is kept, so a collab saved several times within the interval is
+/// processed once.
+pub struct DebouncedQueue<K, T> {
+  tx: mpsc::UnboundedSender<(K, T)>,
+}
+
+impl<K, T> DebouncedQueue<K, T>
+where
+  K: Eq + Hash + Send + 'static,
+  T: Send + 'static,
+{
+  pub fn new<F, Fut>(debounce: Duration, process: F) -> Self
+  where
+    F: FnMut(Vec<T>) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+  {
+    Self::with_merge(debounce, |pending, item| *pending = item, process)
+  }
+
+  /// Same as [DebouncedQueue::new], but an item queued for a key that is already pending is merged
+  /// into the pending item rather than replacing it.
+  pub fn with_merge<M, F, Fut>(debounce: Duration, merge: M, mut process: F) -> Self
+  where
+    M: Fn(&mut T, T) + Send + 'static,
+    F: FnMut(Vec<T>) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+  {
+    let (tx, mut rx) = mpsc::unbounded_channel::<(K, T)>();
+    tokio::spawn(async move {
+      let mut pending: HashMap<K, T> = HashMap::new();
+      let mut interval = interval(debounce);
+      loop {
+        tokio::select! {
+          item = rx.recv() => match item {
+            Some((key, item)) => match pending.entry(key) {
+              Entry::Occupied(mut entry) => merge(entry.get_mut(), item),
+              Entry::Vacant(entry) => {
+                entry.insert(item);
+              },
+            },
+            None => break,
+          },
+          _ = interval.tick() => {
+            if !pending.is_empty() {
+              process(pending.drain().map(|(_, item)| item).collect()).await;
+            }
+          }
+        }
+      }
+    });
+    Self { tx }
+  }
+
+  pub fn push(&self, key: K, item: T) {
+    let _ = self.tx.send((key, item));
+  }
+}
@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+use client_api_entity::id::user_awareness_object_id;
+use collab::core::collab::DataSource;
+use collab::core::origin::CollabOrigin;
+use collab::preclude::Collab;
+use collab_database::rows::RowDetail;
+use collab_document::document::DocumentBody;
+use collab_entity::{CollabType, EncodedCollab};
+use collab_user::core::UserAwareness;
+use sqlx::PgPool;
+use tracing::{error, trace};
+use uuid::Uuid;
+use yrs::Any;
+
+use app_error::AppError;
+use database::reminder::{replace_reminder_owners, replace_reminders, ReminderParams};
+use database::user::select_web_user_from_uid;
+
+use super::persistence_hook::{DebouncedQueue, PersistedCollab, PersistenceHook};
+
+const MAX_PREVIEW_LENGTH: usize = 200;
+/// Reminders set for a whole day are sent at this hour of the day.
+const DAY_REMINDER_HOUR: u32 = 9;
+
+struct CollabReminders {
+  workspace_id: Uuid,
+  object_id: Uuid,
+  view_id: Option<Uuid>,
+  reminders: Vec<ReminderParams>,
+}
+
+/// Stores the reminders set in the documents and the database rows, so that the appflowy worker
+/// can fire them when they are due even if no client is online.
+///
+/// The reminders of a collab are queued after each save and the pending collabs are processed
+/// every `debounce` interval. The stored reminders of a collab are replaced, which cancels the
+/// reminders removed from the content. The users reminded are stored by [ReminderOwnerRecorder].
+pub struct ReminderScheduler {
+  queue: DebouncedQueue<Uuid, CollabReminders>,
+}
+
+impl ReminderScheduler {
+  pub fn new(pg_pool: PgPool, debounce: Duration) -> Self {
+    let queue = DebouncedQueue::new(debounce, move |pending: Vec<CollabReminders>| {
+      let pg_pool = pg_pool.clone();
+      async move {
+        for reminders in pending {
+          if let Err(err) = store_reminders(&pg_pool, &reminders).await {
+            error!(
+              "Failed to store the reminders of {}: {}",
+              reminders.object_id, err
+            );
+          }
+        }
+      }
+    });
+    Self { queue }
+  }
+}
+
+impl PersistenceHook for ReminderScheduler {
+  /// Reminders are set in the date mentions of documents and the date cells of database rows
+  fn is_enabled(&self, collab_type: &CollabType) -> bool {
+    matches!(collab_type, CollabType::Document | CollabType::DatabaseRow)
+  }
+
+  fn on_saved(&self, collab: &PersistedCollab, content: &Collab, _encoded_collab: &Bytes) {
+    let (workspace_id, object_id) = match (
+      Uuid::parse_str(&collab.workspace_id),
+      Uuid::parse_str(&collab.object_id),
+    ) {
+      (Ok(workspace_id), Ok(object_id)) => (workspace_id, object_id),
+      _ => return,
+    };
+    // The id of a document is the id of its page
+    let view_id = matches!(collab.collab_type, CollabType::Document).then_some(object_id);
+    self.queue.push(
+      object_id,
+      CollabReminders {
+        workspace_id,
+        object_id,
+        view_id,
+        reminders: collab_reminders(&collab.object_id, content, &collab.collab_type),
+      },
+    );
+  }
+}
+
+struct SavedUserAwareness {
+  workspace_id: Uuid,
+  object_id: Uuid,
+  uid: i64,
+  encoded_collab: Bytes,
+}
+
+/// Stores the reminders each user set, listed in its user awareness collab, so that only the user
+/// that set a reminder is reminded, whoever edits the document or the database row it is set on.
+///
+/// The user awareness collabs are queued after each save and the pending collabs are processed
+/// every `debounce` interval.
+pub struct ReminderOwnerRecorder {
+  queue: DebouncedQueue<Uuid, SavedUserAwareness>,
+}
+
+impl ReminderOwnerRecorder {
+  pub fn new(pg_pool: PgPool, debounce: Duration) -> Self {
+    let queue = DebouncedQueue::new(debounce, move |pending: Vec<SavedUserAwareness>| {
+      let pg_pool = pg_pool.clone();
+      async move {
+        for saved in pending {
+          let object_id = saved.object_id;
+          if let Err(err) = store_reminder_owners(&pg_pool, saved).await {
+            error!(
+              "Failed to store the reminder owners of {}: {}",
+              object_id, err
+            );
+          }
+        }
+      }
+    });
+    Self { queue }
+  }
+}
+
+impl PersistenceHook for ReminderOwnerRecorder {
+  fn is_enabled(&self, collab_type: &CollabType) -> bool {
+    matches!(collab_type, CollabType::UserAwareness)
+  }
+
+  fn on_saved(&self, collab: &PersistedCollab, _content: &Collab, encoded_collab: &Bytes) {
+    if let (Ok(workspace_id), Ok(object_id)) = (
+      Uuid::parse_str(&collab.workspace_id),
+      Uuid::parse_str(&collab.object_id),
+    ) {
+      self.queue.push(
+        object_id,
+        SavedUserAwareness {
+          workspace_id,
+          object_id,
+          uid: collab.uid,
+          encoded_collab: encoded_collab.clone(),
+        },
+      );
+    }
+  }
+}
+
+/// The user awareness collab of a user is only opened by the user, and its id is derived from the
+/// id of the user, which tells whether the group was opened by its owner.
+async fn store_reminder_owners(
+  pg_pool: &PgPool,
+  saved: SavedUserAwareness,
+) -> Result<(), AppError> {
+  let SavedUserAwareness {
+    workspace_id,
+    object_id,
+    uid,
+    encoded_collab,
+  } = saved;
+  let user_uuid = select_web_user_from_uid(pg_pool, uid).await?.uuid;
+  if user_awareness_object_id(&user_uuid, &workspace_id.to_string()) != object_id {
+    trace!(
+      "Skip the user awareness {} not opened by its owner",
+      object_id
+    );
+    return Ok(());
+  }
+  let reminder_ids = tokio::task::spawn_blocking(move || {
+    let encoded_collab = EncodedCollab::decode_from_bytes(&encoded_collab)
+      .map_err(|err| AppError::Internal(err.into()))?;
+    let collab = Collab::new_with_source(
+      CollabOrigin::Server,
+      &object_id.to_string(),
+      DataSource::DocStateV1(encoded_collab.doc_state.into()),
+      vec![],
+      false,
+    )
+    .map_err(|err| AppError::Internal(err.into()))?;
+    let user_awareness =
+      UserAwareness::open(collab, None).map_err(|err| AppError::Internal(err.into()))?;
+    let reminder_ids: Vec<String> = user_awareness
+      .get_all_reminders()
+      .into_iter()
+      .map(|reminder| reminder.id)
+      .collect();
+    Ok::<_, AppError>(reminder_ids)
+  })
+  .await
+  .map_err(|err| AppError::Internal(err.into()))??;
+
+  let mut txn = pg_pool.begin().await?;
+  replace_reminder_owners(&mut txn, &workspace_id, uid, &reminder_ids).await?;
+  txn.commit().await?;
+  trace!("Stored {} reminders owned by {}", reminder_ids.len(), uid);
+  Ok(())
+}
+
+/// Returns the reminders set in a document or a database row, with unique reminder ids.
+fn collab_reminders(
+  object_id: &str,
+  collab: &Collab,
+  collab_type: &CollabType,
+) -> Vec<ReminderParams> {
+  let reminders = match collab_type {
+    CollabType::Document => document_reminders(collab),
+    CollabType::DatabaseRow => database_row_reminders(object_id, collab),
+    _ => vec![],
+  };
+  let mut reminder_by_id: HashMap<String, ReminderParams> = HashMap::new();
+  for reminder in reminders {
+    reminder_by_id.insert(reminder.reminder_id.clone(), reminder);
+  }
+  reminder_by_id.into_values().collect()
+}
+
+/// Date mentions with a reminder are stored in the deltas of the document texts as
+/// `{"mention": {"type": "date", "date": <ISO 8601 date>, "reminder_id": <id>,
+/// "reminder_option": <option>, "include_time": <bool>}}` attributes.
+fn document_reminders(collab: &Collab) -> Vec<ReminderParams> {
+  let document_data = match DocumentBody::from_collab(collab)
+    .and_then(|body| body.get_document_data(&collab.transact()).ok())
+  {
+    Some(document_data) => document_data,
+    None => return vec![],
+  };
+  let mut reminders = vec![];
+  for delta in document_data
+    .meta
+    .text_map
+    .iter()
+    .flat_map(|map| map.values())
+  {
+    let ops = match serde_json::from_str::<serde_json::Value>(delta) {
+      Ok(serde_json::Value::Array(ops)) => ops,
+      _ => continue,
+    };
+    let mentions: Vec<&serde_json::Value> = ops
+      .iter()
+      .flat_map(|op| op.get("attributes"))
+      .flat_map(|attributes| attributes.get("mention"))
+      .filter(|mention| mention.get("type").and_then(|t| t.as_str()) == Some("date"))
+      .collect();
+    if mentions.is_empty() {
+      continue;
+    }
+    // The text around the mention tells what the reminder is about
+    let text: String = ops
+      .iter()
+      .flat_map(|op| op.get("insert"))
+      .flat_map(|insert| insert.as_str())
+      .collect();
+    for mention in mentions {
+      let reminder_id = match mention.get("reminder_id").and_then(|id| id.as_str()) {
+        Some(reminder_id) if !reminder_id.is_empty() => reminder_id,
+        _ => continue,
+      };
+      let date = match mention
+        .get("date")
+        .and_then(|date| date.as_str())
+        .and_then(parse_mention_date)
+      {
+        Some(date) => date,
+        None => continue,
+      };
+      let include_time = mention
+        .get("include_time")
+        .and_then(|include_time| include_time.as_bool())
+        .unwrap_or(false);
+      let option = mention
+        .get("reminder_option")
+        .and_then(|option| option.as_str());
+      if let Some(scheduled_at) = reminder_time(date, option, include_time) {
+        reminders.push(ReminderParams {
+          reminder_id: reminder_id.to_string(),
+          scheduled_at,
+          payload: serde_json::json!({ "preview": preview(&text) }),
+        });
+      }
+    }
+  }
+  reminders
+}
+
+/// Date cells with a reminder have a `reminder_id` next to their `data`, the timestamp of the date
+/// in seconds.
+fn database_row_reminders(object_id: &str, collab: &Collab) -> Vec<ReminderParams> {
+  let row_detail = match RowDetail::from_collab(collab) {
+    Some(row_detail) => row_detail,
+    None => return vec![],
+  };
+  let mut reminders = vec![];
+  for cell in row_detail.row.cells.values() {
+    let reminder_id = match cell.get("reminder_id") {
+      Some(Any::String(reminder_id)) if !reminder_id.is_empty() => reminder_id.to_string(),
+      _ => continue,
+    };
+    let timestamp = match cell.get("data") {
+      Some(Any::String(data)) => data.parse::<i64>().ok(),
+      Some(data) => data.clone().cast::<i64>().ok(),
+      None => None,
+    };
+    let date = match timestamp.and_then(|timestamp| DateTime::from_timestamp(timestamp, 0)) {
+      Some(date) => date,
+      None => continue,
+    };
+    let include_time = matches!(cell.get("include_time"), Some(Any::Bool(true)));
+    if let Some(scheduled_at) = reminder_time(date, None, include_time) {
+      reminders.push(ReminderParams {
+        reminder_id,
+        scheduled_at,
+        payload: serde_json::json!({
+          "row_id": object_id,
+          "database_id": row_detail.row.database_id,
+        }),
+      });
+    }
+  }
+  reminders
+}
+
+/// Dates without an offset are stored in the local time of the client, which the server doesn't
+/// know, so they are taken as UTC.
+fn parse_mention_date(date: &str) -> Option<DateTime<Utc>> {
+  if let Ok(date) = DateTime::parse_from_rfc3339(date) {
+    return Some(date.with_timezone(&Utc));
+  }
+  if let Ok(date) = NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S%.f") {
+    return Some(date.and_utc());
+  }
+  NaiveDate::parse_from_str(date, "%Y-%m-%d")
+    .ok()
+    .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+}
+
+/// When the reminder of the date is due, given the `reminder_option` of the client. Returns None
+/// when the reminder was turned off.
+fn reminder_time(
+  date: DateTime<Utc>,
+  option: Option<&str>,
+  include_time: bool,
+) -> Option<DateTime<Utc>> {
+  let on_day = |days_before: u64| {
+    date
+      .date_naive()
+      .checked_sub_days(Days::new(days_before))
+      .and_then(|day| day.and_hms_opt(DAY_REMINDER_HOUR, 0, 0))
+      .map(|time| time.and_utc())
+  };
+  let before = |delta: TimeDelta| date.checked_sub_signed(delta);
+  match option {
+    Some("none") => None,
+    Some("atTimeOfEvent") => Some(date),
+    Some("fiveMinsBefore") => before(TimeDelta::minutes(5)),
+    Some("tenMinsBefore") => before(TimeDelta::minutes(10)),
+    Some("fifteenMinsBefore") => before(TimeDelta::minutes(15)),
+    Some("thirtyMinsBefore") => before(TimeDelta::minutes(30)),
+    Some("oneHourBefore") => before(TimeDelta::hours(1)),
+    Some("twoHoursBefore") => before(TimeDelta::hours(2)),
+    Some("onDayOfEvent") => on_day(0),
+    Some("oneDayBefore") => on_day(1),
+    Some("twoDaysBefore") => on_day(2),
+    Some("oneWeekBefore") => on_day(7),
+    _ if include_time => Some(date),
+    _ => on_day(0),
+  }
+}
+
+fn preview(text: &str) -> String {
+  let text = text.trim();
+  match text.char_indices().nth(MAX_PREVIEW_LENGTH) {
+    Some((end, _)) => format!("{}…", &text[..end]),
+    None => text.to_string(),
+  }
+}
+
+async fn store_reminders(pg_pool: &PgPool, reminders: &CollabReminders) -> Result<(), AppError> {
+  let mut txn = pg_pool.begin().await?;
+  replace_reminders(
+    &mut txn,
+    &reminders.workspace_id,
+    &reminders.object_id,
+    reminders.view_id.as_ref(),
+    &reminders.reminders,
+  )
+  .await?;
+  txn.commit().await?;
+  trace!(
+    "Stored {} reminders of {}",
+    reminders.reminders.len(),
+    reminders.object_id
+  );
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::{TimeZone, Utc};
+
+  use super::{parse_mention_date, reminder_time};
+
+  #[test]
+  fn parse_mention_date_test() {
+    let date = Utc.with_ymd_and_hms(2024, 12, 24, 10, 30, 0).unwrap();
+    assert_eq!(parse_mention_date("2024-12-24T10:30:00.000"), Some(date));
+    assert_eq!(parse_mention_date("2024-12-24T11:30:00+01:00"), Some(date));
+    assert_eq!(
+      parse_mention_date("2024-12-24"),
+      Some(Utc.with_ymd_and_hms(2024, 12, 24, 0, 0, 0).unwrap())
+    );
+    assert_eq!(parse_mention_date("tomorrow"), None);
+  }
+
+  #[test]
+  fn reminder_time_test() {
+    let date = Utc.with_ymd_and_hms(2024, 12, 24, 10, 30, 0).unwrap();
+    assert_eq!(reminder_time(date, Some("none"), true), None);
+    assert_eq!(reminder_time(date, Some("atTimeOfEvent"), true), Some(date));
+    assert_eq!(
+      reminder_time(date, Some("fifteenMinsBefore"), true),
+      Some(Utc.with_ymd_and_hms(2024, 12, 24, 10, 15, 0).unwrap())
+    );
+    assert_eq!(
+      reminder_time(date, Some("oneDayBefore"), false),
+      Some(Utc.with_ymd_and_hms(2024, 12, 23, 9, 0, 0).unwrap())
+    );
+    // Without an option, a date with a time is reminded at that time, a day in the morning
+    assert_eq!(reminder_time(date, None, true), Some(date));
+    assert_eq!(
+      reminder_time(date, None, false),
+      Some(Utc.with_ymd_and_hms(2024, 12, 24, 9, 0, 0).unwrap())
+    );
+  }
+}
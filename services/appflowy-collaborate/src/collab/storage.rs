@@ -30,11 +30,14 @@ use database_entity::dto::{
   AFAccessLevel, AFSnapshotMeta, AFSnapshotMetas, CollabParams, InsertSnapshotParams,
   PendingCollabWrite, QueryCollab, QueryCollabParams, QueryCollabResult, SnapshotData,
 };
+use shared_entity::dto::webhook_dto::WebhookEvent;
 
 use crate::collab::access_control::CollabStorageAccessControlImpl;
 use crate::collab::validator::CollabValidator;
 use crate::metrics::CollabMetrics;
 use crate::snapshot::SnapshotControl;
+use crate::state::RedisConnectionManager;
+use crate::webhook::queue_webhook_event;
 
 pub type CollabAccessControlStorage = CollabStorageImpl<CollabStorageAccessControlImpl>;
 
@@ -48,6 +51,7 @@ pub struct CollabStorageImpl<AC> {
   rt_cmd_sender: CLCommandSender,
   queue: Sender<PendingCollabWrite>,
   metrics: Arc<CollabMetrics>,
+  redis_client: RedisConnectionManager,
 }
 
 impl<AC> CollabStorageImpl<AC>
@@ -60,6 +64,7 @@ where
     snapshot_control: SnapshotControl,
     rt_cmd_sender: CLCommandSender,
     metrics: Arc<CollabMetrics>,
+    redis_client: RedisConnectionManager,
   ) -> Self {
     let (queue, reader) = channel(1000);
     tokio::spawn(Self::periodic_write_task(
@@ -74,6 +79,7 @@ where
       rt_cmd_sender,
       queue,
       metrics,
+      redis_client,
     }
   }
 
@@ -301,11 +307,24 @@ where
         .update_policy(uid, &params.object_id, AFAccessLevel::FullAccess)
         .await?;
     }
+    // Rows are saved at most once per persistence interval while they are being edited, which
+    // also throttles the events.
+    let row_changed = (params.collab_type == CollabType::DatabaseRow)
+      .then(|| serde_json::json!({ "row_id": params.object_id, "created": !is_exist, "uid": uid }));
     if write_immediately {
       self.insert_collab(workspace_id, uid, params).await?;
     } else {
       self.queue_insert_collab(workspace_id, uid, params).await?;
     }
+    if let (Some(data), Ok(workspace_id)) = (row_changed, Uuid::parse_str(workspace_id)) {
+      queue_webhook_event(
+        &self.redis_client,
+        &workspace_id,
+        WebhookEvent::DatabaseRowChanged,
+        data,
+      )
+      .await;
+    }
     Ok(())
   }
 
@@ -0,0 +1,321 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::DerefMut;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use collab::core::origin::CollabOrigin;
+use collab::preclude::Collab;
+use collab_entity::{CollabType, EncodedCollab};
+use collab_folder::Folder;
+use sqlx::PgPool;
+use tracing::{error, trace};
+use uuid::Uuid;
+
+use access_control::collab::CollabAccessControl;
+use app_error::AppError;
+use database::view_permission::{
+  delete_effective_view_permissions, insert_effective_view_permissions, select_user_view_grants,
+  select_view_permission_grantees, AFEffectiveViewPermission,
+};
+use database_entity::dto::AFAccessLevel;
+
+use super::persistence_hook::{DebouncedQueue, PersistedCollab, PersistenceHook};
+
+/// Guards against a cycle in a corrupted folder.
+const MAX_FOLDER_DEPTH: usize = 64;
+
+/// Returns the access level granted on the view or on the closest space or page above it, along
+/// with the view it was granted on. A grant on a page overrides the grants above it.
+pub fn resolve_granted_access_level<F>(
+  view_id: &str,
+  grants: &HashMap<String, AFAccessLevel>,
+  parent_of: F,
+) -> Option<(String, AFAccessLevel)>
+where
+  F: Fn(&str) -> Option<String>,
+{
+  let mut current = view_id.to_string();
+  for _ in 0..MAX_FOLDER_DEPTH {
+    if let Some(level) = grants.get(&current) {
+      return Some((current, *level));
+    }
+    current = parent_of(&current)?;
+  }
+  None
+}
+
+pub fn folder_parent_of(folder: &Folder) -> impl Fn(&str) -> Option<String> + '_ {
+  move |view_id| {
+    folder
+      .get_view(view_id)
+      .map(|view| view.parent_view_id.clone())
+      .filter(|parent_view_id| !parent_view_id.is_empty() && parent_view_id.as_str() != view_id)
+  }
+}
+
+/// Returns the view and all the views below it.
+fn view_and_descendants(folder: &Folder, view_id: &str) -> Vec<String> {
+  let mut visited = HashSet::new();
+  let mut stack = vec![(view_id.to_string(), 0)];
+  while let Some((current, depth)) = stack.pop() {
+    if depth > MAX_FOLDER_DEPTH || !visited.insert(current.clone()) {
+      continue;
+    }
+    if let Some(view) = folder.get_view(&current) {
+      for child in view.children.iter() {
+        stack.push((child.id.clone(), depth + 1));
+      }
+    }
+  }
+  visited.into_iter().collect()
+}
+
+pub fn parse_view_id(view_id: &str) -> Option<Uuid> {
+  match Uuid::parse_str(view_id) {
+    Ok(view_id) => Some(view_id),
+    Err(err) => {
+      error!("Invalid view id {} in folder: {}", view_id, err);
+      None
+    },
+  }
+}
+
+/// Computes the access level of the member on every page below the spaces and pages it was
+/// granted an access level on, and replaces the previous ones in the database and in the access
+/// control. Only the access levels that changed are updated in the access control.
+pub async fn refresh_effective_view_permissions(
+  pg_pool: &PgPool,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  folder: &Folder,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<(), AppError> {
+  let grants: HashMap<String, AFAccessLevel> = select_user_view_grants(pg_pool, workspace_id, uid)
+    .await?
+    .into_iter()
+    .map(|(view_id, level)| (view_id.to_string(), level))
+    .collect();
+
+  let mut view_ids = HashSet::new();
+  for granted_view_id in grants.keys() {
+    view_ids.extend(view_and_descendants(folder, granted_view_id));
+  }
+  let parent_of = folder_parent_of(folder);
+  let permissions: Vec<AFEffectiveViewPermission> = view_ids
+    .iter()
+    .filter_map(|view_id| {
+      let (source_view_id, access_level) =
+        resolve_granted_access_level(view_id, &grants, &parent_of)?;
+      Some(AFEffectiveViewPermission {
+        view_id: parse_view_id(view_id)?,
+        access_level,
+        source_view_id: parse_view_id(&source_view_id)?,
+      })
+    })
+    .collect();
+
+  let mut txn = pg_pool.begin().await?;
+  let previous: HashMap<Uuid, AFAccessLevel> =
+    delete_effective_view_permissions(txn.deref_mut(), workspace_id, uid)
+      .await?
+      .into_iter()
+      .collect();
+  insert_effective_view_permissions(txn.deref_mut(), workspace_id, uid, &permissions).await?;
+  txn.commit().await?;
+
+  let current_view_ids: HashSet<Uuid> = permissions.iter().map(|p| p.view_id).collect();
+  for (view_id, previous_level) in &previous {
+    if !current_view_ids.contains(view_id) {
+      collab_access_control
+        .update_view_access_level(&uid, &view_id.to_string(), Some(*previous_level), None)
+        .await?;
+    }
+  }
+  for permission in &permissions {
+    let previous_level = previous.get(&permission.view_id).copied();
+    if previous_level == Some(permission.access_level) {
+      continue;
+    }
+    collab_access_control
+      .update_view_access_level(
+        &uid,
+        &permission.view_id.to_string(),
+        previous_level,
+        Some(permission.access_level),
+      )
+      .await?;
+  }
+  Ok(())
+}
+
+/// Recomputes the access levels of the given members.
+pub async fn refresh_grantees_view_permissions(
+  pg_pool: &PgPool,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  folder: &Folder,
+  workspace_id: &Uuid,
+  grantees: Vec<i64>,
+) -> Result<(), AppError> {
+  for grantee in grantees {
+    refresh_effective_view_permissions(
+      pg_pool,
+      collab_access_control,
+      folder,
+      workspace_id,
+      grantee,
+    )
+    .await?;
+  }
+  Ok(())
+}
+
+struct SavedFolder {
+  workspace_id: Uuid,
+  encoded_folder: Bytes,
+}
+
+/// Recomputes the access levels of the members that were granted one in a workspace when its
+/// folder is saved, so the pages created or moved by the clients inherit the access levels of the
+/// spaces and pages above them.
+///
+/// The latest folder of a workspace is queued after each save and the pending folders are
+/// processed every `debounce` interval. Workspaces without any grant are skipped.
+pub struct ViewPermissionRefresher {
+  queue: DebouncedQueue<Uuid, SavedFolder>,
+}
+
+impl ViewPermissionRefresher {
+  pub fn new(
+    pg_pool: PgPool,
+    collab_access_control: Arc<dyn CollabAccessControl>,
+    debounce: Duration,
+  ) -> Self {
+    let queue = DebouncedQueue::new(debounce, move |pending: Vec<SavedFolder>| {
+      let pg_pool = pg_pool.clone();
+      let collab_access_control = collab_access_control.clone();
+      async move {
+        for saved in pending {
+          if let Err(err) = refresh_saved_folder(&pg_pool, &collab_access_control, saved).await {
+            error!("Failed to refresh the view permissions: {}", err);
+          }
+        }
+      }
+    });
+    Self { queue }
+  }
+}
+
+impl PersistenceHook for ViewPermissionRefresher {
+  fn is_enabled(&self, collab_type: &CollabType) -> bool {
+    matches!(collab_type, CollabType::Folder)
+  }
+
+  fn on_saved(&self, collab: &PersistedCollab, _content: &Collab, encoded_collab: &Bytes) {
+    if let Ok(workspace_id) = Uuid::parse_str(&collab.workspace_id) {
+      self.queue.push(
+        workspace_id,
+        SavedFolder {
+          workspace_id,
+          encoded_folder: encoded_collab.clone(),
+        },
+      );
+    }
+  }
+}
+
+async fn refresh_saved_folder(
+  pg_pool: &PgPool,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  saved: SavedFolder,
+) -> Result<(), AppError> {
+  let grantees = select_view_permission_grantees(pg_pool, &saved.workspace_id).await?;
+  if grantees.is_empty() {
+    return Ok(());
+  }
+  trace!(
+    "Refresh the view permissions of {} members of workspace {}",
+    grantees.len(),
+    saved.workspace_id
+  );
+  let workspace_id = saved.workspace_id.to_string();
+  let folder = tokio::task::spawn_blocking(move || {
+    let encoded_collab = EncodedCollab::decode_from_bytes(&saved.encoded_folder)
+      .map_err(|err| AppError::Internal(err.into()))?;
+    Folder::from_collab_doc_state(
+      0,
+      CollabOrigin::Server,
+      encoded_collab.into(),
+      &workspace_id,
+      vec![],
+    )
+    .map_err(|err| AppError::Unhandled(err.to_string()))
+  })
+  .await
+  .map_err(|err| AppError::Internal(err.into()))??;
+  refresh_grantees_view_permissions(
+    pg_pool,
+    collab_access_control,
+    &folder,
+    &saved.workspace_id,
+    grantees,
+  )
+  .await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parent_of(view_id: &str) -> Option<String> {
+    // space -> project -> page -> subpage
+    match view_id {
+      "subpage" => Some("page".to_string()),
+      "page" => Some("project".to_string()),
+      "project" => Some("space".to_string()),
+      _ => None,
+    }
+  }
+
+  #[test]
+  fn granted_access_level_is_inherited_test() {
+    let grants = HashMap::from([("project".to_string(), AFAccessLevel::ReadAndWrite)]);
+    assert_eq!(
+      resolve_granted_access_level("subpage", &grants, parent_of),
+      Some(("project".to_string(), AFAccessLevel::ReadAndWrite))
+    );
+    assert_eq!(
+      resolve_granted_access_level("space", &grants, parent_of),
+      None
+    );
+  }
+
+  #[test]
+  fn closest_grant_overrides_inherited_one_test() {
+    let grants = HashMap::from([
+      ("space".to_string(), AFAccessLevel::FullAccess),
+      ("page".to_string(), AFAccessLevel::ReadOnly),
+    ]);
+    assert_eq!(
+      resolve_granted_access_level("subpage", &grants, parent_of),
+      Some(("page".to_string(), AFAccessLevel::ReadOnly))
+    );
+    assert_eq!(
+      resolve_granted_access_level("project", &grants, parent_of),
+      Some(("space".to_string(), AFAccessLevel::FullAccess))
+    );
+  }
+
+  #[test]
+  fn cyclic_folder_terminates_test() {
+    let grants = HashMap::new();
+    let cyclic_parent_of = |view_id: &str| match view_id {
+      "a" => Some("b".to_string()),
+      _ => Some("a".to_string()),
+    };
+    assert_eq!(
+      resolve_granted_access_level("a", &grants, cyclic_parent_of),
+      None
+    );
+  }
+}
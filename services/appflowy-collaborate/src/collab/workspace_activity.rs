@@ -0,0 +1,522 @@
+use std::collections::{HashMap, HashSet};
+
+use bytes::Bytes;
+use collab::core::origin::CollabOrigin;
+use collab::preclude::Collab;
+use collab_database::rows::RowDetail;
+use collab_entity::{CollabType, EncodedCollab};
+use collab_folder::Folder;
+use redis::aio::ConnectionManager;
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use tracing::{error, trace};
+use uuid::Uuid;
+
+use database::workspace_activity::{insert_workspace_activities, WorkspaceActivityParams};
+use shared_entity::dto::webhook_dto::WebhookEvent;
+use shared_entity::dto::workspace_activity_dto::WorkspaceActivityKind;
+
+use super::persistence_hook::{PersistedCollab, PersistenceHook};
+use crate::webhook::queue_webhook_event;
+
+/// The pages of the folder of a workspace, as far as the activity feed is concerned.
+#[derive(Debug, Clone, Default)]
+pub struct FolderSnapshot {
+  pages: HashMap<String, PageSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PageSnapshot {
+  name: String,
+  parent_view_id: String,
+  is_trashed: bool,
+  created_by: Option<i64>,
+  last_edited_by: Option<i64>,
+}
+
+impl FolderSnapshot {
+  pub fn from_folder(workspace_id: &str, folder: &Folder) -> Self {
+    let trash: HashSet<String> = folder
+      .get_all_trash_sections()
+      .into_iter()
+      .map(|section| section.id)
+      .collect();
+    let mut pages = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![workspace_id.to_string()];
+    while let Some(view_id) = stack.pop() {
+      // Guards against a cycle in a corrupted folder
+      if !visited.insert(view_id.clone()) {
+        continue;
+      }
+      let view = match folder.get_view(&view_id) {
+        Some(view) => view,
+        None => continue,
+      };
+      stack.extend(view.children.iter().map(|child| child.id.clone()));
+      if view_id == workspace_id {
+        continue;
+      }
+      pages.insert(
+        view_id.clone(),
+        PageSnapshot {
+          name: view.name.clone(),
+          parent_view_id: view.parent_view_id.clone(),
+          is_trashed: trash.contains(&view_id),
+          created_by: view.created_by,
+          last_edited_by: view.last_edited_by,
+        },
+      );
+    }
+    Self { pages }
+  }
+
+  fn from_encoded_folder(workspace_id: &str, encoded_folder: &[u8]) -> Option<Self> {
+    let encoded_collab = EncodedCollab::decode_from_bytes(encoded_folder).ok()?;
+    let folder = Folder::from_collab_doc_state(
+      0,
+      CollabOrigin::Server,
+      encoded_collab.into(),
+      workspace_id,
+      vec![],
+    )
+    .ok()?;
+    Some(Self::from_folder(workspace_id, &folder))
+  }
+}
+
+/// Returns the activities that turn the `before` folder into the `after` folder. The author of a
+/// change is `actor_uid` when known, or the last editor of the page otherwise. The pages removed
+/// from the folder, which were deleted from the trash, are left out.
+fn folder_activities(
+  before: &FolderSnapshot,
+  after: &FolderSnapshot,
+  actor_uid: Option<i64>,
+) -> Vec<WorkspaceActivityParams> {
+  let mut activities = vec![];
+  for (view_id, page) in &after.pages {
+    let old_page = match before.pages.get(view_id) {
+      Some(old_page) => old_page,
+      None => {
+        activities.push(WorkspaceActivityParams {
+          actor_uid: actor_uid.or(page.created_by).or(page.last_edited_by),
+          kind: WorkspaceActivityKind::PageCreated,
+          object_id: view_id.clone(),
+          metadata: serde_json::json!({
+            "name": page.name,
+            "parent_view_id": page.parent_view_id,
+          }),
+        });
+        continue;
+      },
+    };
+    let actor_uid = actor_uid.or(page.last_edited_by);
+    if old_page.name != page.name {
+      activities.push(WorkspaceActivityParams {
+        actor_uid,
+        kind: WorkspaceActivityKind::PageRenamed,
+        object_id: view_id.clone(),
+        metadata: serde_json::json!({ "old_name": old_page.name, "name": page.name }),
+      });
+    }
+    if old_page.parent_view_id != page.parent_view_id {
+      activities.push(WorkspaceActivityParams {
+        actor_uid,
+        kind: WorkspaceActivityKind::PageMoved,
+        object_id: view_id.clone(),
+        metadata: serde_json::json!({
+          "old_parent_view_id": old_page.parent_view_id,
+          "parent_view_id": page.parent_view_id,
+        }),
+      });
+    }
+    if old_page.is_trashed != page.is_trashed {
+      let kind = if page.is_trashed {
+        WorkspaceActivityKind::PageTrashed
+      } else {
+        WorkspaceActivityKind::PageRestored
+      };
+      activities.push(WorkspaceActivityParams {
+        actor_uid,
+        kind,
+        object_id: view_id.clone(),
+        metadata: serde_json::json!({ "name": page.name }),
+      });
+    }
+  }
+  activities
+}
+
+/// The webhook event of a page activity. The pages changed through the workspace api queue their
+/// webhook events from the api handlers, so this is only used for the changes saved by the collab
+/// group of the folder.
+fn page_webhook_event(
+  activity: &WorkspaceActivityParams,
+) -> Option<(WebhookEvent, serde_json::Value)> {
+  let view_id = &activity.object_id;
+  let uid = activity.actor_uid;
+  match activity.kind {
+    WorkspaceActivityKind::PageCreated => Some((
+      WebhookEvent::PageCreated,
+      serde_json::json!({
+        "view_id": view_id,
+        "parent_view_id": activity.metadata.get("parent_view_id"),
+        "name": activity.metadata.get("name"),
+        "uid": uid,
+      }),
+    )),
+    WorkspaceActivityKind::PageRenamed => Some((
+      WebhookEvent::PageRenamed,
+      serde_json::json!({
+        "view_id": view_id,
+        "name": activity.metadata.get("name"),
+        "uid": uid,
+      }),
+    )),
+    WorkspaceActivityKind::PageTrashed => Some((
+      WebhookEvent::PageTrashed,
+      serde_json::json!({ "view_id": view_id, "uid": uid }),
+    )),
+    _ => None,
+  }
+}
+
+enum ActivityEvent {
+  /// The folder was loaded in a collab group. Its pages are compared with the next saves.
+  FolderOpened {
+    workspace_id: Uuid,
+    encoded_folder: Bytes,
+  },
+  /// The collab group of the folder saved it.
+  FolderSaved {
+    workspace_id: Uuid,
+    encoded_folder: Bytes,
+  },
+  /// The folder was changed through the workspace api.
+  FolderUpdated {
+    workspace_id: Uuid,
+    before: FolderSnapshot,
+    after: FolderSnapshot,
+    actor_uid: i64,
+  },
+  FolderClosed {
+    workspace_id: Uuid,
+  },
+  RowAdded {
+    workspace_id: Uuid,
+    row_id: String,
+    database_id: String,
+    uid: i64,
+  },
+}
+
+/// Records the activities of the workspaces derived from the changes of their folders and the new
+/// database rows, see [WorkspaceActivityKind]. The page changes saved by the collab group of the
+/// folder are also sent to the webhooks of the workspace.
+///
+/// The folder is compared with the last version seen, whether it was saved by its collab group or
+/// changed through the workspace api, so a change made through the api and then applied to the
+/// collab group is only recorded once. The last version of a folder is kept while its collab group
+/// is open.
+pub struct WorkspaceActivityRecorder {
+  tx: mpsc::UnboundedSender<ActivityEvent>,
+}
+
+impl WorkspaceActivityRecorder {
+  pub fn new(pg_pool: PgPool, redis_client: ConnectionManager) -> Self {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(run_activity_recorder(pg_pool, redis_client, rx));
+    Self { tx }
+  }
+
+  pub fn queue_folder_updated(
+    &self,
+    workspace_id: &Uuid,
+    before: FolderSnapshot,
+    after: FolderSnapshot,
+    actor_uid: i64,
+  ) {
+    let _ = self.tx.send(ActivityEvent::FolderUpdated {
+      workspace_id: *workspace_id,
+      before,
+      after,
+      actor_uid,
+    });
+  }
+
+  fn send(&self, workspace_id: &str, event: impl FnOnce(Uuid) -> ActivityEvent) {
+    if let Ok(workspace_id) = Uuid::parse_str(workspace_id) {
+      let _ = self.tx.send(event(workspace_id));
+    }
+  }
+}
+
+impl PersistenceHook for WorkspaceActivityRecorder {
+  /// The workspace activities are derived from the folder and the new database rows
+  fn is_enabled(&self, collab_type: &CollabType) -> bool {
+    matches!(collab_type, CollabType::Folder | CollabType::DatabaseRow)
+  }
+
+  /// The pages of the folder are compared with the ones saved next.
+  fn on_opened(&self, collab: &PersistedCollab, content: &Collab) {
+    if !matches!(collab.collab_type, CollabType::Folder) {
+      return;
+    }
+    let encoded_folder = content
+      .encode_collab_v1(|_| Ok::<_, anyhow::Error>(()))
+      .and_then(|encoded_collab| {
+        encoded_collab
+          .encode_to_bytes()
+          .map_err(anyhow::Error::from)
+      });
+    match encoded_folder {
+      Ok(encoded_folder) => self.send(&collab.workspace_id, |workspace_id| {
+        ActivityEvent::FolderOpened {
+          workspace_id,
+          encoded_folder: Bytes::from(encoded_folder),
+        }
+      }),
+      Err(err) => error!("Failed to encode folder {}: {}", collab.object_id, err),
+    }
+  }
+
+  fn on_saved(&self, collab: &PersistedCollab, content: &Collab, encoded_collab: &Bytes) {
+    match collab.collab_type {
+      CollabType::Folder => self.send(&collab.workspace_id, |workspace_id| {
+        ActivityEvent::FolderSaved {
+          workspace_id,
+          encoded_folder: encoded_collab.clone(),
+        }
+      }),
+      // A new database row was added by the user that opened it
+      CollabType::DatabaseRow if collab.is_new => {
+        if let Some(row_detail) = RowDetail::from_collab(content) {
+          self.send(&collab.workspace_id, |workspace_id| {
+            ActivityEvent::RowAdded {
+              workspace_id,
+              row_id: collab.object_id.clone(),
+              database_id: row_detail.row.database_id,
+              uid: collab.uid,
+            }
+          });
+        }
+      },
+      _ => {},
+    }
+  }
+
+  fn on_closed(&self, collab: &PersistedCollab) {
+    if matches!(collab.collab_type, CollabType::Folder) {
+      self.send(&collab.workspace_id, |workspace_id| {
+        ActivityEvent::FolderClosed { workspace_id }
+      });
+    }
+  }
+}
+
+async fn run_activity_recorder(
+  pg_pool: PgPool,
+  redis_client: ConnectionManager,
+  mut rx: mpsc::UnboundedReceiver<ActivityEvent>,
+) {
+  let mut folders: HashMap<Uuid, FolderSnapshot> = HashMap::new();
+  while let Some(event) = rx.recv().await {
+    let (workspace_id, activities, is_collab_change) = match event {
+      ActivityEvent::FolderOpened {
+        workspace_id,
+        encoded_folder,
+      } => {
+        if !folders.contains_key(&workspace_id) {
+          if let Some(snapshot) = decode_folder(workspace_id, encoded_folder).await {
+            folders.insert(workspace_id, snapshot);
+          }
+        }
+        continue;
+      },
+      ActivityEvent::FolderSaved {
+        workspace_id,
+        encoded_folder,
+      } => {
+        let after = match decode_folder(workspace_id, encoded_folder).await {
+          Some(after) => after,
+          None => continue,
+        };
+        let activities = folders
+          .get(&workspace_id)
+          .map(|before| folder_activities(before, &after, None))
+          .unwrap_or_default();
+        folders.insert(workspace_id, after);
+        (workspace_id, activities, true)
+      },
+      ActivityEvent::FolderUpdated {
+        workspace_id,
+        before,
+        after,
+        actor_uid,
+      } => {
+        let before = folders.get(&workspace_id).unwrap_or(&before);
+        let activities = folder_activities(before, &after, Some(actor_uid));
+        folders.insert(workspace_id, after);
+        (workspace_id, activities, false)
+      },
+      ActivityEvent::FolderClosed { workspace_id } => {
+        folders.remove(&workspace_id);
+        continue;
+      },
+      ActivityEvent::RowAdded {
+        workspace_id,
+        row_id,
+        database_id,
+        uid,
+      } => {
+        let activity = WorkspaceActivityParams {
+          actor_uid: Some(uid),
+          kind: WorkspaceActivityKind::DatabaseRowAdded,
+          object_id: row_id,
+          metadata: serde_json::json!({ "database_id": database_id }),
+        };
+        (workspace_id, vec![activity], false)
+      },
+    };
+    if activities.is_empty() {
+      continue;
+    }
+    trace!(
+      "Record {} activities of workspace {}",
+      activities.len(),
+      workspace_id
+    );
+    if let Err(err) = insert_workspace_activities(&pg_pool, &workspace_id, &activities).await {
+      error!(
+        "Failed to record the activities of workspace {}: {:?}",
+        workspace_id, err
+      );
+    }
+    if is_collab_change {
+      for (event, data) in activities.iter().filter_map(page_webhook_event) {
+        queue_webhook_event(&redis_client, &workspace_id, event, data).await;
+      }
+    }
+  }
+}
+
+async fn decode_folder(workspace_id: Uuid, encoded_folder: Bytes) -> Option<FolderSnapshot> {
+  let snapshot = tokio::task::spawn_blocking(move || {
+    FolderSnapshot::from_encoded_folder(&workspace_id.to_string(), &encoded_folder)
+  })
+  .await
+  .ok()
+  .flatten();
+  if snapshot.is_none() {
+    error!("Failed to read the folder of workspace {}", workspace_id);
+  }
+  snapshot
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn page(name: &str, parent_view_id: &str, is_trashed: bool) -> PageSnapshot {
+    PageSnapshot {
+      name: name.to_string(),
+      parent_view_id: parent_view_id.to_string(),
+      is_trashed,
+      created_by: Some(1),
+      last_edited_by: Some(2),
+    }
+  }
+
+  fn snapshot(pages: Vec<(&str, PageSnapshot)>) -> FolderSnapshot {
+    FolderSnapshot {
+      pages: pages
+        .into_iter()
+        .map(|(view_id, page)| (view_id.to_string(), page))
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn folder_activities_test() {
+    let before = snapshot(vec![
+      ("a", page("A", "space", false)),
+      ("b", page("B", "space", false)),
+      ("c", page("C", "space", true)),
+      ("d", page("D", "space", false)),
+    ]);
+    let after = snapshot(vec![
+      ("a", page("A2", "b", false)),
+      ("b", page("B", "space", true)),
+      ("c", page("C", "space", false)),
+      ("e", page("E", "a", false)),
+    ]);
+    let mut activities = folder_activities(&before, &after, None)
+      .into_iter()
+      .map(|activity| (activity.object_id, activity.kind, activity.actor_uid))
+      .collect::<Vec<_>>();
+    activities.sort_by_key(|(object_id, kind, _)| (object_id.clone(), *kind as i16));
+    assert_eq!(
+      activities,
+      vec![
+        ("a".to_string(), WorkspaceActivityKind::PageRenamed, Some(2)),
+        ("a".to_string(), WorkspaceActivityKind::PageMoved, Some(2)),
+        ("b".to_string(), WorkspaceActivityKind::PageTrashed, Some(2)),
+        (
+          "c".to_string(),
+          WorkspaceActivityKind::PageRestored,
+          Some(2)
+        ),
+        ("e".to_string(), WorkspaceActivityKind::PageCreated, Some(1)),
+      ]
+    );
+  }
+
+  #[test]
+  fn page_webhook_event_test() {
+    let before = snapshot(vec![("a", page("A", "space", false))]);
+    let after = snapshot(vec![
+      ("a", page("A2", "b", true)),
+      ("e", page("E", "a", false)),
+    ]);
+    let mut events = folder_activities(&before, &after, None)
+      .iter()
+      .filter_map(page_webhook_event)
+      .map(|(event, data)| (event.as_str(), data))
+      .collect::<Vec<_>>();
+    events.sort_by_key(|(event, _)| *event);
+    assert_eq!(
+      events,
+      vec![
+        (
+          "page.created",
+          serde_json::json!({ "view_id": "e", "parent_view_id": "a", "name": "E", "uid": 1 })
+        ),
+        (
+          "page.renamed",
+          serde_json::json!({ "view_id": "a", "name": "A2", "uid": 2 })
+        ),
+        (
+          "page.trashed",
+          serde_json::json!({ "view_id": "a", "uid": 2 })
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn folder_activities_actor_test() {
+    let before = FolderSnapshot::default();
+    let mut created = page("A", "space", false);
+    created.created_by = None;
+    let after = snapshot(vec![("a", created)]);
+    assert_eq!(
+      folder_activities(&before, &after, None)[0].actor_uid,
+      Some(2)
+    );
+    assert_eq!(
+      folder_activities(&before, &after, Some(3))[0].actor_uid,
+      Some(3)
+    );
+    assert!(folder_activities(&after, &after, None).is_empty());
+  }
+}
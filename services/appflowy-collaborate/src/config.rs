@@ -129,6 +129,14 @@ pub struct CollabSetting {
   pub edit_state_max_count: u32,
   pub edit_state_max_secs: i64,
   pub s3_collab_threshold: u64,
+  /// Minimum delay between two updates of the blob of a live published document.
+  pub live_publish_debounce_secs: u64,
+  /// Minimum delay between two checks of the members mentioned in a document.
+  pub mention_notify_debounce_secs: u64,
+  /// Minimum delay between two updates of the reminders of a document or a database row.
+  pub reminder_sync_debounce_secs: u64,
+  /// Minimum delay between two refreshes of the page access levels of a workspace.
+  pub view_permission_refresh_debounce_secs: u64,
 }
 
 pub fn get_env_var(key: &str, default: &str) -> String {
@@ -193,6 +201,20 @@ pub fn get_configuration() -> Result<Config, anyhow::Error> {
       edit_state_max_count: get_env_var("APPFLOWY_COLLAB_EDIT_STATE_MAX_COUNT", "100").parse()?,
       edit_state_max_secs: get_env_var("APPFLOWY_COLLAB_EDIT_STATE_MAX_SECS", "60").parse()?,
       s3_collab_threshold: get_env_var("APPFLOWY_COLLAB_S3_THRESHOLD", "8000").parse()?,
+      live_publish_debounce_secs: get_env_var("APPFLOWY_COLLAB_LIVE_PUBLISH_DEBOUNCE_SECS", "10")
+        .parse()?,
+      mention_notify_debounce_secs: get_env_var(
+        "APPFLOWY_COLLAB_MENTION_NOTIFY_DEBOUNCE_SECS",
+        "10",
+      )
+      .parse()?,
+      reminder_sync_debounce_secs: get_env_var("APPFLOWY_COLLAB_REMINDER_SYNC_DEBOUNCE_SECS", "10")
+        .parse()?,
+      view_permission_refresh_debounce_secs: get_env_var(
+        "APPFLOWY_COLLAB_VIEW_PERMISSION_REFRESH_DEBOUNCE_SECS",
+        "10",
+      )
+      .parse()?,
     },
     redis_uri: get_env_var("APPFLOWY_REDIS_URI", "redis://localhost:6379").into(),
     ai: AISettings {
@@ -29,6 +29,10 @@ use crate::error::RealtimeError;
 use crate::group::group_init::EditState;
 use crate::group::protocol::ServerSyncProtocol;
 use crate::metrics::CollabRealtimeMetrics;
+
+/// Length of an encoded [yrs::Update] without any change.
+const EMPTY_UPDATE_LEN: usize = 2;
+
 /// A broadcast can be used to propagate updates produced by yrs [yrs::Doc] and [Awareness]
 /// to subscribes. One broadcast can be used to propagate updates for a single document with
 /// object_id.
@@ -421,12 +425,21 @@ async fn handle_message(
     match msg {
       Ok(msg) => {
         is_sync_step2 = matches!(msg, Message::Sync(SyncMessage::SyncStep2(_)));
+        // The sync step 2 of a client without changes carries an empty update
+        let is_update = match &msg {
+          Message::Sync(SyncMessage::Update(_)) => true,
+          Message::Sync(SyncMessage::SyncStep2(update)) => update.len() > EMPTY_UPDATE_LEN,
+          _ => false,
+        };
         match ServerSyncProtocol::new(metrics_calculate.clone())
           .handle_message(message_origin, collab, msg)
           .await
         {
           Ok(payload) => {
             metrics_calculate.apply_update_count.inc();
+            if let (true, CollabOrigin::Client(client)) = (is_update, message_origin) {
+              edit_state.record_editor(client.uid);
+            }
             // One ClientCollabMessage can have multiple Yrs [Message] in it, but we only need to
             // send one ack back to the client.
             if ack_response.is_none() {
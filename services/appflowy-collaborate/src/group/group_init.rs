@@ -10,6 +10,7 @@ use collab::preclude::Collab;
 use collab_entity::CollabType;
 use dashmap::DashMap;
 use futures_util::{SinkExt, StreamExt};
+use parking_lot::Mutex;
 use tokio::sync::mpsc;
 use tracing::{event, info, trace};
 
@@ -21,6 +22,7 @@ use collab_stream::error::StreamError;
 
 use database::collab::CollabStorage;
 
+use crate::collab::persistence_hook::PersistenceHook;
 use crate::error::RealtimeError;
 use crate::group::broadcast::{CollabBroadcast, Subscription};
 use crate::group::persistence::GroupPersistence;
@@ -64,6 +66,7 @@ impl CollabGroup {
     edit_state_max_count: u32,
     edit_state_max_secs: i64,
     indexer: Option<Arc<dyn Indexer>>,
+    persistence_hooks: Vec<Arc<dyn PersistenceHook>>,
   ) -> Result<Self, StreamError>
   where
     S: CollabStorage,
@@ -90,6 +93,7 @@ impl CollabGroup {
         collab_type.clone(),
         persistence_interval,
         indexer,
+        persistence_hooks,
       )
       .run(rx),
     );
@@ -264,6 +268,8 @@ pub(crate) struct EditState {
   /// Indicate the collab is ready to save to disk.
   /// If is_ready_to_save is true, which means the collab contains the requirement data and ready to save to disk.
   is_ready_to_save: AtomicBool,
+  /// The users whose updates were applied since the last save, the latest editor last.
+  editors: Mutex<Vec<i64>>,
 }
 
 impl Display for EditState {
@@ -291,6 +297,7 @@ impl EditState {
       max_secs,
       is_new: AtomicBool::new(is_new),
       is_ready_to_save: AtomicBool::new(false),
+      editors: Mutex::new(vec![]),
     }
   }
 
@@ -309,6 +316,17 @@ impl EditState {
       .unwrap()
   }
 
+  pub(crate) fn record_editor(&self, uid: i64) {
+    let mut editors = self.editors.lock();
+    editors.retain(|editor| *editor != uid);
+    editors.push(uid);
+  }
+
+  /// Returns the users that edited the collab since the last call.
+  pub(crate) fn take_editors(&self) -> Vec<i64> {
+    std::mem::take(&mut *self.editors.lock())
+  }
+
   pub(crate) fn tick(&self) {
     self
       .prev_edit_count
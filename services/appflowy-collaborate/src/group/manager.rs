@@ -18,6 +18,7 @@ use database::collab::{CollabStorage, GetCollabOrigin};
 use database_entity::dto::QueryCollabParams;
 
 use crate::client::client_msg_router::ClientMessageRouter;
+use crate::collab::persistence_hook::PersistenceHook;
 use crate::error::{CreateGroupFailedReason, RealtimeError};
 use crate::group::group_init::CollabGroup;
 use crate::group::state::GroupManagementState;
@@ -33,6 +34,7 @@ pub struct GroupManager<S> {
   edit_state_max_count: u32,
   edit_state_max_secs: i64,
   indexer_provider: Arc<IndexerProvider>,
+  persistence_hooks: Vec<Arc<dyn PersistenceHook>>,
 }
 
 impl<S> GroupManager<S>
@@ -48,6 +50,7 @@ where
     edit_state_max_count: u32,
     edit_state_max_secs: i64,
     indexer_provider: Arc<IndexerProvider>,
+    persistence_hooks: Vec<Arc<dyn PersistenceHook>>,
   ) -> Result<Self, RealtimeError> {
     Ok(Self {
       state: GroupManagementState::new(metrics_calculate.clone()),
@@ -58,6 +61,7 @@ where
       edit_state_max_count,
       edit_state_max_secs,
       indexer_provider,
+      persistence_hooks,
     })
   }
 
@@ -169,6 +173,12 @@ where
       tracing::trace!("workspace {} indexing is disabled", workspace_id);
       indexer = None;
     }
+    let persistence_hooks = self
+      .persistence_hooks
+      .iter()
+      .filter(|hook| hook.is_enabled(&collab_type))
+      .cloned()
+      .collect();
     let group = Arc::new(
       CollabGroup::new(
         user.uid,
@@ -183,6 +193,7 @@ where
         self.edit_state_max_count,
         self.edit_state_max_secs,
         indexer,
+        persistence_hooks,
       )
       .await?,
     );
@@ -2,6 +2,7 @@ use std::sync::{Arc, Weak};
 use std::time::Duration;
 
 use anyhow::anyhow;
+use bytes::Bytes;
 use collab::lock::RwLock;
 use collab::preclude::Collab;
 use collab_entity::{validate_data_for_folder, CollabType};
@@ -13,6 +14,7 @@ use app_error::AppError;
 use database::collab::CollabStorage;
 use database_entity::dto::CollabParams;
 
+use crate::collab::persistence_hook::{PersistedCollab, PersistenceHook};
 use crate::group::group_init::EditState;
 use crate::indexer::Indexer;
 
@@ -26,6 +28,8 @@ pub(crate) struct GroupPersistence<S> {
   collab_type: CollabType,
   persistence_interval: Duration,
   indexer: Option<Arc<dyn Indexer>>,
+  /// The hooks enabled for the type of the collab.
+  hooks: Vec<Arc<dyn PersistenceHook>>,
 }
 
 impl<S> GroupPersistence<S>
@@ -43,6 +47,7 @@ where
     collab_type: CollabType,
     persistence_interval: Duration,
     ai_client: Option<Arc<dyn Indexer>>,
+    hooks: Vec<Arc<dyn PersistenceHook>>,
   ) -> Self {
     Self {
       workspace_id,
@@ -54,10 +59,12 @@ where
       collab_type,
       persistence_interval,
       indexer: ai_client,
+      hooks,
     }
   }
 
   pub async fn run(self, mut destroy_group_rx: mpsc::Receiver<Arc<RwLock<Collab>>>) {
+    self.notify_opened().await;
     let mut interval = interval(self.persistence_interval);
     loop {
       // delay 30 seconds before the first save. We don't want to save immediately after the collab is created
@@ -71,12 +78,68 @@ where
         },
         _collab = destroy_group_rx.recv() => {
           self.force_save().await;
+          let persisted = self.persisted_collab(false, vec![]);
+          for hook in &self.hooks {
+            hook.on_closed(&persisted);
+          }
           break;
         }
       }
     }
   }
 
+  fn persisted_collab(&self, is_new: bool, editors: Vec<i64>) -> PersistedCollab {
+    PersistedCollab {
+      workspace_id: self.workspace_id.clone(),
+      object_id: self.object_id.clone(),
+      collab_type: self.collab_type.clone(),
+      uid: self.uid,
+      is_new,
+      editors,
+    }
+  }
+
+  async fn notify_opened(&self) {
+    if self.hooks.is_empty() {
+      return;
+    }
+    let collab = match self.collab.upgrade() {
+      Some(collab) => collab,
+      None => return,
+    };
+    let persisted = self.persisted_collab(self.edit_state.is_new(), vec![]);
+    let lock = collab.read().await;
+    for hook in &self.hooks {
+      hook.on_opened(&persisted, &lock);
+    }
+  }
+
+  /// Hands the saved collab over to the hooks. The collab is read again, so the hooks only get the
+  /// collabs that were saved successfully.
+  async fn notify_saved(
+    &self,
+    collab: Arc<RwLock<Collab>>,
+    is_new: bool,
+    editors: Vec<i64>,
+    encoded_collab: Bytes,
+  ) {
+    if self.hooks.is_empty() {
+      return;
+    }
+    let persisted = self.persisted_collab(is_new, editors);
+    let hooks = self.hooks.clone();
+    let result = tokio::task::spawn_blocking(move || {
+      let collab = collab.blocking_read();
+      for hook in &hooks {
+        hook.on_saved(&persisted, &collab, &encoded_collab);
+      }
+    })
+    .await;
+    if let Err(err) = result {
+      warn!("fail to run persistence hooks: {}:{}", self.object_id, err);
+    }
+  }
+
   async fn force_save(&self) {
     if self.edit_state.is_new() && self.save(true).await.is_ok() {
       self.edit_state.set_is_new(false);
@@ -123,6 +186,7 @@ where
       None => return Err(AppError::Internal(anyhow!("collab has been dropped"))),
     };
 
+    let is_new = self.edit_state.is_new();
     let params = {
       let cloned_collab = collab.clone();
       let (workspace_id, mut params, object_id) = tokio::task::spawn_blocking(move || {
@@ -159,6 +223,7 @@ where
       }
       params
     };
+    let encoded_collab = params.encoded_collab_v1.clone();
 
     self
       .storage
@@ -166,6 +231,11 @@ where
       .await?;
     // Update the edit state on successful save
     self.edit_state.tick();
+    let editors = self.edit_state.take_editors();
+
+    self
+      .notify_saved(collab, is_new, editors, encoded_collab)
+      .await;
     Ok(())
   }
 }
@@ -18,6 +18,7 @@ pub mod snapshot;
 mod state;
 pub mod telemetry;
 mod util;
+pub mod webhook;
 
 pub use metrics::*;
 pub use permission::*;
@@ -1,16 +1,42 @@
 use anyhow::Error;
+use collab_rt_entity::user::{
+  AFAccessRequestChange, AFChatMessageChange, AFDocumentCommentChange, AFNotificationChange,
+};
+use database::access_request_listener::AccessRequestListener;
+use database::chat::chat_listener::ChatMessageListener;
+use database::document_comment_listener::DocumentCommentListener;
 use database::listener::PostgresDBListener;
-use database::pg_row::AFUserNotification;
+use database::pg_row::{AFNotificationRow, AFUserNotification};
 use sqlx::PgPool;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc};
 
 pub struct PgListeners {
   user_listener: UserListener,
+  chat_message_listener: ChatMessageListener,
+  access_request_listener: AccessRequestListener,
+  document_comment_listener: DocumentCommentListener,
+  notification_listener: NotificationListener,
 }
 
 impl PgListeners {
   pub async fn new(pg_pool: &PgPool) -> Result<Self, Error> {
     let user_listener = UserListener::new(pg_pool, "af_user_channel").await?;
-    Ok(Self { user_listener })
+    let chat_message_listener =
+      ChatMessageListener::new(pg_pool, "af_chat_message_channel").await?;
+    let access_request_listener =
+      AccessRequestListener::new(pg_pool, "af_access_request_channel").await?;
+    let document_comment_listener =
+      DocumentCommentListener::new(pg_pool, "af_document_comment_channel").await?;
+    let notification_listener =
+      NotificationListener::new(pg_pool, "af_notification_channel").await?;
+    Ok(Self {
+      user_listener,
+      chat_message_listener,
+      access_request_listener,
+      document_comment_listener,
+      notification_listener,
+    })
   }
 
   pub fn subscribe_user_change(&self, uid: i64) -> tokio::sync::mpsc::Receiver<AFUserNotification> {
@@ -27,8 +53,105 @@ impl PgListeners {
     });
     rx
   }
+
+  /// Subscribes to the new and edited messages of the chats the user participates in.
+  pub fn subscribe_chat_message_change(&self, uid: i64) -> mpsc::Receiver<AFChatMessageChange> {
+    forward_notifications(
+      self.chat_message_listener.notify.subscribe(),
+      move |event| {
+        event
+          .participants
+          .contains(&uid)
+          .then(|| event.change.clone())
+      },
+    )
+  }
+
+  /// Subscribes to the new access requests of the workspaces the user owns, and to the status
+  /// changes of the requests of the user.
+  pub fn subscribe_access_request_change(&self, uid: i64) -> mpsc::Receiver<AFAccessRequestChange> {
+    forward_notifications(
+      self.access_request_listener.notify.subscribe(),
+      move |event| {
+        event
+          .recipients
+          .contains(&uid)
+          .then(|| event.change.clone())
+      },
+    )
+  }
+
+  /// Subscribes to the comments on the documents of the workspaces the user is a member of.
+  pub fn subscribe_document_comment_change(
+    &self,
+    uid: i64,
+  ) -> mpsc::Receiver<AFDocumentCommentChange> {
+    forward_notifications(
+      self.document_comment_listener.notify.subscribe(),
+      move |event| {
+        event
+          .recipients
+          .contains(&uid)
+          .then(|| event.change.clone())
+      },
+    )
+  }
+
+  /// Subscribes to the new notifications of the user.
+  pub fn subscribe_notification(&self, uid: i64) -> mpsc::Receiver<AFNotificationChange> {
+    forward_notifications(
+      self.notification_listener.notify.subscribe(),
+      move |notification| {
+        (notification.uid == uid).then(|| AFNotificationChange {
+          notification_id: notification.notification_id.to_string(),
+          workspace_id: notification.workspace_id.to_string(),
+          kind: notification.kind,
+          view_id: notification.view_id.map(|view_id| view_id.to_string()),
+          object_id: notification.object_id,
+          actor_uid: notification.actor_uid,
+          payload: notification.payload.to_string(),
+          created_at: notification.created_at.timestamp_millis(),
+        })
+      },
+    )
+  }
+}
+
+/// Forwards the notifications picked by `filter_map` to the subscriber until it disconnects. The
+/// notifications missed while the subscriber lagged behind are skipped.
+fn forward_notifications<T, U, F>(
+  mut notify: broadcast::Receiver<T>,
+  filter_map: F,
+) -> mpsc::Receiver<U>
+where
+  T: Clone + Send + 'static,
+  U: Send + 'static,
+  F: Fn(T) -> Option<U> + Send + 'static,
+{
+  let (tx, rx) = mpsc::channel(100);
+  tokio::spawn(async move {
+    loop {
+      // Subscribers come and go, stop as soon as the subscriber disconnects instead of waiting for
+      // the next notification.
+      let notification = tokio::select! {
+        _ = tx.closed() => break,
+        notification = notify.recv() => match notification {
+          Ok(notification) => notification,
+          Err(RecvError::Lagged(_)) => continue,
+          Err(RecvError::Closed) => break,
+        },
+      };
+      if let Some(item) = filter_map(notification) {
+        if tx.send(item).await.is_err() {
+          break;
+        }
+      }
+    }
+  });
+  rx
 }
 
 // pub type CollabMemberListener = PostgresDBListener<CollabMemberNotification>;
 // pub type WorkspaceMemberListener = PostgresDBListener<WorkspaceMemberNotification>;
 pub type UserListener = PostgresDBListener<AFUserNotification>;
+pub type NotificationListener = PostgresDBListener<AFNotificationRow>;
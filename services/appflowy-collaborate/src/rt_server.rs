@@ -17,6 +17,7 @@ use collab_rt_entity::MessageByObjectId;
 use database::collab::CollabStorage;
 
 use crate::client::client_msg_router::ClientMessageRouter;
+use crate::collab::persistence_hook::PersistenceHook;
 use crate::command::{spawn_collaboration_command, CLCommandReceiver};
 use crate::config::get_env_var;
 use crate::connect_state::ConnectState;
@@ -54,6 +55,7 @@ where
     edit_state_max_count: u32,
     edit_state_max_secs: i64,
     indexer_provider: Arc<IndexerProvider>,
+    persistence_hooks: Vec<Arc<dyn PersistenceHook>>,
   ) -> Result<Self, RealtimeError> {
     let enable_custom_runtime = get_env_var("APPFLOWY_COLLABORATE_MULTI_THREAD", "false")
       .parse::<bool>()
@@ -75,6 +77,7 @@ where
         edit_state_max_count,
         edit_state_max_secs,
         indexer_provider.clone(),
+        persistence_hooks,
       )
       .await?,
     );
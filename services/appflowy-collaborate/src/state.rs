@@ -10,6 +10,7 @@ use access_control::metrics::AccessControlMetrics;
 use app_error::AppError;
 use database::user::{select_all_uid_uuid, select_uid_from_uuid};
 
+use crate::collab::persistence_hook::PersistenceHook;
 use crate::collab::storage::CollabAccessControlStorage;
 use crate::config::Config;
 use crate::indexer::IndexerProvider;
@@ -22,6 +23,7 @@ pub type RedisConnectionManager = redis::aio::ConnectionManager;
 #[derive(Clone)]
 pub struct AppState {
   pub config: Arc<Config>,
+  pub pg_pool: PgPool,
   pub pg_listeners: Arc<PgListeners>,
   pub user_cache: UserCache,
   pub redis_connection_manager: RedisConnectionManager,
@@ -29,6 +31,7 @@ pub struct AppState {
   pub collab_access_control_storage: Arc<CollabAccessControlStorage>,
   pub metrics: AppMetrics,
   pub indexer_provider: Arc<IndexerProvider>,
+  pub persistence_hooks: Vec<Arc<dyn PersistenceHook>>,
 }
 
 #[derive(Clone)]
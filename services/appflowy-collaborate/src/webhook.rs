@@ -0,0 +1,57 @@
+use chrono::Utc;
+use redis::aio::ConnectionManager;
+use redis::streams::StreamMaxlen;
+use redis::AsyncCommands;
+use shared_entity::dto::webhook_dto::{WebhookEvent, WebhookPayload};
+use tracing::error;
+use uuid::Uuid;
+
+/// Name of the Redis stream consumed by the webhook worker of appflowy-worker.
+pub const WEBHOOK_EVENT_STREAM: &str = "webhook_event_stream";
+
+/// Approximate number of events kept in the stream. The oldest events are dropped when the worker
+/// falls behind or is down, rather than letting the stream grow without bound.
+const MAX_WEBHOOK_EVENT_STREAM_LEN: usize = 100_000;
+
+/// Queues an event of the workspace for delivery to its webhooks. The worker looks up the webhooks
+/// subscribed to the event, so the event is queued even if the workspace has none.
+///
+/// Webhooks are best effort: a failure is logged and never fails the request that caused the
+/// event.
+pub async fn queue_webhook_event(
+  redis_client: &ConnectionManager,
+  workspace_id: &Uuid,
+  event: WebhookEvent,
+  data: serde_json::Value,
+) {
+  let payload = WebhookPayload {
+    event: event.as_str().to_string(),
+    workspace_id: *workspace_id,
+    timestamp: Utc::now(),
+    data,
+  };
+  let task = match serde_json::to_string(&payload) {
+    Ok(task) => task,
+    Err(err) => {
+      error!("Failed to serialize webhook event: {}", err);
+      return;
+    },
+  };
+  let result: Result<(), redis::RedisError> = redis_client
+    .clone()
+    .xadd_maxlen(
+      WEBHOOK_EVENT_STREAM,
+      StreamMaxlen::Approx(MAX_WEBHOOK_EVENT_STREAM_LEN),
+      "*",
+      &[("task", task)],
+    )
+    .await;
+  if let Err(err) = result {
+    error!(
+      "Failed to queue webhook event {} of workspace {}: {}",
+      event.as_str(),
+      workspace_id,
+      err
+    );
+  }
+}
@@ -0,0 +1,25 @@
+use crate::error::WorkerError;
+use database::access_request::expire_access_requests;
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info};
+
+/// Expires the access requests still pending after their expiration time. The requesters are
+/// notified by the `af_access_request_change_trigger`.
+pub async fn run_access_request_worker(
+  pg_pool: PgPool,
+  tick_interval_secs: u64,
+) -> Result<(), WorkerError> {
+  info!("Starting access request worker");
+  let mut interval = interval(Duration::from_secs(tick_interval_secs));
+
+  loop {
+    interval.tick().await;
+    match expire_access_requests(&pg_pool).await {
+      Ok(0) => {},
+      Ok(count) => info!("Expired {} access requests", count),
+      Err(err) => error!("Failed to expire access requests: {:?}", err),
+    }
+  }
+}
@@ -0,0 +1,260 @@
+use crate::error::WorkerError;
+use crate::import_worker::worker::get_encode_collab_from_bytes;
+use crate::mailer::{AFWorkerMailer, ACTIVITY_DIGEST_TEMPLATE};
+use crate::s3_client::S3Client;
+use anyhow::anyhow;
+use collab::core::origin::CollabOrigin;
+use collab_entity::CollabType;
+use collab_folder::Folder;
+use database::activity_digest::{
+  claim_due_activity_digests, select_activity_digest_comments, select_activity_digest_edited_views,
+  select_activity_digest_new_members, AFDueActivityDigestRow,
+};
+use serde::Serialize;
+use shared_entity::dto::notification_dto::ActivityDigestFrequency;
+use sqlx::types::chrono::{Duration as ChronoDuration, Utc};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info, trace};
+
+/// Digests are claimed in batches of this size until none are due.
+const DIGEST_BATCH_SIZE: i64 = 100;
+const MAX_EDITED_PAGES_PER_DIGEST: i64 = 20;
+const MAX_COMMENTS_PER_DIGEST: i64 = 20;
+const MAX_NEW_MEMBERS_PER_DIGEST: i64 = 20;
+const MAX_PREVIEW_LENGTH: usize = 200;
+
+#[derive(Serialize)]
+struct DigestItem {
+  title: String,
+  detail: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ActivityDigestMailerParam {
+  user_name: String,
+  workspace_name: String,
+  period: String,
+  edited_pages: Vec<DigestItem>,
+  comments: Vec<DigestItem>,
+  new_members: Vec<String>,
+}
+
+/// Name of a page and the last user that edited it.
+struct PageInfo {
+  name: String,
+  last_edited_by: Option<i64>,
+}
+
+/// Emails the members of the workspaces a daily or weekly digest of the activity of the workspace:
+/// the pages they own or follow that others edited, the new comments on these pages and the new
+/// members. A page is followed by the members mentioned in it or that commented on it. The
+/// frequency is set per user and can be overridden per workspace, see `ActivityDigestFrequency`.
+pub async fn run_activity_digest_worker(
+  pg_pool: PgPool,
+  s3_client: Arc<dyn S3Client>,
+  mailer: AFWorkerMailer,
+  tick_interval_secs: u64,
+) -> Result<(), WorkerError> {
+  info!("Starting activity digest worker");
+  let mut interval = interval(Duration::from_secs(tick_interval_secs));
+
+  loop {
+    interval.tick().await;
+    loop {
+      let digests = match claim_due_activity_digests(&pg_pool, DIGEST_BATCH_SIZE).await {
+        Ok(digests) => digests,
+        Err(err) => {
+          error!("Failed to claim the due activity digests: {:?}", err);
+          break;
+        },
+      };
+      let is_last_batch = (digests.len() as i64) < DIGEST_BATCH_SIZE;
+      for digest in digests {
+        if let Err(err) = send_digest(&pg_pool, &s3_client, &mailer, &digest).await {
+          error!(
+            "Failed to send the activity digest of workspace {} to {}: {:?}",
+            digest.workspace_id, digest.uid, err
+          );
+        }
+      }
+      if is_last_batch {
+        break;
+      }
+    }
+  }
+}
+
+async fn send_digest(
+  pg_pool: &PgPool,
+  s3_client: &Arc<dyn S3Client>,
+  mailer: &AFWorkerMailer,
+  digest: &AFDueActivityDigestRow,
+) -> Result<(), WorkerError> {
+  let now = Utc::now();
+  let (period, period_name) = match ActivityDigestFrequency::from_i16(digest.frequency) {
+    Some(ActivityDigestFrequency::Daily) => (ChronoDuration::days(1), "today"),
+    _ => (ChronoDuration::weeks(1), "this week"),
+  };
+  // The first digest covers the last period
+  let since = digest.last_sent_at.unwrap_or(now - period);
+
+  let edited_views = select_activity_digest_edited_views(
+    pg_pool,
+    &digest.workspace_id,
+    digest.uid,
+    since,
+    MAX_EDITED_PAGES_PER_DIGEST,
+  )
+  .await
+  .map_err(|err| WorkerError::Internal(err.into()))?;
+  let comments = select_activity_digest_comments(
+    pg_pool,
+    &digest.workspace_id,
+    digest.uid,
+    since,
+    MAX_COMMENTS_PER_DIGEST,
+  )
+  .await
+  .map_err(|err| WorkerError::Internal(err.into()))?;
+  let new_members = select_activity_digest_new_members(
+    pg_pool,
+    &digest.workspace_id,
+    digest.uid,
+    since,
+    MAX_NEW_MEMBERS_PER_DIGEST,
+  )
+  .await
+  .map_err(|err| WorkerError::Internal(err.into()))?;
+
+  let mut view_ids: HashSet<String> = edited_views
+    .iter()
+    .map(|view| view.view_id.clone())
+    .collect();
+  view_ids.extend(comments.iter().map(|comment| comment.view_id.to_string()));
+  let pages = if view_ids.is_empty() {
+    HashMap::new()
+  } else {
+    get_page_infos(pg_pool, s3_client, digest, &view_ids).await?
+  };
+
+  // The pages the user edited last, trashed or unknown to the folder are left out
+  let edited_pages: Vec<DigestItem> = edited_views
+    .iter()
+    .filter_map(|view| {
+      let page = pages.get(&view.view_id)?;
+      if page.last_edited_by == Some(digest.uid) {
+        return None;
+      }
+      Some(DigestItem {
+        title: page_name(page),
+        detail: Some(format!(
+          "Edited on {}",
+          view.updated_at.format("%Y-%m-%d %H:%M UTC")
+        )),
+      })
+    })
+    .collect();
+  let comments: Vec<DigestItem> = comments
+    .iter()
+    .filter_map(|comment| {
+      let page = pages.get(&comment.view_id.to_string())?;
+      Some(DigestItem {
+        title: format!("{} commented on {}", comment.author_name, page_name(page)),
+        detail: Some(preview(&comment.content)),
+      })
+    })
+    .collect();
+
+  if edited_pages.is_empty() && comments.is_empty() && new_members.is_empty() {
+    trace!(
+      "No activity to send to {} for workspace {}",
+      digest.uid,
+      digest.workspace_id
+    );
+  } else {
+    let param = ActivityDigestMailerParam {
+      user_name: digest.name.clone(),
+      workspace_name: digest.workspace_name.clone(),
+      period: period_name.to_string(),
+      edited_pages,
+      comments,
+      new_members,
+    };
+    mailer
+      .send_email_template(
+        Some(digest.name.clone()),
+        &digest.email,
+        ACTIVITY_DIGEST_TEMPLATE,
+        param,
+        &format!("What happened in {} {}", digest.workspace_name, period_name),
+      )
+      .await?;
+  }
+  Ok(())
+}
+
+/// Reads the names of the pages from the folder of the workspace.
+async fn get_page_infos(
+  pg_pool: &PgPool,
+  s3_client: &Arc<dyn S3Client>,
+  digest: &AFDueActivityDigestRow,
+  view_ids: &HashSet<String>,
+) -> Result<HashMap<String, PageInfo>, WorkerError> {
+  let workspace_id = digest.workspace_id.to_string();
+  let encoded_collab = get_encode_collab_from_bytes(
+    &workspace_id,
+    &workspace_id,
+    &CollabType::Folder,
+    pg_pool,
+    s3_client,
+  )
+  .await?;
+  let folder = Folder::from_collab_doc_state(
+    digest.uid,
+    CollabOrigin::Server,
+    encoded_collab.into(),
+    &workspace_id,
+    vec![],
+  )
+  .map_err(|err| WorkerError::Internal(anyhow!("Failed to open the folder: {}", err)))?;
+  let trash: HashSet<String> = folder
+    .get_all_trash_sections()
+    .into_iter()
+    .map(|section| section.id)
+    .collect();
+  let pages = view_ids
+    .iter()
+    .filter(|view_id| !trash.contains(*view_id))
+    .filter_map(|view_id| {
+      let view = folder.get_view(view_id)?;
+      Some((
+        view_id.clone(),
+        PageInfo {
+          name: view.name.clone(),
+          last_edited_by: view.last_edited_by,
+        },
+      ))
+    })
+    .collect();
+  Ok(pages)
+}
+
+fn page_name(page: &PageInfo) -> String {
+  if page.name.is_empty() {
+    "Untitled".to_string()
+  } else {
+    page.name.clone()
+  }
+}
+
+fn preview(text: &str) -> String {
+  let text = text.trim();
+  match text.char_indices().nth(MAX_PREVIEW_LENGTH) {
+    Some((end, _)) => format!("{}…", &text[..end]),
+    None => text.to_string(),
+  }
+}
@@ -4,7 +4,14 @@ use redis::aio::ConnectionManager;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
 
+use crate::access_request_worker::worker::run_access_request_worker;
+use crate::activity_digest_worker::worker::run_activity_digest_worker;
+use crate::attachment_indexer::worker::run_attachment_indexer;
 use crate::import_worker::worker::run_import_worker;
+use crate::notification_digest_worker::worker::run_notification_digest_worker;
+use crate::reminder_worker::worker::run_reminder_worker;
+use crate::webhook_worker::worker::run_webhook_worker;
+use appflowy_ai_client::client::AppFlowyAIClient;
 use aws_sdk_s3::config::{Credentials, Region, SharedCredentialsProvider};
 
 use crate::import_worker::email_notifier::EmailNotifier;
@@ -124,6 +131,69 @@ pub async fn create_app(listener: TcpListener, config: Config) -> Result<(), Err
     maximum_import_file_size,
   ));
 
+  // Maximum file size of the attachments which text is extracted from
+  let maximum_attachment_index_size =
+    get_env_var("APPFLOWY_WORKER_MAX_ATTACHMENT_INDEX_SIZE", "50000000")
+      .parse::<u64>()
+      .unwrap_or(50_000_000);
+
+  let attachment_indexer_fut = run_attachment_indexer(
+    state.pg_pool.clone(),
+    state.redis_client.clone(),
+    Arc::new(state.s3_client.clone()),
+    AppFlowyAIClient::new(&config.ai.url()),
+    "attachment_index_stream",
+    tick_interval,
+    maximum_attachment_index_size,
+  );
+
+  let webhook_tick_interval = get_env_var("APPFLOWY_WORKER_WEBHOOK_TICK_INTERVAL", "2")
+    .parse::<u64>()
+    .unwrap_or(2);
+  let webhook_worker_fut = run_webhook_worker(
+    state.pg_pool.clone(),
+    state.redis_client.clone(),
+    "webhook_event_stream",
+    webhook_tick_interval,
+  );
+
+  let access_request_tick_interval =
+    get_env_var("APPFLOWY_WORKER_ACCESS_REQUEST_TICK_INTERVAL", "300")
+      .parse::<u64>()
+      .unwrap_or(300);
+  let access_request_worker_fut =
+    run_access_request_worker(state.pg_pool.clone(), access_request_tick_interval);
+
+  let notification_digest_tick_interval =
+    get_env_var("APPFLOWY_WORKER_NOTIFICATION_DIGEST_TICK_INTERVAL", "3600")
+      .parse::<u64>()
+      .unwrap_or(3600);
+  let notification_digest_worker_fut = run_notification_digest_worker(
+    state.pg_pool.clone(),
+    state.mailer.clone(),
+    notification_digest_tick_interval,
+  );
+
+  let reminder_tick_interval = get_env_var("APPFLOWY_WORKER_REMINDER_TICK_INTERVAL", "60")
+    .parse::<u64>()
+    .unwrap_or(60);
+  let reminder_worker_fut = run_reminder_worker(
+    state.pg_pool.clone(),
+    state.mailer.clone(),
+    reminder_tick_interval,
+  );
+
+  let activity_digest_tick_interval =
+    get_env_var("APPFLOWY_WORKER_ACTIVITY_DIGEST_TICK_INTERVAL", "3600")
+      .parse::<u64>()
+      .unwrap_or(3600);
+  let activity_digest_worker_fut = run_activity_digest_worker(
+    state.pg_pool.clone(),
+    Arc::new(state.s3_client.clone()),
+    state.mailer.clone(),
+    activity_digest_tick_interval,
+  );
+
   let app = Router::new()
     .route("/metrics", get(metrics_handler))
     .with_state(Arc::new(state));
@@ -132,6 +202,24 @@ pub async fn create_app(listener: TcpListener, config: Config) -> Result<(), Err
     _ = import_worker_fut => {
       info!("Notion importer stopped");
     },
+    _ = attachment_indexer_fut => {
+      info!("Attachment indexer stopped");
+    },
+    _ = webhook_worker_fut => {
+      info!("Webhook worker stopped");
+    },
+    _ = access_request_worker_fut => {
+      info!("Access request worker stopped");
+    },
+    _ = notification_digest_worker_fut => {
+      info!("Notification digest worker stopped");
+    },
+    _ = reminder_worker_fut => {
+      info!("Reminder worker stopped");
+    },
+    _ = activity_digest_worker_fut => {
+      info!("Activity digest worker stopped");
+    },
     _ = axum::serve(listener, app) => {
       info!("worker stopped");
     },
@@ -0,0 +1,222 @@
+use crate::error::WorkerError;
+use anyhow::anyhow;
+use async_zip::base::read::mem::ZipFileReader;
+use futures::AsyncReadExt;
+
+const DOCX_MIME_TYPE: &str =
+  "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+const DOCX_DOCUMENT_PATH: &str = "word/document.xml";
+/// Upper bound of the uncompressed `word/document.xml` size. The archive size is limited by the
+/// attachment size, but a small archive can still inflate into a huge entry.
+const MAX_DOCX_XML_LEN: u64 = 64 * 1024 * 1024;
+
+/// Kinds of attachments that text can be extracted from.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AttachmentKind {
+  Pdf,
+  Docx,
+  PlainText,
+}
+
+impl AttachmentKind {
+  /// Resolves the attachment kind from the mime type stored in the blob metadata. Returns `None`
+  /// for file types which are not supported by the indexer.
+  pub fn from_mime_type(mime_type: &str) -> Option<Self> {
+    let mime_type = mime_type
+      .split(';')
+      .next()
+      .unwrap_or_default()
+      .trim()
+      .to_lowercase();
+    match mime_type.as_str() {
+      "application/pdf" => Some(AttachmentKind::Pdf),
+      DOCX_MIME_TYPE => Some(AttachmentKind::Docx),
+      "application/json" | "application/xml" => Some(AttachmentKind::PlainText),
+      other if other.starts_with("text/") => Some(AttachmentKind::PlainText),
+      _ => None,
+    }
+  }
+}
+
+/// Extracts the plain text content of an attachment. PDF parsing is CPU bound, so it's offloaded
+/// to a blocking thread.
+pub async fn extract_text(kind: AttachmentKind, content: Vec<u8>) -> Result<String, WorkerError> {
+  let text = match kind {
+    AttachmentKind::Pdf => tokio::task::spawn_blocking(move || {
+      pdf_extract::extract_text_from_mem(&content)
+        .map_err(|err| WorkerError::Internal(anyhow!("Failed to extract text from pdf: {}", err)))
+    })
+    .await
+    .map_err(|err| WorkerError::Internal(err.into()))??,
+    AttachmentKind::Docx => extract_docx_text(content).await?,
+    AttachmentKind::PlainText => String::from_utf8_lossy(&content).into_owned(),
+  };
+  Ok(normalize_whitespace(&text))
+}
+
+async fn extract_docx_text(content: Vec<u8>) -> Result<String, WorkerError> {
+  let reader = ZipFileReader::new(content).await?;
+  let index = reader
+    .file()
+    .entries()
+    .iter()
+    .position(|entry| {
+      entry
+        .filename()
+        .as_str()
+        .map(|name| name == DOCX_DOCUMENT_PATH)
+        .unwrap_or(false)
+    })
+    .ok_or_else(|| WorkerError::RecordNotFound(format!("{} is missing", DOCX_DOCUMENT_PATH)))?;
+
+  // The entry is truncated at the limit, which may cut a multi-byte character, so the content
+  // is decoded lossily instead of with `read_to_string`.
+  let mut xml = Vec::new();
+  reader
+    .reader_without_entry(index)
+    .await?
+    .take(MAX_DOCX_XML_LEN)
+    .read_to_end(&mut xml)
+    .await?;
+  Ok(docx_xml_to_text(&String::from_utf8_lossy(&xml)))
+}
+
+/// Converts the WordprocessingML body into plain text: the content of `<w:t>` elements is kept,
+/// paragraphs and line breaks are turned into new lines.
+fn docx_xml_to_text(xml: &str) -> String {
+  let mut text = String::with_capacity(xml.len() / 4);
+  let mut rest = xml;
+  let mut in_text_run = false;
+  while let Some(start) = rest.find('<') {
+    if in_text_run {
+      text.push_str(&unescape_xml(&rest[..start]));
+    }
+    let end = match rest[start..].find('>') {
+      Some(end) => start + end,
+      None => break,
+    };
+    let tag = &rest[start + 1..end];
+    let name = tag
+      .trim_start_matches('/')
+      .split(|c: char| c.is_whitespace() || c == '/')
+      .next()
+      .unwrap_or_default();
+    let is_closing = tag.starts_with('/');
+    let is_self_closing = tag.ends_with('/');
+    match name {
+      "w:t" => in_text_run = !is_closing && !is_self_closing,
+      "w:p" if is_closing => text.push('\n'),
+      "w:br" | "w:cr" => text.push('\n'),
+      "w:tab" => text.push('\t'),
+      _ => {},
+    }
+    rest = &rest[end + 1..];
+  }
+  text
+}
+
+fn unescape_xml(value: &str) -> String {
+  if !value.contains('&') {
+    return value.to_string();
+  }
+  value
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&quot;", "\"")
+    .replace("&apos;", "'")
+    .replace("&amp;", "&")
+}
+
+/// Collapses runs of blank lines and trailing spaces which are common in text extracted from PDFs.
+fn normalize_whitespace(text: &str) -> String {
+  let mut result = String::with_capacity(text.len());
+  let mut blank_lines = 0;
+  for line in text.lines() {
+    let line = line.trim_end();
+    if line.is_empty() {
+      blank_lines += 1;
+      if blank_lines > 1 {
+        continue;
+      }
+    } else {
+      blank_lines = 0;
+    }
+    result.push_str(line);
+    result.push('\n');
+  }
+  result.trim().to_string()
+}
+
+/// Splits the text into fragments of at most `max_content_len` bytes. Fragments are cut at
+/// paragraph boundaries when possible and never in the middle of a UTF-8 character.
+pub fn split_text_by_max_content_len(content: &str, max_content_len: usize) -> Vec<String> {
+  let mut result = Vec::with_capacity(1 + content.len() / max_content_len);
+  let mut fragment = String::with_capacity(max_content_len);
+  for paragraph in content.split_inclusive('\n') {
+    if fragment.len() + paragraph.len() > max_content_len && !fragment.is_empty() {
+      result.push(std::mem::take(&mut fragment));
+    }
+
+    if paragraph.len() <= max_content_len {
+      fragment.push_str(paragraph);
+      continue;
+    }
+
+    // a single paragraph is too long, split it at char boundaries
+    for c in paragraph.chars() {
+      if fragment.len() + c.len_utf8() > max_content_len {
+        result.push(std::mem::take(&mut fragment));
+      }
+      fragment.push(c);
+    }
+  }
+  if !fragment.trim().is_empty() {
+    result.push(fragment);
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn attachment_kind_from_mime_type_test() {
+    assert_eq!(
+      AttachmentKind::from_mime_type("application/pdf"),
+      Some(AttachmentKind::Pdf)
+    );
+    assert_eq!(
+      AttachmentKind::from_mime_type(DOCX_MIME_TYPE),
+      Some(AttachmentKind::Docx)
+    );
+    assert_eq!(
+      AttachmentKind::from_mime_type("text/markdown; charset=utf-8"),
+      Some(AttachmentKind::PlainText)
+    );
+    assert_eq!(AttachmentKind::from_mime_type("image/png"), None);
+  }
+
+  #[test]
+  fn docx_xml_to_text_test() {
+    let xml = r#"<w:document><w:body><w:p><w:r><w:t>Hello</w:t></w:r><w:r><w:tab/><w:t xml:space="preserve">world &amp; co</w:t></w:r></w:p><w:p><w:r><w:t>Second</w:t><w:br/><w:t>line</w:t></w:r></w:p></w:body></w:document>"#;
+    assert_eq!(docx_xml_to_text(xml), "Hello\tworld & co\nSecond\nline\n");
+  }
+
+  #[test]
+  fn split_text_at_paragraphs_test() {
+    let content = "first paragraph\nsecond paragraph\nthird";
+    let fragments = split_text_by_max_content_len(content, 20);
+    assert_eq!(
+      fragments,
+      vec!["first paragraph\n", "second paragraph\n", "third"]
+    );
+  }
+
+  #[test]
+  fn split_text_at_char_boundary_test() {
+    let content = "😃😃😃😃😃";
+    let fragments = split_text_by_max_content_len(content, 9);
+    assert_eq!(fragments, vec!["😃😃", "😃😃", "😃"]);
+  }
+}
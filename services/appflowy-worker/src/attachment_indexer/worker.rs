@@ -0,0 +1,270 @@
+use crate::attachment_indexer::extract::{
+  extract_text, split_text_by_max_content_len, AttachmentKind,
+};
+use crate::error::WorkerError;
+use crate::import_worker::worker::ensure_consumer_group;
+use crate::s3_client::S3Client;
+use anyhow::anyhow;
+use appflowy_ai_client::client::AppFlowyAIClient;
+use appflowy_ai_client::dto::{
+  EmbeddingEncodingFormat, EmbeddingInput, EmbeddingModel, EmbeddingOutput, EmbeddingRequest,
+};
+use collab_entity::CollabType;
+use database::collab::is_collab_exists;
+use database::index::upsert_attachment_embeddings;
+use database::workspace::select_workspace_settings;
+use database_entity::dto::{AFCollabEmbeddingParams, EmbeddingContentType};
+use futures::AsyncReadExt;
+use redis::aio::ConnectionManager;
+use redis::streams::{StreamId, StreamReadOptions, StreamReadReply};
+use redis::{AsyncCommands, Value};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info, trace, warn};
+use uuid::Uuid;
+
+const GROUP_NAME: &str = "attachment_index_group";
+const CONSUMER_NAME: &str = "appflowy_worker";
+
+/// We assume that every token is ~4 bytes. Extracted text is split into fragments of ~2000 tokens.
+const MAX_FRAGMENT_CONTENT_LEN: usize = 8000;
+
+/// Task pushed by the appflowy cloud server after an attachment has been uploaded to the bucket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentIndexTask {
+  pub workspace_id: Uuid,
+  /// Id of the document the file is attached to.
+  pub object_id: String,
+  /// Key of the file in the `af_blob_metadata` table.
+  pub file_id: String,
+  /// Key of the file in the bucket.
+  pub s3_key: String,
+  pub file_type: String,
+}
+
+impl TryFrom<&StreamId> for AttachmentIndexTask {
+  type Error = WorkerError;
+
+  fn try_from(stream_id: &StreamId) -> Result<Self, Self::Error> {
+    match stream_id.map.get("task") {
+      Some(Value::Data(data)) => {
+        serde_json::from_slice(data).map_err(|err| WorkerError::Internal(err.into()))
+      },
+      Some(value) => Err(WorkerError::Internal(anyhow!(
+        "Unexpected value type for task field: {:?}",
+        value
+      ))),
+      None => Err(WorkerError::Internal(anyhow!(
+        "Task field not found in Redis stream entry"
+      ))),
+    }
+  }
+}
+
+pub async fn run_attachment_indexer(
+  pg_pool: PgPool,
+  mut redis_client: ConnectionManager,
+  s3_client: Arc<dyn S3Client>,
+  ai_client: AppFlowyAIClient,
+  stream_name: &str,
+  tick_interval_secs: u64,
+  max_attachment_size: u64,
+) -> Result<(), WorkerError> {
+  info!("Starting attachment indexer");
+  if let Err(err) = ensure_consumer_group(stream_name, GROUP_NAME, &mut redis_client).await {
+    error!("Failed to ensure consumer group: {:?}", err);
+  }
+
+  let options = StreamReadOptions::default()
+    .group(GROUP_NAME, CONSUMER_NAME)
+    .count(10);
+  let mut interval = interval(Duration::from_secs(tick_interval_secs));
+  interval.tick().await;
+
+  loop {
+    interval.tick().await;
+    let tasks: StreamReadReply = match redis_client
+      .xread_options(&[stream_name], &[">"], &options)
+      .await
+    {
+      Ok(tasks) => tasks,
+      Err(err) => {
+        error!(
+          "Failed to read attachment tasks from Redis stream: {:?}",
+          err
+        );
+        if err.code() == Some("NOGROUP") {
+          if let Err(err) = ensure_consumer_group(stream_name, GROUP_NAME, &mut redis_client).await
+          {
+            error!("Failed to ensure consumer group: {:?}", err);
+          }
+        }
+        continue;
+      },
+    };
+
+    for stream_key in tasks.keys {
+      for stream_id in stream_key.ids {
+        match AttachmentIndexTask::try_from(&stream_id) {
+          Ok(task) => {
+            if let Err(err) = index_attachment(
+              &pg_pool,
+              s3_client.as_ref(),
+              &ai_client,
+              &task,
+              max_attachment_size,
+            )
+            .await
+            {
+              error!(
+                "[Attachment] failed to index {}/{}: {:?}",
+                task.workspace_id, task.file_id, err
+              );
+            }
+          },
+          Err(err) => error!("Failed to deserialize attachment task: {:?}", err),
+        }
+
+        // Indexing is best effort. Failed tasks are not retried, the file can be uploaded again.
+        let result: Result<(), redis::RedisError> = redis_client
+          .xack(stream_name, GROUP_NAME, &[&stream_id.id])
+          .await;
+        if let Err(err) = result {
+          error!("Failed to acknowledge attachment task: {:?}", err);
+        }
+      }
+    }
+  }
+}
+
+async fn index_attachment(
+  pg_pool: &PgPool,
+  s3_client: &dyn S3Client,
+  ai_client: &AppFlowyAIClient,
+  task: &AttachmentIndexTask,
+  max_attachment_size: u64,
+) -> Result<(), WorkerError> {
+  let kind = match AttachmentKind::from_mime_type(&task.file_type) {
+    Some(kind) => kind,
+    None => {
+      trace!(
+        "[Attachment] skip unsupported file type: {}",
+        task.file_type
+      );
+      return Ok(());
+    },
+  };
+
+  let settings = select_workspace_settings(pg_pool, &task.workspace_id)
+    .await
+    .map_err(|err| WorkerError::Internal(err.into()))?;
+  if settings.map(|s| s.disable_search_indexing).unwrap_or(false) {
+    trace!(
+      "[Attachment] search indexing disabled for workspace: {}",
+      task.workspace_id
+    );
+    return Ok(());
+  }
+
+  // Embeddings reference the document the file is attached to. Files uploaded into a directory
+  // that is not a document can't be searched.
+  if !is_collab_exists(&task.object_id, pg_pool)
+    .await
+    .map_err(|err| WorkerError::Internal(err.into()))?
+  {
+    warn!(
+      "[Attachment] document {} of attachment {} does not exist",
+      task.object_id, task.file_id
+    );
+    return Ok(());
+  }
+
+  let mut response = s3_client.get_blob_stream(&task.s3_key).await?;
+  if let Some(content_length) = response.content_length {
+    if content_length as u64 > max_attachment_size {
+      info!(
+        "[Attachment] skip {}: file size {} exceeds the limit {}",
+        task.file_id, content_length, max_attachment_size
+      );
+      return Ok(());
+    }
+  }
+  let mut content = Vec::with_capacity(response.content_length.unwrap_or(0) as usize);
+  response.stream.read_to_end(&mut content).await?;
+
+  let text = extract_text(kind, content).await?;
+  let fragments = split_text_by_max_content_len(&text, MAX_FRAGMENT_CONTENT_LEN);
+  if fragments.is_empty() {
+    trace!("[Attachment] no text found in {}", task.file_id);
+    return Ok(());
+  }
+
+  let mut params = fragments
+    .into_iter()
+    .map(|content| AFCollabEmbeddingParams {
+      fragment_id: Uuid::new_v4().to_string(),
+      object_id: task.object_id.clone(),
+      collab_type: CollabType::Document,
+      content_type: EmbeddingContentType::Attachment,
+      content,
+      embedding: None,
+    })
+    .collect::<Vec<_>>();
+
+  let resp = ai_client
+    .embeddings(EmbeddingRequest {
+      input: EmbeddingInput::StringArray(params.iter().map(|p| p.content.clone()).collect()),
+      model: EmbeddingModel::TextEmbedding3Small.to_string(),
+      chunk_size: 2000,
+      encoding_format: EmbeddingEncodingFormat::Float,
+      dimensions: EmbeddingModel::TextEmbedding3Small.default_dimensions(),
+    })
+    .await
+    .map_err(|err| WorkerError::Internal(err.into()))?;
+
+  let fragments_len = params.len();
+  for embedding in resp.data {
+    let param = params.get_mut(embedding.index as usize).ok_or_else(|| {
+      WorkerError::Internal(anyhow!(
+        "Embedding index {} is out of range of {} fragments",
+        embedding.index,
+        fragments_len
+      ))
+    })?;
+    match embedding.embedding {
+      EmbeddingOutput::Float(embedding) => {
+        param.embedding = Some(embedding.into_iter().map(|f| f as f32).collect());
+      },
+      EmbeddingOutput::Base64(_) => {
+        return Err(WorkerError::Internal(anyhow!("Unexpected base64 encoding")))
+      },
+    }
+  }
+
+  info!(
+    "[Attachment] indexed {} fragments of {} - tokens used: {}",
+    params.len(),
+    task.file_id,
+    resp.total_tokens
+  );
+  let mut tx = pg_pool
+    .begin()
+    .await
+    .map_err(|err| WorkerError::Internal(err.into()))?;
+  upsert_attachment_embeddings(
+    &mut tx,
+    &task.workspace_id,
+    &task.file_id,
+    resp.total_tokens as u32,
+    params,
+  )
+  .await
+  .map_err(|err| WorkerError::Internal(err.into()))?;
+  tx.commit()
+    .await
+    .map_err(|err| WorkerError::Internal(err.into()))?;
+  Ok(())
+}
@@ -13,6 +13,7 @@ pub struct Config {
   pub db_settings: DatabaseSetting,
   pub s3_setting: S3Setting,
   pub mailer: MailerSetting,
+  pub ai: AISettings,
 }
 
 impl Config {
@@ -57,10 +58,26 @@ impl Config {
         smtp_username: get_env_var("APPFLOWY_MAILER_SMTP_USERNAME", "sender@example.com"),
         smtp_password: get_env_var("APPFLOWY_MAILER_SMTP_PASSWORD", "password").into(),
       },
+      ai: AISettings {
+        port: get_env_var("APPFLOWY_AI_SERVER_PORT", "5001").parse()?,
+        host: get_env_var("APPFLOWY_AI_SERVER_HOST", "localhost"),
+      },
     })
   }
 }
 
+#[derive(Clone, Debug)]
+pub struct AISettings {
+  pub port: u16,
+  pub host: String,
+}
+
+impl AISettings {
+  pub fn url(&self) -> String {
+    format!("http://{}:{}", self.host, self.port)
+  }
+}
+
 #[derive(Clone, Debug)]
 pub struct DatabaseSetting {
   pub pg_conn_opts: PgConnectOptions,
@@ -1321,7 +1321,7 @@ async fn upload_file_to_s3(
   ))
 }
 
-async fn get_encode_collab_from_bytes(
+pub(crate) async fn get_encode_collab_from_bytes(
   workspace_id: &str,
   object_id: &str,
   collab_type: &CollabType,
@@ -1360,7 +1360,7 @@ async fn get_encode_collab_from_bytes(
 }
 
 /// Ensure the consumer group exists, if not, create it.
-async fn ensure_consumer_group(
+pub(crate) async fn ensure_consumer_group(
   stream_key: &str,
   group_name: &str,
   redis_client: &mut ConnectionManager,
@@ -1,5 +1,11 @@
+pub mod access_request_worker;
+pub mod activity_digest_worker;
+pub mod attachment_indexer;
 pub mod error;
 pub mod import_worker;
 mod mailer;
 pub mod metric;
+pub mod notification_digest_worker;
+pub mod reminder_worker;
 pub mod s3_client;
+pub mod webhook_worker;
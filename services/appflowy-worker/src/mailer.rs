@@ -3,6 +3,9 @@ use std::ops::Deref;
 
 pub const IMPORT_SUCCESS_TEMPLATE: &str = "import_notion_success";
 pub const IMPORT_FAIL_TEMPLATE: &str = "import_notion_fail";
+pub const NOTIFICATION_DIGEST_TEMPLATE: &str = "notification_digest";
+pub const ACTIVITY_DIGEST_TEMPLATE: &str = "activity_digest";
+pub const REMINDER_TEMPLATE: &str = "reminder";
 #[derive(Clone)]
 pub struct AFWorkerMailer(Mailer);
 
@@ -22,9 +25,20 @@ impl AFWorkerMailer {
     let import_data_fail =
       include_str!("../../../assets/mailer_templates/build_production/import_data_fail.html");
 
+    let notification_digest =
+      include_str!("../../../assets/mailer_templates/build_production/notification_digest.html");
+
+    let reminder = include_str!("../../../assets/mailer_templates/build_production/reminder.html");
+
+    let activity_digest =
+      include_str!("../../../assets/mailer_templates/build_production/activity_digest.html");
+
     for (name, template) in [
       (IMPORT_SUCCESS_TEMPLATE, import_data_success),
       (IMPORT_FAIL_TEMPLATE, import_data_fail),
+      (NOTIFICATION_DIGEST_TEMPLATE, notification_digest),
+      (REMINDER_TEMPLATE, reminder),
+      (ACTIVITY_DIGEST_TEMPLATE, activity_digest),
     ] {
       mailer
         .register_template(name, template)
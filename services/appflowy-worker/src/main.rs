@@ -1,10 +1,13 @@
+mod access_request_worker;
 mod application;
+mod attachment_indexer;
 mod config;
 pub mod error;
 pub mod import_worker;
 pub(crate) mod s3_client;
 
 mod metric;
+mod webhook_worker;
 
 mod mailer;
 use crate::application::run_server;
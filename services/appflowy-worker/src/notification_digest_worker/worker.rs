@@ -0,0 +1,164 @@
+use crate::error::WorkerError;
+use crate::mailer::{AFWorkerMailer, NOTIFICATION_DIGEST_TEMPLATE};
+use database::notification::{
+  select_notification_digest_recipients, select_notifications_to_email,
+  update_notifications_emailed_at,
+};
+use database::pg_row::{AFNotificationDigestRecipientRow, AFNotificationRow};
+use serde::Serialize;
+use shared_entity::dto::notification_dto::NotificationKind;
+use sqlx::types::chrono::{Duration as ChronoDuration, Utc};
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info};
+
+/// Notifications read within this delay are not emailed.
+const DIGEST_DELAY_MINUTES: i64 = 30;
+const MAX_RECIPIENTS_PER_TICK: i64 = 100;
+const MAX_NOTIFICATIONS_PER_DIGEST: i64 = 20;
+
+#[derive(Serialize)]
+struct DigestNotification {
+  title: String,
+  detail: Option<String>,
+}
+
+#[derive(Serialize)]
+struct NotificationDigestMailerParam {
+  user_name: String,
+  unread_count: usize,
+  notifications: Vec<DigestNotification>,
+  has_more: bool,
+}
+
+/// Emails the users a summary of the notifications they didn't read, unless they disabled the
+/// email digests. A notification is emailed at most once.
+pub async fn run_notification_digest_worker(
+  pg_pool: PgPool,
+  mailer: AFWorkerMailer,
+  tick_interval_secs: u64,
+) -> Result<(), WorkerError> {
+  info!("Starting notification digest worker");
+  let mut interval = interval(Duration::from_secs(tick_interval_secs));
+
+  loop {
+    interval.tick().await;
+    let created_before = Utc::now() - ChronoDuration::minutes(DIGEST_DELAY_MINUTES);
+    let recipients = match select_notification_digest_recipients(
+      &pg_pool,
+      created_before,
+      MAX_RECIPIENTS_PER_TICK,
+    )
+    .await
+    {
+      Ok(recipients) => recipients,
+      Err(err) => {
+        error!(
+          "Failed to select the notification digest recipients: {:?}",
+          err
+        );
+        continue;
+      },
+    };
+    for recipient in recipients {
+      if let Err(err) = send_digest(&pg_pool, &mailer, &recipient, created_before).await {
+        error!(
+          "Failed to send the notification digest to {}: {:?}",
+          recipient.uid, err
+        );
+      }
+    }
+  }
+}
+
+async fn send_digest(
+  pg_pool: &PgPool,
+  mailer: &AFWorkerMailer,
+  recipient: &AFNotificationDigestRecipientRow,
+  created_before: sqlx::types::chrono::DateTime<Utc>,
+) -> Result<(), WorkerError> {
+  let mut notifications = select_notifications_to_email(
+    pg_pool,
+    recipient.uid,
+    created_before,
+    MAX_NOTIFICATIONS_PER_DIGEST + 1,
+  )
+  .await
+  .map_err(|err| WorkerError::Internal(err.into()))?;
+  let has_more = notifications.len() as i64 > MAX_NOTIFICATIONS_PER_DIGEST;
+  notifications.truncate(MAX_NOTIFICATIONS_PER_DIGEST as usize);
+  if !notifications.is_empty() {
+    let notifications: Vec<DigestNotification> =
+      notifications.iter().map(to_digest_notification).collect();
+    let param = NotificationDigestMailerParam {
+      user_name: recipient.name.clone(),
+      unread_count: notifications.len(),
+      notifications,
+      has_more,
+    };
+    mailer
+      .send_email_template(
+        Some(recipient.name.clone()),
+        &recipient.email,
+        NOTIFICATION_DIGEST_TEMPLATE,
+        param,
+        "You have unread notifications in AppFlowy",
+      )
+      .await?;
+  }
+  // The notifications that didn't fit in the digest are not emailed later either
+  update_notifications_emailed_at(pg_pool, recipient.uid, created_before)
+    .await
+    .map_err(|err| WorkerError::Internal(err.into()))?;
+  Ok(())
+}
+
+fn payload_str(notification: &AFNotificationRow, key: &str) -> Option<String> {
+  notification
+    .payload
+    .get(key)
+    .and_then(|value| value.as_str())
+    .filter(|value| !value.is_empty())
+    .map(|value| value.to_string())
+}
+
+fn to_digest_notification(notification: &AFNotificationRow) -> DigestNotification {
+  let workspace_name = payload_str(notification, "workspace_name").unwrap_or_default();
+  let (title, detail) = match NotificationKind::from_i16(notification.kind) {
+    Some(NotificationKind::DocumentMention) => ("You were mentioned in a page".to_string(), None),
+    Some(NotificationKind::CommentMention) => (
+      "You were mentioned in a comment".to_string(),
+      payload_str(notification, "preview"),
+    ),
+    Some(NotificationKind::CommentReply) => (
+      "New reply to a comment".to_string(),
+      payload_str(notification, "preview"),
+    ),
+    Some(NotificationKind::WorkspaceInvitation) => (
+      format!("You were invited to join {}", workspace_name),
+      payload_str(notification, "inviter_name").map(|name| format!("Invited by {}", name)),
+    ),
+    Some(NotificationKind::AccessRequest) => (
+      format!(
+        "{} requested access to a page of {}",
+        payload_str(notification, "requester_name").unwrap_or_default(),
+        workspace_name
+      ),
+      payload_str(notification, "reason"),
+    ),
+    Some(NotificationKind::AccessRequestApproved) => (
+      format!("Your request to access {} was approved", workspace_name),
+      None,
+    ),
+    Some(NotificationKind::AccessRequestRejected) => (
+      format!("Your request to access {} was declined", workspace_name),
+      payload_str(notification, "reason"),
+    ),
+    Some(NotificationKind::Reminder) => {
+      ("Reminder".to_string(), payload_str(notification, "preview"))
+    },
+    None => ("New notification".to_string(), None),
+  };
+  DigestNotification { title, detail }
+}
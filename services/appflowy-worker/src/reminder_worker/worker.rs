@@ -0,0 +1,129 @@
+use crate::error::WorkerError;
+use crate::mailer::{AFWorkerMailer, REMINDER_TEMPLATE};
+use database::notification::{
+  insert_notifications, select_notification_email_digest_enabled,
+  update_object_notifications_emailed_at,
+};
+use database::pg_row::AFDueReminderRow;
+use database::reminder::claim_due_reminders;
+use serde::Serialize;
+use shared_entity::dto::notification_dto::NotificationKind;
+use sqlx::types::chrono::{Duration as ChronoDuration, Utc};
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info, trace};
+
+/// Reminders due for longer than this, like the ones set on a past date, are not sent.
+const MAX_REMINDER_DELAY_HOURS: i64 = 24;
+const MAX_REMINDERS_PER_TICK: i64 = 100;
+
+#[derive(Serialize)]
+struct ReminderMailerParam {
+  user_name: String,
+  scheduled_at: String,
+  preview: Option<String>,
+}
+
+/// Fires the reminders stored by the collab persistence when they are due: the user is notified in
+/// the notification center and by email, unless the user muted the workspace or the page. Users
+/// that disabled the email digests don't get the reminder emails either.
+pub async fn run_reminder_worker(
+  pg_pool: PgPool,
+  mailer: AFWorkerMailer,
+  tick_interval_secs: u64,
+) -> Result<(), WorkerError> {
+  info!("Starting reminder worker");
+  let mut interval = interval(Duration::from_secs(tick_interval_secs));
+
+  loop {
+    interval.tick().await;
+    let reminders = match claim_due_reminders(&pg_pool, MAX_REMINDERS_PER_TICK).await {
+      Ok(reminders) => reminders,
+      Err(err) => {
+        error!("Failed to claim the due reminders: {:?}", err);
+        continue;
+      },
+    };
+    for reminder in reminders {
+      if let Err(err) = fire_reminder(&pg_pool, &mailer, &reminder).await {
+        error!(
+          "Failed to fire reminder {} of {}: {:?}",
+          reminder.reminder_id, reminder.object_id, err
+        );
+      }
+    }
+  }
+}
+
+async fn fire_reminder(
+  pg_pool: &PgPool,
+  mailer: &AFWorkerMailer,
+  reminder: &AFDueReminderRow,
+) -> Result<(), WorkerError> {
+  if reminder.scheduled_at < Utc::now() - ChronoDuration::hours(MAX_REMINDER_DELAY_HOURS) {
+    trace!(
+      "Skip reminder {} of {} scheduled at {}",
+      reminder.reminder_id,
+      reminder.object_id,
+      reminder.scheduled_at
+    );
+    return Ok(());
+  }
+
+  let notified = insert_notifications(
+    pg_pool,
+    &[reminder.uid],
+    &reminder.workspace_id,
+    NotificationKind::Reminder,
+    reminder.view_id.as_ref(),
+    Some(&reminder.reminder_id),
+    None,
+    &reminder.payload,
+  )
+  .await
+  .map_err(|err| WorkerError::Internal(err.into()))?;
+  if notified.is_empty() {
+    return Ok(());
+  }
+
+  let email_enabled = select_notification_email_digest_enabled(pg_pool, reminder.uid)
+    .await
+    .map_err(|err| WorkerError::Internal(err.into()))?
+    .unwrap_or(true);
+  if !email_enabled {
+    return Ok(());
+  }
+  let param = ReminderMailerParam {
+    user_name: reminder.name.clone(),
+    scheduled_at: reminder
+      .scheduled_at
+      .format("%Y-%m-%d %H:%M UTC")
+      .to_string(),
+    preview: reminder
+      .payload
+      .get("preview")
+      .and_then(|preview| preview.as_str())
+      .filter(|preview| !preview.is_empty())
+      .map(|preview| preview.to_string()),
+  };
+  mailer
+    .send_email_template(
+      Some(reminder.name.clone()),
+      &reminder.email,
+      REMINDER_TEMPLATE,
+      param,
+      "Reminder from AppFlowy",
+    )
+    .await?;
+  // The email digest doesn't summarise the reminder again
+  update_object_notifications_emailed_at(
+    pg_pool,
+    reminder.uid,
+    NotificationKind::Reminder,
+    &reminder.reminder_id,
+  )
+  .await
+  .map_err(|err| WorkerError::Internal(err.into()))?;
+  Ok(())
+}
@@ -0,0 +1,322 @@
+use crate::error::WorkerError;
+use crate::import_worker::worker::ensure_consumer_group;
+use anyhow::anyhow;
+use database::webhook::{
+  claim_due_webhook_deliveries, insert_webhook_delivery, select_webhook_targets,
+  update_webhook_delivery_attempt,
+};
+use hmac::{Hmac, Mac};
+use infra::url_util::{check_public_http_url, PublicHttpUrl};
+use redis::aio::ConnectionManager;
+use redis::streams::{StreamId, StreamReadOptions, StreamReadReply};
+use redis::{AsyncCommands, Value};
+use sha2::Sha256;
+use shared_entity::dto::webhook_dto::{
+  WebhookDeliveryStatus, WebhookPayload, WEBHOOK_DELIVERY_HEADER, WEBHOOK_EVENT_HEADER,
+  WEBHOOK_SIGNATURE_HEADER,
+};
+use sqlx::types::chrono::{DateTime, Duration as ChronoDuration, Utc};
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info, trace};
+use uuid::Uuid;
+
+const GROUP_NAME: &str = "webhook_event_group";
+const CONSUMER_NAME: &str = "appflowy_worker";
+
+/// A delivery is given up after this many attempts.
+const MAX_DELIVERY_ATTEMPTS: i32 = 6;
+/// Delay before the first retry, doubled after every failed attempt.
+const RETRY_BASE_DELAY_SECS: i64 = 30;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// A delivery being sent is not retried by another worker before the lease expires.
+const DELIVERY_LEASE_SECS: i64 = 60;
+const MAX_RETRIES_PER_TICK: i64 = 50;
+
+fn parse_webhook_event(stream_id: &StreamId) -> Result<WebhookPayload, WorkerError> {
+  match stream_id.map.get("task") {
+    Some(Value::Data(data)) => {
+      serde_json::from_slice(data).map_err(|err| WorkerError::Internal(err.into()))
+    },
+    Some(value) => Err(WorkerError::Internal(anyhow!(
+      "Unexpected value type for task field: {:?}",
+      value
+    ))),
+    None => Err(WorkerError::Internal(anyhow!(
+      "Task field not found in Redis stream entry"
+    ))),
+  }
+}
+
+/// Delivers the events queued by the appflowy cloud server to the webhooks subscribed to them, and
+/// retries the failed deliveries with an exponential backoff.
+pub async fn run_webhook_worker(
+  pg_pool: PgPool,
+  mut redis_client: ConnectionManager,
+  stream_name: &str,
+  tick_interval_secs: u64,
+) -> Result<(), WorkerError> {
+  info!("Starting webhook worker");
+  if let Err(err) = ensure_consumer_group(stream_name, GROUP_NAME, &mut redis_client).await {
+    error!("Failed to ensure consumer group: {:?}", err);
+  }
+
+  let options = StreamReadOptions::default()
+    .group(GROUP_NAME, CONSUMER_NAME)
+    .count(50);
+  let mut interval = interval(Duration::from_secs(tick_interval_secs));
+  interval.tick().await;
+
+  loop {
+    interval.tick().await;
+    match redis_client
+      .xread_options::<_, _, StreamReadReply>(&[stream_name], &[">"], &options)
+      .await
+    {
+      Ok(events) => {
+        for stream_key in events.keys {
+          for stream_id in stream_key.ids {
+            match parse_webhook_event(&stream_id) {
+              Ok(payload) => {
+                if let Err(err) = dispatch_webhook_event(&pg_pool, &payload).await {
+                  error!(
+                    "[Webhook] failed to dispatch {} of workspace {}: {:?}",
+                    payload.event, payload.workspace_id, err
+                  );
+                }
+              },
+              Err(err) => error!("Failed to deserialize webhook event: {:?}", err),
+            }
+
+            // Failed deliveries are retried from the delivery log, not from the stream.
+            let result: Result<(), redis::RedisError> = redis_client
+              .xack(stream_name, GROUP_NAME, &[&stream_id.id])
+              .await;
+            if let Err(err) = result {
+              error!("Failed to acknowledge webhook event: {:?}", err);
+            }
+          }
+        }
+      },
+      Err(err) => {
+        error!("Failed to read webhook events from Redis stream: {:?}", err);
+        if err.code() == Some("NOGROUP") {
+          if let Err(err) = ensure_consumer_group(stream_name, GROUP_NAME, &mut redis_client).await
+          {
+            error!("Failed to ensure consumer group: {:?}", err);
+          }
+        }
+      },
+    }
+
+    if let Err(err) = retry_due_deliveries(&pg_pool).await {
+      error!("[Webhook] failed to retry deliveries: {:?}", err);
+    }
+  }
+}
+
+async fn dispatch_webhook_event(
+  pg_pool: &PgPool,
+  payload: &WebhookPayload,
+) -> Result<(), WorkerError> {
+  let targets = select_webhook_targets(pg_pool, &payload.workspace_id, &payload.event)
+    .await
+    .map_err(|err| WorkerError::Internal(err.into()))?;
+  if targets.is_empty() {
+    trace!(
+      "[Webhook] no webhook subscribed to {} in workspace {}",
+      payload.event,
+      payload.workspace_id
+    );
+    return Ok(());
+  }
+
+  let payload_value =
+    serde_json::to_value(payload).map_err(|err| WorkerError::Internal(err.into()))?;
+  // A failure for one target doesn't stop the delivery to the others. A delivery whose attempt
+  // couldn't be recorded stays pending, and is retried once its lease expires.
+  for target in targets {
+    let delivery_id = match insert_webhook_delivery(
+      pg_pool,
+      &target.webhook_id,
+      &payload.event,
+      &payload_value,
+      Utc::now() + ChronoDuration::seconds(DELIVERY_LEASE_SECS),
+    )
+    .await
+    {
+      Ok(delivery_id) => delivery_id,
+      Err(err) => {
+        error!(
+          "[Webhook] failed to record the delivery of {} to webhook {}: {:?}",
+          payload.event, target.webhook_id, err
+        );
+        continue;
+      },
+    };
+    if let Err(err) = attempt_delivery(
+      pg_pool,
+      &delivery_id,
+      &target.url,
+      &target.secret,
+      &payload.event,
+      &payload_value,
+      0,
+    )
+    .await
+    {
+      error!(
+        "[Webhook] failed to deliver {} to webhook {}: {:?}",
+        payload.event, target.webhook_id, err
+      );
+    }
+  }
+  Ok(())
+}
+
+async fn retry_due_deliveries(pg_pool: &PgPool) -> Result<(), WorkerError> {
+  let deliveries = claim_due_webhook_deliveries(
+    pg_pool,
+    MAX_RETRIES_PER_TICK,
+    Utc::now() + ChronoDuration::seconds(DELIVERY_LEASE_SECS),
+  )
+  .await
+  .map_err(|err| WorkerError::Internal(err.into()))?;
+  for delivery in deliveries {
+    if let Err(err) = attempt_delivery(
+      pg_pool,
+      &delivery.delivery_id,
+      &delivery.url,
+      &delivery.secret,
+      &delivery.event,
+      &delivery.payload,
+      delivery.attempts,
+    )
+    .await
+    {
+      error!(
+        "[Webhook] failed to retry delivery {}: {:?}",
+        delivery.delivery_id, err
+      );
+    }
+  }
+  Ok(())
+}
+
+/// Sends the payload once and records the result in the delivery log.
+#[allow(clippy::too_many_arguments)]
+async fn attempt_delivery(
+  pg_pool: &PgPool,
+  delivery_id: &Uuid,
+  url: &str,
+  secret: &str,
+  event: &str,
+  payload: &serde_json::Value,
+  previous_attempts: i32,
+) -> Result<(), WorkerError> {
+  let body = serde_json::to_vec(payload).map_err(|err| WorkerError::Internal(err.into()))?;
+  let signature = sign_webhook_payload(secret, &body)?;
+  // The host is resolved again before every delivery, since its address can change after the
+  // webhook was created.
+  let (response_status, error) = match check_public_http_url(url).await {
+    Ok(public_url) => {
+      let result = match delivery_http_client(&public_url) {
+        Ok(http_client) => {
+          http_client
+            .post(public_url.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_SIGNATURE_HEADER, format!("sha256={}", signature))
+            .header(WEBHOOK_EVENT_HEADER, event)
+            .header(WEBHOOK_DELIVERY_HEADER, delivery_id.to_string())
+            .body(body)
+            .send()
+            .await
+        },
+        Err(err) => Err(err),
+      };
+      match result {
+        Ok(resp) if resp.status().is_success() => (Some(resp.status().as_u16() as i32), None),
+        Ok(resp) => (
+          Some(resp.status().as_u16() as i32),
+          Some(format!("Unexpected status {}", resp.status())),
+        ),
+        Err(err) => (None, Some(err.to_string())),
+      }
+    },
+    Err(err) => (None, Some(err.to_string())),
+  };
+
+  let attempts = previous_attempts + 1;
+  let status = match &error {
+    None => WebhookDeliveryStatus::Delivered,
+    Some(_) if attempts >= MAX_DELIVERY_ATTEMPTS => WebhookDeliveryStatus::Failed,
+    Some(_) => WebhookDeliveryStatus::Pending,
+  };
+  match &error {
+    None => trace!("[Webhook] delivered {} to {}", event, url),
+    Some(error) => info!(
+      "[Webhook] attempt {} to deliver {} to {} failed: {}",
+      attempts, event, url, error
+    ),
+  }
+
+  update_webhook_delivery_attempt(
+    pg_pool,
+    delivery_id,
+    status,
+    response_status,
+    error.as_deref(),
+    next_attempt_at(Utc::now(), attempts),
+  )
+  .await
+  .map_err(|err| WorkerError::Internal(err.into()))?;
+  Ok(())
+}
+
+/// A client that only connects to the checked addresses of the url, so the host can't be
+/// resolved to an internal address between the check and the request. Redirects are not
+/// followed, since they could point to an address that was not checked.
+fn delivery_http_client(public_url: &PublicHttpUrl) -> Result<reqwest::Client, reqwest::Error> {
+  reqwest::Client::builder()
+    .timeout(DELIVERY_TIMEOUT)
+    .redirect(reqwest::redirect::Policy::none())
+    .resolve_to_addrs(&public_url.host, &public_url.addrs)
+    .build()
+}
+
+/// Hex encoded HMAC-SHA256 of the body, sent in the [WEBHOOK_SIGNATURE_HEADER] header.
+pub fn sign_webhook_payload(secret: &str, body: &[u8]) -> Result<String, WorkerError> {
+  let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+    .map_err(|err| WorkerError::Internal(anyhow!("Invalid webhook secret: {}", err)))?;
+  mac.update(body);
+  Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+fn next_attempt_at(now: DateTime<Utc>, attempts: i32) -> DateTime<Utc> {
+  let exponent = (attempts - 1).clamp(0, MAX_DELIVERY_ATTEMPTS) as u32;
+  now + ChronoDuration::seconds(RETRY_BASE_DELAY_SECS * 2_i64.pow(exponent))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sign_webhook_payload_test() {
+    // Test case 2 of RFC 4231.
+    let signature = sign_webhook_payload("Jefe", b"what do ya want for nothing?").unwrap();
+    assert_eq!(
+      signature,
+      "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+  }
+
+  #[test]
+  fn next_attempt_backoff_test() {
+    let now = Utc::now();
+    assert_eq!(next_attempt_at(now, 1) - now, ChronoDuration::seconds(30));
+    assert_eq!(next_attempt_at(now, 2) - now, ChronoDuration::seconds(60));
+    assert_eq!(next_attempt_at(now, 5) - now, ChronoDuration::seconds(480));
+  }
+}
@@ -1,6 +1,6 @@
 use actix_web::{
   web::{self, Data, Json},
-  Result, Scope,
+  HttpRequest, Result, Scope,
 };
 use anyhow::anyhow;
 use app_error::AppError;
@@ -9,21 +9,34 @@ use database_entity::dto::{
   AccessRequestMinimal, ApproveAccessRequestParams, CreateAccessRequestParams,
 };
 use shared_entity::{
-  dto::access_request_dto::AccessRequest,
+  dto::{
+    access_request_dto::{AccessRequest, QueryAccessRequestParams, RepeatedAccessRequestItem},
+    audit_log_dto::AuditAction,
+  },
   response::{AppResponse, JsonAppResponse},
 };
+use tracing::error;
 use uuid::Uuid;
 
 use crate::{
-  biz::access_request::ops::{
-    approve_or_reject_access_request, create_access_request, get_access_request,
+  biz::{
+    access_request::ops::{
+      approve_or_reject_access_request, create_access_request, get_access_request,
+      list_access_requests,
+    },
+    audit_log::ops::{record_audit_log, AuditSource},
+    workspace::view_permission::refresh_workspace_view_permissions,
   },
   state::AppState,
 };
 
 pub fn access_request_scope() -> Scope {
   web::scope("/api/access-request")
-    .service(web::resource("").route(web::post().to(post_access_request_handler)))
+    .service(
+      web::resource("")
+        .route(web::get().to(list_access_requests_handler))
+        .route(web::post().to(post_access_request_handler)),
+    )
     .service(web::resource("/{request_id}").route(web::get().to(get_access_request_handler)))
     .service(
       web::resource("/{request_id}/approve")
@@ -48,14 +61,34 @@ async fn get_access_request_handler(
   Ok(Json(AppResponse::Ok().with_data(access_request)))
 }
 
+async fn list_access_requests_handler(
+  uuid: UserUuid,
+  query: web::Query<QueryAccessRequestParams>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<RepeatedAccessRequestItem>> {
+  let uid = state.user_cache.get_user_uid(&uuid).await?;
+  let access_requests = list_access_requests(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    state.workspace_access_control.clone(),
+    uid,
+    query.into_inner(),
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(access_requests)))
+}
+
 async fn post_access_request_handler(
   uuid: UserUuid,
   create_access_request_params: Json<CreateAccessRequestParams>,
   state: Data<AppState>,
 ) -> Result<JsonAppResponse<AccessRequestMinimal>> {
   let uid = state.user_cache.get_user_uid(&uuid).await?;
-  let workspace_id = create_access_request_params.workspace_id;
-  let view_id = create_access_request_params.view_id;
+  let CreateAccessRequestParams {
+    workspace_id,
+    view_id,
+    reason,
+  } = create_access_request_params.into_inner();
   let appflowy_web_url = state
     .config
     .appflowy_web_url
@@ -70,6 +103,7 @@ async fn post_access_request_handler(
     workspace_id,
     view_id,
     uid,
+    reason,
   )
   .await?;
   let access_request = AccessRequestMinimal {
@@ -86,10 +120,15 @@ async fn post_approve_access_request_handler(
   access_request_id: web::Path<Uuid>,
   approve_access_request_params: Json<ApproveAccessRequestParams>,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<()>> {
   let uid = state.user_cache.get_user_uid(&uuid).await?;
   let access_request_id = access_request_id.into_inner();
-  let is_approved = approve_access_request_params.is_approved;
+  let ApproveAccessRequestParams {
+    is_approved,
+    group_id,
+    reason,
+  } = approve_access_request_params.into_inner();
   let appflowy_web_url = state
     .config
     .appflowy_web_url
@@ -97,7 +136,7 @@ async fn post_approve_access_request_handler(
     .ok_or(AppError::Internal(anyhow!(
       "AppFlowy web url has not been set"
     )))?;
-  approve_or_reject_access_request(
+  let workspace_id = approve_or_reject_access_request(
     &state.pg_pool,
     state.workspace_access_control.clone(),
     state.mailer.clone(),
@@ -105,7 +144,41 @@ async fn post_approve_access_request_handler(
     access_request_id,
     uid,
     is_approved,
+    group_id,
+    reason.clone(),
   )
   .await?;
+  if is_approved && group_id.is_some() {
+    // The requester gets the access levels granted to the group on the spaces and pages.
+    if let Err(err) = refresh_workspace_view_permissions(
+      &state.pg_pool,
+      &state.collab_access_control_storage,
+      &state.collab_access_control,
+      uid,
+      &workspace_id,
+    )
+    .await
+    {
+      error!(
+        "Failed to refresh the view permissions of workspace {}: {:?}",
+        workspace_id, err
+      );
+    }
+  }
+  let action = if is_approved {
+    AuditAction::AccessRequestApproved
+  } else {
+    AuditAction::AccessRequestRejected
+  };
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    action,
+    Some(&access_request_id.to_string()),
+    serde_json::json!({ "reason": reason }),
+  )
+  .await;
   Ok(Json(AppResponse::Ok()))
 }
@@ -8,7 +8,9 @@ use appflowy_ai_client::dto::{
   CalculateSimilarityParams, CompleteTextResponse, LocalAIConfig, SimilarityResponse,
   TranslateRowParams, TranslateRowResponse,
 };
+use authentication::jwt::UserUuid;
 
+use database_entity::dto::AFWorkspacePermission;
 use futures_util::{stream, TryStreamExt};
 
 use serde::Deserialize;
@@ -18,6 +20,7 @@ use shared_entity::dto::ai_dto::{
 use shared_entity::response::{AppResponse, JsonAppResponse};
 
 use tracing::{error, instrument, trace};
+use uuid::Uuid;
 
 pub fn ai_completion_scope() -> Scope {
   web::scope("/api/ai/{workspace_id}")
@@ -32,10 +35,21 @@ pub fn ai_completion_scope() -> Scope {
 }
 
 async fn complete_text_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
   state: Data<AppState>,
   payload: Json<CompleteTextParams>,
   req: HttpRequest,
 ) -> actix_web::Result<JsonAppResponse<CompleteTextResponse>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_permission(
+      &uid,
+      &workspace_id.to_string(),
+      AFWorkspacePermission::UseAI,
+    )
+    .await?;
   let ai_model = ai_model_from_header(&req);
   let params = payload.into_inner();
   let resp = state
@@ -47,10 +61,21 @@ async fn complete_text_handler(
 }
 
 async fn stream_complete_text_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
   state: Data<AppState>,
   payload: Json<CompleteTextParams>,
   req: HttpRequest,
 ) -> actix_web::Result<HttpResponse> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_permission(
+      &uid,
+      &workspace_id.to_string(),
+      AFWorkspacePermission::UseAI,
+    )
+    .await?;
   let ai_model = ai_model_from_header(&req);
   let params = payload.into_inner();
   match state
@@ -80,10 +105,21 @@ async fn stream_complete_text_handler(
 
 #[instrument(level = "debug", skip(state, payload), err)]
 async fn summarize_row_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
   state: Data<AppState>,
   payload: Json<SummarizeRowParams>,
   req: HttpRequest,
 ) -> actix_web::Result<Json<AppResponse<SummarizeRowResponse>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_permission(
+      &uid,
+      &workspace_id.to_string(),
+      AFWorkspacePermission::UseAI,
+    )
+    .await?;
   let params = payload.into_inner();
   match params.data {
     SummarizeRowData::Identity { .. } => {
@@ -119,10 +155,21 @@ async fn summarize_row_handler(
 
 #[instrument(level = "debug", skip(state, payload), err)]
 async fn translate_row_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
   state: web::Data<AppState>,
   payload: web::Json<TranslateRowParams>,
   req: HttpRequest,
 ) -> actix_web::Result<Json<AppResponse<TranslateRowResponse>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_permission(
+      &uid,
+      &workspace_id.to_string(),
+      AFWorkspacePermission::UseAI,
+    )
+    .await?;
   let params = payload.into_inner();
   let ai_model = ai_model_from_header(&req);
   match state.ai_client.translate_row(params.data, ai_model).await {
@@ -1,25 +1,38 @@
 use crate::biz::chat::ops::{
-  create_chat, create_chat_message, delete_chat, generate_chat_message_answer, get_chat_messages,
-  update_chat_message,
+  chat_export_to_markdown, create_chat, create_chat_message, delete_chat, export_chat,
+  generate_chat_message_answer, get_answer_versions, get_chat_messages, search_chat_messages,
+  update_chat_message, update_selected_answer_version,
 };
+use crate::biz::chat::participant::{
+  add_chat_participants, enforce_chat_action, get_chat_participants, get_chats_for_user,
+  remove_chat_participants,
+};
+use crate::biz::chat::tools::{stream_answer_with_tools, ChatToolContext};
 use crate::state::AppState;
 use actix_web::web::{Data, Json};
 use actix_web::{web, HttpRequest, HttpResponse, Scope};
 
 use crate::api::util::ai_model_from_header;
+use access_control::act::Action;
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
 use app_error::AppError;
 use appflowy_ai_client::dto::{CreateChatContext, RepeatedRelatedQuestion};
 use authentication::jwt::UserUuid;
 use bytes::Bytes;
 use database::chat;
+use database_entity::dto::AFWorkspacePermission;
 use futures::Stream;
 use futures_util::stream;
 use futures_util::{FutureExt, TryStreamExt};
 use pin_project::pin_project;
 use shared_entity::dto::chat_dto::{
-  ChatAuthor, ChatMessage, ChatSettings, CreateAnswerMessageParams, CreateChatMessageParams,
-  CreateChatMessageParamsV2, CreateChatParams, GetChatMessageParams, MessageCursor,
-  RepeatedChatMessage, UpdateChatMessageContentParams, UpdateChatParams,
+  AddChatParticipantsParams, ChatAuthor, ChatExportFormat, ChatMessage, ChatParticipantRole,
+  ChatSettings, CreateAnswerMessageParams, CreateChatMessageParams, CreateChatMessageParamsV2,
+  CreateChatParams, ExportChatParams, GetChatMessageParams, MessageCursor,
+  RemoveChatParticipantsParams, RepeatedAnswerVersion, RepeatedChatBranch, RepeatedChatInfo,
+  RepeatedChatMessage, RepeatedChatMessageSearchResult, RepeatedChatParticipant,
+  SearchChatMessageParams, SelectAnswerVersionParams, UpdateActiveChatBranchParams,
+  UpdateChatMessageContentParams, UpdateChatParams,
 };
 use shared_entity::response::{AppResponse, JsonAppResponse};
 use std::collections::HashMap;
@@ -36,6 +49,12 @@ pub fn chat_scope() -> Scope {
       .service(
         web::resource("")
             .route(web::post().to(create_chat_handler))
+            .route(web::get().to(get_chats_handler))
+      )
+      // Must be registered before /{chat_id}
+      .service(
+        web::resource("/search")
+            .route(web::get().to(search_chat_messages_handler))
       )
       .service(
         web::resource("/{chat_id}")
@@ -44,12 +63,33 @@ pub fn chat_scope() -> Scope {
             .route(web::get().to(get_chat_message_handler))
       )
 
+      // Participants
+      .service(
+        web::resource("/{chat_id}/participant")
+            .route(web::get().to(get_chat_participants_handler))
+            .route(web::post().to(add_chat_participants_handler))
+            .route(web::delete().to(remove_chat_participants_handler))
+      )
+
       // Settings
       .service(
         web::resource("/{chat_id}/settings")
             .route(web::get().to(get_chat_settings_handler))
             .route(web::post().to(update_chat_settings_handler))
       )
+      // Branches
+      .service(
+        web::resource("/{chat_id}/branch")
+            .route(web::get().to(get_chat_branches_handler))
+      )
+      .service(
+        web::resource("/{chat_id}/branch/active")
+            .route(web::put().to(update_active_chat_branch_handler))
+      )
+      .service(
+        web::resource("/{chat_id}/export")
+            .route(web::get().to(export_chat_handler))
+      )
 
       // Message management
       .service(
@@ -75,6 +115,11 @@ pub fn chat_scope() -> Scope {
         web::resource("/{chat_id}/{message_id}/answer")
             .route(web::get().to(answer_handler))
       )
+      .service(
+        web::resource("/{chat_id}/{message_id}/answer/versions")
+            .route(web::get().to(get_answer_versions_handler))
+            .route(web::put().to(select_answer_version_handler))
+      )
       .service(
         web::resource("/{chat_id}/{message_id}/answer/stream")
             .route(web::get().to(answer_stream_handler)) // Deprecated
@@ -94,31 +139,182 @@ pub fn chat_scope() -> Scope {
             .route(web::post().to(create_chat_context_handler))
       )
 }
+/// Resolves the uid of the user and checks that the user can perform the action on the chat.
+async fn enforce_chat_action_for_user(
+  state: &AppState,
+  user_uuid: &UserUuid,
+  workspace_id: &str,
+  chat_id: &str,
+  action: Action,
+) -> Result<(i64, ChatParticipantRole), AppError> {
+  let uid = state.user_cache.get_user_uid(user_uuid).await?;
+  let role = enforce_chat_action(
+    &state.pg_pool,
+    &state.workspace_access_control,
+    uid,
+    workspace_id,
+    chat_id,
+    action,
+  )
+  .await?;
+  Ok((uid, role))
+}
+
+/// Checks that the user is allowed to use the AI or export in the workspace.
+async fn enforce_workspace_permission(
+  state: &AppState,
+  uid: i64,
+  workspace_id: &str,
+  permission: AFWorkspacePermission,
+) -> Result<(), AppError> {
+  state
+    .workspace_access_control
+    .enforce_permission(&uid, workspace_id, permission)
+    .await
+}
+
+/// The tools called by the AI act on behalf of the user who asked the question.
+fn chat_tool_context(
+  state: &AppState,
+  uid: i64,
+  workspace_id: &str,
+) -> Result<ChatToolContext, AppError> {
+  Ok(ChatToolContext {
+    pg_pool: state.pg_pool.clone(),
+    ai_client: state.ai_client.clone(),
+    collab_storage: state.collab_access_control_storage.clone(),
+    activity_recorder: state.activity_recorder.clone(),
+    workspace_access_control: state.workspace_access_control.clone(),
+    collab_access_control: state.collab_access_control.clone(),
+    request_metrics: state.metrics.request_metrics.clone(),
+    uid,
+    workspace_id: Uuid::parse_str(workspace_id)?,
+  })
+}
+
 async fn create_chat_handler(
+  user_uuid: UserUuid,
   path: web::Path<String>,
   state: Data<AppState>,
   payload: Json<CreateChatParams>,
 ) -> actix_web::Result<JsonAppResponse<()>> {
   let workspace_id = path.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_action(&uid, &workspace_id, Action::Write)
+    .await?;
+  enforce_workspace_permission(&state, uid, &workspace_id, AFWorkspacePermission::UseAI).await?;
   let params = payload.into_inner();
-  create_chat(&state.pg_pool, params, &workspace_id).await?;
+  create_chat(&state.pg_pool, uid, params, &workspace_id).await?;
   Ok(AppResponse::Ok().into())
 }
 
+#[instrument(level = "debug", skip_all, err)]
+async fn get_chats_handler(
+  user_uuid: UserUuid,
+  path: web::Path<String>,
+  state: Data<AppState>,
+) -> actix_web::Result<JsonAppResponse<RepeatedChatInfo>> {
+  let workspace_id = path.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_action(&uid, &workspace_id, Action::Read)
+    .await?;
+  let chats = get_chats_for_user(&state.pg_pool, &workspace_id, uid).await?;
+  Ok(AppResponse::Ok().with_data(chats).into())
+}
+
 async fn delete_chat_handler(
+  user_uuid: UserUuid,
   path: web::Path<(String, String)>,
   state: Data<AppState>,
 ) -> actix_web::Result<JsonAppResponse<()>> {
-  let (_workspace_id, chat_id) = path.into_inner();
+  let (workspace_id, chat_id) = path.into_inner();
+  let (uid, role) =
+    enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Write)
+      .await?;
+  if role != ChatParticipantRole::Owner {
+    return Err(
+      AppError::NotEnoughPermissions {
+        user: uid.to_string(),
+        workspace_id,
+      }
+      .into(),
+    );
+  }
   delete_chat(&state.pg_pool, &chat_id).await?;
   Ok(AppResponse::Ok().into())
 }
 
+#[instrument(level = "debug", skip_all, err)]
+async fn get_chat_participants_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(String, String)>,
+  state: Data<AppState>,
+) -> actix_web::Result<JsonAppResponse<RepeatedChatParticipant>> {
+  let (workspace_id, chat_id) = path.into_inner();
+  enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Read).await?;
+  let participants = get_chat_participants(&state.pg_pool, &chat_id).await?;
+  Ok(AppResponse::Ok().with_data(participants).into())
+}
+
+#[instrument(level = "debug", skip_all, err)]
+async fn add_chat_participants_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(String, String)>,
+  state: Data<AppState>,
+  payload: Json<AddChatParticipantsParams>,
+) -> actix_web::Result<JsonAppResponse<()>> {
+  let (workspace_id, chat_id) = path.into_inner();
+  let (uid, role) =
+    enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Write)
+      .await?;
+  add_chat_participants(
+    &state.pg_pool,
+    uid,
+    role,
+    &workspace_id,
+    &chat_id,
+    payload.into_inner(),
+  )
+  .await?;
+  Ok(AppResponse::Ok().into())
+}
+
+#[instrument(level = "debug", skip_all, err)]
+async fn remove_chat_participants_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(String, String)>,
+  state: Data<AppState>,
+  payload: Json<RemoveChatParticipantsParams>,
+) -> actix_web::Result<JsonAppResponse<()>> {
+  let (workspace_id, chat_id) = path.into_inner();
+  // Leaving a chat only requires read access.
+  let (uid, role) =
+    enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Read).await?;
+  remove_chat_participants(
+    &state.pg_pool,
+    uid,
+    role,
+    &workspace_id,
+    &chat_id,
+    payload.into_inner(),
+  )
+  .await?;
+  Ok(AppResponse::Ok().into())
+}
+
 #[instrument(level = "debug", skip_all, err)]
 async fn create_chat_context_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(String, String)>,
   state: Data<AppState>,
   payload: Json<CreateChatContext>,
 ) -> actix_web::Result<JsonAppResponse<()>> {
+  let (workspace_id, chat_id) = path.into_inner();
+  enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Write).await?;
   let params = payload.into_inner();
   state
     .ai_client
@@ -129,22 +325,36 @@ async fn create_chat_context_handler(
 }
 
 async fn update_question_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(String, String)>,
   state: Data<AppState>,
   payload: Json<UpdateChatMessageContentParams>,
   req: HttpRequest,
 ) -> actix_web::Result<JsonAppResponse<()>> {
+  let (workspace_id, _chat_id) = path.into_inner();
   let params = payload.into_inner();
   let ai_model = ai_model_from_header(&req);
-  update_chat_message(&state.pg_pool, params, state.ai_client.clone(), ai_model).await?;
+  let (uid, _) = enforce_chat_action_for_user(
+    &state,
+    &user_uuid,
+    &workspace_id,
+    &params.chat_id,
+    Action::Write,
+  )
+  .await?;
+  let tool_context = chat_tool_context(&state, uid, &workspace_id)?;
+  update_chat_message(&state.pg_pool, uid, params, &tool_context, ai_model).await?;
   Ok(AppResponse::Ok().into())
 }
 
 async fn get_related_message_handler(
+  user_uuid: UserUuid,
   path: web::Path<(String, String, i64)>,
   state: Data<AppState>,
   req: HttpRequest,
 ) -> actix_web::Result<JsonAppResponse<RepeatedRelatedQuestion>> {
-  let (_workspace_id, chat_id, message_id) = path.into_inner();
+  let (workspace_id, chat_id, message_id) = path.into_inner();
+  enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Read).await?;
   let ai_model = ai_model_from_header(&req);
   let resp = state
     .ai_client
@@ -161,7 +371,10 @@ async fn create_question_handler(
   payload: Json<CreateChatMessageParams>,
   uuid: UserUuid,
 ) -> actix_web::Result<JsonAppResponse<ChatMessage>> {
-  let (_workspace_id, chat_id) = path.into_inner();
+  let (workspace_id, chat_id) = path.into_inner();
+  let (uid, _) =
+    enforce_chat_action_for_user(&state, &uuid, &workspace_id, &chat_id, Action::Write).await?;
+  enforce_workspace_permission(&state, uid, &workspace_id, AFWorkspacePermission::UseAI).await?;
   let params = payload.into_inner();
 
   // When create a question, we will extract the metadata from the question content.
@@ -184,7 +397,6 @@ async fn create_question_handler(
       .map_err(AppError::from)?;
   }
 
-  let uid = state.user_cache.get_user_uid(&uuid).await?;
   let resp = create_chat_message(&state.pg_pool, uid, chat_id, params).await?;
   Ok(AppResponse::Ok().with_data(resp).into())
 }
@@ -200,6 +412,7 @@ async fn create_question_handler_v2(
 }
 
 async fn save_answer_handler(
+  user_uuid: UserUuid,
   path: web::Path<(String, String)>,
   payload: Json<CreateAnswerMessageParams>,
   state: Data<AppState>,
@@ -207,7 +420,8 @@ async fn save_answer_handler(
   let payload = payload.into_inner();
   payload.validate().map_err(AppError::from)?;
 
-  let (_workspace_id, chat_id) = path.into_inner();
+  let (workspace_id, chat_id) = path.into_inner();
+  enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Write).await?;
   let message = database::chat::chat_ops::insert_answer_message(
     &state.pg_pool,
     ChatAuthor::ai(),
@@ -221,15 +435,21 @@ async fn save_answer_handler(
   Ok(AppResponse::Ok().with_data(message).into())
 }
 async fn answer_handler(
+  user_uuid: UserUuid,
   path: web::Path<(String, String, i64)>,
   state: Data<AppState>,
   req: HttpRequest,
 ) -> actix_web::Result<JsonAppResponse<ChatMessage>> {
-  let (_workspace_id, chat_id, message_id) = path.into_inner();
+  let (workspace_id, chat_id, message_id) = path.into_inner();
+  let (uid, _) =
+    enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Write)
+      .await?;
+  enforce_workspace_permission(&state, uid, &workspace_id, AFWorkspacePermission::UseAI).await?;
   let ai_model = ai_model_from_header(&req);
+  let tool_context = chat_tool_context(&state, uid, &workspace_id)?;
   let message = generate_chat_message_answer(
     &state.pg_pool,
-    state.ai_client.clone(),
+    &tool_context,
     message_id,
     &chat_id,
     ai_model,
@@ -240,11 +460,16 @@ async fn answer_handler(
 
 #[instrument(level = "debug", skip_all, err)]
 async fn answer_stream_handler(
+  user_uuid: UserUuid,
   path: web::Path<(String, String, i64)>,
   state: Data<AppState>,
   req: HttpRequest,
 ) -> actix_web::Result<HttpResponse> {
-  let (_workspace_id, chat_id, question_id) = path.into_inner();
+  let (workspace_id, chat_id, question_id) = path.into_inner();
+  let (uid, _) =
+    enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Write)
+      .await?;
+  enforce_workspace_permission(&state, uid, &workspace_id, AFWorkspacePermission::UseAI).await?;
   let (content, metadata) =
     chat::chat_ops::select_chat_message_content(&state.pg_pool, question_id).await?;
   let rag_ids = chat::chat_ops::select_chat_rag_ids(&state.pg_pool, &chat_id).await?;
@@ -274,15 +499,21 @@ async fn answer_stream_handler(
 
 #[instrument(level = "debug", skip_all, err)]
 async fn answer_stream_v2_handler(
+  user_uuid: UserUuid,
   path: web::Path<(String, String, i64)>,
   state: Data<AppState>,
   req: HttpRequest,
 ) -> actix_web::Result<HttpResponse> {
-  let (_workspace_id, chat_id, question_id) = path.into_inner();
+  let (workspace_id, chat_id, question_id) = path.into_inner();
+  let (uid, _) =
+    enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Write)
+      .await?;
+  enforce_workspace_permission(&state, uid, &workspace_id, AFWorkspacePermission::UseAI).await?;
   let (content, metadata) =
     chat::chat_ops::select_chat_message_content(&state.pg_pool, question_id).await?;
   let rag_ids = chat::chat_ops::select_chat_rag_ids(&state.pg_pool, &chat_id).await?;
   let ai_model = ai_model_from_header(&req);
+  let tool_context = chat_tool_context(&state, uid, &workspace_id)?;
 
   trace!(
     "[Chat] stream answer for chat: {}, question: {}, rag_ids: {:?}",
@@ -290,38 +521,25 @@ async fn answer_stream_v2_handler(
     content,
     rag_ids
   );
-  match state
-    .ai_client
-    .stream_question_v2(
-      &chat_id,
-      question_id,
-      &content,
-      Some(metadata),
-      rag_ids,
-      &ai_model,
-    )
-    .await
-  {
-    Ok(answer_stream) => {
-      let new_answer_stream = answer_stream.map_err(AppError::from);
-      Ok(
-        HttpResponse::Ok()
-          .content_type("text/event-stream")
-          .streaming(new_answer_stream),
-      )
-    },
-    Err(err) => Ok(
-      HttpResponse::Ok()
-        .content_type("text/event-stream")
-        .streaming(stream::once(async move {
-          Err(AppError::AIServiceUnavailable(err.to_string()))
-        })),
-    ),
-  }
+  let answer_stream = stream_answer_with_tools(
+    tool_context,
+    chat_id,
+    question_id,
+    content,
+    Some(metadata),
+    rag_ids,
+    ai_model,
+  );
+  Ok(
+    HttpResponse::Ok()
+      .content_type("text/event-stream")
+      .streaming(answer_stream),
+  )
 }
 
 #[instrument(level = "debug", skip_all, err)]
 async fn get_chat_message_handler(
+  user_uuid: UserUuid,
   path: web::Path<(String, String)>,
   query: web::Query<HashMap<String, String>>,
   state: Data<AppState>,
@@ -332,6 +550,7 @@ async fn get_chat_message_handler(
       .get("limit")
       .and_then(|s| s.parse::<u64>().ok())
       .unwrap_or(10),
+    branch_id: query.get("branch_id").and_then(|s| s.parse::<i64>().ok()),
   };
   if let Some(value) = query.get("offset").and_then(|s| s.parse::<u64>().ok()) {
     params.cursor = MessageCursor::Offset(value);
@@ -344,33 +563,137 @@ async fn get_chat_message_handler(
   }
 
   trace!("get chat messages: {:?}", params);
-  let (_workspace_id, chat_id) = path.into_inner();
+  let (workspace_id, chat_id) = path.into_inner();
+  enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Read).await?;
   let messages = get_chat_messages(&state.pg_pool, params, &chat_id).await?;
   Ok(AppResponse::Ok().with_data(messages).into())
 }
 
 #[instrument(level = "debug", skip_all, err)]
 async fn get_chat_settings_handler(
+  user_uuid: UserUuid,
   path: web::Path<(String, String)>,
   state: Data<AppState>,
 ) -> actix_web::Result<JsonAppResponse<ChatSettings>> {
-  let (_, chat_id) = path.into_inner();
+  let (workspace_id, chat_id) = path.into_inner();
+  enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Read).await?;
   let chat_id_uuid = Uuid::parse_str(&chat_id).map_err(AppError::from)?;
   let settings = chat::chat_ops::select_chat_settings(&state.pg_pool, &chat_id_uuid).await?;
   Ok(AppResponse::Ok().with_data(settings).into())
 }
 
 async fn update_chat_settings_handler(
+  user_uuid: UserUuid,
   path: web::Path<(String, String)>,
   state: Data<AppState>,
   payload: Json<UpdateChatParams>,
 ) -> actix_web::Result<JsonAppResponse<()>> {
-  let (_workspace_id, chat_id) = path.into_inner();
+  let (workspace_id, chat_id) = path.into_inner();
+  enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Write).await?;
   let chat_id_uuid = Uuid::parse_str(&chat_id).map_err(AppError::from)?;
   chat::chat_ops::update_chat_settings(&state.pg_pool, &chat_id_uuid, payload.into_inner()).await?;
   Ok(AppResponse::Ok().into())
 }
 
+#[instrument(level = "debug", skip_all, err)]
+async fn get_chat_branches_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(String, String)>,
+  state: Data<AppState>,
+) -> actix_web::Result<JsonAppResponse<RepeatedChatBranch>> {
+  let (workspace_id, chat_id) = path.into_inner();
+  enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Read).await?;
+  let branches = chat::chat_ops::select_chat_branches(&state.pg_pool, &chat_id).await?;
+  Ok(AppResponse::Ok().with_data(branches).into())
+}
+
+#[instrument(level = "debug", skip_all, err)]
+async fn update_active_chat_branch_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(String, String)>,
+  state: Data<AppState>,
+  payload: Json<UpdateActiveChatBranchParams>,
+) -> actix_web::Result<JsonAppResponse<()>> {
+  let (workspace_id, chat_id) = path.into_inner();
+  enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Write).await?;
+  chat::chat_ops::update_chat_active_branch(&state.pg_pool, &chat_id, payload.branch_id).await?;
+  Ok(AppResponse::Ok().into())
+}
+
+#[instrument(level = "debug", skip_all, err)]
+async fn get_answer_versions_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(String, String, i64)>,
+  state: Data<AppState>,
+) -> actix_web::Result<JsonAppResponse<RepeatedAnswerVersion>> {
+  let (workspace_id, chat_id, question_message_id) = path.into_inner();
+  enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Read).await?;
+  let versions = get_answer_versions(&state.pg_pool, &chat_id, question_message_id).await?;
+  Ok(AppResponse::Ok().with_data(versions).into())
+}
+
+#[instrument(level = "debug", skip_all, err)]
+async fn select_answer_version_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(String, String, i64)>,
+  state: Data<AppState>,
+  payload: Json<SelectAnswerVersionParams>,
+) -> actix_web::Result<JsonAppResponse<()>> {
+  let (workspace_id, chat_id, question_message_id) = path.into_inner();
+  enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Write).await?;
+  update_selected_answer_version(
+    &state.pg_pool,
+    &chat_id,
+    question_message_id,
+    payload.answer_message_id,
+  )
+  .await?;
+  Ok(AppResponse::Ok().into())
+}
+
+#[instrument(level = "debug", skip_all, err)]
+async fn search_chat_messages_handler(
+  user_uuid: UserUuid,
+  path: web::Path<String>,
+  query: web::Query<SearchChatMessageParams>,
+  state: Data<AppState>,
+) -> actix_web::Result<JsonAppResponse<RepeatedChatMessageSearchResult>> {
+  let workspace_id = path.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_action(&uid, &workspace_id, Action::Read)
+    .await?;
+  let result = search_chat_messages(&state.pg_pool, &workspace_id, uid, query.into_inner()).await?;
+  Ok(AppResponse::Ok().with_data(result).into())
+}
+
+#[instrument(level = "debug", skip_all, err)]
+async fn export_chat_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(String, String)>,
+  query: web::Query<ExportChatParams>,
+  state: Data<AppState>,
+) -> actix_web::Result<HttpResponse> {
+  let (workspace_id, chat_id) = path.into_inner();
+  let (uid, _) =
+    enforce_chat_action_for_user(&state, &user_uuid, &workspace_id, &chat_id, Action::Read).await?;
+  enforce_workspace_permission(&state, uid, &workspace_id, AFWorkspacePermission::Export).await?;
+  let export = export_chat(&state.pg_pool, &workspace_id, &chat_id).await?;
+  match query.format {
+    ChatExportFormat::Json => Ok(HttpResponse::Ok().json(AppResponse::Ok().with_data(export))),
+    ChatExportFormat::Markdown => Ok(
+      HttpResponse::Ok()
+        .content_type("text/markdown; charset=utf-8")
+        .insert_header(ContentDisposition {
+          disposition: DispositionType::Attachment,
+          parameters: vec![DispositionParam::Filename(format!("chat-{}.md", chat_id))],
+        })
+        .body(chat_export_to_markdown(&export)),
+    ),
+  }
+}
+
 #[pin_project]
 pub struct FinalAnswerStream<S, F> {
   #[pin]
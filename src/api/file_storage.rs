@@ -15,17 +15,21 @@ use authentication::jwt::UserUuid;
 use chrono::DateTime;
 use database::file::BlobKey;
 use database::resource_usage::{get_all_workspace_blob_metadata, get_workspace_usage_size};
+use database_entity::dto::AFWorkspacePermission;
 use database_entity::file_dto::{
   CompleteUploadRequest, CreateUploadRequest, CreateUploadResponse, UploadPartData,
   UploadPartResponse,
 };
 
+use crate::biz::attachment::ops::queue_attachment_indexing;
+use crate::biz::audit_log::ops::{record_audit_log, AuditSource};
 use crate::biz::data_import::LimitedPayload;
 use crate::state::AppState;
 use anyhow::anyhow;
 use aws_sdk_s3::primitives::ByteStream;
 use collab_importer::util::FileId;
 use serde::Deserialize;
+use shared_entity::dto::audit_log_dto::AuditAction;
 use shared_entity::dto::file_dto::PutFileResponse;
 use shared_entity::dto::workspace_dto::{BlobMetadata, RepeatedBlobMetaData, WorkspaceSpaceUsage};
 use shared_entity::response::{AppResponse, AppResponseError, JsonAppResponse};
@@ -211,10 +215,30 @@ async fn complete_upload_handler(
   };
   state
     .bucket_storage
-    .complete_upload(key, req)
+    .complete_upload(key.clone(), req)
     .await
     .map_err(AppResponseError::from)?;
 
+  match state
+    .bucket_storage
+    .get_blob_metadata(&workspace_id, &key.meta_key())
+    .await
+  {
+    Ok(metadata) => {
+      if let Err(err) = queue_attachment_indexing(
+        &state.redis_connection_manager,
+        &key,
+        &key.parent_dir,
+        &metadata.file_type,
+      )
+      .await
+      {
+        error!("Failed to queue attachment indexing: {:?}", err);
+      }
+    },
+    Err(err) => error!("Failed to get metadata of uploaded file: {:?}", err),
+  }
+
   Ok(AppResponse::Ok().into())
 }
 
@@ -295,11 +319,12 @@ async fn put_blob_handler(
   Ok(AppResponse::Ok().into())
 }
 
-#[instrument(level = "debug", skip(state), err)]
+#[instrument(level = "debug", skip(state, req), err)]
 async fn delete_blob_handler(
   user_uuid: UserUuid,
   state: Data<AppState>,
   path: web::Path<BlobPathV0>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<()>> {
   let path = path.into_inner();
   let workspace_id = path.workspace_id;
@@ -308,11 +333,22 @@ async fn delete_blob_handler(
     .workspace_access_control
     .enforce_action(&uid, &workspace_id.to_string(), Action::Write)
     .await?;
+  let object_key = path.object_key();
   state
     .bucket_storage
     .delete_blob(path)
     .await
     .map_err(AppResponseError::from)?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::BlobDeleted,
+    Some(&object_key),
+    serde_json::json!({}),
+  )
+  .await;
 
   Ok(AppResponse::Ok().into())
 }
@@ -327,11 +363,12 @@ async fn get_blob_v1_handler(
   get_blob_by_object_key(state, &path, req).await
 }
 
-#[instrument(level = "debug", skip(state), err)]
+#[instrument(level = "debug", skip(state, req), err)]
 async fn delete_blob_v1_handler(
   user_uuid: UserUuid,
   state: Data<AppState>,
   path: web::Path<BlobPathV1>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<()>> {
   let path = path.into_inner();
   let workspace_id = path.workspace_id;
@@ -340,11 +377,22 @@ async fn delete_blob_v1_handler(
     .workspace_access_control
     .enforce_action(&uid, &workspace_id.to_string(), Action::Write)
     .await?;
+  let object_key = path.object_key();
   state
     .bucket_storage
     .delete_blob(path)
     .await
     .map_err(AppResponseError::from)?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::BlobDeleted,
+    Some(&object_key),
+    serde_json::json!({}),
+  )
+  .await;
 
   Ok(AppResponse::Ok().into())
 }
@@ -464,9 +512,19 @@ async fn get_blob_metadata_v1_handler(
 
 #[instrument(level = "debug", skip(state), err)]
 async fn get_workspace_usage_handler(
+  user_uuid: UserUuid,
   state: Data<AppState>,
   workspace_id: web::Path<Uuid>,
 ) -> Result<JsonAppResponse<WorkspaceSpaceUsage>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_permission(
+      &uid,
+      &workspace_id.to_string(),
+      AFWorkspacePermission::ManageBilling,
+    )
+    .await?;
   let current = get_workspace_usage_size(&state.pg_pool, &workspace_id)
     .await
     .map_err(AppResponseError::from)?;
@@ -500,6 +558,10 @@ async fn get_all_workspace_blob_metadata_handler(
       .into(),
   )
 }
+
+/// The maximum size of a blob uploaded in a single request.
+const MAX_PUT_BLOB_SIZE: usize = 200 * 1024 * 1024;
+
 fn payload_to_async_read(payload: Payload) -> Pin<Box<dyn AsyncRead>> {
   let mapped =
     payload.map(|chunk| chunk.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e)));
@@ -513,7 +575,7 @@ async fn put_blob_handler_v1(
   state: Data<AppState>,
   path: web::Path<BlobPathV2>,
   content_type: web::Header<ContentType>,
-  content_length: web::Header<ContentLength>,
+  content_length: Option<web::Header<ContentLength>>,
   payload: Payload,
 ) -> Result<JsonAppResponse<PutFileResponse>> {
   let path = path.into_inner();
@@ -523,29 +585,54 @@ async fn put_blob_handler_v1(
     .enforce_action(&uid, &path.workspace_id.to_string(), Action::Write)
     .await?;
 
-  let content_length = content_length.into_inner().into_inner();
   let content_type = content_type.into_inner().to_string();
+  let content = match content_length {
+    Some(content_length) => {
+      let content_length = content_length.into_inner().into_inner();
+      if content_length > MAX_PUT_BLOB_SIZE {
+        return Err(
+          AppError::PayloadTooLarge(format!(
+            "Content length is {}, but the maximum size is {}",
+            content_length, MAX_PUT_BLOB_SIZE
+          ))
+          .into(),
+        );
+      }
 
-  let mut content = Vec::with_capacity(content_length);
-  if content.try_reserve_exact(content_length).is_err() {
-    return Err(
-      AppError::Internal(anyhow!(
-        "Can not alloc mem for blob content size:{}",
-        content_length
-      ))
-      .into(),
-    );
-  }
-  content.resize(content_length, 0);
-
-  let mut limited_payload = LimitedPayload::new(payload, content_length);
-  let mut offset = 0;
-  while let Some(bytes) = limited_payload.next().await {
-    let bytes = bytes?;
-    let len = bytes.len();
-    content[offset..offset + len].copy_from_slice(&bytes);
-    offset += len;
-  }
+      let mut content = Vec::with_capacity(content_length);
+      if content.try_reserve_exact(content_length).is_err() {
+        return Err(
+          AppError::Internal(anyhow!(
+            "Can not alloc mem for blob content size:{}",
+            content_length
+          ))
+          .into(),
+        );
+      }
+      content.resize(content_length, 0);
+
+      let mut limited_payload = LimitedPayload::new(payload, content_length);
+      let mut offset = 0;
+      while let Some(bytes) = limited_payload.next().await {
+        let bytes = bytes?;
+        let len = bytes.len();
+        content[offset..offset + len].copy_from_slice(&bytes);
+        offset += len;
+      }
+      content
+    },
+    None => {
+      // Without a Content-Length the size is only known once the body is read, so stop
+      // reading as soon as it goes over the limit.
+      let mut content = Vec::new();
+      let mut limited_payload = LimitedPayload::with_max(payload, MAX_PUT_BLOB_SIZE);
+      while let Some(bytes) = limited_payload.next().await {
+        content.extend_from_slice(&bytes?);
+      }
+      content
+    },
+  };
+  let content_length = content.len();
 
   let file_id = FileId::from_bytes(&content, "".to_string());
   let resp_data = PutFileResponse {
@@ -560,16 +647,28 @@ async fn put_blob_handler_v1(
   );
 
   let file_stream = ByteStream::from(content);
+  let key = BlobPathV1::from((path, file_id));
   state
     .bucket_storage
     .put_blob_with_content_type(
-      BlobPathV1::from((path, file_id)),
+      key.clone(),
       file_stream,
-      content_type,
+      content_type.clone(),
       content_length,
     )
     .await
     .map_err(AppResponseError::from)?;
+
+  if let Err(err) = queue_attachment_indexing(
+    &state.redis_connection_manager,
+    &key,
+    &key.parent_dir,
+    &content_type,
+  )
+  .await
+  {
+    error!("Failed to queue attachment indexing: {:?}", err);
+  }
   Ok(AppResponse::Ok().with_data(resp_data).into())
 }
 
@@ -599,7 +698,7 @@ impl BlobKey for BlobPathV0 {
 }
 
 /// Use [BlobPathV1] when put/get object by multiple upload parts
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct BlobPathV1 {
   pub workspace_id: Uuid,
   pub parent_dir: String,
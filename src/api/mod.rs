@@ -5,8 +5,12 @@ pub mod data_import;
 pub mod file_storage;
 pub mod history;
 pub mod metrics;
+pub mod notification;
+pub mod oauth;
+pub mod scim;
 pub mod search;
 pub mod server_info;
+pub mod share_link;
 pub mod template;
 pub mod user;
 pub mod util;
@@ -0,0 +1,142 @@
+use actix_web::{
+  web::{self, Data, Json},
+  Result, Scope,
+};
+use authentication::jwt::UserUuid;
+use shared_entity::{
+  dto::notification_dto::{
+    ActivityDigestSetting, ActivityDigestSettings, MarkNotificationsReadParams, NotificationMute,
+    NotificationSettings, QueryNotifications, RepeatedNotification, RepeatedNotificationMute,
+    ResetActivityDigestSettingParams, UnmuteNotificationParams,
+  },
+  response::{AppResponse, JsonAppResponse},
+};
+
+use crate::{
+  biz::notification::ops::{
+    get_activity_digest_settings, get_notification_settings, list_notification_mutes,
+    list_notifications, mark_notifications_read, mute_notifications, reset_activity_digest_setting,
+    unmute_notifications, update_activity_digest_setting, update_notification_settings,
+  },
+  state::AppState,
+};
+
+pub fn notification_scope() -> Scope {
+  web::scope("/api/notification")
+    .service(web::resource("").route(web::get().to(list_notifications_handler)))
+    .service(web::resource("/read").route(web::post().to(mark_notifications_read_handler)))
+    .service(
+      web::resource("/mute")
+        .route(web::get().to(list_notification_mutes_handler))
+        .route(web::put().to(mute_notifications_handler))
+        .route(web::delete().to(unmute_notifications_handler)),
+    )
+    .service(
+      web::resource("/settings")
+        .route(web::get().to(get_notification_settings_handler))
+        .route(web::put().to(update_notification_settings_handler)),
+    )
+    .service(
+      web::resource("/activity-digest")
+        .route(web::get().to(get_activity_digest_settings_handler))
+        .route(web::put().to(update_activity_digest_setting_handler))
+        .route(web::delete().to(reset_activity_digest_setting_handler)),
+    )
+}
+
+async fn list_notifications_handler(
+  uuid: UserUuid,
+  query: web::Query<QueryNotifications>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<RepeatedNotification>> {
+  let uid = state.user_cache.get_user_uid(&uuid).await?;
+  let notifications = list_notifications(&state.pg_pool, uid, query.into_inner()).await?;
+  Ok(Json(AppResponse::Ok().with_data(notifications)))
+}
+
+async fn mark_notifications_read_handler(
+  uuid: UserUuid,
+  params: Json<MarkNotificationsReadParams>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<()>> {
+  let uid = state.user_cache.get_user_uid(&uuid).await?;
+  mark_notifications_read(&state.pg_pool, uid, params.into_inner()).await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn list_notification_mutes_handler(
+  uuid: UserUuid,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<RepeatedNotificationMute>> {
+  let uid = state.user_cache.get_user_uid(&uuid).await?;
+  let mutes = list_notification_mutes(&state.pg_pool, uid).await?;
+  Ok(Json(AppResponse::Ok().with_data(mutes)))
+}
+
+async fn mute_notifications_handler(
+  uuid: UserUuid,
+  mute: Json<NotificationMute>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<()>> {
+  let uid = state.user_cache.get_user_uid(&uuid).await?;
+  mute_notifications(&state.pg_pool, uid, mute.into_inner()).await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn unmute_notifications_handler(
+  uuid: UserUuid,
+  params: Json<UnmuteNotificationParams>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<()>> {
+  let uid = state.user_cache.get_user_uid(&uuid).await?;
+  unmute_notifications(&state.pg_pool, uid, params.into_inner()).await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn get_notification_settings_handler(
+  uuid: UserUuid,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<NotificationSettings>> {
+  let uid = state.user_cache.get_user_uid(&uuid).await?;
+  let settings = get_notification_settings(&state.pg_pool, uid).await?;
+  Ok(Json(AppResponse::Ok().with_data(settings)))
+}
+
+async fn update_notification_settings_handler(
+  uuid: UserUuid,
+  settings: Json<NotificationSettings>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<()>> {
+  let uid = state.user_cache.get_user_uid(&uuid).await?;
+  update_notification_settings(&state.pg_pool, uid, settings.into_inner()).await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn get_activity_digest_settings_handler(
+  uuid: UserUuid,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<ActivityDigestSettings>> {
+  let uid = state.user_cache.get_user_uid(&uuid).await?;
+  let settings = get_activity_digest_settings(&state.pg_pool, uid).await?;
+  Ok(Json(AppResponse::Ok().with_data(settings)))
+}
+
+async fn update_activity_digest_setting_handler(
+  uuid: UserUuid,
+  setting: Json<ActivityDigestSetting>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<()>> {
+  let uid = state.user_cache.get_user_uid(&uuid).await?;
+  update_activity_digest_setting(&state.pg_pool, uid, setting.into_inner()).await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn reset_activity_digest_setting_handler(
+  uuid: UserUuid,
+  params: Json<ResetActivityDigestSettingParams>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<()>> {
+  let uid = state.user_cache.get_user_uid(&uuid).await?;
+  reset_activity_digest_setting(&state.pg_pool, uid, params.into_inner()).await?;
+  Ok(Json(AppResponse::Ok()))
+}
@@ -0,0 +1,120 @@
+use actix_web::web::{Data, Form, Json};
+use actix_web::{web, HttpResponse, Result, Scope};
+use app_error::AppError;
+use authentication::jwt::UserUuid;
+use shared_entity::dto::oauth_dto::{
+  CreateOAuthClientParams, CreatedOAuthClient, OAuthAuthorizeParams, OAuthAuthorizeResponse,
+  OAuthClient, OAuthErrorResponse, OAuthTokenParams, RepeatedOAuthClient,
+};
+use shared_entity::response::{AppResponse, JsonAppResponse};
+use validator::Validate;
+
+use crate::biz::oauth::ops::{
+  authorize_oauth_client, create_oauth_client, exchange_oauth_authorization_code, get_oauth_client,
+  list_oauth_clients, remove_oauth_client,
+};
+use crate::state::AppState;
+
+pub fn oauth_scope() -> Scope {
+  web::scope("/api/oauth")
+    .service(
+      web::resource("/client")
+        .route(web::get().to(list_oauth_clients_handler))
+        .route(web::post().to(create_oauth_client_handler)),
+    )
+    .service(
+      web::resource("/client/{client_id}")
+        .route(web::get().to(get_oauth_client_handler))
+        .route(web::delete().to(delete_oauth_client_handler)),
+    )
+    .service(web::resource("/authorize").route(web::post().to(authorize_handler)))
+    .service(web::resource("/token").route(web::post().to(token_handler)))
+}
+
+async fn create_oauth_client_handler(
+  user_uuid: UserUuid,
+  payload: Json<CreateOAuthClientParams>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<CreatedOAuthClient>> {
+  let params = payload.into_inner();
+  params.validate().map_err(AppError::from)?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let client = create_oauth_client(&state.pg_pool, uid, params).await?;
+  Ok(AppResponse::Ok().with_data(client).into())
+}
+
+async fn list_oauth_clients_handler(
+  user_uuid: UserUuid,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<RepeatedOAuthClient>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let items = list_oauth_clients(&state.pg_pool, uid).await?;
+  Ok(
+    AppResponse::Ok()
+      .with_data(RepeatedOAuthClient { items })
+      .into(),
+  )
+}
+
+/// Used by the consent page to show which application requests access.
+async fn get_oauth_client_handler(
+  _user_uuid: UserUuid,
+  client_id: web::Path<String>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<OAuthClient>> {
+  let client = get_oauth_client(&state.pg_pool, &client_id).await?;
+  Ok(AppResponse::Ok().with_data(client).into())
+}
+
+async fn delete_oauth_client_handler(
+  user_uuid: UserUuid,
+  client_id: web::Path<String>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<()>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  remove_oauth_client(&state.pg_pool, uid, &client_id).await?;
+  Ok(AppResponse::Ok().into())
+}
+
+async fn authorize_handler(
+  user_uuid: UserUuid,
+  payload: Json<OAuthAuthorizeParams>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<OAuthAuthorizeResponse>> {
+  let params = payload.into_inner();
+  params.validate().map_err(AppError::from)?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let resp = authorize_oauth_client(
+    &state.pg_pool,
+    state.workspace_access_control.clone(),
+    uid,
+    params,
+  )
+  .await?;
+  Ok(AppResponse::Ok().with_data(resp).into())
+}
+
+/// The token endpoint is called by the third-party application, not by an AppFlowy client, so it
+/// follows RFC 6749 instead of returning an [AppResponse]: the request is form encoded and the
+/// response is the bare token or error.
+async fn token_handler(form: Form<OAuthTokenParams>, state: Data<AppState>) -> HttpResponse {
+  match exchange_oauth_authorization_code(
+    &state.pg_pool,
+    state.workspace_access_control.clone(),
+    form.into_inner(),
+  )
+  .await
+  {
+    Ok(token) => HttpResponse::Ok()
+      .insert_header(("Cache-Control", "no-store"))
+      .json(token),
+    Err(err) => {
+      let mut resp = match err.error {
+        "invalid_client" => HttpResponse::Unauthorized(),
+        "server_error" => HttpResponse::InternalServerError(),
+        _ => HttpResponse::BadRequest(),
+      };
+      resp.json(OAuthErrorResponse::from(err))
+    },
+  }
+}
@@ -0,0 +1,409 @@
+use actix_web::http::StatusCode;
+use actix_web::web::{Data, Json, Query};
+use actix_web::{web, HttpRequest, HttpResponse, Scope};
+use app_error::ErrorCode;
+use appflowy_collaborate::webhook::queue_webhook_event;
+use authentication::jwt::UserUuid;
+use database_entity::dto::AFRole;
+use serde::Serialize;
+use shared_entity::dto::audit_log_dto::AuditAction;
+use shared_entity::dto::scim_dto::{
+  ScimError, ScimGroupParams, ScimListQuery, ScimPatchParams, ScimUserParams, SCIM_CONTENT_TYPE,
+  SCIM_ERROR_SCHEMA, SCIM_SERVICE_PROVIDER_CONFIG_SCHEMA,
+};
+use shared_entity::dto::webhook_dto::WebhookEvent;
+use shared_entity::response::AppResponseError;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::biz::audit_log::ops::{record_audit_log, AuditSource};
+use crate::biz::scim::ops::{
+  create_scim_group, create_scim_user, get_scim_group, get_scim_user, list_scim_groups,
+  list_scim_users, patch_scim_group, patch_scim_user, remove_scim_group, remove_scim_user,
+  replace_scim_group, replace_scim_user, ScimGroupUpdate, ScimMembershipChange, ScimUserUpdate,
+};
+use crate::state::AppState;
+
+/// SCIM 2.0 provisioning of the members and groups of a workspace, RFC 7644. The identity provider
+/// authenticates with a workspace API token that has the admin scope, and the requests and
+/// responses follow the SCIM protocol instead of returning an [shared_entity::response::AppResponse].
+pub fn scim_scope() -> Scope {
+  web::scope("/api/scim/v2/{workspace_id}")
+    .service(
+      web::resource("/ServiceProviderConfig")
+        .route(web::get().to(get_service_provider_config_handler)),
+    )
+    .service(
+      web::resource("/Users")
+        .route(web::get().to(list_scim_users_handler))
+        .route(web::post().to(create_scim_user_handler)),
+    )
+    .service(
+      web::resource("/Users/{id}")
+        .route(web::get().to(get_scim_user_handler))
+        .route(web::put().to(replace_scim_user_handler))
+        .route(web::patch().to(patch_scim_user_handler))
+        .route(web::delete().to(delete_scim_user_handler)),
+    )
+    .service(
+      web::resource("/Groups")
+        .route(web::get().to(list_scim_groups_handler))
+        .route(web::post().to(create_scim_group_handler)),
+    )
+    .service(
+      web::resource("/Groups/{id}")
+        .route(web::get().to(get_scim_group_handler))
+        .route(web::put().to(replace_scim_group_handler))
+        .route(web::patch().to(patch_scim_group_handler))
+        .route(web::delete().to(delete_scim_group_handler)),
+    )
+}
+
+/// Only the owners of the workspace can provision its members.
+async fn enforce_scim_owner(
+  state: &AppState,
+  user_uuid: &UserUuid,
+  workspace_id: &Uuid,
+) -> Result<i64, AppResponseError> {
+  let uid = state.user_cache.get_user_uid(user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  Ok(uid)
+}
+
+fn scim_response<T: Serialize>(
+  status: StatusCode,
+  result: Result<T, AppResponseError>,
+) -> HttpResponse {
+  match result {
+    Ok(resource) => HttpResponse::build(status)
+      .content_type(SCIM_CONTENT_TYPE)
+      .json(resource),
+    Err(err) => scim_error_response(err),
+  }
+}
+
+fn scim_no_content(result: Result<(), AppResponseError>) -> HttpResponse {
+  match result {
+    Ok(()) => HttpResponse::NoContent().finish(),
+    Err(err) => scim_error_response(err),
+  }
+}
+
+fn scim_error_response(err: AppResponseError) -> HttpResponse {
+  let (status, scim_type) = match err.code {
+    ErrorCode::RecordNotFound => (StatusCode::NOT_FOUND, None),
+    ErrorCode::RecordAlreadyExists => (StatusCode::CONFLICT, Some("uniqueness")),
+    ErrorCode::InvalidRequest => (StatusCode::BAD_REQUEST, Some("invalidValue")),
+    ErrorCode::NotEnoughPermissions => (StatusCode::FORBIDDEN, None),
+    ErrorCode::UserUnAuthorized | ErrorCode::NotLoggedIn => (StatusCode::UNAUTHORIZED, None),
+    _ => (StatusCode::INTERNAL_SERVER_ERROR, None),
+  };
+  if status == StatusCode::INTERNAL_SERVER_ERROR {
+    error!("SCIM request failed: {}", err);
+  }
+  HttpResponse::build(status)
+    .content_type(SCIM_CONTENT_TYPE)
+    .json(ScimError {
+      schemas: vec![SCIM_ERROR_SCHEMA.to_string()],
+      status: status.as_u16().to_string(),
+      scim_type: scim_type.map(str::to_string),
+      detail: err.message.to_string(),
+    })
+}
+
+/// Members that join or leave the workspace through the provisioning are audited and notified to
+/// the webhooks like the other members. Invited users are notified when they accept.
+async fn record_membership_change(
+  state: &AppState,
+  req: &HttpRequest,
+  workspace_id: &Uuid,
+  uid: i64,
+  update: &ScimUserUpdate,
+) {
+  let (action, event) = match update.membership_change {
+    Some(ScimMembershipChange::Provisioned) => (
+      AuditAction::MemberProvisioned,
+      Some(WebhookEvent::MemberJoined),
+    ),
+    Some(ScimMembershipChange::Invited) => (AuditAction::MemberInvited, None),
+    Some(ScimMembershipChange::Deprovisioned) => (
+      AuditAction::MemberDeprovisioned,
+      Some(WebhookEvent::MemberRemoved),
+    ),
+    None => return,
+  };
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(req),
+    Some(workspace_id),
+    Some(uid),
+    action,
+    Some(&update.user.id),
+    serde_json::json!({
+      "email": update.user.user_name,
+      "external_id": update.user.external_id,
+    }),
+  )
+  .await;
+  if let Some(event) = event {
+    queue_webhook_event(
+      &state.redis_connection_manager,
+      workspace_id,
+      event,
+      serde_json::json!({ "uid": update.user.id, "email": update.user.user_name }),
+    )
+    .await;
+  }
+}
+
+async fn record_group_members_change(
+  state: &AppState,
+  req: &HttpRequest,
+  workspace_id: &Uuid,
+  uid: i64,
+  update: &ScimGroupUpdate,
+) {
+  if !update.members_changed {
+    return;
+  }
+  let members = update
+    .group
+    .members
+    .iter()
+    .map(|member| member.value.as_str())
+    .collect::<Vec<_>>();
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(req),
+    Some(workspace_id),
+    Some(uid),
+    AuditAction::GroupMembersUpdated,
+    Some(&update.group.id),
+    serde_json::json!({ "members": members, "source": "scim" }),
+  )
+  .await;
+}
+
+async fn get_service_provider_config_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  state: Data<AppState>,
+) -> HttpResponse {
+  let result = enforce_scim_owner(&state, &user_uuid, &workspace_id)
+    .await
+    .map(|_| {
+      serde_json::json!({
+        "schemas": [SCIM_SERVICE_PROVIDER_CONFIG_SCHEMA],
+        "patch": { "supported": true },
+        "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+        "filter": { "supported": true, "maxResults": 200 },
+        "changePassword": { "supported": false },
+        "sort": { "supported": false },
+        "etag": { "supported": false },
+        "authenticationSchemes": [{
+          "type": "oauthbearertoken",
+          "name": "API token",
+          "description": "A workspace API token with the admin scope",
+        }],
+      })
+    });
+  scim_response(StatusCode::OK, result)
+}
+
+async fn list_scim_users_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  query: Query<ScimListQuery>,
+  state: Data<AppState>,
+) -> HttpResponse {
+  let result = async {
+    enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    list_scim_users(&state.pg_pool, &workspace_id, &query).await
+  }
+  .await;
+  scim_response(StatusCode::OK, result)
+}
+
+async fn get_scim_user_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  state: Data<AppState>,
+) -> HttpResponse {
+  let (workspace_id, id) = path.into_inner();
+  let result = async {
+    enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    get_scim_user(&state.pg_pool, &workspace_id, &id).await
+  }
+  .await;
+  scim_response(StatusCode::OK, result)
+}
+
+async fn create_scim_user_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  payload: Json<ScimUserParams>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> HttpResponse {
+  let result = async {
+    let uid = enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    let update = create_scim_user(&state, &user_uuid, &workspace_id, payload.into_inner()).await?;
+    record_membership_change(&state, &req, &workspace_id, uid, &update).await;
+    Ok::<_, AppResponseError>(update.user)
+  }
+  .await;
+  scim_response(StatusCode::CREATED, result)
+}
+
+async fn replace_scim_user_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  payload: Json<ScimUserParams>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> HttpResponse {
+  let (workspace_id, id) = path.into_inner();
+  let result = async {
+    let uid = enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    let update =
+      replace_scim_user(&state, &user_uuid, &workspace_id, &id, payload.into_inner()).await?;
+    record_membership_change(&state, &req, &workspace_id, uid, &update).await;
+    Ok::<_, AppResponseError>(update.user)
+  }
+  .await;
+  scim_response(StatusCode::OK, result)
+}
+
+async fn patch_scim_user_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  payload: Json<ScimPatchParams>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> HttpResponse {
+  let (workspace_id, id) = path.into_inner();
+  let result = async {
+    let uid = enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    let update =
+      patch_scim_user(&state, &user_uuid, &workspace_id, &id, payload.into_inner()).await?;
+    record_membership_change(&state, &req, &workspace_id, uid, &update).await;
+    Ok::<_, AppResponseError>(update.user)
+  }
+  .await;
+  scim_response(StatusCode::OK, result)
+}
+
+async fn delete_scim_user_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> HttpResponse {
+  let (workspace_id, id) = path.into_inner();
+  let result = async {
+    let uid = enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    let update = remove_scim_user(&state, &workspace_id, &id).await?;
+    record_membership_change(&state, &req, &workspace_id, uid, &update).await;
+    Ok::<_, AppResponseError>(())
+  }
+  .await;
+  scim_no_content(result)
+}
+
+async fn list_scim_groups_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  query: Query<ScimListQuery>,
+  state: Data<AppState>,
+) -> HttpResponse {
+  let result = async {
+    enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    list_scim_groups(&state.pg_pool, &workspace_id, &query).await
+  }
+  .await;
+  scim_response(StatusCode::OK, result)
+}
+
+async fn get_scim_group_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  state: Data<AppState>,
+) -> HttpResponse {
+  let (workspace_id, id) = path.into_inner();
+  let result = async {
+    enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    get_scim_group(&state.pg_pool, &workspace_id, &id).await
+  }
+  .await;
+  scim_response(StatusCode::OK, result)
+}
+
+async fn create_scim_group_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  payload: Json<ScimGroupParams>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> HttpResponse {
+  let result = async {
+    let uid = enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    let update = create_scim_group(&state, uid, &workspace_id, payload.into_inner()).await?;
+    record_group_members_change(&state, &req, &workspace_id, uid, &update).await;
+    Ok::<_, AppResponseError>(update.group)
+  }
+  .await;
+  scim_response(StatusCode::CREATED, result)
+}
+
+async fn replace_scim_group_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  payload: Json<ScimGroupParams>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> HttpResponse {
+  let (workspace_id, id) = path.into_inner();
+  let result = async {
+    let uid = enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    let update = replace_scim_group(&state, uid, &workspace_id, &id, payload.into_inner()).await?;
+    record_group_members_change(&state, &req, &workspace_id, uid, &update).await;
+    Ok::<_, AppResponseError>(update.group)
+  }
+  .await;
+  scim_response(StatusCode::OK, result)
+}
+
+async fn patch_scim_group_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  payload: Json<ScimPatchParams>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> HttpResponse {
+  let (workspace_id, id) = path.into_inner();
+  let result = async {
+    let uid = enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    let update = patch_scim_group(&state, uid, &workspace_id, &id, payload.into_inner()).await?;
+    record_group_members_change(&state, &req, &workspace_id, uid, &update).await;
+    Ok::<_, AppResponseError>(update.group)
+  }
+  .await;
+  scim_response(StatusCode::OK, result)
+}
+
+async fn delete_scim_group_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  state: Data<AppState>,
+) -> HttpResponse {
+  let (workspace_id, id) = path.into_inner();
+  let result = async {
+    let uid = enforce_scim_owner(&state, &user_uuid, &workspace_id).await?;
+    remove_scim_group(&state, uid, &workspace_id, &id).await
+  }
+  .await;
+  scim_no_content(result)
+}
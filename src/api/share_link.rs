@@ -0,0 +1,64 @@
+use actix_web::web::{Data, Json};
+use actix_web::{web, HttpRequest, Result, Scope};
+use authentication::jwt::UserUuid;
+use shared_entity::dto::share_link_dto::{
+  JoinShareLinkParams, ShareLinkAccess, SharedPage, SHARE_LINK_PASSWORD_HEADER,
+};
+use shared_entity::response::{AppResponse, JsonAppResponse};
+
+use crate::api::util::client_ip;
+use crate::biz::workspace::share_link::{get_shared_page, join_share_link};
+use crate::state::AppState;
+
+/// Endpoints used by the holders of a share link, who are not necessarily members of the
+/// workspace of the page. The links are managed with the pages, see the workspace scope.
+pub fn share_link_scope() -> Scope {
+  web::scope("/api/share-link")
+    .service(web::resource("/{token}").route(web::get().to(get_shared_page_handler)))
+    .service(web::resource("/{token}/join").route(web::post().to(join_share_link_handler)))
+}
+
+/// No login is required, the password of the link is sent in a header if it has one.
+async fn get_shared_page_handler(
+  path: web::Path<String>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> Result<JsonAppResponse<SharedPage>> {
+  let token = path.into_inner();
+  let password = req
+    .headers()
+    .get(SHARE_LINK_PASSWORD_HEADER)
+    .and_then(|value| value.to_str().ok())
+    .map(|value| value.to_string());
+  let page = get_shared_page(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    &state.collab_access_control_storage,
+    &token,
+    password,
+    client_ip(&req).as_deref(),
+  )
+  .await?;
+  Ok(AppResponse::Ok().with_data(page).into())
+}
+
+async fn join_share_link_handler(
+  user_uuid: UserUuid,
+  path: web::Path<String>,
+  payload: Json<JoinShareLinkParams>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> Result<JsonAppResponse<ShareLinkAccess>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let token = path.into_inner();
+  let access = join_share_link(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    uid,
+    &token,
+    payload.into_inner().password,
+    client_ip(&req).as_deref(),
+  )
+  .await?;
+  Ok(AppResponse::Ok().with_data(access).into())
+}
@@ -1,12 +1,15 @@
+use crate::biz::audit_log::ops::{record_audit_log, AuditSource};
 use crate::biz::user::user_delete::delete_user;
 use crate::biz::user::user_info::{get_profile, get_user_workspace_info, update_user};
 use crate::biz::user::user_verify::verify_token;
 use crate::state::AppState;
 use actix_web::web::{Data, Json};
-use actix_web::Result;
 use actix_web::{web, Scope};
-use authentication::jwt::{Authorization, UserUuid};
+use actix_web::{HttpRequest, Result};
+use authentication::jwt::{authorization_from_token, Authorization, UserUuid};
 use database_entity::dto::{AFUserProfile, AFUserWorkspaceInfo};
+use secrecy::Secret;
+use shared_entity::dto::audit_log_dto::AuditAction;
 use shared_entity::dto::auth_dto::{DeleteUserQuery, SignInTokenResponse, UpdateUserParams};
 use shared_entity::response::AppResponseError;
 use shared_entity::response::{AppResponse, JsonAppResponse};
@@ -20,16 +23,41 @@ pub fn user_scope() -> Scope {
     .service(web::resource("").route(web::delete().to(delete_user_handler)))
 }
 
-#[tracing::instrument(skip(state, path), err)]
+#[tracing::instrument(skip(state, path, req, jwt_secret), err)]
 async fn verify_user_handler(
   path: web::Path<String>,
   state: Data<AppState>,
+  jwt_secret: Data<Secret<String>>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<SignInTokenResponse>> {
   let access_token = path.into_inner();
-  let is_new = verify_token(&access_token, state.as_ref())
+  let verified_user = verify_token(&access_token, state.as_ref())
     .await
     .map_err(AppResponseError::from)?;
-  let resp = SignInTokenResponse { is_new };
+  // The clients verify the token right after signing in. The token can be verified again while it
+  // is valid, so the sign-in is only recorded for the first verification of its GoTrue session.
+  let session_id = authorization_from_token(&access_token, &jwt_secret)
+    .ok()
+    .and_then(|auth| auth.claims.session_id);
+  if let Some(session_id) = session_id {
+    let uid = state
+      .user_cache
+      .get_user_uid(&verified_user.user_uuid)
+      .await?;
+    record_audit_log(
+      &state.pg_pool,
+      &AuditSource::from_request(&req),
+      None,
+      Some(uid),
+      AuditAction::UserSignedIn,
+      Some(&verified_user.user_uuid.to_string()),
+      serde_json::json!({ "is_new": verified_user.is_new, "session_id": session_id }),
+    )
+    .await;
+  }
+  let resp = SignInTokenResponse {
+    is_new: verified_user.is_new,
+  };
   Ok(AppResponse::Ok().with_data(resp).into())
 }
 
@@ -1,6 +1,7 @@
 use crate::domain::compression::{CompressionType, X_COMPRESSION_BUFFER_SIZE, X_COMPRESSION_TYPE};
+use crate::state::AppState;
 use actix_http::header::HeaderMap;
-use actix_web::web::Payload;
+use actix_web::web::{Data, Payload};
 use app_error::AppError;
 
 use actix_web::HttpRequest;
@@ -181,3 +182,26 @@ pub(crate) fn ai_model_from_header(req: &HttpRequest) -> AIModel {
     })
     .unwrap_or(AIModel::GPT4oMini)
 }
+
+/// Returns the address of the client. The `Forwarded` and `X-Forwarded-For` headers can be set to
+/// anything by the clients, so they are only used when the request comes from one of the trusted
+/// proxies of the configuration.
+pub(crate) fn client_ip(req: &HttpRequest) -> Option<String> {
+  let peer_ip = req.peer_addr()?.ip();
+  let is_trusted_proxy = req
+    .app_data::<Data<AppState>>()
+    .map(|state| {
+      state
+        .config
+        .application
+        .trusted_proxies
+        .contains(&peer_ip)
+    })
+    .unwrap_or(false);
+  if is_trusted_proxy {
+    if let Some(addr) = req.connection_info().realip_remote_addr() {
+      return Some(addr.to_string());
+    }
+  }
+  Some(peer_ip.to_string())
+}
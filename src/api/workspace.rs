@@ -2,7 +2,7 @@ use access_control::act::Action;
 use actix_web::web::{Bytes, Path, Payload};
 use actix_web::web::{Data, Json, PayloadConfig};
 use actix_web::{web, Scope};
-use actix_web::{HttpRequest, Result};
+use actix_web::{HttpRequest, HttpResponse, Result};
 use anyhow::{anyhow, Context};
 use bytes::BytesMut;
 use chrono::{DateTime, Duration, Utc};
@@ -23,28 +23,73 @@ use validator::Validate;
 use app_error::AppError;
 use appflowy_collaborate::actix_ws::entities::ClientStreamMessage;
 use appflowy_collaborate::indexer::IndexerProvider;
+use appflowy_collaborate::webhook::queue_webhook_event;
 use authentication::jwt::{Authorization, OptionalUserUuid, UserUuid};
 use collab_rt_entity::realtime_proto::HttpRealtimeMessage;
 use collab_rt_entity::RealtimeMessage;
 use collab_rt_protocol::validate_encode_collab;
 use database::collab::{CollabStorage, GetCollabOrigin};
 use database::user::select_uid_from_email;
+use database::workspace_activity::WorkspaceActivityParams;
 use database_entity::dto::PublishCollabItem;
 use database_entity::dto::PublishInfo;
 use database_entity::dto::*;
+use shared_entity::dto::api_token_dto::{CreateApiTokenParams, CreatedApiToken, RepeatedApiToken};
+use shared_entity::dto::audit_log_dto::{AuditAction, QueryAuditLogParams, RepeatedAuditLogEntry};
+use shared_entity::dto::comment_moderation_dto::{
+  BanCommenterParams, BannedCommenter, CommentModeration, PublishedViewCommentSettings,
+};
+use shared_entity::dto::document_comment_dto::{
+  CreateDocumentCommentParams, CreateDocumentCommentThreadParams, DocumentComment,
+  DocumentCommentThread, QueryDocumentCommentThreads, RepeatedDocumentCommentThread,
+  UpdateDocumentCommentParams, UpdateDocumentCommentThreadParams,
+};
+use shared_entity::dto::group_dto::{
+  CreateWorkspaceGroupParams, RepeatedCollabGroupMember, RepeatedWorkspaceGroup,
+  RepeatedWorkspaceGroupMember, UpdateWorkspaceGroupParams, UpsertCollabGroupMemberParams,
+  WorkspaceGroup, WorkspaceGroupMembersParams,
+};
+use shared_entity::dto::publish_dto::{
+  PublishedViewAnalytics, QueryPublishedViewAnalytics, PUBLISHED_VIEW_PASSWORD_HEADER,
+};
+use shared_entity::dto::role_dto::{
+  AssignCustomRoleParams, CreateCustomRoleParams, CustomRole, RepeatedCustomRole,
+  UpdateCustomRoleParams, WorkspacePermissions,
+};
+use shared_entity::dto::share_link_dto::{
+  CreateShareLinkParams, CreatedShareLink, RepeatedShareLink,
+};
+use shared_entity::dto::view_permission_dto::{
+  EffectiveViewPermission, GrantViewGroupPermissionParams, GrantViewPermissionParams,
+  QueryEffectiveViewPermission, RepeatedViewPermission,
+};
+use shared_entity::dto::webhook_dto::{
+  CreateWebhookParams, CreatedWebhook, QueryWebhookDeliveries, RepeatedWebhook,
+  RepeatedWebhookDelivery, UpdateWebhookParams, Webhook, WebhookEvent,
+};
+use shared_entity::dto::workspace_activity_dto::{
+  QueryWorkspaceActivityParams, RepeatedWorkspaceActivity, WorkspaceActivityKind,
+};
 use shared_entity::dto::workspace_dto::*;
 use shared_entity::response::AppResponseError;
 use shared_entity::response::{AppResponse, JsonAppResponse};
 
-use crate::api::util::PayloadReader;
+use crate::api::util::{client_ip, PayloadReader};
 use crate::api::util::{compress_type_from_header_value, device_id_from_headers, CollabValidator};
 use crate::api::ws::RealtimeServerAddr;
 use crate::biz;
+use crate::biz::audit_log::ops::{record_audit_log, AuditSource};
 use crate::biz::collab::ops::{
   get_user_favorite_folder_views, get_user_recent_folder_views, get_user_trash_folder_views,
 };
 use crate::biz::user::user_verify::verify_token;
 use crate::biz::workspace;
+use crate::biz::workspace::activity::{get_workspace_activities, record_workspace_activities};
+use crate::biz::workspace::comment_moderation::{
+  approve_comment_on_published_view, ban_commenter, get_published_view_comment_settings,
+  get_workspace_comment_moderation, list_banned_commenters, unban_commenter,
+  update_published_view_comment_settings, update_workspace_comment_moderation,
+};
 use crate::biz::workspace::ops::{
   create_comment_on_published_view, create_reaction_on_comment, get_comments_on_published_view,
   get_reactions_on_published_view, remove_comment_on_published_view, remove_reaction_on_comment,
@@ -55,6 +100,10 @@ use crate::biz::workspace::page_view::{
   update_space,
 };
 use crate::biz::workspace::publish::get_workspace_default_publish_view_info_meta;
+use crate::biz::workspace::publish_access::{
+  enforce_published_view_access, enforce_published_view_access_by_name, PublishedViewReader,
+};
+use crate::biz::workspace::publish_analytics::{get_published_view_analytics, PublishedViewHit};
 use crate::domain::compression::{
   blocking_decompress, decompress, CompressionType, X_COMPRESSION_TYPE,
 };
@@ -149,6 +198,56 @@ pub fn workspace_scope() -> Scope {
       web::resource("/{workspace_id}/page-view/{view_id}/restore-from-trash")
         .route(web::post().to(restore_page_from_trash_handler)),
     )
+    .service(
+      web::resource("/{workspace_id}/page-view/{view_id}/permission")
+        .route(web::get().to(list_view_permissions_handler))
+        .route(web::put().to(grant_view_permission_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/page-view/{view_id}/permission/effective")
+        .route(web::get().to(get_effective_view_permission_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/page-view/{view_id}/permission/{uid}")
+        .route(web::delete().to(revoke_view_permission_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/page-view/{view_id}/group-permission")
+        .route(web::put().to(grant_view_group_permission_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/page-view/{view_id}/group-permission/{group_id}")
+        .route(web::delete().to(revoke_view_group_permission_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/page-view/{view_id}/share-link")
+        .route(web::get().to(list_share_links_handler))
+        .route(web::post().to(create_share_link_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/page-view/{view_id}/share-link/{link_id}")
+        .route(web::delete().to(revoke_share_link_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/page-view/{view_id}/comment-thread")
+        .route(web::get().to(list_document_comment_threads_handler))
+        .route(web::post().to(create_document_comment_thread_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/page-view/{view_id}/comment-thread/{thread_id}")
+        .route(web::patch().to(update_document_comment_thread_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/page-view/{view_id}/comment-thread/{thread_id}/comment")
+        .route(web::post().to(create_document_comment_handler)),
+    )
+    .service(
+      web::resource(
+        "/{workspace_id}/page-view/{view_id}/comment-thread/{thread_id}/comment/{comment_id}",
+      )
+      .route(web::patch().to(update_document_comment_handler))
+      .route(web::delete().to(delete_document_comment_handler)),
+    )
     .service(
       web::resource("/{workspace_id}/restore-all-pages-from-trash")
         .route(web::post().to(restore_all_pages_from_trash_handler)),
@@ -176,6 +275,15 @@ pub fn workspace_scope() -> Scope {
         .route(web::put().to(update_collab_member_handler))
         .route(web::delete().to(remove_collab_member_handler)),
     )
+    .service(
+      web::resource("/{workspace_id}/collab/{object_id}/group-member")
+        .route(web::get().to(get_collab_group_member_list_handler))
+        .route(web::put().to(upsert_collab_group_member_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/collab/{object_id}/group-member/{group_id}")
+        .route(web::delete().to(remove_collab_group_member_handler)),
+    )
     .service(
       web::resource("/published/{publish_namespace}")
         .route(web::get().to(get_default_published_collab_info_meta_handler)),
@@ -188,6 +296,10 @@ pub fn workspace_scope() -> Scope {
       web::resource("/published/{publish_namespace}/{publish_name}/blob")
         .route(web::get().to(get_published_collab_blob_handler)),
     )
+    .service(
+      web::resource("/v1/published/{publish_namespace}/{publish_name}/html")
+        .route(web::get().to(get_published_collab_html_handler)),
+    )
     .service(
       web::resource("{workspace_id}/published-duplicate")
         .route(web::post().to(post_published_duplicate_handler)),
@@ -196,6 +308,10 @@ pub fn workspace_scope() -> Scope {
       web::resource("/{workspace_id}/published-info")
         .route(web::get().to(list_published_collab_info_handler)),
     )
+    .service(
+      web::resource("/{workspace_id}/published-info/analytics")
+        .route(web::get().to(get_published_view_analytics_handler)),
+    )
     .service(
       // deprecated since 0.7.4
       web::resource("/published-info/{view_id}")
@@ -211,6 +327,29 @@ pub fn workspace_scope() -> Scope {
         .route(web::post().to(post_published_collab_comment_handler))
         .route(web::delete().to(delete_published_collab_comment_handler)),
     )
+    .service(
+      web::resource("/published-info/{view_id}/comment/{comment_id}/approve")
+        .route(web::post().to(approve_published_collab_comment_handler)),
+    )
+    .service(
+      web::resource("/published-info/{view_id}/comment-settings")
+        .route(web::get().to(get_published_collab_comment_settings_handler))
+        .route(web::put().to(put_published_collab_comment_settings_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/published-comment-moderation")
+        .route(web::get().to(get_workspace_comment_moderation_handler))
+        .route(web::put().to(put_workspace_comment_moderation_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/published-comment-ban")
+        .route(web::get().to(list_banned_commenters_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/published-comment-ban/{user_uuid}")
+        .route(web::put().to(ban_commenter_handler))
+        .route(web::delete().to(unban_commenter_handler)),
+    )
     .service(
       web::resource("/published-info/{view_id}/reaction")
         .route(web::get().to(get_published_collab_reaction_handler))
@@ -273,6 +412,72 @@ pub fn workspace_scope() -> Scope {
       web::resource("/{workspace_id}/database/{database_id}/row/detail")
         .route(web::get().to(list_database_row_details_handler)),
     )
+    .service(
+      web::resource("/{workspace_id}/api-token")
+        .route(web::get().to(list_api_tokens_handler))
+        .route(web::post().to(create_api_token_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/api-token/{token_id}")
+        .route(web::delete().to(revoke_api_token_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/webhook")
+        .route(web::get().to(list_webhooks_handler))
+        .route(web::post().to(create_webhook_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/webhook/{webhook_id}")
+        .route(web::patch().to(patch_webhook_handler))
+        .route(web::delete().to(delete_webhook_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/webhook/{webhook_id}/delivery")
+        .route(web::get().to(list_webhook_deliveries_handler)),
+    )
+    .service(web::resource("/{workspace_id}/audit-log").route(web::get().to(get_audit_log_handler)))
+    .service(
+      web::resource("/{workspace_id}/activity")
+        .route(web::get().to(get_workspace_activity_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/audit-log/export")
+        .route(web::get().to(export_audit_log_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/role")
+        .route(web::get().to(list_custom_roles_handler))
+        .route(web::post().to(create_custom_role_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/role/{role_id}")
+        .route(web::patch().to(patch_custom_role_handler))
+        .route(web::delete().to(delete_custom_role_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/member/custom-role")
+        .route(web::put().to(assign_custom_role_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/permission")
+        .route(web::get().to(get_workspace_permissions_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/group")
+        .route(web::get().to(list_workspace_groups_handler))
+        .route(web::post().to(create_workspace_group_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/group/{group_id}")
+        .route(web::patch().to(patch_workspace_group_handler))
+        .route(web::delete().to(delete_workspace_group_handler)),
+    )
+    .service(
+      web::resource("/{workspace_id}/group/{group_id}/member")
+        .route(web::get().to(list_workspace_group_members_handler))
+        .route(web::post().to(add_workspace_group_members_handler))
+        .route(web::delete().to(remove_workspace_group_members_handler)),
+    )
 }
 
 pub fn collab_scope() -> Scope {
@@ -338,6 +543,7 @@ async fn delete_workspace_handler(
   user_uuid: UserUuid,
   workspace_id: web::Path<Uuid>,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<Json<AppResponse<()>>> {
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
   state
@@ -350,6 +556,16 @@ async fn delete_workspace_handler(
     state.bucket_storage.clone(),
   )
   .await?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::WorkspaceDeleted,
+    Some(&workspace_id.to_string()),
+    serde_json::json!({}),
+  )
+  .await;
   Ok(AppResponse::Ok().into())
 }
 
@@ -375,20 +591,39 @@ async fn list_workspace_handler(
   Ok(AppResponse::Ok().with_data(workspaces).into())
 }
 
-#[instrument(skip(payload, state), err)]
+#[instrument(skip(payload, state, req), err)]
 async fn post_workspace_invite_handler(
   user_uuid: UserUuid,
   workspace_id: web::Path<Uuid>,
   payload: Json<Vec<WorkspaceMemberInvitation>>,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<()>> {
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let invited_members = payload.into_inner();
   state
     .workspace_access_control
-    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .enforce_permission(
+      &uid,
+      &workspace_id.to_string(),
+      AFWorkspacePermission::InviteMembers,
+    )
     .await?;
+  // Only an owner can make someone else an owner.
+  if invited_members
+    .iter()
+    .any(|member| member.role == AFRole::Owner)
+  {
+    state
+      .workspace_access_control
+      .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+      .await?;
+  }
 
-  let invited_members = payload.into_inner();
+  let audited_members = invited_members
+    .iter()
+    .map(|member| (member.email.clone(), member.role.clone()))
+    .collect::<Vec<_>>();
   workspace::ops::invite_workspace_members(
     &state.mailer,
     &state.gotrue_admin,
@@ -400,6 +635,19 @@ async fn post_workspace_invite_handler(
     state.config.appflowy_web_url.as_deref(),
   )
   .await?;
+  let source = AuditSource::from_request(&req);
+  for (email, role) in audited_members {
+    record_audit_log(
+      &state.pg_pool,
+      &source,
+      Some(&workspace_id),
+      Some(uid),
+      AuditAction::MemberInvited,
+      Some(&email),
+      serde_json::json!({ "role": role }),
+    )
+    .await;
+  }
   Ok(AppResponse::Ok().into())
 }
 
@@ -432,11 +680,11 @@ async fn post_accept_workspace_invite_handler(
   invite_id: web::Path<Uuid>,
   state: Data<AppState>,
 ) -> Result<JsonAppResponse<()>> {
-  let _is_new = verify_token(&auth.token, state.as_ref()).await?;
+  verify_token(&auth.token, state.as_ref()).await?;
   let user_uuid = auth.uuid()?;
   let user_uid = state.user_cache.get_user_uid(&user_uuid).await?;
   let invite_id = invite_id.into_inner();
-  workspace::ops::accept_workspace_invite(
+  let workspace_id = workspace::ops::accept_workspace_invite(
     &state.pg_pool,
     state.workspace_access_control.clone(),
     user_uid,
@@ -444,6 +692,13 @@ async fn post_accept_workspace_invite_handler(
     &invite_id,
   )
   .await?;
+  queue_webhook_event(
+    &state.redis_connection_manager,
+    &workspace_id,
+    WebhookEvent::MemberJoined,
+    serde_json::json!({ "uid": user_uid, "email": auth.claims.email }),
+  )
+  .await;
   Ok(AppResponse::Ok().into())
 }
 
@@ -468,6 +723,7 @@ async fn post_workspace_settings_handler(
   state: Data<AppState>,
   workspace_id: web::Path<Uuid>,
   data: Json<AFWorkspaceSettingsChange>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<AFWorkspaceSettings>> {
   let data = data.into_inner();
   trace!("workspace settings: {:?}", data);
@@ -476,8 +732,19 @@ async fn post_workspace_settings_handler(
     .workspace_access_control
     .enforce_action(&uid, &workspace_id.to_string(), Action::Write)
     .await?;
+  let change = serde_json::to_value(&data).unwrap_or_default();
   let settings =
     workspace::ops::update_workspace_settings(&state.pg_pool, &workspace_id, data).await?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::WorkspaceSettingsUpdated,
+    Some(&workspace_id.to_string()),
+    change,
+  )
+  .await;
   Ok(AppResponse::Ok().with_data(settings).into())
 }
 
@@ -512,6 +779,7 @@ async fn remove_workspace_member_handler(
   payload: Json<WorkspaceMembers>,
   state: Data<AppState>,
   workspace_id: web::Path<Uuid>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<()>> {
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
   state
@@ -532,6 +800,26 @@ async fn remove_workspace_member_handler(
     state.workspace_access_control.clone(),
   )
   .await?;
+  let source = AuditSource::from_request(&req);
+  for email in member_emails {
+    record_audit_log(
+      &state.pg_pool,
+      &source,
+      Some(&workspace_id),
+      Some(uid),
+      AuditAction::MemberRemoved,
+      Some(&email),
+      serde_json::json!({}),
+    )
+    .await;
+    queue_webhook_event(
+      &state.redis_connection_manager,
+      &workspace_id,
+      WebhookEvent::MemberRemoved,
+      serde_json::json!({ "email": email }),
+    )
+    .await;
+  }
 
   Ok(AppResponse::Ok().into())
 }
@@ -585,13 +873,20 @@ async fn leave_workspace_handler(
   workspace_id: web::Path<Uuid>,
 ) -> Result<JsonAppResponse<()>> {
   let workspace_id = workspace_id.into_inner();
-  workspace::ops::leave_workspace(
+  let email = workspace::ops::leave_workspace(
     &state.pg_pool,
     &workspace_id,
     &user_uuid,
     state.workspace_access_control.clone(),
   )
   .await?;
+  queue_webhook_event(
+    &state.redis_connection_manager,
+    &workspace_id,
+    WebhookEvent::MemberRemoved,
+    serde_json::json!({ "email": email }),
+  )
+  .await;
   Ok(AppResponse::Ok().into())
 }
 
@@ -601,6 +896,7 @@ async fn update_workspace_member_handler(
   payload: Json<WorkspaceMemberChangeset>,
   state: Data<AppState>,
   workspace_id: web::Path<Uuid>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<()>> {
   let workspace_id = workspace_id.into_inner();
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
@@ -611,7 +907,7 @@ async fn update_workspace_member_handler(
 
   let changeset = payload.into_inner();
 
-  if changeset.role.is_some() {
+  if let Some(role) = &changeset.role {
     let changeset_uid = select_uid_from_email(&state.pg_pool, &changeset.email)
       .await
       .map_err(AppResponseError::from)?;
@@ -623,6 +919,16 @@ async fn update_workspace_member_handler(
       state.workspace_access_control.clone(),
     )
     .await?;
+    record_audit_log(
+      &state.pg_pool,
+      &AuditSource::from_request(&req),
+      Some(&workspace_id),
+      Some(uid),
+      AuditAction::MemberRoleUpdated,
+      Some(&changeset.email),
+      serde_json::json!({ "uid": changeset_uid, "role": role }),
+    )
+    .await;
   }
 
   Ok(AppResponse::Ok().into())
@@ -935,6 +1241,7 @@ async fn post_space_handler(
   let space = create_space(
     &state.pg_pool,
     &state.collab_access_control_storage,
+    &state.activity_recorder,
     uid,
     workspace_uuid,
     &payload.space_permission,
@@ -957,6 +1264,7 @@ async fn update_space_handler(
   update_space(
     &state.pg_pool,
     &state.collab_access_control_storage,
+    &state.activity_recorder,
     uid,
     workspace_uuid,
     &view_id,
@@ -980,6 +1288,7 @@ async fn post_page_view_handler(
   let page = create_page(
     &state.pg_pool,
     &state.collab_access_control_storage,
+    &state.activity_recorder,
     uid,
     workspace_uuid,
     &payload.parent_view_id,
@@ -987,148 +1296,529 @@ async fn post_page_view_handler(
     payload.name.as_deref(),
   )
   .await?;
+  // The new page inherits the permissions granted on the spaces and pages above it.
+  if let Err(err) = workspace::view_permission::refresh_workspace_view_permissions(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    &state.collab_access_control,
+    uid,
+    &workspace_uuid,
+  )
+  .await
+  {
+    error!("Failed to refresh the view permissions: {:?}", err);
+  }
+  queue_webhook_event(
+    &state.redis_connection_manager,
+    &workspace_uuid,
+    WebhookEvent::PageCreated,
+    serde_json::json!({
+      "view_id": page.view_id,
+      "parent_view_id": payload.parent_view_id,
+      "layout": payload.layout,
+      "name": payload.name,
+      "uid": uid,
+    }),
+  )
+  .await;
   Ok(Json(AppResponse::Ok().with_data(page)))
 }
 
-async fn move_page_to_trash_handler(
+async fn list_view_permissions_handler(
   user_uuid: UserUuid,
-  path: web::Path<(Uuid, String)>,
+  path: web::Path<(Uuid, Uuid)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<RepeatedViewPermission>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_id, view_id) = path.into_inner();
+  let permissions = workspace::view_permission::list_view_permissions(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    uid,
+    &workspace_id,
+    &view_id,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(permissions)))
+}
+
+async fn grant_view_permission_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, Uuid)>,
+  payload: Json<GrantViewPermissionParams>,
   state: Data<AppState>,
 ) -> Result<Json<AppResponse<()>>> {
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
-  let (workspace_uuid, view_id) = path.into_inner();
-  move_page_to_trash(
+  let (workspace_id, view_id) = path.into_inner();
+  workspace::view_permission::grant_view_permission(
     &state.pg_pool,
     &state.collab_access_control_storage,
+    &state.collab_access_control,
     uid,
-    workspace_uuid,
+    &workspace_id,
     &view_id,
+    payload.into_inner(),
   )
   .await?;
   Ok(Json(AppResponse::Ok()))
 }
 
-async fn restore_page_from_trash_handler(
+async fn revoke_view_permission_handler(
   user_uuid: UserUuid,
-  path: web::Path<(Uuid, String)>,
+  path: web::Path<(Uuid, Uuid, i64)>,
   state: Data<AppState>,
 ) -> Result<Json<AppResponse<()>>> {
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
-  let (workspace_uuid, view_id) = path.into_inner();
-  restore_page_from_trash(
+  let (workspace_id, view_id, grantee) = path.into_inner();
+  workspace::view_permission::revoke_view_permission(
     &state.pg_pool,
     &state.collab_access_control_storage,
+    &state.collab_access_control,
     uid,
-    workspace_uuid,
+    &workspace_id,
     &view_id,
+    grantee,
   )
   .await?;
   Ok(Json(AppResponse::Ok()))
 }
 
-async fn restore_all_pages_from_trash_handler(
+async fn grant_view_group_permission_handler(
   user_uuid: UserUuid,
-  path: web::Path<Uuid>,
+  path: web::Path<(Uuid, Uuid)>,
+  payload: Json<GrantViewGroupPermissionParams>,
   state: Data<AppState>,
 ) -> Result<Json<AppResponse<()>>> {
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
-  let workspace_uuid = path.into_inner();
-  restore_all_pages_from_trash(
+  let (workspace_id, view_id) = path.into_inner();
+  workspace::view_permission::grant_view_group_permission(
     &state.pg_pool,
     &state.collab_access_control_storage,
+    &state.collab_access_control,
     uid,
-    workspace_uuid,
+    &workspace_id,
+    &view_id,
+    payload.into_inner(),
   )
   .await?;
   Ok(Json(AppResponse::Ok()))
 }
 
-async fn update_page_view_handler(
+async fn revoke_view_group_permission_handler(
   user_uuid: UserUuid,
-  path: web::Path<(Uuid, String)>,
-  payload: Json<UpdatePageParams>,
+  path: web::Path<(Uuid, Uuid, Uuid)>,
   state: Data<AppState>,
 ) -> Result<Json<AppResponse<()>>> {
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
-  let (workspace_uuid, view_id) = path.into_inner();
-  let icon = payload.icon.as_ref();
-  let extra = payload
-    .extra
-    .as_ref()
-    .map(|json_value| json_value.to_string());
-  update_page(
+  let (workspace_id, view_id, group_id) = path.into_inner();
+  workspace::view_permission::revoke_view_group_permission(
     &state.pg_pool,
     &state.collab_access_control_storage,
+    &state.collab_access_control,
     uid,
-    workspace_uuid,
+    &workspace_id,
     &view_id,
-    &payload.name,
-    icon,
-    extra.as_ref(),
+    &group_id,
   )
   .await?;
   Ok(Json(AppResponse::Ok()))
 }
 
-async fn get_page_view_handler(
+async fn list_share_links_handler(
   user_uuid: UserUuid,
-  path: web::Path<(Uuid, String)>,
+  path: web::Path<(Uuid, Uuid)>,
   state: Data<AppState>,
-) -> Result<Json<AppResponse<PageCollab>>> {
-  let (workspace_uuid, view_id) = path.into_inner();
-  let uid = state
-    .user_cache
-    .get_user_uid(&user_uuid)
-    .await
-    .map_err(AppResponseError::from)?;
+) -> Result<Json<AppResponse<RepeatedShareLink>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_id, view_id) = path.into_inner();
+  let links = workspace::share_link::list_share_links(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    uid,
+    &workspace_id,
+    &view_id,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(links)))
+}
 
-  let page_collab = get_page_view_collab(
+async fn create_share_link_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, Uuid)>,
+  payload: Json<CreateShareLinkParams>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> Result<Json<AppResponse<CreatedShareLink>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_id, view_id) = path.into_inner();
+  let created = workspace::share_link::create_share_link(
     &state.pg_pool,
     &state.collab_access_control_storage,
     uid,
-    workspace_uuid,
+    &workspace_id,
     &view_id,
+    payload.into_inner(),
   )
   .await?;
-  Ok(Json(AppResponse::Ok().with_data(page_collab)))
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::ShareLinkCreated,
+    Some(&created.share_link.link_id.to_string()),
+    serde_json::json!({
+      "view_id": view_id,
+      "access_level": created.share_link.access_level,
+      "has_password": created.share_link.has_password,
+      "expires_at": created.share_link.expires_at,
+    }),
+  )
+  .await;
+  Ok(Json(AppResponse::Ok().with_data(created)))
 }
 
-#[instrument(level = "trace", skip_all, err)]
-async fn get_collab_snapshot_handler(
-  payload: Json<QuerySnapshotParams>,
-  path: web::Path<(String, String)>,
+async fn revoke_share_link_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, Uuid, Uuid)>,
   state: Data<AppState>,
-) -> Result<Json<AppResponse<SnapshotData>>> {
-  let (workspace_id, object_id) = path.into_inner();
-  let data = state
-    .collab_access_control_storage
-    .get_collab_snapshot(&workspace_id.to_string(), &object_id, &payload.snapshot_id)
-    .await
-    .map_err(AppResponseError::from)?;
+  req: HttpRequest,
+) -> Result<Json<AppResponse<()>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_id, view_id, link_id) = path.into_inner();
+  workspace::share_link::revoke_share_link(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    uid,
+    &workspace_id,
+    &view_id,
+    &link_id,
+  )
+  .await?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::ShareLinkRevoked,
+    Some(&link_id.to_string()),
+    serde_json::json!({ "view_id": view_id }),
+  )
+  .await;
+  Ok(Json(AppResponse::Ok()))
+}
 
-  Ok(Json(AppResponse::Ok().with_data(data)))
+async fn get_effective_view_permission_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, Uuid)>,
+  query: web::Query<QueryEffectiveViewPermission>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<EffectiveViewPermission>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_id, view_id) = path.into_inner();
+  let permission = workspace::view_permission::get_effective_view_permission(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    uid,
+    &workspace_id,
+    &view_id,
+    query.uid,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(permission)))
 }
 
-#[instrument(level = "trace", skip_all, err)]
-async fn create_collab_snapshot_handler(
+async fn list_document_comment_threads_handler(
   user_uuid: UserUuid,
+  path: web::Path<(Uuid, Uuid)>,
+  query: web::Query<QueryDocumentCommentThreads>,
   state: Data<AppState>,
-  path: web::Path<(String, String)>,
-  payload: Json<CollabType>,
-) -> Result<Json<AppResponse<AFSnapshotMeta>>> {
-  let (workspace_id, object_id) = path.into_inner();
-  let collab_type = payload.into_inner();
-  let uid = state
-    .user_cache
-    .get_user_uid(&user_uuid)
-    .await
-    .map_err(AppResponseError::from)?;
-  let data = state
-    .collab_access_control_storage
-    .get_encode_collab(
-      GetCollabOrigin::User { uid },
-      QueryCollabParams::new(&object_id, collab_type.clone(), &workspace_id),
-      true,
+) -> Result<Json<AppResponse<RepeatedDocumentCommentThread>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_id, view_id) = path.into_inner();
+  let threads = workspace::document_comment::list_document_comment_threads(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    uid,
+    &workspace_id,
+    &view_id,
+    query.into_inner(),
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(threads)))
+}
+
+async fn create_document_comment_thread_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, Uuid)>,
+  payload: Json<CreateDocumentCommentThreadParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<DocumentCommentThread>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_id, view_id) = path.into_inner();
+  let thread = workspace::document_comment::create_document_comment_thread(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    uid,
+    &workspace_id,
+    &view_id,
+    payload.into_inner(),
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(thread)))
+}
+
+async fn update_document_comment_thread_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, Uuid, Uuid)>,
+  payload: Json<UpdateDocumentCommentThreadParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_id, view_id, thread_id) = path.into_inner();
+  workspace::document_comment::update_document_comment_thread(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    uid,
+    &workspace_id,
+    &view_id,
+    &thread_id,
+    payload.into_inner(),
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn create_document_comment_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, Uuid, Uuid)>,
+  payload: Json<CreateDocumentCommentParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<DocumentComment>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_id, view_id, thread_id) = path.into_inner();
+  let comment = workspace::document_comment::create_document_comment(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    uid,
+    &workspace_id,
+    &view_id,
+    &thread_id,
+    payload.into_inner(),
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(comment)))
+}
+
+async fn update_document_comment_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, Uuid, Uuid, Uuid)>,
+  payload: Json<UpdateDocumentCommentParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<DocumentComment>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_id, view_id, thread_id, comment_id) = path.into_inner();
+  let comment = workspace::document_comment::update_document_comment(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    uid,
+    &workspace_id,
+    &view_id,
+    &thread_id,
+    &comment_id,
+    payload.into_inner(),
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(comment)))
+}
+
+async fn delete_document_comment_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, Uuid, Uuid, Uuid)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_id, view_id, thread_id, comment_id) = path.into_inner();
+  workspace::document_comment::delete_document_comment(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    uid,
+    &workspace_id,
+    &view_id,
+    &thread_id,
+    &comment_id,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn move_page_to_trash_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_uuid, view_id) = path.into_inner();
+  state
+    .workspace_access_control
+    .enforce_permission(
+      &uid,
+      &workspace_uuid.to_string(),
+      AFWorkspacePermission::DeletePages,
+    )
+    .await?;
+  move_page_to_trash(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    &state.activity_recorder,
+    uid,
+    workspace_uuid,
+    &view_id,
+  )
+  .await?;
+  queue_webhook_event(
+    &state.redis_connection_manager,
+    &workspace_uuid,
+    WebhookEvent::PageTrashed,
+    serde_json::json!({ "view_id": view_id, "uid": uid }),
+  )
+  .await;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn restore_page_from_trash_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_uuid, view_id) = path.into_inner();
+  restore_page_from_trash(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    &state.activity_recorder,
+    uid,
+    workspace_uuid,
+    &view_id,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn restore_all_pages_from_trash_handler(
+  user_uuid: UserUuid,
+  path: web::Path<Uuid>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let workspace_uuid = path.into_inner();
+  restore_all_pages_from_trash(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    &state.activity_recorder,
+    uid,
+    workspace_uuid,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn update_page_view_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  payload: Json<UpdatePageParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let (workspace_uuid, view_id) = path.into_inner();
+  let icon = payload.icon.as_ref();
+  let extra = payload
+    .extra
+    .as_ref()
+    .map(|json_value| json_value.to_string());
+  let is_renamed = update_page(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    &state.activity_recorder,
+    uid,
+    workspace_uuid,
+    &view_id,
+    &payload.name,
+    icon,
+    extra.as_ref(),
+  )
+  .await?;
+  if is_renamed {
+    queue_webhook_event(
+      &state.redis_connection_manager,
+      &workspace_uuid,
+      WebhookEvent::PageRenamed,
+      serde_json::json!({ "view_id": view_id, "name": payload.name, "uid": uid }),
+    )
+    .await;
+  }
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn get_page_view_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<PageCollab>>> {
+  let (workspace_uuid, view_id) = path.into_inner();
+  let uid = state
+    .user_cache
+    .get_user_uid(&user_uuid)
+    .await
+    .map_err(AppResponseError::from)?;
+
+  let page_collab = get_page_view_collab(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    uid,
+    workspace_uuid,
+    &view_id,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(page_collab)))
+}
+
+#[instrument(level = "trace", skip_all, err)]
+async fn get_collab_snapshot_handler(
+  payload: Json<QuerySnapshotParams>,
+  path: web::Path<(String, String)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<SnapshotData>>> {
+  let (workspace_id, object_id) = path.into_inner();
+  let data = state
+    .collab_access_control_storage
+    .get_collab_snapshot(&workspace_id.to_string(), &object_id, &payload.snapshot_id)
+    .await
+    .map_err(AppResponseError::from)?;
+
+  Ok(Json(AppResponse::Ok().with_data(data)))
+}
+
+#[instrument(level = "trace", skip_all, err)]
+async fn create_collab_snapshot_handler(
+  user_uuid: UserUuid,
+  state: Data<AppState>,
+  path: web::Path<(String, String)>,
+  payload: Json<CollabType>,
+) -> Result<Json<AppResponse<AFSnapshotMeta>>> {
+  let (workspace_id, object_id) = path.into_inner();
+  let collab_type = payload.into_inner();
+  let uid = state
+    .user_cache
+    .get_user_uid(&user_uuid)
+    .await
+    .map_err(AppResponseError::from)?;
+  let data = state
+    .collab_access_control_storage
+    .get_encode_collab(
+      GetCollabOrigin::User { uid },
+      QueryCollabParams::new(&object_id, collab_type.clone(), &workspace_id),
+      true,
     )
     .await?
     .doc_state;
@@ -1284,11 +1974,12 @@ async fn add_collab_member_handler(
   Ok(Json(AppResponse::Ok()))
 }
 
-#[instrument(level = "debug", skip(state, payload), err)]
+#[instrument(level = "debug", skip(state, payload, req), err)]
 async fn update_collab_member_handler(
   user_uuid: UserUuid,
   payload: Json<UpdateCollabMemberParams>,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<Json<AppResponse<()>>> {
   let payload = payload.into_inner();
 
@@ -1312,6 +2003,17 @@ async fn update_collab_member_handler(
     state.collab_access_control.clone(),
   )
   .await?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Uuid::parse_str(&payload.workspace_id).ok().as_ref(),
+    Some(uid),
+    AuditAction::CollabMemberUpdated,
+    Some(&payload.object_id),
+    serde_json::json!({ "uid": payload.uid, "access_level": payload.access_level }),
+  )
+  .await;
   Ok(Json(AppResponse::Ok()))
 }
 #[instrument(level = "debug", skip(state, payload), err)]
@@ -1340,30 +2042,120 @@ async fn remove_collab_member_handler(
   Ok(Json(AppResponse::Ok()))
 }
 
-async fn put_workspace_default_published_view_handler(
+#[instrument(level = "debug", skip(state), err)]
+async fn get_collab_group_member_list_handler(
   user_uuid: UserUuid,
-  workspace_id: web::Path<Uuid>,
-  payload: Json<UpdateDefaultPublishView>,
+  path: web::Path<(Uuid, String)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<RepeatedCollabGroupMember>>> {
+  let (workspace_id, object_id) = path.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .collab_access_control
+    .enforce_action(&workspace_id.to_string(), &uid, &object_id, Action::Read)
+    .await?;
+  let items =
+    biz::collab::ops::get_collab_group_member_list(&state.pg_pool, &workspace_id, &object_id)
+      .await?;
+  Ok(Json(
+    AppResponse::Ok().with_data(RepeatedCollabGroupMember { items }),
+  ))
+}
+
+/// Granting an access level to a group requires full access to the collab.
+#[instrument(level = "debug", skip(state, payload, req), err)]
+async fn upsert_collab_group_member_handler(
+  user_uuid: UserUuid,
+  path: web::Path<(Uuid, String)>,
+  payload: Json<UpsertCollabGroupMemberParams>,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<Json<AppResponse<()>>> {
+  let (workspace_id, object_id) = path.into_inner();
+  let payload = payload.into_inner();
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
   state
-    .workspace_access_control
-    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .collab_access_control
+    .enforce_access_level(
+      &workspace_id.to_string(),
+      &uid,
+      &object_id,
+      AFAccessLevel::FullAccess,
+    )
     .await?;
-  let new_default_pub_view_id = payload.into_inner().view_id;
-  biz::workspace::publish::set_workspace_default_publish_view(
+  biz::collab::ops::upsert_collab_group_member(
     &state.pg_pool,
     &workspace_id,
-    &new_default_pub_view_id,
+    &object_id,
+    &payload,
+    state.collab_access_control.clone(),
   )
   .await?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::CollabMemberUpdated,
+    Some(&object_id),
+    serde_json::json!({ "group_id": payload.group_id, "access_level": payload.access_level }),
+  )
+  .await;
   Ok(Json(AppResponse::Ok()))
 }
 
-async fn delete_workspace_default_published_view_handler(
+#[instrument(skip(state), err)]
+async fn remove_collab_group_member_handler(
   user_uuid: UserUuid,
-  workspace_id: web::Path<Uuid>,
+  path: web::Path<(Uuid, String, Uuid)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let (workspace_id, object_id, group_id) = path.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .collab_access_control
+    .enforce_access_level(
+      &workspace_id.to_string(),
+      &uid,
+      &object_id,
+      AFAccessLevel::FullAccess,
+    )
+    .await?;
+  biz::collab::ops::delete_collab_group_member(
+    &state.pg_pool,
+    &workspace_id,
+    &object_id,
+    &group_id,
+    state.collab_access_control.clone(),
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn put_workspace_default_published_view_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  payload: Json<UpdateDefaultPublishView>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let new_default_pub_view_id = payload.into_inner().view_id;
+  biz::workspace::publish::set_workspace_default_publish_view(
+    &state.pg_pool,
+    &workspace_id,
+    &new_default_pub_view_id,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn delete_workspace_default_published_view_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
   state: Data<AppState>,
 ) -> Result<Json<AppResponse<()>>> {
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
@@ -1425,11 +2217,20 @@ async fn get_publish_namespace_handler(
 
 async fn get_default_published_collab_info_meta_handler(
   publish_namespace: web::Path<String>,
+  optional_user_uuid: OptionalUserUuid,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<Json<AppResponse<PublishInfoMeta<serde_json::Value>>>> {
   let publish_namespace = publish_namespace.into_inner();
   let (info, meta) =
     get_workspace_default_publish_view_info_meta(&state.pg_pool, &publish_namespace).await?;
+  enforce_published_view_access(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    &info.view_id,
+    &published_view_reader(&req, &optional_user_uuid),
+  )
+  .await?;
   Ok(Json(
     AppResponse::Ok().with_data(PublishInfoMeta { info, meta }),
   ))
@@ -1437,21 +2238,46 @@ async fn get_default_published_collab_info_meta_handler(
 
 async fn get_v1_published_collab_handler(
   path_param: web::Path<(String, String)>,
+  optional_user_uuid: OptionalUserUuid,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<Json<AppResponse<serde_json::Value>>> {
   let (workspace_namespace, publish_name) = path_param.into_inner();
+  enforce_published_view_access_by_name(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    &workspace_namespace,
+    &publish_name,
+    &published_view_reader(&req, &optional_user_uuid),
+  )
+  .await?;
   let metadata = state
     .published_collab_store
     .get_collab_metadata(&workspace_namespace, &publish_name)
     .await?;
+  state.published_view_analytics.record_view(
+    &workspace_namespace,
+    &publish_name,
+    PublishedViewHit::from_request(&req),
+  );
   Ok(Json(AppResponse::Ok().with_data(metadata)))
 }
 
 async fn get_published_collab_blob_handler(
   path_param: web::Path<(String, String)>,
+  optional_user_uuid: OptionalUserUuid,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<Vec<u8>> {
   let (publish_namespace, publish_name) = path_param.into_inner();
+  enforce_published_view_access_by_name(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    &publish_namespace,
+    &publish_name,
+    &published_view_reader(&req, &optional_user_uuid),
+  )
+  .await?;
   let collab_data = state
     .published_collab_store
     .get_collab_blob_by_publish_namespace(&publish_namespace, &publish_name)
@@ -1459,11 +2285,49 @@ async fn get_published_collab_blob_handler(
   Ok(collab_data)
 }
 
+async fn get_published_collab_html_handler(
+  path_param: web::Path<(String, String)>,
+  optional_user_uuid: OptionalUserUuid,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> Result<HttpResponse> {
+  let (publish_namespace, publish_name) = path_param.into_inner();
+  let is_restricted = enforce_published_view_access_by_name(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    &publish_namespace,
+    &publish_name,
+    &published_view_reader(&req, &optional_user_uuid),
+  )
+  .await?;
+  let html = biz::workspace::publish_html::render_published_view_html(
+    state.published_collab_store.as_ref(),
+    state.config.appflowy_web_url.as_deref(),
+    &publish_namespace,
+    &publish_name,
+  )
+  .await?;
+  Ok(
+    HttpResponse::Ok()
+      .content_type("text/html; charset=utf-8")
+      .insert_header((
+        "Cache-Control",
+        if is_restricted {
+          "private, no-store"
+        } else {
+          "public, max-age=300"
+        },
+      ))
+      .body(html),
+  )
+}
+
 async fn post_published_duplicate_handler(
   user_uuid: UserUuid,
   workspace_id: web::Path<String>,
   state: Data<AppState>,
   params: Json<PublishedDuplicate>,
+  req: HttpRequest,
 ) -> Result<Json<AppResponse<()>>> {
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
   state
@@ -1471,6 +2335,18 @@ async fn post_published_duplicate_handler(
     .enforce_action(&uid, &workspace_id.to_string(), Action::Write)
     .await?;
   let params = params.into_inner();
+  let published_view_id = Uuid::parse_str(&params.published_view_id).map_err(AppError::from)?;
+  enforce_published_view_access(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    &published_view_id,
+    &PublishedViewReader {
+      user_uuid: Some(*user_uuid),
+      password: published_view_password(&req),
+      client_ip: client_ip(&req),
+    },
+  )
+  .await?;
   biz::workspace::publish_dup::duplicate_published_collab_to_workspace(
     &state.pg_pool,
     state.bucket_client.clone(),
@@ -1490,6 +2366,7 @@ async fn list_published_collab_info_handler(
   state: Data<AppState>,
 ) -> Result<Json<AppResponse<Vec<PublishInfoView>>>> {
   let publish_infos = biz::workspace::publish::list_collab_publish_info(
+    &state.pg_pool,
     state.published_collab_store.as_ref(),
     &state.collab_access_control_storage,
     &workspace_id.into_inner(),
@@ -1499,6 +2376,23 @@ async fn list_published_collab_info_handler(
   Ok(Json(AppResponse::Ok().with_data(publish_infos)))
 }
 
+async fn get_published_view_analytics_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  query: web::Query<QueryPublishedViewAnalytics>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<Vec<PublishedViewAnalytics>>> {
+  let workspace_id = workspace_id.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let analytics =
+    get_published_view_analytics(&state.pg_pool, &workspace_id, query.into_inner()).await?;
+  Ok(Json(AppResponse::Ok().with_data(analytics)))
+}
+
 // Deprecated since 0.7.4
 async fn get_published_collab_info_handler(
   view_id: web::Path<Uuid>,
@@ -1531,8 +2425,16 @@ async fn get_published_collab_comment_handler(
   view_id: web::Path<Uuid>,
   optional_user_uuid: OptionalUserUuid,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<GlobalComments>> {
   let view_id = view_id.into_inner();
+  enforce_published_view_access(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    &view_id,
+    &published_view_reader(&req, &optional_user_uuid),
+  )
+  .await?;
   let comments =
     get_comments_on_published_view(&state.pg_pool, &view_id, &optional_user_uuid).await?;
   let resp = GlobalComments { comments };
@@ -1544,9 +2446,21 @@ async fn post_published_collab_comment_handler(
   view_id: web::Path<Uuid>,
   state: Data<AppState>,
   data: Json<CreateGlobalCommentParams>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<()>> {
   let view_id = view_id.into_inner();
-  create_comment_on_published_view(
+  enforce_published_view_access(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    &view_id,
+    &PublishedViewReader {
+      user_uuid: Some(*user_uuid),
+      password: published_view_password(&req),
+      client_ip: client_ip(&req),
+    },
+  )
+  .await?;
+  let is_pending_approval = create_comment_on_published_view(
     &state.pg_pool,
     &view_id,
     &data.reply_comment_id,
@@ -1554,6 +2468,165 @@ async fn post_published_collab_comment_handler(
     &user_uuid,
   )
   .await?;
+  if let Some(workspace_id) =
+    database::publish::select_workspace_id_for_published_view(&state.pg_pool, &view_id).await?
+  {
+    queue_webhook_event(
+      &state.redis_connection_manager,
+      &workspace_id,
+      WebhookEvent::CommentCreated,
+      serde_json::json!({
+        "view_id": view_id,
+        "content": data.content,
+        "reply_comment_id": data.reply_comment_id,
+        "user_uuid": *user_uuid,
+        "is_pending_approval": is_pending_approval,
+      }),
+    )
+    .await;
+  }
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn approve_published_collab_comment_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<()>> {
+  let (view_id, comment_id) = path_param.into_inner();
+  approve_comment_on_published_view(&state.pg_pool, &view_id, &comment_id, &user_uuid).await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn get_published_collab_comment_settings_handler(
+  view_id: web::Path<Uuid>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<PublishedViewCommentSettings>> {
+  let settings = get_published_view_comment_settings(&state.pg_pool, &view_id).await?;
+  Ok(Json(AppResponse::Ok().with_data(settings)))
+}
+
+async fn put_published_collab_comment_settings_handler(
+  user_uuid: UserUuid,
+  view_id: web::Path<Uuid>,
+  state: Data<AppState>,
+  payload: Json<PublishedViewCommentSettings>,
+) -> Result<JsonAppResponse<()>> {
+  update_published_view_comment_settings(&state.pg_pool, &view_id, &user_uuid, &payload).await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn get_workspace_comment_moderation_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<CommentModeration>> {
+  let workspace_id = workspace_id.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let moderation = get_workspace_comment_moderation(&state.pg_pool, &workspace_id).await?;
+  Ok(Json(AppResponse::Ok().with_data(moderation)))
+}
+
+async fn put_workspace_comment_moderation_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  state: Data<AppState>,
+  payload: Json<CommentModeration>,
+) -> Result<JsonAppResponse<CommentModeration>> {
+  let workspace_id = workspace_id.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let moderation =
+    update_workspace_comment_moderation(&state.pg_pool, &workspace_id, payload.into_inner())
+      .await?;
+  Ok(Json(AppResponse::Ok().with_data(moderation)))
+}
+
+async fn list_banned_commenters_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  state: Data<AppState>,
+) -> Result<JsonAppResponse<Vec<BannedCommenter>>> {
+  let workspace_id = workspace_id.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let bans = list_banned_commenters(&state.pg_pool, &workspace_id).await?;
+  Ok(Json(AppResponse::Ok().with_data(bans)))
+}
+
+async fn ban_commenter_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  state: Data<AppState>,
+  payload: Json<BanCommenterParams>,
+  req: HttpRequest,
+) -> Result<JsonAppResponse<()>> {
+  let (workspace_id, banned_user_uuid) = path_param.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  if banned_user_uuid == *user_uuid {
+    return Err(AppError::InvalidRequest("You can't ban yourself".to_string()).into());
+  }
+  ban_commenter(
+    &state.pg_pool,
+    &workspace_id,
+    uid,
+    &banned_user_uuid,
+    &payload,
+  )
+  .await?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::CommenterBanned,
+    Some(&banned_user_uuid.to_string()),
+    serde_json::json!({
+      "reason": payload.reason,
+      "delete_comments": payload.delete_comments,
+    }),
+  )
+  .await;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn unban_commenter_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> Result<JsonAppResponse<()>> {
+  let (workspace_id, banned_user_uuid) = path_param.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  unban_commenter(&state.pg_pool, &workspace_id, &banned_user_uuid).await?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::CommenterUnbanned,
+    Some(&banned_user_uuid.to_string()),
+    serde_json::json!({}),
+  )
+  .await;
   Ok(Json(AppResponse::Ok()))
 }
 
@@ -1571,9 +2644,18 @@ async fn delete_published_collab_comment_handler(
 async fn get_published_collab_reaction_handler(
   view_id: web::Path<Uuid>,
   query: web::Query<GetReactionQueryParams>,
+  optional_user_uuid: OptionalUserUuid,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<Reactions>> {
   let view_id = view_id.into_inner();
+  enforce_published_view_access(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    &view_id,
+    &published_view_reader(&req, &optional_user_uuid),
+  )
+  .await?;
   let reactions =
     get_reactions_on_published_view(&state.pg_pool, &view_id, &query.comment_id).await?;
   let resp = Reactions { reactions };
@@ -1585,8 +2667,20 @@ async fn post_published_collab_reaction_handler(
   view_id: web::Path<Uuid>,
   data: Json<CreateReactionParams>,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<JsonAppResponse<()>> {
   let view_id = view_id.into_inner();
+  enforce_published_view_access(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    &view_id,
+    &PublishedViewReader {
+      user_uuid: Some(*user_uuid),
+      password: published_view_password(&req),
+      client_ip: client_ip(&req),
+    },
+  )
+  .await?;
   create_reaction_on_comment(
     &state.pg_pool,
     &data.comment_id,
@@ -1618,8 +2712,18 @@ async fn post_publish_collabs_handler(
   user_uuid: UserUuid,
   payload: Payload,
   state: Data<AppState>,
+  req: HttpRequest,
 ) -> Result<Json<AppResponse<()>>> {
   let workspace_id = workspace_id.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_permission(
+      &uid,
+      &workspace_id.to_string(),
+      AFWorkspacePermission::Publish,
+    )
+    .await?;
 
   let mut accumulator = Vec::<PublishCollabItem<serde_json::Value, Vec<u8>>>::new();
   let mut payload_reader: PayloadReader = PayloadReader::new(payload);
@@ -1659,10 +2763,44 @@ async fn post_publish_collabs_handler(
       AppError::InvalidRequest(String::from("did not receive any data to publish")).into(),
     );
   }
+  let published = accumulator
+    .iter()
+    .map(|item| (item.meta.view_id, item.meta.publish_name.clone()))
+    .collect::<Vec<_>>();
   state
     .published_collab_store
     .publish_collabs(accumulator, &workspace_id, &user_uuid)
     .await?;
+  let activities = published
+    .iter()
+    .map(|(view_id, publish_name)| WorkspaceActivityParams {
+      actor_uid: Some(uid),
+      kind: WorkspaceActivityKind::PagePublished,
+      object_id: view_id.to_string(),
+      metadata: serde_json::json!({ "publish_name": publish_name }),
+    })
+    .collect::<Vec<_>>();
+  record_workspace_activities(&state.pg_pool, &workspace_id, &activities).await;
+  let source = AuditSource::from_request(&req);
+  for (view_id, publish_name) in published {
+    record_audit_log(
+      &state.pg_pool,
+      &source,
+      Some(&workspace_id),
+      Some(uid),
+      AuditAction::PagePublished,
+      Some(&view_id.to_string()),
+      serde_json::json!({ "publish_name": publish_name }),
+    )
+    .await;
+    queue_webhook_event(
+      &state.redis_connection_manager,
+      &workspace_id,
+      WebhookEvent::PagePublished,
+      serde_json::json!({ "view_id": view_id, "publish_name": publish_name }),
+    )
+    .await;
+  }
   Ok(Json(AppResponse::Ok()))
 }
 
@@ -1676,6 +2814,15 @@ async fn patch_published_collabs_handler(
   if patches.is_empty() {
     return Err(AppError::InvalidRequest("No patches provided".to_string()).into());
   }
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_permission(
+      &uid,
+      &workspace_id.to_string(),
+      AFWorkspacePermission::Publish,
+    )
+    .await?;
   state
     .published_collab_store
     .patch_collabs(&workspace_id, &user_uuid, &patches)
@@ -1688,16 +2835,46 @@ async fn delete_published_collabs_handler(
   user_uuid: UserUuid,
   state: Data<AppState>,
   view_ids: Json<Vec<Uuid>>,
+  req: HttpRequest,
 ) -> Result<Json<AppResponse<()>>> {
   let workspace_id = workspace_id.into_inner();
   let view_ids = view_ids.into_inner();
   if view_ids.is_empty() {
     return Err(AppError::InvalidRequest("No view_ids provided".to_string()).into());
   }
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_permission(
+      &uid,
+      &workspace_id.to_string(),
+      AFWorkspacePermission::Publish,
+    )
+    .await?;
   state
     .published_collab_store
     .unpublish_collabs(&workspace_id, &view_ids, &user_uuid)
     .await?;
+  let source = AuditSource::from_request(&req);
+  for view_id in view_ids {
+    record_audit_log(
+      &state.pg_pool,
+      &source,
+      Some(&workspace_id),
+      Some(uid),
+      AuditAction::PageUnpublished,
+      Some(&view_id.to_string()),
+      serde_json::json!({}),
+    )
+    .await;
+    queue_webhook_event(
+      &state.redis_connection_manager,
+      &workspace_id,
+      WebhookEvent::PageUnpublished,
+      serde_json::json!({ "view_id": view_id }),
+    )
+    .await;
+  }
   Ok(Json(AppResponse::Ok()))
 }
 
@@ -1764,7 +2941,11 @@ async fn get_workspace_usage_handler(
   let uid = state.user_cache.get_user_uid(&user_uuid).await?;
   state
     .workspace_access_control
-    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .enforce_permission(
+      &uid,
+      &workspace_id.to_string(),
+      AFWorkspacePermission::ManageBilling,
+    )
     .await?;
   let res =
     biz::workspace::ops::get_workspace_document_total_bytes(&state.pg_pool, &workspace_id).await?;
@@ -1868,6 +3049,26 @@ async fn get_trash_views_handler(
   Ok(Json(AppResponse::Ok().with_data(section_items)))
 }
 
+/// The password of a protected published view is sent in a header.
+fn published_view_password(req: &HttpRequest) -> Option<String> {
+  req
+    .headers()
+    .get(PUBLISHED_VIEW_PASSWORD_HEADER)
+    .and_then(|value| value.to_str().ok())
+    .map(|value| value.to_string())
+}
+
+pub(crate) fn published_view_reader(
+  req: &HttpRequest,
+  optional_user_uuid: &OptionalUserUuid,
+) -> PublishedViewReader {
+  PublishedViewReader {
+    user_uuid: optional_user_uuid.as_uuid(),
+    password: published_view_password(req),
+    client_ip: client_ip(req),
+  }
+}
+
 async fn get_workspace_publish_outline_handler(
   publish_namespace: web::Path<String>,
   state: Data<AppState>,
@@ -1999,6 +3200,519 @@ async fn list_database_row_details_handler(
   Ok(Json(AppResponse::Ok().with_data(db_rows)))
 }
 
+async fn create_api_token_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  payload: Json<CreateApiTokenParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<CreatedApiToken>>> {
+  let params = payload.into_inner();
+  params.validate().map_err(AppError::from)?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let api_token = workspace::api_token::create_api_token(
+    &state.pg_pool,
+    state.workspace_access_control.clone(),
+    uid,
+    &workspace_id,
+    params,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(api_token)))
+}
+
+async fn list_api_tokens_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<RepeatedApiToken>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_action(&uid, &workspace_id.to_string(), Action::Read)
+    .await?;
+  let items = workspace::api_token::list_api_tokens(&state.pg_pool, uid, &workspace_id).await?;
+  Ok(Json(
+    AppResponse::Ok().with_data(RepeatedApiToken { items }),
+  ))
+}
+
+async fn revoke_api_token_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let (workspace_id, token_id) = path_param.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  workspace::api_token::revoke_api_token(
+    &state.pg_pool,
+    state.workspace_access_control.clone(),
+    uid,
+    &workspace_id,
+    &token_id,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn create_webhook_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  payload: Json<CreateWebhookParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<CreatedWebhook>>> {
+  let params = payload.into_inner();
+  params.validate().map_err(AppError::from)?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let webhook =
+    biz::webhook::ops::create_webhook(&state.pg_pool, uid, &workspace_id, params).await?;
+  Ok(Json(AppResponse::Ok().with_data(webhook)))
+}
+
+async fn list_webhooks_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<RepeatedWebhook>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let items = biz::webhook::ops::list_webhooks(&state.pg_pool, &workspace_id).await?;
+  Ok(Json(AppResponse::Ok().with_data(RepeatedWebhook { items })))
+}
+
+async fn patch_webhook_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  payload: Json<UpdateWebhookParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<Webhook>>> {
+  let (workspace_id, webhook_id) = path_param.into_inner();
+  let params = payload.into_inner();
+  params.validate().map_err(AppError::from)?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let webhook =
+    biz::webhook::ops::patch_webhook(&state.pg_pool, &workspace_id, &webhook_id, params).await?;
+  Ok(Json(AppResponse::Ok().with_data(webhook)))
+}
+
+async fn delete_webhook_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let (workspace_id, webhook_id) = path_param.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  biz::webhook::ops::remove_webhook(&state.pg_pool, &workspace_id, &webhook_id).await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn list_webhook_deliveries_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  query: web::Query<QueryWebhookDeliveries>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<RepeatedWebhookDelivery>>> {
+  let (workspace_id, webhook_id) = path_param.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let items = biz::webhook::ops::list_webhook_deliveries(
+    &state.pg_pool,
+    &workspace_id,
+    &webhook_id,
+    query.limit,
+  )
+  .await?;
+  Ok(Json(
+    AppResponse::Ok().with_data(RepeatedWebhookDelivery { items }),
+  ))
+}
+
+async fn get_audit_log_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  query: web::Query<QueryAuditLogParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<RepeatedAuditLogEntry>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let entries =
+    biz::audit_log::ops::get_audit_log(&state.pg_pool, &workspace_id, &query.into_inner()).await?;
+  Ok(Json(AppResponse::Ok().with_data(entries)))
+}
+
+async fn get_workspace_activity_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  query: web::Query<QueryWorkspaceActivityParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<RepeatedWorkspaceActivity>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Member)
+    .await?;
+  let activities = get_workspace_activities(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    &state.collab_access_control,
+    uid,
+    &workspace_id,
+    &query.into_inner(),
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(activities)))
+}
+
+/// Returns the entries matching the filters as a CSV file.
+async fn export_audit_log_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  query: web::Query<QueryAuditLogParams>,
+  state: Data<AppState>,
+) -> Result<HttpResponse> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let csv =
+    biz::audit_log::ops::export_audit_log_csv(&state.pg_pool, &workspace_id, query.into_inner())
+      .await?;
+  Ok(
+    HttpResponse::Ok()
+      .content_type("text/csv; charset=utf-8")
+      .insert_header((
+        "Content-Disposition",
+        format!("attachment; filename=\"audit-log-{}.csv\"", workspace_id),
+      ))
+      .body(csv),
+  )
+}
+
+async fn list_custom_roles_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<RepeatedCustomRole>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let items = workspace::role::list_custom_roles(&state.pg_pool, &workspace_id).await?;
+  Ok(Json(
+    AppResponse::Ok().with_data(RepeatedCustomRole { items }),
+  ))
+}
+
+async fn create_custom_role_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  payload: Json<CreateCustomRoleParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<CustomRole>>> {
+  let params = payload.into_inner();
+  params.validate().map_err(AppError::from)?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let role =
+    workspace::role::create_custom_role(&state.pg_pool, uid, &workspace_id, params).await?;
+  Ok(Json(AppResponse::Ok().with_data(role)))
+}
+
+async fn patch_custom_role_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  payload: Json<UpdateCustomRoleParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<CustomRole>>> {
+  let (workspace_id, role_id) = path_param.into_inner();
+  let params = payload.into_inner();
+  params.validate().map_err(AppError::from)?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let role = workspace::role::edit_custom_role(
+    &state.pg_pool,
+    state.workspace_access_control.clone(),
+    &workspace_id,
+    &role_id,
+    params,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok().with_data(role)))
+}
+
+async fn delete_custom_role_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let (workspace_id, role_id) = path_param.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  workspace::role::remove_custom_role(
+    &state.pg_pool,
+    state.workspace_access_control.clone(),
+    &workspace_id,
+    &role_id,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn assign_custom_role_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  payload: Json<AssignCustomRoleParams>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> Result<Json<AppResponse<()>>> {
+  let workspace_id = workspace_id.into_inner();
+  let params = payload.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let email = params.email.clone();
+  let role_id = params.role_id;
+  workspace::role::assign_custom_role(
+    &state.pg_pool,
+    state.workspace_access_control.clone(),
+    &workspace_id,
+    params,
+  )
+  .await?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::MemberRoleUpdated,
+    Some(&email),
+    serde_json::json!({ "custom_role_id": role_id }),
+  )
+  .await;
+  Ok(Json(AppResponse::Ok()))
+}
+
+/// Returns what the current user is allowed to do in the workspace.
+async fn get_workspace_permissions_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<WorkspacePermissions>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  let permissions =
+    workspace::role::get_workspace_permissions(&state.pg_pool, &workspace_id, uid).await?;
+  Ok(Json(AppResponse::Ok().with_data(permissions)))
+}
+
+/// Any member of the workspace can see its groups, so they can share pages with them.
+async fn list_workspace_groups_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<RepeatedWorkspaceGroup>>> {
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_action(&uid, &workspace_id.to_string(), Action::Read)
+    .await?;
+  let items = workspace::group::list_workspace_groups(&state.pg_pool, &workspace_id).await?;
+  Ok(Json(
+    AppResponse::Ok().with_data(RepeatedWorkspaceGroup { items }),
+  ))
+}
+
+async fn create_workspace_group_handler(
+  user_uuid: UserUuid,
+  workspace_id: web::Path<Uuid>,
+  payload: Json<CreateWorkspaceGroupParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<WorkspaceGroup>>> {
+  let params = payload.into_inner();
+  params.validate().map_err(AppError::from)?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let group =
+    workspace::group::create_workspace_group(&state.pg_pool, uid, &workspace_id, params).await?;
+  Ok(Json(AppResponse::Ok().with_data(group)))
+}
+
+async fn patch_workspace_group_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  payload: Json<UpdateWorkspaceGroupParams>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<WorkspaceGroup>>> {
+  let (workspace_id, group_id) = path_param.into_inner();
+  let params = payload.into_inner();
+  params.validate().map_err(AppError::from)?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let group =
+    workspace::group::rename_workspace_group(&state.pg_pool, &workspace_id, &group_id, params)
+      .await?;
+  Ok(Json(AppResponse::Ok().with_data(group)))
+}
+
+async fn delete_workspace_group_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<()>>> {
+  let (workspace_id, group_id) = path_param.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  workspace::group::remove_workspace_group(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    state.workspace_access_control.clone(),
+    &state.collab_access_control,
+    uid,
+    &workspace_id,
+    &group_id,
+  )
+  .await?;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn list_workspace_group_members_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  state: Data<AppState>,
+) -> Result<Json<AppResponse<RepeatedWorkspaceGroupMember>>> {
+  let (workspace_id, group_id) = path_param.into_inner();
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_action(&uid, &workspace_id.to_string(), Action::Read)
+    .await?;
+  let items =
+    workspace::group::list_workspace_group_members(&state.pg_pool, &workspace_id, &group_id)
+      .await?;
+  Ok(Json(
+    AppResponse::Ok().with_data(RepeatedWorkspaceGroupMember { items }),
+  ))
+}
+
+async fn add_workspace_group_members_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  payload: Json<WorkspaceGroupMembersParams>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> Result<Json<AppResponse<()>>> {
+  let (workspace_id, group_id) = path_param.into_inner();
+  let params = payload.into_inner();
+  params.validate().map_err(AppError::from)?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  workspace::group::add_workspace_group_members(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    state.workspace_access_control.clone(),
+    &state.collab_access_control,
+    uid,
+    &workspace_id,
+    &group_id,
+    &params.emails,
+  )
+  .await?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::GroupMembersUpdated,
+    Some(&group_id.to_string()),
+    serde_json::json!({ "added": params.emails }),
+  )
+  .await;
+  Ok(Json(AppResponse::Ok()))
+}
+
+async fn remove_workspace_group_members_handler(
+  user_uuid: UserUuid,
+  path_param: web::Path<(Uuid, Uuid)>,
+  payload: Json<WorkspaceGroupMembersParams>,
+  state: Data<AppState>,
+  req: HttpRequest,
+) -> Result<Json<AppResponse<()>>> {
+  let (workspace_id, group_id) = path_param.into_inner();
+  let params = payload.into_inner();
+  params.validate().map_err(AppError::from)?;
+  let uid = state.user_cache.get_user_uid(&user_uuid).await?;
+  state
+    .workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  workspace::group::remove_workspace_group_members(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    state.workspace_access_control.clone(),
+    &state.collab_access_control,
+    uid,
+    &workspace_id,
+    &group_id,
+    &params.emails,
+  )
+  .await?;
+  record_audit_log(
+    &state.pg_pool,
+    &AuditSource::from_request(&req),
+    Some(&workspace_id),
+    Some(uid),
+    AuditAction::GroupMembersUpdated,
+    Some(&group_id.to_string()),
+    serde_json::json!({ "removed": params.emails }),
+  )
+  .await;
+  Ok(Json(AppResponse::Ok()))
+}
+
 #[inline]
 async fn parser_realtime_msg(
   payload: Bytes,
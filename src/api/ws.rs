@@ -1,7 +1,7 @@
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use actix::Addr;
+use actix::{Actor, ActorContext, Addr, AsyncContext, StreamHandler};
 use actix_http::header::AUTHORIZATION;
 use actix_web::web::{Data, Path, Payload};
 use actix_web::{get, web, HttpRequest, HttpResponse, Result, Scope};
@@ -10,23 +10,32 @@ use secrecy::Secret;
 use semver::Version;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::Sender;
+use tokio_stream::wrappers::ReceiverStream;
 use tracing::{debug, error, instrument, trace};
 
 use app_error::AppError;
 use appflowy_collaborate::actix_ws::client::rt_client::RealtimeClient;
 use appflowy_collaborate::actix_ws::server::RealtimeServerActor;
 use appflowy_collaborate::collab::storage::CollabAccessControlStorage;
-use authentication::jwt::{authorization_from_token, UserUuid};
+use authentication::jwt::{authorization_from_token, OptionalUserUuid, UserUuid};
 use collab_rt_entity::user::{AFUserChange, RealtimeUser, UserMessage};
 use collab_rt_entity::RealtimeMessage;
+use database::publish::select_live_published_view_id;
+use shared_entity::dto::publish_dto::PublishedViewUpdate;
 use shared_entity::response::AppResponseError;
 
+use crate::api::workspace::published_view_reader;
+use crate::biz::workspace::publish_access::enforce_published_view_access_by_name;
 use crate::state::AppState;
 
 pub fn ws_scope() -> Scope {
   web::scope("/ws")
     //.service(establish_ws_connection)
     .service(web::resource("/v1").route(web::get().to(establish_ws_connection_v1)))
+    .service(
+      web::resource("/v1/published/{publish_namespace}/{publish_name}")
+        .route(web::get().to(subscribe_published_view_handler)),
+    )
 }
 const MAX_FRAME_SIZE: usize = 65_536; // 64 KiB
 
@@ -146,7 +155,15 @@ async fn start_connect(
       );
 
       // Receive user change notifications and send them to the client.
-      listen_on_user_change(state, uid, tx);
+      listen_on_user_change(state, uid, tx.clone());
+      // Receive the messages of the chats the user participates in.
+      listen_on_chat_message_change(state, uid, tx.clone());
+      // Receive the access requests to approve and the answers to the requests of the user.
+      listen_on_access_request_change(state, uid, tx.clone());
+      // Receive the comments on the documents of the workspaces of the user.
+      listen_on_document_comment_change(state, uid, tx.clone());
+      // Receive the new notifications of the user.
+      listen_on_notification(state, uid, tx);
 
       match ws::WsResponseBuilder::new(client, request, payload)
         .frame_size(MAX_FRAME_SIZE * 2)
@@ -194,6 +211,62 @@ fn listen_on_user_change(state: &Data<AppState>, uid: i64, tx: Sender<RealtimeMe
   });
 }
 
+fn listen_on_chat_message_change(state: &Data<AppState>, uid: i64, tx: Sender<RealtimeMessage>) {
+  let mut chat_message_recv = state.pg_listeners.subscribe_chat_message_change(uid);
+  actix::spawn(async move {
+    while let Some(change) = chat_message_recv.recv().await {
+      trace!("Receive chat message change: {:?}", change);
+      let msg = UserMessage::ChatMessageChange(change);
+      if tx.send(RealtimeMessage::User(msg)).await.is_err() {
+        break;
+      }
+    }
+  });
+}
+
+fn listen_on_access_request_change(state: &Data<AppState>, uid: i64, tx: Sender<RealtimeMessage>) {
+  let mut access_request_recv = state.pg_listeners.subscribe_access_request_change(uid);
+  actix::spawn(async move {
+    while let Some(change) = access_request_recv.recv().await {
+      trace!("Receive access request change: {:?}", change);
+      let msg = UserMessage::AccessRequestChange(change);
+      if tx.send(RealtimeMessage::User(msg)).await.is_err() {
+        break;
+      }
+    }
+  });
+}
+
+fn listen_on_document_comment_change(
+  state: &Data<AppState>,
+  uid: i64,
+  tx: Sender<RealtimeMessage>,
+) {
+  let mut document_comment_recv = state.pg_listeners.subscribe_document_comment_change(uid);
+  actix::spawn(async move {
+    while let Some(change) = document_comment_recv.recv().await {
+      trace!("Receive document comment change: {:?}", change);
+      let msg = UserMessage::DocumentCommentChange(change);
+      if tx.send(RealtimeMessage::User(msg)).await.is_err() {
+        break;
+      }
+    }
+  });
+}
+
+fn listen_on_notification(state: &Data<AppState>, uid: i64, tx: Sender<RealtimeMessage>) {
+  let mut notification_recv = state.pg_listeners.subscribe_notification(uid);
+  actix::spawn(async move {
+    while let Some(notification) = notification_recv.recv().await {
+      trace!("Receive notification: {:?}", notification);
+      let msg = UserMessage::Notification(notification);
+      if tx.send(RealtimeMessage::User(msg)).await.is_err() {
+        break;
+      }
+    }
+  });
+}
+
 struct ConnectInfo {
   access_token: String,
   client_version: Version,
@@ -262,3 +335,96 @@ impl ConnectInfo {
     })
   }
 }
+
+/// Read-only websocket of a live published view. The reader receives a [PublishedViewUpdate] each
+/// time the published blob changes. As for the other published endpoints, no authentication is
+/// required unless the view is restricted to some readers.
+#[instrument(skip_all, err)]
+async fn subscribe_published_view_handler(
+  request: HttpRequest,
+  payload: Payload,
+  path: Path<(String, String)>,
+  optional_user_uuid: OptionalUserUuid,
+  state: Data<AppState>,
+) -> Result<HttpResponse> {
+  let (publish_namespace, publish_name) = path.into_inner();
+  enforce_published_view_access_by_name(
+    &state.pg_pool,
+    &state.redis_connection_manager,
+    &publish_namespace,
+    &publish_name,
+    &published_view_reader(&request, &optional_user_uuid),
+  )
+  .await?;
+  let view_id = select_live_published_view_id(&state.pg_pool, &publish_namespace, &publish_name)
+    .await?
+    .ok_or_else(|| {
+      AppError::RecordNotFound(format!(
+        "{}/{} is not published live",
+        publish_namespace, publish_name
+      ))
+    })?;
+  let watcher = PublishedViewWatcher {
+    updates: Some(state.pg_listeners.subscribe_published_view_update(view_id)),
+    hb: Instant::now(),
+    heartbeat_interval: Duration::from_secs(state.config.websocket.heartbeat_interval as u64),
+    client_timeout: Duration::from_secs(state.config.websocket.client_timeout as u64),
+  };
+  ws::start(watcher, &request, payload)
+}
+
+struct PublishedViewWatcher {
+  updates: Option<mpsc::Receiver<PublishedViewUpdate>>,
+  hb: Instant,
+  heartbeat_interval: Duration,
+  client_timeout: Duration,
+}
+
+impl Actor for PublishedViewWatcher {
+  type Context = ws::WebsocketContext<Self>;
+
+  fn started(&mut self, ctx: &mut Self::Context) {
+    if let Some(updates) = self.updates.take() {
+      ctx.add_stream(ReceiverStream::new(updates));
+    }
+    ctx.run_interval(self.heartbeat_interval, |act, ctx| {
+      if Instant::now().duration_since(act.hb) > act.client_timeout {
+        ctx.stop();
+        return;
+      }
+      ctx.ping(b"");
+    });
+  }
+}
+
+impl StreamHandler<PublishedViewUpdate> for PublishedViewWatcher {
+  fn handle(&mut self, update: PublishedViewUpdate, ctx: &mut Self::Context) {
+    match serde_json::to_string(&update) {
+      Ok(text) => ctx.text(text),
+      Err(err) => error!("Failed to serialize published view update: {}", err),
+    }
+  }
+
+  fn finished(&mut self, ctx: &mut Self::Context) {
+    ctx.stop();
+  }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for PublishedViewWatcher {
+  fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+    match msg {
+      Ok(ws::Message::Ping(msg)) => {
+        self.hb = Instant::now();
+        ctx.pong(&msg);
+      },
+      Ok(ws::Message::Pong(_)) => self.hb = Instant::now(),
+      Ok(ws::Message::Close(reason)) => {
+        ctx.close(reason);
+        ctx.stop();
+      },
+      // The channel is read-only, the other messages are ignored.
+      Ok(_) => {},
+      Err(_) => ctx.stop(),
+    }
+  }
+}
@@ -37,7 +37,13 @@ use tracing::{error, info, warn};
 
 use appflowy_ai_client::client::AppFlowyAIClient;
 use appflowy_collaborate::actix_ws::server::RealtimeServerActor;
+use appflowy_collaborate::collab::document_mention::DocumentMentionNotifier;
+use appflowy_collaborate::collab::live_publish::LivePublisher;
+use appflowy_collaborate::collab::persistence_hook::PersistenceHook;
+use appflowy_collaborate::collab::reminder::{ReminderOwnerRecorder, ReminderScheduler};
 use appflowy_collaborate::collab::storage::CollabStorageImpl;
+use appflowy_collaborate::collab::view_permission::ViewPermissionRefresher;
+use appflowy_collaborate::collab::workspace_activity::WorkspaceActivityRecorder;
 use appflowy_collaborate::command::{CLCommandReceiver, CLCommandSender};
 use appflowy_collaborate::indexer::IndexerProvider;
 use appflowy_collaborate::snapshot::SnapshotControl;
@@ -55,8 +61,12 @@ use crate::api::data_import::data_import_scope;
 use crate::api::file_storage::file_storage_scope;
 use crate::api::history::history_scope;
 use crate::api::metrics::metrics_scope;
+use crate::api::notification::notification_scope;
+use crate::api::oauth::oauth_scope;
+use crate::api::scim::scim_scope;
 use crate::api::search::search_scope;
 use crate::api::server_info::server_info_scope;
+use crate::api::share_link::share_link_scope;
 use crate::api::template::template_scope;
 use crate::api::user::user_scope;
 use crate::api::workspace::{collab_scope, workspace_scope};
@@ -65,10 +75,12 @@ use crate::biz::pg_listener::PgListeners;
 use crate::biz::workspace::publish::{
   PublishedCollabPostgresStore, PublishedCollabS3StoreWithPostgresFallback, PublishedCollabStore,
 };
+use crate::biz::workspace::publish_analytics::PublishedViewAnalyticsRecorder;
 use crate::config::config::{
   Config, DatabaseSetting, GoTrueSetting, PublishedCollabStorageBackend, S3Setting,
 };
 use crate::mailer::AFCloudMailer;
+use crate::middleware::api_token_mw::ApiTokenMiddleware;
 use crate::middleware::metrics_mw::MetricsMiddleware;
 use crate::middleware::request_id::RequestIdMiddleware;
 use crate::self_signed::create_self_signed_certificate;
@@ -136,6 +148,7 @@ pub async fn run_actix_server(
     config.collab.edit_state_max_count,
     config.collab.edit_state_max_secs,
     state.indexer_provider.clone(),
+    state.persistence_hooks.clone(),
   )
   .await
   .unwrap();
@@ -151,6 +164,7 @@ pub async fn run_actix_server(
         SessionMiddleware::builder(redis_store.clone(), key.clone())
           .build(),
       )
+      .wrap(ApiTokenMiddleware)
       .wrap(RequestIdMiddleware)
       .service(server_info_scope())
       .service(user_scope())
@@ -166,6 +180,10 @@ pub async fn run_actix_server(
       .service(template_scope())
       .service(data_import_scope())
       .service(access_request_scope())
+      .service(oauth_scope())
+      .service(scim_scope())
+      .service(share_link_scope())
+      .service(notification_scope())
       .app_data(Data::new(state.metrics.registry.clone()))
       .app_data(Data::new(state.metrics.request_metrics.clone()))
       .app_data(Data::new(state.metrics.realtime_metrics.clone()))
@@ -249,6 +267,11 @@ pub async fn init_state(config: &Config, rt_cmd_tx: CLCommandSender) -> Result<A
   info!("Setup AppFlowy AI: {}", config.appflowy_ai.url());
   let appflowy_ai_client = AppFlowyAIClient::new(&config.appflowy_ai.url());
   let indexer_provider = IndexerProvider::new(pg_pool.clone(), appflowy_ai_client.clone());
+  let published_view_analytics = Arc::new(PublishedViewAnalyticsRecorder::new(pg_pool.clone()));
+  let activity_recorder = Arc::new(WorkspaceActivityRecorder::new(
+    pg_pool.clone(),
+    redis_conn_manager.clone(),
+  ));
 
   // Pg listeners
   info!("Setting up Pg listeners...");
@@ -265,10 +288,38 @@ pub async fn init_state(config: &Config, rt_cmd_tx: CLCommandSender) -> Result<A
   let user_cache = UserCache::new(pg_pool.clone()).await;
   let collab_access_control: Arc<dyn CollabAccessControl> =
     if config.access_control.is_enabled && config.access_control.enable_collab_access_control {
-      Arc::new(CollabAccessControlImpl::new(access_control.clone()))
+      Arc::new(CollabAccessControlImpl::new(
+        access_control.clone(),
+        pg_pool.clone(),
+      ))
     } else {
       Arc::new(NoOpsCollabAccessControlImpl::new())
     };
+  let persistence_hooks: Vec<Arc<dyn PersistenceHook>> = vec![
+    Arc::new(LivePublisher::new(
+      pg_pool.clone(),
+      s3_client.clone(),
+      Duration::from_secs(config.collab.live_publish_debounce_secs),
+    )),
+    Arc::new(DocumentMentionNotifier::new(
+      pg_pool.clone(),
+      Duration::from_secs(config.collab.mention_notify_debounce_secs),
+    )),
+    Arc::new(ReminderScheduler::new(
+      pg_pool.clone(),
+      Duration::from_secs(config.collab.reminder_sync_debounce_secs),
+    )),
+    Arc::new(ReminderOwnerRecorder::new(
+      pg_pool.clone(),
+      Duration::from_secs(config.collab.reminder_sync_debounce_secs),
+    )),
+    Arc::new(ViewPermissionRefresher::new(
+      pg_pool.clone(),
+      collab_access_control.clone(),
+      Duration::from_secs(config.collab.view_permission_refresh_debounce_secs),
+    )),
+    activity_recorder.clone(),
+  ];
   let workspace_access_control: Arc<dyn WorkspaceAccessControl> =
     if config.access_control.is_enabled && config.access_control.enable_workspace_access_control {
       Arc::new(WorkspaceAccessControlImpl::new(access_control.clone()))
@@ -277,7 +328,10 @@ pub async fn init_state(config: &Config, rt_cmd_tx: CLCommandSender) -> Result<A
     };
   let realtime_access_control: Arc<dyn RealtimeAccessControl> =
     if config.access_control.is_enabled && config.access_control.enable_realtime_access_control {
-      Arc::new(RealtimeCollabAccessControlImpl::new(access_control))
+      Arc::new(RealtimeCollabAccessControlImpl::new(
+        access_control,
+        pg_pool.clone(),
+      ))
     } else {
       Arc::new(NoOpsRealtimeCollabAccessControlImpl::new())
     };
@@ -305,6 +359,7 @@ pub async fn init_state(config: &Config, rt_cmd_tx: CLCommandSender) -> Result<A
     snapshot_control,
     rt_cmd_tx,
     metrics.collab_metrics.clone(),
+    redis_conn_manager.clone(),
   ));
 
   info!(
@@ -342,6 +397,9 @@ pub async fn init_state(config: &Config, rt_cmd_tx: CLCommandSender) -> Result<A
     ai_client: appflowy_ai_client,
     grpc_history_client,
     indexer_provider,
+    persistence_hooks,
+    published_view_analytics,
+    activity_recorder,
   })
 }
 
@@ -1,31 +1,78 @@
 use std::ops::DerefMut;
 use std::sync::Arc;
 
+use crate::biz::notification::ops::create_notifications;
+use crate::biz::workspace::group::add_approved_requester_to_group;
 use crate::mailer::AFCloudMailer;
 use crate::{
   biz::collab::{
     folder_view::{to_dto_view_icon, to_dto_view_layout},
     ops::get_latest_collab_folder,
   },
-  mailer::{WorkspaceAccessRequestApprovedMailerParam, WorkspaceAccessRequestMailerParam},
+  mailer::{
+    WorkspaceAccessRequestApprovedMailerParam, WorkspaceAccessRequestMailerParam,
+    WorkspaceAccessRequestRejectedMailerParam,
+  },
 };
 use access_control::workspace::WorkspaceAccessControl;
 use anyhow::Context;
 use app_error::AppError;
 use appflowy_collaborate::collab::storage::CollabAccessControlStorage;
+use chrono::Duration;
+use collab_folder::Folder;
 use database::{
   access_request::{
-    insert_new_access_request, select_access_request_by_request_id, update_access_request_status,
+    select_access_request_approver_uids, select_access_request_by_request_id,
+    select_access_request_row, select_access_requests, update_pending_access_request_status,
+    upsert_access_request,
   },
   collab::GetCollabOrigin,
   pg_row::AFAccessRequestStatusColumn,
   workspace::upsert_workspace_member_with_txn,
 };
 use database_entity::dto::AFRole;
-use shared_entity::dto::access_request_dto::{AccessRequest, AccessRequestView};
+use shared_entity::dto::access_request_dto::{
+  AccessRequest, AccessRequestItem, AccessRequestView, QueryAccessRequestParams,
+  RepeatedAccessRequestItem,
+};
+use shared_entity::dto::notification_dto::NotificationKind;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+const MAX_ACCESS_REQUEST_REASON_LENGTH: usize = 1000;
+/// A rejected requester can't ask for the same view again before this many hours, so the owners
+/// are not flooded with requests and emails.
+const ACCESS_REQUEST_REJECTION_COOLDOWN_HOURS: i64 = 24;
+
+/// Trims the reason, an empty reason is the same as no reason.
+fn normalize_reason(reason: Option<String>) -> Result<Option<String>, AppError> {
+  let reason = reason
+    .map(|reason| reason.trim().to_string())
+    .filter(|reason| !reason.is_empty());
+  if let Some(reason) = &reason {
+    if reason.chars().count() > MAX_ACCESS_REQUEST_REASON_LENGTH {
+      return Err(AppError::InvalidRequest(format!(
+        "The reason can't be longer than {} characters",
+        MAX_ACCESS_REQUEST_REASON_LENGTH
+      )));
+    }
+  }
+  Ok(reason)
+}
+
+fn to_access_request_view(folder: &Folder, view_id: &Uuid) -> Option<AccessRequestView> {
+  folder
+    .get_view(&view_id.to_string())
+    .map(|v| AccessRequestView {
+      view_id: v.id.clone(),
+      name: v.name.clone(),
+      icon: v.icon.as_ref().map(|icon| to_dto_view_icon(icon.clone())),
+      layout: to_dto_view_layout(&v.layout),
+    })
+}
+
+/// Creates a request to access the view. An expired request can be made again, and so can a
+/// rejected request after a cooldown.
 pub async fn create_access_request(
   pg_pool: &PgPool,
   mailer: AFCloudMailer,
@@ -33,9 +80,43 @@ pub async fn create_access_request(
   workspace_id: Uuid,
   view_id: Uuid,
   uid: i64,
+  reason: Option<String>,
 ) -> Result<Uuid, AppError> {
-  let request_id = insert_new_access_request(pg_pool, workspace_id, view_id, uid).await?;
+  let reason = normalize_reason(reason)?;
+  let request_id = upsert_access_request(
+    pg_pool,
+    workspace_id,
+    view_id,
+    uid,
+    reason.as_deref(),
+    Duration::hours(ACCESS_REQUEST_REJECTION_COOLDOWN_HOURS),
+  )
+  .await?;
   let access_request = select_access_request_by_request_id(pg_pool, request_id).await?;
+  match select_access_request_approver_uids(pg_pool, &workspace_id).await {
+    Ok(approver_uids) => {
+      create_notifications(
+        pg_pool,
+        &approver_uids,
+        &workspace_id,
+        NotificationKind::AccessRequest,
+        Some(&view_id),
+        Some(&request_id.to_string()),
+        Some(uid),
+        serde_json::json!({
+          "requester_name": access_request.requester.name,
+          "workspace_name": access_request.workspace.workspace_name,
+          "reason": reason,
+        }),
+      )
+      .await
+    },
+    Err(err) => tracing::error!(
+      "Failed to get the approvers of the access requests of workspace {}: {}",
+      workspace_id,
+      err
+    ),
+  }
   let cloned_mailer = mailer.clone();
   let approve_url = format!(
     "{}/app/approve-request?request_id={}",
@@ -61,6 +142,7 @@ pub async fn create_access_request(
           workspace_icon_url,
           workspace_member_count: access_request.workspace.member_count.unwrap_or(0),
           approve_url,
+          reason,
         },
       )
       .await
@@ -98,29 +180,69 @@ pub async fn get_access_request(
       .to_string(),
   )
   .await?;
-  let view = folder.get_view(&access_request_with_view_id.view_id.to_string());
-  let access_request_view = view
-    .map(|v| AccessRequestView {
-      view_id: v.id.clone(),
-      name: v.name.clone(),
-      icon: v.icon.as_ref().map(|icon| to_dto_view_icon(icon.clone())),
-      layout: to_dto_view_layout(&v.layout),
-    })
+  let access_request_view = to_access_request_view(&folder, &access_request_with_view_id.view_id)
     .ok_or(AppError::MissingView(format!(
-      "the view {} is missing",
-      access_request_with_view_id.view_id
-    )))?;
+    "the view {} is missing",
+    access_request_with_view_id.view_id
+  )))?;
+  let row = select_access_request_row(pg_pool, access_request_id).await?;
   let access_request = AccessRequest {
     request_id: access_request_with_view_id.request_id,
     workspace: access_request_with_view_id.workspace,
     requester: access_request_with_view_id.requester,
     view: access_request_view,
-    status: access_request_with_view_id.status,
+    status: row.status.into(),
     created_at: access_request_with_view_id.created_at,
+    reason: row.reason,
+    rejection_reason: row.rejection_reason,
+    expires_at: row.expires_at,
   };
   Ok(access_request)
 }
 
+/// Returns the access requests of the workspace, the most recent first. Only the owners of the
+/// workspace can list them.
+pub async fn list_access_requests(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  uid: i64,
+  params: QueryAccessRequestParams,
+) -> Result<RepeatedAccessRequestItem, AppError> {
+  workspace_access_control
+    .enforce_role(&uid, &params.workspace_id.to_string(), AFRole::Owner)
+    .await?;
+  let rows = select_access_requests(
+    pg_pool,
+    &params.workspace_id,
+    params.view_id.as_ref(),
+    params.status.map(AFAccessRequestStatusColumn::from),
+  )
+  .await?;
+  let folder = get_latest_collab_folder(
+    collab_storage,
+    GetCollabOrigin::Server,
+    &params.workspace_id.to_string(),
+  )
+  .await?;
+  let items = rows
+    .into_iter()
+    .map(|row| AccessRequestItem {
+      request_id: row.request_id,
+      view_id: row.view_id,
+      view: to_access_request_view(&folder, &row.view_id),
+      requester: row.requester(),
+      status: row.status.into(),
+      reason: row.reason,
+      rejection_reason: row.rejection_reason,
+      created_at: row.created_at,
+      expires_at: row.expires_at,
+    })
+    .collect();
+  Ok(RepeatedAccessRequestItem { items })
+}
+
+/// Approves or rejects a pending request. The rejection reason is sent to the requester.
 #[allow(clippy::too_many_arguments)]
 pub async fn approve_or_reject_access_request(
   pg_pool: &PgPool,
@@ -130,8 +252,11 @@ pub async fn approve_or_reject_access_request(
   request_id: Uuid,
   uid: i64,
   is_approved: bool,
-) -> Result<(), AppError> {
+  group_id: Option<Uuid>,
+  rejection_reason: Option<String>,
+) -> Result<Uuid, AppError> {
   let access_request = select_access_request_by_request_id(pg_pool, request_id).await?;
+  let workspace_id = access_request.workspace.workspace_id;
   workspace_access_control
     .enforce_role(
       &uid,
@@ -139,8 +264,31 @@ pub async fn approve_or_reject_access_request(
       AFRole::Owner,
     )
     .await?;
+  let rejection_reason = if is_approved {
+    None
+  } else {
+    normalize_reason(rejection_reason)?
+  };
 
   let mut txn = pg_pool.begin().await.context("approving request")?;
+  let status = if is_approved {
+    AFAccessRequestStatusColumn::Approved
+  } else {
+    AFAccessRequestStatusColumn::Rejected
+  };
+  if !update_pending_access_request_status(
+    txn.deref_mut(),
+    request_id,
+    status,
+    rejection_reason.as_deref(),
+  )
+  .await?
+  {
+    return Err(AppError::InvalidRequest(format!(
+      "The access request {} is no longer pending",
+      request_id
+    )));
+  }
   let role = AFRole::Member;
   if is_approved {
     upsert_workspace_member_with_txn(
@@ -157,17 +305,51 @@ pub async fn approve_or_reject_access_request(
         role.clone(),
       )
       .await?;
-    let cloned_mailer = mailer.clone();
+    if let Some(group_id) = &group_id {
+      add_approved_requester_to_group(
+        &mut txn,
+        &access_request.workspace.workspace_id,
+        group_id,
+        access_request.requester.uid,
+      )
+      .await?;
+      workspace_access_control
+        .insert_group_member(&access_request.requester.uid, group_id)
+        .await?;
+    }
+  }
+  txn.commit().await.context("committing transaction")?;
+
+  let kind = if is_approved {
+    NotificationKind::AccessRequestApproved
+  } else {
+    NotificationKind::AccessRequestRejected
+  };
+  create_notifications(
+    pg_pool,
+    &[access_request.requester.uid],
+    &workspace_id,
+    kind,
+    Some(&access_request.view_id),
+    Some(&request_id.to_string()),
+    Some(uid),
+    serde_json::json!({
+      "workspace_name": access_request.workspace.workspace_name,
+      "reason": rejection_reason,
+    }),
+  )
+  .await;
+
+  // use default icon until we have workspace icon
+  let workspace_icon_url =
+    "https://miro.medium.com/v2/resize:fit:2400/1*mTPfm7CwU31-tLhtLNkyJw.png".to_string();
+  if is_approved {
     let launch_workspace_url = format!(
       "{}/app/{}",
       appflowy_web_url, &access_request.workspace.workspace_id
     );
-
-    // use default icon until we have workspace icon
-    let workspace_icon_url =
-      "https://miro.medium.com/v2/resize:fit:2400/1*mTPfm7CwU31-tLhtLNkyJw.png".to_string();
     tokio::spawn(async move {
-      if let Err(err) = cloned_mailer
+      if let Err(err) = mailer
         .send_workspace_access_request_approval_notification(
           &access_request.requester.name,
           &access_request.requester.email,
@@ -186,13 +368,27 @@ pub async fn approve_or_reject_access_request(
         );
       };
     });
-  }
-  let status = if is_approved {
-    AFAccessRequestStatusColumn::Approved
   } else {
-    AFAccessRequestStatusColumn::Rejected
-  };
-  update_access_request_status(txn.deref_mut(), request_id, status).await?;
-  txn.commit().await.context("committing transaction")?;
-  Ok(())
+    tokio::spawn(async move {
+      if let Err(err) = mailer
+        .send_workspace_access_request_rejection_notification(
+          &access_request.requester.name,
+          &access_request.requester.email,
+          WorkspaceAccessRequestRejectedMailerParam {
+            workspace_name: access_request.workspace.workspace_name,
+            workspace_icon_url,
+            workspace_member_count: access_request.workspace.member_count.unwrap_or(0),
+            reason: rejection_reason,
+          },
+        )
+        .await
+      {
+        tracing::error!(
+          "Failed to send access request rejected notification email: {:?}",
+          err
+        );
+      };
+    });
+  }
+  Ok(workspace_id)
 }
@@ -0,0 +1,35 @@
+use anyhow::anyhow;
+use app_error::AppError;
+use database::file::BlobKey;
+use redis::AsyncCommands;
+use serde_json::json;
+
+use crate::state::RedisConnectionManager;
+
+/// Name of the Redis stream consumed by the attachment indexer of appflowy-worker.
+const ATTACHMENT_INDEX_STREAM: &str = "attachment_index_stream";
+
+/// Queues the uploaded file for text extraction, so that its content can be found by search and
+/// used by the chat. The worker skips files which text can't be extracted from.
+pub async fn queue_attachment_indexing(
+  redis_client: &RedisConnectionManager,
+  key: &impl BlobKey,
+  object_id: &str,
+  file_type: &str,
+) -> Result<(), AppError> {
+  // This task will be deserialized into AttachmentIndexTask
+  let task = json!({
+    "workspace_id": key.workspace_id(),
+    "object_id": object_id,
+    "file_id": key.meta_key(),
+    "s3_key": key.object_key(),
+    "file_type": file_type,
+  });
+
+  let _: () = redis_client
+    .clone()
+    .xadd(ATTACHMENT_INDEX_STREAM, "*", &[("task", task.to_string())])
+    .await
+    .map_err(|err| AppError::Internal(anyhow!("Failed to push task to Redis stream: {}", err)))?;
+  Ok(())
+}
@@ -0,0 +1,179 @@
+use std::borrow::Cow;
+
+use actix_web::http::header::USER_AGENT;
+use actix_web::HttpRequest;
+use app_error::AppError;
+use database::audit_log::{insert_audit_log, select_audit_log};
+use shared_entity::dto::audit_log_dto::{
+  AuditAction, AuditLogEntry, QueryAuditLogParams, RepeatedAuditLogEntry,
+};
+use sqlx::PgPool;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::api::util::client_ip;
+
+const DEFAULT_AUDIT_LOG_LIMIT: i64 = 100;
+const MAX_AUDIT_LOG_LIMIT: i64 = 1000;
+/// An export is cut after this many entries. Narrow the time range to export older entries.
+const MAX_AUDIT_LOG_EXPORT_ENTRIES: usize = 100_000;
+
+/// Where the request of an audited action comes from.
+#[derive(Debug, Clone, Default)]
+pub struct AuditSource {
+  pub ip_address: Option<String>,
+  pub user_agent: Option<String>,
+}
+
+impl AuditSource {
+  pub fn from_request(req: &HttpRequest) -> Self {
+    Self {
+      ip_address: client_ip(req),
+      user_agent: req
+        .headers()
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string()),
+    }
+  }
+}
+
+/// Records an action that already happened. A failure is logged rather than returned, so the
+/// action is not reported as failed to the user.
+pub async fn record_audit_log(
+  pg_pool: &PgPool,
+  source: &AuditSource,
+  workspace_id: Option<&Uuid>,
+  actor_uid: Option<i64>,
+  action: AuditAction,
+  object_id: Option<&str>,
+  metadata: serde_json::Value,
+) {
+  if let Err(err) = insert_audit_log(
+    pg_pool,
+    workspace_id,
+    actor_uid,
+    action,
+    object_id,
+    &metadata,
+    source.ip_address.as_deref(),
+    source.user_agent.as_deref(),
+  )
+  .await
+  {
+    error!(
+      "Failed to record {} of {:?} in the audit log: {}",
+      action.as_str(),
+      actor_uid,
+      err
+    );
+  }
+}
+
+pub async fn get_audit_log(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  params: &QueryAuditLogParams,
+) -> Result<RepeatedAuditLogEntry, AppError> {
+  let limit = params
+    .limit
+    .unwrap_or(DEFAULT_AUDIT_LOG_LIMIT)
+    .clamp(1, MAX_AUDIT_LOG_LIMIT);
+  let items = select_audit_log(pg_pool, workspace_id, params, limit).await?;
+  let next_cursor = if items.len() as i64 == limit {
+    items.last().map(|entry| entry.log_id)
+  } else {
+    None
+  };
+  Ok(RepeatedAuditLogEntry { items, next_cursor })
+}
+
+/// Exports the entries matching the filters as CSV, the most recent first. The `limit` of the
+/// params is ignored.
+pub async fn export_audit_log_csv(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  params: QueryAuditLogParams,
+) -> Result<String, AppError> {
+  let mut csv = String::from(
+    "log_id,created_at,workspace_id,actor_uid,actor_email,action,object_type,object_id,\
+     ip_address,user_agent,metadata\n",
+  );
+  let mut params = QueryAuditLogParams {
+    limit: Some(MAX_AUDIT_LOG_LIMIT),
+    ..params
+  };
+  let mut exported = 0;
+  while exported < MAX_AUDIT_LOG_EXPORT_ENTRIES {
+    let page = get_audit_log(pg_pool, workspace_id, &params).await?;
+    exported += page.items.len();
+    for entry in &page.items {
+      csv.push_str(&csv_record(entry));
+    }
+    match page.next_cursor {
+      Some(cursor) => params.before = Some(cursor),
+      None => break,
+    }
+  }
+  Ok(csv)
+}
+
+fn csv_record(entry: &AuditLogEntry) -> String {
+  let fields = [
+    entry.log_id.to_string(),
+    entry.created_at.to_rfc3339(),
+    entry
+      .workspace_id
+      .map(|id| id.to_string())
+      .unwrap_or_default(),
+    entry
+      .actor_uid
+      .map(|uid| uid.to_string())
+      .unwrap_or_default(),
+    entry.actor_email.clone().unwrap_or_default(),
+    entry.action.clone(),
+    entry.object_type.clone(),
+    entry.object_id.clone().unwrap_or_default(),
+    entry.ip_address.clone().unwrap_or_default(),
+    entry.user_agent.clone().unwrap_or_default(),
+    entry.metadata.to_string(),
+  ];
+  let mut record = fields
+    .iter()
+    .map(|field| csv_field(field))
+    .collect::<Vec<_>>()
+    .join(",");
+  record.push('\n');
+  record
+}
+
+/// Quotes the field if needed, as described by RFC 4180. A field that a spreadsheet would read
+/// as a formula is prefixed with `'`, so opening the export can't run it.
+fn csv_field(field: &str) -> String {
+  let field = if field.starts_with(['=', '+', '-', '@']) {
+    Cow::Owned(format!("'{}", field))
+  } else {
+    Cow::Borrowed(field)
+  };
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.into_owned()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn csv_field_test() {
+    assert_eq!(csv_field("page.created"), "page.created");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+    assert_eq!(csv_field("+1"), "'+1");
+    assert_eq!(csv_field("-1"), "'-1");
+    assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+  }
+}
@@ -1 +1,3 @@
 pub mod ops;
+pub mod participant;
+pub mod tools;
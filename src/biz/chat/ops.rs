@@ -2,35 +2,47 @@ use actix_web::web::Bytes;
 use anyhow::anyhow;
 
 use app_error::AppError;
-use appflowy_ai_client::client::AppFlowyAIClient;
 use async_stream::stream;
 use database::chat;
 use database::chat::chat_ops::{
-  delete_answer_message_by_question_message_id, insert_answer_message,
-  insert_answer_message_with_transaction, insert_chat, insert_question_message,
-  select_chat_messages,
+  get_all_chat_messages, insert_answer_message, insert_answer_message_with_transaction,
+  insert_chat, insert_chat_branch, insert_question_message, insert_question_message_in_branch,
+  select_answer_version, select_answer_versions, select_chat, select_chat_messages,
 };
+use database::chat::chat_participant_ops::insert_chat_participant;
 use futures::stream::Stream;
 use serde_json::json;
 use shared_entity::dto::chat_dto::{
-  ChatAuthor, ChatAuthorType, ChatMessage, ChatMessageType, CreateChatMessageParams,
-  CreateChatParams, GetChatMessageParams, RepeatedChatMessage, UpdateChatMessageContentParams,
+  ChatAuthor, ChatAuthorType, ChatExport, ChatMessage, ChatMessageType, ChatParticipantRole,
+  CreateChatMessageParams, CreateChatParams, GetChatMessageParams, RepeatedAnswerVersion,
+  RepeatedChatMessage, RepeatedChatMessageSearchResult, SearchChatMessageParams,
+  UpdateChatMessageContentParams,
 };
 use sqlx::PgPool;
+use std::ops::DerefMut;
+use std::str::FromStr;
 use tracing::{error, info, trace};
+use uuid::Uuid;
 
+use super::tools::ChatToolContext;
 use appflowy_ai_client::dto::AIModel;
 use validator::Validate;
 
+/// The user who creates the chat becomes its owner.
 pub(crate) async fn create_chat(
   pg_pool: &PgPool,
+  uid: i64,
   params: CreateChatParams,
   workspace_id: &str,
 ) -> Result<(), AppError> {
   params.validate()?;
   trace!("[Chat] create chat {:?}", params);
 
-  insert_chat(pg_pool, workspace_id, params).await?;
+  let chat_id = Uuid::from_str(&params.chat_id)?;
+  let mut txn = pg_pool.begin().await?;
+  insert_chat(txn.deref_mut(), uid, workspace_id, params).await?;
+  insert_chat_participant(txn.deref_mut(), &chat_id, uid, ChatParticipantRole::Owner).await?;
+  txn.commit().await?;
   Ok(())
 }
 
@@ -41,15 +53,28 @@ pub(crate) async fn delete_chat(pg_pool: &PgPool, chat_id: &str) -> Result<(), A
   Ok(())
 }
 
+/// Editing a question keeps the original conversation: the edited question is inserted into a new
+/// branch forked from the branch of the original question, then answered by the AI.
 pub async fn update_chat_message(
   pg_pool: &PgPool,
+  uid: i64,
   params: UpdateChatMessageContentParams,
-  ai_client: AppFlowyAIClient,
+  tool_context: &ChatToolContext,
   ai_model: AIModel,
 ) -> Result<(), AppError> {
   let mut txn = pg_pool.begin().await?;
-  delete_answer_message_by_question_message_id(&mut txn, params.message_id).await?;
-  chat::chat_ops::update_chat_message_content(&mut txn, &params).await?;
+  let branch = insert_chat_branch(&mut txn, &params.chat_id, params.message_id).await?;
+  let (_, metadata) =
+    chat::chat_ops::select_chat_message_content(txn.deref_mut(), params.message_id).await?;
+  let question = insert_question_message_in_branch(
+    txn.deref_mut(),
+    ChatAuthor::new(uid, ChatAuthorType::Human),
+    &params.chat_id,
+    params.content.clone(),
+    serde_json::from_value(metadata.clone()).unwrap_or_default(),
+    Some(branch.branch_id),
+  )
+  .await?;
   txn.commit().await.map_err(|err| {
     AppError::Internal(anyhow!(
       "Failed to commit transaction to update chat message: {}",
@@ -57,14 +82,13 @@ pub async fn update_chat_message(
     ))
   })?;
 
-  // TODO(nathan): query the metadata from the database
-  let new_answer = ai_client
+  let new_answer = tool_context
     .send_question(
       &params.chat_id,
-      params.message_id,
+      question.message_id,
       &params.content,
       &ai_model,
-      None,
+      Some(metadata),
     )
     .await?;
   let _answer = insert_answer_message(
@@ -73,7 +97,7 @@ pub async fn update_chat_message(
     &params.chat_id,
     new_answer.content,
     new_answer.metadata,
-    params.message_id,
+    question.message_id,
   )
   .await?;
 
@@ -82,14 +106,14 @@ pub async fn update_chat_message(
 
 pub async fn generate_chat_message_answer(
   pg_pool: &PgPool,
-  ai_client: AppFlowyAIClient,
+  tool_context: &ChatToolContext,
   question_message_id: i64,
   chat_id: &str,
   ai_model: AIModel,
 ) -> Result<ChatMessage, AppError> {
   let (content, metadata) =
     chat::chat_ops::select_chat_message_content(pg_pool, question_message_id).await?;
-  let new_answer = ai_client
+  let new_answer = tool_context
     .send_question(
       chat_id,
       question_message_id,
@@ -146,7 +170,7 @@ pub async fn create_chat_message_stream(
   uid: i64,
   chat_id: String,
   params: CreateChatMessageParams,
-  ai_client: AppFlowyAIClient,
+  tool_context: ChatToolContext,
   ai_model: AIModel,
 ) -> impl Stream<Item = Result<Bytes, AppError>> {
   let params = params.clone();
@@ -185,11 +209,11 @@ pub async fn create_chat_message_stream(
       match params.message_type {
           ChatMessageType::System => {}
           ChatMessageType::User => {
-              let answer = match ai_client.send_question(&chat_id,question_id, &params.content, &ai_model, Some(json!(params.metadata))).await {
+              let answer = match tool_context.send_question(&chat_id,question_id, &params.content, &ai_model, Some(json!(params.metadata))).await {
                   Ok(response) => response,
                   Err(err) => {
                       error!("Failed to send question to AI: {}", err);
-                      yield Err(AppError::from(err));
+                      yield Err(err);
                       return;
                   }
               };
@@ -232,3 +256,110 @@ pub async fn get_chat_messages(
   txn.commit().await?;
   Ok(messages)
 }
+
+pub async fn get_answer_versions(
+  pg_pool: &PgPool,
+  chat_id: &str,
+  question_message_id: i64,
+) -> Result<RepeatedAnswerVersion, AppError> {
+  let mut txn = pg_pool.begin().await?;
+  let versions = select_answer_versions(&mut txn, chat_id, question_message_id).await?;
+  txn.commit().await?;
+  Ok(versions)
+}
+
+pub async fn update_selected_answer_version(
+  pg_pool: &PgPool,
+  chat_id: &str,
+  question_message_id: i64,
+  answer_message_id: i64,
+) -> Result<(), AppError> {
+  let mut txn = pg_pool.begin().await?;
+  select_answer_version(&mut txn, chat_id, question_message_id, answer_message_id).await?;
+  txn.commit().await?;
+  Ok(())
+}
+
+const DEFAULT_SEARCH_LIMIT: u64 = 10;
+const MAX_SEARCH_LIMIT: u64 = 100;
+
+pub async fn search_chat_messages(
+  pg_pool: &PgPool,
+  workspace_id: &str,
+  uid: i64,
+  params: SearchChatMessageParams,
+) -> Result<RepeatedChatMessageSearchResult, AppError> {
+  params.validate()?;
+  let limit = params
+    .limit
+    .unwrap_or(DEFAULT_SEARCH_LIMIT)
+    .clamp(1, MAX_SEARCH_LIMIT);
+  let offset = params.offset.unwrap_or(0);
+  trace!("[Chat] search messages: {:?}", params);
+  chat::chat_ops::search_chat_messages(pg_pool, workspace_id, uid, &params, limit, offset).await
+}
+
+pub async fn export_chat(
+  pg_pool: &PgPool,
+  workspace_id: &str,
+  chat_id: &str,
+) -> Result<ChatExport, AppError> {
+  let chat = select_chat(pg_pool, chat_id).await?;
+  if chat.workspace_id.to_string() != workspace_id {
+    return Err(AppError::RecordNotFound(format!(
+      "chat with given id:{} is not found",
+      chat_id
+    )));
+  }
+  let messages = get_all_chat_messages(pg_pool, chat_id).await?;
+  Ok(ChatExport {
+    chat_id: chat.chat_id.to_string(),
+    name: chat.name,
+    created_at: chat.created_at,
+    messages,
+  })
+}
+
+/// Renders the conversation as a Markdown document: questions are rendered as headings followed
+/// by the answers, so a long history can be skimmed by its questions.
+pub fn chat_export_to_markdown(export: &ChatExport) -> String {
+  let mut markdown = String::new();
+  let title = if export.name.is_empty() {
+    "Untitled chat"
+  } else {
+    export.name.as_str()
+  };
+  markdown.push_str(&format!("# {}\n\n", title));
+  markdown.push_str(&format!(
+    "_Exported from chat {} created at {}_\n\n",
+    export.chat_id,
+    export.created_at.to_rfc3339()
+  ));
+
+  for message in &export.messages {
+    let timestamp = message.created_at.format("%Y-%m-%d %H:%M:%S UTC");
+    match message.author.author_type {
+      ChatAuthorType::Human => {
+        markdown.push_str(&format!("## {}\n\n", first_line(&message.content)));
+        markdown.push_str(&format!("_Asked at {}_\n\n", timestamp));
+        if message.content.trim().contains('\n') {
+          markdown.push_str(message.content.trim());
+          markdown.push_str("\n\n");
+        }
+      },
+      ChatAuthorType::AI => {
+        markdown.push_str(&format!("**AI** _{}_\n\n", timestamp));
+        markdown.push_str(message.content.trim());
+        markdown.push_str("\n\n");
+      },
+      ChatAuthorType::System | ChatAuthorType::Unknown => {
+        markdown.push_str(&format!("> {}\n\n", message.content.trim()));
+      },
+    }
+  }
+  markdown
+}
+
+fn first_line(content: &str) -> &str {
+  content.trim().lines().next().unwrap_or_default()
+}
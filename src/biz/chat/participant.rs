@@ -0,0 +1,173 @@
+use std::ops::DerefMut;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use access_control::act::Action;
+use access_control::workspace::WorkspaceAccessControl;
+use app_error::AppError;
+use database::chat::chat_participant_ops::{
+  delete_chat_participants, insert_chat_members, insert_chat_participant, legacy_chat_role,
+  select_chat_access, select_chat_participants, select_chats_for_user,
+  select_workspace_member_uids_by_email,
+};
+use shared_entity::dto::chat_dto::{
+  AddChatParticipantsParams, ChatParticipantRole, RemoveChatParticipantsParams, RepeatedChatInfo,
+  RepeatedChatParticipant,
+};
+use sqlx::PgPool;
+use tracing::trace;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Checks that the user can perform the action on the chat:
+/// - the user must be allowed to perform the action on the workspace of the chat.
+/// - the user must participate in the chat, unless the chat has no participant at all.
+/// - a viewer can only read the chat.
+///
+/// Returns the role of the user in the chat. Chats without participants were created before chats
+/// could be shared, they are owned by their creator and the other members of the workspace are
+/// their viewers.
+pub async fn enforce_chat_action(
+  pg_pool: &PgPool,
+  workspace_access_control: &Arc<dyn WorkspaceAccessControl>,
+  uid: i64,
+  workspace_id: &str,
+  chat_id: &str,
+  action: Action,
+) -> Result<ChatParticipantRole, AppError> {
+  workspace_access_control
+    .enforce_action(&uid, workspace_id, action)
+    .await?;
+
+  let chat_uuid = Uuid::from_str(chat_id)?;
+  let access = select_chat_access(pg_pool, &chat_uuid, uid)
+    .await?
+    .filter(|access| access.workspace_id.to_string() == workspace_id)
+    .ok_or_else(|| {
+      AppError::RecordNotFound(format!("chat with given id:{} is not found", chat_id))
+    })?;
+
+  let role = match access.role {
+    Some(role) => role,
+    None if !access.has_participants => legacy_chat_role(uid, access.created_by),
+    None => {
+      return Err(AppError::NotEnoughPermissions {
+        user: uid.to_string(),
+        workspace_id: workspace_id.to_string(),
+      })
+    },
+  };
+  if role == ChatParticipantRole::Viewer && action != Action::Read {
+    return Err(AppError::NotEnoughPermissions {
+      user: uid.to_string(),
+      workspace_id: workspace_id.to_string(),
+    });
+  }
+  Ok(role)
+}
+
+pub async fn get_chats_for_user(
+  pg_pool: &PgPool,
+  workspace_id: &str,
+  uid: i64,
+) -> Result<RepeatedChatInfo, AppError> {
+  let workspace_id = Uuid::from_str(workspace_id)?;
+  let items = select_chats_for_user(pg_pool, &workspace_id, uid).await?;
+  Ok(RepeatedChatInfo { items })
+}
+
+pub async fn get_chat_participants(
+  pg_pool: &PgPool,
+  chat_id: &str,
+) -> Result<RepeatedChatParticipant, AppError> {
+  let chat_id = Uuid::from_str(chat_id)?;
+  let items = select_chat_participants(pg_pool, &chat_id).await?;
+  Ok(RepeatedChatParticipant { items })
+}
+
+/// Adds members of the workspace to the chat. Fails if one of the emails doesn't belong to a
+/// member of the workspace. The creator who shares a chat without participants becomes its owner.
+pub async fn add_chat_participants(
+  pg_pool: &PgPool,
+  uid: i64,
+  role: ChatParticipantRole,
+  workspace_id: &str,
+  chat_id: &str,
+  params: AddChatParticipantsParams,
+) -> Result<(), AppError> {
+  params.validate()?;
+  if role != ChatParticipantRole::Owner {
+    return Err(AppError::NotEnoughPermissions {
+      user: uid.to_string(),
+      workspace_id: workspace_id.to_string(),
+    });
+  }
+
+  let workspace_uuid = Uuid::from_str(workspace_id)?;
+  let chat_uuid = Uuid::from_str(chat_id)?;
+  let members =
+    select_workspace_member_uids_by_email(pg_pool, &workspace_uuid, &params.emails).await?;
+  let missing = params
+    .emails
+    .iter()
+    .filter(|email| {
+      !members
+        .iter()
+        .any(|(member_email, _)| member_email == *email)
+    })
+    .cloned()
+    .collect::<Vec<_>>();
+  if !missing.is_empty() {
+    return Err(AppError::InvalidRequest(format!(
+      "{} are not members of the workspace",
+      missing.join(", ")
+    )));
+  }
+
+  trace!(
+    "[Chat] add participants {:?} to chat {}",
+    params.emails,
+    chat_id
+  );
+  let uids = members.into_iter().map(|(_, uid)| uid).collect::<Vec<_>>();
+  let mut txn = pg_pool.begin().await?;
+  insert_chat_participant(txn.deref_mut(), &chat_uuid, uid, ChatParticipantRole::Owner).await?;
+  insert_chat_members(txn.deref_mut(), &chat_uuid, &uids).await?;
+  txn.commit().await?;
+  Ok(())
+}
+
+/// The owner of the chat can remove any participant except itself, other participants can only
+/// leave the chat.
+pub async fn remove_chat_participants(
+  pg_pool: &PgPool,
+  uid: i64,
+  role: ChatParticipantRole,
+  workspace_id: &str,
+  chat_id: &str,
+  params: RemoveChatParticipantsParams,
+) -> Result<(), AppError> {
+  params.validate()?;
+  let workspace_uuid = Uuid::from_str(workspace_id)?;
+  let chat_uuid = Uuid::from_str(chat_id)?;
+  let uids = select_workspace_member_uids_by_email(pg_pool, &workspace_uuid, &params.emails)
+    .await?
+    .into_iter()
+    .map(|(_, uid)| uid)
+    .collect::<Vec<_>>();
+
+  let allowed = match role {
+    ChatParticipantRole::Owner => !uids.contains(&uid),
+    ChatParticipantRole::Member => uids.iter().all(|participant| *participant == uid),
+    ChatParticipantRole::Viewer => false,
+  };
+  if !allowed {
+    return Err(AppError::NotEnoughPermissions {
+      user: uid.to_string(),
+      workspace_id: workspace_id.to_string(),
+    });
+  }
+
+  delete_chat_participants(pg_pool, &chat_uuid, &uids).await?;
+  Ok(())
+}
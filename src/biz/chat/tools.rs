@@ -0,0 +1,694 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use access_control::act::Action;
+use access_control::collab::CollabAccessControl;
+use access_control::workspace::WorkspaceAccessControl;
+use actix_web::web::Bytes;
+use anyhow::anyhow;
+use app_error::AppError;
+use appflowy_ai_client::client::AppFlowyAIClient;
+use appflowy_ai_client::dto::{
+  AIModel, ChatAnswer, ToolCall, ToolDefinition, ToolResult, STREAM_METADATA_KEY,
+  STREAM_TOOL_CALL_KEY,
+};
+use appflowy_collaborate::collab::storage::CollabAccessControlStorage;
+use appflowy_collaborate::collab::workspace_activity::WorkspaceActivityRecorder;
+use appflowy_collaborate::indexer::DocumentDataExt;
+use async_stream::stream;
+use collab_document::document::Document;
+use collab_folder::Folder;
+use database::collab::GetCollabOrigin;
+use futures::stream::Stream;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use shared_entity::dto::chat_dto::{ChatToolCallMetadata, CHAT_TOOL_METADATA_SOURCE};
+use shared_entity::dto::search_dto::SearchDocumentRequest;
+use shared_entity::dto::workspace_dto::ViewLayout;
+use sqlx::PgPool;
+use tracing::{error, trace};
+use uuid::Uuid;
+
+use crate::api::metrics::RequestMetrics;
+use crate::biz::collab::folder_view::{hidden_space_ids, is_view_visible};
+use crate::biz::collab::ops::{
+  append_database_rows, collab_from_doc_state, get_database_fields, get_latest_collab_folder,
+  get_latest_workspace_database, list_database_row_details, list_database_row_ids,
+};
+use crate::biz::search::search_document;
+use crate::biz::workspace::page_view::{create_page, get_page_view_collab};
+
+/// The model can call tools during a few rounds, after that it has to answer with what it got.
+const MAX_TOOL_ROUNDS: usize = 3;
+const MAX_PAGE_TEXT_CHARS: usize = 8000;
+const MAX_DATABASE_ROWS: usize = 50;
+const MAX_APPENDED_ROWS: usize = 100;
+
+pub const SEARCH_WORKSPACE_TOOL: &str = "search_workspace";
+pub const READ_PAGE_TOOL: &str = "read_page";
+pub const CREATE_PAGE_TOOL: &str = "create_page";
+pub const APPEND_DATABASE_ROWS_TOOL: &str = "append_database_rows";
+
+/// The tools offered to the model when it answers a question.
+pub fn chat_tool_definitions() -> Vec<ToolDefinition> {
+  vec![
+    ToolDefinition {
+      name: SEARCH_WORKSPACE_TOOL.to_string(),
+      description: "Search the pages of the workspace that match the query.".to_string(),
+      parameters: json!({
+        "type": "object",
+        "properties": {
+          "query": { "type": "string" },
+          "limit": { "type": "integer", "minimum": 1, "maximum": 20 }
+        },
+        "required": ["query"]
+      }),
+    },
+    ToolDefinition {
+      name: READ_PAGE_TOOL.to_string(),
+      description: "Read the content of a page: the text of a document, or the fields and the \
+                    rows of a database."
+        .to_string(),
+      parameters: json!({
+        "type": "object",
+        "properties": {
+          "view_id": { "type": "string" }
+        },
+        "required": ["view_id"]
+      }),
+    },
+    ToolDefinition {
+      name: CREATE_PAGE_TOOL.to_string(),
+      description: "Create a page under the given parent page.".to_string(),
+      parameters: json!({
+        "type": "object",
+        "properties": {
+          "parent_view_id": { "type": "string" },
+          "name": { "type": "string" },
+          "layout": { "type": "string", "enum": ["document", "grid", "board", "calendar"] }
+        },
+        "required": ["parent_view_id", "name"]
+      }),
+    },
+    ToolDefinition {
+      name: APPEND_DATABASE_ROWS_TOOL.to_string(),
+      description: "Append rows to a database. Each row maps field names to text values, the \
+                    names of the options are used for select fields."
+        .to_string(),
+      parameters: json!({
+        "type": "object",
+        "properties": {
+          "view_id": { "type": "string" },
+          "rows": {
+            "type": "array",
+            "items": { "type": "object", "additionalProperties": { "type": "string" } }
+          }
+        },
+        "required": ["view_id", "rows"]
+      }),
+    },
+  ]
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchWorkspaceArgs {
+  query: String,
+  limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadPageArgs {
+  view_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreatePageArgs {
+  parent_view_id: String,
+  name: String,
+  #[serde(default)]
+  layout: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AppendDatabaseRowsArgs {
+  view_id: String,
+  rows: Vec<HashMap<String, String>>,
+}
+
+/// Runs the tools called by the model on behalf of the user who asked the question. Each tool
+/// checks the permissions of the user in the workspace, and the access to the pages it reads or
+/// writes: the pages in the private spaces of the other members are left out.
+#[derive(Clone)]
+pub struct ChatToolContext {
+  pub pg_pool: PgPool,
+  pub ai_client: AppFlowyAIClient,
+  pub collab_storage: Arc<CollabAccessControlStorage>,
+  pub activity_recorder: Arc<WorkspaceActivityRecorder>,
+  pub workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  pub collab_access_control: Arc<dyn CollabAccessControl>,
+  pub request_metrics: Arc<RequestMetrics>,
+  pub uid: i64,
+  pub workspace_id: Uuid,
+}
+
+impl ChatToolContext {
+  /// Sends the question to the AI and runs the tools it calls until it answers. The calls are
+  /// appended to the metadata of the answer.
+  pub async fn send_question(
+    &self,
+    chat_id: &str,
+    question_id: i64,
+    content: &str,
+    model: &AIModel,
+    metadata: Option<Value>,
+  ) -> Result<ChatAnswer, AppError> {
+    let mut tool_results = vec![];
+    let mut records = vec![];
+    let mut round = 0;
+    loop {
+      let tools = if round < MAX_TOOL_ROUNDS {
+        chat_tool_definitions()
+      } else {
+        vec![]
+      };
+      let mut answer = self
+        .ai_client
+        .send_question_with_tools(
+          chat_id,
+          question_id,
+          content,
+          model,
+          metadata.clone(),
+          tools,
+          tool_results.clone(),
+        )
+        .await?;
+      if answer.tool_calls.is_empty() || round >= MAX_TOOL_ROUNDS {
+        answer.tool_calls.clear();
+        answer.metadata = append_tool_calls_to_metadata(answer.metadata.take(), records);
+        return Ok(answer);
+      }
+
+      for call in answer.tool_calls {
+        let result = self.call(&call).await;
+        records.push(tool_call_metadata(&call, &result));
+        tool_results.push(result);
+      }
+      round += 1;
+    }
+  }
+
+  /// Runs the tool. Failures are returned to the model as an error result so it can recover.
+  pub async fn call(&self, call: &ToolCall) -> ToolResult {
+    trace!("[Chat] call tool {}: {}", call.name, call.arguments);
+    match self.run(call).await {
+      Ok(content) => ToolResult {
+        call_id: call.id.clone(),
+        name: call.name.clone(),
+        content,
+        is_error: false,
+      },
+      Err(err) => {
+        error!("[Chat] tool {} failed: {}", call.name, err);
+        ToolResult {
+          call_id: call.id.clone(),
+          name: call.name.clone(),
+          content: json!({ "error": err.to_string() }),
+          is_error: true,
+        }
+      },
+    }
+  }
+
+  async fn run(&self, call: &ToolCall) -> Result<Value, AppError> {
+    match call.name.as_str() {
+      SEARCH_WORKSPACE_TOOL => self.search_workspace(parse_args(call)?).await,
+      READ_PAGE_TOOL => self.read_page(parse_args(call)?).await,
+      CREATE_PAGE_TOOL => self.create_page(parse_args(call)?).await,
+      APPEND_DATABASE_ROWS_TOOL => self.append_database_rows(parse_args(call)?).await,
+      name => Err(AppError::InvalidRequest(format!("unknown tool: {}", name))),
+    }
+  }
+
+  async fn enforce_action(&self, action: Action) -> Result<(), AppError> {
+    self
+      .workspace_access_control
+      .enforce_action(&self.uid, &self.workspace_id.to_string(), action)
+      .await?;
+    Ok(())
+  }
+
+  async fn folder(&self) -> Result<Folder, AppError> {
+    get_latest_collab_folder(
+      &self.collab_storage,
+      GetCollabOrigin::User { uid: self.uid },
+      &self.workspace_id.to_string(),
+    )
+    .await
+  }
+
+  /// Checks that the user can act on the page: the page must not be in the private space of
+  /// another member, and the access level of the user on the page, which a grant on the page or
+  /// above it replaces, must allow the action.
+  async fn enforce_view_action(
+    &self,
+    folder: &Folder,
+    hidden_spaces: &HashSet<String>,
+    view_id: &str,
+    action: Action,
+  ) -> Result<(), AppError> {
+    let workspace_id = self.workspace_id.to_string();
+    if !is_view_visible(folder, &workspace_id, view_id, hidden_spaces) {
+      return Err(AppError::RecordNotFound(format!(
+        "page {} not found",
+        view_id
+      )));
+    }
+    self
+      .collab_access_control
+      .enforce_action(&workspace_id, &self.uid, view_id, action)
+      .await
+  }
+
+  async fn search_workspace(&self, args: SearchWorkspaceArgs) -> Result<Value, AppError> {
+    self.enforce_action(Action::Read).await?;
+    let items = search_document(
+      &self.pg_pool,
+      &self.ai_client,
+      self.uid,
+      self.workspace_id,
+      SearchDocumentRequest {
+        query: args.query,
+        limit: Some(args.limit.unwrap_or(5).clamp(1, 20)),
+        preview_size: None,
+      },
+      &self.request_metrics,
+    )
+    .await
+    .map_err(|err| AppError::Internal(anyhow!("failed to search workspace: {}", err.message)))?;
+    let folder = self.folder().await?;
+    let hidden_spaces = hidden_space_ids(&folder);
+    let mut readable_items = Vec::with_capacity(items.len());
+    for item in items {
+      if self
+        .enforce_view_action(&folder, &hidden_spaces, &item.object_id, Action::Read)
+        .await
+        .is_ok()
+      {
+        readable_items.push(item);
+      }
+    }
+    Ok(json!(readable_items))
+  }
+
+  async fn read_page(&self, args: ReadPageArgs) -> Result<Value, AppError> {
+    self.enforce_action(Action::Read).await?;
+    let folder = self.folder().await?;
+    self
+      .enforce_view_action(
+        &folder,
+        &hidden_space_ids(&folder),
+        &args.view_id,
+        Action::Read,
+      )
+      .await?;
+    let page = get_page_view_collab(
+      &self.pg_pool,
+      &self.collab_storage,
+      self.uid,
+      self.workspace_id,
+      &args.view_id,
+    )
+    .await?;
+    match page.view.layout {
+      ViewLayout::Document => {
+        let collab = collab_from_doc_state(page.data.encoded_collab, &args.view_id)?;
+        let document =
+          Document::open(collab).map_err(|err| AppError::Unhandled(err.to_string()))?;
+        let text = document
+          .get_document_data()
+          .map_err(|err| AppError::Unhandled(err.to_string()))?
+          .to_plain_text();
+        Ok(json!({
+          "name": page.view.name,
+          "layout": page.view.layout.to_string(),
+          "content": text.chars().take(MAX_PAGE_TEXT_CHARS).collect::<String>(),
+        }))
+      },
+      ViewLayout::Grid | ViewLayout::Board | ViewLayout::Calendar => {
+        let workspace_id = self.workspace_id.to_string();
+        let database_id = self.database_id_for_view(&args.view_id).await?;
+        let fields = get_database_fields(&self.collab_storage, &workspace_id, &database_id).await?;
+        let row_ids = list_database_row_ids(&self.collab_storage, &workspace_id, &database_id)
+          .await?
+          .into_iter()
+          .take(MAX_DATABASE_ROWS)
+          .map(|row| row.id)
+          .collect::<Vec<_>>();
+        let row_ids = row_ids.iter().map(String::as_str).collect::<Vec<_>>();
+        let rows = list_database_row_details(
+          &self.collab_storage,
+          self.uid,
+          workspace_id,
+          database_id,
+          &row_ids,
+        )
+        .await?;
+        Ok(json!({
+          "name": page.view.name,
+          "layout": page.view.layout.to_string(),
+          "fields": fields.into_iter().map(|field| field.name).collect::<Vec<_>>(),
+          "rows": rows,
+        }))
+      },
+      ViewLayout::Chat => Err(AppError::InvalidRequest(
+        "reading an AI chat is not supported".to_string(),
+      )),
+    }
+  }
+
+  async fn create_page(&self, args: CreatePageArgs) -> Result<Value, AppError> {
+    self.enforce_action(Action::Write).await?;
+    let layout = match args.layout.as_deref().unwrap_or("document") {
+      "document" => ViewLayout::Document,
+      "grid" => ViewLayout::Grid,
+      "board" => ViewLayout::Board,
+      "calendar" => ViewLayout::Calendar,
+      layout => {
+        return Err(AppError::InvalidRequest(format!(
+          "unsupported page layout: {}",
+          layout
+        )))
+      },
+    };
+    let folder = self.folder().await?;
+    self
+      .enforce_view_action(
+        &folder,
+        &hidden_space_ids(&folder),
+        &args.parent_view_id,
+        Action::Write,
+      )
+      .await?;
+    let page = create_page(
+      &self.pg_pool,
+      &self.collab_storage,
+      &self.activity_recorder,
+      self.uid,
+      self.workspace_id,
+      &args.parent_view_id,
+      &layout,
+      Some(&args.name),
+    )
+    .await?;
+    Ok(json!({ "view_id": page.view_id }))
+  }
+
+  async fn append_database_rows(&self, args: AppendDatabaseRowsArgs) -> Result<Value, AppError> {
+    self.enforce_action(Action::Write).await?;
+    if args.rows.is_empty() || args.rows.len() > MAX_APPENDED_ROWS {
+      return Err(AppError::InvalidRequest(format!(
+        "between 1 and {} rows can be appended at once",
+        MAX_APPENDED_ROWS
+      )));
+    }
+    let folder = self.folder().await?;
+    self
+      .enforce_view_action(
+        &folder,
+        &hidden_space_ids(&folder),
+        &args.view_id,
+        Action::Write,
+      )
+      .await?;
+    let database_id = self.database_id_for_view(&args.view_id).await?;
+    let row_ids = append_database_rows(
+      &self.pg_pool,
+      &self.collab_storage,
+      self.uid,
+      self.workspace_id,
+      &database_id,
+      args.rows,
+    )
+    .await?;
+    Ok(json!({ "row_ids": row_ids }))
+  }
+
+  async fn database_id_for_view(&self, view_id: &str) -> Result<String, AppError> {
+    let (_, workspace_database) = get_latest_workspace_database(
+      &self.collab_storage,
+      &self.pg_pool,
+      GetCollabOrigin::User { uid: self.uid },
+      self.workspace_id,
+    )
+    .await?;
+    let meta = workspace_database
+      .get_database_meta_with_view_id(view_id)
+      .ok_or_else(|| AppError::RecordNotFound(format!("database view {} not found", view_id)))?;
+    Ok(meta.database_id)
+  }
+}
+
+/// Streams the answer of the question and runs the tools called by the model. The stream keeps
+/// the format of the AI service: the answer is forwarded as is, and the calls of each round are
+/// sent as metadata so that they end up in the metadata of the saved answer.
+pub fn stream_answer_with_tools(
+  context: ChatToolContext,
+  chat_id: String,
+  question_id: i64,
+  content: String,
+  metadata: Option<Value>,
+  rag_ids: Vec<String>,
+  model: AIModel,
+) -> impl Stream<Item = Result<Bytes, AppError>> {
+  stream! {
+    let mut tool_results = vec![];
+    for round in 0..=MAX_TOOL_ROUNDS {
+      let tools = if round < MAX_TOOL_ROUNDS {
+        chat_tool_definitions()
+      } else {
+        vec![]
+      };
+      let answer_stream = match context
+        .ai_client
+        .stream_question_v2_with_tools(
+          &chat_id,
+          question_id,
+          &content,
+          metadata.clone(),
+          rag_ids.clone(),
+          &model,
+          tools,
+          tool_results.clone(),
+        )
+        .await
+      {
+        Ok(answer_stream) => answer_stream,
+        Err(err) => {
+          yield Err(AppError::AIServiceUnavailable(err.to_string()));
+          return;
+        },
+      };
+      tokio::pin!(answer_stream);
+
+      let mut buffer = Vec::new();
+      let mut tool_calls = vec![];
+      while let Some(chunk) = answer_stream.next().await {
+        match chunk {
+          Ok(bytes) => buffer.extend_from_slice(&bytes),
+          Err(err) => {
+            yield Err(AppError::from(err));
+            return;
+          },
+        }
+        let values = match drain_json_values(&mut buffer) {
+          Ok(values) => values,
+          Err(err) => {
+            yield Err(AppError::from(err));
+            return;
+          },
+        };
+        for value in values {
+          match take_tool_call(value) {
+            Ok(StreamedValue::ToolCall(call)) => tool_calls.push(call),
+            Ok(StreamedValue::Answer(value)) => yield Ok(Bytes::from(value.to_string())),
+            Err(err) => {
+              yield Err(err);
+              return;
+            },
+          }
+        }
+      }
+
+      if tool_calls.is_empty() {
+        return;
+      }
+      let mut records = Vec::with_capacity(tool_calls.len());
+      for call in tool_calls {
+        let result = context.call(&call).await;
+        records.push(tool_call_metadata(&call, &result));
+        tool_results.push(result);
+      }
+      let mut metadata_value = serde_json::Map::new();
+      metadata_value.insert(STREAM_METADATA_KEY.to_string(), json!(records));
+      yield Ok(Bytes::from(Value::Object(metadata_value).to_string()));
+    }
+  }
+}
+
+/// Appends the tool calls to the metadata of an answer. The metadata of an answer is a list of
+/// sources, a single object is kept as the first source.
+pub fn append_tool_calls_to_metadata(
+  metadata: Option<Value>,
+  records: Vec<ChatToolCallMetadata>,
+) -> Option<Value> {
+  if records.is_empty() {
+    return metadata;
+  }
+  let mut items = match metadata {
+    Some(Value::Array(items)) => items,
+    None | Some(Value::Null) => vec![],
+    Some(value) => vec![value],
+  };
+  items.extend(records.into_iter().map(|record| json!(record)));
+  Some(Value::Array(items))
+}
+
+fn tool_call_metadata(call: &ToolCall, result: &ToolResult) -> ChatToolCallMetadata {
+  ChatToolCallMetadata {
+    id: call.id.clone(),
+    source: CHAT_TOOL_METADATA_SOURCE.to_string(),
+    name: call.name.clone(),
+    arguments: call.arguments.clone(),
+    result: result.content.clone(),
+    is_error: result.is_error,
+  }
+}
+
+fn parse_args<T: serde::de::DeserializeOwned>(call: &ToolCall) -> Result<T, AppError> {
+  serde_json::from_value(call.arguments.clone())
+    .map_err(|err| AppError::InvalidRequest(format!("invalid arguments: {}", err)))
+}
+
+/// A value of the streamed answer.
+#[derive(Debug)]
+enum StreamedValue {
+  ToolCall(ToolCall),
+  /// A part of the answer, forwarded as is.
+  Answer(Value),
+}
+
+/// Returns the tool call carried by the streamed value, or the value itself. A tool call without
+/// an id or a name, or with arguments that are not an object, is rejected.
+fn take_tool_call(value: Value) -> Result<StreamedValue, AppError> {
+  let mut object = match value {
+    Value::Object(object) => object,
+    value => return Ok(StreamedValue::Answer(value)),
+  };
+  let call = match object.remove(STREAM_TOOL_CALL_KEY) {
+    Some(call) => call,
+    None => return Ok(StreamedValue::Answer(Value::Object(object))),
+  };
+  let call: ToolCall = serde_json::from_value(call)
+    .map_err(|err| AppError::Internal(anyhow!("invalid tool call: {}", err)))?;
+  if call.id.is_empty() || call.name.is_empty() || !call.arguments.is_object() {
+    return Err(AppError::Internal(anyhow!(
+      "invalid tool call {:?}: {}",
+      call.name,
+      call.arguments
+    )));
+  }
+  Ok(StreamedValue::ToolCall(call))
+}
+
+/// Removes the complete JSON values at the beginning of the buffer, an incomplete value is kept
+/// until the rest of it is received.
+fn drain_json_values(buffer: &mut Vec<u8>) -> Result<Vec<Value>, serde_json::Error> {
+  let mut values = vec![];
+  let offset = {
+    let mut de = serde_json::Deserializer::from_slice(buffer).into_iter::<Value>();
+    let mut offset = 0;
+    loop {
+      match de.next() {
+        Some(Ok(value)) => {
+          offset = de.byte_offset();
+          values.push(value);
+        },
+        Some(Err(err)) if err.is_eof() => break,
+        Some(Err(err)) => return Err(err),
+        None => break,
+      }
+    }
+    offset
+  };
+  buffer.drain(..offset);
+  Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn drain_json_values_keeps_incomplete_value() {
+    let mut buffer = br#"{"1":"Hello"}{"2":{"id":"call_1","name":"read_page","argu"#.to_vec();
+    let values = drain_json_values(&mut buffer).unwrap();
+    assert_eq!(values, vec![json!({"1": "Hello"})]);
+
+    buffer.extend_from_slice(br#"ments":{"view_id":"v1"}}}"#);
+    let values = drain_json_values(&mut buffer).unwrap();
+    assert!(buffer.is_empty());
+    let call = match take_tool_call(values[0].clone()).unwrap() {
+      StreamedValue::ToolCall(call) => call,
+      value => panic!("expected a tool call: {:?}", value),
+    };
+    assert_eq!(call.name, READ_PAGE_TOOL);
+    assert_eq!(call.arguments, json!({"view_id": "v1"}));
+  }
+
+  #[test]
+  fn malformed_tool_calls_are_rejected() {
+    for call in [
+      json!({"id": "call_1", "name": "read_page"}),
+      json!({"id": "", "name": "read_page", "arguments": {}}),
+      json!({"id": "call_1", "name": "read_page", "arguments": "view_id=v1"}),
+    ] {
+      let mut value = serde_json::Map::new();
+      value.insert(STREAM_TOOL_CALL_KEY.to_string(), call);
+      assert!(take_tool_call(Value::Object(value)).is_err());
+    }
+    assert!(matches!(
+      take_tool_call(json!({"1": "Hello"})),
+      Ok(StreamedValue::Answer(_))
+    ));
+  }
+
+  #[test]
+  fn tool_calls_are_appended_to_sources() {
+    let call = ToolCall {
+      id: "call_1".to_string(),
+      name: SEARCH_WORKSPACE_TOOL.to_string(),
+      arguments: json!({"query": "roadmap"}),
+    };
+    let result = ToolResult {
+      call_id: "call_1".to_string(),
+      name: SEARCH_WORKSPACE_TOOL.to_string(),
+      content: json!([]),
+      is_error: false,
+    };
+    let source = json!({"id": "doc_1", "source": "appflowy", "name": "Roadmap"});
+    let metadata = append_tool_calls_to_metadata(
+      Some(json!([source.clone()])),
+      vec![tool_call_metadata(&call, &result)],
+    )
+    .unwrap();
+    let items = metadata.as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0], source);
+    assert_eq!(items[1]["source"], CHAT_TOOL_METADATA_SOURCE);
+    assert_eq!(items[1]["arguments"]["query"], "roadmap");
+
+    assert_eq!(append_tool_calls_to_metadata(None, vec![]), None);
+  }
+}
@@ -11,6 +11,9 @@ use shared_entity::dto::workspace_dto::{
 };
 use uuid::Uuid;
 
+/// Guards against a cycle in a corrupted folder.
+const MAX_FOLDER_DEPTH: usize = 64;
+
 /// Return all folders belonging to a workspace, excluding private sections which the user does not have access to.
 pub fn collab_folder_to_folder_view(
   workspace_id: Uuid,
@@ -247,6 +250,50 @@ pub fn check_if_view_is_space(view: &collab_folder::View) -> bool {
   }
 }
 
+/// The private spaces of the other members.
+pub fn hidden_space_ids(folder: &Folder) -> HashSet<String> {
+  let my_private_view_ids: HashSet<String> = folder
+    .get_my_private_sections()
+    .into_iter()
+    .map(|section| section.id)
+    .collect();
+  folder
+    .get_all_private_sections()
+    .into_iter()
+    .map(|section| section.id)
+    .filter(|view_id| !my_private_view_ids.contains(view_id))
+    .filter(|view_id| {
+      folder
+        .get_view(view_id)
+        .map(|view| check_if_view_is_space(&view))
+        .unwrap_or(false)
+    })
+    .collect()
+}
+
+/// Whether the view is in the folder of the workspace, outside of the hidden spaces.
+pub fn is_view_visible(
+  folder: &Folder,
+  workspace_id: &str,
+  view_id: &str,
+  hidden_spaces: &HashSet<String>,
+) -> bool {
+  let mut view_id = view_id.to_string();
+  for _ in 0..MAX_FOLDER_DEPTH {
+    if hidden_spaces.contains(&view_id) {
+      return false;
+    }
+    if view_id == workspace_id {
+      return true;
+    }
+    view_id = match folder.get_view(&view_id) {
+      Some(view) => view.parent_view_id.clone(),
+      None => return false,
+    };
+  }
+  false
+}
+
 pub fn parse_extra_field_as_json(extra: &str) -> serde_json::Value {
   serde_json::from_str::<serde_json::Value>(extra).unwrap_or_else(|e| {
     tracing::warn!("failed to parse extra field({}): {}", extra, e);
@@ -7,11 +7,20 @@ use chrono::DateTime;
 use chrono::Utc;
 use collab::core::collab::DataSource;
 use collab::preclude::Collab;
+use collab_database::database::gen_row_id;
 use collab_database::database::DatabaseBody;
 use collab_database::entity::FieldType;
 use collab_database::fields::Field;
 use collab_database::fields::TypeOptions;
+use collab_database::rows::new_cell_builder;
+use collab_database::rows::Cell;
+use collab_database::rows::CreateRowParams;
+use collab_database::rows::DatabaseRowBody;
+use collab_database::rows::Row;
 use collab_database::rows::RowDetail;
+use collab_database::rows::RowOrder;
+use collab_database::template::entity::CELL_DATA;
+use collab_database::views::OrderObjectPosition;
 use collab_database::workspace_database::NoPersistenceDatabaseCollabService;
 use collab_database::workspace_database::WorkspaceDatabase;
 use collab_database::workspace_database::WorkspaceDatabaseBody;
@@ -22,10 +31,16 @@ use collab_folder::{CollabOrigin, Folder};
 use database::collab::select_last_updated_database_row_ids;
 use database::collab::select_workspace_database_oid;
 use database::collab::{CollabStorage, GetCollabOrigin};
-use database::publish::select_published_view_ids_for_workspace;
 use database::publish::select_workspace_id_for_publish_namespace;
+use database::publish::{
+  select_published_collab_settings_for_workspace, select_published_view_ids_for_workspace,
+};
+use database::workspace_activity::{insert_workspace_activities, WorkspaceActivityParams};
+use database_entity::dto::CollabParams;
 use database_entity::dto::QueryCollabResult;
 use database_entity::dto::{QueryCollab, QueryCollabParams};
+use shared_entity::dto::group_dto::{CollabGroupMember, UpsertCollabGroupMemberParams};
+use shared_entity::dto::workspace_activity_dto::WorkspaceActivityKind;
 use shared_entity::dto::workspace_dto::AFDatabase;
 use shared_entity::dto::workspace_dto::AFDatabaseField;
 use shared_entity::dto::workspace_dto::AFDatabaseRow;
@@ -52,6 +67,10 @@ use database_entity::dto::{
   UpdateCollabMemberParams,
 };
 
+use crate::biz::workspace::group::get_workspace_group;
+use crate::biz::workspace::ops::broadcast_update;
+use crate::biz::workspace::publish_access::is_listed_in_outline;
+
 use super::folder_view::collab_folder_to_folder_view;
 use super::folder_view::section_items_to_favorite_folder_view;
 use super::folder_view::section_items_to_recent_folder_view;
@@ -177,6 +196,51 @@ pub async fn delete_collab_member(
   Ok(())
 }
 
+/// Grants the access level on the collab to the members of a group of the workspace.
+pub async fn upsert_collab_group_member(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  oid: &str,
+  params: &UpsertCollabGroupMemberParams,
+  collab_access_control: Arc<dyn CollabAccessControl>,
+) -> Result<(), AppError> {
+  get_workspace_group(pg_pool, workspace_id, &params.group_id).await?;
+  database::group::upsert_collab_group_member(pg_pool, &params.group_id, oid, params.access_level)
+    .await?;
+  collab_access_control
+    .update_group_access_level_policy(&params.group_id, oid, params.access_level)
+    .await?;
+  Ok(())
+}
+
+pub async fn delete_collab_group_member(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  oid: &str,
+  group_id: &Uuid,
+  collab_access_control: Arc<dyn CollabAccessControl>,
+) -> Result<(), AppError> {
+  get_workspace_group(pg_pool, workspace_id, group_id).await?;
+  if !database::group::delete_collab_group_member(pg_pool, group_id, oid).await? {
+    return Err(AppError::RecordNotFound(format!(
+      "The group {} is not a member of {}",
+      group_id, oid
+    )));
+  }
+  collab_access_control
+    .remove_group_access_level(group_id, oid)
+    .await?;
+  Ok(())
+}
+
+pub async fn get_collab_group_member_list(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  oid: &str,
+) -> Result<Vec<CollabGroupMember>, AppError> {
+  database::group::select_collab_group_members(pg_pool, workspace_id, oid).await
+}
+
 pub async fn get_collab_member_list(
   pg_pool: &PgPool,
   params: &QueryCollabMembers,
@@ -199,8 +263,17 @@ pub async fn get_user_favorite_folder_views(
   )
   .await?;
   let publish_view_ids = select_published_view_ids_for_workspace(pg_pool, workspace_id).await?;
+  // Restricted views are only reachable from their own link
+  let unlisted_view_ids: HashSet<Uuid> =
+    select_published_collab_settings_for_workspace(pg_pool, &workspace_id)
+      .await?
+      .into_iter()
+      .filter(|settings| !is_listed_in_outline(settings))
+      .map(|settings| settings.view_id)
+      .collect();
   let publish_view_ids: HashSet<String> = publish_view_ids
     .into_iter()
+    .filter(|id| !unlisted_view_ids.contains(id))
     .map(|id| id.to_string())
     .collect();
   let deleted_section_item_ids: Vec<String> = folder
@@ -581,32 +654,9 @@ pub async fn list_database_row_details(
     ))
   })?;
 
-  // create a map of field id to field.
-  // ensure that the field name is unique.
-  // if the field name is repeated, it will be appended with the field id,
-  // under practical usage circumstances, no other collision should occur
-  let field_by_id: HashMap<String, Field> = {
-    let all_fields = db_body.fields.get_all_fields(&database_collab.transact());
-
-    let mut uniq_name_set: HashSet<String> = HashSet::with_capacity(all_fields.len());
-    let mut field_by_id: HashMap<String, Field> = HashMap::with_capacity(all_fields.len());
-
-    for mut field in all_fields {
-      // if the name already exists, append the field id to the name
-      if uniq_name_set.contains(&field.name) {
-        let new_name = format!("{}-{}", field.name, field.id);
-        field.name.clone_from(&new_name);
-      }
-      uniq_name_set.insert(field.name.clone());
-      field_by_id.insert(field.id.clone(), field);
-    }
-    field_by_id
-  };
-
-  let mut selection_name_by_id: HashMap<String, String> = HashMap::new();
-  for field in field_by_id.values() {
-    add_to_selection_from_field(&mut selection_name_by_id, field);
-  }
+  let field_by_id =
+    field_by_unique_name_id(db_body.fields.get_all_fields(&database_collab.transact()));
+  let selection_name_by_id = selection_name_by_id(field_by_id.values());
 
   let database_row_details = collab_storage
     .batch_get_collab(&uid, &workspace_uuid_str, query_collabs, true)
@@ -635,7 +685,208 @@ pub async fn list_database_row_details(
   Ok(database_row_details)
 }
 
-fn convert_database_cells_human_readable(
+/// Appends rows at the end of all the views of the database. The cells of a row are given as text
+/// and keyed by field name, the names of the options are used for select fields. Returns the ids of
+/// the new rows.
+pub async fn append_database_rows(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: Uuid,
+  database_id: &str,
+  rows: Vec<HashMap<String, String>>,
+) -> Result<Vec<String>, AppError> {
+  let workspace_id_str = workspace_id.to_string();
+  let mut db_collab = get_latest_collab(
+    collab_storage,
+    GetCollabOrigin::User { uid },
+    &workspace_id_str,
+    database_id,
+    CollabType::Database,
+  )
+  .await?;
+  let db_body = DatabaseBody::from_collab(
+    &db_collab,
+    Arc::new(NoPersistenceDatabaseCollabService),
+    None,
+  )
+  .ok_or_else(|| {
+    AppError::Internal(anyhow::anyhow!(
+      "Failed to create database body from collab, db_collab_id: {}",
+      database_id,
+    ))
+  })?;
+  let fields = db_body.fields.get_all_fields(&db_collab.transact());
+
+  let mut new_rows = Vec::with_capacity(rows.len());
+  for cells_by_name in rows {
+    let mut params = CreateRowParams::new(gen_row_id(), database_id.to_string());
+    for (name, text) in cells_by_name {
+      let field = fields
+        .iter()
+        .find(|field| field.name == name)
+        .ok_or_else(|| AppError::InvalidRequest(format!("field {} is not found", name)))?;
+      params
+        .cells
+        .insert(field.id.clone(), text_to_database_cell(field, &text)?);
+    }
+    new_rows.push(Row::from(params));
+  }
+
+  let mut row_collab_params_list = Vec::with_capacity(new_rows.len());
+  let encoded_update = {
+    let mut txn = db_collab.context.transact_mut();
+    let view_ids = db_body
+      .views
+      .get_all_views(&txn)
+      .into_iter()
+      .map(|view| view.id)
+      .collect::<Vec<_>>();
+    for row in &new_rows {
+      let row_order = RowOrder::from(row);
+      for view_id in &view_ids {
+        db_body
+          .views
+          .update_database_view(&mut txn, view_id, |update| {
+            update.insert_row_order(&row_order, &OrderObjectPosition::End);
+          });
+      }
+    }
+    txn.encode_update_v1()
+  };
+  for row in new_rows.iter().cloned() {
+    let row_id = row.id.to_string();
+    let mut row_collab = Collab::new_with_origin(CollabOrigin::Server, &row_id, vec![], false);
+    DatabaseRowBody::create(row.id.clone(), &mut row_collab, row);
+    let encoded_row_collab = row_collab
+      .encode_collab_v1(|collab| CollabType::DatabaseRow.validate_require_data(collab))
+      .map_err(|err| AppError::Internal(anyhow::anyhow!("Failed to encode row: {}", err)))?;
+    row_collab_params_list.push(CollabParams {
+      object_id: row_id,
+      encoded_collab_v1: encoded_row_collab.encode_to_bytes()?.into(),
+      collab_type: CollabType::DatabaseRow,
+      embeddings: None,
+    });
+  }
+  let encoded_db_collab = db_collab
+    .encode_collab_v1(|collab| CollabType::Database.validate_require_data(collab))
+    .map_err(|err| AppError::Internal(anyhow::anyhow!("Failed to encode database: {}", err)))?;
+
+  collab_storage
+    .batch_insert_new_collab(&workspace_id_str, &uid, row_collab_params_list)
+    .await?;
+  let mut transaction = pg_pool.begin().await?;
+  let action = format!("Append rows to database: {}", database_id);
+  collab_storage
+    .upsert_new_collab_with_transaction(
+      &workspace_id_str,
+      &uid,
+      CollabParams {
+        object_id: database_id.to_string(),
+        encoded_collab_v1: encoded_db_collab.encode_to_bytes()?.into(),
+        collab_type: CollabType::Database,
+        embeddings: None,
+      },
+      &mut transaction,
+      &action,
+    )
+    .await?;
+  let activities = new_rows
+    .iter()
+    .map(|row| WorkspaceActivityParams {
+      actor_uid: Some(uid),
+      kind: WorkspaceActivityKind::DatabaseRowAdded,
+      object_id: row.id.to_string(),
+      metadata: serde_json::json!({ "database_id": database_id }),
+    })
+    .collect::<Vec<_>>();
+  insert_workspace_activities(transaction.deref_mut(), &workspace_id, &activities).await?;
+  transaction.commit().await?;
+  broadcast_update(collab_storage, database_id, encoded_update).await?;
+
+  Ok(new_rows.into_iter().map(|row| row.id.to_string()).collect())
+}
+
+/// Builds the cell of the field from its text representation.
+fn text_to_database_cell(field: &Field, text: &str) -> Result<Cell, AppError> {
+  let field_type = FieldType::from(field.field_type);
+  let data = match field_type {
+    FieldType::RichText | FieldType::URL | FieldType::Number => text.to_string(),
+    FieldType::Checkbox => {
+      let checked = matches!(
+        text.trim().to_lowercase().as_str(),
+        "yes" | "true" | "1" | "x" | "checked"
+      );
+      if checked { "Yes" } else { "No" }.to_string()
+    },
+    FieldType::SingleSelect | FieldType::MultiSelect => {
+      let mut name_by_id = HashMap::new();
+      add_to_selection_from_field(&mut name_by_id, field);
+      let names = match field_type {
+        FieldType::SingleSelect => vec![text.trim()],
+        _ => text.split(',').map(str::trim).collect(),
+      };
+      let mut option_ids = Vec::with_capacity(names.len());
+      for name in names.into_iter().filter(|name| !name.is_empty()) {
+        let option_id = name_by_id
+          .iter()
+          .find(|(_, option_name)| option_name.as_str() == name)
+          .map(|(id, _)| id.clone())
+          .ok_or_else(|| {
+            AppError::InvalidRequest(format!(
+              "option {} is not found in field {}",
+              name, field.name
+            ))
+          })?;
+        option_ids.push(option_id);
+      }
+      option_ids.join(",")
+    },
+    _ => {
+      return Err(AppError::InvalidRequest(format!(
+        "field {} of type {:?} can't be set from text",
+        field.name, field_type
+      )))
+    },
+  };
+  let mut cell = new_cell_builder(field_type);
+  cell.insert(CELL_DATA.into(), data.into());
+  Ok(cell)
+}
+
+/// Creates a map of field id to field, ensuring that the field name is unique.
+/// If the field name is repeated, it will be appended with the field id,
+/// under practical usage circumstances, no other collision should occur
+pub fn field_by_unique_name_id(all_fields: Vec<Field>) -> HashMap<String, Field> {
+  let mut uniq_name_set: HashSet<String> = HashSet::with_capacity(all_fields.len());
+  let mut field_by_id: HashMap<String, Field> = HashMap::with_capacity(all_fields.len());
+
+  for mut field in all_fields {
+    // if the name already exists, append the field id to the name
+    if uniq_name_set.contains(&field.name) {
+      let new_name = format!("{}-{}", field.name, field.id);
+      field.name.clone_from(&new_name);
+    }
+    uniq_name_set.insert(field.name.clone());
+    field_by_id.insert(field.id.clone(), field);
+  }
+  field_by_id
+}
+
+/// Names of the options of the select fields, by option id.
+pub fn selection_name_by_id<'a>(
+  fields: impl Iterator<Item = &'a Field>,
+) -> HashMap<String, String> {
+  let mut selection_name_by_id: HashMap<String, String> = HashMap::new();
+  for field in fields {
+    add_to_selection_from_field(&mut selection_name_by_id, field);
+  }
+  selection_name_by_id
+}
+
+/// Converts the cells of a row, keyed by field id, to cells keyed by field name with readable
+/// values: dates as RFC 3339 strings and select options by name.
+pub fn convert_database_cells_human_readable(
   db_cells: HashMap<String, HashMap<String, yrs::Any>>,
   field_by_id: &HashMap<String, Field>,
   selection_name_by_id: &HashMap<String, String>,
@@ -55,13 +55,26 @@ pub async fn create_archive(
 pub struct LimitedPayload {
   payload: Payload,
   remaining: usize,
+  exact: bool,
 }
 
 impl LimitedPayload {
+  /// The payload must be exactly `limit` bytes long.
   pub fn new(payload: Payload, limit: usize) -> Self {
     LimitedPayload {
       payload,
       remaining: limit,
+      exact: true,
+    }
+  }
+
+  /// The payload may be shorter than `max`, but not longer. Used when the request has no
+  /// Content-Length header.
+  pub fn with_max(payload: Payload, max: usize) -> Self {
+    LimitedPayload {
+      payload,
+      remaining: max,
+      exact: false,
     }
   }
 }
@@ -93,7 +106,7 @@ impl Stream for LimitedPayload {
       },
       Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(AppError::Internal(anyhow::anyhow!(e))))),
       Poll::Ready(None) => {
-        if self.remaining > 0 {
+        if self.exact && self.remaining > 0 {
           return Poll::Ready(Some(Err(AppError::InvalidRequest(
             "Content shorter than Content-Length".into(),
           ))));
@@ -1,10 +1,16 @@
 pub mod access_request;
+pub mod attachment;
+pub mod audit_log;
 pub mod chat;
 pub mod collab;
 pub mod data_import;
+pub mod notification;
+pub mod oauth;
 pub mod pg_listener;
+pub mod scim;
 pub mod search;
 pub mod template;
 pub mod user;
 pub mod utils;
+pub mod webhook;
 pub mod workspace;
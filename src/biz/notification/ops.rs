@@ -0,0 +1,264 @@
+use app_error::AppError;
+use database::activity_digest::{
+  delete_activity_digest_setting, select_activity_digest_settings, upsert_activity_digest_setting,
+};
+use database::notification::{
+  delete_notification_mute, insert_notifications, select_notification_email_digest_enabled,
+  select_notification_mutes, select_notifications, select_unread_notification_count,
+  update_notifications_read_at, upsert_notification_mute, upsert_notification_settings,
+};
+use database::pg_row::AFNotificationRow;
+use database::workspace::select_user_role;
+use shared_entity::dto::notification_dto::{
+  ActivityDigestFrequency, ActivityDigestSetting, ActivityDigestSettings,
+  MarkNotificationsReadParams, Notification, NotificationKind, NotificationMute,
+  NotificationSettings, QueryNotifications, RepeatedNotification, RepeatedNotificationMute,
+  ResetActivityDigestSettingParams, UnmuteNotificationParams, WorkspaceActivityDigestSetting,
+};
+use sqlx::PgPool;
+use tracing::error;
+use uuid::Uuid;
+
+const DEFAULT_NOTIFICATION_LIMIT: u32 = 50;
+const MAX_NOTIFICATION_LIMIT: u32 = 100;
+const MAX_PREVIEW_LENGTH: usize = 200;
+
+fn to_notification(row: AFNotificationRow) -> Option<Notification> {
+  Some(Notification {
+    notification_id: row.notification_id,
+    workspace_id: row.workspace_id,
+    kind: NotificationKind::from_i16(row.kind)?,
+    view_id: row.view_id,
+    object_id: row.object_id,
+    actor_uid: row.actor_uid,
+    payload: row.payload,
+    created_at: row.created_at,
+    read_at: row.read_at,
+  })
+}
+
+/// The beginning of a text shown in a notification.
+pub fn preview(text: &str) -> String {
+  let text = text.trim();
+  match text.char_indices().nth(MAX_PREVIEW_LENGTH) {
+    Some((end, _)) => format!("{}…", &text[..end]),
+    None => text.to_string(),
+  }
+}
+
+async fn check_workspace_member(
+  pg_pool: &PgPool,
+  uid: i64,
+  workspace_id: &Uuid,
+) -> Result<(), AppError> {
+  match select_user_role(pg_pool, &uid, workspace_id).await {
+    Ok(_) => Ok(()),
+    Err(AppError::RecordNotFound(_)) => Err(AppError::NotEnoughPermissions {
+      user: uid.to_string(),
+      workspace_id: workspace_id.to_string(),
+    }),
+    Err(err) => Err(err),
+  }
+}
+
+/// Notifies the recipients, except the actor and the recipients that muted the workspace or the
+/// page. A failure is logged and doesn't fail the action that caused the notification.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_notifications(
+  pg_pool: &PgPool,
+  recipients: &[i64],
+  workspace_id: &Uuid,
+  kind: NotificationKind,
+  view_id: Option<&Uuid>,
+  object_id: Option<&str>,
+  actor_uid: Option<i64>,
+  payload: serde_json::Value,
+) {
+  if let Err(err) = insert_notifications(
+    pg_pool,
+    recipients,
+    workspace_id,
+    kind,
+    view_id,
+    object_id,
+    actor_uid,
+    &payload,
+  )
+  .await
+  {
+    error!(
+      "Failed to notify {:?} of {:?} in workspace {}: {}",
+      recipients, kind, workspace_id, err
+    );
+  }
+}
+
+pub async fn list_notifications(
+  pg_pool: &PgPool,
+  uid: i64,
+  query: QueryNotifications,
+) -> Result<RepeatedNotification, AppError> {
+  let limit = query
+    .limit
+    .unwrap_or(DEFAULT_NOTIFICATION_LIMIT)
+    .clamp(1, MAX_NOTIFICATION_LIMIT) as usize;
+  // One more notification than asked for tells whether there are more
+  let mut rows = select_notifications(
+    pg_pool,
+    uid,
+    query.workspace_id.as_ref(),
+    query.unread_only,
+    query.before,
+    limit as i64 + 1,
+  )
+  .await?;
+  let has_more = rows.len() > limit;
+  rows.truncate(limit);
+  let unread_count =
+    select_unread_notification_count(pg_pool, uid, query.workspace_id.as_ref()).await?;
+  Ok(RepeatedNotification {
+    notifications: rows.into_iter().filter_map(to_notification).collect(),
+    unread_count,
+    has_more,
+  })
+}
+
+/// Returns the number of notifications marked as read.
+pub async fn mark_notifications_read(
+  pg_pool: &PgPool,
+  uid: i64,
+  params: MarkNotificationsReadParams,
+) -> Result<u64, AppError> {
+  if matches!(&params.notification_ids, Some(ids) if ids.is_empty()) {
+    return Ok(0);
+  }
+  update_notifications_read_at(
+    pg_pool,
+    uid,
+    params.notification_ids.as_deref(),
+    params.workspace_id.as_ref(),
+  )
+  .await
+}
+
+pub async fn mute_notifications(
+  pg_pool: &PgPool,
+  uid: i64,
+  mute: NotificationMute,
+) -> Result<(), AppError> {
+  check_workspace_member(pg_pool, uid, &mute.workspace_id).await?;
+  upsert_notification_mute(
+    pg_pool,
+    uid,
+    &mute.workspace_id,
+    mute.view_id.as_ref(),
+    mute.muted_until,
+  )
+  .await
+}
+
+pub async fn unmute_notifications(
+  pg_pool: &PgPool,
+  uid: i64,
+  params: UnmuteNotificationParams,
+) -> Result<(), AppError> {
+  let deleted =
+    delete_notification_mute(pg_pool, uid, &params.workspace_id, params.view_id.as_ref()).await?;
+  if !deleted {
+    return Err(AppError::RecordNotFound(format!(
+      "notifications of workspace {} and page {:?} are not muted",
+      params.workspace_id, params.view_id
+    )));
+  }
+  Ok(())
+}
+
+pub async fn list_notification_mutes(
+  pg_pool: &PgPool,
+  uid: i64,
+) -> Result<RepeatedNotificationMute, AppError> {
+  let items = select_notification_mutes(pg_pool, uid)
+    .await?
+    .into_iter()
+    .map(|row| NotificationMute {
+      workspace_id: row.workspace_id,
+      view_id: row.view_id,
+      muted_until: row.muted_until,
+    })
+    .collect();
+  Ok(RepeatedNotificationMute { items })
+}
+
+pub async fn get_notification_settings(
+  pg_pool: &PgPool,
+  uid: i64,
+) -> Result<NotificationSettings, AppError> {
+  let settings = select_notification_email_digest_enabled(pg_pool, uid)
+    .await?
+    .map(|email_digest_enabled| NotificationSettings {
+      email_digest_enabled,
+    })
+    .unwrap_or_default();
+  Ok(settings)
+}
+
+pub async fn update_notification_settings(
+  pg_pool: &PgPool,
+  uid: i64,
+  settings: NotificationSettings,
+) -> Result<(), AppError> {
+  upsert_notification_settings(pg_pool, uid, settings.email_digest_enabled).await
+}
+
+pub async fn get_activity_digest_settings(
+  pg_pool: &PgPool,
+  uid: i64,
+) -> Result<ActivityDigestSettings, AppError> {
+  let mut settings = ActivityDigestSettings::default();
+  for row in select_activity_digest_settings(pg_pool, uid).await? {
+    let frequency = match ActivityDigestFrequency::from_i16(row.frequency) {
+      Some(frequency) => frequency,
+      None => continue,
+    };
+    match row.workspace_id {
+      Some(workspace_id) => settings.workspaces.push(WorkspaceActivityDigestSetting {
+        workspace_id,
+        frequency,
+      }),
+      None => settings.frequency = frequency,
+    }
+  }
+  Ok(settings)
+}
+
+pub async fn update_activity_digest_setting(
+  pg_pool: &PgPool,
+  uid: i64,
+  setting: ActivityDigestSetting,
+) -> Result<(), AppError> {
+  if let Some(workspace_id) = &setting.workspace_id {
+    check_workspace_member(pg_pool, uid, workspace_id).await?;
+  }
+  upsert_activity_digest_setting(
+    pg_pool,
+    uid,
+    setting.workspace_id.as_ref(),
+    setting.frequency as i16,
+  )
+  .await
+}
+
+pub async fn reset_activity_digest_setting(
+  pg_pool: &PgPool,
+  uid: i64,
+  params: ResetActivityDigestSettingParams,
+) -> Result<(), AppError> {
+  let deleted = delete_activity_digest_setting(pg_pool, uid, &params.workspace_id).await?;
+  if !deleted {
+    return Err(AppError::RecordNotFound(format!(
+      "workspace {} has no activity digest setting",
+      params.workspace_id
+    )));
+  }
+  Ok(())
+}
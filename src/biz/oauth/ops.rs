@@ -0,0 +1,396 @@
+use std::fmt::Display;
+use std::ops::DerefMut;
+use std::sync::Arc;
+
+use access_control::workspace::WorkspaceAccessControl;
+use app_error::AppError;
+use authentication::api_token::{api_token_display_prefix, generate_api_token, hash_api_token};
+use authentication::oauth::{
+  generate_oauth_authorization_code, generate_oauth_client_id, generate_oauth_client_secret,
+  generate_oauth_refresh_token, verify_pkce_s256, PKCE_METHOD_S256,
+};
+use chrono::{Duration, Utc};
+use database::api_token::{delete_api_token, insert_api_token};
+use database::oauth::{
+  delete_oauth_authorization_code, delete_oauth_client, delete_oauth_refresh_token,
+  insert_oauth_authorization_code, insert_oauth_client, insert_oauth_refresh_token,
+  select_oauth_client, select_oauth_clients, AFOAuthClientWithSecret, AFOAuthRefreshToken,
+};
+use shared_entity::dto::api_token_dto::ApiTokenScope;
+use shared_entity::dto::oauth_dto::{
+  CreateOAuthClientParams, CreatedOAuthClient, OAuthAuthorizeParams, OAuthAuthorizeResponse,
+  OAuthClient, OAuthErrorResponse, OAuthTokenParams, OAuthTokenResponse,
+};
+use sqlx::{PgPool, Postgres, Transaction};
+use tracing::error;
+use uuid::Uuid;
+
+/// The client has to exchange the code for a token shortly after the user approved the access.
+const AUTHORIZATION_CODE_EXPIRATION_MINUTES: i64 = 10;
+const ACCESS_TOKEN_EXPIRATION_SECS: i64 = 3600;
+/// A client that doesn't use the access for this long has to ask the user again.
+const REFRESH_TOKEN_EXPIRATION_DAYS: i64 = 30;
+
+/// Authorization codes are only sent over https, or over http to the loopback interface for the
+/// native applications, as allowed by RFC 8252 section 7.3.
+fn check_redirect_uri(redirect_uri: &str) -> Result<(), AppError> {
+  let url = url::Url::parse(redirect_uri).map_err(|err| {
+    AppError::InvalidRequest(format!("Invalid redirect uri {}: {}", redirect_uri, err))
+  })?;
+  if url.fragment().is_some() {
+    return Err(AppError::InvalidRequest(format!(
+      "The redirect uri {} must not contain a fragment",
+      redirect_uri
+    )));
+  }
+  let is_loopback = match url.host() {
+    Some(url::Host::Domain(domain)) => domain == "localhost",
+    Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+    Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+    None => false,
+  };
+  match url.scheme() {
+    "https" if url.host().is_some() => Ok(()),
+    "http" if is_loopback => Ok(()),
+    _ => Err(AppError::InvalidRequest(format!(
+      "The redirect uri {} must use https, or http to a loopback address",
+      redirect_uri
+    ))),
+  }
+}
+
+pub async fn create_oauth_client(
+  pg_pool: &PgPool,
+  uid: i64,
+  params: CreateOAuthClientParams,
+) -> Result<CreatedOAuthClient, AppError> {
+  for redirect_uri in &params.redirect_uris {
+    check_redirect_uri(redirect_uri)?;
+  }
+
+  let client_id = generate_oauth_client_id();
+  let client_secret = params.confidential.then(generate_oauth_client_secret);
+  let client = insert_oauth_client(
+    pg_pool,
+    &client_id,
+    client_secret.as_deref().map(hash_api_token).as_deref(),
+    params.name.trim(),
+    &params.redirect_uris,
+    uid,
+  )
+  .await?;
+  Ok(CreatedOAuthClient {
+    client,
+    client_secret,
+  })
+}
+
+pub async fn list_oauth_clients(pg_pool: &PgPool, uid: i64) -> Result<Vec<OAuthClient>, AppError> {
+  select_oauth_clients(pg_pool, uid).await
+}
+
+pub async fn get_oauth_client(pg_pool: &PgPool, client_id: &str) -> Result<OAuthClient, AppError> {
+  select_oauth_client(pg_pool, client_id)
+    .await?
+    .map(|client| client.client)
+    .ok_or_else(|| AppError::RecordNotFound(format!("OAuth client {} not found", client_id)))
+}
+
+/// Deleting a client also revokes all the tokens issued to it.
+pub async fn remove_oauth_client(
+  pg_pool: &PgPool,
+  uid: i64,
+  client_id: &str,
+) -> Result<(), AppError> {
+  if delete_oauth_client(pg_pool, client_id, uid).await? {
+    Ok(())
+  } else {
+    Err(AppError::RecordNotFound(format!(
+      "OAuth client {} not found",
+      client_id
+    )))
+  }
+}
+
+/// Called once the user approved the access of the client to the workspace on the consent page.
+/// The user must have the role required by the requested scope.
+pub async fn authorize_oauth_client(
+  pg_pool: &PgPool,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  uid: i64,
+  params: OAuthAuthorizeParams,
+) -> Result<OAuthAuthorizeResponse, AppError> {
+  let client = select_oauth_client(pg_pool, &params.client_id)
+    .await?
+    .ok_or_else(|| {
+      AppError::InvalidRequest(format!("Unknown OAuth client {}", params.client_id))
+    })?;
+  if !client.client.redirect_uris.contains(&params.redirect_uri) {
+    return Err(AppError::InvalidRequest(format!(
+      "The redirect uri {} is not registered for the client",
+      params.redirect_uri
+    )));
+  }
+  let scope = ApiTokenScope::from_oauth_scope(&params.scope)
+    .ok_or_else(|| AppError::InvalidRequest(format!("Invalid scope: {}", params.scope)))?;
+
+  match (
+    &params.code_challenge,
+    params.code_challenge_method.as_deref(),
+  ) {
+    (Some(_), Some(PKCE_METHOD_S256)) => {},
+    (Some(_), _) => {
+      return Err(AppError::InvalidRequest(
+        "Only the S256 code challenge method is supported".to_string(),
+      ))
+    },
+    (None, _) if client.client_secret_hash.is_none() => {
+      return Err(AppError::InvalidRequest(
+        "Public clients must send a code challenge".to_string(),
+      ))
+    },
+    (None, _) => {},
+  }
+
+  workspace_access_control
+    .enforce_role(
+      &uid,
+      &params.workspace_id.to_string(),
+      scope.required_role(),
+    )
+    .await?;
+
+  let code = generate_oauth_authorization_code();
+  insert_oauth_authorization_code(
+    pg_pool,
+    &hash_api_token(&code),
+    &params.client_id,
+    uid,
+    &params.workspace_id,
+    scope as i32,
+    &params.redirect_uri,
+    params.code_challenge.as_deref(),
+    Utc::now() + Duration::minutes(AUTHORIZATION_CODE_EXPIRATION_MINUTES),
+  )
+  .await?;
+  Ok(OAuthAuthorizeResponse { code })
+}
+
+/// Error of the token endpoint. The codes are defined by RFC 6749 section 5.2.
+#[derive(Debug)]
+pub struct OAuthTokenError {
+  pub error: &'static str,
+  pub description: String,
+}
+
+impl OAuthTokenError {
+  fn new(error: &'static str, description: impl Display) -> Self {
+    Self {
+      error,
+      description: description.to_string(),
+    }
+  }
+}
+
+impl From<AppError> for OAuthTokenError {
+  fn from(err: AppError) -> Self {
+    error!("OAuth token request failed: {}", err);
+    Self::new("server_error", "Internal server error")
+  }
+}
+
+impl From<OAuthTokenError> for OAuthErrorResponse {
+  fn from(err: OAuthTokenError) -> Self {
+    OAuthErrorResponse {
+      error: err.error.to_string(),
+      error_description: Some(err.description),
+    }
+  }
+}
+
+/// Handles the token requests: exchanges an authorization code, or a refresh token, for a new
+/// access token and refresh token.
+pub async fn exchange_oauth_authorization_code(
+  pg_pool: &PgPool,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  params: OAuthTokenParams,
+) -> Result<OAuthTokenResponse, OAuthTokenError> {
+  match params.grant_type.as_str() {
+    "authorization_code" => {
+      exchange_authorization_code(pg_pool, workspace_access_control, params).await
+    },
+    "refresh_token" => exchange_refresh_token(pg_pool, workspace_access_control, params).await,
+    _ => Err(OAuthTokenError::new(
+      "unsupported_grant_type",
+      "Only the authorization_code and refresh_token grants are supported",
+    )),
+  }
+}
+
+/// Confidential clients authenticate with their secret on every token request.
+async fn verify_oauth_client(
+  pg_pool: &PgPool,
+  params: &OAuthTokenParams,
+) -> Result<AFOAuthClientWithSecret, OAuthTokenError> {
+  let client = select_oauth_client(pg_pool, &params.client_id)
+    .await?
+    .ok_or_else(|| OAuthTokenError::new("invalid_client", "Unknown client"))?;
+  if let Some(client_secret_hash) = &client.client_secret_hash {
+    let is_valid = params
+      .client_secret
+      .as_deref()
+      .map(|secret| hash_api_token(secret) == *client_secret_hash)
+      .unwrap_or(false);
+    if !is_valid {
+      return Err(OAuthTokenError::new(
+        "invalid_client",
+        "Invalid client secret",
+      ));
+    }
+  }
+  Ok(client)
+}
+
+/// The access token is a workspace API token bound to the client, so it is accepted by the same
+/// routes as the other API tokens.
+async fn exchange_authorization_code(
+  pg_pool: &PgPool,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  params: OAuthTokenParams,
+) -> Result<OAuthTokenResponse, OAuthTokenError> {
+  let client = verify_oauth_client(pg_pool, &params).await?;
+
+  // The code is consumed even if the request turns out to be invalid, so it can't be retried.
+  let code = delete_oauth_authorization_code(pg_pool, &hash_api_token(&params.code))
+    .await?
+    .ok_or_else(|| OAuthTokenError::new("invalid_grant", "Invalid or expired code"))?;
+  if code.client_id != params.client_id || code.redirect_uri != params.redirect_uri {
+    return Err(OAuthTokenError::new(
+      "invalid_grant",
+      "The code was issued to another client or redirect uri",
+    ));
+  }
+  if let Some(code_challenge) = &code.code_challenge {
+    let is_valid = params
+      .code_verifier
+      .as_deref()
+      .map(|verifier| verify_pkce_s256(code_challenge, verifier))
+      .unwrap_or(false);
+    if !is_valid {
+      return Err(OAuthTokenError::new(
+        "invalid_grant",
+        "Invalid code verifier",
+      ));
+    }
+  }
+
+  let mut transaction = pg_pool.begin().await.map_err(AppError::from)?;
+  let response = issue_oauth_tokens(
+    &mut transaction,
+    workspace_access_control,
+    &client,
+    code.uid,
+    code.workspace_id,
+    ApiTokenScope::from(code.scope),
+    None,
+  )
+  .await?;
+  transaction.commit().await.map_err(AppError::from)?;
+  Ok(response)
+}
+
+/// Refresh tokens are rotated: the used one and its access token are revoked in the transaction
+/// issuing the new ones, so a failed request doesn't lose the access.
+async fn exchange_refresh_token(
+  pg_pool: &PgPool,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  params: OAuthTokenParams,
+) -> Result<OAuthTokenResponse, OAuthTokenError> {
+  // The client is authenticated first, and only its own tokens are consumed, so another client
+  // can't revoke a token it got hold of.
+  let client = verify_oauth_client(pg_pool, &params).await?;
+  let refresh_token = params
+    .refresh_token
+    .as_deref()
+    .ok_or_else(|| OAuthTokenError::new("invalid_request", "Missing refresh token"))?;
+
+  let mut transaction = pg_pool.begin().await.map_err(AppError::from)?;
+  let token = delete_oauth_refresh_token(
+    transaction.deref_mut(),
+    &hash_api_token(refresh_token),
+    &client.client.client_id,
+  )
+  .await?
+  .ok_or_else(|| OAuthTokenError::new("invalid_grant", "Invalid or expired refresh token"))?;
+  let response = issue_oauth_tokens(
+    &mut transaction,
+    workspace_access_control,
+    &client,
+    token.uid,
+    token.workspace_id,
+    ApiTokenScope::from(token.scope),
+    Some(token.access_token_id),
+  )
+  .await?;
+  transaction.commit().await.map_err(AppError::from)?;
+  Ok(response)
+}
+
+async fn issue_oauth_tokens(
+  transaction: &mut Transaction<'_, Postgres>,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  client: &AFOAuthClientWithSecret,
+  uid: i64,
+  workspace_id: Uuid,
+  scope: ApiTokenScope,
+  revoked_access_token_id: Option<Uuid>,
+) -> Result<OAuthTokenResponse, OAuthTokenError> {
+  // The role of the user may have changed since the access was granted.
+  if let Err(err) = workspace_access_control
+    .enforce_role(&uid, &workspace_id.to_string(), scope.required_role())
+    .await
+  {
+    return Err(OAuthTokenError::new("invalid_grant", err));
+  }
+
+  let access_token = generate_api_token();
+  let refresh_token = generate_oauth_refresh_token();
+  let now = Utc::now();
+  if let Some(token_id) = revoked_access_token_id {
+    delete_api_token(transaction.deref_mut(), &workspace_id, &token_id).await?;
+  }
+  let api_token = insert_api_token(
+    transaction.deref_mut(),
+    &workspace_id,
+    uid,
+    &client.client.name,
+    scope,
+    &hash_api_token(&access_token),
+    &api_token_display_prefix(&access_token),
+    Some(now + Duration::seconds(ACCESS_TOKEN_EXPIRATION_SECS)),
+    Some(&client.client.client_id),
+  )
+  .await?;
+  insert_oauth_refresh_token(
+    transaction.deref_mut(),
+    &hash_api_token(&refresh_token),
+    &AFOAuthRefreshToken {
+      client_id: client.client.client_id.clone(),
+      uid,
+      workspace_id,
+      scope: scope as i32,
+      access_token_id: api_token.token_id,
+    },
+    now + Duration::days(REFRESH_TOKEN_EXPIRATION_DAYS),
+  )
+  .await?;
+
+  Ok(OAuthTokenResponse {
+    access_token,
+    token_type: "bearer".to_string(),
+    expires_in: ACCESS_TOKEN_EXPIRATION_SECS,
+    refresh_token,
+    scope: scope.as_oauth_scope().to_string(),
+    workspace_id,
+  })
+}
@@ -0,0 +1,1017 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::Context;
+use app_error::AppError;
+use database::api_token::delete_api_tokens_of_user;
+use database::role::select_workspace_member_role_info;
+use database::scim::{
+  delete_scim_user, select_scim_group, select_scim_group_count, select_scim_group_members,
+  select_scim_groups, select_scim_user, select_scim_user_count, select_scim_users,
+  select_user_has_other_shared_workspace, update_scim_user_external_id, update_workspace_group_external_id, upsert_scim_user,
+  AFScimGroupRow, AFScimUserRow,
+};
+use database::user::{select_uid_from_email, update_user};
+use database::workspace::upsert_workspace_member_with_txn;
+use database_entity::dto::AFRole;
+use gotrue::params::AdminUserParams;
+use serde_json::Value;
+use shared_entity::dto::group_dto::{CreateWorkspaceGroupParams, UpdateWorkspaceGroupParams};
+use shared_entity::dto::workspace_dto::WorkspaceMemberInvitation;
+use shared_entity::dto::scim_dto::{
+  ScimGroup, ScimGroupMember, ScimGroupParams, ScimListQuery, ScimListResponse, ScimMeta, ScimName,
+  ScimPatchParams, ScimUser, ScimUserParams, SCIM_GROUP_SCHEMA, SCIM_LIST_RESPONSE_SCHEMA,
+};
+use shared_entity::response::AppResponseError;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::biz::user::user_verify::create_user_with_workspace;
+use crate::biz::workspace::group::{
+  create_workspace_group, delete_group_member_uids, insert_group_member_uids,
+  remove_workspace_group, rename_workspace_group,
+};
+use crate::biz::workspace::ops::{invite_workspace_members, remove_workspace_members};
+use crate::biz::workspace::view_permission::refresh_workspace_view_permissions;
+use crate::state::AppState;
+
+/// Used when the identity provider doesn't give the page size.
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 200;
+/// GoTrue has no permanent ban, so deprovisioned users are banned for a century.
+const DEPROVISIONED_BAN_DURATION: &str = "876000h";
+
+/// How a request of the identity provider changed the membership of the user in the workspace.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ScimMembershipChange {
+  Provisioned,
+  /// The user has an account of its own, so it was invited instead of being added.
+  Invited,
+  Deprovisioned,
+}
+
+pub struct ScimUserUpdate {
+  pub user: ScimUser,
+  pub membership_change: Option<ScimMembershipChange>,
+}
+
+pub struct ScimGroupUpdate {
+  pub group: ScimGroup,
+  pub members_changed: bool,
+}
+
+/// The only filters supported are `eq` comparisons, which is what the identity providers use to
+/// look up a resource before creating it.
+#[derive(Debug, Eq, PartialEq)]
+struct ScimFilter {
+  /// Attribute names are case insensitive, so they are lowercased.
+  attribute: String,
+  value: String,
+}
+
+fn parse_scim_filter(filter: &str) -> Result<ScimFilter, AppError> {
+  let invalid = || AppError::InvalidRequest(format!("Unsupported filter: {}", filter));
+  let (attribute, rest) = filter
+    .trim()
+    .split_once(char::is_whitespace)
+    .ok_or_else(invalid)?;
+  let (op, value) = rest
+    .trim_start()
+    .split_once(char::is_whitespace)
+    .ok_or_else(invalid)?;
+  if !op.eq_ignore_ascii_case("eq") {
+    return Err(invalid());
+  }
+  let value = value
+    .trim()
+    .strip_prefix('"')
+    .and_then(|value| value.strip_suffix('"'))
+    .ok_or_else(invalid)?;
+  Ok(ScimFilter {
+    attribute: attribute.to_ascii_lowercase(),
+    value: value.to_string(),
+  })
+}
+
+/// Returns the 1-based start index and the page size.
+fn page_of(query: &ScimListQuery) -> (i64, i64) {
+  let start_index = query.start_index.unwrap_or(1).max(1);
+  let count = query
+    .count
+    .unwrap_or(DEFAULT_PAGE_SIZE)
+    .clamp(0, MAX_PAGE_SIZE);
+  (start_index, count)
+}
+
+fn list_response<T>(
+  total_results: i64,
+  start_index: i64,
+  resources: Vec<T>,
+) -> ScimListResponse<T> {
+  ScimListResponse {
+    schemas: vec![SCIM_LIST_RESPONSE_SCHEMA.to_string()],
+    total_results,
+    start_index,
+    items_per_page: resources.len() as i64,
+    resources,
+  }
+}
+
+fn parse_user_id(id: &str) -> Result<i64, AppError> {
+  id.trim()
+    .parse()
+    .map_err(|_| AppError::InvalidRequest(format!("Invalid user id: {}", id)))
+}
+
+fn parse_group_id(id: &str) -> Result<Uuid, AppError> {
+  Uuid::parse_str(id.trim())
+    .map_err(|_| AppError::RecordNotFound(format!("Group {} not found", id)))
+}
+
+fn parse_bool(value: &Value) -> Result<bool, AppError> {
+  match value {
+    Value::Bool(value) => Ok(*value),
+    // Some identity providers send booleans as strings.
+    Value::String(value) if value.eq_ignore_ascii_case("true") => Ok(true),
+    Value::String(value) if value.eq_ignore_ascii_case("false") => Ok(false),
+    _ => Err(AppError::InvalidRequest(format!(
+      "Expected a boolean, got {}",
+      value
+    ))),
+  }
+}
+
+fn optional_string(value: &Value) -> Option<String> {
+  value
+    .as_str()
+    .map(|value| value.trim().to_string())
+    .filter(|value| !value.is_empty())
+}
+
+async fn get_scim_user_row(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<AFScimUserRow, AppError> {
+  select_scim_user(pg_pool, workspace_id, uid)
+    .await?
+    .ok_or_else(|| AppError::RecordNotFound(format!("User {} not found", uid)))
+}
+
+pub async fn list_scim_users(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  query: &ScimListQuery,
+) -> Result<ScimListResponse<ScimUser>, AppResponseError> {
+  let (start_index, count) = page_of(query);
+  let (mut email, mut external_id) = (None, None);
+  if let Some(filter) = query.filter.as_deref().filter(|f| !f.trim().is_empty()) {
+    let filter = parse_scim_filter(filter)?;
+    match filter.attribute.as_str() {
+      "username" | "emails" | "emails.value" => email = Some(filter.value),
+      "externalid" => external_id = Some(filter.value),
+      _ => {
+        return Err(
+          AppError::InvalidRequest(format!(
+            "Unsupported filter attribute: {}",
+            filter.attribute
+          ))
+          .into(),
+        )
+      },
+    }
+  }
+
+  let total_results = select_scim_user_count(
+    pg_pool,
+    workspace_id,
+    email.as_deref(),
+    external_id.as_deref(),
+  )
+  .await?;
+  let rows = select_scim_users(
+    pg_pool,
+    workspace_id,
+    email.as_deref(),
+    external_id.as_deref(),
+    start_index - 1,
+    count,
+  )
+  .await?;
+  Ok(list_response(
+    total_results,
+    start_index,
+    rows.into_iter().map(ScimUser::from).collect(),
+  ))
+}
+
+pub async fn get_scim_user(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  id: &str,
+) -> Result<ScimUser, AppResponseError> {
+  let uid = parse_user_id(id)?;
+  Ok(get_scim_user_row(pg_pool, workspace_id, uid).await?.into())
+}
+
+/// Adds the user to the workspace as a member. A user who never signed in to AppFlowy Cloud is
+/// created, and is then managed by the identity provider. A user who already has an account is
+/// invited on behalf of `inviter` instead, so it has to accept to join the workspace.
+pub async fn create_scim_user(
+  state: &AppState,
+  inviter: &Uuid,
+  workspace_id: &Uuid,
+  params: ScimUserParams,
+) -> Result<ScimUserUpdate, AppResponseError> {
+  let email = params.email();
+  if !email.contains('@') {
+    return Err(AppError::InvalidRequest(format!("Invalid email: {}", email)).into());
+  }
+  let (uid, managed) = match select_uid_from_email(&state.pg_pool, &email).await {
+    Ok(uid) => (uid, false),
+    Err(AppError::RecordNotFound(_)) => {
+      create_provisioned_user(state, &email, params.full_name()).await?
+    },
+    Err(err) => return Err(err.into()),
+  };
+  if select_scim_user(&state.pg_pool, workspace_id, uid)
+    .await?
+    .is_some()
+  {
+    return Err(AppError::RecordAlreadyExists(format!("User {} already exists", email)).into());
+  }
+
+  upsert_scim_user(
+    &state.pg_pool,
+    workspace_id,
+    uid,
+    params.external_id.as_deref(),
+    managed,
+  )
+  .await?;
+  let row = get_scim_user_row(&state.pg_pool, workspace_id, uid).await?;
+  // The user may already have joined the workspace on its own
+  let membership_change = if params.active && !row.active {
+    Some(provision_member(state, inviter, workspace_id, &row).await?)
+  } else {
+    None
+  };
+  let user = get_scim_user_row(&state.pg_pool, workspace_id, uid)
+    .await?
+    .into();
+  Ok(ScimUserUpdate {
+    user,
+    membership_change,
+  })
+}
+
+/// Creates the GoTrue account, unless the user already signed up, and the AppFlowy user. Returns
+/// the uid, and whether the GoTrue account was created.
+async fn create_provisioned_user(
+  state: &AppState,
+  email: &str,
+  name: Option<String>,
+) -> Result<(i64, bool), AppResponseError> {
+  let admin_token = state.gotrue_admin.token().await?;
+  let mut user_metadata = BTreeMap::new();
+  if let Some(name) = &name {
+    user_metadata.insert("name".to_string(), Value::String(name.clone()));
+  }
+  let params = AdminUserParams {
+    email: email.to_string(),
+    email_confirm: true,
+    user_metadata,
+    ..Default::default()
+  };
+  let (gotrue_user, created) = match state
+    .gotrue_client
+    .admin_add_user(&admin_token, &params)
+    .await
+  {
+    Ok(user) => (user, true),
+    Err(err) => {
+      // The user signed up, but never signed in to AppFlowy Cloud.
+      let users = state
+        .gotrue_client
+        .admin_list_user(&admin_token, Some(email))
+        .await?;
+      match users
+        .users
+        .into_iter()
+        .find(|user| user.email.eq_ignore_ascii_case(email))
+      {
+        Some(user) => (user, false),
+        None => return Err(err.into()),
+      }
+    },
+  };
+
+  let user_uuid = Uuid::parse_str(&gotrue_user.id)?;
+  let txn = state
+    .pg_pool
+    .begin()
+    .await
+    .context("Begin transaction to create provisioned user")?;
+  let uid =
+    create_user_with_workspace(state, txn, &user_uuid, email, &name.unwrap_or_default()).await?;
+  Ok((uid, created))
+}
+
+/// Adds a user created by the provisioning to the workspace. The other users are invited: the
+/// identity provider doesn't own their account, so it can't make them join a workspace.
+async fn provision_member(
+  state: &AppState,
+  inviter: &Uuid,
+  workspace_id: &Uuid,
+  row: &AFScimUserRow,
+) -> Result<ScimMembershipChange, AppResponseError> {
+  if !row.managed {
+    invite_workspace_members(
+      &state.mailer,
+      &state.gotrue_admin,
+      &state.pg_pool,
+      &state.gotrue_client,
+      inviter,
+      workspace_id,
+      vec![WorkspaceMemberInvitation {
+        email: row.email.clone(),
+        role: AFRole::Member,
+      }],
+      state.config.appflowy_web_url.as_deref(),
+    )
+    .await?;
+    return Ok(ScimMembershipChange::Invited);
+  }
+
+  let mut txn = state
+    .pg_pool
+    .begin()
+    .await
+    .context("Begin transaction to provision workspace member")?;
+  upsert_workspace_member_with_txn(&mut txn, workspace_id, &row.email, AFRole::Member).await?;
+  txn
+    .commit()
+    .await
+    .context("Commit transaction to provision workspace member")?;
+  state
+    .workspace_access_control
+    .insert_role(&row.uid, workspace_id, AFRole::Member)
+    .await?;
+  upsert_scim_user(&state.pg_pool, workspace_id, row.uid, None, false).await?;
+  set_user_banned(state, &row.uuid, false).await?;
+  Ok(ScimMembershipChange::Provisioned)
+}
+
+/// Removes the user from the workspace and revokes its API tokens. A user created by the
+/// provisioning is banned as well, so its sessions can't be refreshed, unless it also joined
+/// another workspace. The other users may still use their account in other workspaces.
+async fn deprovision_member(
+  state: &AppState,
+  workspace_id: &Uuid,
+  row: &AFScimUserRow,
+) -> Result<(), AppResponseError> {
+  if let Some(info) =
+    select_workspace_member_role_info(&state.pg_pool, workspace_id, row.uid).await?
+  {
+    if AFRole::from(info.role_id) == AFRole::Owner {
+      return Err(
+        AppError::InvalidRequest("The owner of the workspace can't be deprovisioned".to_string())
+          .into(),
+      );
+    }
+  }
+  remove_workspace_members(
+    &state.pg_pool,
+    workspace_id,
+    std::slice::from_ref(&row.email),
+    state.workspace_access_control.clone(),
+  )
+  .await?;
+  delete_api_tokens_of_user(&state.pg_pool, workspace_id, row.uid).await?;
+  upsert_scim_user(&state.pg_pool, workspace_id, row.uid, None, false).await?;
+  if row.managed
+    && !select_user_has_other_shared_workspace(&state.pg_pool, workspace_id, row.uid).await?
+  {
+    set_user_banned(state, &row.uuid, true).await?;
+  }
+  Ok(())
+}
+
+async fn set_user_banned(state: &AppState, user_uuid: &Uuid, banned: bool) -> Result<(), AppError> {
+  let admin_token = state.gotrue_admin.token().await?;
+  let ban_duration = if banned {
+    DEPROVISIONED_BAN_DURATION
+  } else {
+    "none"
+  };
+  state
+    .gotrue_client
+    .admin_update_user(
+      &admin_token,
+      &user_uuid.to_string(),
+      &AdminUserParams {
+        ban_duration: ban_duration.to_string(),
+        ..Default::default()
+      },
+    )
+    .await?;
+  Ok(())
+}
+
+/// The attributes of a user that a request changes. Attributes AppFlowy doesn't store, like the
+/// title or the phone numbers, are ignored.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct ScimUserChanges {
+  active: Option<bool>,
+  external_id: Option<Option<String>>,
+  name: Option<String>,
+  email: Option<String>,
+}
+
+impl ScimUserChanges {
+  fn from_params(params: &ScimUserParams) -> Self {
+    Self {
+      active: Some(params.active),
+      external_id: Some(params.external_id.clone()),
+      name: params.full_name(),
+      email: Some(params.email()),
+    }
+  }
+
+  fn from_patch(params: &ScimPatchParams) -> Result<Self, AppError> {
+    let mut changes = Self::default();
+    for operation in &params.operations {
+      let value = operation.value.clone().unwrap_or(Value::Null);
+      match (
+        operation.op.to_ascii_lowercase().as_str(),
+        operation.path.as_deref(),
+      ) {
+        ("add" | "replace", Some(path)) => changes.set_attribute(path, value)?,
+        ("add" | "replace", None) => {
+          let attributes = value.as_object().ok_or_else(|| {
+            AppError::InvalidRequest("Expected the attributes of the user".to_string())
+          })?;
+          for (attribute, value) in attributes {
+            changes.set_attribute(attribute, value.clone())?;
+          }
+        },
+        ("remove", Some(path)) if path.eq_ignore_ascii_case("externalId") => {
+          changes.external_id = Some(None);
+        },
+        (op, path) => {
+          return Err(AppError::InvalidRequest(format!(
+            "Unsupported patch operation: {} {}",
+            op,
+            path.unwrap_or_default()
+          )))
+        },
+      }
+    }
+    Ok(changes)
+  }
+
+  fn set_attribute(&mut self, attribute: &str, value: Value) -> Result<(), AppError> {
+    match attribute.to_ascii_lowercase().as_str() {
+      "active" => self.active = Some(parse_bool(&value)?),
+      "externalid" => self.external_id = Some(optional_string(&value)),
+      "username" => self.email = optional_string(&value).map(|email| email.to_lowercase()),
+      "displayname" | "name.formatted" => {
+        if let Some(name) = optional_string(&value) {
+          self.name = Some(name);
+        }
+      },
+      "name" => {
+        let name: ScimName = serde_json::from_value(value)
+          .map_err(|err| AppError::InvalidRequest(format!("Invalid name: {}", err)))?;
+        if let Some(name) = name.full_name() {
+          self.name = Some(name);
+        }
+      },
+      _ => {},
+    }
+    Ok(())
+  }
+}
+
+pub async fn replace_scim_user(
+  state: &AppState,
+  inviter: &Uuid,
+  workspace_id: &Uuid,
+  id: &str,
+  params: ScimUserParams,
+) -> Result<ScimUserUpdate, AppResponseError> {
+  let changes = ScimUserChanges::from_params(&params);
+  update_scim_user(state, inviter, workspace_id, id, changes).await
+}
+
+pub async fn patch_scim_user(
+  state: &AppState,
+  inviter: &Uuid,
+  workspace_id: &Uuid,
+  id: &str,
+  params: ScimPatchParams,
+) -> Result<ScimUserUpdate, AppResponseError> {
+  let changes = ScimUserChanges::from_patch(&params)?;
+  update_scim_user(state, inviter, workspace_id, id, changes).await
+}
+
+/// Setting `active` to false deprovisions the user, and setting it back to true provisions it
+/// again. The name is only updated for the users created by the provisioning: the profile of the
+/// others belongs to them.
+async fn update_scim_user(
+  state: &AppState,
+  inviter: &Uuid,
+  workspace_id: &Uuid,
+  id: &str,
+  changes: ScimUserChanges,
+) -> Result<ScimUserUpdate, AppResponseError> {
+  let uid = parse_user_id(id)?;
+  let row = get_scim_user_row(&state.pg_pool, workspace_id, uid).await?;
+  if let Some(email) = &changes.email {
+    if !email.eq_ignore_ascii_case(&row.email) {
+      return Err(
+        AppError::InvalidRequest("The userName of a user can't be changed".to_string()).into(),
+      );
+    }
+  }
+
+  if let Some(external_id) = &changes.external_id {
+    update_scim_user_external_id(&state.pg_pool, workspace_id, uid, external_id.as_deref()).await?;
+  }
+  if let Some(name) = changes.name.filter(|name| row.managed && name != &row.name) {
+    update_user(&state.pg_pool, &row.uuid, Some(name), None, None).await?;
+  }
+  let membership_change = match changes.active {
+    Some(true) if !row.active => Some(provision_member(state, inviter, workspace_id, &row).await?),
+    Some(false) if row.active => {
+      deprovision_member(state, workspace_id, &row).await?;
+      Some(ScimMembershipChange::Deprovisioned)
+    },
+    _ => None,
+  };
+
+  let user = get_scim_user_row(&state.pg_pool, workspace_id, uid)
+    .await?
+    .into();
+  Ok(ScimUserUpdate {
+    user,
+    membership_change,
+  })
+}
+
+/// Deprovisions the user, and forgets its id in the identity provider.
+pub async fn remove_scim_user(
+  state: &AppState,
+  workspace_id: &Uuid,
+  id: &str,
+) -> Result<ScimUserUpdate, AppResponseError> {
+  let uid = parse_user_id(id)?;
+  let row = get_scim_user_row(&state.pg_pool, workspace_id, uid).await?;
+  let membership_change = if row.active {
+    deprovision_member(state, workspace_id, &row).await?;
+    Some(ScimMembershipChange::Deprovisioned)
+  } else {
+    None
+  };
+  delete_scim_user(&state.pg_pool, workspace_id, uid).await?;
+
+  let mut user = ScimUser::from(row);
+  user.active = false;
+  Ok(ScimUserUpdate {
+    user,
+    membership_change,
+  })
+}
+
+async fn scim_groups_from_rows(
+  pg_pool: &PgPool,
+  rows: Vec<AFScimGroupRow>,
+) -> Result<Vec<ScimGroup>, AppError> {
+  let group_ids = rows.iter().map(|row| row.group_id).collect::<Vec<_>>();
+  let mut members_by_group: HashMap<Uuid, Vec<ScimGroupMember>> = HashMap::new();
+  for member in select_scim_group_members(pg_pool, &group_ids).await? {
+    members_by_group
+      .entry(member.group_id)
+      .or_default()
+      .push(ScimGroupMember {
+        value: member.uid.to_string(),
+        display: Some(member.email),
+      });
+  }
+  let groups = rows
+    .into_iter()
+    .map(|row| ScimGroup {
+      schemas: vec![SCIM_GROUP_SCHEMA.to_string()],
+      id: row.group_id.to_string(),
+      external_id: row.external_id,
+      display_name: row.name,
+      members: members_by_group.remove(&row.group_id).unwrap_or_default(),
+      meta: ScimMeta {
+        resource_type: "Group".to_string(),
+        created: row.created_at,
+        last_modified: row.created_at,
+      },
+    })
+    .collect();
+  Ok(groups)
+}
+
+async fn get_scim_group_by_id(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+) -> Result<ScimGroup, AppError> {
+  let row = select_scim_group(pg_pool, workspace_id, group_id)
+    .await?
+    .ok_or_else(|| AppError::RecordNotFound(format!("Group {} not found", group_id)))?;
+  let mut groups = scim_groups_from_rows(pg_pool, vec![row]).await?;
+  groups
+    .pop()
+    .ok_or_else(|| AppError::RecordNotFound(format!("Group {} not found", group_id)))
+}
+
+pub async fn list_scim_groups(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  query: &ScimListQuery,
+) -> Result<ScimListResponse<ScimGroup>, AppResponseError> {
+  let (start_index, count) = page_of(query);
+  let (mut name, mut external_id) = (None, None);
+  if let Some(filter) = query.filter.as_deref().filter(|f| !f.trim().is_empty()) {
+    let filter = parse_scim_filter(filter)?;
+    match filter.attribute.as_str() {
+      "displayname" => name = Some(filter.value),
+      "externalid" => external_id = Some(filter.value),
+      _ => {
+        return Err(
+          AppError::InvalidRequest(format!(
+            "Unsupported filter attribute: {}",
+            filter.attribute
+          ))
+          .into(),
+        )
+      },
+    }
+  }
+
+  let total_results = select_scim_group_count(
+    pg_pool,
+    workspace_id,
+    name.as_deref(),
+    external_id.as_deref(),
+  )
+  .await?;
+  let rows = select_scim_groups(
+    pg_pool,
+    workspace_id,
+    name.as_deref(),
+    external_id.as_deref(),
+    start_index - 1,
+    count,
+  )
+  .await?;
+  let groups = scim_groups_from_rows(pg_pool, rows).await?;
+  Ok(list_response(total_results, start_index, groups))
+}
+
+pub async fn get_scim_group(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  id: &str,
+) -> Result<ScimGroup, AppResponseError> {
+  let group_id = parse_group_id(id)?;
+  Ok(get_scim_group_by_id(pg_pool, workspace_id, &group_id).await?)
+}
+
+/// The attributes of a group that a request changes.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct ScimGroupChanges {
+  display_name: Option<String>,
+  external_id: Option<Option<String>>,
+  /// Replaces the members of the group, before `added` and `removed` are applied.
+  members: Option<Vec<i64>>,
+  added: Vec<i64>,
+  removed: Vec<i64>,
+}
+
+impl ScimGroupChanges {
+  fn from_params(params: &ScimGroupParams) -> Result<Self, AppError> {
+    let members = params
+      .members
+      .iter()
+      .map(|member| parse_user_id(&member.value))
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(Self {
+      display_name: Some(params.display_name.trim().to_string()),
+      external_id: Some(params.external_id.clone()),
+      members: Some(members),
+      ..Default::default()
+    })
+  }
+
+  fn from_patch(params: &ScimPatchParams) -> Result<Self, AppError> {
+    let mut changes = Self::default();
+    for operation in &params.operations {
+      let op = operation.op.to_ascii_lowercase();
+      let value = operation.value.clone().unwrap_or(Value::Null);
+      match (op.as_str(), operation.path.as_deref().map(str::trim)) {
+        ("add" | "replace", Some(path)) => changes.set_attribute(&op, path, value)?,
+        ("add" | "replace", None) => {
+          let attributes = value.as_object().ok_or_else(|| {
+            AppError::InvalidRequest("Expected the attributes of the group".to_string())
+          })?;
+          for (attribute, value) in attributes {
+            changes.set_attribute(&op, attribute, value.clone())?;
+          }
+        },
+        ("remove", Some(path)) if path.eq_ignore_ascii_case("members") => {
+          match operation.value.as_ref() {
+            Some(value) => changes.removed.extend(member_uids(value)?),
+            None => changes.members = Some(vec![]),
+          }
+        },
+        ("remove", Some(path)) if path.eq_ignore_ascii_case("externalId") => {
+          changes.external_id = Some(None);
+        },
+        ("remove", Some(path)) => changes.removed.push(member_uid_from_path(path)?),
+        (op, path) => {
+          return Err(AppError::InvalidRequest(format!(
+            "Unsupported patch operation: {} {}",
+            op,
+            path.unwrap_or_default()
+          )))
+        },
+      }
+    }
+    Ok(changes)
+  }
+
+  fn set_attribute(&mut self, op: &str, attribute: &str, value: Value) -> Result<(), AppError> {
+    match attribute.to_ascii_lowercase().as_str() {
+      "displayname" => self.display_name = optional_string(&value),
+      "externalid" => self.external_id = Some(optional_string(&value)),
+      "members" if op == "add" => self.added.extend(member_uids(&value)?),
+      "members" => {
+        self.members = Some(member_uids(&value)?);
+        self.added.clear();
+        self.removed.clear();
+      },
+      _ => {},
+    }
+    Ok(())
+  }
+}
+
+/// Parses a member or a list of members.
+fn member_uids(value: &Value) -> Result<Vec<i64>, AppError> {
+  let members: Vec<ScimGroupMember> = match value {
+    Value::Array(_) => serde_json::from_value(value.clone()),
+    _ => serde_json::from_value(value.clone()).map(|member| vec![member]),
+  }
+  .map_err(|err| AppError::InvalidRequest(format!("Invalid members: {}", err)))?;
+  members
+    .iter()
+    .map(|member| parse_user_id(&member.value))
+    .collect()
+}
+
+/// Parses the member of a path like `members[value eq "42"]`.
+fn member_uid_from_path(path: &str) -> Result<i64, AppError> {
+  let invalid = || AppError::InvalidRequest(format!("Unsupported path: {}", path));
+  let filter = path
+    .get(..8)
+    .filter(|prefix| prefix.eq_ignore_ascii_case("members["))
+    .and_then(|_| path[8..].strip_suffix(']'))
+    .ok_or_else(invalid)?;
+  let filter = parse_scim_filter(filter)?;
+  if filter.attribute != "value" {
+    return Err(invalid());
+  }
+  parse_user_id(&filter.value)
+}
+
+pub async fn create_scim_group(
+  state: &AppState,
+  uid: i64,
+  workspace_id: &Uuid,
+  params: ScimGroupParams,
+) -> Result<ScimGroupUpdate, AppResponseError> {
+  let changes = ScimGroupChanges::from_params(&params)?;
+  let create_params = CreateWorkspaceGroupParams {
+    name: params.display_name.trim().to_string(),
+  };
+  create_params.validate().map_err(AppError::from)?;
+  let group = create_workspace_group(&state.pg_pool, uid, workspace_id, create_params).await?;
+  let changes = ScimGroupChanges {
+    display_name: None,
+    ..changes
+  };
+  update_scim_group_by_id(state, uid, workspace_id, &group.group_id, changes).await
+}
+
+pub async fn replace_scim_group(
+  state: &AppState,
+  uid: i64,
+  workspace_id: &Uuid,
+  id: &str,
+  params: ScimGroupParams,
+) -> Result<ScimGroupUpdate, AppResponseError> {
+  let group_id = parse_group_id(id)?;
+  let changes = ScimGroupChanges::from_params(&params)?;
+  update_scim_group_by_id(state, uid, workspace_id, &group_id, changes).await
+}
+
+pub async fn patch_scim_group(
+  state: &AppState,
+  uid: i64,
+  workspace_id: &Uuid,
+  id: &str,
+  params: ScimPatchParams,
+) -> Result<ScimGroupUpdate, AppResponseError> {
+  let group_id = parse_group_id(id)?;
+  let changes = ScimGroupChanges::from_patch(&params)?;
+  update_scim_group_by_id(state, uid, workspace_id, &group_id, changes).await
+}
+
+/// The members of a group must be members of the workspace. The access levels of the spaces and
+/// pages are refreshed if the members changed.
+async fn update_scim_group_by_id(
+  state: &AppState,
+  uid: i64,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+  changes: ScimGroupChanges,
+) -> Result<ScimGroupUpdate, AppResponseError> {
+  let group = get_scim_group_by_id(&state.pg_pool, workspace_id, group_id).await?;
+  if let Some(name) = changes
+    .display_name
+    .filter(|name| name != &group.display_name)
+  {
+    let params = UpdateWorkspaceGroupParams { name };
+    params.validate().map_err(AppError::from)?;
+    rename_workspace_group(&state.pg_pool, workspace_id, group_id, params).await?;
+  }
+  if let Some(external_id) = &changes.external_id {
+    update_workspace_group_external_id(
+      &state.pg_pool,
+      workspace_id,
+      group_id,
+      external_id.as_deref(),
+    )
+    .await?;
+  }
+
+  let current = group
+    .members
+    .iter()
+    .map(|member| parse_user_id(&member.value))
+    .collect::<Result<BTreeSet<_>, _>>()?;
+  let mut target = match changes.members {
+    Some(members) => members.into_iter().collect(),
+    None => current.clone(),
+  };
+  target.extend(changes.added);
+  for removed in &changes.removed {
+    target.remove(removed);
+  }
+  let to_add = target.difference(&current).copied().collect::<Vec<_>>();
+  let to_remove = current.difference(&target).copied().collect::<Vec<_>>();
+  for member_uid in &to_add {
+    if select_workspace_member_role_info(&state.pg_pool, workspace_id, *member_uid)
+      .await?
+      .is_none()
+    {
+      return Err(
+        AppError::InvalidRequest(format!(
+          "User {} is not a member of the workspace",
+          member_uid
+        ))
+        .into(),
+      );
+    }
+  }
+
+  let members_changed = !to_add.is_empty() || !to_remove.is_empty();
+  if members_changed {
+    insert_group_member_uids(
+      &state.pg_pool,
+      &state.workspace_access_control,
+      group_id,
+      &to_add,
+    )
+    .await?;
+    delete_group_member_uids(
+      &state.pg_pool,
+      &state.workspace_access_control,
+      group_id,
+      &to_remove,
+    )
+    .await?;
+    refresh_workspace_view_permissions(
+      &state.pg_pool,
+      &state.collab_access_control_storage,
+      &state.collab_access_control,
+      uid,
+      workspace_id,
+    )
+    .await?;
+  }
+
+  let group = get_scim_group_by_id(&state.pg_pool, workspace_id, group_id).await?;
+  Ok(ScimGroupUpdate {
+    group,
+    members_changed,
+  })
+}
+
+pub async fn remove_scim_group(
+  state: &AppState,
+  uid: i64,
+  workspace_id: &Uuid,
+  id: &str,
+) -> Result<(), AppResponseError> {
+  let group_id = parse_group_id(id)?;
+  remove_workspace_group(
+    &state.pg_pool,
+    &state.collab_access_control_storage,
+    state.workspace_access_control.clone(),
+    &state.collab_access_control,
+    uid,
+    workspace_id,
+    &group_id,
+  )
+  .await?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn parse_scim_filter_test() {
+    assert_eq!(
+      parse_scim_filter(r#"userName eq "Alice@Example.com""#).unwrap(),
+      ScimFilter {
+        attribute: "username".to_string(),
+        value: "Alice@Example.com".to_string(),
+      }
+    );
+    assert_eq!(
+      parse_scim_filter(r#"displayName EQ "Design team""#)
+        .unwrap()
+        .value,
+      "Design team"
+    );
+    assert!(parse_scim_filter(r#"userName co "alice""#).is_err());
+    assert!(parse_scim_filter("userName eq alice").is_err());
+  }
+
+  #[test]
+  fn user_changes_from_patch_test() {
+    let params: ScimPatchParams = serde_json::from_value(json!({
+      "Operations": [
+        { "op": "Replace", "path": "active", "value": "False" },
+        { "op": "replace", "value": { "externalId": "00u1", "name": { "givenName": "Ada", "familyName": "Lovelace" } } },
+      ]
+    }))
+    .unwrap();
+    assert_eq!(
+      ScimUserChanges::from_patch(&params).unwrap(),
+      ScimUserChanges {
+        active: Some(false),
+        external_id: Some(Some("00u1".to_string())),
+        name: Some("Ada Lovelace".to_string()),
+        email: None,
+      }
+    );
+  }
+
+  #[test]
+  fn group_changes_from_patch_test() {
+    let params: ScimPatchParams = serde_json::from_value(json!({
+      "Operations": [
+        { "op": "add", "path": "members", "value": [{ "value": "1" }, { "value": "2" }] },
+        { "op": "remove", "path": "members[value eq \"3\"]" },
+        { "op": "replace", "path": "displayName", "value": "Designers" },
+      ]
+    }))
+    .unwrap();
+    assert_eq!(
+      ScimGroupChanges::from_patch(&params).unwrap(),
+      ScimGroupChanges {
+        display_name: Some("Designers".to_string()),
+        added: vec![1, 2],
+        removed: vec![3],
+        ..Default::default()
+      }
+    );
+
+    let params: ScimPatchParams = serde_json::from_value(json!({
+      "Operations": [{ "op": "remove", "path": "members[display eq \"3\"]" }]
+    }))
+    .unwrap();
+    assert!(ScimGroupChanges::from_patch(&params).is_err());
+  }
+}
@@ -2,6 +2,7 @@ use std::ops::DerefMut;
 
 use anyhow::{Context, Result};
 use sqlx::types::uuid;
+use sqlx::{Postgres, Transaction};
 use tracing::{event, instrument, trace};
 
 use app_error::AppError;
@@ -13,11 +14,16 @@ use workspace_template::document::getting_started::GettingStartedTemplate;
 use crate::biz::user::user_init::initialize_workspace_for_user;
 use crate::state::AppState;
 
+pub struct VerifiedUser {
+  pub user_uuid: uuid::Uuid,
+  /// True if the user was created by the verification
+  pub is_new: bool,
+}
+
 /// Verify the token from the gotrue server and create the user if it is a new user
-/// Return true if the user is a new user
 ///
 #[instrument(skip_all, err)]
-pub async fn verify_token(access_token: &str, state: &AppState) -> Result<bool, AppError> {
+pub async fn verify_token(access_token: &str, state: &AppState) -> Result<VerifiedUser, AppError> {
   let user = state.gotrue_client.user_info(access_token).await?;
   let user_uuid = uuid::Uuid::parse_str(&user.id)?;
   let name = name_from_user_metadata(&user.user_metadata);
@@ -31,44 +37,56 @@ pub async fn verify_token(access_token: &str, state: &AppState) -> Result<bool,
 
   let is_new = !is_user_exist(txn.deref_mut(), &user_uuid).await?;
   if is_new {
-    let new_uid = state.id_gen.write().await.next_id();
-    event!(tracing::Level::INFO, "create new user:{}", new_uid);
-    let workspace_id =
-      create_user(txn.deref_mut(), new_uid, &user_uuid, &user.email, &name).await?;
-    let workspace_row = select_workspace(txn.deref_mut(), &workspace_id).await?;
-
-    // It's essential to cache the user's role because subsequent actions will rely on this cached information.
-    state
-      .workspace_access_control
-      .insert_role(&new_uid, &workspace_id, AFRole::Owner)
-      .await?;
-    // Need to commit the transaction for the record in `af_user` to be inserted
-    // so that `initialize_workspace_for_user` will be able to find the user
-    txn
-      .commit()
-      .await
-      .context("fail to commit transaction to verify token")?;
-
-    // Create a workspace with the GetStarted template
-    let mut txn2 = state.pg_pool.begin().await?;
-    initialize_workspace_for_user(
-      new_uid,
-      &user_uuid,
-      &workspace_row,
-      &mut txn2,
-      vec![GettingStartedTemplate],
-      &state.collab_access_control_storage,
-    )
-    .await?;
-    txn2
-      .commit()
-      .await
-      .context("fail to commit transaction to initialize workspace")?;
+    create_user_with_workspace(state, txn, &user_uuid, &user.email, &name).await?;
   } else {
     trace!("user already exists:{},{}", user.id, user.email);
   }
 
-  Ok(is_new)
+  Ok(VerifiedUser { user_uuid, is_new })
+}
+
+/// Creates the user of a GoTrue account along with its own workspace, which is initialized with
+/// the getting started template. Returns the uid of the new user.
+pub async fn create_user_with_workspace(
+  state: &AppState,
+  mut txn: Transaction<'_, Postgres>,
+  user_uuid: &uuid::Uuid,
+  email: &str,
+  name: &str,
+) -> Result<i64, AppError> {
+  let new_uid = state.id_gen.write().await.next_id();
+  event!(tracing::Level::INFO, "create new user:{}", new_uid);
+  let workspace_id = create_user(txn.deref_mut(), new_uid, user_uuid, email, name).await?;
+  let workspace_row = select_workspace(txn.deref_mut(), &workspace_id).await?;
+
+  // It's essential to cache the user's role because subsequent actions will rely on this cached information.
+  state
+    .workspace_access_control
+    .insert_role(&new_uid, &workspace_id, AFRole::Owner)
+    .await?;
+  // Need to commit the transaction for the record in `af_user` to be inserted
+  // so that `initialize_workspace_for_user` will be able to find the user
+  txn
+    .commit()
+    .await
+    .context("fail to commit transaction to verify token")?;
+
+  // Create a workspace with the GetStarted template
+  let mut txn2 = state.pg_pool.begin().await?;
+  initialize_workspace_for_user(
+    new_uid,
+    user_uuid,
+    &workspace_row,
+    &mut txn2,
+    vec![GettingStartedTemplate],
+    &state.collab_access_control_storage,
+  )
+  .await?;
+  txn2
+    .commit()
+    .await
+    .context("fail to commit transaction to initialize workspace")?;
+  Ok(new_uid)
 }
 
 // Best effort to get user's name after oauth
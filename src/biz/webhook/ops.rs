@@ -0,0 +1,111 @@
+use app_error::AppError;
+use database::webhook::{
+  delete_webhook, insert_webhook, select_webhook_deliveries, select_webhooks, update_webhook,
+};
+use infra::url_util::check_public_http_url;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use shared_entity::dto::webhook_dto::{
+  CreateWebhookParams, CreatedWebhook, UpdateWebhookParams, Webhook, WebhookDelivery,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const WEBHOOK_SECRET_PREFIX: &str = "af_whsec_";
+const MAX_DELIVERY_LIMIT: i64 = 200;
+
+/// Only http and https urls that resolve to public addresses can receive deliveries. The worker
+/// checks the url again before every delivery, since the address of the host can change.
+async fn check_webhook_url(url: &str) -> Result<(), AppError> {
+  check_public_http_url(url)
+    .await
+    .map_err(|err| AppError::InvalidRequest(format!("Invalid webhook url: {}", err)))?;
+  Ok(())
+}
+
+fn generate_webhook_secret() -> String {
+  let secret: String = rand::thread_rng()
+    .sample_iter(&Alphanumeric)
+    .take(32)
+    .map(char::from)
+    .collect();
+  format!("{}{}", WEBHOOK_SECRET_PREFIX, secret)
+}
+
+pub async fn create_webhook(
+  pg_pool: &PgPool,
+  uid: i64,
+  workspace_id: &Uuid,
+  params: CreateWebhookParams,
+) -> Result<CreatedWebhook, AppError> {
+  check_webhook_url(&params.url).await?;
+  let secret = generate_webhook_secret();
+  let webhook = insert_webhook(
+    pg_pool,
+    workspace_id,
+    &params.url,
+    &secret,
+    &params.events,
+    uid,
+  )
+  .await?;
+  Ok(CreatedWebhook { webhook, secret })
+}
+
+pub async fn list_webhooks(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+) -> Result<Vec<Webhook>, AppError> {
+  select_webhooks(pg_pool, workspace_id).await
+}
+
+pub async fn patch_webhook(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  webhook_id: &Uuid,
+  params: UpdateWebhookParams,
+) -> Result<Webhook, AppError> {
+  if let Some(url) = &params.url {
+    check_webhook_url(url).await?;
+  }
+  update_webhook(
+    pg_pool,
+    workspace_id,
+    webhook_id,
+    params.url.as_deref(),
+    params.events.as_deref(),
+    params.enabled,
+  )
+  .await?
+  .ok_or_else(|| AppError::RecordNotFound(format!("Webhook {} not found", webhook_id)))
+}
+
+pub async fn remove_webhook(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  webhook_id: &Uuid,
+) -> Result<(), AppError> {
+  if delete_webhook(pg_pool, workspace_id, webhook_id).await? {
+    Ok(())
+  } else {
+    Err(AppError::RecordNotFound(format!(
+      "Webhook {} not found",
+      webhook_id
+    )))
+  }
+}
+
+pub async fn list_webhook_deliveries(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  webhook_id: &Uuid,
+  limit: i64,
+) -> Result<Vec<WebhookDelivery>, AppError> {
+  select_webhook_deliveries(
+    pg_pool,
+    workspace_id,
+    webhook_id,
+    limit.clamp(1, MAX_DELIVERY_LIMIT),
+  )
+  .await
+}
@@ -0,0 +1,177 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use access_control::act::Action;
+use access_control::collab::CollabAccessControl;
+use app_error::AppError;
+use appflowy_collaborate::collab::storage::CollabAccessControlStorage;
+use collab_folder::Folder;
+use database::collab::GetCollabOrigin;
+use database::workspace_activity::{
+  insert_workspace_activities, select_workspace_activities, AFWorkspaceActivityRow,
+  WorkspaceActivityParams,
+};
+use shared_entity::dto::workspace_activity_dto::{
+  QueryWorkspaceActivityParams, RepeatedWorkspaceActivity, WorkspaceActivity, WorkspaceActivityKind,
+};
+use sqlx::PgPool;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::biz::collab::folder_view::{hidden_space_ids, is_view_visible};
+use crate::biz::collab::ops::{get_latest_collab_folder, get_latest_workspace_database};
+
+const DEFAULT_ACTIVITY_LIMIT: i64 = 50;
+const MAX_ACTIVITY_LIMIT: i64 = 200;
+
+/// Records activities that already happened. A failure is logged rather than returned, so the
+/// action is not reported as failed to the user.
+pub async fn record_workspace_activities(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  activities: &[WorkspaceActivityParams],
+) {
+  if let Err(err) = insert_workspace_activities(pg_pool, workspace_id, activities).await {
+    error!(
+      "Failed to record the activities of workspace {}: {}",
+      workspace_id, err
+    );
+  }
+}
+
+/// Returns the activities of the workspace, the most recent first. The activities of the pages in
+/// the private spaces of other members, of the pages the user can't read, and of the pages deleted
+/// since, are left out. A database row activity is kept if the user can read one of the views of
+/// the database.
+pub async fn get_workspace_activities(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  uid: i64,
+  workspace_id: &Uuid,
+  params: &QueryWorkspaceActivityParams,
+) -> Result<RepeatedWorkspaceActivity, AppError> {
+  let limit = params
+    .limit
+    .unwrap_or(DEFAULT_ACTIVITY_LIMIT)
+    .clamp(1, MAX_ACTIVITY_LIMIT);
+  let rows =
+    select_workspace_activities(pg_pool, workspace_id, params.since, params.before, limit).await?;
+  let next_cursor = if rows.len() as i64 == limit {
+    rows.last().map(|row| row.activity_id)
+  } else {
+    None
+  };
+
+  let activities: Vec<WorkspaceActivity> =
+    rows.into_iter().filter_map(to_workspace_activity).collect();
+  let has_page_activity = activities.iter().any(|activity| activity.kind.is_page());
+  let has_row_activity = activities
+    .iter()
+    .any(|activity| activity.kind == WorkspaceActivityKind::DatabaseRowAdded);
+  if !has_page_activity && !has_row_activity {
+    return Ok(RepeatedWorkspaceActivity {
+      items: activities,
+      next_cursor,
+    });
+  }
+
+  let collab_origin = GetCollabOrigin::User { uid };
+  let folder = get_latest_collab_folder(
+    collab_storage,
+    collab_origin.clone(),
+    &workspace_id.to_string(),
+  )
+  .await?;
+  let database_views: HashMap<String, Vec<String>> = if has_row_activity {
+    let (_, workspace_database) =
+      get_latest_workspace_database(collab_storage, pg_pool, collab_origin, *workspace_id).await?;
+    workspace_database
+      .get_all_database_meta()
+      .into_iter()
+      .map(|meta| (meta.database_id, meta.linked_views))
+      .collect()
+  } else {
+    HashMap::new()
+  };
+
+  let mut view_reader = ViewReader {
+    collab_access_control,
+    folder: &folder,
+    hidden_spaces: hidden_space_ids(&folder),
+    workspace_id: workspace_id.to_string(),
+    uid,
+    readable: HashMap::new(),
+  };
+  let mut items = Vec::with_capacity(activities.len());
+  for activity in activities {
+    let is_visible = if activity.kind.is_page() {
+      view_reader.can_read(&activity.object_id).await
+    } else if activity.kind == WorkspaceActivityKind::DatabaseRowAdded {
+      let view_ids = activity
+        .metadata
+        .get("database_id")
+        .and_then(|database_id| database_id.as_str())
+        .and_then(|database_id| database_views.get(database_id))
+        .map(|view_ids| view_ids.as_slice())
+        .unwrap_or_default();
+      let mut is_visible = false;
+      for view_id in view_ids {
+        if view_reader.can_read(view_id).await {
+          is_visible = true;
+          break;
+        }
+      }
+      is_visible
+    } else {
+      true
+    };
+    if is_visible {
+      items.push(activity);
+    }
+  }
+  Ok(RepeatedWorkspaceActivity { items, next_cursor })
+}
+
+/// Tells whether the user can read the views of the folder. The answers are cached, since many
+/// activities refer to the same views.
+struct ViewReader<'a> {
+  collab_access_control: &'a Arc<dyn CollabAccessControl>,
+  folder: &'a Folder,
+  hidden_spaces: HashSet<String>,
+  workspace_id: String,
+  uid: i64,
+  readable: HashMap<String, bool>,
+}
+
+impl ViewReader<'_> {
+  async fn can_read(&mut self, view_id: &str) -> bool {
+    if let Some(readable) = self.readable.get(view_id) {
+      return *readable;
+    }
+    let readable = is_view_visible(
+      self.folder,
+      &self.workspace_id,
+      view_id,
+      &self.hidden_spaces,
+    ) && self
+      .collab_access_control
+      .enforce_action(&self.workspace_id, &self.uid, view_id, Action::Read)
+      .await
+      .is_ok();
+    self.readable.insert(view_id.to_string(), readable);
+    readable
+  }
+}
+
+fn to_workspace_activity(row: AFWorkspaceActivityRow) -> Option<WorkspaceActivity> {
+  Some(WorkspaceActivity {
+    activity_id: row.activity_id,
+    kind: WorkspaceActivityKind::from_i16(row.kind)?,
+    actor_uid: row.actor_uid,
+    actor_name: row.actor_name,
+    object_id: row.object_id,
+    metadata: row.metadata,
+    created_at: row.created_at,
+  })
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use access_control::workspace::WorkspaceAccessControl;
+use app_error::AppError;
+use authentication::api_token::{api_token_display_prefix, generate_api_token, hash_api_token};
+use chrono::Utc;
+use database::api_token::{
+  delete_api_token, insert_api_token, select_api_token_creator, select_api_tokens,
+};
+use database_entity::dto::AFRole;
+use shared_entity::dto::api_token_dto::{ApiToken, CreateApiTokenParams, CreatedApiToken};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Creates a token acting on behalf of the user in the workspace. The user must have the role
+/// required by the scope, so a token can't grant more than its creator is allowed to do.
+pub async fn create_api_token(
+  pg_pool: &PgPool,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  uid: i64,
+  workspace_id: &Uuid,
+  params: CreateApiTokenParams,
+) -> Result<CreatedApiToken, AppError> {
+  workspace_access_control
+    .enforce_role(
+      &uid,
+      &workspace_id.to_string(),
+      params.scope.required_role(),
+    )
+    .await?;
+  if let Some(expires_at) = params.expires_at {
+    if expires_at <= Utc::now() {
+      return Err(AppError::InvalidRequest(
+        "The expiration date of the token must be in the future".to_string(),
+      ));
+    }
+  }
+
+  let token = generate_api_token();
+  let api_token = insert_api_token(
+    pg_pool,
+    workspace_id,
+    uid,
+    params.name.trim(),
+    params.scope,
+    &hash_api_token(&token),
+    &api_token_display_prefix(&token),
+    params.expires_at,
+    None,
+  )
+  .await?;
+  Ok(CreatedApiToken { token, api_token })
+}
+
+pub async fn list_api_tokens(
+  pg_pool: &PgPool,
+  uid: i64,
+  workspace_id: &Uuid,
+) -> Result<Vec<ApiToken>, AppError> {
+  select_api_tokens(pg_pool, workspace_id, uid).await
+}
+
+/// The creator of a token can revoke it. The owner of the workspace can revoke any token of the
+/// workspace.
+pub async fn revoke_api_token(
+  pg_pool: &PgPool,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  uid: i64,
+  workspace_id: &Uuid,
+  token_id: &Uuid,
+) -> Result<(), AppError> {
+  let creator = select_api_token_creator(pg_pool, workspace_id, token_id)
+    .await?
+    .ok_or_else(|| AppError::RecordNotFound(format!("API token {} not found", token_id)))?;
+  if creator != uid {
+    workspace_access_control
+      .enforce_role(&uid, &workspace_id.to_string(), AFRole::Owner)
+      .await?;
+  }
+  delete_api_token(pg_pool, workspace_id, token_id).await
+}
@@ -0,0 +1,311 @@
+use app_error::AppError;
+use chrono::Utc;
+use database::comment_moderation::{
+  delete_workspace_comment_ban, select_pending_comment_ids, select_published_view_comment_policy,
+  select_user_comment_count_since, select_workspace_comment_bans,
+  select_workspace_comment_moderation, update_comment_approval_status,
+  update_user_comments_deletion_status, upsert_published_view_comment_settings,
+  upsert_workspace_comment_ban, upsert_workspace_comment_moderation,
+};
+use database::pg_row::AFPublishedViewCommentPolicyRow;
+use database_entity::dto::{AFWebUser, GlobalComment};
+use shared_entity::dto::comment_moderation_dto::{
+  BanCommenterParams, BannedCommenter, CommentModeration, PublishedViewCommentSettings,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const MAX_BLOCKED_KEYWORDS: usize = 500;
+const MAX_BLOCKED_KEYWORD_LENGTH: usize = 100;
+
+/// Checks that the user can comment on the published view. Returns whether the comment must be
+/// approved by a moderator before being shown.
+pub async fn check_user_can_comment(
+  pg_pool: &PgPool,
+  view_id: &Uuid,
+  user_uuid: &Uuid,
+  content: &str,
+) -> Result<bool, AppError> {
+  let policy = select_published_view_comment_policy(pg_pool, view_id, user_uuid).await?;
+  if policy.is_moderator {
+    return Ok(false);
+  }
+  check_user_can_interact(&policy)?;
+
+  let lowercase_content = content.to_lowercase();
+  if policy
+    .blocked_keywords
+    .iter()
+    .any(|keyword| lowercase_content.contains(keyword.as_str()))
+  {
+    return Err(AppError::InvalidRequest(
+      "The comment contains a blocked keyword".to_string(),
+    ));
+  }
+
+  if let (Some(workspace_id), Some(max_comments_per_hour)) =
+    (policy.workspace_id, policy.max_comments_per_hour)
+  {
+    let since = Utc::now() - chrono::Duration::hours(1);
+    let count = select_user_comment_count_since(pg_pool, &workspace_id, user_uuid, since).await?;
+    if count >= max_comments_per_hour as i64 {
+      return Err(AppError::TooManyRequests(format!(
+        "A user can write at most {} comments per hour",
+        max_comments_per_hour
+      )));
+    }
+  }
+  Ok(policy.require_approval)
+}
+
+pub async fn check_user_can_react(
+  pg_pool: &PgPool,
+  view_id: &Uuid,
+  user_uuid: &Uuid,
+) -> Result<(), AppError> {
+  let policy = select_published_view_comment_policy(pg_pool, view_id, user_uuid).await?;
+  if policy.is_moderator {
+    return Ok(());
+  }
+  check_user_can_interact(&policy)
+}
+
+fn check_user_can_interact(policy: &AFPublishedViewCommentPolicyRow) -> Result<(), AppError> {
+  if !policy.comments_enabled {
+    return Err(AppError::InvalidRequest(
+      "Comments are disabled on this published view".to_string(),
+    ));
+  }
+  if policy.is_banned {
+    return Err(AppError::UserUnAuthorized(
+      "The user is banned from commenting on this workspace".to_string(),
+    ));
+  }
+  Ok(())
+}
+
+/// Hides the comments waiting for approval, unless the user is a moderator or their author.
+pub async fn filter_pending_comments(
+  pg_pool: &PgPool,
+  view_id: &Uuid,
+  user_uuid: Option<Uuid>,
+  comments: Vec<GlobalComment>,
+) -> Result<Vec<GlobalComment>, AppError> {
+  let pending_comment_ids = select_pending_comment_ids(pg_pool, view_id).await?;
+  if pending_comment_ids.is_empty() {
+    return Ok(comments);
+  }
+  let user_uuid = user_uuid.unwrap_or(Uuid::nil());
+  let is_moderator = !user_uuid.is_nil()
+    && select_published_view_comment_policy(pg_pool, view_id, &user_uuid)
+      .await?
+      .is_moderator;
+  let comments = comments
+    .into_iter()
+    .filter_map(|mut comment| {
+      if !pending_comment_ids.contains(&comment.comment_id) {
+        return Some(comment);
+      }
+      let is_author = comment
+        .user
+        .as_ref()
+        .map(|user| user.uuid == user_uuid)
+        .unwrap_or(false);
+      (is_moderator || is_author).then(|| {
+        comment.is_pending_approval = true;
+        comment
+      })
+    })
+    .collect();
+  Ok(comments)
+}
+
+async fn check_user_is_moderator(
+  pg_pool: &PgPool,
+  view_id: &Uuid,
+  user_uuid: &Uuid,
+) -> Result<(), AppError> {
+  let policy = select_published_view_comment_policy(pg_pool, view_id, user_uuid).await?;
+  if !policy.is_moderator {
+    return Err(AppError::UserUnAuthorized(
+      "Only the publisher of the view and the owners of the workspace can moderate its comments"
+        .to_string(),
+    ));
+  }
+  Ok(())
+}
+
+pub async fn get_published_view_comment_settings(
+  pg_pool: &PgPool,
+  view_id: &Uuid,
+) -> Result<PublishedViewCommentSettings, AppError> {
+  let policy = select_published_view_comment_policy(pg_pool, view_id, &Uuid::nil()).await?;
+  Ok(PublishedViewCommentSettings {
+    comments_enabled: policy.comments_enabled,
+    require_approval: policy.require_approval,
+  })
+}
+
+pub async fn update_published_view_comment_settings(
+  pg_pool: &PgPool,
+  view_id: &Uuid,
+  user_uuid: &Uuid,
+  settings: &PublishedViewCommentSettings,
+) -> Result<(), AppError> {
+  check_user_is_moderator(pg_pool, view_id, user_uuid).await?;
+  upsert_published_view_comment_settings(
+    pg_pool,
+    view_id,
+    settings.comments_enabled,
+    settings.require_approval,
+  )
+  .await
+}
+
+pub async fn approve_comment_on_published_view(
+  pg_pool: &PgPool,
+  view_id: &Uuid,
+  comment_id: &Uuid,
+  user_uuid: &Uuid,
+) -> Result<(), AppError> {
+  check_user_is_moderator(pg_pool, view_id, user_uuid).await?;
+  if !update_comment_approval_status(pg_pool, view_id, comment_id).await? {
+    return Err(AppError::RecordNotFound(format!(
+      "comment {} not found on view {}",
+      comment_id, view_id
+    )));
+  }
+  Ok(())
+}
+
+pub async fn get_workspace_comment_moderation(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+) -> Result<CommentModeration, AppError> {
+  let moderation = select_workspace_comment_moderation(pg_pool, workspace_id)
+    .await?
+    .map(|row| CommentModeration {
+      blocked_keywords: row.blocked_keywords,
+      max_comments_per_hour: row.max_comments_per_hour,
+    })
+    .unwrap_or_default();
+  Ok(moderation)
+}
+
+pub async fn update_workspace_comment_moderation(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  moderation: CommentModeration,
+) -> Result<CommentModeration, AppError> {
+  if moderation
+    .max_comments_per_hour
+    .map(|max| max < 1)
+    .unwrap_or(false)
+  {
+    return Err(AppError::InvalidRequest(
+      "The maximum number of comments per hour must be positive".to_string(),
+    ));
+  }
+  let blocked_keywords = normalize_blocked_keywords(moderation.blocked_keywords)?;
+  upsert_workspace_comment_moderation(
+    pg_pool,
+    workspace_id,
+    &blocked_keywords,
+    moderation.max_comments_per_hour,
+  )
+  .await?;
+  Ok(CommentModeration {
+    blocked_keywords,
+    max_comments_per_hour: moderation.max_comments_per_hour,
+  })
+}
+
+/// Keywords are matched ignoring the case, they are stored lowercase.
+fn normalize_blocked_keywords(keywords: Vec<String>) -> Result<Vec<String>, AppError> {
+  if keywords.len() > MAX_BLOCKED_KEYWORDS {
+    return Err(AppError::InvalidRequest(format!(
+      "A workspace can block {} keywords at most",
+      MAX_BLOCKED_KEYWORDS
+    )));
+  }
+  let mut normalized: Vec<String> = Vec::with_capacity(keywords.len());
+  for keyword in keywords {
+    let keyword = keyword.trim().to_lowercase();
+    if keyword.is_empty() {
+      continue;
+    }
+    if keyword.chars().count() > MAX_BLOCKED_KEYWORD_LENGTH {
+      return Err(AppError::InvalidRequest(format!(
+        "A blocked keyword can't be longer than {} characters",
+        MAX_BLOCKED_KEYWORD_LENGTH
+      )));
+    }
+    if !normalized.contains(&keyword) {
+      normalized.push(keyword);
+    }
+  }
+  Ok(normalized)
+}
+
+pub async fn ban_commenter(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  banned_by: i64,
+  user_uuid: &Uuid,
+  params: &BanCommenterParams,
+) -> Result<(), AppError> {
+  let mut txn = pg_pool.begin().await?;
+  let banned = upsert_workspace_comment_ban(
+    txn.as_mut(),
+    workspace_id,
+    user_uuid,
+    params.reason.as_deref(),
+    banned_by,
+  )
+  .await?;
+  if !banned {
+    return Err(AppError::RecordNotFound(format!(
+      "user {} not found",
+      user_uuid
+    )));
+  }
+  if params.delete_comments {
+    update_user_comments_deletion_status(txn.as_mut(), workspace_id, user_uuid).await?;
+  }
+  txn.commit().await?;
+  Ok(())
+}
+
+pub async fn unban_commenter(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  user_uuid: &Uuid,
+) -> Result<(), AppError> {
+  if !delete_workspace_comment_ban(pg_pool, workspace_id, user_uuid).await? {
+    return Err(AppError::RecordNotFound(format!(
+      "user {} is not banned",
+      user_uuid
+    )));
+  }
+  Ok(())
+}
+
+pub async fn list_banned_commenters(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+) -> Result<Vec<BannedCommenter>, AppError> {
+  let bans = select_workspace_comment_bans(pg_pool, workspace_id)
+    .await?
+    .into_iter()
+    .map(|row| BannedCommenter {
+      user: AFWebUser {
+        uuid: row.uuid,
+        name: row.name,
+        avatar_url: row.avatar_url,
+      },
+      reason: row.reason,
+      banned_at: row.created_at,
+    })
+    .collect();
+  Ok(bans)
+}
@@ -0,0 +1,484 @@
+use std::collections::HashMap;
+
+use app_error::AppError;
+use appflowy_collaborate::collab::storage::CollabAccessControlStorage;
+use database::document_comment::{
+  insert_document_comment, insert_document_comment_thread, select_document_comment,
+  select_document_comment_thread, select_document_comment_thread_participant_uids,
+  select_document_comment_threads, select_document_comments, select_non_member_uids,
+  update_document_comment_content, update_document_comment_deletion_status,
+  update_document_comment_thread_resolution,
+};
+use database::pg_row::{AFDocumentCommentRow, AFDocumentCommentThreadRow};
+use database_entity::dto::{AFAccessLevel, AFWebUser};
+use shared_entity::dto::document_comment_dto::{
+  CreateDocumentCommentParams, CreateDocumentCommentThreadParams, DocumentComment,
+  DocumentCommentAnchor, DocumentCommentThread, QueryDocumentCommentThreads,
+  RepeatedDocumentCommentThread, UpdateDocumentCommentParams, UpdateDocumentCommentThreadParams,
+};
+use shared_entity::dto::notification_dto::NotificationKind;
+use shared_entity::dto::view_permission_dto::EffectiveViewPermission;
+use sqlx::PgPool;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::biz::notification::ops::{create_notifications, preview};
+
+use super::view_permission::get_effective_view_permission;
+
+const MAX_COMMENT_LENGTH: usize = 10_000;
+const MAX_QUOTED_TEXT_LENGTH: usize = 1_000;
+const MAX_MENTIONS: usize = 50;
+
+/// Returns the access level of the member on the page. All the members of the workspace can read
+/// the comments, commenting requires the [AFAccessLevel::ReadAndComment] access level.
+async fn get_comment_permission(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  comment: bool,
+) -> Result<EffectiveViewPermission, AppError> {
+  let permission =
+    get_effective_view_permission(pg_pool, collab_storage, uid, workspace_id, view_id, None)
+      .await?;
+  if comment && !permission.access_level.can_comment() {
+    return Err(AppError::NotEnoughPermissions {
+      user: uid.to_string(),
+      workspace_id: workspace_id.to_string(),
+    });
+  }
+  Ok(permission)
+}
+
+fn validate_content(content: &str) -> Result<(), AppError> {
+  if content.trim().is_empty() {
+    return Err(AppError::InvalidRequest(
+      "The comment can't be empty".to_string(),
+    ));
+  }
+  if content.chars().count() > MAX_COMMENT_LENGTH {
+    return Err(AppError::InvalidRequest(format!(
+      "The comment can't be longer than {} characters",
+      MAX_COMMENT_LENGTH
+    )));
+  }
+  Ok(())
+}
+
+fn validate_anchor(anchor: &DocumentCommentAnchor) -> Result<(), AppError> {
+  if anchor.block_id.is_empty() {
+    return Err(AppError::InvalidRequest(
+      "The comment thread must be anchored to a block".to_string(),
+    ));
+  }
+  match (anchor.range_start, anchor.range_end) {
+    (None, None) => {},
+    (Some(start), Some(end)) if 0 <= start && start < end => {},
+    _ => {
+      return Err(AppError::InvalidRequest(
+        "The range of the comment thread must have a start before its end".to_string(),
+      ))
+    },
+  }
+  if let Some(quoted_text) = &anchor.quoted_text {
+    if quoted_text.chars().count() > MAX_QUOTED_TEXT_LENGTH {
+      return Err(AppError::InvalidRequest(format!(
+        "The quoted text can't be longer than {} characters",
+        MAX_QUOTED_TEXT_LENGTH
+      )));
+    }
+  }
+  Ok(())
+}
+
+/// Only the members of the workspace can be mentioned. Returns the mentions without duplicates.
+async fn validate_mentions(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  mentions: Vec<i64>,
+) -> Result<Vec<i64>, AppError> {
+  let mut unique_mentions = Vec::with_capacity(mentions.len());
+  for uid in mentions {
+    if !unique_mentions.contains(&uid) {
+      unique_mentions.push(uid);
+    }
+  }
+  if unique_mentions.len() > MAX_MENTIONS {
+    return Err(AppError::InvalidRequest(format!(
+      "A comment can mention {} members at most",
+      MAX_MENTIONS
+    )));
+  }
+  if unique_mentions.is_empty() {
+    return Ok(unique_mentions);
+  }
+  let non_member_uids = select_non_member_uids(pg_pool, workspace_id, &unique_mentions).await?;
+  if !non_member_uids.is_empty() {
+    return Err(AppError::InvalidRequest(format!(
+      "Only the members of the workspace can be mentioned, {:?} are not",
+      non_member_uids
+    )));
+  }
+  Ok(unique_mentions)
+}
+
+/// Returns the thread if it belongs to the page.
+async fn get_thread(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  thread_id: &Uuid,
+) -> Result<AFDocumentCommentThreadRow, AppError> {
+  select_document_comment_thread(pg_pool, thread_id)
+    .await?
+    .filter(|thread| thread.workspace_id == *workspace_id && thread.view_id == *view_id)
+    .ok_or_else(|| {
+      AppError::RecordNotFound(format!(
+        "comment thread {} not found on view {}",
+        thread_id, view_id
+      ))
+    })
+}
+
+/// Returns the comment if it belongs to the thread.
+async fn get_comment(
+  pg_pool: &PgPool,
+  thread_id: &Uuid,
+  comment_id: &Uuid,
+) -> Result<AFDocumentCommentRow, AppError> {
+  select_document_comment(pg_pool, comment_id)
+    .await?
+    .filter(|comment| comment.thread_id == *thread_id)
+    .ok_or_else(|| {
+      AppError::RecordNotFound(format!(
+        "comment {} not found in thread {}",
+        comment_id, thread_id
+      ))
+    })
+}
+
+fn to_document_comment(row: AFDocumentCommentRow) -> DocumentComment {
+  let author = match (row.author_uuid, row.author_name) {
+    (Some(uuid), Some(name)) => Some(AFWebUser {
+      uuid,
+      name,
+      avatar_url: row.author_avatar_url,
+    }),
+    _ => None,
+  };
+  DocumentComment {
+    comment_id: row.comment_id,
+    thread_id: row.thread_id,
+    author,
+    author_uid: row.created_by,
+    content: row.content,
+    mentions: row.mentions,
+    created_at: row.created_at,
+    updated_at: row.updated_at,
+    is_deleted: row.is_deleted,
+  }
+}
+
+fn to_document_comment_thread(
+  row: AFDocumentCommentThreadRow,
+  comments: Vec<DocumentComment>,
+) -> DocumentCommentThread {
+  DocumentCommentThread {
+    thread_id: row.thread_id,
+    view_id: row.view_id,
+    anchor: DocumentCommentAnchor {
+      block_id: row.block_id,
+      range_start: row.range_start,
+      range_end: row.range_end,
+      quoted_text: row.quoted_text,
+    },
+    created_by: row.created_by,
+    created_at: row.created_at,
+    resolved_by: row.resolved_by,
+    resolved_at: row.resolved_at,
+    comments,
+  }
+}
+
+async fn load_threads(
+  pg_pool: &PgPool,
+  threads: Vec<AFDocumentCommentThreadRow>,
+) -> Result<Vec<DocumentCommentThread>, AppError> {
+  let thread_ids: Vec<Uuid> = threads.iter().map(|thread| thread.thread_id).collect();
+  let mut comments_by_thread: HashMap<Uuid, Vec<DocumentComment>> = HashMap::new();
+  for row in select_document_comments(pg_pool, &thread_ids).await? {
+    comments_by_thread
+      .entry(row.thread_id)
+      .or_default()
+      .push(to_document_comment(row));
+  }
+  Ok(
+    threads
+      .into_iter()
+      .map(|thread| {
+        let comments = comments_by_thread
+          .remove(&thread.thread_id)
+          .unwrap_or_default();
+        to_document_comment_thread(thread, comments)
+      })
+      .collect(),
+  )
+}
+
+/// Notifies the mentioned members with [NotificationKind::CommentMention], and the other
+/// `participants` with [NotificationKind::CommentReply].
+#[allow(clippy::too_many_arguments)]
+async fn notify_comment(
+  pg_pool: &PgPool,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  thread_id: &Uuid,
+  comment_id: &Uuid,
+  content: &str,
+  mentions: &[i64],
+  participants: &[i64],
+) {
+  let payload = serde_json::json!({
+    "thread_id": thread_id,
+    "comment_id": comment_id,
+    "preview": preview(content),
+  });
+  let object_id = comment_id.to_string();
+  create_notifications(
+    pg_pool,
+    mentions,
+    workspace_id,
+    NotificationKind::CommentMention,
+    Some(view_id),
+    Some(&object_id),
+    Some(uid),
+    payload.clone(),
+  )
+  .await;
+  let participants: Vec<i64> = participants
+    .iter()
+    .filter(|participant| !mentions.contains(participant))
+    .copied()
+    .collect();
+  create_notifications(
+    pg_pool,
+    &participants,
+    workspace_id,
+    NotificationKind::CommentReply,
+    Some(view_id),
+    Some(&object_id),
+    Some(uid),
+    payload,
+  )
+  .await;
+}
+
+pub async fn list_document_comment_threads(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  query: QueryDocumentCommentThreads,
+) -> Result<RepeatedDocumentCommentThread, AppError> {
+  get_comment_permission(pg_pool, collab_storage, uid, workspace_id, view_id, false).await?;
+  let threads =
+    select_document_comment_threads(pg_pool, workspace_id, view_id, query.include_resolved).await?;
+  Ok(RepeatedDocumentCommentThread {
+    threads: load_threads(pg_pool, threads).await?,
+  })
+}
+
+pub async fn create_document_comment_thread(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  params: CreateDocumentCommentThreadParams,
+) -> Result<DocumentCommentThread, AppError> {
+  validate_anchor(&params.anchor)?;
+  validate_content(&params.content)?;
+  get_comment_permission(pg_pool, collab_storage, uid, workspace_id, view_id, true).await?;
+  let mentions = validate_mentions(pg_pool, workspace_id, params.mentions).await?;
+
+  let mut txn = pg_pool.begin().await?;
+  let thread = insert_document_comment_thread(
+    &mut txn,
+    workspace_id,
+    view_id,
+    &params.anchor.block_id,
+    params.anchor.range_start,
+    params.anchor.range_end,
+    params.anchor.quoted_text.as_deref(),
+    uid,
+  )
+  .await?;
+  let comment_id =
+    insert_document_comment(&mut txn, &thread.thread_id, uid, &params.content, &mentions).await?;
+  txn.commit().await?;
+  notify_comment(
+    pg_pool,
+    uid,
+    workspace_id,
+    view_id,
+    &thread.thread_id,
+    &comment_id,
+    &params.content,
+    &mentions,
+    &[],
+  )
+  .await;
+
+  let mut threads = load_threads(pg_pool, vec![thread]).await?;
+  threads
+    .pop()
+    .ok_or_else(|| AppError::Internal(anyhow::anyhow!("the new comment thread is missing")))
+}
+
+/// Replies to the thread. Resolved threads can be replied to, they stay resolved.
+pub async fn create_document_comment(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  thread_id: &Uuid,
+  params: CreateDocumentCommentParams,
+) -> Result<DocumentComment, AppError> {
+  validate_content(&params.content)?;
+  get_comment_permission(pg_pool, collab_storage, uid, workspace_id, view_id, true).await?;
+  get_thread(pg_pool, workspace_id, view_id, thread_id).await?;
+  let mentions = validate_mentions(pg_pool, workspace_id, params.mentions).await?;
+
+  let mut txn = pg_pool.begin().await?;
+  let comment_id =
+    insert_document_comment(&mut txn, thread_id, uid, &params.content, &mentions).await?;
+  txn.commit().await?;
+  match select_document_comment_thread_participant_uids(pg_pool, thread_id).await {
+    Ok(participants) => {
+      notify_comment(
+        pg_pool,
+        uid,
+        workspace_id,
+        view_id,
+        thread_id,
+        &comment_id,
+        &params.content,
+        &mentions,
+        &participants,
+      )
+      .await
+    },
+    Err(err) => error!(
+      "Failed to get the participants of comment thread {}: {}",
+      thread_id, err
+    ),
+  }
+  Ok(to_document_comment(
+    get_comment(pg_pool, thread_id, &comment_id).await?,
+  ))
+}
+
+/// Resolves or reopens the thread.
+pub async fn update_document_comment_thread(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  thread_id: &Uuid,
+  params: UpdateDocumentCommentThreadParams,
+) -> Result<(), AppError> {
+  get_comment_permission(pg_pool, collab_storage, uid, workspace_id, view_id, true).await?;
+  get_thread(pg_pool, workspace_id, view_id, thread_id).await?;
+  update_document_comment_thread_resolution(pg_pool, thread_id, params.resolved.then_some(uid))
+    .await
+}
+
+/// Only the author of the comment can edit it.
+#[allow(clippy::too_many_arguments)]
+pub async fn update_document_comment(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  thread_id: &Uuid,
+  comment_id: &Uuid,
+  params: UpdateDocumentCommentParams,
+) -> Result<DocumentComment, AppError> {
+  validate_content(&params.content)?;
+  get_comment_permission(pg_pool, collab_storage, uid, workspace_id, view_id, true).await?;
+  get_thread(pg_pool, workspace_id, view_id, thread_id).await?;
+  let comment = get_comment(pg_pool, thread_id, comment_id).await?;
+  if comment.is_deleted {
+    return Err(AppError::RecordNotFound(format!(
+      "comment {} was deleted",
+      comment_id
+    )));
+  }
+  if comment.created_by != Some(uid) {
+    return Err(AppError::UserUnAuthorized(
+      "Only the author of the comment can edit it".to_string(),
+    ));
+  }
+  let mentions = validate_mentions(pg_pool, workspace_id, params.mentions).await?;
+
+  let mut txn = pg_pool.begin().await?;
+  update_document_comment_content(&mut txn, comment_id, &params.content, &mentions).await?;
+  txn.commit().await?;
+  // Only the members mentioned by the edit are notified
+  let new_mentions: Vec<i64> = mentions
+    .iter()
+    .filter(|mentioned| !comment.mentions.contains(mentioned))
+    .copied()
+    .collect();
+  notify_comment(
+    pg_pool,
+    uid,
+    workspace_id,
+    view_id,
+    thread_id,
+    comment_id,
+    &params.content,
+    &new_mentions,
+    &[],
+  )
+  .await;
+  Ok(to_document_comment(
+    get_comment(pg_pool, thread_id, comment_id).await?,
+  ))
+}
+
+/// The author of the comment and the members with full access to the page can delete it.
+pub async fn delete_document_comment(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  thread_id: &Uuid,
+  comment_id: &Uuid,
+) -> Result<(), AppError> {
+  let permission =
+    get_comment_permission(pg_pool, collab_storage, uid, workspace_id, view_id, false).await?;
+  get_thread(pg_pool, workspace_id, view_id, thread_id).await?;
+  let comment = get_comment(pg_pool, thread_id, comment_id).await?;
+  if comment.is_deleted {
+    return Ok(());
+  }
+  if comment.created_by != Some(uid) && permission.access_level != AFAccessLevel::FullAccess {
+    return Err(AppError::UserUnAuthorized(
+      "Only the author of the comment and the members with full access to the page can delete it"
+        .to_string(),
+    ));
+  }
+  let mut txn = pg_pool.begin().await?;
+  update_document_comment_deletion_status(&mut txn, comment_id).await?;
+  txn.commit().await?;
+  Ok(())
+}
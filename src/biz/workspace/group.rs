@@ -0,0 +1,226 @@
+use std::ops::DerefMut;
+use std::sync::Arc;
+
+use access_control::collab::CollabAccessControl;
+use access_control::workspace::WorkspaceAccessControl;
+use app_error::AppError;
+use appflowy_collaborate::collab::storage::CollabAccessControlStorage;
+use database::group::{
+  delete_workspace_group, delete_workspace_group_members, insert_workspace_group,
+  insert_workspace_group_members, select_workspace_group, select_workspace_group_members,
+  select_workspace_groups, update_workspace_group_name,
+};
+use database::role::select_workspace_member_role_info;
+use database::user::select_uid_from_email;
+use shared_entity::dto::group_dto::{
+  CreateWorkspaceGroupParams, UpdateWorkspaceGroupParams, WorkspaceGroup, WorkspaceGroupMember,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::view_permission::refresh_workspace_view_permissions;
+
+/// Returns the group, or an error if the workspace has no such group.
+pub async fn get_workspace_group(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+) -> Result<WorkspaceGroup, AppError> {
+  select_workspace_group(pg_pool, workspace_id, group_id)
+    .await?
+    .ok_or_else(|| AppError::RecordNotFound(format!("Group {} not found", group_id)))
+}
+
+pub async fn list_workspace_groups(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+) -> Result<Vec<WorkspaceGroup>, AppError> {
+  select_workspace_groups(pg_pool, workspace_id).await
+}
+
+pub async fn create_workspace_group(
+  pg_pool: &PgPool,
+  uid: i64,
+  workspace_id: &Uuid,
+  params: CreateWorkspaceGroupParams,
+) -> Result<WorkspaceGroup, AppError> {
+  let name = params.name.trim();
+  insert_workspace_group(pg_pool, workspace_id, name, uid)
+    .await?
+    .ok_or_else(|| AppError::RecordAlreadyExists(format!("The group {} already exists", name)))
+}
+
+pub async fn rename_workspace_group(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+  params: UpdateWorkspaceGroupParams,
+) -> Result<WorkspaceGroup, AppError> {
+  update_workspace_group_name(pg_pool, workspace_id, group_id, params.name.trim())
+    .await?
+    .ok_or_else(|| AppError::RecordNotFound(format!("Group {} not found", group_id)))
+}
+
+/// The members of the group lose the access levels that were granted to the group.
+pub async fn remove_workspace_group(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  uid: i64,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+) -> Result<(), AppError> {
+  if !delete_workspace_group(pg_pool, workspace_id, group_id).await? {
+    return Err(AppError::RecordNotFound(format!(
+      "Group {} not found",
+      group_id
+    )));
+  }
+  workspace_access_control.remove_group(group_id).await?;
+  refresh_workspace_view_permissions(
+    pg_pool,
+    collab_storage,
+    collab_access_control,
+    uid,
+    workspace_id,
+  )
+  .await
+}
+
+pub async fn list_workspace_group_members(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+) -> Result<Vec<WorkspaceGroupMember>, AppError> {
+  get_workspace_group(pg_pool, workspace_id, group_id).await?;
+  select_workspace_group_members(pg_pool, group_id).await
+}
+
+async fn select_member_uids(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  emails: &[String],
+) -> Result<Vec<i64>, AppError> {
+  let mut uids = Vec::with_capacity(emails.len());
+  for email in emails {
+    let member_uid = select_uid_from_email(pg_pool, email).await?;
+    if select_workspace_member_role_info(pg_pool, workspace_id, member_uid)
+      .await?
+      .is_none()
+    {
+      return Err(AppError::InvalidRequest(format!(
+        "{} is not a member of the workspace",
+        email
+      )));
+    }
+    uids.push(member_uid);
+  }
+  Ok(uids)
+}
+
+/// The users get the access levels granted to the group. They must be members of the workspace.
+#[allow(clippy::too_many_arguments)]
+pub async fn add_workspace_group_members(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  uid: i64,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+  emails: &[String],
+) -> Result<(), AppError> {
+  get_workspace_group(pg_pool, workspace_id, group_id).await?;
+  let member_uids = select_member_uids(pg_pool, workspace_id, emails).await?;
+  insert_group_member_uids(pg_pool, &workspace_access_control, group_id, &member_uids).await?;
+  refresh_workspace_view_permissions(
+    pg_pool,
+    collab_storage,
+    collab_access_control,
+    uid,
+    workspace_id,
+  )
+  .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn remove_workspace_group_members(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  uid: i64,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+  emails: &[String],
+) -> Result<(), AppError> {
+  get_workspace_group(pg_pool, workspace_id, group_id).await?;
+  let mut member_uids = Vec::with_capacity(emails.len());
+  for email in emails {
+    member_uids.push(select_uid_from_email(pg_pool, email).await?);
+  }
+  delete_group_member_uids(pg_pool, &workspace_access_control, group_id, &member_uids).await?;
+  refresh_workspace_view_permissions(
+    pg_pool,
+    collab_storage,
+    collab_access_control,
+    uid,
+    workspace_id,
+  )
+  .await
+}
+
+/// Adds the users to the group, which must be members of the workspace. The caller refreshes the
+/// access levels of the spaces and pages.
+pub async fn insert_group_member_uids(
+  pg_pool: &PgPool,
+  workspace_access_control: &Arc<dyn WorkspaceAccessControl>,
+  group_id: &Uuid,
+  member_uids: &[i64],
+) -> Result<(), AppError> {
+  insert_workspace_group_members(pg_pool, group_id, member_uids).await?;
+  for member_uid in member_uids {
+    workspace_access_control
+      .insert_group_member(member_uid, group_id)
+      .await?;
+  }
+  Ok(())
+}
+
+/// Removes the users from the group. The caller refreshes the access levels of the spaces and
+/// pages.
+pub async fn delete_group_member_uids(
+  pg_pool: &PgPool,
+  workspace_access_control: &Arc<dyn WorkspaceAccessControl>,
+  group_id: &Uuid,
+  member_uids: &[i64],
+) -> Result<(), AppError> {
+  let removed_uids = delete_workspace_group_members(pg_pool, group_id, member_uids).await?;
+  for removed_uid in &removed_uids {
+    workspace_access_control
+      .remove_group_member(removed_uid, group_id)
+      .await?;
+  }
+  Ok(())
+}
+
+/// Adds the requester of an approved access request to the group, within the transaction that
+/// makes it a member of the workspace.
+pub async fn add_approved_requester_to_group(
+  txn: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+  workspace_id: &Uuid,
+  group_id: &Uuid,
+  uid: i64,
+) -> Result<(), AppError> {
+  if select_workspace_group(txn.deref_mut(), workspace_id, group_id)
+    .await?
+    .is_none()
+  {
+    return Err(AppError::RecordNotFound(format!(
+      "Group {} not found",
+      group_id
+    )));
+  }
+  insert_workspace_group_members(txn.deref_mut(), group_id, &[uid]).await
+}
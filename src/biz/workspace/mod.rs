@@ -1,4 +1,16 @@
+pub mod activity;
+pub mod api_token;
+pub mod comment_moderation;
+pub mod document_comment;
+pub mod group;
 pub mod ops;
 pub mod page_view;
+pub mod password_attempt;
 pub mod publish;
+pub mod publish_access;
+pub mod publish_analytics;
 pub mod publish_dup;
+pub mod publish_html;
+pub mod role;
+pub mod share_link;
+pub mod view_permission;
@@ -20,10 +20,14 @@ use access_control::workspace::WorkspaceAccessControl;
 use app_error::AppError;
 use appflowy_collaborate::collab::storage::CollabAccessControlStorage;
 use database::collab::{upsert_collab_member_with_txn, CollabStorage};
+use database::comment_moderation::insert_pending_comment_to_published_view;
 use database::file::s3_client_impl::S3BucketStorage;
+use database::group::delete_workspace_group_memberships_of_user;
 use database::pg_row::AFWorkspaceMemberRow;
+use database::role::update_workspace_member_custom_role;
+use database::view_permission::delete_view_permissions_of_user;
 
-use database::user::select_uid_from_email;
+use database::user::{select_optional_uid_from_email, select_uid_from_email};
 use database::workspace::*;
 use database_entity::dto::{
   AFAccessLevel, AFRole, AFWorkspace, AFWorkspaceInvitation, AFWorkspaceInvitationStatus,
@@ -31,16 +35,21 @@ use database_entity::dto::{
 };
 use gotrue::params::{GenerateLinkParams, GenerateLinkType};
 
+use shared_entity::dto::notification_dto::NotificationKind;
 use shared_entity::dto::workspace_dto::{
   CreateWorkspaceMember, WorkspaceMemberChangeset, WorkspaceMemberInvitation,
 };
 use shared_entity::response::AppResponseError;
 use workspace_template::document::getting_started::GettingStartedTemplate;
 
+use crate::biz::notification::ops::create_notifications;
 use crate::biz::user::user_init::{
   create_user_awareness, create_workspace_collab, create_workspace_database_collab,
   initialize_workspace_for_user,
 };
+use crate::biz::workspace::comment_moderation::{
+  check_user_can_comment, check_user_can_react, filter_pending_comments,
+};
 use crate::mailer::{AFCloudMailer, WorkspaceInviteMailerParam};
 use crate::state::{GoTrueAdmin, RedisConnectionManager};
 
@@ -181,23 +190,39 @@ pub async fn get_comments_on_published_view(
     &page_owner_uuid,
   )
   .await?;
+  let comments =
+    filter_pending_comments(pg_pool, view_id, optional_user_uuid.as_uuid(), comments).await?;
   Ok(comments)
 }
 
+/// Returns whether the comment waits for the approval of a moderator.
 pub async fn create_comment_on_published_view(
   pg_pool: &PgPool,
   view_id: &Uuid,
   reply_comment_id: &Option<Uuid>,
   content: &str,
   user_uuid: &Uuid,
-) -> Result<(), AppError> {
+) -> Result<bool, AppError> {
   if content.len() > MAX_COMMENT_LENGTH {
     return Err(AppError::StringLengthLimitReached(
       "comment content exceed limit".to_string(),
     ));
   }
-  insert_comment_to_published_view(pg_pool, view_id, user_uuid, content, reply_comment_id).await?;
-  Ok(())
+  let require_approval = check_user_can_comment(pg_pool, view_id, user_uuid, content).await?;
+  if require_approval {
+    insert_pending_comment_to_published_view(
+      pg_pool,
+      view_id,
+      user_uuid,
+      content,
+      reply_comment_id,
+    )
+    .await?;
+  } else {
+    insert_comment_to_published_view(pg_pool, view_id, user_uuid, content, reply_comment_id)
+      .await?;
+  }
+  Ok(require_approval)
 }
 
 pub async fn remove_comment_on_published_view(
@@ -236,6 +261,7 @@ pub async fn create_reaction_on_comment(
   reaction_type: &str,
   user_uuid: &Uuid,
 ) -> Result<(), AppError> {
+  check_user_can_react(pg_pool, view_id, user_uuid).await?;
   insert_reaction_on_comment(pg_pool, comment_id, view_id, user_uuid, reaction_type).await?;
   Ok(())
 }
@@ -318,13 +344,14 @@ pub async fn open_workspace(
   Ok(workspace)
 }
 
+/// Returns the workspace the user joined.
 pub async fn accept_workspace_invite(
   pg_pool: &PgPool,
   workspace_access_control: Arc<dyn WorkspaceAccessControl>,
   user_uid: i64,
   user_uuid: &Uuid,
   invite_id: &Uuid,
-) -> Result<(), AppError> {
+) -> Result<Uuid, AppError> {
   let mut txn = pg_pool.begin().await?;
   let inv = get_invitation_by_id(&mut txn, invite_id).await?;
   if let Some(invitee_uid) = inv.invitee_uid {
@@ -343,7 +370,7 @@ pub async fn accept_workspace_invite(
     .insert_role(&invited_uid, &inv.workspace_id, inv.role)
     .await?;
   txn.commit().await?;
-  Ok(())
+  Ok(inv.workspace_id)
 }
 
 #[instrument(level = "debug", skip_all, err)]
@@ -365,6 +392,7 @@ pub async fn invite_workspace_members(
   let admin_token = gotrue_admin.token().await?;
 
   let inviter_name = database::user::select_name_from_uuid(pg_pool, inviter).await?;
+  let inviter_uid = database::user::select_uid_from_uuid(pg_pool, inviter).await?;
   let workspace_name =
     database::workspace::select_workspace_name_from_workspace_id(pg_pool, workspace_id)
       .await?
@@ -392,6 +420,7 @@ pub async fn invite_workspace_members(
     }
   }
 
+  let mut new_invitations = vec![];
   for invitation in invitations {
     let inviter_name = inviter_name.clone();
     let workspace_name = workspace_name.clone();
@@ -417,6 +446,7 @@ pub async fn invite_workspace_members(
           invitation.role,
         )
         .await?;
+        new_invitations.push((invite_id, invitation.email.clone()));
         invite_id
       },
       Some(invite_id) => {
@@ -479,6 +509,30 @@ pub async fn invite_workspace_members(
     .commit()
     .await
     .context("Commit transaction to invite workspace members")?;
+
+  // The invitees that already have an account also find the invitation in their notifications
+  for (invite_id, email) in new_invitations {
+    match select_optional_uid_from_email(pg_pool, &email).await {
+      Ok(Some(invitee_uid)) => {
+        create_notifications(
+          pg_pool,
+          &[invitee_uid],
+          workspace_id,
+          NotificationKind::WorkspaceInvitation,
+          None,
+          Some(&invite_id.to_string()),
+          Some(inviter_uid),
+          json!({
+            "workspace_name": workspace_name,
+            "inviter_name": inviter_name,
+          }),
+        )
+        .await
+      },
+      Ok(None) => {},
+      Err(err) => tracing::error!("Failed to get the user invited with {}: {}", email, err),
+    }
+  }
   Ok(())
 }
 
@@ -547,9 +601,16 @@ pub async fn leave_workspace(
   workspace_id: &Uuid,
   user_uuid: &Uuid,
   workspace_access_control: Arc<dyn WorkspaceAccessControl>,
-) -> Result<(), AppResponseError> {
+) -> Result<String, AppResponseError> {
   let email = database::user::select_email_from_user_uuid(pg_pool, user_uuid).await?;
-  remove_workspace_members(pg_pool, workspace_id, &[email], workspace_access_control).await
+  remove_workspace_members(
+    pg_pool,
+    workspace_id,
+    std::slice::from_ref(&email),
+    workspace_access_control,
+  )
+  .await?;
+  Ok(email)
 }
 
 pub async fn remove_workspace_members(
@@ -572,6 +633,14 @@ pub async fn remove_workspace_members(
       workspace_access_control
         .remove_user_from_workspace(&uid, workspace_id)
         .await?;
+      delete_view_permissions_of_user(&mut txn, workspace_id, uid).await?;
+      for group_id in
+        delete_workspace_group_memberships_of_user(&mut txn, workspace_id, uid).await?
+      {
+        workspace_access_control
+          .remove_group_member(&uid, &group_id)
+          .await?;
+      }
     }
   }
 
@@ -606,6 +675,8 @@ pub async fn update_workspace_member(
 ) -> Result<(), AppError> {
   if let Some(role) = &changeset.role {
     upsert_workspace_member(pg_pool, workspace_id, &changeset.email, role.clone()).await?;
+    // The custom role was built on the previous role.
+    update_workspace_member_custom_role(pg_pool, workspace_id, *uid, None, None).await?;
     workspace_access_control
       .insert_role(uid, workspace_id, role.clone())
       .await?;
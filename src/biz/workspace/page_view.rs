@@ -1,6 +1,7 @@
 use anyhow::anyhow;
 use app_error::AppError;
 use appflowy_collaborate::collab::storage::CollabAccessControlStorage;
+use appflowy_collaborate::collab::workspace_activity::{FolderSnapshot, WorkspaceActivityRecorder};
 use chrono::DateTime;
 use collab::core::collab::Collab;
 use collab_database::database::{
@@ -68,6 +69,7 @@ struct FolderUpdate {
 pub async fn update_space(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
   view_id: &str,
@@ -79,6 +81,7 @@ pub async fn update_space(
   let collab_origin = GetCollabOrigin::User { uid };
   let mut folder =
     get_latest_collab_folder(collab_storage, collab_origin, &workspace_id.to_string()).await?;
+  let before = FolderSnapshot::from_folder(&workspace_id.to_string(), &folder);
   let folder_update = update_space_properties(
     view_id,
     &mut folder,
@@ -94,6 +97,9 @@ pub async fn update_space(
     workspace_id,
     folder_update,
     collab_storage,
+    activity_recorder,
+    before,
+    &folder,
     &mut transaction,
   )
   .await?;
@@ -105,6 +111,7 @@ pub async fn update_space(
 pub async fn create_space(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
   space_permission: &SpacePermission,
@@ -117,6 +124,7 @@ pub async fn create_space(
   let collab_origin = GetCollabOrigin::User { uid };
   let mut folder =
     get_latest_collab_folder(collab_storage, collab_origin, &workspace_id.to_string()).await?;
+  let before = FolderSnapshot::from_folder(&workspace_id.to_string(), &folder);
   let folder_update = add_new_space_to_folder(
     uid,
     &workspace_id.to_string(),
@@ -144,6 +152,9 @@ pub async fn create_space(
     workspace_id,
     folder_update,
     collab_storage,
+    activity_recorder,
+    before,
+    &folder,
     &mut transaction,
   )
   .await?;
@@ -154,6 +165,7 @@ pub async fn create_space(
 pub async fn create_page(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
   parent_view_id: &str,
@@ -165,6 +177,7 @@ pub async fn create_page(
       create_document_page(
         pg_pool,
         collab_storage,
+        activity_recorder,
         uid,
         workspace_id,
         parent_view_id,
@@ -176,6 +189,7 @@ pub async fn create_page(
       create_grid_page(
         pg_pool,
         collab_storage,
+        activity_recorder,
         uid,
         workspace_id,
         parent_view_id,
@@ -187,6 +201,7 @@ pub async fn create_page(
       create_calendar_page(
         pg_pool,
         collab_storage,
+        activity_recorder,
         uid,
         workspace_id,
         parent_view_id,
@@ -198,6 +213,7 @@ pub async fn create_page(
       create_board_page(
         pg_pool,
         collab_storage,
+        activity_recorder,
         uid,
         workspace_id,
         parent_view_id,
@@ -669,11 +685,17 @@ async fn insert_and_broadcast_workspace_database_update(
   Ok(())
 }
 
+/// Saves and broadcasts the updated folder, and records the workspace activities derived from the
+/// changes of its pages.
+#[allow(clippy::too_many_arguments)]
 async fn insert_and_broadcast_workspace_folder_update(
   uid: i64,
   workspace_id: Uuid,
   folder_update: FolderUpdate,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
+  before: FolderSnapshot,
+  folder: &Folder,
   transaction: &mut Transaction<'_, sqlx::Postgres>,
 ) -> Result<(), AppError> {
   let params = CollabParams {
@@ -698,12 +720,15 @@ async fn insert_and_broadcast_workspace_folder_update(
     folder_update.encoded_updates.clone(),
   )
   .await?;
+  let after = FolderSnapshot::from_folder(&workspace_id.to_string(), folder);
+  activity_recorder.queue_folder_updated(&workspace_id, before, after, uid);
   Ok(())
 }
 
 async fn create_document_page(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
   parent_view_id: &str,
@@ -714,6 +739,7 @@ async fn create_document_page(
   let collab_origin = GetCollabOrigin::User { uid };
   let mut folder =
     get_latest_collab_folder(collab_storage, collab_origin, &workspace_id.to_string()).await?;
+  let before = FolderSnapshot::from_folder(&workspace_id.to_string(), &folder);
   let folder_update = add_new_view_to_folder(
     uid,
     parent_view_id,
@@ -739,6 +765,9 @@ async fn create_document_page(
     workspace_id,
     folder_update,
     collab_storage,
+    activity_recorder,
+    before,
+    &folder,
     &mut transaction,
   )
   .await?;
@@ -749,6 +778,7 @@ async fn create_document_page(
 async fn create_grid_page(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
   parent_view_id: &str,
@@ -761,6 +791,7 @@ async fn create_grid_page(
   create_database_page(
     pg_pool,
     collab_storage,
+    activity_recorder,
     uid,
     workspace_id,
     parent_view_id,
@@ -775,6 +806,7 @@ async fn create_grid_page(
 async fn create_board_page(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
   parent_view_id: &str,
@@ -788,6 +820,7 @@ async fn create_board_page(
   create_database_page(
     pg_pool,
     collab_storage,
+    activity_recorder,
     uid,
     workspace_id,
     parent_view_id,
@@ -802,6 +835,7 @@ async fn create_board_page(
 async fn create_calendar_page(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
   parent_view_id: &str,
@@ -815,6 +849,7 @@ async fn create_calendar_page(
   create_database_page(
     pg_pool,
     collab_storage,
+    activity_recorder,
     uid,
     workspace_id,
     parent_view_id,
@@ -830,6 +865,7 @@ async fn create_calendar_page(
 async fn create_database_page(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
   parent_view_id: &str,
@@ -845,6 +881,7 @@ async fn create_database_page(
     &workspace_id.to_string(),
   )
   .await?;
+  let before = FolderSnapshot::from_folder(&workspace_id.to_string(), &folder);
   let folder_update =
     add_new_view_to_folder(uid, parent_view_id, view_id, &mut folder, name, view_layout).await?;
   let (workspace_database_id, mut workspace_database) =
@@ -892,6 +929,9 @@ async fn create_database_page(
     workspace_id,
     folder_update,
     collab_storage,
+    activity_recorder,
+    before,
+    &folder,
     &mut transaction,
   )
   .await?;
@@ -913,6 +953,7 @@ async fn create_database_page(
 pub async fn move_page_to_trash(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
   view_id: &str,
@@ -920,6 +961,7 @@ pub async fn move_page_to_trash(
   let collab_origin = GetCollabOrigin::User { uid };
   let mut folder =
     get_latest_collab_folder(collab_storage, collab_origin, &workspace_id.to_string()).await?;
+  let before = FolderSnapshot::from_folder(&workspace_id.to_string(), &folder);
   let trash_info = folder.get_my_trash_info();
   if trash_info.into_iter().any(|info| info.id == view_id) {
     return Ok(());
@@ -931,6 +973,9 @@ pub async fn move_page_to_trash(
     workspace_id,
     folder_update,
     collab_storage,
+    activity_recorder,
+    before,
+    &folder,
     &mut transaction,
   )
   .await?;
@@ -941,6 +986,7 @@ pub async fn move_page_to_trash(
 pub async fn restore_page_from_trash(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
   view_id: &str,
@@ -948,6 +994,7 @@ pub async fn restore_page_from_trash(
   let collab_origin = GetCollabOrigin::User { uid };
   let mut folder =
     get_latest_collab_folder(collab_storage, collab_origin, &workspace_id.to_string()).await?;
+  let before = FolderSnapshot::from_folder(&workspace_id.to_string(), &folder);
   let folder_update = move_view_out_from_trash(view_id, &mut folder).await?;
   let mut transaction = pg_pool.begin().await?;
   insert_and_broadcast_workspace_folder_update(
@@ -955,6 +1002,9 @@ pub async fn restore_page_from_trash(
     workspace_id,
     folder_update,
     collab_storage,
+    activity_recorder,
+    before,
+    &folder,
     &mut transaction,
   )
   .await?;
@@ -965,12 +1015,14 @@ pub async fn restore_page_from_trash(
 pub async fn restore_all_pages_from_trash(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
 ) -> Result<(), AppError> {
   let collab_origin = GetCollabOrigin::User { uid };
   let mut folder =
     get_latest_collab_folder(collab_storage, collab_origin, &workspace_id.to_string()).await?;
+  let before = FolderSnapshot::from_folder(&workspace_id.to_string(), &folder);
   let folder_update = move_all_views_out_from_trash(&mut folder).await?;
   let mut transaction = pg_pool.begin().await?;
   insert_and_broadcast_workspace_folder_update(
@@ -978,6 +1030,9 @@ pub async fn restore_all_pages_from_trash(
     workspace_id,
     folder_update,
     collab_storage,
+    activity_recorder,
+    before,
+    &folder,
     &mut transaction,
   )
   .await?;
@@ -985,20 +1040,27 @@ pub async fn restore_all_pages_from_trash(
   Ok(())
 }
 
+/// Returns true if the name of the page changed.
 #[allow(clippy::too_many_arguments)]
 pub async fn update_page(
   pg_pool: &PgPool,
   collab_storage: &CollabAccessControlStorage,
+  activity_recorder: &WorkspaceActivityRecorder,
   uid: i64,
   workspace_id: Uuid,
   view_id: &str,
   name: &str,
   icon: Option<&ViewIcon>,
   extra: Option<impl AsRef<str>>,
-) -> Result<(), AppError> {
+) -> Result<bool, AppError> {
   let collab_origin = GetCollabOrigin::User { uid };
   let mut folder =
     get_latest_collab_folder(collab_storage, collab_origin, &workspace_id.to_string()).await?;
+  let before = FolderSnapshot::from_folder(&workspace_id.to_string(), &folder);
+  let is_renamed = folder
+    .get_view(view_id)
+    .map(|view| view.name != name)
+    .unwrap_or(false);
   let folder_update = update_view_properties(view_id, &mut folder, name, icon, extra).await?;
   let mut transaction = pg_pool.begin().await?;
   insert_and_broadcast_workspace_folder_update(
@@ -1006,12 +1068,15 @@ pub async fn update_page(
     workspace_id,
     folder_update,
     collab_storage,
+    activity_recorder,
+    before,
+    &folder,
     &mut transaction,
   )
   .await?;
   transaction.commit().await?;
 
-  Ok(())
+  Ok(is_renamed)
 }
 
 pub async fn get_page_view_collab(
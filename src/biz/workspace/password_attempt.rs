@@ -0,0 +1,73 @@
+use anyhow::anyhow;
+use app_error::AppError;
+use redis::AsyncCommands;
+
+use crate::state::RedisConnectionManager;
+
+/// Failed guesses allowed on a resource, e.g. a share link, before it stops accepting passwords.
+const MAX_FAILED_ATTEMPTS_PER_RESOURCE: u64 = 100;
+/// Failed guesses allowed from an address, whatever the resource.
+const MAX_FAILED_ATTEMPTS_PER_CLIENT: u64 = 10;
+/// The failed guesses are forgotten once no guess failed for this long.
+const FAILED_ATTEMPTS_WINDOW_SECS: i64 = 15 * 60;
+
+fn attempt_keys(resource: &str, client_ip: Option<&str>) -> Vec<(String, u64)> {
+  let mut keys = vec![(
+    format!("password_attempt:resource:{}", resource),
+    MAX_FAILED_ATTEMPTS_PER_RESOURCE,
+  )];
+  if let Some(client_ip) = client_ip {
+    keys.push((
+      format!("password_attempt:client:{}", client_ip),
+      MAX_FAILED_ATTEMPTS_PER_CLIENT,
+    ));
+  }
+  keys
+}
+
+/// Counts a password guess on the resource before the password is checked, so the concurrent
+/// guesses are counted too. Returns an error if the resource or the client made too many failed
+/// guesses recently: checking a password costs a hash on the blocking pool.
+///
+/// The guess has to be forgotten with [forget_password_attempt] if the password is right.
+pub async fn record_password_attempt(
+  redis_client: &RedisConnectionManager,
+  resource: &str,
+  client_ip: Option<&str>,
+) -> Result<(), AppError> {
+  let mut conn = redis_client.clone();
+  let mut exceeded = false;
+  for (key, max_attempts) in attempt_keys(resource, client_ip) {
+    let (attempts,): (u64,) = redis::pipe()
+      .atomic()
+      .incr(&key, 1)
+      .expire(&key, FAILED_ATTEMPTS_WINDOW_SECS)
+      .ignore()
+      .query_async(&mut conn)
+      .await
+      .map_err(|err| AppError::Internal(anyhow!("Failed to count password attempt: {}", err)))?;
+    exceeded |= attempts > max_attempts;
+  }
+  if exceeded {
+    return Err(AppError::TooManyRequests(
+      "Too many wrong passwords, try again later".to_string(),
+    ));
+  }
+  Ok(())
+}
+
+/// Forgets a guess recorded by [record_password_attempt] that turned out to be right.
+pub async fn forget_password_attempt(
+  redis_client: &RedisConnectionManager,
+  resource: &str,
+  client_ip: Option<&str>,
+) -> Result<(), AppError> {
+  let mut conn = redis_client.clone();
+  for (key, _) in attempt_keys(resource, client_ip) {
+    let _: i64 = conn
+      .decr(&key, 1)
+      .await
+      .map_err(|err| AppError::Internal(anyhow!("Failed to forget password attempt: {}", err)))?;
+  }
+  Ok(())
+}
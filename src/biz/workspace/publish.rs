@@ -4,22 +4,25 @@ use database::{
   publish::{
     insert_non_orginal_workspace_publish_namespace, select_all_published_collab_info,
     select_default_published_view_id, select_default_published_view_id_for_namespace,
-    select_workspace_publish_namespace, select_workspace_publish_namespaces,
-    update_published_collabs, update_workspace_default_publish_view,
+    select_published_collab_settings_for_workspace, select_workspace_publish_namespace,
+    select_workspace_publish_namespaces, update_published_collab_access,
+    update_published_collab_live, update_published_collabs, update_workspace_default_publish_view,
     update_workspace_default_publish_view_set_null,
   },
   workspace::{select_publish_name_exists, select_view_id_from_publish_name},
 };
 use database_entity::dto::PatchPublishedCollab;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use app_error::AppError;
 use async_trait::async_trait;
 use aws_sdk_s3::primitives::ByteStream;
+use chrono::Utc;
 use database_entity::dto::{PublishCollabItem, PublishInfo};
 use shared_entity::dto::{
   publish_dto::PublishViewMetaData,
-  workspace_dto::{FolderViewMinimal, PublishInfoView},
+  workspace_dto::{FolderViewMinimal, PublishInfoView, PublishedViewSettings, ViewLayout},
 };
 use sqlx::PgPool;
 use tracing::debug;
@@ -28,15 +31,19 @@ use uuid::Uuid;
 use database::{
   file::{s3_client_impl::AwsS3BucketClientImpl, BucketClient, ResponseBlob},
   publish::{
-    insert_or_replace_publish_collabs, select_publish_collab_meta, select_published_collab_blob,
-    select_published_collab_info, select_published_collab_workspace_view_id,
-    select_published_data_for_view_id, select_published_metadata_for_view_id,
-    select_user_is_collab_publisher_for_all_views, select_workspace_publish_namespace_exists,
-    set_published_collabs_as_unpublished, update_non_orginal_workspace_publish_namespace,
+    insert_or_replace_publish_collabs, published_collab_s3_key, select_publish_collab_meta,
+    select_published_collab_blob, select_published_collab_info,
+    select_published_collab_workspace_view_id, select_published_data_for_view_id,
+    select_published_metadata_for_view_id, select_user_is_collab_publisher_for_all_views,
+    select_workspace_publish_namespace_exists, set_published_collabs_as_unpublished,
+    update_non_orginal_workspace_publish_namespace,
   },
   workspace::select_user_is_workspace_owner,
 };
 
+use super::publish_access::{
+  hash_published_view_password, normalize_allowed_email_domains, to_published_view_settings,
+};
 use crate::{
   api::metrics::PublishedCollabMetrics,
   biz::collab::{folder_view::to_dto_folder_view_miminal, ops::get_latest_collab_folder},
@@ -83,10 +90,6 @@ fn check_collab_publish_name(publish_name: &str) -> Result<(), AppError> {
   Ok(())
 }
 
-fn get_collab_s3_key(workspace_id: &Uuid, view_id: &Uuid) -> String {
-  format!("published-collab/{}/{}", workspace_id, view_id)
-}
-
 pub async fn set_workspace_namespace(
   pg_pool: &PgPool,
   workspace_id: &Uuid,
@@ -202,6 +205,7 @@ pub async fn get_workspace_publish_namespace(
 }
 
 pub async fn list_collab_publish_info(
+  pg_pool: &PgPool,
   publish_collab_store: &dyn PublishedCollabStore,
   collab_storage: &CollabAccessControlStorage,
   workspace_id: &Uuid,
@@ -216,15 +220,26 @@ pub async fn list_collab_publish_info(
   let publish_infos = publish_collab_store
     .list_collab_publish_info(workspace_id)
     .await?;
+  let settings_by_view_id: HashMap<Uuid, PublishedViewSettings> =
+    select_published_collab_settings_for_workspace(pg_pool, workspace_id)
+      .await?
+      .iter()
+      .map(|settings| (settings.view_id, to_published_view_settings(settings)))
+      .collect();
 
   let mut publish_info_views: Vec<PublishInfoView> = Vec::with_capacity(publish_infos.len());
   for publish_info in publish_infos {
     let view_id = publish_info.view_id.to_string();
+    let settings = settings_by_view_id
+      .get(&publish_info.view_id)
+      .cloned()
+      .unwrap_or_default();
     match folder.get_view(&view_id) {
       Some(view) => {
         publish_info_views.push(PublishInfoView {
           view: to_dto_folder_view_miminal(&view),
           info: publish_info,
+          settings,
         });
       },
       None => {
@@ -236,6 +251,7 @@ pub async fn list_collab_publish_info(
             ..Default::default()
           },
           info: publish_info,
+          settings,
         });
       },
     };
@@ -464,7 +480,7 @@ impl PublishedCollabStore for PublishedCollabS3StoreWithPostgresFallback {
       )
       .await?;
 
-      let object_key = get_collab_s3_key(workspace_id, &publish_item.meta.view_id);
+      let object_key = published_collab_s3_key(workspace_id, &publish_item.meta.view_id);
       let data = publish_item.data.clone();
       let bucket_client = self.bucket_client.clone();
       let metrics = self.metrics.clone();
@@ -516,7 +532,7 @@ impl PublishedCollabStore for PublishedCollabS3StoreWithPostgresFallback {
     match result {
       Some((workspace_id, js_val)) => {
         let metadata = serde_json::from_value(js_val)?;
-        let object_key = get_collab_s3_key(&workspace_id, view_id);
+        let object_key = published_collab_s3_key(&workspace_id, view_id);
         match self.bucket_client.get_blob(&object_key).await {
           Ok(resp) => {
             self.metrics.incr_success_read_count(1);
@@ -565,7 +581,7 @@ impl PublishedCollabStore for PublishedCollabS3StoreWithPostgresFallback {
     let collab_key =
       select_published_collab_workspace_view_id(&self.pg_pool, publish_namespace, publish_name)
         .await?;
-    let object_key = get_collab_s3_key(&collab_key.workspace_id, &collab_key.view_id);
+    let object_key = published_collab_s3_key(&collab_key.workspace_id, &collab_key.view_id);
     let resp = self.bucket_client.get_blob(&object_key).await;
     match resp {
       Ok(resp) => {
@@ -598,7 +614,7 @@ impl PublishedCollabStore for PublishedCollabS3StoreWithPostgresFallback {
     check_workspace_owner_or_publisher(&self.pg_pool, user_uuid, workspace_id, view_ids).await?;
     let object_keys = view_ids
       .iter()
-      .map(|view_id| get_collab_s3_key(workspace_id, view_id))
+      .map(|view_id| published_collab_s3_key(workspace_id, view_id))
       .collect::<Vec<String>>();
     self.bucket_client.delete_blobs(object_keys).await?;
     set_published_collabs_as_unpublished(&self.pg_pool, workspace_id, view_ids).await?;
@@ -630,15 +646,98 @@ async fn patch_collabs(
       check_collab_publish_name(new_publish_name)?;
       check_publish_name_already_exists(pg_pool, workspace_id, new_publish_name).await?;
     }
+    if patch.live == Some(true) {
+      check_view_can_be_published_live(pg_pool, &patch.view_id).await?;
+    }
+    if let Some(Some(expires_at)) = patch.expires_at {
+      if expires_at <= Utc::now() {
+        return Err(AppError::InvalidRequest(
+          "The expiration date of the published view must be in the future".to_string(),
+        ));
+      }
+    }
   }
   check_workspace_owner_or_publisher(pg_pool, user_uuid, workspace_id, &view_ids).await?;
 
+  let mut access_patches = Vec::new();
+  for patch in patches {
+    if patch.password.is_none()
+      && patch.expires_at.is_none()
+      && patch.allowed_email_domains.is_none()
+    {
+      continue;
+    }
+    let password_hash = match patch.password.clone() {
+      Some(Some(password)) => Some(Some(hash_published_view_password(password).await?)),
+      Some(None) => Some(None),
+      None => None,
+    };
+    let allowed_email_domains = patch
+      .allowed_email_domains
+      .clone()
+      .map(normalize_allowed_email_domains)
+      .transpose()?;
+    access_patches.push((
+      patch.view_id,
+      password_hash,
+      patch.expires_at,
+      allowed_email_domains,
+    ));
+  }
+
   let mut txn = pg_pool.begin().await?;
   update_published_collabs(&mut txn, workspace_id, patches).await?;
+  for patch in patches {
+    if let Some(live) = patch.live {
+      let updated =
+        update_published_collab_live(txn.as_mut(), workspace_id, &patch.view_id, live).await?;
+      if !updated {
+        return Err(AppError::RecordNotFound(format!(
+          "view {} is not published",
+          patch.view_id
+        )));
+      }
+    }
+  }
+  for (view_id, password_hash, expires_at, allowed_email_domains) in access_patches {
+    let updated = update_published_collab_access(
+      txn.as_mut(),
+      workspace_id,
+      &view_id,
+      password_hash,
+      expires_at,
+      allowed_email_domains,
+    )
+    .await?;
+    if !updated {
+      return Err(AppError::RecordNotFound(format!(
+        "view {} is not published",
+        view_id
+      )));
+    }
+  }
   txn.commit().await?;
   Ok(())
 }
 
+/// Only documents can be published live: their published blob is the doc state of the document,
+/// which the collab group persistence can re-materialise on its own.
+async fn check_view_can_be_published_live(
+  pg_pool: &PgPool,
+  view_id: &Uuid,
+) -> Result<(), AppError> {
+  let (_, metadata) = select_published_metadata_for_view_id(pg_pool, view_id)
+    .await?
+    .ok_or_else(|| AppError::RecordNotFound(format!("view {} is not published", view_id)))?;
+  let metadata: PublishViewMetaData = serde_json::from_value(metadata)?;
+  if metadata.view.layout != ViewLayout::Document {
+    return Err(AppError::InvalidRequest(
+      "Only documents can be published live".to_string(),
+    ));
+  }
+  Ok(())
+}
+
 /// Checks if the `publish_name` already exists for the workspace
 async fn check_publish_name_already_exists(
   pg_pool: &PgPool,
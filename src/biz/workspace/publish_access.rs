@@ -0,0 +1,159 @@
+use app_error::AppError;
+use authentication::password::{compute_hash_password, verify_password_hash};
+use database::pg_row::AFPublishedCollabSettingsRow;
+use database::publish::{
+  select_published_collab_settings, select_published_collab_settings_by_name,
+};
+use database::user::select_email_from_user_uuid;
+use secrecy::{ExposeSecret, Secret};
+use shared_entity::dto::workspace_dto::PublishedViewSettings;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::state::RedisConnectionManager;
+
+use super::password_attempt::{forget_password_attempt, record_password_attempt};
+
+const MAX_ALLOWED_EMAIL_DOMAINS: usize = 20;
+
+/// Whoever reads a published view: a visitor, or a signed-in user, with the password of the view
+/// if it was given.
+#[derive(Debug, Default)]
+pub struct PublishedViewReader {
+  pub user_uuid: Option<Uuid>,
+  pub password: Option<String>,
+  /// Used to limit the wrong passwords per client, see [record_password_attempt].
+  pub client_ip: Option<String>,
+}
+
+/// Checks that the reader can read the published view. Views that are not published are let
+/// through, the caller returns its own not found error for them.
+pub async fn enforce_published_view_access(
+  pg_pool: &PgPool,
+  redis_client: &RedisConnectionManager,
+  view_id: &Uuid,
+  reader: &PublishedViewReader,
+) -> Result<(), AppError> {
+  if let Some(settings) = select_published_collab_settings(pg_pool, view_id).await? {
+    check_reader_access(pg_pool, redis_client, &settings, reader).await?;
+  }
+  Ok(())
+}
+
+/// Same as [enforce_published_view_access] for a view given by its publish name. Returns whether
+/// the view is restricted to some readers, in which case its content must not be cached by
+/// shared caches.
+pub async fn enforce_published_view_access_by_name(
+  pg_pool: &PgPool,
+  redis_client: &RedisConnectionManager,
+  publish_namespace: &str,
+  publish_name: &str,
+  reader: &PublishedViewReader,
+) -> Result<bool, AppError> {
+  match select_published_collab_settings_by_name(pg_pool, publish_namespace, publish_name).await? {
+    Some(settings) => {
+      check_reader_access(pg_pool, redis_client, &settings, reader).await?;
+      Ok(!settings.is_public())
+    },
+    None => Ok(false),
+  }
+}
+
+async fn check_reader_access(
+  pg_pool: &PgPool,
+  redis_client: &RedisConnectionManager,
+  settings: &AFPublishedCollabSettingsRow,
+  reader: &PublishedViewReader,
+) -> Result<(), AppError> {
+  if settings.is_expired() {
+    return Err(AppError::RecordNotFound(
+      "The published view has expired".to_string(),
+    ));
+  }
+
+  if !settings.allowed_email_domains.is_empty() {
+    let user_uuid = reader
+      .user_uuid
+      .ok_or_else(|| AppError::NotLoggedIn("Sign in to read this published view".to_string()))?;
+    let email = select_email_from_user_uuid(pg_pool, &user_uuid).await?;
+    let domain = email
+      .rsplit_once('@')
+      .map(|(_, domain)| domain.to_lowercase())
+      .unwrap_or_default();
+    if !settings.allowed_email_domains.contains(&domain) {
+      return Err(AppError::UserUnAuthorized(
+        "The published view is not shared with your email domain".to_string(),
+      ));
+    }
+  }
+
+  if let Some(password_hash) = settings.password_hash.clone() {
+    let password = reader.password.clone().ok_or_else(|| {
+      AppError::InvalidPassword("The published view requires a password".to_string())
+    })?;
+    let resource = format!("published_view:{}", settings.view_id);
+    let client_ip = reader.client_ip.as_deref();
+    record_password_attempt(redis_client, &resource, client_ip).await?;
+    tokio::task::spawn_blocking(move || {
+      verify_password_hash(Secret::new(password_hash), Secret::new(password))
+    })
+    .await?
+    .map_err(|_| AppError::InvalidPassword("Invalid published view password".to_string()))?;
+    forget_password_attempt(redis_client, &resource, client_ip).await?;
+  }
+  Ok(())
+}
+
+/// Views listed in the outline of the namespace: the restricted views are only reachable from
+/// their own link.
+pub fn is_listed_in_outline(settings: &AFPublishedCollabSettingsRow) -> bool {
+  settings.password_hash.is_none()
+    && settings.allowed_email_domains.is_empty()
+    && !settings.is_expired()
+}
+
+pub async fn hash_published_view_password(password: String) -> Result<String, AppError> {
+  if password.is_empty() {
+    return Err(AppError::InvalidRequest(
+      "The password of the published view can't be empty".to_string(),
+    ));
+  }
+  let password_hash =
+    tokio::task::spawn_blocking(move || compute_hash_password(password.as_bytes())).await??;
+  Ok(password_hash.expose_secret().clone())
+}
+
+/// Lowercases the domains and removes the leading `@` the owners often type.
+pub fn normalize_allowed_email_domains(domains: Vec<String>) -> Result<Vec<String>, AppError> {
+  if domains.len() > MAX_ALLOWED_EMAIL_DOMAINS {
+    return Err(AppError::InvalidRequest(format!(
+      "A published view can be restricted to {} email domains at most",
+      MAX_ALLOWED_EMAIL_DOMAINS
+    )));
+  }
+  let mut normalized = Vec::with_capacity(domains.len());
+  for domain in domains {
+    let domain = domain.trim().trim_start_matches('@').to_lowercase();
+    if domain.is_empty() || domain.contains('@') || !domain.contains('.') {
+      return Err(AppError::InvalidRequest(format!(
+        "Invalid email domain: {}",
+        domain
+      )));
+    }
+    if !normalized.contains(&domain) {
+      normalized.push(domain);
+    }
+  }
+  Ok(normalized)
+}
+
+pub fn to_published_view_settings(
+  settings: &AFPublishedCollabSettingsRow,
+) -> PublishedViewSettings {
+  PublishedViewSettings {
+    live: settings.live,
+    has_password: settings.password_hash.is_some(),
+    expires_at: settings.expires_at,
+    allowed_email_domains: settings.allowed_email_domains.clone(),
+  }
+}
@@ -0,0 +1,355 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use actix_web::http::header::{REFERER, USER_AGENT};
+use actix_web::HttpRequest;
+use app_error::AppError;
+use chrono::{NaiveDate, Utc};
+use database::publish_analytics::{
+  delete_published_view_visitors_before, insert_published_view_visitors,
+  select_or_insert_published_view_salt, select_published_view_daily_stats,
+  select_published_view_referrer_stats, select_published_views_by_name,
+  upsert_published_view_daily_stats, upsert_published_view_referrer_stats,
+};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use shared_entity::dto::publish_dto::{
+  PublishedViewAnalytics, PublishedViewDailyStats, PublishedViewReferrer,
+  QueryPublishedViewAnalytics,
+};
+use sqlx::PgPool;
+use tokio::time::interval;
+use tracing::{error, trace};
+use uuid::Uuid;
+
+use crate::api::util::client_ip;
+
+const ANALYTICS_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+const DEFAULT_ANALYTICS_DAYS: i64 = 30;
+const MAX_ANALYTICS_DAYS: i64 = 366;
+const MAX_REFERRER_LEN: usize = 255;
+
+/// One read of a published view. The address of the reader is hashed right away, only the hash is
+/// kept until the next flush.
+pub struct PublishedViewHit {
+  day: NaiveDate,
+  visitor: [u8; 32],
+  referrer: String,
+}
+
+impl PublishedViewHit {
+  pub fn from_request(req: &HttpRequest) -> Self {
+    let mut hasher = Sha256::new();
+    if let Some(addr) = client_ip(req) {
+      hasher.update(addr.as_bytes());
+    }
+    hasher.update(b"\n");
+    if let Some(user_agent) = req.headers().get(USER_AGENT) {
+      hasher.update(user_agent.as_bytes());
+    }
+    let referrer = req
+      .headers()
+      .get(REFERER)
+      .and_then(|value| value.to_str().ok())
+      .and_then(referrer_host)
+      .unwrap_or_default();
+    Self {
+      day: Utc::now().date_naive(),
+      visitor: hasher.finalize().into(),
+      referrer,
+    }
+  }
+}
+
+/// Only the host of the referring page is kept, the path may identify the reader.
+fn referrer_host(referrer: &str) -> Option<String> {
+  let url = url::Url::parse(referrer).ok()?;
+  let host = url.host_str()?.to_lowercase();
+  if host.len() > MAX_REFERRER_LEN {
+    return None;
+  }
+  Some(host)
+}
+
+#[derive(Default)]
+struct PendingStats {
+  view_count: i64,
+  visitors: HashSet<[u8; 32]>,
+  referrers: HashMap<String, i64>,
+}
+
+/// (publish namespace, publish name, day)
+type PendingKey = (String, String, NaiveDate);
+
+/// Counts the reads of the published views.
+///
+/// Recording a read only updates counters in memory, they are written to the database every
+/// [ANALYTICS_FLUSH_INTERVAL]. The visitors are counted once per day with a hash of their address
+/// and user agent, salted with a salt of the day shared by all the servers. The salts and the hashes
+/// of the previous days are deleted.
+pub struct PublishedViewAnalyticsRecorder {
+  pending: Arc<Mutex<HashMap<PendingKey, PendingStats>>>,
+}
+
+impl PublishedViewAnalyticsRecorder {
+  pub fn new(pg_pool: PgPool) -> Self {
+    let pending = Arc::new(Mutex::new(HashMap::new()));
+    tokio::spawn(run_flush(pg_pool, pending.clone()));
+    Self { pending }
+  }
+
+  pub fn record_view(&self, publish_namespace: &str, publish_name: &str, hit: PublishedViewHit) {
+    let mut pending = match self.pending.lock() {
+      Ok(pending) => pending,
+      Err(_) => return,
+    };
+    let stats = pending
+      .entry((
+        publish_namespace.to_string(),
+        publish_name.to_string(),
+        hit.day,
+      ))
+      .or_default();
+    stats.view_count += 1;
+    stats.visitors.insert(hit.visitor);
+    *stats.referrers.entry(hit.referrer).or_default() += 1;
+  }
+}
+
+async fn run_flush(pg_pool: PgPool, pending: Arc<Mutex<HashMap<PendingKey, PendingStats>>>) {
+  let mut interval = interval(ANALYTICS_FLUSH_INTERVAL);
+  let mut cleaned_up_day = None;
+  loop {
+    interval.tick().await;
+    let stats = match pending.lock() {
+      Ok(mut pending) => std::mem::take(&mut *pending),
+      Err(_) => break,
+    };
+    if stats.is_empty() {
+      continue;
+    }
+    if let Err(err) = flush_stats(&pg_pool, stats, &mut cleaned_up_day).await {
+      error!("Failed to write published view analytics: {}", err);
+    }
+  }
+}
+
+#[derive(Default)]
+struct ViewStats {
+  view_count: i64,
+  visitors: HashSet<[u8; 32]>,
+  referrers: HashMap<String, i64>,
+}
+
+async fn flush_stats(
+  pg_pool: &PgPool,
+  stats: HashMap<PendingKey, PendingStats>,
+  cleaned_up_day: &mut Option<NaiveDate>,
+) -> Result<(), AppError> {
+  let (namespaces, publish_names): (Vec<String>, Vec<String>) = stats
+    .keys()
+    .map(|(namespace, publish_name, _)| (namespace.clone(), publish_name.clone()))
+    .collect::<HashSet<_>>()
+    .into_iter()
+    .unzip();
+  let views: HashMap<(String, String), (Uuid, Uuid)> =
+    select_published_views_by_name(pg_pool, &namespaces, &publish_names)
+      .await?
+      .into_iter()
+      .map(|row| {
+        (
+          (row.namespace, row.publish_name),
+          (row.workspace_id, row.view_id),
+        )
+      })
+      .collect();
+
+  // A view can be read through several namespaces of its workspace.
+  let mut view_stats: HashMap<(Uuid, Uuid, NaiveDate), ViewStats> = HashMap::new();
+  for ((namespace, publish_name, day), pending) in stats {
+    let (workspace_id, view_id) = match views.get(&(namespace, publish_name)) {
+      Some(view) => *view,
+      None => continue,
+    };
+    let entry = view_stats.entry((workspace_id, view_id, day)).or_default();
+    entry.view_count += pending.view_count;
+    entry.visitors.extend(pending.visitors);
+    for (referrer, count) in pending.referrers {
+      *entry.referrers.entry(referrer).or_default() += count;
+    }
+  }
+  if view_stats.is_empty() {
+    return Ok(());
+  }
+
+  let mut salts = HashMap::new();
+  for (_, _, day) in view_stats.keys() {
+    if !salts.contains_key(day) {
+      let new_salt: [u8; 32] = rand::thread_rng().gen();
+      let salt = select_or_insert_published_view_salt(pg_pool, *day, &new_salt).await?;
+      salts.insert(*day, salt);
+    }
+  }
+
+  let mut visitor_view_ids = vec![];
+  let mut visitor_days = vec![];
+  let mut visitor_hashes = vec![];
+  for ((_, view_id, day), stats) in &view_stats {
+    let salt = &salts[day];
+    for visitor in &stats.visitors {
+      let mut hasher = Sha256::new();
+      hasher.update(salt);
+      hasher.update(visitor);
+      visitor_view_ids.push(*view_id);
+      visitor_days.push(*day);
+      visitor_hashes.push(hasher.finalize().to_vec());
+    }
+  }
+
+  let mut txn = pg_pool.begin().await?;
+  let today = Utc::now().date_naive();
+  if *cleaned_up_day != Some(today) {
+    // The hashes of yesterday are kept for the reads recorded just before midnight.
+    if let Some(yesterday) = today.pred_opt() {
+      delete_published_view_visitors_before(&mut txn, yesterday).await?;
+    }
+  }
+
+  let mut unique_visitor_counts: HashMap<(Uuid, NaiveDate), i64> = HashMap::new();
+  for key in
+    insert_published_view_visitors(&mut txn, &visitor_view_ids, &visitor_days, &visitor_hashes)
+      .await?
+  {
+    *unique_visitor_counts.entry(key).or_default() += 1;
+  }
+
+  let mut workspace_ids = vec![];
+  let mut view_ids = vec![];
+  let mut days = vec![];
+  let mut view_counts = vec![];
+  let mut new_visitor_counts = vec![];
+  let mut referrer_workspace_ids = vec![];
+  let mut referrer_view_ids = vec![];
+  let mut referrer_days = vec![];
+  let mut referrers = vec![];
+  let mut referrer_counts = vec![];
+  for ((workspace_id, view_id, day), stats) in view_stats {
+    workspace_ids.push(workspace_id);
+    view_ids.push(view_id);
+    days.push(day);
+    view_counts.push(stats.view_count);
+    new_visitor_counts.push(
+      unique_visitor_counts
+        .get(&(view_id, day))
+        .copied()
+        .unwrap_or_default(),
+    );
+    for (referrer, count) in stats.referrers {
+      referrer_workspace_ids.push(workspace_id);
+      referrer_view_ids.push(view_id);
+      referrer_days.push(day);
+      referrers.push(referrer);
+      referrer_counts.push(count);
+    }
+  }
+  upsert_published_view_daily_stats(
+    &mut txn,
+    &workspace_ids,
+    &view_ids,
+    &days,
+    &view_counts,
+    &new_visitor_counts,
+  )
+  .await?;
+  upsert_published_view_referrer_stats(
+    &mut txn,
+    &referrer_workspace_ids,
+    &referrer_view_ids,
+    &referrer_days,
+    &referrers,
+    &referrer_counts,
+  )
+  .await?;
+  txn.commit().await?;
+  *cleaned_up_day = Some(today);
+  trace!("Wrote the analytics of {} published views", view_ids.len());
+  Ok(())
+}
+
+pub async fn get_published_view_analytics(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  query: QueryPublishedViewAnalytics,
+) -> Result<Vec<PublishedViewAnalytics>, AppError> {
+  let end = query.end.unwrap_or_else(|| Utc::now().date_naive());
+  let start = query
+    .start
+    .unwrap_or_else(|| end - chrono::Duration::days(DEFAULT_ANALYTICS_DAYS - 1));
+  if start > end {
+    return Err(AppError::InvalidRequest(
+      "The start of the range must be before its end".to_string(),
+    ));
+  }
+  if (end - start).num_days() >= MAX_ANALYTICS_DAYS {
+    return Err(AppError::InvalidRequest(format!(
+      "The range can't be longer than {} days",
+      MAX_ANALYTICS_DAYS
+    )));
+  }
+
+  let mut analytics: BTreeMap<Uuid, PublishedViewAnalytics> = BTreeMap::new();
+  let daily_stats =
+    select_published_view_daily_stats(pg_pool, workspace_id, query.view_id.as_ref(), start, end)
+      .await?;
+  for row in daily_stats {
+    let view = analytics
+      .entry(row.view_id)
+      .or_insert_with(|| empty_analytics(row.view_id));
+    view.view_count += row.view_count;
+    view.unique_visitor_count += row.unique_visitor_count;
+    view.daily.push(PublishedViewDailyStats {
+      day: row.day,
+      view_count: row.view_count,
+      unique_visitor_count: row.unique_visitor_count,
+    });
+  }
+  let referrer_stats =
+    select_published_view_referrer_stats(pg_pool, workspace_id, query.view_id.as_ref(), start, end)
+      .await?;
+  for row in referrer_stats {
+    let view = analytics
+      .entry(row.view_id)
+      .or_insert_with(|| empty_analytics(row.view_id));
+    view.referrers.push(PublishedViewReferrer {
+      referrer: (!row.referrer.is_empty()).then_some(row.referrer),
+      view_count: row.view_count,
+    });
+  }
+  Ok(analytics.into_values().collect())
+}
+
+fn empty_analytics(view_id: Uuid) -> PublishedViewAnalytics {
+  PublishedViewAnalytics {
+    view_id,
+    view_count: 0,
+    unique_visitor_count: 0,
+    referrers: vec![],
+    daily: vec![],
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn referrer_host_test() {
+    assert_eq!(
+      referrer_host("https://News.Example.com/some/article?id=1"),
+      Some("news.example.com".to_string())
+    );
+    assert_eq!(referrer_host("not a url"), None);
+    assert_eq!(referrer_host("mailto:someone@example.com"), None);
+  }
+}
@@ -0,0 +1,771 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use app_error::AppError;
+use chrono::DateTime;
+use collab_database::database::DatabaseBody;
+use collab_database::rows::RowDetail;
+use collab_database::workspace_database::NoPersistenceDatabaseCollabService;
+use collab_document::blocks::{Block, DocumentData};
+use collab_document::document::Document;
+use serde_json::Value;
+use shared_entity::dto::publish_dto::{PublishDatabaseData, PublishViewInfo, PublishViewMetaData};
+use shared_entity::dto::workspace_dto::{IconType, ViewLayout};
+
+use super::publish::PublishedCollabStore;
+use crate::biz::collab::ops::{
+  collab_from_doc_state, convert_database_cells_human_readable, field_by_unique_name_id,
+  selection_name_by_id,
+};
+
+/// Length of the page description used by search engines and link previews.
+const MAX_DESCRIPTION_CHARS: usize = 200;
+/// Rows of a published database rendered in the page, the others are only visible in the web
+/// client.
+const MAX_RENDERED_ROWS: usize = 1000;
+/// Blocks nested deeper than this are not rendered. The document is written by the publisher, so
+/// the nesting is bounded to keep the recursion from overflowing the stack.
+const MAX_BLOCK_DEPTH: usize = 64;
+
+const PAGE_STYLE: &str = "body{margin:0;font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',\
+Roboto,Helvetica,Arial,sans-serif;color:#1f2329;line-height:1.6}\
+article{max-width:900px;margin:0 auto;padding:48px 24px}\
+.cover{width:100%;max-height:280px;object-fit:cover}\
+.icon{font-size:48px}.children{margin-left:24px}\
+blockquote{margin:0;padding-left:16px;border-left:4px solid #00b5ff}\
+aside{padding:16px;border-radius:8px;background:#f2f3f5}\
+pre{padding:16px;border-radius:8px;background:#f2f3f5;overflow-x:auto}\
+img{max-width:100%}ul.todo{list-style:none;padding-left:0}\
+table{border-collapse:collapse;width:100%}\
+th,td{border:1px solid #e5e6eb;padding:6px 8px;text-align:left;vertical-align:top}";
+
+/// Renders a published document or database to a static HTML page, with the Open Graph metadata
+/// of the view, for crawlers, link previews and readers without JavaScript.
+pub async fn render_published_view_html(
+  publish_collab_store: &dyn PublishedCollabStore,
+  appflowy_web_url: Option<&str>,
+  publish_namespace: &str,
+  publish_name: &str,
+) -> Result<String, AppError> {
+  let metadata = publish_collab_store
+    .get_collab_metadata(publish_namespace, publish_name)
+    .await?;
+  let metadata: PublishViewMetaData = serde_json::from_value(metadata)?;
+  let blob = publish_collab_store
+    .get_collab_blob_by_publish_namespace(publish_namespace, publish_name)
+    .await?;
+  let page_url = appflowy_web_url.map(|url| {
+    format!(
+      "{}/{}/{}",
+      url.trim_end_matches('/'),
+      publish_namespace,
+      publish_name
+    )
+  });
+  tokio::task::spawn_blocking(move || {
+    let body = match metadata.view.layout {
+      ViewLayout::Document => render_document_body(blob)?,
+      ViewLayout::Grid | ViewLayout::Board | ViewLayout::Calendar => {
+        render_database_body(blob, &metadata.view.view_id)?
+      },
+      ViewLayout::Chat => {
+        return Err(AppError::InvalidRequest(
+          "An AI chat can't be rendered to HTML".to_string(),
+        ))
+      },
+    };
+    Ok(render_page(&metadata.view, page_url.as_deref(), body))
+  })
+  .await?
+}
+
+/// The rendered content of a page and its plain text description.
+struct RenderedBody {
+  html: String,
+  description: String,
+}
+
+fn render_page(view: &PublishViewInfo, page_url: Option<&str>, body: RenderedBody) -> String {
+  let title = if view.name.trim().is_empty() {
+    "Untitled"
+  } else {
+    view.name.trim()
+  };
+  let cover_url = cover_url(view);
+  let mut html = String::new();
+  html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+  html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+  let _ = writeln!(html, "<title>{}</title>", escape_html(title));
+  let _ = writeln!(
+    html,
+    "<meta name=\"description\" content=\"{}\">",
+    escape_html(&body.description)
+  );
+  html.push_str("<meta property=\"og:type\" content=\"article\">\n");
+  html.push_str("<meta property=\"og:site_name\" content=\"AppFlowy\">\n");
+  let _ = writeln!(
+    html,
+    "<meta property=\"og:title\" content=\"{}\">",
+    escape_html(title)
+  );
+  let _ = writeln!(
+    html,
+    "<meta property=\"og:description\" content=\"{}\">",
+    escape_html(&body.description)
+  );
+  if let Some(page_url) = page_url {
+    let _ = writeln!(
+      html,
+      "<meta property=\"og:url\" content=\"{}\">",
+      escape_html(page_url)
+    );
+    let _ = writeln!(
+      html,
+      "<link rel=\"canonical\" href=\"{}\">",
+      escape_html(page_url)
+    );
+  }
+  if let Some(cover_url) = &cover_url {
+    let _ = writeln!(
+      html,
+      "<meta property=\"og:image\" content=\"{}\">",
+      escape_html(cover_url)
+    );
+    html.push_str("<meta name=\"twitter:card\" content=\"summary_large_image\">\n");
+  } else {
+    html.push_str("<meta name=\"twitter:card\" content=\"summary\">\n");
+  }
+  if let Some(modified_time) = DateTime::from_timestamp(view.last_edited_time, 0) {
+    let _ = writeln!(
+      html,
+      "<meta property=\"article:modified_time\" content=\"{}\">",
+      modified_time.to_rfc3339()
+    );
+  }
+  let _ = writeln!(html, "<style>{}</style>\n</head>\n<body>", PAGE_STYLE);
+  if let Some(cover_url) = &cover_url {
+    let _ = writeln!(
+      html,
+      "<img class=\"cover\" src=\"{}\" alt=\"\">",
+      escape_html(cover_url)
+    );
+  }
+  html.push_str("<article>\n<header>\n");
+  if let Some(icon) = view.icon.as_ref().filter(|icon| icon.ty == IconType::Emoji) {
+    let _ = writeln!(
+      html,
+      "<div class=\"icon\">{}</div>",
+      escape_html(&icon.value)
+    );
+  }
+  let _ = writeln!(html, "<h1>{}</h1>\n</header>", escape_html(title));
+  html.push_str(&body.html);
+  html.push_str("</article>\n</body>\n</html>\n");
+  html
+}
+
+/// The cover of the view if it is an image, set in the `extra` of the view by the client.
+fn cover_url(view: &PublishViewInfo) -> Option<String> {
+  let extra: Value = serde_json::from_str(view.extra.as_deref()?).ok()?;
+  let value = extra.get("cover")?.get("value")?.as_str()?;
+  is_safe_url(value).then(|| value.to_string())
+}
+
+fn render_document_body(blob: Vec<u8>) -> Result<RenderedBody, AppError> {
+  let collab = collab_from_doc_state(blob, "")?;
+  let document = Document::open(collab).map_err(|err| AppError::Unhandled(err.to_string()))?;
+  let data = document
+    .get_document_data()
+    .map_err(|err| AppError::Unhandled(err.to_string()))?;
+  let mut writer = DocumentHtmlWriter::new(&data);
+  writer.write_children(&data.page_id);
+  Ok(RenderedBody {
+    html: writer.html,
+    description: writer
+      .description
+      .chars()
+      .take(MAX_DESCRIPTION_CHARS)
+      .collect(),
+  })
+}
+
+struct DocumentHtmlWriter<'a> {
+  data: &'a DocumentData,
+  html: String,
+  description: String,
+  /// Blocks already written, so a block listed in the children of one of its descendants is not
+  /// written again.
+  visited: HashSet<&'a str>,
+  depth: usize,
+}
+
+impl<'a> DocumentHtmlWriter<'a> {
+  fn new(data: &'a DocumentData) -> Self {
+    Self {
+      data,
+      html: String::new(),
+      description: String::new(),
+      visited: HashSet::from([data.page_id.as_str()]),
+      depth: 0,
+    }
+  }
+
+  fn children(&self, block_id: &str) -> Vec<&'a Block> {
+    let data = self.data;
+    data
+      .blocks
+      .get(block_id)
+      .and_then(|block| data.meta.children_map.get(&block.children))
+      .map(|children| {
+        children
+          .iter()
+          .filter_map(|child_id| data.blocks.get(child_id))
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+
+  /// Writes the children of the block. Consecutive list items are grouped in the same list.
+  fn write_children(&mut self, block_id: &str) {
+    if self.depth >= MAX_BLOCK_DEPTH {
+      return;
+    }
+    self.depth += 1;
+    let children = self.children(block_id);
+    let mut open_list: Option<&'static str> = None;
+    for child in children {
+      if !self.visited.insert(child.id.as_str()) {
+        continue;
+      }
+      let list = match child.ty.as_str() {
+        "bulleted_list" => Some("<ul>"),
+        "numbered_list" => Some("<ol>"),
+        "todo_list" => Some("<ul class=\"todo\">"),
+        _ => None,
+      };
+      if open_list != list {
+        if let Some(open) = open_list {
+          self.html.push_str(closing_list_tag(open));
+        }
+        if let Some(list) = list {
+          self.html.push_str(list);
+        }
+        open_list = list;
+      }
+      self.write_block(child);
+    }
+    if let Some(open) = open_list {
+      self.html.push_str(closing_list_tag(open));
+    }
+    self.depth -= 1;
+  }
+
+  /// Writes the children of the block indented, as in the editor.
+  fn write_nested_children(&mut self, block: &Block) {
+    if self
+      .children(&block.id)
+      .iter()
+      .any(|child| !self.visited.contains(child.id.as_str()))
+    {
+      self.html.push_str("<div class=\"children\">");
+      self.write_children(&block.id);
+      self.html.push_str("</div>");
+    }
+  }
+
+  fn write_block(&mut self, block: &Block) {
+    match block.ty.as_str() {
+      "heading" => {
+        // The title of the page is the only h1.
+        let level = block
+          .data
+          .get("level")
+          .and_then(Value::as_u64)
+          .unwrap_or(1)
+          .clamp(1, 5)
+          + 1;
+        let _ = write!(self.html, "<h{}>", level);
+        self.write_text(block);
+        let _ = write!(self.html, "</h{}>", level);
+        self.write_nested_children(block);
+      },
+      "bulleted_list" | "numbered_list" => {
+        self.html.push_str("<li>");
+        self.write_text(block);
+        self.write_children(&block.id);
+        self.html.push_str("</li>");
+      },
+      "todo_list" => {
+        let checked = block
+          .data
+          .get("checked")
+          .and_then(Value::as_bool)
+          .unwrap_or(false);
+        self.html.push_str(if checked {
+          "<li><input type=\"checkbox\" disabled checked> "
+        } else {
+          "<li><input type=\"checkbox\" disabled> "
+        });
+        self.write_text(block);
+        self.write_children(&block.id);
+        self.html.push_str("</li>");
+      },
+      "toggle_list" => {
+        self.html.push_str("<details open><summary>");
+        self.write_text(block);
+        self.html.push_str("</summary>");
+        self.write_children(&block.id);
+        self.html.push_str("</details>");
+      },
+      "quote" => {
+        self.html.push_str("<blockquote><p>");
+        self.write_text(block);
+        self.html.push_str("</p>");
+        self.write_children(&block.id);
+        self.html.push_str("</blockquote>");
+      },
+      "callout" => {
+        self.html.push_str("<aside>");
+        if let Some(icon) = block.data.get("icon").and_then(Value::as_str) {
+          let _ = write!(self.html, "{} ", escape_html(icon));
+        }
+        self.write_text(block);
+        self.write_children(&block.id);
+        self.html.push_str("</aside>");
+      },
+      "code" => {
+        match block.data.get("language").and_then(Value::as_str) {
+          Some(language) if !language.is_empty() => {
+            let _ = write!(
+              self.html,
+              "<pre><code class=\"language-{}\">",
+              escape_html(&language.to_lowercase())
+            );
+          },
+          _ => self.html.push_str("<pre><code>"),
+        }
+        let text = self
+          .delta(block)
+          .iter()
+          .filter_map(|op| op.get("insert").and_then(Value::as_str))
+          .collect::<String>();
+        self.html.push_str(&escape_html(&text));
+        self.html.push_str("</code></pre>");
+      },
+      "divider" => self.html.push_str("<hr>"),
+      "image" => {
+        if let Some(url) = block
+          .data
+          .get("url")
+          .and_then(Value::as_str)
+          .filter(|url| is_safe_url(url))
+        {
+          let _ = write!(
+            self.html,
+            "<figure><img src=\"{}\" alt=\"\" loading=\"lazy\"></figure>",
+            escape_html(url)
+          );
+        }
+      },
+      "math_equation" => {
+        if let Some(formula) = block.data.get("formula").and_then(Value::as_str) {
+          let _ = write!(self.html, "<p><code>{}</code></p>", escape_html(formula));
+        }
+      },
+      "link_preview" => {
+        if let Some(url) = block
+          .data
+          .get("url")
+          .and_then(Value::as_str)
+          .filter(|url| is_safe_url(url))
+        {
+          let _ = write!(
+            self.html,
+            "<p><a href=\"{0}\" rel=\"noopener nofollow\">{0}</a></p>",
+            escape_html(url)
+          );
+        }
+      },
+      // Databases embedded in the document are published as separate views.
+      "grid" | "board" | "calendar" => {},
+      _ => {
+        if !self.delta(block).is_empty() {
+          self.html.push_str("<p>");
+          self.write_text(block);
+          self.html.push_str("</p>");
+        }
+        self.write_nested_children(block);
+      },
+    }
+  }
+
+  /// The text of the block, as the operations of a delta.
+  fn delta(&self, block: &Block) -> Vec<Value> {
+    if let Some(Value::Array(ops)) = block.data.get("delta") {
+      return ops.clone();
+    }
+    if block.external_type.as_deref() == Some("text") {
+      if let Some(json) = block
+        .external_id
+        .as_ref()
+        .and_then(|text_id| self.data.meta.text_map.as_ref()?.get(text_id))
+      {
+        return serde_json::from_str(json).unwrap_or_default();
+      }
+    }
+    vec![]
+  }
+
+  fn write_text(&mut self, block: &Block) {
+    for op in self.delta(block) {
+      let text = match op.get("insert").and_then(Value::as_str) {
+        Some(text) => text,
+        None => continue,
+      };
+      if self.description.chars().count() < MAX_DESCRIPTION_CHARS {
+        if !self.description.is_empty() && !self.description.ends_with(' ') {
+          self.description.push(' ');
+        }
+        self
+          .description
+          .extend(text.trim().chars().take(MAX_DESCRIPTION_CHARS));
+      }
+      write_text_op(&mut self.html, text, op.get("attributes"));
+    }
+  }
+}
+
+fn closing_list_tag(open: &str) -> &'static str {
+  if open == "<ol>" {
+    "</ol>"
+  } else {
+    "</ul>"
+  }
+}
+
+/// Writes the inserted text of a delta operation with its formatting.
+fn write_text_op(html: &mut String, text: &str, attributes: Option<&Value>) {
+  let is_set = |name: &str| {
+    attributes
+      .and_then(|attributes| attributes.get(name))
+      .and_then(Value::as_bool)
+      .unwrap_or(false)
+  };
+  let href = attributes
+    .and_then(|attributes| attributes.get("href"))
+    .and_then(Value::as_str)
+    .filter(|href| is_safe_url(href));
+  let tags = [
+    ("code", is_set("code")),
+    ("strong", is_set("bold")),
+    ("em", is_set("italic")),
+    ("u", is_set("underline")),
+    ("s", is_set("strikethrough")),
+  ];
+  if let Some(href) = href {
+    let _ = write!(
+      html,
+      "<a href=\"{}\" rel=\"noopener nofollow\">",
+      escape_html(href)
+    );
+  }
+  for (tag, _) in tags.iter().filter(|(_, set)| *set) {
+    let _ = write!(html, "<{}>", tag);
+  }
+  html.push_str(&escape_html(text).replace('\n', "<br>"));
+  for (tag, _) in tags.iter().rev().filter(|(_, set)| *set) {
+    let _ = write!(html, "</{}>", tag);
+  }
+  if href.is_some() {
+    html.push_str("</a>");
+  }
+}
+
+fn render_database_body(blob: Vec<u8>, view_id: &str) -> Result<RenderedBody, AppError> {
+  let published_db: PublishDatabaseData = serde_json::from_slice(&blob)?;
+  let db_collab = collab_from_doc_state(published_db.database_collab, "")?;
+  let db_body = DatabaseBody::from_collab(
+    &db_collab,
+    Arc::new(NoPersistenceDatabaseCollabService),
+    None,
+  )
+  .ok_or_else(|| AppError::RecordNotFound("no database body found".to_string()))?;
+  let (mut fields, row_ids) = {
+    let txn = db_collab.transact();
+    let mut row_orders = db_body.views.get_row_orders(&txn, view_id);
+    if row_orders.is_empty() {
+      row_orders = db_body
+        .views
+        .get_row_orders(&txn, &db_body.get_inline_view_id(&txn));
+    }
+    let row_ids = row_orders
+      .into_iter()
+      .map(|row_order| row_order.id.to_string())
+      .collect::<Vec<_>>();
+    (db_body.fields.get_all_fields(&txn), row_ids)
+  };
+  // The primary field is the first column, as in the grid.
+  fields.sort_by_key(|field| !field.is_primary);
+  let field_ids = fields
+    .iter()
+    .map(|field| field.id.clone())
+    .collect::<Vec<_>>();
+  let field_by_id = field_by_unique_name_id(fields);
+  let selection_name_by_id = selection_name_by_id(field_by_id.values());
+  let field_names = field_ids
+    .iter()
+    .filter_map(|field_id| field_by_id.get(field_id))
+    .map(|field| field.name.clone())
+    .collect::<Vec<_>>();
+
+  let mut html = String::from("<table>\n<thead><tr>");
+  for name in &field_names {
+    let _ = write!(html, "<th>{}</th>", escape_html(name));
+  }
+  html.push_str("</tr></thead>\n<tbody>\n");
+  let mut row_titles = Vec::new();
+  for row_id in row_ids.iter().take(MAX_RENDERED_ROWS) {
+    let row_detail = published_db
+      .database_row_collabs
+      .get(row_id)
+      .and_then(|doc_state| collab_from_doc_state(doc_state.clone(), row_id).ok())
+      .and_then(|collab| RowDetail::from_collab(&collab));
+    let row_detail = match row_detail {
+      Some(row_detail) => row_detail,
+      None => {
+        tracing::warn!("Published database row {} can't be read", row_id);
+        continue;
+      },
+    };
+    let cells = convert_database_cells_human_readable(
+      row_detail.row.cells,
+      &field_by_id,
+      &selection_name_by_id,
+    );
+    html.push_str("<tr>");
+    for (index, name) in field_names.iter().enumerate() {
+      let text = cells
+        .get(name)
+        .and_then(|cell| cell.get("data"))
+        .map(cell_text)
+        .unwrap_or_default();
+      if index == 0 && !text.is_empty() {
+        row_titles.push(text.clone());
+      }
+      let _ = write!(html, "<td>{}</td>", escape_html(&text));
+    }
+    html.push_str("</tr>\n");
+  }
+  html.push_str("</tbody>\n</table>\n");
+  let description = row_titles
+    .join(", ")
+    .chars()
+    .take(MAX_DESCRIPTION_CHARS)
+    .collect();
+  Ok(RenderedBody { html, description })
+}
+
+/// The readable text of the data of a cell converted by [convert_database_cells_human_readable].
+fn cell_text(value: &Value) -> String {
+  match value {
+    Value::Null => String::new(),
+    Value::String(text) => text.clone(),
+    Value::Bool(value) => value.to_string(),
+    Value::Number(value) => value.to_string(),
+    Value::Array(values) => values
+      .iter()
+      .map(cell_text)
+      .filter(|text| !text.is_empty())
+      .collect::<Vec<_>>()
+      .join(", "),
+    Value::Object(object) => {
+      // checklist
+      if let (Some(Value::Array(options)), Some(Value::Array(selected))) =
+        (object.get("options"), object.get("selected_option_ids"))
+      {
+        return format!("{}/{}", selected.len(), options.len());
+      }
+      // media and files
+      object
+        .get("name")
+        .or_else(|| object.get("url"))
+        .map(cell_text)
+        .unwrap_or_default()
+    },
+  }
+}
+
+fn is_safe_url(url: &str) -> bool {
+  url.starts_with("https://") || url.starts_with("http://")
+}
+
+fn escape_html(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use collab_document::blocks::DocumentMeta;
+  use serde_json::json;
+  use std::collections::HashMap;
+
+  fn block(id: &str, ty: &str, parent: &str, data: Value) -> Block {
+    Block {
+      id: id.to_string(),
+      ty: ty.to_string(),
+      parent: parent.to_string(),
+      children: format!("{}-children", id),
+      external_id: None,
+      external_type: None,
+      data: serde_json::from_value(data).unwrap(),
+    }
+  }
+
+  fn document(blocks: Vec<Block>) -> DocumentData {
+    let mut children_map: HashMap<String, Vec<String>> = HashMap::new();
+    for block in &blocks {
+      children_map.entry(block.children.clone()).or_default();
+      if let Some(parent) = blocks.iter().find(|b| b.id == block.parent) {
+        children_map
+          .entry(parent.children.clone())
+          .or_default()
+          .push(block.id.clone());
+      }
+    }
+    DocumentData {
+      page_id: "page".to_string(),
+      blocks: blocks.into_iter().map(|b| (b.id.clone(), b)).collect(),
+      meta: DocumentMeta {
+        children_map,
+        text_map: Some(HashMap::new()),
+      },
+    }
+  }
+
+  fn render(data: &DocumentData) -> (String, String) {
+    let mut writer = DocumentHtmlWriter::new(data);
+    writer.write_children(&data.page_id);
+    (writer.html, writer.description)
+  }
+
+  #[test]
+  fn cyclic_and_deep_blocks_are_rendered_once() {
+    let mut data = document(vec![
+      block("page", "page", "", json!({})),
+      block(
+        "a",
+        "paragraph",
+        "page",
+        json!({ "delta": [{ "insert": "a" }] }),
+      ),
+      block(
+        "b",
+        "paragraph",
+        "a",
+        json!({ "delta": [{ "insert": "b" }] }),
+      ),
+    ]);
+    // b lists its ancestors as its children.
+    data.meta.children_map.insert(
+      "b-children".to_string(),
+      vec!["a".to_string(), "page".to_string()],
+    );
+    let (html, _) = render(&data);
+    assert_eq!(html.matches("<p>a</p>").count(), 1);
+    assert_eq!(html.matches("<p>b</p>").count(), 1);
+
+    let mut blocks = vec![block("page", "page", "", json!({}))];
+    let mut parent = "page".to_string();
+    for i in 0..200 {
+      let id = format!("q{}", i);
+      blocks.push(block(&id, "quote", &parent, json!({})));
+      parent = id;
+    }
+    let (html, _) = render(&document(blocks));
+    assert_eq!(html.matches("<blockquote>").count(), MAX_BLOCK_DEPTH);
+  }
+
+  #[test]
+  fn document_blocks_are_rendered_and_escaped() {
+    let data = document(vec![
+      block("page", "page", "", json!({})),
+      block(
+        "h",
+        "heading",
+        "page",
+        json!({ "level": 1, "delta": [{ "insert": "Plan <2025>" }] }),
+      ),
+      block(
+        "p",
+        "paragraph",
+        "page",
+        json!({ "delta": [
+          { "insert": "Read " },
+          { "insert": "the docs", "attributes": { "bold": true, "href": "https://appflowy.io" } },
+          { "insert": " now", "attributes": { "href": "javascript:alert(1)" } },
+        ] }),
+      ),
+      block(
+        "b1",
+        "bulleted_list",
+        "page",
+        json!({ "delta": [{ "insert": "one" }] }),
+      ),
+      block(
+        "b2",
+        "bulleted_list",
+        "page",
+        json!({ "delta": [{ "insert": "two" }] }),
+      ),
+      block(
+        "t",
+        "todo_list",
+        "page",
+        json!({ "checked": true, "delta": [{ "insert": "done" }] }),
+      ),
+    ]);
+    let (html, description) = render(&data);
+    assert!(html.contains("<h2>Plan &lt;2025&gt;</h2>"));
+    assert!(html.contains(
+      "<a href=\"https://appflowy.io\" rel=\"noopener nofollow\"><strong>the docs</strong></a>"
+    ));
+    assert!(!html.contains("javascript:"));
+    assert!(html.contains("<ul><li>one</li><li>two</li></ul><ul class=\"todo\">"));
+    assert!(html.contains("<input type=\"checkbox\" disabled checked> done"));
+    assert!(description.starts_with("Plan <2025> Read"));
+  }
+
+  #[test]
+  fn page_has_open_graph_metadata() {
+    let view = PublishViewInfo {
+      name: "Roadmap \"Q1\"".to_string(),
+      extra: Some(r#"{"cover":{"type":"custom","value":"https://example.com/c.png"}}"#.to_string()),
+      ..Default::default()
+    };
+    let html = render_page(
+      &view,
+      Some("https://appflowy.com/ns/roadmap"),
+      RenderedBody {
+        html: "<p>content</p>".to_string(),
+        description: "The plan".to_string(),
+      },
+    );
+    assert!(html.contains("<title>Roadmap &quot;Q1&quot;</title>"));
+    assert!(html.contains("<meta property=\"og:description\" content=\"The plan\">"));
+    assert!(html.contains("<link rel=\"canonical\" href=\"https://appflowy.com/ns/roadmap\">"));
+    assert!(html.contains("<meta property=\"og:image\" content=\"https://example.com/c.png\">"));
+  }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+use std::ops::DerefMut;
+use std::sync::Arc;
+
+use access_control::workspace::WorkspaceAccessControl;
+use app_error::AppError;
+use database::role::{
+  delete_custom_role, insert_custom_role, permissions_from_names, select_custom_role,
+  select_custom_role_member_uids, select_custom_roles, select_workspace_member_role_info,
+  update_custom_role, update_custom_role_members_role, update_workspace_member_custom_role,
+};
+use database::user::select_uid_from_email;
+use database_entity::dto::{AFRole, AFWorkspacePermission};
+use shared_entity::dto::role_dto::{
+  AssignCustomRoleParams, CreateCustomRoleParams, CustomRole, UpdateCustomRoleParams,
+  WorkspacePermissions,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+fn check_base_role(base_role: &AFRole) -> Result<(), AppError> {
+  if *base_role == AFRole::Owner {
+    return Err(AppError::InvalidRequest(
+      "A custom role can't be based on the owner role".to_string(),
+    ));
+  }
+  Ok(())
+}
+
+fn dedup_permissions(permissions: &[AFWorkspacePermission]) -> Vec<AFWorkspacePermission> {
+  let mut seen = HashSet::new();
+  permissions
+    .iter()
+    .filter(|permission| seen.insert(**permission))
+    .copied()
+    .collect()
+}
+
+pub async fn list_custom_roles(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+) -> Result<Vec<CustomRole>, AppError> {
+  select_custom_roles(pg_pool, workspace_id).await
+}
+
+pub async fn create_custom_role(
+  pg_pool: &PgPool,
+  uid: i64,
+  workspace_id: &Uuid,
+  params: CreateCustomRoleParams,
+) -> Result<CustomRole, AppError> {
+  check_base_role(&params.base_role)?;
+  let name = params.name.trim();
+  insert_custom_role(
+    pg_pool,
+    workspace_id,
+    name,
+    &params.base_role,
+    &dedup_permissions(&params.permissions),
+    uid,
+  )
+  .await?
+  .ok_or_else(|| AppError::RecordAlreadyExists(format!("The role {} already exists", name)))
+}
+
+/// The policies of the members that have the role are updated with the new permissions.
+pub async fn edit_custom_role(
+  pg_pool: &PgPool,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  workspace_id: &Uuid,
+  role_id: &Uuid,
+  params: UpdateCustomRoleParams,
+) -> Result<CustomRole, AppError> {
+  if let Some(base_role) = &params.base_role {
+    check_base_role(base_role)?;
+  }
+  let permissions = params.permissions.as_deref().map(dedup_permissions);
+
+  let mut tx = pg_pool.begin().await?;
+  let role = update_custom_role(
+    tx.deref_mut(),
+    workspace_id,
+    role_id,
+    params.name.as_deref().map(str::trim),
+    params.base_role.as_ref(),
+    permissions.as_deref(),
+  )
+  .await?
+  .ok_or_else(|| AppError::RecordNotFound(format!("Role {} not found", role_id)))?;
+  if let Some(base_role) = &params.base_role {
+    update_custom_role_members_role(tx.deref_mut(), role_id, base_role).await?;
+  }
+  let member_uids = select_custom_role_member_uids(tx.deref_mut(), role_id).await?;
+  tx.commit().await?;
+
+  for member_uid in member_uids {
+    workspace_access_control
+      .insert_role_with_permissions(
+        &member_uid,
+        workspace_id,
+        role.base_role.clone(),
+        &role.permissions,
+      )
+      .await?;
+  }
+  Ok(role)
+}
+
+/// The members that had the role fall back to the default permissions of its base role.
+pub async fn remove_custom_role(
+  pg_pool: &PgPool,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  workspace_id: &Uuid,
+  role_id: &Uuid,
+) -> Result<(), AppError> {
+  let mut tx = pg_pool.begin().await?;
+  let role = select_custom_role(tx.deref_mut(), workspace_id, role_id)
+    .await?
+    .ok_or_else(|| AppError::RecordNotFound(format!("Role {} not found", role_id)))?;
+  let member_uids = select_custom_role_member_uids(tx.deref_mut(), role_id).await?;
+  delete_custom_role(tx.deref_mut(), workspace_id, role_id).await?;
+  tx.commit().await?;
+
+  for member_uid in member_uids {
+    workspace_access_control
+      .insert_role(&member_uid, workspace_id, role.base_role.clone())
+      .await?;
+  }
+  Ok(())
+}
+
+/// The role of the member becomes the base role of the custom role. The owners always have all
+/// the permissions, so they can't be given a custom role.
+pub async fn assign_custom_role(
+  pg_pool: &PgPool,
+  workspace_access_control: Arc<dyn WorkspaceAccessControl>,
+  workspace_id: &Uuid,
+  params: AssignCustomRoleParams,
+) -> Result<(), AppError> {
+  let member_uid = select_uid_from_email(pg_pool, &params.email).await?;
+  let member = select_workspace_member_role_info(pg_pool, workspace_id, member_uid)
+    .await?
+    .ok_or_else(|| {
+      AppError::RecordNotFound(format!("{} is not a member of the workspace", params.email))
+    })?;
+  if AFRole::from(member.role_id) == AFRole::Owner {
+    return Err(AppError::InvalidRequest(
+      "The owner of the workspace can't be given a custom role".to_string(),
+    ));
+  }
+
+  match params.role_id {
+    Some(role_id) => {
+      let role = select_custom_role(pg_pool, workspace_id, &role_id)
+        .await?
+        .ok_or_else(|| AppError::RecordNotFound(format!("Role {} not found", role_id)))?;
+      update_workspace_member_custom_role(
+        pg_pool,
+        workspace_id,
+        member_uid,
+        Some(&role_id),
+        Some(&role.base_role),
+      )
+      .await?;
+      workspace_access_control
+        .insert_role_with_permissions(&member_uid, workspace_id, role.base_role, &role.permissions)
+        .await?;
+    },
+    None => {
+      update_workspace_member_custom_role(pg_pool, workspace_id, member_uid, None, None).await?;
+      workspace_access_control
+        .insert_role(&member_uid, workspace_id, AFRole::from(member.role_id))
+        .await?;
+    },
+  }
+  Ok(())
+}
+
+pub async fn get_workspace_permissions(
+  pg_pool: &PgPool,
+  workspace_id: &Uuid,
+  uid: i64,
+) -> Result<WorkspacePermissions, AppError> {
+  let member = select_workspace_member_role_info(pg_pool, workspace_id, uid)
+    .await?
+    .ok_or_else(|| AppError::NotEnoughPermissions {
+      user: uid.to_string(),
+      workspace_id: workspace_id.to_string(),
+    })?;
+  let role = AFRole::from(member.role_id);
+  let permissions = match &member.custom_permissions {
+    Some(names) => permissions_from_names(names),
+    None => role.default_permissions(),
+  };
+  Ok(WorkspacePermissions {
+    role,
+    custom_role_id: member.custom_role_id,
+    permissions,
+  })
+}
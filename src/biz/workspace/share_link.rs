@@ -0,0 +1,230 @@
+use app_error::AppError;
+use appflowy_collaborate::collab::storage::CollabAccessControlStorage;
+use authentication::api_token::hash_api_token;
+use authentication::password::{compute_hash_password, verify_password_hash};
+use authentication::share_link::{generate_share_link_token, share_link_display_prefix};
+use chrono::Utc;
+use collab_folder::ViewLayout;
+use database::collab::GetCollabOrigin;
+use database::share_link::{
+  delete_share_link, insert_share_link, select_share_link_by_hash, select_share_links,
+  upsert_share_link_user, AFShareLinkTokenRow,
+};
+use database_entity::dto::AFAccessLevel;
+use secrecy::{ExposeSecret, Secret};
+use shared_entity::dto::share_link_dto::{
+  CreateShareLinkParams, CreatedShareLink, RepeatedShareLink, ShareLinkAccess, SharedPage,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::biz::collab::ops::{get_latest_collab_folder, get_latest_workspace_database};
+use crate::state::RedisConnectionManager;
+
+use super::page_view::get_page_view_collab;
+use super::password_attempt::{forget_password_attempt, record_password_attempt};
+use super::view_permission::enforce_full_view_access;
+
+/// Creates a link granting the access level on the page to anyone holding it. Requires full
+/// access to the page.
+pub async fn create_share_link(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  params: CreateShareLinkParams,
+) -> Result<CreatedShareLink, AppError> {
+  if params.access_level == AFAccessLevel::FullAccess {
+    return Err(AppError::InvalidRequest(
+      "Full access can't be shared through a link".to_string(),
+    ));
+  }
+  if let Some(expires_at) = params.expires_at {
+    if expires_at <= Utc::now() {
+      return Err(AppError::InvalidRequest(
+        "The expiration date of the link must be in the future".to_string(),
+      ));
+    }
+  }
+  enforce_full_view_access(pg_pool, collab_storage, uid, workspace_id, view_id).await?;
+
+  let password_hash = match params.password {
+    Some(password) if password.is_empty() => {
+      return Err(AppError::InvalidRequest(
+        "The password of the link can't be empty".to_string(),
+      ));
+    },
+    Some(password) => Some(
+      tokio::task::spawn_blocking(move || compute_hash_password(password.as_bytes()))
+        .await??
+        .expose_secret()
+        .clone(),
+    ),
+    None => None,
+  };
+  let database_id =
+    get_view_database_id(pg_pool, collab_storage, uid, workspace_id, view_id).await?;
+  let token = generate_share_link_token();
+  let share_link = insert_share_link(
+    pg_pool,
+    workspace_id,
+    view_id,
+    database_id.as_ref(),
+    uid,
+    params.access_level,
+    &hash_api_token(&token),
+    &share_link_display_prefix(&token),
+    password_hash.as_deref(),
+    params.expires_at,
+  )
+  .await?;
+  Ok(CreatedShareLink { token, share_link })
+}
+
+/// Returns the id of the database of the view if it's a database view, as the realtime server
+/// opens the database collab rather than the view.
+async fn get_view_database_id(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+) -> Result<Option<Uuid>, AppError> {
+  let collab_origin = GetCollabOrigin::User { uid };
+  let folder = get_latest_collab_folder(
+    collab_storage,
+    collab_origin.clone(),
+    &workspace_id.to_string(),
+  )
+  .await?;
+  let view_id = view_id.to_string();
+  let is_database_view = folder.get_view(&view_id).is_some_and(|view| {
+    matches!(
+      view.layout,
+      ViewLayout::Grid | ViewLayout::Board | ViewLayout::Calendar
+    )
+  });
+  if !is_database_view {
+    return Ok(None);
+  }
+  let (_, workspace_database) =
+    get_latest_workspace_database(collab_storage, pg_pool, collab_origin, *workspace_id).await?;
+  let database_id = workspace_database
+    .get_database_meta_with_view_id(&view_id)
+    .ok_or_else(|| AppError::NoRequiredData(format!("Database view {} not found", view_id)))?
+    .database_id;
+  Uuid::parse_str(&database_id)
+    .map(Some)
+    .map_err(|err| AppError::Internal(err.into()))
+}
+
+/// Requires full access to the page.
+pub async fn list_share_links(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+) -> Result<RepeatedShareLink, AppError> {
+  enforce_full_view_access(pg_pool, collab_storage, uid, workspace_id, view_id).await?;
+  Ok(RepeatedShareLink {
+    items: select_share_links(pg_pool, workspace_id, view_id).await?,
+  })
+}
+
+/// Revoking a link also revokes the access of the users that joined it. Requires full access to
+/// the page.
+pub async fn revoke_share_link(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  link_id: &Uuid,
+) -> Result<(), AppError> {
+  enforce_full_view_access(pg_pool, collab_storage, uid, workspace_id, view_id).await?;
+  if !delete_share_link(pg_pool, workspace_id, view_id, link_id).await? {
+    return Err(AppError::RecordNotFound(format!(
+      "Share link {} not found",
+      link_id
+    )));
+  }
+  Ok(())
+}
+
+/// Returns the link of the token, after checking its password if it has one. The wrong passwords
+/// are counted per link and per client, see [record_password_attempt].
+async fn verify_share_link(
+  pg_pool: &PgPool,
+  redis_client: &RedisConnectionManager,
+  token: &str,
+  password: Option<String>,
+  client_ip: Option<&str>,
+) -> Result<AFShareLinkTokenRow, AppError> {
+  let link = select_share_link_by_hash(pg_pool, &hash_api_token(token))
+    .await?
+    .ok_or_else(|| AppError::RecordNotFound("The share link is invalid or expired".to_string()))?;
+  if let Some(password_hash) = link.password_hash.clone() {
+    let password = password
+      .ok_or_else(|| AppError::InvalidPassword("The share link requires a password".to_string()))?;
+    let resource = format!("share_link:{}", link.link_id);
+    record_password_attempt(redis_client, &resource, client_ip).await?;
+    tokio::task::spawn_blocking(move || {
+      verify_password_hash(Secret::new(password_hash), Secret::new(password))
+    })
+    .await?
+    .map_err(|_| AppError::InvalidPassword("Invalid share link password".to_string()))?;
+    forget_password_attempt(redis_client, &resource, client_ip).await?;
+  }
+  Ok(link)
+}
+
+fn share_link_access(link: &AFShareLinkTokenRow) -> ShareLinkAccess {
+  ShareLinkAccess {
+    workspace_id: link.workspace_id,
+    view_id: link.view_id,
+    access_level: AFAccessLevel::from(link.access_level),
+    expires_at: link.expires_at,
+  }
+}
+
+/// Returns the page of the link to anyone holding it. The page is read on behalf of the creator
+/// of the link.
+pub async fn get_shared_page(
+  pg_pool: &PgPool,
+  redis_client: &RedisConnectionManager,
+  collab_storage: &CollabAccessControlStorage,
+  token: &str,
+  password: Option<String>,
+  client_ip: Option<&str>,
+) -> Result<SharedPage, AppError> {
+  let link = verify_share_link(pg_pool, redis_client, token, password, client_ip).await?;
+  let page = get_page_view_collab(
+    pg_pool,
+    collab_storage,
+    link.created_by,
+    link.workspace_id,
+    &link.view_id.to_string(),
+  )
+  .await?;
+  Ok(SharedPage {
+    access: share_link_access(&link),
+    page,
+  })
+}
+
+/// Gives the user the access level of the link on the page, so it can open the page with the
+/// realtime server, until the link expires or is revoked.
+pub async fn join_share_link(
+  pg_pool: &PgPool,
+  redis_client: &RedisConnectionManager,
+  uid: i64,
+  token: &str,
+  password: Option<String>,
+  client_ip: Option<&str>,
+) -> Result<ShareLinkAccess, AppError> {
+  let link = verify_share_link(pg_pool, redis_client, token, password, client_ip).await?;
+  upsert_share_link_user(pg_pool, &link.link_id, uid).await?;
+  Ok(share_link_access(&link))
+}
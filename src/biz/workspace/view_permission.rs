@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use access_control::collab::CollabAccessControl;
+use app_error::AppError;
+use appflowy_collaborate::collab::storage::CollabAccessControlStorage;
+use appflowy_collaborate::collab::view_permission::{
+  folder_parent_of, parse_view_id, refresh_effective_view_permissions,
+  refresh_grantees_view_permissions, resolve_granted_access_level,
+};
+use collab_folder::Folder;
+use database::collab::GetCollabOrigin;
+use database::role::select_workspace_member_role_info;
+use database::user::select_uid_from_email;
+use database::view_permission::{
+  delete_view_group_permission, delete_view_permission, select_user_view_grants,
+  select_view_group_permissions, select_view_permission_grantees, select_view_permissions,
+  upsert_view_group_permission, upsert_view_permission,
+};
+use database_entity::dto::{AFAccessLevel, AFRole};
+use shared_entity::dto::view_permission_dto::{
+  EffectiveViewPermission, GrantViewGroupPermissionParams, GrantViewPermissionParams,
+  RepeatedViewPermission,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::group::get_workspace_group;
+use crate::biz::collab::ops::get_latest_collab_folder;
+
+async fn get_folder(
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+) -> Result<Folder, AppError> {
+  get_latest_collab_folder(
+    collab_storage,
+    GetCollabOrigin::User { uid },
+    &workspace_id.to_string(),
+  )
+  .await
+}
+
+/// Recomputes the access levels of all the members that were granted one in the workspace. Called
+/// when pages are added, so they inherit the access levels of the spaces and pages above them.
+pub async fn refresh_workspace_view_permissions(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  uid: i64,
+  workspace_id: &Uuid,
+) -> Result<(), AppError> {
+  let grantees = select_view_permission_grantees(pg_pool, workspace_id).await?;
+  if grantees.is_empty() {
+    return Ok(());
+  }
+  let folder = get_folder(collab_storage, uid, workspace_id).await?;
+  refresh_grantees_view_permissions(
+    pg_pool,
+    collab_access_control,
+    &folder,
+    workspace_id,
+    grantees,
+  )
+  .await
+}
+
+async fn compute_effective_view_permission(
+  pg_pool: &PgPool,
+  folder: &Folder,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  uid: i64,
+) -> Result<EffectiveViewPermission, AppError> {
+  let member = select_workspace_member_role_info(pg_pool, workspace_id, uid)
+    .await?
+    .ok_or_else(|| AppError::NotEnoughPermissions {
+      user: uid.to_string(),
+      workspace_id: workspace_id.to_string(),
+    })?;
+  let role = AFRole::from(member.role_id);
+  let grants: HashMap<String, AFAccessLevel> = select_user_view_grants(pg_pool, workspace_id, uid)
+    .await?
+    .into_iter()
+    .map(|(view_id, level)| (view_id.to_string(), level))
+    .collect();
+  let granted =
+    resolve_granted_access_level(&view_id.to_string(), &grants, folder_parent_of(folder));
+
+  // A grant replaces the access level of the role, except for the owners who keep full access
+  let access_level = match &granted {
+    Some((_, level)) if role != AFRole::Owner => *level,
+    _ => AFAccessLevel::from(&role),
+  };
+  Ok(EffectiveViewPermission {
+    view_id: *view_id,
+    uid,
+    role,
+    access_level,
+    granted_access_level: granted.as_ref().map(|(_, level)| *level),
+    granted_on: granted.and_then(|(source, _)| parse_view_id(&source)),
+  })
+}
+
+/// Returns what the member can do on the page. Only the members with full access to the page can
+/// see the access level of another member.
+pub async fn get_effective_view_permission(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  target_uid: Option<i64>,
+) -> Result<EffectiveViewPermission, AppError> {
+  let folder = get_folder(collab_storage, uid, workspace_id).await?;
+  if folder.get_view(&view_id.to_string()).is_none() {
+    return Err(AppError::RecordNotFound(format!(
+      "View {} not found",
+      view_id
+    )));
+  }
+  let permission =
+    compute_effective_view_permission(pg_pool, &folder, workspace_id, view_id, uid).await?;
+  match target_uid {
+    Some(target_uid) if target_uid != uid => {
+      check_full_access(&permission, workspace_id)?;
+      compute_effective_view_permission(pg_pool, &folder, workspace_id, view_id, target_uid).await
+    },
+    _ => Ok(permission),
+  }
+}
+
+fn check_full_access(
+  permission: &EffectiveViewPermission,
+  workspace_id: &Uuid,
+) -> Result<(), AppError> {
+  if permission.access_level < AFAccessLevel::FullAccess {
+    return Err(AppError::NotEnoughPermissions {
+      user: permission.uid.to_string(),
+      workspace_id: workspace_id.to_string(),
+    });
+  }
+  Ok(())
+}
+
+/// Returns an error unless the member has full access to the page.
+pub async fn enforce_full_view_access(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+) -> Result<(), AppError> {
+  let folder = get_folder(collab_storage, uid, workspace_id).await?;
+  if folder.get_view(&view_id.to_string()).is_none() {
+    return Err(AppError::RecordNotFound(format!(
+      "View {} not found",
+      view_id
+    )));
+  }
+  let permission =
+    compute_effective_view_permission(pg_pool, &folder, workspace_id, view_id, uid).await?;
+  check_full_access(&permission, workspace_id)
+}
+
+/// Returns the grants made to members and groups on the page itself. Requires full access to the
+/// page.
+pub async fn list_view_permissions(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+) -> Result<RepeatedViewPermission, AppError> {
+  let folder = get_folder(collab_storage, uid, workspace_id).await?;
+  let permission =
+    compute_effective_view_permission(pg_pool, &folder, workspace_id, view_id, uid).await?;
+  check_full_access(&permission, workspace_id)?;
+  Ok(RepeatedViewPermission {
+    items: select_view_permissions(pg_pool, workspace_id, view_id).await?,
+    groups: select_view_group_permissions(pg_pool, workspace_id, view_id).await?,
+  })
+}
+
+/// Grants an access level on the space or page to a member, which applies to the pages below
+/// it. Requires full access to the page.
+pub async fn grant_view_permission(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  params: GrantViewPermissionParams,
+) -> Result<(), AppError> {
+  let folder = get_folder(collab_storage, uid, workspace_id).await?;
+  if folder.get_view(&view_id.to_string()).is_none() {
+    return Err(AppError::RecordNotFound(format!(
+      "View {} not found",
+      view_id
+    )));
+  }
+  let permission =
+    compute_effective_view_permission(pg_pool, &folder, workspace_id, view_id, uid).await?;
+  check_full_access(&permission, workspace_id)?;
+
+  let grantee = select_uid_from_email(pg_pool, &params.email).await?;
+  if select_workspace_member_role_info(pg_pool, workspace_id, grantee)
+    .await?
+    .is_none()
+  {
+    return Err(AppError::InvalidRequest(format!(
+      "{} is not a member of the workspace",
+      params.email
+    )));
+  }
+  upsert_view_permission(
+    pg_pool,
+    workspace_id,
+    view_id,
+    grantee,
+    params.access_level,
+    uid,
+  )
+  .await?;
+  refresh_effective_view_permissions(
+    pg_pool,
+    collab_access_control,
+    &folder,
+    workspace_id,
+    grantee,
+  )
+  .await
+}
+
+/// Removes the grant of the member on the page. The member gets the access level inherited from
+/// the spaces and pages above it again. Requires full access to the page.
+pub async fn revoke_view_permission(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  grantee: i64,
+) -> Result<(), AppError> {
+  let folder = get_folder(collab_storage, uid, workspace_id).await?;
+  let permission =
+    compute_effective_view_permission(pg_pool, &folder, workspace_id, view_id, uid).await?;
+  check_full_access(&permission, workspace_id)?;
+  if !delete_view_permission(pg_pool, workspace_id, view_id, grantee).await? {
+    return Err(AppError::RecordNotFound(format!(
+      "The user {} has no permission on view {}",
+      grantee, view_id
+    )));
+  }
+  refresh_effective_view_permissions(
+    pg_pool,
+    collab_access_control,
+    &folder,
+    workspace_id,
+    grantee,
+  )
+  .await
+}
+
+/// Grants an access level on the space or page to the members of a group. Requires full access to
+/// the page.
+pub async fn grant_view_group_permission(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  params: GrantViewGroupPermissionParams,
+) -> Result<(), AppError> {
+  let folder = get_folder(collab_storage, uid, workspace_id).await?;
+  if folder.get_view(&view_id.to_string()).is_none() {
+    return Err(AppError::RecordNotFound(format!(
+      "View {} not found",
+      view_id
+    )));
+  }
+  let permission =
+    compute_effective_view_permission(pg_pool, &folder, workspace_id, view_id, uid).await?;
+  check_full_access(&permission, workspace_id)?;
+
+  get_workspace_group(pg_pool, workspace_id, &params.group_id).await?;
+  upsert_view_group_permission(
+    pg_pool,
+    workspace_id,
+    view_id,
+    &params.group_id,
+    params.access_level,
+    uid,
+  )
+  .await?;
+  let grantees = select_view_permission_grantees(pg_pool, workspace_id).await?;
+  refresh_grantees_view_permissions(
+    pg_pool,
+    collab_access_control,
+    &folder,
+    workspace_id,
+    grantees,
+  )
+  .await
+}
+
+/// Removes the grant of the group on the page. Requires full access to the page.
+pub async fn revoke_view_group_permission(
+  pg_pool: &PgPool,
+  collab_storage: &CollabAccessControlStorage,
+  collab_access_control: &Arc<dyn CollabAccessControl>,
+  uid: i64,
+  workspace_id: &Uuid,
+  view_id: &Uuid,
+  group_id: &Uuid,
+) -> Result<(), AppError> {
+  let folder = get_folder(collab_storage, uid, workspace_id).await?;
+  let permission =
+    compute_effective_view_permission(pg_pool, &folder, workspace_id, view_id, uid).await?;
+  check_full_access(&permission, workspace_id)?;
+  if !delete_view_group_permission(pg_pool, workspace_id, view_id, group_id).await? {
+    return Err(AppError::RecordNotFound(format!(
+      "The group {} has no permission on view {}",
+      group_id, view_id
+    )));
+  }
+  let grantees = select_view_permission_grantees(pg_pool, workspace_id).await?;
+  refresh_grantees_view_permissions(
+    pg_pool,
+    collab_access_control,
+    &folder,
+    workspace_id,
+    grantees,
+  )
+  .await
+}
@@ -1,4 +1,5 @@
 use std::fmt::Display;
+use std::net::IpAddr;
 use std::str::FromStr;
 
 use anyhow::Context;
@@ -99,6 +100,9 @@ pub struct ApplicationSetting {
   pub host: String,
   pub server_key: Secret<String>,
   pub use_tls: bool,
+  /// The reverse proxies allowed to set the address of the client in the `Forwarded` and
+  /// `X-Forwarded-For` headers.
+  pub trusted_proxies: Vec<IpAddr>,
 }
 
 #[derive(Clone, Debug)]
@@ -146,6 +150,14 @@ pub struct CollabSetting {
   pub edit_state_max_count: u32,
   pub edit_state_max_secs: i64,
   pub s3_collab_threshold: u64,
+  /// Minimum delay between two updates of the blob of a live published document.
+  pub live_publish_debounce_secs: u64,
+  /// Minimum delay between two checks of the members mentioned in a document.
+  pub mention_notify_debounce_secs: u64,
+  /// Minimum delay between two updates of the reminders of a document or a database row.
+  pub reminder_sync_debounce_secs: u64,
+  /// Minimum delay between two refreshes of the page access levels of a workspace.
+  pub view_permission_refresh_debounce_secs: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -217,6 +229,13 @@ pub fn get_configuration() -> Result<Config, anyhow::Error> {
         .parse()
         .context("fail to get APPFLOWY_APPLICATION_USE_TLS")?,
       server_key: get_env_var("APPFLOWY_APPLICATION_SERVER_KEY", "server_key").into(),
+      trusted_proxies: get_env_var("APPFLOWY_APPLICATION_TRUSTED_PROXIES", "")
+        .split(',')
+        .map(str::trim)
+        .filter(|addr| !addr.is_empty())
+        .map(IpAddr::from_str)
+        .collect::<Result<_, _>>()
+        .context("fail to get APPFLOWY_APPLICATION_TRUSTED_PROXIES")?,
     },
     websocket: WebsocketSetting {
       heartbeat_interval: get_env_var("APPFLOWY_WEBSOCKET_HEARTBEAT_INTERVAL", "6").parse()?,
@@ -253,6 +272,20 @@ pub fn get_configuration() -> Result<Config, anyhow::Error> {
       edit_state_max_count: get_env_var("APPFLOWY_COLLAB_EDIT_STATE_MAX_COUNT", "100").parse()?,
       edit_state_max_secs: get_env_var("APPFLOWY_COLLAB_EDIT_STATE_MAX_SECS", "60").parse()?,
       s3_collab_threshold: get_env_var("APPFLOWY_COLLAB_S3_THRESHOLD", "8000").parse()?,
+      live_publish_debounce_secs: get_env_var("APPFLOWY_COLLAB_LIVE_PUBLISH_DEBOUNCE_SECS", "10")
+        .parse()?,
+      mention_notify_debounce_secs: get_env_var(
+        "APPFLOWY_COLLAB_MENTION_NOTIFY_DEBOUNCE_SECS",
+        "10",
+      )
+      .parse()?,
+      reminder_sync_debounce_secs: get_env_var("APPFLOWY_COLLAB_REMINDER_SYNC_DEBOUNCE_SECS", "10")
+        .parse()?,
+      view_permission_refresh_debounce_secs: get_env_var(
+        "APPFLOWY_COLLAB_VIEW_PERMISSION_REFRESH_DEBOUNCE_SECS",
+        "10",
+      )
+      .parse()?,
     },
     published_collab: PublishedCollabSetting {
       storage_backend: get_env_var("APPFLOWY_PUBLISHED_COLLAB_STORAGE_BACKEND", "postgres")
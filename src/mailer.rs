@@ -5,6 +5,8 @@ pub const WORKSPACE_INVITE_TEMPLATE_NAME: &str = "workspace_invite";
 pub const WORKSPACE_ACCESS_REQUEST_TEMPLATE_NAME: &str = "workspace_access_request";
 pub const WORKSPACE_ACCESS_REQUEST_APPROVED_NOTIFICATION_TEMPLATE_NAME: &str =
   "workspace_access_request_approved_notification";
+pub const WORKSPACE_ACCESS_REQUEST_REJECTED_NOTIFICATION_TEMPLATE_NAME: &str =
+  "workspace_access_request_rejected_notification";
 
 #[derive(Clone)]
 pub struct AFCloudMailer(Mailer);
@@ -75,6 +77,25 @@ impl AFCloudMailer {
       )
       .await
   }
+
+  pub async fn send_workspace_access_request_rejection_notification(
+    &self,
+    recipient_name: &str,
+    email: &str,
+    param: WorkspaceAccessRequestRejectedMailerParam,
+  ) -> Result<(), anyhow::Error> {
+    let subject = "Notification: Workspace access request declined";
+    self
+      .0
+      .send_email_template(
+        Some(recipient_name.to_string()),
+        email,
+        WORKSPACE_ACCESS_REQUEST_REJECTED_NOTIFICATION_TEMPLATE_NAME,
+        param,
+        subject,
+      )
+      .await
+  }
 }
 
 async fn register_mailer(mailer: &mut Mailer) -> Result<(), anyhow::Error> {
@@ -85,6 +106,9 @@ async fn register_mailer(mailer: &mut Mailer) -> Result<(), anyhow::Error> {
   let access_request_approved_notification_template = include_str!(
     "../assets/mailer_templates/build_production/access_request_approved_notification.html"
   );
+  let access_request_rejected_notification_template = include_str!(
+    "../assets/mailer_templates/build_production/access_request_rejected_notification.html"
+  );
   let template_strings = HashMap::from([
     (WORKSPACE_INVITE_TEMPLATE_NAME, workspace_invite_template),
     (
@@ -95,6 +119,10 @@ async fn register_mailer(mailer: &mut Mailer) -> Result<(), anyhow::Error> {
       WORKSPACE_ACCESS_REQUEST_APPROVED_NOTIFICATION_TEMPLATE_NAME,
       access_request_approved_notification_template,
     ),
+    (
+      WORKSPACE_ACCESS_REQUEST_REJECTED_NOTIFICATION_TEMPLATE_NAME,
+      access_request_rejected_notification_template,
+    ),
   ]);
 
   for (template_name, template_string) in template_strings {
@@ -125,6 +153,7 @@ pub struct WorkspaceAccessRequestMailerParam {
   pub workspace_icon_url: String,
   pub workspace_member_count: i64,
   pub approve_url: String,
+  pub reason: Option<String>,
 }
 
 #[derive(serde::Serialize)]
@@ -134,3 +163,11 @@ pub struct WorkspaceAccessRequestApprovedMailerParam {
   pub workspace_member_count: i64,
   pub launch_workspace_url: String,
 }
+
+#[derive(serde::Serialize)]
+pub struct WorkspaceAccessRequestRejectedMailerParam {
+  pub workspace_name: String,
+  pub workspace_icon_url: String,
+  pub workspace_member_count: i64,
+  pub reason: Option<String>,
+}
@@ -0,0 +1,718 @@
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use actix_http::Method;
+use actix_service::{forward_ready, Service, Transform};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::web::Data;
+use actix_web::HttpMessage;
+use app_error::AppError;
+use authentication::api_token::{hash_api_token, is_api_token, ApiTokenIdentity};
+use database::api_token::select_api_token_by_hash;
+use futures_util::future::LocalBoxFuture;
+use shared_entity::dto::api_token_dto::ApiTokenScope;
+use shared_entity::dto::api_token_dto::ApiTokenScope::{Admin, Read, Write};
+use tracing::trace;
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+/// The routes API tokens can call, and the scope the token needs to call them. In the paths,
+/// `{workspace_id}` must be the workspace of the token and `*` matches any segment. The scope of
+/// each route is declared rather than inferred from its method: some `GET` routes call the AI
+/// service. The routes that are not listed, such as the management of the API tokens, are denied.
+static API_TOKEN_ROUTES: &[(Method, &str, ApiTokenScope)] = &[
+  (Method::GET, "/api/user/profile", Read),
+  (Method::DELETE, "/api/workspace/{workspace_id}", Admin),
+  (Method::POST, "/api/workspace/{workspace_id}/invite", Admin),
+  (Method::GET, "/api/workspace/{workspace_id}/settings", Read),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/settings",
+    Admin,
+  ),
+  (Method::GET, "/api/workspace/{workspace_id}/member", Read),
+  (Method::PUT, "/api/workspace/{workspace_id}/member", Admin),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/member",
+    Admin,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/member/user/*",
+    Read,
+  ),
+  (
+    Method::PUT,
+    "/api/workspace/{workspace_id}/member/custom-role",
+    Admin,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/permission",
+    Read,
+  ),
+  (Method::GET, "/api/workspace/{workspace_id}/role", Admin),
+  (Method::POST, "/api/workspace/{workspace_id}/role", Admin),
+  (Method::PATCH, "/api/workspace/{workspace_id}/role/*", Admin),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/role/*",
+    Admin,
+  ),
+  (Method::GET, "/api/workspace/{workspace_id}/group", Read),
+  (Method::POST, "/api/workspace/{workspace_id}/group", Admin),
+  (
+    Method::PATCH,
+    "/api/workspace/{workspace_id}/group/*",
+    Admin,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/group/*",
+    Admin,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/group/*/member",
+    Read,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/group/*/member",
+    Admin,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/group/*/member",
+    Admin,
+  ),
+  (Method::GET, "/api/workspace/{workspace_id}/webhook", Admin),
+  (Method::POST, "/api/workspace/{workspace_id}/webhook", Admin),
+  (
+    Method::PATCH,
+    "/api/workspace/{workspace_id}/webhook/*",
+    Admin,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/webhook/*",
+    Admin,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/webhook/*/delivery",
+    Admin,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/audit-log",
+    Admin,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/audit-log/export",
+    Admin,
+  ),
+  (Method::GET, "/api/workspace/{workspace_id}/activity", Read),
+  (Method::GET, "/api/workspace/{workspace_id}/usage", Read),
+  (Method::GET, "/api/workspace/{workspace_id}/collab/*", Read),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/collab/*",
+    Write,
+  ),
+  (Method::PUT, "/api/workspace/{workspace_id}/collab/*", Write),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/collab/*",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/v1/{workspace_id}/collab/*",
+    Read,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/v1/{workspace_id}/collab/*/web-update",
+    Write,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/batch/collab",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/collab_list",
+    Read,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/collab_list",
+    Read,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/*/snapshot",
+    Read,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/*/snapshot",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/*/snapshot/list",
+    Read,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/collab/*/member",
+    Read,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/collab/*/member",
+    Write,
+  ),
+  (
+    Method::PUT,
+    "/api/workspace/{workspace_id}/collab/*/member",
+    Write,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/collab/*/member",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/collab/*/member/list",
+    Read,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/collab/*/group-member",
+    Read,
+  ),
+  (
+    Method::PUT,
+    "/api/workspace/{workspace_id}/collab/*/group-member",
+    Write,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/collab/*/group-member/*",
+    Write,
+  ),
+  (Method::GET, "/api/workspace/{workspace_id}/folder", Read),
+  (Method::GET, "/api/workspace/{workspace_id}/recent", Read),
+  (Method::GET, "/api/workspace/{workspace_id}/favorite", Read),
+  (Method::GET, "/api/workspace/{workspace_id}/trash", Read),
+  (Method::POST, "/api/workspace/{workspace_id}/space", Write),
+  (
+    Method::PATCH,
+    "/api/workspace/{workspace_id}/space/*",
+    Write,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/page-view",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/page-view/*",
+    Read,
+  ),
+  (
+    Method::PATCH,
+    "/api/workspace/{workspace_id}/page-view/*",
+    Write,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/page-view/*/move-to-trash",
+    Write,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/page-view/*/restore-from-trash",
+    Write,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/restore-all-pages-from-trash",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/page-view/*/permission",
+    Read,
+  ),
+  (
+    Method::PUT,
+    "/api/workspace/{workspace_id}/page-view/*/permission",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/page-view/*/permission/effective",
+    Read,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/page-view/*/permission/*",
+    Write,
+  ),
+  (
+    Method::PUT,
+    "/api/workspace/{workspace_id}/page-view/*/group-permission",
+    Write,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/page-view/*/group-permission/*",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/page-view/*/share-link",
+    Read,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/page-view/*/share-link",
+    Write,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/page-view/*/share-link/*",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/page-view/*/comment-thread",
+    Read,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/page-view/*/comment-thread",
+    Write,
+  ),
+  (
+    Method::PATCH,
+    "/api/workspace/{workspace_id}/page-view/*/comment-thread/*",
+    Write,
+  ),
+  (
+    Method::POST,
+    "/api/workspace/{workspace_id}/page-view/*/comment-thread/*/comment",
+    Write,
+  ),
+  (
+    Method::PATCH,
+    "/api/workspace/{workspace_id}/page-view/*/comment-thread/*/comment/*",
+    Write,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/page-view/*/comment-thread/*/comment/*",
+    Write,
+  ),
+  (Method::GET, "/api/workspace/{workspace_id}/database", Read),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/database/*/row",
+    Read,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/database/*/fields",
+    Read,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/database/*/row/updated",
+    Read,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/database/*/row/detail",
+    Read,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/published-info",
+    Read,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/published-info/analytics",
+    Read,
+  ),
+  (Method::POST, "/api/workspace/{workspace_id}/publish", Write),
+  (
+    Method::PATCH,
+    "/api/workspace/{workspace_id}/publish",
+    Write,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/publish",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/publish-namespace",
+    Read,
+  ),
+  (
+    Method::PUT,
+    "/api/workspace/{workspace_id}/publish-namespace",
+    Admin,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/publish-default",
+    Read,
+  ),
+  (
+    Method::PUT,
+    "/api/workspace/{workspace_id}/publish-default",
+    Admin,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/publish-default",
+    Admin,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/published-comment-moderation",
+    Read,
+  ),
+  (
+    Method::PUT,
+    "/api/workspace/{workspace_id}/published-comment-moderation",
+    Admin,
+  ),
+  (
+    Method::GET,
+    "/api/workspace/{workspace_id}/published-comment-ban",
+    Admin,
+  ),
+  (
+    Method::PUT,
+    "/api/workspace/{workspace_id}/published-comment-ban/*",
+    Admin,
+  ),
+  (
+    Method::DELETE,
+    "/api/workspace/{workspace_id}/published-comment-ban/*",
+    Admin,
+  ),
+  (Method::GET, "/api/file_storage/{workspace_id}/blob/*", Read),
+  (
+    Method::PUT,
+    "/api/file_storage/{workspace_id}/blob/*",
+    Write,
+  ),
+  (
+    Method::DELETE,
+    "/api/file_storage/{workspace_id}/blob/*",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/file_storage/{workspace_id}/metadata/*",
+    Read,
+  ),
+  (Method::GET, "/api/file_storage/{workspace_id}/usage", Read),
+  (Method::GET, "/api/file_storage/{workspace_id}/blobs", Read),
+  (
+    Method::POST,
+    "/api/file_storage/{workspace_id}/create_upload",
+    Write,
+  ),
+  (
+    Method::PUT,
+    "/api/file_storage/{workspace_id}/upload_part/*/*/*/*",
+    Write,
+  ),
+  (
+    Method::PUT,
+    "/api/file_storage/{workspace_id}/complete_upload",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/file_storage/{workspace_id}/v1/blob/*/*",
+    Read,
+  ),
+  (
+    Method::DELETE,
+    "/api/file_storage/{workspace_id}/v1/blob/*/*",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/file_storage/{workspace_id}/v1/metadata/*/*",
+    Read,
+  ),
+  (
+    Method::PUT,
+    "/api/file_storage/{workspace_id}/v1/blob/*",
+    Write,
+  ),
+  (Method::GET, "/api/history/{workspace_id}/*/*", Read),
+  (Method::GET, "/api/history/{workspace_id}/*/*/latest", Read),
+  (Method::GET, "/api/chat/{workspace_id}", Read),
+  (Method::POST, "/api/chat/{workspace_id}", Write),
+  (Method::GET, "/api/chat/{workspace_id}/search", Read),
+  (Method::GET, "/api/chat/{workspace_id}/*", Read),
+  (Method::DELETE, "/api/chat/{workspace_id}/*", Write),
+  (Method::GET, "/api/chat/{workspace_id}/*/participant", Read),
+  (
+    Method::POST,
+    "/api/chat/{workspace_id}/*/participant",
+    Write,
+  ),
+  (
+    Method::DELETE,
+    "/api/chat/{workspace_id}/*/participant",
+    Write,
+  ),
+  (Method::GET, "/api/chat/{workspace_id}/*/settings", Read),
+  (Method::POST, "/api/chat/{workspace_id}/*/settings", Write),
+  (Method::GET, "/api/chat/{workspace_id}/*/branch", Read),
+  (
+    Method::PUT,
+    "/api/chat/{workspace_id}/*/branch/active",
+    Write,
+  ),
+  (Method::GET, "/api/chat/{workspace_id}/*/export", Read),
+  (Method::GET, "/api/chat/{workspace_id}/*/message", Read),
+  (Method::PUT, "/api/chat/{workspace_id}/*/message", Write),
+  (
+    Method::POST,
+    "/api/chat/{workspace_id}/*/message/question",
+    Write,
+  ),
+  (
+    Method::POST,
+    "/api/chat/{workspace_id}/*/v2/message/question",
+    Write,
+  ),
+  (
+    Method::POST,
+    "/api/chat/{workspace_id}/*/message/answer",
+    Write,
+  ),
+  // Generating an answer calls the AI service, even though it is a `GET`.
+  (Method::GET, "/api/chat/{workspace_id}/*/*/answer", Write),
+  (
+    Method::GET,
+    "/api/chat/{workspace_id}/*/*/answer/versions",
+    Read,
+  ),
+  (
+    Method::PUT,
+    "/api/chat/{workspace_id}/*/*/answer/versions",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/chat/{workspace_id}/*/*/answer/stream",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/chat/{workspace_id}/*/*/v2/answer/stream",
+    Write,
+  ),
+  (
+    Method::GET,
+    "/api/chat/{workspace_id}/*/*/related_question",
+    Write,
+  ),
+  (
+    Method::POST,
+    "/api/chat/{workspace_id}/*/context/text",
+    Write,
+  ),
+  (Method::POST, "/api/ai/{workspace_id}/complete", Write),
+  (
+    Method::POST,
+    "/api/ai/{workspace_id}/complete/stream",
+    Write,
+  ),
+  (Method::POST, "/api/ai/{workspace_id}/summarize_row", Write),
+  (Method::POST, "/api/ai/{workspace_id}/translate_row", Write),
+  (Method::GET, "/api/ai/{workspace_id}/local/config", Read),
+  (
+    Method::POST,
+    "/api/ai/{workspace_id}/calculate_similarity",
+    Write,
+  ),
+  // Searching embeds the query with the AI service.
+  (Method::GET, "/api/search/{workspace_id}", Write),
+  // SCIM provisioning manages the members of the workspace.
+  (
+    Method::GET,
+    "/api/scim/v2/{workspace_id}/ServiceProviderConfig",
+    Admin,
+  ),
+  (Method::GET, "/api/scim/v2/{workspace_id}/Users", Admin),
+  (Method::POST, "/api/scim/v2/{workspace_id}/Users", Admin),
+  (Method::GET, "/api/scim/v2/{workspace_id}/Users/*", Admin),
+  (Method::PUT, "/api/scim/v2/{workspace_id}/Users/*", Admin),
+  (Method::PATCH, "/api/scim/v2/{workspace_id}/Users/*", Admin),
+  (Method::DELETE, "/api/scim/v2/{workspace_id}/Users/*", Admin),
+  (Method::GET, "/api/scim/v2/{workspace_id}/Groups", Admin),
+  (Method::POST, "/api/scim/v2/{workspace_id}/Groups", Admin),
+  (Method::GET, "/api/scim/v2/{workspace_id}/Groups/*", Admin),
+  (Method::PUT, "/api/scim/v2/{workspace_id}/Groups/*", Admin),
+  (Method::PATCH, "/api/scim/v2/{workspace_id}/Groups/*", Admin),
+  (
+    Method::DELETE,
+    "/api/scim/v2/{workspace_id}/Groups/*",
+    Admin,
+  ),
+];
+
+/// Verifies the workspace API tokens sent in the `Authorization` header. A valid token is turned
+/// into an [ApiTokenIdentity], which the [authentication::jwt::UserUuid] extractor accepts like a
+/// GoTrue JWT, so the handlers don't need to know how the request was authenticated.
+///
+/// A token only gives access to the routes of its workspace, within the limits of its
+/// [ApiTokenScope]. Tokens can't be used to manage API tokens.
+pub struct ApiTokenMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for ApiTokenMiddleware
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+  S::Future: 'static,
+  B: 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = actix_web::Error;
+  type Transform = ApiTokenMiddlewareService<S>;
+  type InitError = ();
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ready(Ok(ApiTokenMiddlewareService {
+      service: Rc::new(service),
+    }))
+  }
+}
+
+pub struct ApiTokenMiddlewareService<S> {
+  service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for ApiTokenMiddlewareService<S>
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+  S::Future: 'static,
+  B: 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = actix_web::Error;
+  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let token = match get_api_token(&req) {
+      None => return Box::pin(self.service.call(req)),
+      Some(token) => token,
+    };
+
+    let service = self.service.clone();
+    Box::pin(async move {
+      let state = req
+        .app_data::<Data<AppState>>()
+        .cloned()
+        .ok_or_else(|| AppError::Internal(anyhow::anyhow!("AppState not found")))?;
+      let owner = select_api_token_by_hash(&state.pg_pool, &hash_api_token(&token))
+        .await?
+        .ok_or_else(|| AppError::UserUnAuthorized("Invalid or expired API token".to_string()))?;
+
+      let scope = ApiTokenScope::from(owner.scope);
+      if !is_request_allowed(req.method(), req.path(), &owner.workspace_id, scope) {
+        trace!(
+          "API token {} with scope {:?} denied: {} {}",
+          owner.token_id,
+          scope,
+          req.method(),
+          req.path()
+        );
+        return Err(
+          AppError::NotEnoughPermissions {
+            user: owner.uid.to_string(),
+            workspace_id: owner.workspace_id.to_string(),
+          }
+          .into(),
+        );
+      }
+
+      req.extensions_mut().insert(ApiTokenIdentity {
+        token_id: owner.token_id,
+        workspace_id: owner.workspace_id,
+        user_uuid: owner.uuid,
+        email: owner.email,
+      });
+      service.call(req).await
+    })
+  }
+}
+
+fn get_api_token(req: &ServiceRequest) -> Option<String> {
+  let header = req.headers().get("Authorization")?.to_str().ok()?;
+  let (_, token) = header.split_once("Bearer ")?;
+  is_api_token(token).then(|| token.to_string())
+}
+
+/// The request must match one of the [API_TOKEN_ROUTES] within the workspace of the token, and
+/// the token must have the scope of the route, or the widest one if several routes match.
+fn is_request_allowed(
+  method: &Method,
+  path: &str,
+  workspace_id: &Uuid,
+  scope: ApiTokenScope,
+) -> bool {
+  let method = if *method == Method::HEAD {
+    &Method::GET
+  } else {
+    method
+  };
+  let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+  API_TOKEN_ROUTES
+    .iter()
+    .filter(|(route_method, route_path, _)| {
+      route_method == method && route_matches(route_path, &segments, workspace_id)
+    })
+    .map(|(_, _, required_scope)| *required_scope as i32)
+    .max()
+    .is_some_and(|required_scope| scope as i32 >= required_scope)
+}
+
+fn route_matches(route_path: &str, segments: &[&str], workspace_id: &Uuid) -> bool {
+  let route_segments = route_path.trim_matches('/').split('/').collect::<Vec<_>>();
+  route_segments.len() == segments.len()
+    && route_segments
+      .iter()
+      .zip(segments)
+      .all(|(route_segment, segment)| match *route_segment {
+        "*" => true,
+        "{workspace_id}" => Uuid::from_str(segment).ok().as_ref() == Some(workspace_id),
+        route_segment => route_segment == *segment,
+      })
+}
@@ -1,3 +1,4 @@
+pub mod api_token_mw;
 pub mod encrypt_mw;
 pub mod metrics_mw;
 pub mod request_id;
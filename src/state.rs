@@ -14,7 +14,9 @@ use uuid::Uuid;
 use access_control::metrics::AccessControlMetrics;
 use app_error::AppError;
 use appflowy_ai_client::client::AppFlowyAIClient;
+use appflowy_collaborate::collab::persistence_hook::PersistenceHook;
 use appflowy_collaborate::collab::storage::CollabAccessControlStorage;
+use appflowy_collaborate::collab::workspace_activity::WorkspaceActivityRecorder;
 use appflowy_collaborate::indexer::IndexerProvider;
 use appflowy_collaborate::metrics::CollabMetrics;
 use appflowy_collaborate::CollabRealtimeMetrics;
@@ -28,6 +30,7 @@ use tonic_proto::history::history_client::HistoryClient;
 use crate::api::metrics::{AppFlowyWebMetrics, PublishedCollabMetrics, RequestMetrics};
 use crate::biz::pg_listener::PgListeners;
 use crate::biz::workspace::publish::PublishedCollabStore;
+use crate::biz::workspace::publish_analytics::PublishedViewAnalyticsRecorder;
 use crate::config::config::Config;
 use crate::mailer::AFCloudMailer;
 
@@ -55,6 +58,9 @@ pub struct AppState {
   pub ai_client: AppFlowyAIClient,
   pub grpc_history_client: Arc<Mutex<HistoryClient<tonic::transport::Channel>>>,
   pub indexer_provider: Arc<IndexerProvider>,
+  pub persistence_hooks: Vec<Arc<dyn PersistenceHook>>,
+  pub published_view_analytics: Arc<PublishedViewAnalyticsRecorder>,
+  pub activity_recorder: Arc<WorkspaceActivityRecorder>,
 }
 
 impl AppState {
@@ -1,14 +1,19 @@
 use crate::ai_test::util::read_text_from_asset;
 
+use app_error::ErrorCode;
 use assert_json_diff::{assert_json_eq, assert_json_include};
 use client_api::entity::{QuestionStream, QuestionStreamValue};
 use client_api_test::{ai_test_enabled, TestClient};
+use collab_rt_entity::user::UserMessage;
+use database_entity::dto::AFRole;
 use futures_util::StreamExt;
 use serde_json::json;
 use shared_entity::dto::chat_dto::{
-  ChatMessageMetadata, ChatRAGData, CreateChatMessageParams, CreateChatParams, MessageCursor,
+  ChatMessageMetadata, ChatParticipantRole, ChatRAGData, CreateAnswerMessageParams,
+  CreateChatMessageParams, CreateChatParams, MessageCursor, SearchChatMessageParams,
   UpdateChatParams,
 };
+use std::time::Duration;
 
 #[tokio::test]
 async fn update_chat_settings_test() {
@@ -392,6 +397,218 @@ async fn create_chat_context_test() {
 //   assert_ne!(remote_messages[1].content, messages[1].content);
 // }
 
+#[tokio::test]
+async fn search_and_export_chat_messages_test() {
+  let test_client = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = test_client.workspace_id().await;
+  let chat_id = uuid::Uuid::new_v4().to_string();
+  test_client
+    .api_client
+    .create_chat(
+      &workspace_id,
+      CreateChatParams {
+        chat_id: chat_id.clone(),
+        name: "travel plans".to_string(),
+        rag_ids: vec![],
+      },
+    )
+    .await
+    .unwrap();
+
+  let question = test_client
+    .api_client
+    .create_question(
+      &workspace_id,
+      &chat_id,
+      CreateChatMessageParams::new_user("What is the capital of Iceland?"),
+    )
+    .await
+    .unwrap();
+  test_client
+    .api_client
+    .save_answer(
+      &workspace_id,
+      &chat_id,
+      CreateAnswerMessageParams {
+        content: "Reykjavik is the capital of Iceland.".to_string(),
+        metadata: None,
+        question_message_id: question.message_id,
+      },
+    )
+    .await
+    .unwrap();
+
+  // both the question and the answer match
+  let result = test_client
+    .api_client
+    .search_chat_messages(
+      &workspace_id,
+      SearchChatMessageParams {
+        query: "iceland".to_string(),
+        limit: None,
+        offset: None,
+      },
+    )
+    .await
+    .unwrap();
+  assert_eq!(result.items.len(), 2);
+  assert!(!result.has_more);
+  assert!(result.items.iter().all(|item| item.chat_id == chat_id));
+  assert_eq!(result.items[0].chat_name, "travel plans");
+
+  let result = test_client
+    .api_client
+    .search_chat_messages(
+      &workspace_id,
+      SearchChatMessageParams {
+        query: "reykjavik".to_string(),
+        limit: None,
+        offset: None,
+      },
+    )
+    .await
+    .unwrap();
+  assert_eq!(result.items.len(), 1);
+  assert!(result.items[0].message.content.starts_with("Reykjavik"));
+
+  let export = test_client
+    .api_client
+    .export_chat(&workspace_id, &chat_id)
+    .await
+    .unwrap();
+  assert_eq!(export.name, "travel plans");
+  assert_eq!(export.messages.len(), 2);
+  assert_eq!(export.messages[0].message_id, question.message_id);
+
+  let markdown = test_client
+    .api_client
+    .export_chat_markdown(&workspace_id, &chat_id)
+    .await
+    .unwrap();
+  assert!(markdown.starts_with("# travel plans"));
+  assert!(markdown.contains("## What is the capital of Iceland?"));
+  assert!(markdown.contains("Reykjavik is the capital of Iceland."));
+
+  // a user outside of the workspace can neither search nor export the chat
+  let other_client = TestClient::new_user_without_ws_conn().await;
+  let err = other_client
+    .api_client
+    .export_chat(&workspace_id, &chat_id)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+}
+
+#[tokio::test]
+async fn shared_chat_participants_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  let member = TestClient::new_user().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+
+  let chat_id = uuid::Uuid::new_v4().to_string();
+  owner
+    .api_client
+    .create_chat(
+      &workspace_id,
+      CreateChatParams {
+        chat_id: chat_id.clone(),
+        name: "roadmap".to_string(),
+        rag_ids: vec![],
+      },
+    )
+    .await
+    .unwrap();
+
+  // the chat is private to its creator until it is shared
+  let err = member
+    .api_client
+    .get_chat_messages(&workspace_id, &chat_id, MessageCursor::NextBack, 10)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+  let chats = member.api_client.get_chats(&workspace_id).await.unwrap();
+  assert!(chats.items.iter().all(|chat| chat.chat_id != chat_id));
+
+  let member_email = member.email().await;
+  owner
+    .api_client
+    .add_chat_participants(&workspace_id, &chat_id, vec![member_email.clone()])
+    .await
+    .unwrap();
+  let participants = owner
+    .api_client
+    .get_chat_participants(&workspace_id, &chat_id)
+    .await
+    .unwrap();
+  assert_eq!(participants.items.len(), 2);
+  assert_eq!(participants.items[0].role, ChatParticipantRole::Owner);
+  assert_eq!(participants.items[1].email, member_email);
+  assert_eq!(participants.items[1].role, ChatParticipantRole::Member);
+
+  let chats = member.api_client.get_chats(&workspace_id).await.unwrap();
+  let chat = chats
+    .items
+    .iter()
+    .find(|chat| chat.chat_id == chat_id)
+    .unwrap();
+  assert_eq!(chat.role, ChatParticipantRole::Member);
+  assert_eq!(chat.participant_count, 2);
+
+  // the participants receive the new messages of the chat
+  let mut user_change_recv = member.ws_client.subscribe_user_changed();
+  let question = owner
+    .api_client
+    .create_question(
+      &workspace_id,
+      &chat_id,
+      CreateChatMessageParams::new_user("Who owns the Q3 roadmap?"),
+    )
+    .await
+    .unwrap();
+  let change = tokio::time::timeout(Duration::from_secs(10), async {
+    loop {
+      if let UserMessage::ChatMessageChange(change) = user_change_recv.recv().await.unwrap() {
+        return change;
+      }
+    }
+  })
+  .await
+  .unwrap();
+  assert_eq!(change.chat_id, chat_id);
+  assert_eq!(change.message_id, question.message_id);
+  assert_eq!(change.content, "Who owns the Q3 roadmap?");
+
+  let messages = member
+    .api_client
+    .get_chat_messages(&workspace_id, &chat_id, MessageCursor::NextBack, 10)
+    .await
+    .unwrap();
+  assert_eq!(messages.messages.len(), 1);
+
+  // only the owner can delete the chat, a member can only leave it
+  let err = member
+    .api_client
+    .delete_chat(&workspace_id, &chat_id)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+  member
+    .api_client
+    .remove_chat_participants(&workspace_id, &chat_id, vec![member_email])
+    .await
+    .unwrap();
+  let err = member
+    .api_client
+    .get_chat_messages(&workspace_id, &chat_id, MessageCursor::NextBack, 10)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+}
+
 async fn collect_answer(mut stream: QuestionStream) -> String {
   let mut answer = String::new();
   while let Some(value) = stream.next().await {
@@ -8,6 +8,7 @@ use collab_document::document::Document;
 use collab_document::importer::md_importer::MDImporter;
 use collab_entity::CollabType;
 use shared_entity::dto::chat_dto::{CreateChatMessageParams, CreateChatParams};
+use shared_entity::dto::search_dto::SearchContentType;
 use tokio::time::sleep;
 use workspace_template::document::getting_started::getting_started_document_data;
 
@@ -189,6 +190,56 @@ async fn test_document_indexing_and_search() {
   assert!(preview.contains("Welcome to AppFlowy"));
 }
 
+#[tokio::test]
+async fn test_search_text_attachment() {
+  let mut test_client = TestClient::new_user().await;
+  let workspace_id = test_client.workspace_id().await;
+
+  let object_id = uuid::Uuid::new_v4().to_string();
+  let document = create_document_collab(&object_id, "appflowy_values.md").await;
+  test_client
+    .create_collab_with_data(
+      &workspace_id,
+      &object_id,
+      CollabType::Document,
+      document.encode_collab().unwrap(),
+    )
+    .await
+    .unwrap();
+
+  let attachment =
+    std::fs::read("tests/search/asset/kathryn_tennis_story.md").expect("attachment file");
+  test_client
+    .api_client
+    .put_blob_v1(
+      &workspace_id,
+      &object_id,
+      attachment,
+      &mime::TEXT_PLAIN_UTF_8,
+    )
+    .await
+    .unwrap();
+
+  // attachments are indexed by appflowy-worker in the background
+  let mut found = false;
+  for _ in 0..30 {
+    let search_resp = test_client
+      .api_client
+      .search_documents(&workspace_id, "Kathryn tennis", 5, 100)
+      .await
+      .unwrap();
+    found = search_resp.iter().any(|item| {
+      item.object_id == object_id
+        && matches!(item.content_type, Some(SearchContentType::Attachment))
+    });
+    if found {
+      break;
+    }
+    sleep(Duration::from_secs(2)).await;
+  }
+  assert!(found, "attachment content should be searchable");
+}
+
 async fn create_document_collab(document_id: &str, file_name: &str) -> Document {
   let file_path = PathBuf::from(format!("tests/search/asset/{}", file_name));
   let md = std::fs::read_to_string(file_path).unwrap();
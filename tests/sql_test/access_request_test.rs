@@ -0,0 +1,70 @@
+use crate::sql_test::util::{setup_db, test_create_user};
+
+use app_error::AppError;
+use chrono::Duration;
+use database::access_request::{update_pending_access_request_status, upsert_access_request};
+use database::pg_row::AFAccessRequestStatusColumn;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[sqlx::test(migrations = false)]
+async fn reopen_rejected_access_request_after_cooldown_sql_test(pool: PgPool) {
+  setup_db(&pool).await.unwrap();
+
+  let owner_uuid = Uuid::new_v4();
+  let owner = test_create_user(
+    &pool,
+    owner_uuid,
+    &format!("{}@appflowy.io", owner_uuid),
+    "owner",
+  )
+  .await
+  .unwrap();
+  let requester_uuid = Uuid::new_v4();
+  let requester = test_create_user(
+    &pool,
+    requester_uuid,
+    &format!("{}@appflowy.io", requester_uuid),
+    "requester",
+  )
+  .await
+  .unwrap();
+  let workspace_id = Uuid::parse_str(&owner.workspace_id).unwrap();
+  let view_id = Uuid::new_v4();
+  let cooldown = Duration::hours(1);
+
+  let request_id =
+    upsert_access_request(&pool, workspace_id, view_id, requester.uid, None, cooldown)
+      .await
+      .unwrap();
+  let err = upsert_access_request(&pool, workspace_id, view_id, requester.uid, None, cooldown)
+    .await
+    .unwrap_err();
+  assert!(matches!(err, AppError::AccessRequestAlreadyExists { .. }));
+
+  update_pending_access_request_status(
+    &pool,
+    request_id,
+    AFAccessRequestStatusColumn::Rejected,
+    None,
+  )
+  .await
+  .unwrap();
+  let err = upsert_access_request(&pool, workspace_id, view_id, requester.uid, None, cooldown)
+    .await
+    .unwrap_err();
+  assert!(matches!(err, AppError::TooManyRequests(_)));
+
+  // Once the cooldown has passed, the rejected request is reopened
+  let reopened_request_id = upsert_access_request(
+    &pool,
+    workspace_id,
+    view_id,
+    requester.uid,
+    None,
+    Duration::zero(),
+  )
+  .await
+  .unwrap();
+  assert_eq!(reopened_request_id, request_id);
+}
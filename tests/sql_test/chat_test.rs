@@ -1,11 +1,15 @@
 use crate::sql_test::util::{setup_db, test_create_user};
 use database::chat::chat_ops::{
-  delete_chat, get_all_chat_messages, insert_chat, insert_question_message, select_chat,
-  select_chat_messages, select_chat_settings, update_chat_settings,
+  delete_chat, get_all_chat_messages, insert_answer_message, insert_chat, insert_chat_branch,
+  insert_question_message, search_chat_messages, select_answer_version, select_answer_versions,
+  select_chat, select_chat_branches, select_chat_messages, select_chat_settings,
+  update_chat_active_branch, update_chat_settings,
 };
+use database::chat::chat_participant_ops::{legacy_chat_role, select_chat_access};
 use serde_json::json;
 use shared_entity::dto::chat_dto::{
-  ChatAuthor, ChatAuthorType, CreateChatParams, GetChatMessageParams,
+  ChatAuthor, ChatAuthorType, ChatParticipantRole, CreateChatParams, GetChatMessageParams,
+  SearchChatMessageParams,
 };
 
 use shared_entity::dto::chat_dto::UpdateChatParams;
@@ -27,6 +31,7 @@ async fn chat_crud_test(pool: PgPool) {
   {
     insert_chat(
       &pool,
+      user.uid,
       &user.workspace_id,
       CreateChatParams {
         chat_id: chat_id.clone(),
@@ -78,6 +83,7 @@ async fn chat_message_crud_test(pool: PgPool) {
   {
     insert_chat(
       &pool,
+      user.uid,
       &user.workspace_id,
       CreateChatParams {
         chat_id: chat_id.clone(),
@@ -201,7 +207,7 @@ async fn chat_setting_test(pool: PgPool) {
     rag_ids: vec!["rag1".to_string(), "rag2".to_string()],
   };
 
-  insert_chat(&pool, &workspace_id, insert_params)
+  insert_chat(&pool, user.uid, &workspace_id, insert_params)
     .await
     .expect("Failed to insert chat");
 
@@ -249,3 +255,320 @@ async fn chat_setting_test(pool: PgPool) {
   );
   assert_eq!(settings.rag_ids, vec!["rag3", "rag4"]);
 }
+
+#[sqlx::test(migrations = false)]
+async fn chat_message_search_test(pool: PgPool) {
+  setup_db(&pool).await.unwrap();
+  let user_uuid = uuid::Uuid::new_v4();
+  let name = user_uuid.to_string();
+  let email = format!("{}@appflowy.io", name);
+  let user = test_create_user(&pool, user_uuid, &email, &name)
+    .await
+    .unwrap();
+
+  let mut chat_ids = vec![];
+  for i in 0..2 {
+    let chat_id = uuid::Uuid::new_v4().to_string();
+    insert_chat(
+      &pool,
+      user.uid,
+      &user.workspace_id,
+      CreateChatParams {
+        chat_id: chat_id.clone(),
+        name: format!("chat {}", i),
+        rag_ids: vec![],
+      },
+    )
+    .await
+    .unwrap();
+    for content in ["how to bake sourdough bread", "what is the weather today"] {
+      insert_question_message(
+        &pool,
+        ChatAuthor::new(user.uid, ChatAuthorType::Human),
+        &chat_id,
+        content.to_string(),
+        vec![],
+      )
+      .await
+      .unwrap();
+    }
+    chat_ids.push(chat_id);
+  }
+
+  let params = SearchChatMessageParams {
+    query: "sourdough".to_string(),
+    limit: None,
+    offset: None,
+  };
+  let result = search_chat_messages(&pool, &user.workspace_id, user.uid, &params, 1, 0)
+    .await
+    .unwrap();
+  assert_eq!(result.items.len(), 1);
+  assert!(result.has_more);
+  // the most recent message comes first
+  assert_eq!(result.items[0].chat_id, chat_ids[1]);
+  assert_eq!(result.items[0].chat_name, "chat 1");
+
+  let result = search_chat_messages(&pool, &user.workspace_id, user.uid, &params, 1, 1)
+    .await
+    .unwrap();
+  assert_eq!(result.items.len(), 1);
+  assert!(!result.has_more);
+  assert_eq!(result.items[0].chat_id, chat_ids[0]);
+
+  // the chats the user neither created nor participates in are not searched
+  let result = search_chat_messages(&pool, &user.workspace_id, user.uid + 1, &params, 10, 0)
+    .await
+    .unwrap();
+  assert!(result.items.is_empty());
+
+  // messages of deleted chats are not returned
+  let mut txn = pool.begin().await.unwrap();
+  delete_chat(&mut txn, &chat_ids[1]).await.unwrap();
+  txn.commit().await.unwrap();
+  let result = search_chat_messages(&pool, &user.workspace_id, user.uid, &params, 10, 0)
+    .await
+    .unwrap();
+  assert_eq!(result.items.len(), 1);
+  assert_eq!(result.items[0].chat_id, chat_ids[0]);
+
+  // no match
+  let params = SearchChatMessageParams {
+    query: "sourdough -bread".to_string(),
+    limit: None,
+    offset: None,
+  };
+  let result = search_chat_messages(&pool, &user.workspace_id, user.uid, &params, 10, 0)
+    .await
+    .unwrap();
+  assert!(result.items.is_empty());
+}
+
+#[sqlx::test(migrations = false)]
+async fn chat_answer_versions_and_branches_test(pool: PgPool) {
+  setup_db(&pool).await.unwrap();
+  let user_uuid = uuid::Uuid::new_v4();
+  let name = user_uuid.to_string();
+  let email = format!("{}@appflowy.io", name);
+  let user = test_create_user(&pool, user_uuid, &email, &name)
+    .await
+    .unwrap();
+  let chat_id = uuid::Uuid::new_v4().to_string();
+  insert_chat(
+    &pool,
+    user.uid,
+    &user.workspace_id,
+    CreateChatParams {
+      chat_id: chat_id.clone(),
+      name: "branches".to_string(),
+      rag_ids: vec![],
+    },
+  )
+  .await
+  .unwrap();
+
+  let author = ChatAuthor::new(user.uid, ChatAuthorType::Human);
+  let q1 = insert_question_message(&pool, author.clone(), &chat_id, "q1".to_string(), vec![])
+    .await
+    .unwrap();
+  let a1 = insert_answer_message(
+    &pool,
+    ChatAuthor::ai(),
+    &chat_id,
+    "a1".to_string(),
+    None,
+    q1.message_id,
+  )
+  .await
+  .unwrap();
+  let q2 = insert_question_message(&pool, author.clone(), &chat_id, "q2".to_string(), vec![])
+    .await
+    .unwrap();
+  insert_answer_message(
+    &pool,
+    ChatAuthor::ai(),
+    &chat_id,
+    "a2".to_string(),
+    None,
+    q2.message_id,
+  )
+  .await
+  .unwrap();
+
+  // regenerating the first answer keeps the previous version, the new version stays right after
+  // its question
+  let regenerated = insert_answer_message(
+    &pool,
+    ChatAuthor::ai(),
+    &chat_id,
+    "a1 regenerated".to_string(),
+    None,
+    q1.message_id,
+  )
+  .await
+  .unwrap();
+  let messages = get_all_chat_messages(&pool, &chat_id).await.unwrap();
+  let contents = messages
+    .iter()
+    .map(|m| m.content.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(contents, vec!["q1", "a1 regenerated", "q2", "a2"]);
+
+  let mut txn = pool.begin().await.unwrap();
+  let versions = select_answer_versions(&mut txn, &chat_id, q1.message_id)
+    .await
+    .unwrap();
+  assert_eq!(versions.versions.len(), 2);
+  assert_eq!(versions.selected_message_id, Some(regenerated.message_id));
+  // the question must belong to the chat
+  let other_chat_id = uuid::Uuid::new_v4().to_string();
+  let err = select_answer_versions(&mut txn, &other_chat_id, q1.message_id)
+    .await
+    .unwrap_err();
+  assert!(err.is_record_not_found());
+
+  // switch back to the first version
+  select_answer_version(&mut txn, &chat_id, q1.message_id, a1.message_id)
+    .await
+    .unwrap();
+  // a message which doesn't answer the question can't be selected
+  let err = select_answer_version(&mut txn, &chat_id, q1.message_id, q2.message_id)
+    .await
+    .unwrap_err();
+  assert!(err.is_record_not_found());
+  let err = select_answer_version(&mut txn, &other_chat_id, q1.message_id, a1.message_id)
+    .await
+    .unwrap_err();
+  assert!(err.is_record_not_found());
+  txn.commit().await.unwrap();
+
+  // editing the second question forks a new branch which becomes the active branch
+  let mut txn = pool.begin().await.unwrap();
+  let branch = insert_chat_branch(&mut txn, &chat_id, q2.message_id)
+    .await
+    .unwrap();
+  assert_eq!(branch.parent_branch_id, 0);
+  // answers can't be edited
+  assert!(insert_chat_branch(&mut txn, &chat_id, a1.message_id)
+    .await
+    .is_err());
+  txn.commit().await.unwrap();
+
+  let edited = insert_question_message(
+    &pool,
+    author.clone(),
+    &chat_id,
+    "q2 edited".to_string(),
+    vec![],
+  )
+  .await
+  .unwrap();
+  insert_answer_message(
+    &pool,
+    ChatAuthor::ai(),
+    &chat_id,
+    "a2 edited".to_string(),
+    None,
+    edited.message_id,
+  )
+  .await
+  .unwrap();
+
+  let mut txn = pool.begin().await.unwrap();
+  let active = select_chat_messages(&mut txn, &chat_id, GetChatMessageParams::offset(0, 10))
+    .await
+    .unwrap();
+  let contents = active
+    .messages
+    .iter()
+    .map(|m| m.content.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(contents, vec!["q1", "a1", "q2 edited", "a2 edited"]);
+  assert_eq!(active.total, 4);
+
+  let main = select_chat_messages(
+    &mut txn,
+    &chat_id,
+    GetChatMessageParams::offset(0, 10).with_branch_id(0),
+  )
+  .await
+  .unwrap();
+  let contents = main
+    .messages
+    .iter()
+    .map(|m| m.content.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(contents, vec!["q1", "a1", "q2", "a2"]);
+
+  // cursors follow the order of the branch
+  let after = select_chat_messages(
+    &mut txn,
+    &chat_id,
+    GetChatMessageParams::after_message_id(a1.message_id, 10),
+  )
+  .await
+  .unwrap();
+  assert_eq!(after.messages.len(), 2);
+  assert_eq!(after.messages[1].message_id, edited.message_id);
+  assert!(!after.has_more);
+  txn.commit().await.unwrap();
+
+  let branches = select_chat_branches(&pool, &chat_id).await.unwrap();
+  assert_eq!(branches.active_branch_id, branch.branch_id);
+  assert_eq!(branches.branches.len(), 1);
+  assert_eq!(branches.branches[0].fork_message_id, q2.message_id);
+
+  update_chat_active_branch(&pool, &chat_id, 0).await.unwrap();
+  let messages = get_all_chat_messages(&pool, &chat_id).await.unwrap();
+  assert_eq!(messages[2].content, "q2");
+  let err = update_chat_active_branch(&pool, &chat_id, branch.branch_id + 100)
+    .await
+    .unwrap_err();
+  assert!(err.is_record_not_found());
+}
+
+#[sqlx::test(migrations = false)]
+async fn chat_without_participants_access_test(pool: PgPool) {
+  setup_db(&pool).await.unwrap();
+  let user_uuid = uuid::Uuid::new_v4();
+  let name = user_uuid.to_string();
+  let email = format!("{}@appflowy.io", name);
+  let user = test_create_user(&pool, user_uuid, &email, &name)
+    .await
+    .unwrap();
+  let chat_id = uuid::Uuid::new_v4();
+  insert_chat(
+    &pool,
+    user.uid,
+    &user.workspace_id,
+    CreateChatParams {
+      chat_id: chat_id.to_string(),
+      name: "legacy".to_string(),
+      rag_ids: vec![],
+    },
+  )
+  .await
+  .unwrap();
+
+  let access = select_chat_access(&pool, &chat_id, user.uid)
+    .await
+    .unwrap()
+    .unwrap();
+  assert!(access.role.is_none());
+  assert!(!access.has_participants);
+  assert_eq!(access.created_by, Some(user.uid));
+
+  // the creator owns the chat, the other members can only read it
+  assert_eq!(
+    legacy_chat_role(user.uid, access.created_by),
+    ChatParticipantRole::Owner
+  );
+  assert_eq!(
+    legacy_chat_role(user.uid + 1, access.created_by),
+    ChatParticipantRole::Viewer
+  );
+  assert_eq!(
+    legacy_chat_role(user.uid, None),
+    ChatParticipantRole::Viewer
+  );
+}
@@ -1,3 +1,4 @@
+mod access_request_test;
 mod chat_test;
 mod history_test;
 pub(crate) mod util;
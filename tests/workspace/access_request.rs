@@ -1,14 +1,12 @@
 use app_error::ErrorCode;
-use client_api::entity::CreateAccessRequestParams;
+use client_api::entity::{AccessRequestStatus, CreateAccessRequestParams};
+use client_api::Client;
 use client_api_test::generate_unique_registered_user_client;
+use shared_entity::dto::access_request_dto::QueryAccessRequestParams;
 use shared_entity::dto::workspace_dto::ViewLayout;
 use uuid::Uuid;
 
-#[tokio::test]
-async fn access_request_test() {
-  let (owner_client, _) = generate_unique_registered_user_client().await;
-  let workspaces = owner_client.get_workspaces().await.unwrap();
-  let workspace_id = workspaces[0].workspace_id;
+async fn get_todos_view_id(owner_client: &Client, workspace_id: Uuid) -> Uuid {
   let folder_view = owner_client
     .get_workspace_folder(&workspace_id.to_string(), Some(2), None)
     .await
@@ -24,10 +22,19 @@ async fn access_request_test() {
     .unwrap()
     .view_id
     .clone();
-  let view_id = Uuid::parse_str(&view_id).unwrap();
+  Uuid::parse_str(&view_id).unwrap()
+}
+
+#[tokio::test]
+async fn access_request_test() {
+  let (owner_client, _) = generate_unique_registered_user_client().await;
+  let workspaces = owner_client.get_workspaces().await.unwrap();
+  let workspace_id = workspaces[0].workspace_id;
+  let view_id = get_todos_view_id(&owner_client, workspace_id).await;
   let data = CreateAccessRequestParams {
     workspace_id,
     view_id,
+    reason: None,
   };
   let (requester_client, requester) = generate_unique_registered_user_client().await;
   let access_request = requester_client
@@ -79,3 +86,90 @@ async fn access_request_test() {
     .unwrap();
   assert!(workspace_members.iter().any(|m| m.email == requester.email));
 }
+
+#[tokio::test]
+async fn reject_access_request_with_reason_test() {
+  let (owner_client, _) = generate_unique_registered_user_client().await;
+  let workspace_id = owner_client.get_workspaces().await.unwrap()[0].workspace_id;
+  let view_id = get_todos_view_id(&owner_client, workspace_id).await;
+  let (requester_client, requester) = generate_unique_registered_user_client().await;
+  let data = CreateAccessRequestParams {
+    workspace_id,
+    view_id,
+    reason: Some("  I need to update the tasks of my team  ".to_string()),
+  };
+  let access_request = requester_client
+    .create_access_request(data.clone())
+    .await
+    .unwrap();
+
+  // Only the owners of the workspace can list the requests.
+  let query = QueryAccessRequestParams {
+    workspace_id,
+    view_id: Some(view_id),
+    status: Some(AccessRequestStatus::Pending),
+  };
+  let err = requester_client
+    .list_access_requests(&query)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+  let pending = owner_client.list_access_requests(&query).await.unwrap();
+  assert_eq!(pending.items.len(), 1);
+  let item = &pending.items[0];
+  assert_eq!(item.request_id, access_request.request_id);
+  assert_eq!(item.requester.email, requester.email);
+  assert_eq!(
+    item.reason.as_deref(),
+    Some("I need to update the tasks of my team")
+  );
+  assert_eq!(item.view.as_ref().unwrap().view_id, view_id.to_string());
+  assert!(item.expires_at > item.created_at);
+
+  owner_client
+    .reject_access_request(
+      access_request.request_id,
+      Some("Ask your team lead first".to_string()),
+    )
+    .await
+    .unwrap();
+  let rejected = owner_client
+    .get_access_request(access_request.request_id)
+    .await
+    .unwrap();
+  assert_eq!(rejected.status, AccessRequestStatus::Rejected);
+  assert_eq!(
+    rejected.rejection_reason.as_deref(),
+    Some("Ask your team lead first")
+  );
+  assert!(owner_client
+    .list_access_requests(&query)
+    .await
+    .unwrap()
+    .items
+    .is_empty());
+
+  // A request which is no longer pending can't be approved.
+  let err = owner_client
+    .approve_access_request(access_request.request_id)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+  let workspace_members = owner_client
+    .get_workspace_members(workspace_id.to_string())
+    .await
+    .unwrap();
+  assert!(!workspace_members.iter().any(|m| m.email == requester.email));
+
+  // The requester can't ask again right after the rejection.
+  let err = requester_client
+    .create_access_request(data)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::TooManyRequests);
+  let rejected = owner_client
+    .get_access_request(access_request.request_id)
+    .await
+    .unwrap();
+  assert_eq!(rejected.status, AccessRequestStatus::Rejected);
+}
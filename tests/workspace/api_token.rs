@@ -0,0 +1,157 @@
+use app_error::ErrorCode;
+use client_api::entity::AFWorkspaceMember;
+use client_api_test::TestClient;
+use database_entity::dto::AFRole;
+use reqwest::Method;
+use shared_entity::dto::api_token_dto::{ApiTokenScope, CreateApiTokenParams};
+use shared_entity::response::{AppResponse, AppResponseError};
+use uuid::Uuid;
+
+async fn send_with_api_token(
+  client: &TestClient,
+  method: Method,
+  path: &str,
+  token: &str,
+) -> reqwest::Response {
+  let url = format!("{}{}", client.api_client.base_url, path);
+  let request = reqwest::Client::new()
+    .request(method, url)
+    .bearer_auth(token)
+    .json(&serde_json::json!({}));
+  request.send().await.unwrap()
+}
+
+async fn get_members_with_api_token(
+  client: &TestClient,
+  workspace_id: &str,
+  token: &str,
+) -> Result<Vec<AFWorkspaceMember>, AppResponseError> {
+  let path = format!("/api/workspace/{}/member", workspace_id);
+  let resp = send_with_api_token(client, Method::GET, &path, token).await;
+  AppResponse::<Vec<AFWorkspaceMember>>::from_response(resp)
+    .await?
+    .into_data()
+}
+
+#[tokio::test]
+async fn read_only_api_token_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  let created = owner
+    .api_client
+    .create_api_token(
+      &workspace_id,
+      &CreateApiTokenParams {
+        name: "CI bot".to_string(),
+        scope: ApiTokenScope::Read,
+        expires_at: None,
+      },
+    )
+    .await
+    .unwrap();
+  assert!(created.token.starts_with(&created.api_token.token_prefix));
+
+  let tokens = owner
+    .api_client
+    .get_api_tokens(&workspace_id)
+    .await
+    .unwrap();
+  assert_eq!(tokens.items.len(), 1);
+  assert_eq!(tokens.items[0].token_id, created.api_token.token_id);
+
+  // The token acts on behalf of its creator.
+  let members = get_members_with_api_token(&owner, &workspace_id, &created.token)
+    .await
+    .unwrap();
+  assert_eq!(members[0].email, owner.email().await);
+
+  // Writes and other workspaces are denied.
+  let path = format!("/api/workspace/{}/page-view", workspace_id);
+  let resp = send_with_api_token(&owner, Method::POST, &path, &created.token).await;
+  let err = AppResponse::<()>::from_response(resp)
+    .await
+    .unwrap()
+    .into_error()
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+  // Searching calls the AI service, even though it is a GET.
+  let path = format!("/api/search/{}?query=hello", workspace_id);
+  let resp = send_with_api_token(&owner, Method::GET, &path, &created.token).await;
+  let err = AppResponse::<()>::from_response(resp)
+    .await
+    .unwrap()
+    .into_error()
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+  let err = get_members_with_api_token(&owner, &Uuid::new_v4().to_string(), &created.token)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+
+  // A revoked token is rejected.
+  owner
+    .api_client
+    .revoke_api_token(&workspace_id, &created.api_token.token_id)
+    .await
+    .unwrap();
+  let err = get_members_with_api_token(&owner, &workspace_id, &created.token)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::UserUnAuthorized);
+  assert!(owner
+    .api_client
+    .get_api_tokens(&workspace_id)
+    .await
+    .unwrap()
+    .items
+    .is_empty());
+}
+
+#[tokio::test]
+async fn api_token_scope_limited_by_role_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+
+  let err = member
+    .api_client
+    .create_api_token(
+      &workspace_id,
+      &CreateApiTokenParams {
+        name: "admin bot".to_string(),
+        scope: ApiTokenScope::Admin,
+        expires_at: None,
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+
+  let created = member
+    .api_client
+    .create_api_token(
+      &workspace_id,
+      &CreateApiTokenParams {
+        name: "write bot".to_string(),
+        scope: ApiTokenScope::Write,
+        expires_at: None,
+      },
+    )
+    .await
+    .unwrap();
+
+  // The owner can revoke the tokens of the members.
+  owner
+    .api_client
+    .revoke_api_token(&workspace_id, &created.api_token.token_id)
+    .await
+    .unwrap();
+  let err = get_members_with_api_token(&member, &workspace_id, &created.token)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::UserUnAuthorized);
+}
@@ -0,0 +1,199 @@
+use app_error::ErrorCode;
+use client_api_test::TestClient;
+use database_entity::dto::{AFRole, AFWorkspaceSettingsChange};
+use shared_entity::dto::audit_log_dto::{AuditAction, QueryAuditLogParams};
+
+#[tokio::test]
+async fn audit_log_records_member_and_settings_changes_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  let owner_uid = owner.uid().await;
+  let member_email = member.email().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+  owner
+    .try_update_workspace_member(&workspace_id, &member, AFRole::Guest)
+    .await
+    .unwrap();
+  owner
+    .api_client
+    .update_workspace_settings(
+      &workspace_id,
+      &AFWorkspaceSettingsChange::new().disable_search_indexing(true),
+    )
+    .await
+    .unwrap();
+
+  let entries = owner
+    .api_client
+    .get_audit_log(
+      &workspace_id,
+      &QueryAuditLogParams {
+        action: Some(AuditAction::MemberInvited),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap()
+    .items;
+  assert_eq!(entries.len(), 1);
+  assert_eq!(entries[0].object_id.as_deref(), Some(member_email.as_str()));
+  assert_eq!(entries[0].actor_uid, Some(owner_uid));
+
+  let entries = owner
+    .api_client
+    .get_audit_log(
+      &workspace_id,
+      &QueryAuditLogParams {
+        object_type: Some("member".to_string()),
+        object_id: Some(member_email.clone()),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap()
+    .items;
+  // the most recent entry comes first
+  let actions = entries
+    .iter()
+    .map(|entry| entry.action.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(actions, vec!["member.role_updated", "member.invited"]);
+
+  // The sign-ins of the members are part of the audit log of the workspace.
+  let entries = owner
+    .api_client
+    .get_audit_log(
+      &workspace_id,
+      &QueryAuditLogParams {
+        actor_uid: Some(member.uid().await),
+        action: Some(AuditAction::UserSignedIn),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap()
+    .items;
+  assert!(!entries.is_empty());
+  assert!(entries.iter().all(|entry| entry.workspace_id.is_none()));
+
+  // Verifying the token of a session again doesn't record another sign-in.
+  let url = format!(
+    "{}/api/user/verify/{}",
+    member.api_client.base_url,
+    member.api_client.access_token().unwrap()
+  );
+  reqwest::get(url).await.unwrap().error_for_status().unwrap();
+  let sign_in_count = owner
+    .api_client
+    .get_audit_log(
+      &workspace_id,
+      &QueryAuditLogParams {
+        actor_uid: Some(member.uid().await),
+        action: Some(AuditAction::UserSignedIn),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap()
+    .items
+    .len();
+  assert_eq!(sign_in_count, entries.len());
+
+  owner
+    .api_client
+    .remove_workspace_members(&workspace_id, vec![member_email.clone()])
+    .await
+    .unwrap();
+  let entries = owner
+    .api_client
+    .get_audit_log(
+      &workspace_id,
+      &QueryAuditLogParams {
+        action: Some(AuditAction::MemberRemoved),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap()
+    .items;
+  assert_eq!(entries.len(), 1);
+  assert_eq!(entries[0].object_id.as_deref(), Some(member_email.as_str()));
+  assert_eq!(entries[0].actor_uid, Some(owner_uid));
+
+  let csv = owner
+    .api_client
+    .export_audit_log(&workspace_id, &QueryAuditLogParams::default())
+    .await
+    .unwrap();
+  let mut lines = csv.lines();
+  assert!(lines.next().unwrap().starts_with("log_id,created_at,"));
+  assert!(lines.any(|line| line.contains("workspace.settings_updated")));
+}
+
+#[tokio::test]
+async fn audit_log_pagination_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  for disable_search_indexing in [true, false, true] {
+    owner
+      .api_client
+      .update_workspace_settings(
+        &workspace_id,
+        &AFWorkspaceSettingsChange::new().disable_search_indexing(disable_search_indexing),
+      )
+      .await
+      .unwrap();
+  }
+
+  let mut params = QueryAuditLogParams {
+    action: Some(AuditAction::WorkspaceSettingsUpdated),
+    limit: Some(2),
+    ..Default::default()
+  };
+  let first_page = owner
+    .api_client
+    .get_audit_log(&workspace_id, &params)
+    .await
+    .unwrap();
+  assert_eq!(first_page.items.len(), 2);
+  params.before = first_page.next_cursor;
+  let second_page = owner
+    .api_client
+    .get_audit_log(&workspace_id, &params)
+    .await
+    .unwrap();
+  assert_eq!(second_page.items.len(), 1);
+  assert!(second_page.next_cursor.is_none());
+  assert_eq!(
+    second_page.items[0].metadata["disable_search_indexing"],
+    serde_json::json!(true)
+  );
+}
+
+#[tokio::test]
+async fn only_owner_can_read_audit_log_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+
+  let err = member
+    .api_client
+    .get_audit_log(&workspace_id, &QueryAuditLogParams::default())
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+  let err = member
+    .api_client
+    .export_audit_log(&workspace_id, &QueryAuditLogParams::default())
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+}
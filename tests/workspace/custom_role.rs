@@ -0,0 +1,253 @@
+use app_error::ErrorCode;
+use client_api_test::TestClient;
+use database_entity::dto::{AFRole, AFWorkspacePermission};
+use shared_entity::dto::role_dto::{
+  AssignCustomRoleParams, CreateCustomRoleParams, UpdateCustomRoleParams,
+};
+use shared_entity::dto::workspace_dto::WorkspaceMemberInvitation;
+
+#[tokio::test]
+async fn publisher_role_cannot_invite_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user_without_ws_conn().await;
+  let invitee = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+
+  let role = owner
+    .api_client
+    .create_custom_role(
+      &workspace_id,
+      &CreateCustomRoleParams {
+        name: "Publisher".to_string(),
+        base_role: AFRole::Member,
+        permissions: vec![AFWorkspacePermission::Publish],
+      },
+    )
+    .await
+    .unwrap();
+  owner
+    .api_client
+    .assign_custom_role(
+      &workspace_id,
+      &AssignCustomRoleParams {
+        email: member.email().await,
+        role_id: Some(role.role_id),
+      },
+    )
+    .await
+    .unwrap();
+
+  let permissions = member
+    .api_client
+    .get_workspace_permissions(&workspace_id)
+    .await
+    .unwrap();
+  assert_eq!(permissions.role, AFRole::Member);
+  assert_eq!(permissions.custom_role_id, Some(role.role_id));
+  assert_eq!(
+    permissions.permissions,
+    vec![AFWorkspacePermission::Publish]
+  );
+
+  let invitee_email = invitee.email().await;
+  let err = member
+    .api_client
+    .invite_workspace_members(
+      &workspace_id,
+      vec![WorkspaceMemberInvitation {
+        email: invitee_email.clone(),
+        role: AFRole::Member,
+      }],
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+
+  // The members that have the role get the new permissions.
+  owner
+    .api_client
+    .update_custom_role(
+      &workspace_id,
+      &role.role_id,
+      &UpdateCustomRoleParams {
+        permissions: Some(vec![
+          AFWorkspacePermission::Publish,
+          AFWorkspacePermission::InviteMembers,
+        ]),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap();
+  member
+    .api_client
+    .invite_workspace_members(
+      &workspace_id,
+      vec![WorkspaceMemberInvitation {
+        email: invitee_email.clone(),
+        role: AFRole::Member,
+      }],
+    )
+    .await
+    .unwrap();
+
+  // Only an owner can invite an owner.
+  let err = member
+    .api_client
+    .invite_workspace_members(
+      &workspace_id,
+      vec![WorkspaceMemberInvitation {
+        email: invitee_email,
+        role: AFRole::Owner,
+      }],
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+
+  // Without the custom role, the member has the default permissions of its role.
+  owner
+    .api_client
+    .delete_custom_role(&workspace_id, &role.role_id)
+    .await
+    .unwrap();
+  let permissions = member
+    .api_client
+    .get_workspace_permissions(&workspace_id)
+    .await
+    .unwrap();
+  assert_eq!(permissions.custom_role_id, None);
+  assert_eq!(
+    permissions.permissions,
+    AFRole::Member.default_permissions()
+  );
+}
+
+#[tokio::test]
+async fn custom_role_validation_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+
+  let params = CreateCustomRoleParams {
+    name: "Reviewer".to_string(),
+    base_role: AFRole::Guest,
+    permissions: vec![AFWorkspacePermission::Export],
+  };
+  let role = owner
+    .api_client
+    .create_custom_role(&workspace_id, &params)
+    .await
+    .unwrap();
+  let err = owner
+    .api_client
+    .create_custom_role(&workspace_id, &params)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::RecordAlreadyExists);
+
+  let err = owner
+    .api_client
+    .create_custom_role(
+      &workspace_id,
+      &CreateCustomRoleParams {
+        name: "Admin".to_string(),
+        base_role: AFRole::Owner,
+        permissions: vec![],
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+
+  // The owner always has all the permissions.
+  let err = owner
+    .api_client
+    .assign_custom_role(
+      &workspace_id,
+      &AssignCustomRoleParams {
+        email: owner.email().await,
+        role_id: Some(role.role_id),
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+
+  let err = member
+    .api_client
+    .create_custom_role(&workspace_id, &params)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+  let err = member
+    .api_client
+    .get_custom_roles(&workspace_id)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+
+  let roles = owner
+    .api_client
+    .get_custom_roles(&workspace_id)
+    .await
+    .unwrap();
+  assert_eq!(roles.items.len(), 1);
+  assert_eq!(roles.items[0].name, "Reviewer");
+}
+
+#[tokio::test]
+async fn billing_manager_role_can_get_usage_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+
+  // The members can't manage the billing by default.
+  let err = member
+    .api_client
+    .get_workspace_usage(&workspace_id)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+
+  let role = owner
+    .api_client
+    .create_custom_role(
+      &workspace_id,
+      &CreateCustomRoleParams {
+        name: "Billing manager".to_string(),
+        base_role: AFRole::Member,
+        permissions: vec![AFWorkspacePermission::ManageBilling],
+      },
+    )
+    .await
+    .unwrap();
+  owner
+    .api_client
+    .assign_custom_role(
+      &workspace_id,
+      &AssignCustomRoleParams {
+        email: member.email().await,
+        role_id: Some(role.role_id),
+      },
+    )
+    .await
+    .unwrap();
+  member
+    .api_client
+    .get_workspace_usage(&workspace_id)
+    .await
+    .unwrap();
+}
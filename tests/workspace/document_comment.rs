@@ -0,0 +1,329 @@
+use std::time::Duration;
+
+use app_error::ErrorCode;
+use client_api_test::TestClient;
+use collab_rt_entity::user::UserMessage;
+use database_entity::dto::{AFAccessLevel, AFRole};
+use shared_entity::dto::document_comment_dto::{
+  CreateDocumentCommentParams, CreateDocumentCommentThreadParams, DocumentCommentAnchor,
+  QueryDocumentCommentThreads, UpdateDocumentCommentParams, UpdateDocumentCommentThreadParams,
+};
+use shared_entity::dto::view_permission_dto::GrantViewPermissionParams;
+use shared_entity::dto::workspace_dto::{CreatePageParams, ViewLayout};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn document_comment_thread_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+  let workspace_id = Uuid::parse_str(&workspace_id).unwrap();
+  let owner_uid = owner.uid().await;
+  let member_uid = member.uid().await;
+
+  let folder_view = owner
+    .api_client
+    .get_workspace_folder(&workspace_id.to_string(), Some(2), None)
+    .await
+    .unwrap();
+  let general_space = folder_view
+    .children
+    .into_iter()
+    .find(|v| v.name == "General")
+    .unwrap();
+  let page = owner
+    .api_client
+    .create_workspace_page_view(
+      workspace_id,
+      &CreatePageParams {
+        parent_view_id: general_space.view_id.clone(),
+        layout: ViewLayout::Document,
+        name: Some("Roadmap".to_string()),
+      },
+    )
+    .await
+    .unwrap();
+
+  // The members of the workspace receive the new comments
+  let mut user_change_recv = member.ws_client.subscribe_user_changed();
+  let thread = owner
+    .api_client
+    .create_document_comment_thread(
+      workspace_id,
+      &page.view_id,
+      &CreateDocumentCommentThreadParams {
+        anchor: DocumentCommentAnchor {
+          block_id: "block_1".to_string(),
+          range_start: Some(4),
+          range_end: Some(11),
+          quoted_text: Some("Q3 plan".to_string()),
+        },
+        content: "Can you double check the dates?".to_string(),
+        mentions: vec![member_uid],
+      },
+    )
+    .await
+    .unwrap();
+  assert_eq!(thread.anchor.block_id, "block_1");
+  assert_eq!(thread.comments.len(), 1);
+  assert_eq!(thread.comments[0].author_uid, Some(owner_uid));
+  assert_eq!(thread.comments[0].mentions, vec![member_uid]);
+  let change = tokio::time::timeout(Duration::from_secs(10), async {
+    loop {
+      if let UserMessage::DocumentCommentChange(change) = user_change_recv.recv().await.unwrap() {
+        return change;
+      }
+    }
+  })
+  .await
+  .unwrap();
+  assert_eq!(change.thread_id, thread.thread_id.to_string());
+  assert_eq!(change.view_id, page.view_id);
+  assert_eq!(change.action, 0);
+  assert_eq!(change.mentions, vec![member_uid]);
+
+  let reply = member
+    .api_client
+    .create_document_comment(
+      workspace_id,
+      &page.view_id,
+      &thread.thread_id,
+      &CreateDocumentCommentParams {
+        content: "Done, they are right".to_string(),
+        mentions: vec![],
+      },
+    )
+    .await
+    .unwrap();
+
+  // Only the author can edit a comment
+  let err = owner
+    .api_client
+    .update_document_comment(
+      workspace_id,
+      &page.view_id,
+      &thread.thread_id,
+      &reply.comment_id,
+      &UpdateDocumentCommentParams {
+        content: "Not my comment".to_string(),
+        mentions: vec![],
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::UserUnAuthorized);
+  let reply = member
+    .api_client
+    .update_document_comment(
+      workspace_id,
+      &page.view_id,
+      &thread.thread_id,
+      &reply.comment_id,
+      &UpdateDocumentCommentParams {
+        content: "Done, the dates are right".to_string(),
+        mentions: vec![owner_uid],
+      },
+    )
+    .await
+    .unwrap();
+  assert_eq!(reply.content, "Done, the dates are right");
+  assert_eq!(reply.mentions, vec![owner_uid]);
+
+  // Only the members of the workspace can be mentioned
+  let outsider = TestClient::new_user_without_ws_conn().await;
+  let err = member
+    .api_client
+    .create_document_comment(
+      workspace_id,
+      &page.view_id,
+      &thread.thread_id,
+      &CreateDocumentCommentParams {
+        content: "Adding someone else".to_string(),
+        mentions: vec![outsider.uid().await],
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+
+  // The resolved threads are hidden by default
+  member
+    .api_client
+    .update_document_comment_thread(
+      workspace_id,
+      &page.view_id,
+      &thread.thread_id,
+      &UpdateDocumentCommentThreadParams { resolved: true },
+    )
+    .await
+    .unwrap();
+  let threads = owner
+    .api_client
+    .get_document_comment_threads(
+      workspace_id,
+      &page.view_id,
+      &QueryDocumentCommentThreads::default(),
+    )
+    .await
+    .unwrap();
+  assert!(threads.threads.is_empty());
+  let threads = owner
+    .api_client
+    .get_document_comment_threads(
+      workspace_id,
+      &page.view_id,
+      &QueryDocumentCommentThreads {
+        include_resolved: true,
+      },
+    )
+    .await
+    .unwrap();
+  assert_eq!(threads.threads.len(), 1);
+  assert_eq!(threads.threads[0].resolved_by, Some(member_uid));
+  assert_eq!(threads.threads[0].comments.len(), 2);
+
+  // The owner has full access to the page, so it can delete the comments of the others
+  owner
+    .api_client
+    .delete_document_comment(
+      workspace_id,
+      &page.view_id,
+      &thread.thread_id,
+      &reply.comment_id,
+    )
+    .await
+    .unwrap();
+  let threads = member
+    .api_client
+    .get_document_comment_threads(
+      workspace_id,
+      &page.view_id,
+      &QueryDocumentCommentThreads {
+        include_resolved: true,
+      },
+    )
+    .await
+    .unwrap();
+  let deleted = &threads.threads[0].comments[1];
+  assert!(deleted.is_deleted);
+  assert!(deleted.content.is_empty());
+  assert!(deleted.mentions.is_empty());
+}
+
+#[tokio::test]
+async fn document_comment_access_level_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let guest = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &guest, AFRole::Guest)
+    .await
+    .unwrap();
+  let workspace_id = Uuid::parse_str(&workspace_id).unwrap();
+
+  let folder_view = owner
+    .api_client
+    .get_workspace_folder(&workspace_id.to_string(), Some(2), None)
+    .await
+    .unwrap();
+  let general_space = folder_view
+    .children
+    .into_iter()
+    .find(|v| v.name == "General")
+    .unwrap();
+  let page = owner
+    .api_client
+    .create_workspace_page_view(
+      workspace_id,
+      &CreatePageParams {
+        parent_view_id: general_space.view_id.clone(),
+        layout: ViewLayout::Document,
+        name: Some("Review".to_string()),
+      },
+    )
+    .await
+    .unwrap();
+  let thread = owner
+    .api_client
+    .create_document_comment_thread(
+      workspace_id,
+      &page.view_id,
+      &CreateDocumentCommentThreadParams {
+        anchor: DocumentCommentAnchor {
+          block_id: "block_1".to_string(),
+          ..Default::default()
+        },
+        content: "Please review this section".to_string(),
+        mentions: vec![],
+      },
+    )
+    .await
+    .unwrap();
+
+  // A guest can only read the comments
+  let threads = guest
+    .api_client
+    .get_document_comment_threads(
+      workspace_id,
+      &page.view_id,
+      &QueryDocumentCommentThreads::default(),
+    )
+    .await
+    .unwrap();
+  assert_eq!(threads.threads.len(), 1);
+  let reply = CreateDocumentCommentParams {
+    content: "Looks good to me".to_string(),
+    mentions: vec![],
+  };
+  let err = guest
+    .api_client
+    .create_document_comment(workspace_id, &page.view_id, &thread.thread_id, &reply)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+
+  // Until it's granted the access level to comment on the page
+  owner
+    .api_client
+    .grant_view_permission(
+      workspace_id,
+      &page.view_id,
+      &GrantViewPermissionParams {
+        email: guest.email().await,
+        access_level: AFAccessLevel::ReadAndComment,
+      },
+    )
+    .await
+    .unwrap();
+  let comment = guest
+    .api_client
+    .create_document_comment(workspace_id, &page.view_id, &thread.thread_id, &reply)
+    .await
+    .unwrap();
+  assert_eq!(comment.content, "Looks good to me");
+
+  // The range of a thread must not be empty
+  let err = guest
+    .api_client
+    .create_document_comment_thread(
+      workspace_id,
+      &page.view_id,
+      &CreateDocumentCommentThreadParams {
+        anchor: DocumentCommentAnchor {
+          block_id: "block_2".to_string(),
+          range_start: Some(5),
+          range_end: Some(5),
+          quoted_text: None,
+        },
+        content: "Typo".to_string(),
+        mentions: vec![],
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+}
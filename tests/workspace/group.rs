@@ -0,0 +1,158 @@
+use app_error::ErrorCode;
+use client_api_test::TestClient;
+use database_entity::dto::{AFAccessLevel, AFRole};
+use shared_entity::dto::group_dto::{CreateWorkspaceGroupParams, WorkspaceGroupMembersParams};
+use shared_entity::dto::view_permission_dto::GrantViewGroupPermissionParams;
+use shared_entity::dto::workspace_dto::{CreatePageParams, ViewLayout};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn group_page_permission_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let guest = TestClient::new_user_without_ws_conn().await;
+  let outsider = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &guest, AFRole::Guest)
+    .await
+    .unwrap();
+  let guest_uid = guest.uid().await;
+
+  // Only the owner can manage the groups.
+  let err = guest
+    .api_client
+    .create_workspace_group(
+      &workspace_id,
+      &CreateWorkspaceGroupParams {
+        name: "Designers".to_string(),
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+  let group = owner
+    .api_client
+    .create_workspace_group(
+      &workspace_id,
+      &CreateWorkspaceGroupParams {
+        name: "Designers".to_string(),
+      },
+    )
+    .await
+    .unwrap();
+  let err = owner
+    .api_client
+    .create_workspace_group(
+      &workspace_id,
+      &CreateWorkspaceGroupParams {
+        name: "Designers".to_string(),
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::RecordAlreadyExists);
+
+  // The members of a group must be members of the workspace.
+  let err = owner
+    .api_client
+    .add_workspace_group_members(
+      &workspace_id,
+      &group.group_id,
+      &WorkspaceGroupMembersParams {
+        emails: vec![outsider.email().await],
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+  owner
+    .api_client
+    .add_workspace_group_members(
+      &workspace_id,
+      &group.group_id,
+      &WorkspaceGroupMembersParams {
+        emails: vec![guest.email().await],
+      },
+    )
+    .await
+    .unwrap();
+  let members = guest
+    .api_client
+    .get_workspace_group_members(&workspace_id, &group.group_id)
+    .await
+    .unwrap();
+  assert_eq!(members.items.len(), 1);
+  assert_eq!(members.items[0].uid, guest_uid);
+  let groups = guest
+    .api_client
+    .get_workspace_groups(&workspace_id)
+    .await
+    .unwrap();
+  assert_eq!(groups.items.len(), 1);
+  assert_eq!(groups.items[0].member_count, 1);
+
+  let workspace_uuid = Uuid::parse_str(&workspace_id).unwrap();
+  let folder_view = owner
+    .api_client
+    .get_workspace_folder(&workspace_id, Some(2), None)
+    .await
+    .unwrap();
+  let general_space = folder_view
+    .children
+    .into_iter()
+    .find(|v| v.name == "General")
+    .unwrap();
+  let page = owner
+    .api_client
+    .create_workspace_page_view(
+      workspace_uuid,
+      &CreatePageParams {
+        parent_view_id: general_space.view_id.clone(),
+        layout: ViewLayout::Document,
+        name: Some("Design".to_string()),
+      },
+    )
+    .await
+    .unwrap();
+
+  // The members of the group get the access level granted to the group.
+  owner
+    .api_client
+    .grant_view_group_permission(
+      workspace_uuid,
+      &page.view_id,
+      &GrantViewGroupPermissionParams {
+        group_id: group.group_id,
+        access_level: AFAccessLevel::ReadAndWrite,
+      },
+    )
+    .await
+    .unwrap();
+  let permission = guest
+    .api_client
+    .get_effective_view_permission(workspace_uuid, &page.view_id, None)
+    .await
+    .unwrap();
+  assert_eq!(permission.access_level, AFAccessLevel::ReadAndWrite);
+  let permissions = owner
+    .api_client
+    .get_view_permissions(workspace_uuid, &page.view_id)
+    .await
+    .unwrap();
+  assert!(permissions.items.is_empty());
+  assert_eq!(permissions.groups.len(), 1);
+  assert_eq!(permissions.groups[0].group_id, group.group_id);
+
+  // Deleting the group drops the access level granted to it.
+  owner
+    .api_client
+    .delete_workspace_group(&workspace_id, &group.group_id)
+    .await
+    .unwrap();
+  let permission = guest
+    .api_client
+    .get_effective_view_permission(workspace_uuid, &page.view_id, None)
+    .await
+    .unwrap();
+  assert_eq!(permission.access_level, AFAccessLevel::ReadOnly);
+}
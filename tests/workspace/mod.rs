@@ -1,13 +1,25 @@
 mod access_request;
+mod api_token;
+mod audit_log;
+mod custom_role;
 mod default_user_workspace;
+mod document_comment;
 mod edit_workspace;
+mod group;
 mod import_test;
 mod invitation_crud;
 mod member_crud;
+mod notification;
+mod oauth;
 mod page_view;
 mod publish;
 mod published_data;
+mod scim;
+mod share_link;
 mod template;
+mod view_permission;
+mod webhook;
+mod workspace_activity;
 mod workspace_crud;
 mod workspace_folder;
 mod workspace_settings;
@@ -0,0 +1,311 @@
+use std::time::Duration;
+
+use client_api_test::TestClient;
+use collab_rt_entity::user::UserMessage;
+use database_entity::dto::AFRole;
+use shared_entity::dto::document_comment_dto::{
+  CreateDocumentCommentParams, CreateDocumentCommentThreadParams, DocumentCommentAnchor,
+};
+use shared_entity::dto::notification_dto::{
+  ActivityDigestFrequency, ActivityDigestSetting, MarkNotificationsReadParams, NotificationKind,
+  NotificationMute, NotificationSettings, QueryNotifications, ResetActivityDigestSettingParams,
+  UnmuteNotificationParams,
+};
+use shared_entity::dto::workspace_dto::{CreatePageParams, ViewLayout};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn comment_notification_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+  let workspace_id = Uuid::parse_str(&workspace_id).unwrap();
+  let owner_uid = owner.uid().await;
+  let member_uid = member.uid().await;
+
+  // The invitation was notified to the member, who already had an account
+  let notifications = member
+    .api_client
+    .get_notifications(&QueryNotifications::default())
+    .await
+    .unwrap();
+  let invitation = notifications
+    .notifications
+    .iter()
+    .find(|n| n.kind == NotificationKind::WorkspaceInvitation)
+    .unwrap();
+  assert_eq!(invitation.workspace_id, workspace_id);
+  assert_eq!(invitation.actor_uid, Some(owner_uid));
+  member
+    .api_client
+    .mark_notifications_read(&MarkNotificationsReadParams::default())
+    .await
+    .unwrap();
+
+  let folder_view = owner
+    .api_client
+    .get_workspace_folder(&workspace_id.to_string(), Some(2), None)
+    .await
+    .unwrap();
+  let general_space = folder_view
+    .children
+    .into_iter()
+    .find(|v| v.name == "General")
+    .unwrap();
+  let page = owner
+    .api_client
+    .create_workspace_page_view(
+      workspace_id,
+      &CreatePageParams {
+        parent_view_id: general_space.view_id.clone(),
+        layout: ViewLayout::Document,
+        name: Some("Roadmap".to_string()),
+      },
+    )
+    .await
+    .unwrap();
+  let page_id = Uuid::parse_str(&page.view_id).unwrap();
+
+  // The mentioned member is notified in realtime
+  let mut user_change_recv = member.ws_client.subscribe_user_changed();
+  let thread = owner
+    .api_client
+    .create_document_comment_thread(
+      workspace_id,
+      &page.view_id,
+      &CreateDocumentCommentThreadParams {
+        anchor: DocumentCommentAnchor {
+          block_id: "block_1".to_string(),
+          ..Default::default()
+        },
+        content: "Can you double check the dates?".to_string(),
+        mentions: vec![member_uid],
+      },
+    )
+    .await
+    .unwrap();
+  let change = tokio::time::timeout(Duration::from_secs(10), async {
+    loop {
+      if let UserMessage::Notification(change) = user_change_recv.recv().await.unwrap() {
+        return change;
+      }
+    }
+  })
+  .await
+  .unwrap();
+  assert_eq!(change.kind, NotificationKind::CommentMention as i16);
+  assert_eq!(change.view_id, Some(page.view_id.clone()));
+  assert_eq!(change.actor_uid, Some(owner_uid));
+
+  let notifications = member
+    .api_client
+    .get_notifications(&QueryNotifications::default())
+    .await
+    .unwrap();
+  assert_eq!(notifications.unread_count, 1);
+  assert!(!notifications.has_more);
+  let notification = &notifications.notifications[0];
+  assert_eq!(notification.kind, NotificationKind::CommentMention);
+  assert_eq!(notification.workspace_id, workspace_id);
+  assert_eq!(notification.view_id, Some(page_id));
+  assert_eq!(
+    notification.payload["preview"],
+    "Can you double check the dates?"
+  );
+  assert!(notification.read_at.is_none());
+  // The author isn't notified of its own comment
+  let owner_notifications = owner
+    .api_client
+    .get_notifications(&QueryNotifications::default())
+    .await
+    .unwrap();
+  assert!(owner_notifications
+    .notifications
+    .iter()
+    .all(|n| n.kind != NotificationKind::CommentMention));
+
+  // The creator of the thread is notified of the replies
+  member
+    .api_client
+    .create_document_comment(
+      workspace_id,
+      &page.view_id,
+      &thread.thread_id,
+      &CreateDocumentCommentParams {
+        content: "The dates are fine".to_string(),
+        mentions: vec![],
+      },
+    )
+    .await
+    .unwrap();
+  let owner_notifications = owner
+    .api_client
+    .get_notifications(&QueryNotifications {
+      workspace_id: Some(workspace_id),
+      unread_only: true,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+  let reply = owner_notifications
+    .notifications
+    .iter()
+    .find(|n| n.kind == NotificationKind::CommentReply)
+    .unwrap();
+  assert_eq!(reply.actor_uid, Some(member_uid));
+  assert_eq!(reply.payload["preview"], "The dates are fine");
+
+  // Marking as read
+  member
+    .api_client
+    .mark_notifications_read(&MarkNotificationsReadParams {
+      notification_ids: Some(vec![notification.notification_id]),
+      workspace_id: None,
+    })
+    .await
+    .unwrap();
+  let notifications = member
+    .api_client
+    .get_notifications(&QueryNotifications::default())
+    .await
+    .unwrap();
+  assert_eq!(notifications.unread_count, 0);
+  assert!(notifications.notifications[0].read_at.is_some());
+  let unread = member
+    .api_client
+    .get_notifications(&QueryNotifications {
+      unread_only: true,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+  assert!(unread.notifications.is_empty());
+
+  // A muted page doesn't notify
+  let mute = NotificationMute {
+    workspace_id,
+    view_id: Some(page_id),
+    muted_until: None,
+  };
+  member.api_client.mute_notifications(&mute).await.unwrap();
+  let mutes = member.api_client.get_notification_mutes().await.unwrap();
+  assert_eq!(mutes.items, vec![mute]);
+  owner
+    .api_client
+    .create_document_comment(
+      workspace_id,
+      &page.view_id,
+      &thread.thread_id,
+      &CreateDocumentCommentParams {
+        content: "Thanks!".to_string(),
+        mentions: vec![member_uid],
+      },
+    )
+    .await
+    .unwrap();
+  let notifications = member
+    .api_client
+    .get_notifications(&QueryNotifications::default())
+    .await
+    .unwrap();
+  assert_eq!(notifications.notifications.len(), 2);
+  assert_eq!(notifications.unread_count, 0);
+
+  member
+    .api_client
+    .unmute_notifications(&UnmuteNotificationParams {
+      workspace_id,
+      view_id: Some(page_id),
+    })
+    .await
+    .unwrap();
+  assert!(member
+    .api_client
+    .get_notification_mutes()
+    .await
+    .unwrap()
+    .items
+    .is_empty());
+}
+
+#[tokio::test]
+async fn notification_settings_test() {
+  let client = TestClient::new_user_without_ws_conn().await;
+  let settings = client.api_client.get_notification_settings().await.unwrap();
+  assert!(settings.email_digest_enabled);
+
+  client
+    .api_client
+    .update_notification_settings(&NotificationSettings {
+      email_digest_enabled: false,
+    })
+    .await
+    .unwrap();
+  let settings = client.api_client.get_notification_settings().await.unwrap();
+  assert!(!settings.email_digest_enabled);
+}
+
+#[tokio::test]
+async fn activity_digest_settings_test() {
+  let client = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = Uuid::parse_str(&client.workspace_id().await).unwrap();
+  let settings = client
+    .api_client
+    .get_activity_digest_settings()
+    .await
+    .unwrap();
+  assert_eq!(settings.frequency, ActivityDigestFrequency::Weekly);
+  assert!(settings.workspaces.is_empty());
+
+  client
+    .api_client
+    .update_activity_digest_setting(&ActivityDigestSetting {
+      workspace_id: None,
+      frequency: ActivityDigestFrequency::Daily,
+    })
+    .await
+    .unwrap();
+  client
+    .api_client
+    .update_activity_digest_setting(&ActivityDigestSetting {
+      workspace_id: Some(workspace_id),
+      frequency: ActivityDigestFrequency::Off,
+    })
+    .await
+    .unwrap();
+  let settings = client
+    .api_client
+    .get_activity_digest_settings()
+    .await
+    .unwrap();
+  assert_eq!(settings.frequency, ActivityDigestFrequency::Daily);
+  assert_eq!(settings.workspaces.len(), 1);
+  assert_eq!(settings.workspaces[0].workspace_id, workspace_id);
+  assert_eq!(
+    settings.workspaces[0].frequency,
+    ActivityDigestFrequency::Off
+  );
+
+  let params = ResetActivityDigestSettingParams { workspace_id };
+  client
+    .api_client
+    .reset_activity_digest_setting(&params)
+    .await
+    .unwrap();
+  let settings = client
+    .api_client
+    .get_activity_digest_settings()
+    .await
+    .unwrap();
+  assert_eq!(settings.frequency, ActivityDigestFrequency::Daily);
+  assert!(settings.workspaces.is_empty());
+  assert!(client
+    .api_client
+    .reset_activity_digest_setting(&params)
+    .await
+    .is_err());
+}
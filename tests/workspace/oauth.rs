@@ -0,0 +1,273 @@
+use app_error::ErrorCode;
+use client_api::entity::AFWorkspaceMember;
+use client_api_test::TestClient;
+use shared_entity::dto::oauth_dto::{
+  CreateOAuthClientParams, OAuthAuthorizeParams, OAuthTokenParams,
+};
+use shared_entity::response::{AppResponse, AppResponseError};
+use uuid::Uuid;
+
+const REDIRECT_URI: &str = "https://example.com/callback";
+// Example pair from RFC 7636 appendix B.
+const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mJ0M2Z5nt0btHa6wwVz3wHsvx69AAo";
+const CODE_CHALLENGE: &str = "E9cPqTyuUI3DOy6D5T6Zk1E4EYi9QXS0tKTfPqGW1aE";
+
+async fn get_members_with_access_token(
+  client: &TestClient,
+  workspace_id: &str,
+  token: &str,
+) -> Result<Vec<AFWorkspaceMember>, AppResponseError> {
+  let url = format!(
+    "{}/api/workspace/{}/member",
+    client.api_client.base_url, workspace_id
+  );
+  let resp = reqwest::Client::new()
+    .get(url)
+    .bearer_auth(token)
+    .send()
+    .await
+    .unwrap();
+  AppResponse::<Vec<AFWorkspaceMember>>::from_response(resp)
+    .await?
+    .into_data()
+}
+
+fn authorize_params(client_id: &str, workspace_id: &str) -> OAuthAuthorizeParams {
+  OAuthAuthorizeParams {
+    client_id: client_id.to_string(),
+    redirect_uri: REDIRECT_URI.to_string(),
+    workspace_id: Uuid::parse_str(workspace_id).unwrap(),
+    scope: "workspace:read".to_string(),
+    code_challenge: Some(CODE_CHALLENGE.to_string()),
+    code_challenge_method: Some("S256".to_string()),
+  }
+}
+
+fn token_params(client_id: &str, code: &str, code_verifier: &str) -> OAuthTokenParams {
+  OAuthTokenParams {
+    grant_type: "authorization_code".to_string(),
+    code: code.to_string(),
+    redirect_uri: REDIRECT_URI.to_string(),
+    client_id: client_id.to_string(),
+    code_verifier: Some(code_verifier.to_string()),
+    ..Default::default()
+  }
+}
+
+fn refresh_params(client_id: &str, refresh_token: &str) -> OAuthTokenParams {
+  OAuthTokenParams {
+    grant_type: "refresh_token".to_string(),
+    client_id: client_id.to_string(),
+    refresh_token: Some(refresh_token.to_string()),
+    ..Default::default()
+  }
+}
+
+#[tokio::test]
+async fn oauth_authorization_code_flow_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  let created = owner
+    .api_client
+    .create_oauth_client(&CreateOAuthClientParams {
+      name: "Calendar sync".to_string(),
+      redirect_uris: vec![REDIRECT_URI.to_string()],
+      confidential: false,
+    })
+    .await
+    .unwrap();
+  assert!(created.client_secret.is_none());
+  let client_id = created.client.client_id;
+
+  let code = owner
+    .api_client
+    .authorize_oauth_client(&authorize_params(&client_id, &workspace_id))
+    .await
+    .unwrap()
+    .code;
+  let token = owner
+    .api_client
+    .exchange_oauth_code(&token_params(&client_id, &code, CODE_VERIFIER))
+    .await
+    .unwrap();
+  assert_eq!(token.scope, "workspace:read");
+  assert_eq!(token.workspace_id.to_string(), workspace_id);
+  assert!(token.expires_in > 0);
+
+  let members = get_members_with_access_token(&owner, &workspace_id, &token.access_token)
+    .await
+    .unwrap();
+  assert_eq!(members[0].email, owner.email().await);
+
+  // The token shows up with the other API tokens of the workspace.
+  let tokens = owner
+    .api_client
+    .get_api_tokens(&workspace_id)
+    .await
+    .unwrap();
+  assert_eq!(tokens.items.len(), 1);
+  assert_eq!(
+    tokens.items[0].client_id.as_deref(),
+    Some(client_id.as_str())
+  );
+  assert!(tokens.items[0].expires_at.is_some());
+
+  // Another client can't use the refresh token, nor revoke it by trying.
+  let other_client_id = owner
+    .api_client
+    .create_oauth_client(&CreateOAuthClientParams {
+      name: "Other".to_string(),
+      redirect_uris: vec![REDIRECT_URI.to_string()],
+      confidential: false,
+    })
+    .await
+    .unwrap()
+    .client
+    .client_id;
+  let err = owner
+    .api_client
+    .exchange_oauth_code(&refresh_params(&other_client_id, &token.refresh_token))
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+
+  // The refresh token is exchanged for a new pair of tokens, which revokes the old ones.
+  let refreshed = owner
+    .api_client
+    .exchange_oauth_code(&refresh_params(&client_id, &token.refresh_token))
+    .await
+    .unwrap();
+  assert_ne!(refreshed.access_token, token.access_token);
+  let err = get_members_with_access_token(&owner, &workspace_id, &token.access_token)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::UserUnAuthorized);
+  let err = owner
+    .api_client
+    .exchange_oauth_code(&refresh_params(&client_id, &token.refresh_token))
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+  let token = refreshed;
+  get_members_with_access_token(&owner, &workspace_id, &token.access_token)
+    .await
+    .unwrap();
+
+  // A code can only be exchanged once.
+  let err = owner
+    .api_client
+    .exchange_oauth_code(&token_params(&client_id, &code, CODE_VERIFIER))
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+
+  // Deleting the client revokes its tokens.
+  owner
+    .api_client
+    .delete_oauth_client(&client_id)
+    .await
+    .unwrap();
+  let err = get_members_with_access_token(&owner, &workspace_id, &token.access_token)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::UserUnAuthorized);
+}
+
+#[tokio::test]
+async fn oauth_client_redirect_uri_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  for redirect_uri in [
+    "https://example.com/callback",
+    "http://localhost:8080/callback",
+    "http://127.0.0.1:53682/callback",
+    "http://[::1]/callback",
+  ] {
+    owner
+      .api_client
+      .create_oauth_client(&CreateOAuthClientParams {
+        name: "Calendar sync".to_string(),
+        redirect_uris: vec![redirect_uri.to_string()],
+        confidential: false,
+      })
+      .await
+      .unwrap();
+  }
+  for redirect_uri in [
+    "http://example.com/callback",
+    "javascript:alert(1)",
+    "data:text/html,<script>alert(1)</script>",
+    "https://example.com/callback#code",
+  ] {
+    let err = owner
+      .api_client
+      .create_oauth_client(&CreateOAuthClientParams {
+        name: "Calendar sync".to_string(),
+        redirect_uris: vec![redirect_uri.to_string()],
+        confidential: false,
+      })
+      .await
+      .unwrap_err();
+    assert_eq!(err.code, ErrorCode::InvalidRequest, "{}", redirect_uri);
+  }
+}
+
+#[tokio::test]
+async fn oauth_invalid_authorization_request_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  let client_id = owner
+    .api_client
+    .create_oauth_client(&CreateOAuthClientParams {
+      name: "Calendar sync".to_string(),
+      redirect_uris: vec![REDIRECT_URI.to_string()],
+      confidential: false,
+    })
+    .await
+    .unwrap()
+    .client
+    .client_id;
+
+  // Unregistered redirect uri.
+  let mut params = authorize_params(&client_id, &workspace_id);
+  params.redirect_uri = "https://attacker.example.com/callback".to_string();
+  let err = owner
+    .api_client
+    .authorize_oauth_client(&params)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+
+  // Public clients must use PKCE.
+  let mut params = authorize_params(&client_id, &workspace_id);
+  params.code_challenge = None;
+  params.code_challenge_method = None;
+  let err = owner
+    .api_client
+    .authorize_oauth_client(&params)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+
+  // The user must be a member of the workspace.
+  let other = TestClient::new_user_without_ws_conn().await;
+  let err = other
+    .api_client
+    .authorize_oauth_client(&authorize_params(&client_id, &workspace_id))
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+
+  // The verifier must match the challenge.
+  let code = owner
+    .api_client
+    .authorize_oauth_client(&authorize_params(&client_id, &workspace_id))
+    .await
+    .unwrap()
+    .code;
+  let err = owner
+    .api_client
+    .exchange_oauth_code(&token_params(&client_id, &code, "wrong-verifier"))
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+}
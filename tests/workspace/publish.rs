@@ -16,13 +16,21 @@ use collab_database::workspace_database::{NoPersistenceDatabaseCollabService, Wo
 use collab_document::document::Document;
 use collab_entity::CollabType;
 use collab_folder::{CollabOrigin, Folder, UserId};
+use futures_util::StreamExt;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use shared_entity::dto::publish_dto::PublishDatabaseData;
+use shared_entity::dto::comment_moderation_dto::{
+  BanCommenterParams, CommentModeration, PublishedViewCommentSettings,
+};
+use shared_entity::dto::publish_dto::{
+  PublishDatabaseData, PublishedViewUpdate, QueryPublishedViewAnalytics,
+};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::thread::sleep;
 use std::time::Duration;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
 use crate::workspace::published_data::{self};
@@ -359,6 +367,7 @@ async fn test_publish_doc() {
           view_id: view_id_1,
           // publish_name_2 already exists
           publish_name: Some(publish_name_2.to_string()),
+          ..Default::default()
         }],
       )
       .await
@@ -373,6 +382,7 @@ async fn test_publish_doc() {
       &[PatchPublishedCollab {
         view_id: view_id_1,
         publish_name: Some(new_publish_name_1.to_string()),
+        ..Default::default()
       }],
     )
     .await
@@ -400,6 +410,7 @@ async fn test_publish_doc() {
       &[PatchPublishedCollab {
         view_id: view_id_1,
         publish_name: Some(publish_name_1.to_string()),
+        ..Default::default()
       }],
     )
     .await
@@ -1798,8 +1809,567 @@ async fn test_republish_patch() {
     &[PatchPublishedCollab {
       view_id: view_id_2,
       publish_name: Some(publish_name.to_string()),
+      ..Default::default()
     }],
   )
   .await
   .unwrap();
 }
+
+#[tokio::test]
+async fn published_view_rendered_as_html() {
+  let client = TestClient::new_user().await;
+  let workspace_id = client.workspace_id().await;
+
+  let doc_view_id = uuid::Uuid::new_v4();
+  let grid_view_id = uuid::Uuid::new_v4();
+  client
+    .publish_collabs(
+      &workspace_id,
+      vec![
+        (
+          doc_view_id,
+          published_data::DOC_1_META,
+          published_data::DOC_1_DOC_STATE_HEX,
+        ),
+        (
+          grid_view_id,
+          published_data::GRID_1_META,
+          published_data::GRID_1_DB_DATA,
+        ),
+      ],
+    )
+    .await;
+
+  let guest_client = localhost_client();
+  let doc_info = guest_client
+    .get_published_collab_info(&doc_view_id)
+    .await
+    .unwrap();
+  let html = guest_client
+    .get_published_view_html(&doc_info.namespace, &doc_info.publish_name)
+    .await
+    .unwrap();
+  assert!(html.contains("<title>doc1</title>"), "{}", html);
+  assert!(html.contains("property=\"og:title\""), "{}", html);
+
+  let grid_info = guest_client
+    .get_published_collab_info(&grid_view_id)
+    .await
+    .unwrap();
+  let html = guest_client
+    .get_published_view_html(&grid_info.namespace, &grid_info.publish_name)
+    .await
+    .unwrap();
+  assert!(html.contains("<title>grid1</title>"), "{}", html);
+  assert!(html.contains("<table>"), "{}", html);
+
+  let err = guest_client
+    .get_published_view_html(&doc_info.namespace, "does-not-exist")
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::RecordNotFound);
+}
+
+#[tokio::test]
+async fn live_published_view_notifies_readers() {
+  let client = TestClient::new_user().await;
+  let workspace_id = client.workspace_id().await;
+
+  let doc_view_id = uuid::Uuid::new_v4();
+  let grid_view_id = uuid::Uuid::new_v4();
+  client
+    .publish_collabs(
+      &workspace_id,
+      vec![
+        (
+          doc_view_id,
+          published_data::DOC_1_META,
+          published_data::DOC_1_DOC_STATE_HEX,
+        ),
+        (
+          grid_view_id,
+          published_data::GRID_1_META,
+          published_data::GRID_1_DB_DATA,
+        ),
+      ],
+    )
+    .await;
+
+  // Only documents can be published live
+  let err = client
+    .api_client
+    .patch_published_collabs(
+      &workspace_id,
+      &[PatchPublishedCollab {
+        view_id: grid_view_id,
+        live: Some(true),
+        ..Default::default()
+      }],
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest, "{:?}", err);
+
+  let guest_client = localhost_client();
+  let publish_info = guest_client
+    .get_published_collab_info(&doc_view_id)
+    .await
+    .unwrap();
+  let ws_url =
+    guest_client.published_view_ws_url(&publish_info.namespace, &publish_info.publish_name);
+
+  // The view is not live yet
+  assert!(connect_async(&ws_url).await.is_err());
+
+  client
+    .api_client
+    .patch_published_collabs(
+      &workspace_id,
+      &[PatchPublishedCollab {
+        view_id: doc_view_id,
+        live: Some(true),
+        ..Default::default()
+      }],
+    )
+    .await
+    .unwrap();
+  let (mut reader, _) = connect_async(&ws_url).await.unwrap();
+
+  // Republishing keeps the view live and notifies the readers
+  client
+    .publish_collabs(
+      &workspace_id,
+      vec![(
+        doc_view_id,
+        published_data::DOC_1_META,
+        published_data::DOC_1_DOC_STATE_HEX,
+      )],
+    )
+    .await;
+  let update = tokio::time::timeout(Duration::from_secs(10), async {
+    loop {
+      match reader.next().await {
+        Some(Ok(Message::Text(text))) => {
+          break serde_json::from_str::<PublishedViewUpdate>(&text).unwrap()
+        },
+        Some(Ok(_)) => continue,
+        other => panic!("unexpected message: {:?}", other),
+      }
+    }
+  })
+  .await
+  .unwrap();
+  assert_eq!(update.view_id, doc_view_id);
+
+  // A live view restricted with a password can't be followed without it
+  client
+    .api_client
+    .patch_published_collabs(
+      &workspace_id,
+      &[PatchPublishedCollab {
+        view_id: doc_view_id,
+        password: Some(Some("secret".to_string())),
+        ..Default::default()
+      }],
+    )
+    .await
+    .unwrap();
+  assert!(connect_async(&ws_url).await.is_err());
+}
+
+#[tokio::test]
+async fn published_view_access_restrictions() {
+  let client = TestClient::new_user().await;
+  let workspace_id = client.workspace_id().await;
+
+  let doc_view_id = uuid::Uuid::new_v4();
+  client
+    .publish_collabs(
+      &workspace_id,
+      vec![(
+        doc_view_id,
+        published_data::DOC_1_META,
+        published_data::DOC_1_DOC_STATE_HEX,
+      )],
+    )
+    .await;
+  let guest_client = localhost_client();
+  let publish_info = guest_client
+    .get_published_collab_info(&doc_view_id)
+    .await
+    .unwrap();
+  let (namespace, publish_name) = (publish_info.namespace, publish_info.publish_name);
+
+  // Password
+  client
+    .api_client
+    .patch_published_collabs(
+      &workspace_id,
+      &[PatchPublishedCollab {
+        view_id: doc_view_id,
+        password: Some(Some("secret".to_string())),
+        ..Default::default()
+      }],
+    )
+    .await
+    .unwrap();
+  let err = guest_client
+    .get_published_collab_blob(&namespace, &publish_name)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidPassword, "{:?}", err);
+  let err = guest_client
+    .get_published_collab_blob_with_password(&namespace, &publish_name, Some("wrong"))
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidPassword, "{:?}", err);
+  guest_client
+    .get_published_collab_blob_with_password(&namespace, &publish_name, Some("secret"))
+    .await
+    .unwrap();
+
+  let settings = client
+    .api_client
+    .list_published_views(&workspace_id)
+    .await
+    .unwrap()
+    .into_iter()
+    .find(|view| view.info.view_id == doc_view_id)
+    .unwrap()
+    .settings;
+  assert!(settings.has_password);
+
+  // Republishing keeps the restrictions
+  client
+    .publish_collabs(
+      &workspace_id,
+      vec![(
+        doc_view_id,
+        published_data::DOC_1_META,
+        published_data::DOC_1_DOC_STATE_HEX,
+      )],
+    )
+    .await;
+  let err = guest_client
+    .get_published_view_html(&namespace, &publish_name)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidPassword, "{:?}", err);
+
+  // Expiry must be in the future
+  let err = client
+    .api_client
+    .patch_published_collabs(
+      &workspace_id,
+      &[PatchPublishedCollab {
+        view_id: doc_view_id,
+        expires_at: Some(Some(chrono::Utc::now() - chrono::Duration::hours(1))),
+        ..Default::default()
+      }],
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest, "{:?}", err);
+
+  // Email domains: visitors must sign in, and only users of the allowed domains can read the view
+  client
+    .api_client
+    .patch_published_collabs(
+      &workspace_id,
+      &[PatchPublishedCollab {
+        view_id: doc_view_id,
+        password: Some(None),
+        allowed_email_domains: Some(vec!["@Example.org".to_string()]),
+        ..Default::default()
+      }],
+    )
+    .await
+    .unwrap();
+  let err = guest_client
+    .get_published_collab_blob(&namespace, &publish_name)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotLoggedIn, "{:?}", err);
+  let other_client = TestClient::new_user().await;
+  let err = other_client
+    .api_client
+    .get_published_collab_blob(&namespace, &publish_name)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::UserUnAuthorized, "{:?}", err);
+
+  client
+    .api_client
+    .patch_published_collabs(
+      &workspace_id,
+      &[PatchPublishedCollab {
+        view_id: doc_view_id,
+        allowed_email_domains: Some(vec!["appflowy.io".to_string()]),
+        ..Default::default()
+      }],
+    )
+    .await
+    .unwrap();
+  other_client
+    .api_client
+    .get_published_collab_blob(&namespace, &publish_name)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn published_view_analytics() {
+  let client = TestClient::new_user().await;
+  let workspace_id = client.workspace_id().await;
+
+  let doc_view_id = uuid::Uuid::new_v4();
+  client
+    .publish_collabs(
+      &workspace_id,
+      vec![(
+        doc_view_id,
+        published_data::DOC_1_META,
+        published_data::DOC_1_DOC_STATE_HEX,
+      )],
+    )
+    .await;
+  let guest_client = localhost_client();
+  let publish_info = guest_client
+    .get_published_collab_info(&doc_view_id)
+    .await
+    .unwrap();
+  for _ in 0..3 {
+    guest_client
+      .get_published_collab::<serde_json::Value>(
+        &publish_info.namespace,
+        &publish_info.publish_name,
+      )
+      .await
+      .unwrap();
+  }
+
+  // The reads are written to the database in batches
+  let query = QueryPublishedViewAnalytics {
+    view_id: Some(doc_view_id),
+    ..Default::default()
+  };
+  let analytics = tokio::time::timeout(Duration::from_secs(30), async {
+    loop {
+      let analytics = client
+        .api_client
+        .get_published_view_analytics(&workspace_id, &query)
+        .await
+        .unwrap();
+      if !analytics.is_empty() {
+        break analytics;
+      }
+      tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+  })
+  .await
+  .unwrap();
+  assert_eq!(analytics.len(), 1);
+  assert_eq!(analytics[0].view_id, doc_view_id);
+  assert_eq!(analytics[0].view_count, 3);
+  assert_eq!(analytics[0].unique_visitor_count, 1);
+  assert_eq!(analytics[0].daily.len(), 1);
+  assert_eq!(analytics[0].referrers[0].referrer, None);
+
+  // Only the owners can read the analytics
+  let other_client = TestClient::new_user().await;
+  let result = other_client
+    .api_client
+    .get_published_view_analytics(&workspace_id, &query)
+    .await;
+  assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn published_view_comment_moderation() {
+  let client = TestClient::new_user().await;
+  let workspace_id = client.workspace_id().await;
+  let view_id = uuid::Uuid::new_v4();
+  client
+    .publish_collabs(
+      &workspace_id,
+      vec![(
+        view_id,
+        published_data::DOC_1_META,
+        published_data::DOC_1_DOC_STATE_HEX,
+      )],
+    )
+    .await;
+  let commenter = TestClient::new_user().await;
+  let commenter_uuid = commenter.api_client.get_profile().await.unwrap().uuid;
+  let guest_client = localhost_client();
+
+  // Comments wait for approval
+  client
+    .api_client
+    .update_published_view_comment_settings(
+      &view_id,
+      &PublishedViewCommentSettings {
+        comments_enabled: true,
+        require_approval: true,
+      },
+    )
+    .await
+    .unwrap();
+  commenter
+    .api_client
+    .create_comment_on_published_view(&view_id, "first comment", &None)
+    .await
+    .unwrap();
+  let comments = guest_client
+    .get_published_view_comments(&view_id)
+    .await
+    .unwrap()
+    .comments;
+  assert!(comments.is_empty());
+  let comments = commenter
+    .api_client
+    .get_published_view_comments(&view_id)
+    .await
+    .unwrap()
+    .comments;
+  assert_eq!(comments.len(), 1);
+  assert!(comments[0].is_pending_approval);
+  client
+    .api_client
+    .approve_comment_on_published_view(&view_id, &comments[0].comment_id)
+    .await
+    .unwrap();
+  let comments = guest_client
+    .get_published_view_comments(&view_id)
+    .await
+    .unwrap()
+    .comments;
+  assert_eq!(comments.len(), 1);
+  assert!(!comments[0].is_pending_approval);
+  client
+    .api_client
+    .update_published_view_comment_settings(&view_id, &PublishedViewCommentSettings::default())
+    .await
+    .unwrap();
+
+  // Blocked keywords and rate limit
+  let moderation = client
+    .api_client
+    .update_workspace_comment_moderation(
+      &workspace_id,
+      &CommentModeration {
+        blocked_keywords: vec![" Spam ".to_string()],
+        max_comments_per_hour: Some(2),
+      },
+    )
+    .await
+    .unwrap();
+  assert_eq!(moderation.blocked_keywords, vec!["spam".to_string()]);
+  let err = commenter
+    .api_client
+    .create_comment_on_published_view(&view_id, "buy SPAM now", &None)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest, "{:?}", err);
+  commenter
+    .api_client
+    .create_comment_on_published_view(&view_id, "second comment", &None)
+    .await
+    .unwrap();
+  let err = commenter
+    .api_client
+    .create_comment_on_published_view(&view_id, "third comment", &None)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::TooManyRequests, "{:?}", err);
+  // The rules don't apply to the moderators
+  client
+    .api_client
+    .create_comment_on_published_view(&view_id, "spam from the owner", &None)
+    .await
+    .unwrap();
+  client
+    .api_client
+    .update_workspace_comment_moderation(&workspace_id, &CommentModeration::default())
+    .await
+    .unwrap();
+
+  // Banned users can't comment, their comments can be deleted along
+  client
+    .api_client
+    .ban_commenter(
+      &workspace_id,
+      &commenter_uuid,
+      &BanCommenterParams {
+        reason: Some("spam".to_string()),
+        delete_comments: true,
+      },
+    )
+    .await
+    .unwrap();
+  let bans = client
+    .api_client
+    .list_banned_commenters(&workspace_id)
+    .await
+    .unwrap();
+  assert_eq!(bans.len(), 1);
+  assert_eq!(bans[0].user.uuid, commenter_uuid);
+  let err = commenter
+    .api_client
+    .create_comment_on_published_view(&view_id, "comment while banned", &None)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::UserUnAuthorized, "{:?}", err);
+  let comments = guest_client
+    .get_published_view_comments(&view_id)
+    .await
+    .unwrap()
+    .comments;
+  assert!(comments
+    .iter()
+    .filter(|comment| comment.user.as_ref().map(|user| user.uuid) == Some(commenter_uuid))
+    .all(|comment| comment.is_deleted));
+  client
+    .api_client
+    .unban_commenter(&workspace_id, &commenter_uuid)
+    .await
+    .unwrap();
+  commenter
+    .api_client
+    .create_comment_on_published_view(&view_id, "comment after the ban", &None)
+    .await
+    .unwrap();
+
+  // Disabled comments
+  client
+    .api_client
+    .update_published_view_comment_settings(
+      &view_id,
+      &PublishedViewCommentSettings {
+        comments_enabled: false,
+        require_approval: false,
+      },
+    )
+    .await
+    .unwrap();
+  let settings = guest_client
+    .get_published_view_comment_settings(&view_id)
+    .await
+    .unwrap();
+  assert!(!settings.comments_enabled);
+  let err = commenter
+    .api_client
+    .create_comment_on_published_view(&view_id, "comment on a closed view", &None)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest, "{:?}", err);
+
+  // Only the moderators can change the settings
+  let err = commenter
+    .api_client
+    .update_published_view_comment_settings(&view_id, &PublishedViewCommentSettings::default())
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::UserUnAuthorized, "{:?}", err);
+}
@@ -0,0 +1,159 @@
+use app_error::ErrorCode;
+use client_api_test::TestClient;
+use database_entity::dto::AFWorkspaceInvitationStatus;
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+use shared_entity::dto::api_token_dto::{ApiTokenScope, CreateApiTokenParams};
+use shared_entity::dto::scim_dto::{ScimListResponse, ScimUser};
+use shared_entity::response::AppResponse;
+
+async fn create_api_token(client: &TestClient, workspace_id: &str, scope: ApiTokenScope) -> String {
+  client
+    .api_client
+    .create_api_token(
+      workspace_id,
+      &CreateApiTokenParams {
+        name: "identity provider".to_string(),
+        scope,
+        expires_at: None,
+      },
+    )
+    .await
+    .unwrap()
+    .token
+}
+
+async fn send_scim_request(
+  client: &TestClient,
+  method: Method,
+  path: &str,
+  token: &str,
+  body: Option<serde_json::Value>,
+) -> reqwest::Response {
+  let url = format!("{}{}", client.api_client.base_url, path);
+  let mut request = reqwest::Client::new()
+    .request(method, url)
+    .bearer_auth(token);
+  if let Some(body) = body {
+    request = request.json(&body);
+  }
+  request.send().await.unwrap()
+}
+
+#[tokio::test]
+async fn scim_provision_and_deprovision_user_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let employee = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  let token = create_api_token(&owner, &workspace_id, ApiTokenScope::Admin).await;
+  let employee_email = employee.email().await;
+  let users_path = format!("/api/scim/v2/{}/Users", workspace_id);
+
+  // Provisioning an existing user invites it to the workspace.
+  let resp = send_scim_request(
+    &owner,
+    Method::POST,
+    &users_path,
+    &token,
+    Some(json!({
+      "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+      "externalId": "idp-employee",
+      "userName": employee_email,
+      "active": true,
+    })),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::CREATED);
+  let user: ScimUser = resp.json().await.unwrap();
+  assert!(!user.active);
+  assert_eq!(user.external_id.as_deref(), Some("idp-employee"));
+  let members = owner
+    .api_client
+    .get_workspace_members(&workspace_id)
+    .await
+    .unwrap();
+  assert!(!members.iter().any(|m| m.email == employee_email));
+
+  // The user joins the workspace once it accepts the invitation.
+  let invitations = employee
+    .api_client
+    .list_workspace_invitations(Some(AFWorkspaceInvitationStatus::Pending))
+    .await
+    .unwrap();
+  let invitation = invitations
+    .iter()
+    .find(|inv| inv.workspace_id.to_string() == workspace_id)
+    .unwrap();
+  employee
+    .api_client
+    .accept_workspace_invitation(&invitation.invite_id.to_string())
+    .await
+    .unwrap();
+  let user_path = format!("{}/{}", users_path, user.id);
+  let resp = send_scim_request(&owner, Method::GET, &user_path, &token, None).await;
+  let user: ScimUser = resp.json().await.unwrap();
+  assert!(user.active);
+
+  // The same user can't be provisioned twice.
+  let resp = send_scim_request(
+    &owner,
+    Method::POST,
+    &users_path,
+    &token,
+    Some(json!({ "userName": employee_email })),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+  let filter_path = format!("{}?filter=externalId%20eq%20%22idp-employee%22", users_path);
+  let resp = send_scim_request(&owner, Method::GET, &filter_path, &token, None).await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let list: ScimListResponse<ScimUser> = resp.json().await.unwrap();
+  assert_eq!(list.total_results, 1);
+  assert_eq!(list.resources[0].id, user.id);
+
+  // Deactivating the user removes it from the workspace, but it is still listed.
+  let resp = send_scim_request(
+    &owner,
+    Method::PATCH,
+    &user_path,
+    &token,
+    Some(json!({
+      "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+      "Operations": [{ "op": "replace", "path": "active", "value": false }],
+    })),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let user: ScimUser = resp.json().await.unwrap();
+  assert!(!user.active);
+  let members = owner
+    .api_client
+    .get_workspace_members(&workspace_id)
+    .await
+    .unwrap();
+  assert!(!members.iter().any(|m| m.email == employee_email));
+  let resp = send_scim_request(&owner, Method::GET, &user_path, &token, None).await;
+  let user: ScimUser = resp.json().await.unwrap();
+  assert!(!user.active);
+
+  // The owner of the workspace can't be deprovisioned.
+  let owner_path = format!("{}/{}", users_path, owner.uid().await);
+  let resp = send_scim_request(&owner, Method::DELETE, &owner_path, &token, None).await;
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn scim_requires_admin_api_token_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  let token = create_api_token(&owner, &workspace_id, ApiTokenScope::Write).await;
+  let path = format!("/api/scim/v2/{}/Users", workspace_id);
+  let resp = send_scim_request(&owner, Method::GET, &path, &token, None).await;
+  let err = AppResponse::<()>::from_response(resp)
+    .await
+    .unwrap()
+    .into_error()
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+}
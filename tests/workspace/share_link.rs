@@ -0,0 +1,140 @@
+use app_error::ErrorCode;
+use chrono::{Duration, Utc};
+use client_api_test::TestClient;
+use database_entity::dto::{AFAccessLevel, AFRole};
+use shared_entity::dto::share_link_dto::{CreateShareLinkParams, JoinShareLinkParams};
+use shared_entity::dto::workspace_dto::{CreatePageParams, ViewLayout};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn share_link_with_password_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let guest = TestClient::new_user_without_ws_conn().await;
+  let reviewer = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &guest, AFRole::Guest)
+    .await
+    .unwrap();
+  let workspace_uuid = Uuid::parse_str(&workspace_id).unwrap();
+  let folder_view = owner
+    .api_client
+    .get_workspace_folder(&workspace_id, Some(2), None)
+    .await
+    .unwrap();
+  let general_space = folder_view
+    .children
+    .into_iter()
+    .find(|v| v.name == "General")
+    .unwrap();
+  let page = owner
+    .api_client
+    .create_workspace_page_view(
+      workspace_uuid,
+      &CreatePageParams {
+        parent_view_id: general_space.view_id.clone(),
+        layout: ViewLayout::Document,
+        name: Some("Draft".to_string()),
+      },
+    )
+    .await
+    .unwrap();
+
+  // Full access can't be shared, and sharing requires full access to the page.
+  let err = owner
+    .api_client
+    .create_share_link(
+      workspace_uuid,
+      &page.view_id,
+      &CreateShareLinkParams {
+        access_level: AFAccessLevel::FullAccess,
+        password: None,
+        expires_at: None,
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+  let params = CreateShareLinkParams {
+    access_level: AFAccessLevel::ReadAndComment,
+    password: Some("secret".to_string()),
+    expires_at: Some(Utc::now() + Duration::days(1)),
+  };
+  let err = guest
+    .api_client
+    .create_share_link(workspace_uuid, &page.view_id, &params)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+  let created = owner
+    .api_client
+    .create_share_link(workspace_uuid, &page.view_id, &params)
+    .await
+    .unwrap();
+  assert!(created.token.starts_with(&created.share_link.token_prefix));
+  assert!(created.share_link.has_password);
+  let links = owner
+    .api_client
+    .get_share_links(workspace_uuid, &page.view_id)
+    .await
+    .unwrap();
+  assert_eq!(links.items.len(), 1);
+
+  // The page can be read without login, with the password of the link.
+  let err = reviewer
+    .api_client
+    .get_shared_page(&created.token, None)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidPassword);
+  let err = reviewer
+    .api_client
+    .get_shared_page(&created.token, Some("wrong"))
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidPassword);
+  let shared = reviewer
+    .api_client
+    .get_shared_page(&created.token, Some("secret"))
+    .await
+    .unwrap();
+  assert_eq!(shared.page.view.view_id, page.view_id);
+  assert_eq!(shared.access.access_level, AFAccessLevel::ReadAndComment);
+
+  let access = reviewer
+    .api_client
+    .join_share_link(
+      &created.token,
+      &JoinShareLinkParams {
+        password: Some("secret".to_string()),
+      },
+    )
+    .await
+    .unwrap();
+  assert_eq!(access.workspace_id, workspace_uuid);
+  assert_eq!(access.view_id.to_string(), page.view_id);
+
+  // A revoked link can no longer be used.
+  owner
+    .api_client
+    .revoke_share_link(workspace_uuid, &page.view_id, &created.share_link.link_id)
+    .await
+    .unwrap();
+  let err = reviewer
+    .api_client
+    .get_shared_page(&created.token, Some("secret"))
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::RecordNotFound);
+  let err = reviewer
+    .api_client
+    .join_share_link(
+      &created.token,
+      &JoinShareLinkParams {
+        password: Some("secret".to_string()),
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::RecordNotFound);
+}
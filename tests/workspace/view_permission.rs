@@ -0,0 +1,234 @@
+use app_error::ErrorCode;
+use client_api_test::TestClient;
+use database_entity::dto::{AFAccessLevel, AFRole};
+use shared_entity::dto::view_permission_dto::GrantViewPermissionParams;
+use shared_entity::dto::workspace_dto::{CreatePageParams, ViewLayout};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn page_permission_inheritance_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let guest = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &guest, AFRole::Guest)
+    .await
+    .unwrap();
+  let workspace_id = Uuid::parse_str(&workspace_id).unwrap();
+  let guest_uid = guest.uid().await;
+  let guest_email = guest.email().await;
+
+  let folder_view = owner
+    .api_client
+    .get_workspace_folder(&workspace_id.to_string(), Some(2), None)
+    .await
+    .unwrap();
+  let general_space = folder_view
+    .children
+    .into_iter()
+    .find(|v| v.name == "General")
+    .unwrap();
+  let parent_page = owner
+    .api_client
+    .create_workspace_page_view(
+      workspace_id,
+      &CreatePageParams {
+        parent_view_id: general_space.view_id.clone(),
+        layout: ViewLayout::Document,
+        name: Some("Parent".to_string()),
+      },
+    )
+    .await
+    .unwrap();
+  let child_page = owner
+    .api_client
+    .create_workspace_page_view(
+      workspace_id,
+      &CreatePageParams {
+        parent_view_id: parent_page.view_id.clone(),
+        layout: ViewLayout::Document,
+        name: Some("Child".to_string()),
+      },
+    )
+    .await
+    .unwrap();
+
+  // A guest can only read the pages and can't share them.
+  let permission = guest
+    .api_client
+    .get_effective_view_permission(workspace_id, &child_page.view_id, None)
+    .await
+    .unwrap();
+  assert_eq!(permission.access_level, AFAccessLevel::ReadOnly);
+  assert_eq!(permission.granted_on, None);
+  let err = guest
+    .api_client
+    .get_view_permissions(workspace_id, &parent_page.view_id)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+
+  // The grant on the parent page applies to the pages below it.
+  owner
+    .api_client
+    .grant_view_permission(
+      workspace_id,
+      &parent_page.view_id,
+      &GrantViewPermissionParams {
+        email: guest_email.clone(),
+        access_level: AFAccessLevel::ReadAndWrite,
+      },
+    )
+    .await
+    .unwrap();
+  let permission = owner
+    .api_client
+    .get_effective_view_permission(workspace_id, &child_page.view_id, Some(guest_uid))
+    .await
+    .unwrap();
+  assert_eq!(permission.uid, guest_uid);
+  assert_eq!(permission.role, AFRole::Guest);
+  assert_eq!(permission.access_level, AFAccessLevel::ReadAndWrite);
+  assert_eq!(
+    permission.granted_on.map(|view_id| view_id.to_string()),
+    Some(parent_page.view_id.clone())
+  );
+
+  // An explicit grant on the child page overrides the inherited one.
+  owner
+    .api_client
+    .grant_view_permission(
+      workspace_id,
+      &child_page.view_id,
+      &GrantViewPermissionParams {
+        email: guest_email.clone(),
+        access_level: AFAccessLevel::ReadOnly,
+      },
+    )
+    .await
+    .unwrap();
+  let permission = guest
+    .api_client
+    .get_effective_view_permission(workspace_id, &child_page.view_id, None)
+    .await
+    .unwrap();
+  assert_eq!(permission.access_level, AFAccessLevel::ReadOnly);
+  assert_eq!(
+    permission.granted_access_level,
+    Some(AFAccessLevel::ReadOnly)
+  );
+  assert_eq!(
+    permission.granted_on.map(|view_id| view_id.to_string()),
+    Some(child_page.view_id.clone())
+  );
+  let permissions = owner
+    .api_client
+    .get_view_permissions(workspace_id, &child_page.view_id)
+    .await
+    .unwrap();
+  assert_eq!(permissions.items.len(), 1);
+  assert_eq!(permissions.items[0].uid, guest_uid);
+
+  // Only the members with full access to the page can see the access level of the others.
+  let err = guest
+    .api_client
+    .get_effective_view_permission(workspace_id, &child_page.view_id, Some(owner.uid().await))
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+
+  // Revoking the override restores the inherited access level.
+  owner
+    .api_client
+    .revoke_view_permission(workspace_id, &child_page.view_id, guest_uid)
+    .await
+    .unwrap();
+  let permission = guest
+    .api_client
+    .get_effective_view_permission(workspace_id, &child_page.view_id, None)
+    .await
+    .unwrap();
+  assert_eq!(permission.access_level, AFAccessLevel::ReadAndWrite);
+  let err = owner
+    .api_client
+    .revoke_view_permission(workspace_id, &child_page.view_id, guest_uid)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::RecordNotFound);
+}
+
+#[tokio::test]
+async fn page_permission_restricts_member_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+  let workspace_id = Uuid::parse_str(&workspace_id).unwrap();
+
+  let folder_view = owner
+    .api_client
+    .get_workspace_folder(&workspace_id.to_string(), Some(2), None)
+    .await
+    .unwrap();
+  let general_space = folder_view
+    .children
+    .into_iter()
+    .find(|v| v.name == "General")
+    .unwrap();
+  let page = owner
+    .api_client
+    .create_workspace_page_view(
+      workspace_id,
+      &CreatePageParams {
+        parent_view_id: general_space.view_id.clone(),
+        layout: ViewLayout::Document,
+        name: Some("Read only".to_string()),
+      },
+    )
+    .await
+    .unwrap();
+
+  // The grant replaces the access level of the member role, even if it's lower.
+  owner
+    .api_client
+    .grant_view_permission(
+      workspace_id,
+      &page.view_id,
+      &GrantViewPermissionParams {
+        email: member.email().await,
+        access_level: AFAccessLevel::ReadOnly,
+      },
+    )
+    .await
+    .unwrap();
+  let permission = member
+    .api_client
+    .get_effective_view_permission(workspace_id, &page.view_id, None)
+    .await
+    .unwrap();
+  assert_eq!(permission.role, AFRole::Member);
+  assert_eq!(permission.access_level, AFAccessLevel::ReadOnly);
+
+  // The owner keeps full access to the pages of the workspace.
+  owner
+    .api_client
+    .grant_view_permission(
+      workspace_id,
+      &page.view_id,
+      &GrantViewPermissionParams {
+        email: owner.email().await,
+        access_level: AFAccessLevel::ReadOnly,
+      },
+    )
+    .await
+    .unwrap();
+  let permission = owner
+    .api_client
+    .get_effective_view_permission(workspace_id, &page.view_id, None)
+    .await
+    .unwrap();
+  assert_eq!(permission.access_level, AFAccessLevel::FullAccess);
+}
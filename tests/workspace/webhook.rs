@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use app_error::ErrorCode;
+use client_api_test::TestClient;
+use database_entity::dto::AFRole;
+use shared_entity::dto::webhook_dto::{
+  CreateWebhookParams, UpdateWebhookParams, WebhookDeliveryStatus, WebhookEvent,
+};
+use tokio::time::sleep;
+
+#[tokio::test]
+async fn webhook_crud_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  let created = owner
+    .api_client
+    .create_webhook(
+      &workspace_id,
+      &CreateWebhookParams {
+        url: "https://example.com/appflowy".to_string(),
+        events: vec![WebhookEvent::PageCreated, WebhookEvent::PageTrashed],
+      },
+    )
+    .await
+    .unwrap();
+  assert!(!created.secret.is_empty());
+  assert!(created.webhook.enabled);
+
+  let webhook = owner
+    .api_client
+    .update_webhook(
+      &workspace_id,
+      &created.webhook.webhook_id,
+      &UpdateWebhookParams {
+        events: Some(vec![WebhookEvent::CommentCreated]),
+        enabled: Some(false),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap();
+  assert_eq!(webhook.url, "https://example.com/appflowy");
+  assert_eq!(webhook.events, vec![WebhookEvent::CommentCreated]);
+  assert!(!webhook.enabled);
+
+  let webhooks = owner.api_client.get_webhooks(&workspace_id).await.unwrap();
+  assert_eq!(webhooks.items.len(), 1);
+  assert_eq!(webhooks.items[0].webhook_id, created.webhook.webhook_id);
+
+  // Only http and https urls are accepted.
+  let err = owner
+    .api_client
+    .create_webhook(
+      &workspace_id,
+      &CreateWebhookParams {
+        url: "ftp://example.com/appflowy".to_string(),
+        events: vec![WebhookEvent::PageCreated],
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::InvalidRequest);
+
+  // Urls of the local and private network are rejected.
+  for url in [
+    "http://localhost:8000/appflowy",
+    "http://10.0.0.1/appflowy",
+    "http://169.254.169.254/latest/meta-data",
+    "http://[::1]/appflowy",
+  ] {
+    let err = owner
+      .api_client
+      .create_webhook(
+        &workspace_id,
+        &CreateWebhookParams {
+          url: url.to_string(),
+          events: vec![WebhookEvent::PageCreated],
+        },
+      )
+      .await
+      .unwrap_err();
+    assert_eq!(err.code, ErrorCode::InvalidRequest, "{}", url);
+  }
+
+  owner
+    .api_client
+    .delete_webhook(&workspace_id, &created.webhook.webhook_id)
+    .await
+    .unwrap();
+  assert!(owner
+    .api_client
+    .get_webhooks(&workspace_id)
+    .await
+    .unwrap()
+    .items
+    .is_empty());
+}
+
+#[tokio::test]
+async fn only_owner_can_manage_webhooks_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+
+  let err = member
+    .api_client
+    .create_webhook(
+      &workspace_id,
+      &CreateWebhookParams {
+        url: "https://example.com/appflowy".to_string(),
+        events: vec![WebhookEvent::PageCreated],
+      },
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+  let err = member
+    .api_client
+    .get_webhooks(&workspace_id)
+    .await
+    .unwrap_err();
+  assert_eq!(err.code, ErrorCode::NotEnoughPermissions);
+}
+
+#[tokio::test]
+async fn failed_webhook_delivery_is_logged_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  // Nothing answers on this port, so the delivery fails and is scheduled for a retry.
+  let webhook = owner
+    .api_client
+    .create_webhook(
+      &workspace_id,
+      &CreateWebhookParams {
+        url: "http://example.com:1/appflowy".to_string(),
+        events: vec![WebhookEvent::MemberJoined],
+      },
+    )
+    .await
+    .unwrap()
+    .webhook;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+
+  // events are delivered by appflowy-worker in the background
+  let mut deliveries = vec![];
+  for _ in 0..30 {
+    deliveries = owner
+      .api_client
+      .get_webhook_deliveries(&workspace_id, &webhook.webhook_id, 10)
+      .await
+      .unwrap()
+      .items;
+    if deliveries.iter().any(|delivery| delivery.attempts > 0) {
+      break;
+    }
+    sleep(Duration::from_secs(2)).await;
+  }
+  assert_eq!(deliveries.len(), 1);
+  let delivery = &deliveries[0];
+  assert_eq!(delivery.event, "member.joined");
+  assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+  assert_eq!(delivery.attempts, 1);
+  assert!(delivery.error.is_some());
+  assert_eq!(
+    delivery.payload["data"]["email"].as_str().unwrap(),
+    member.email().await
+  );
+}
@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use client_api_test::TestClient;
+use database_entity::dto::AFRole;
+use shared_entity::dto::workspace_activity_dto::{
+  QueryWorkspaceActivityParams, WorkspaceActivity, WorkspaceActivityKind,
+};
+use shared_entity::dto::workspace_dto::{CreatePageParams, UpdatePageParams, ViewLayout};
+use tokio::time::sleep;
+use uuid::Uuid;
+
+/// The folder changes are recorded in the background, so the feed is read until it has the
+/// expected number of activities.
+async fn wait_for_activities(
+  client: &TestClient,
+  workspace_id: &str,
+  count: usize,
+) -> Vec<WorkspaceActivity> {
+  let mut activities = vec![];
+  for _ in 0..10 {
+    activities = client
+      .api_client
+      .get_workspace_activities(workspace_id, &QueryWorkspaceActivityParams::default())
+      .await
+      .unwrap()
+      .items;
+    if activities.len() >= count {
+      break;
+    }
+    sleep(Duration::from_millis(500)).await;
+  }
+  activities
+}
+
+#[tokio::test]
+async fn workspace_activity_feed_test() {
+  let owner = TestClient::new_user_without_ws_conn().await;
+  let member = TestClient::new_user_without_ws_conn().await;
+  let workspace_id = owner.workspace_id().await;
+  let owner_uid = owner.uid().await;
+  let member_uid = member.uid().await;
+  owner
+    .invite_and_accepted_workspace_member(&workspace_id, &member, AFRole::Member)
+    .await
+    .unwrap();
+
+  let workspace_uuid = Uuid::parse_str(&workspace_id).unwrap();
+  let folder_view = owner
+    .api_client
+    .get_workspace_folder(&workspace_id, Some(2), None)
+    .await
+    .unwrap();
+  let general_space = folder_view
+    .children
+    .into_iter()
+    .find(|v| v.name == "General")
+    .unwrap();
+  let page = owner
+    .api_client
+    .create_workspace_page_view(
+      workspace_uuid,
+      &CreatePageParams {
+        parent_view_id: general_space.view_id.clone(),
+        layout: ViewLayout::Document,
+        name: Some("Roadmap".to_string()),
+      },
+    )
+    .await
+    .unwrap();
+  owner
+    .api_client
+    .update_workspace_page_view(
+      workspace_uuid,
+      &page.view_id,
+      &UpdatePageParams {
+        name: "Roadmap 2025".to_string(),
+        icon: None,
+        extra: None,
+      },
+    )
+    .await
+    .unwrap();
+  owner
+    .api_client
+    .move_workspace_page_view_to_trash(workspace_uuid, &page.view_id)
+    .await
+    .unwrap();
+
+  let activities = wait_for_activities(&member, &workspace_id, 4).await;
+  // the most recent activity comes first
+  let kinds = activities
+    .iter()
+    .map(|activity| activity.kind)
+    .collect::<Vec<_>>();
+  assert_eq!(
+    kinds,
+    vec![
+      WorkspaceActivityKind::PageTrashed,
+      WorkspaceActivityKind::PageRenamed,
+      WorkspaceActivityKind::PageCreated,
+      WorkspaceActivityKind::MemberJoined,
+    ]
+  );
+  assert_eq!(activities[3].actor_uid, Some(member_uid));
+  assert_eq!(activities[3].object_id, member_uid.to_string());
+  for activity in &activities[..3] {
+    assert_eq!(activity.object_id, page.view_id);
+    assert_eq!(activity.actor_uid, Some(owner_uid));
+  }
+  assert_eq!(activities[1].metadata["old_name"], "Roadmap");
+  assert_eq!(activities[1].metadata["name"], "Roadmap 2025");
+
+  // paginate with the cursor
+  let first_page = member
+    .api_client
+    .get_workspace_activities(
+      &workspace_id,
+      &QueryWorkspaceActivityParams {
+        limit: Some(2),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap();
+  assert_eq!(first_page.items.len(), 2);
+  let second_page = member
+    .api_client
+    .get_workspace_activities(
+      &workspace_id,
+      &QueryWorkspaceActivityParams {
+        before: first_page.next_cursor,
+        limit: Some(2),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap();
+  assert_eq!(
+    second_page.items[0].kind,
+    WorkspaceActivityKind::PageCreated
+  );
+
+  // only the members can read the feed
+  let outsider = TestClient::new_user_without_ws_conn().await;
+  assert!(outsider
+    .api_client
+    .get_workspace_activities(&workspace_id, &QueryWorkspaceActivityParams::default())
+    .await
+    .is_err());
+}